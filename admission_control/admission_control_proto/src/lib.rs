@@ -7,7 +7,7 @@ use failure::prelude::*;
 use logger::prelude::*;
 use mempool::MempoolAddTransactionStatus;
 use proto_conv::{FromProto, IntoProto};
-use types::vm_error::VMStatus;
+use types::{transaction::TransactionOutput, vm_error::VMStatus};
 
 /// AC response status of submit_transaction to clients.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -18,6 +18,14 @@ pub enum AdmissionControlStatus {
     Blacklisted(String),
     /// The transaction is rejected, e.g. due to incorrect signature.
     Rejected(String),
+    /// Too many transactions were submitted by the same client or sender.
+    RateLimited(String),
+    /// The transaction is larger than the limit of this validator.
+    TransactionTooLarge(String),
+    /// The gas unit price is higher than the limit of this validator.
+    GasPriceTooHigh(String),
+    /// The script is not on the allow-list of this validator.
+    ScriptNotAllowed(String),
 }
 
 impl IntoProto for AdmissionControlStatus {
@@ -38,6 +46,22 @@ impl IntoProto for AdmissionControlStatus {
                 admission_control_status.set_message(msg);
                 admission_control_status.set_code(ProtoStatusCode::Rejected)
             }
+            AdmissionControlStatus::RateLimited(msg) => {
+                admission_control_status.set_message(msg);
+                admission_control_status.set_code(ProtoStatusCode::RateLimited)
+            }
+            AdmissionControlStatus::TransactionTooLarge(msg) => {
+                admission_control_status.set_message(msg);
+                admission_control_status.set_code(ProtoStatusCode::TransactionTooLarge)
+            }
+            AdmissionControlStatus::GasPriceTooHigh(msg) => {
+                admission_control_status.set_message(msg);
+                admission_control_status.set_code(ProtoStatusCode::GasPriceTooHigh)
+            }
+            AdmissionControlStatus::ScriptNotAllowed(msg) => {
+                admission_control_status.set_message(msg);
+                admission_control_status.set_code(ProtoStatusCode::ScriptNotAllowed)
+            }
         }
         admission_control_status
     }
@@ -58,6 +82,22 @@ impl FromProto for AdmissionControlStatus {
                 let msg = proto_admission_control_status.take_message();
                 AdmissionControlStatus::Rejected(msg)
             }
+            ProtoStatusCode::RateLimited => {
+                let msg = proto_admission_control_status.take_message();
+                AdmissionControlStatus::RateLimited(msg)
+            }
+            ProtoStatusCode::TransactionTooLarge => {
+                let msg = proto_admission_control_status.take_message();
+                AdmissionControlStatus::TransactionTooLarge(msg)
+            }
+            ProtoStatusCode::GasPriceTooHigh => {
+                let msg = proto_admission_control_status.take_message();
+                AdmissionControlStatus::GasPriceTooHigh(msg)
+            }
+            ProtoStatusCode::ScriptNotAllowed => {
+                let msg = proto_admission_control_status.take_message();
+                AdmissionControlStatus::ScriptNotAllowed(msg)
+            }
         };
         Ok(ret)
     }
@@ -66,7 +106,7 @@ impl FromProto for AdmissionControlStatus {
 /// Rust structure for SubmitTransactionResponse protobuf definition.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct SubmitTransactionResponse {
-    /// AC status returned to client if any - it can be one of: accepted, blacklisted, or rejected.
+    /// AC status returned to client if any - it can be accepted, or one of the rejection reasons.
     pub ac_status: Option<AdmissionControlStatus>,
     /// Mempool error status if any.
     pub mempool_error: Option<MempoolAddTransactionStatus>,
@@ -125,3 +165,90 @@ impl FromProto for SubmitTransactionResponse {
         })
     }
 }
+
+/// Rust structure for SubmitTransactionBatchResponse protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SubmitTransactionBatchResponse {
+    /// Status of each submitted transaction, in the order of the request.
+    pub responses: Vec<SubmitTransactionResponse>,
+}
+
+impl IntoProto for SubmitTransactionBatchResponse {
+    type ProtoType = crate::proto::admission_control::SubmitTransactionBatchResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_responses(
+            self.responses
+                .into_iter()
+                .map(IntoProto::into_proto)
+                .collect(),
+        );
+        proto
+    }
+}
+
+impl FromProto for SubmitTransactionBatchResponse {
+    type ProtoType = crate::proto::admission_control::SubmitTransactionBatchResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(SubmitTransactionBatchResponse {
+            responses: object
+                .take_responses()
+                .into_iter()
+                .map(SubmitTransactionResponse::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+/// Rust structure for SimulateTransactionResponse protobuf definition.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SimulateTransactionResponse {
+    /// AC status returned to client if the transaction is rejected before reaching the VM.
+    pub ac_status: Option<AdmissionControlStatus>,
+    /// Output of the simulated execution if any.
+    pub txn_output: Option<TransactionOutput>,
+    /// The id of validator associated with this AC.
+    pub validator_id: Vec<u8>,
+}
+
+impl IntoProto for SimulateTransactionResponse {
+    type ProtoType = crate::proto::admission_control::SimulateTransactionResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        if let Some(ac_st) = self.ac_status {
+            proto.set_ac_status(ac_st.into_proto());
+        } else if let Some(txn_output) = self.txn_output {
+            proto.set_txn_output(txn_output.into_proto());
+        } else {
+            error!("No status is available in SimulateTransactionResponse!");
+        }
+        proto.set_validator_id(self.validator_id);
+        proto
+    }
+}
+
+impl FromProto for SimulateTransactionResponse {
+    type ProtoType = crate::proto::admission_control::SimulateTransactionResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let ac_status = if object.has_ac_status() {
+            Some(AdmissionControlStatus::from_proto(object.take_ac_status())?)
+        } else {
+            None
+        };
+        let txn_output = if object.has_txn_output() {
+            Some(TransactionOutput::from_proto(object.take_txn_output())?)
+        } else {
+            None
+        };
+
+        Ok(SimulateTransactionResponse {
+            ac_status,
+            txn_output,
+            validator_id: object.take_validator_id(),
+        })
+    }
+}
@@ -1,7 +1,9 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::admission_control_service::AdmissionControlService;
+use crate::{
+    admission_control_service::AdmissionControlService, transaction_filter::TransactionFilterChain,
+};
 use admission_control_proto::proto::admission_control_grpc;
 use config::config::NodeConfig;
 use debug_interface::{node_debug_service::NodeDebugService, proto::node_debug_interface_grpc};
@@ -88,10 +90,15 @@ impl AdmissionControlNode {
             Arc::clone(&storage_client),
         ));
 
+        let transaction_filters = Arc::new(TransactionFilterChain::from_config(
+            &self.node_config.admission_control.filters,
+        )?);
+
         let handle = AdmissionControlService::new(
             mp_client,
             storage_client,
             vm_validator,
+            transaction_filters,
             self.node_config
                 .admission_control
                 .need_to_check_mempool_before_validation,
@@ -5,10 +5,14 @@
 //! from external clients (such as wallets) and performs necessary processing before sending them to
 //! next step.
 
-use crate::OP_COUNTERS;
+use crate::{transaction_filter::TransactionFilterChain, OP_COUNTERS};
 use admission_control_proto::{
     proto::{
-        admission_control::{SubmitTransactionRequest, SubmitTransactionResponse},
+        admission_control::{
+            AdmissionControlStatusCode, SimulateTransactionRequest, SimulateTransactionResponse,
+            SubmitTransactionBatchRequest, SubmitTransactionBatchResponse,
+            SubmitTransactionRequest, SubmitTransactionResponse,
+        },
         admission_control_grpc::AdmissionControl,
     },
     AdmissionControlStatus,
@@ -28,14 +32,27 @@ use mempool::proto::{
 };
 use metrics::counters::SVC_COUNTERS;
 use proto_conv::{FromProto, IntoProto};
-use std::sync::Arc;
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 use storage_client::StorageRead;
 use types::{
-    proto::get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse},
+    proto::{
+        get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse},
+        transaction::SignedTransaction as ProtoSignedTransaction,
+    },
     transaction::SignedTransaction,
+    vm_error::VMStatus,
 };
 use vm_validator::vm_validator::{get_account_state, TransactionValidation};
 
+/// Length of the placeholder signature used when simulating an unsigned transaction.
+const SIGNATURE_LENGTH: usize = 64;
+/// Maximum number of transactions accepted in one `SubmitTransactionBatch` request.
+const MAX_TXN_BATCH_SIZE: usize = 1000;
+
 #[cfg(test)]
 #[path = "unit_tests/admission_control_service_test.rs"]
 mod admission_control_service_test;
@@ -49,6 +66,8 @@ pub struct AdmissionControlService<M, V> {
     storage_read_client: Arc<dyn StorageRead>,
     /// VM validator instance to validate transactions sent from wallets.
     vm_validator: Arc<V>,
+    /// Filters applied to submitted transactions before VM validation.
+    transaction_filters: Arc<TransactionFilterChain>,
     /// Flag indicating whether we need to check mempool before validation, drop txn if check
     /// fails.
     need_to_check_mempool_before_validation: bool,
@@ -64,36 +83,173 @@ where
         mempool_client: Arc<M>,
         storage_read_client: Arc<dyn StorageRead>,
         vm_validator: Arc<V>,
+        transaction_filters: Arc<TransactionFilterChain>,
         need_to_check_mempool_before_validation: bool,
     ) -> Self {
         AdmissionControlService {
             mempool_client,
             storage_read_client,
             vm_validator,
+            transaction_filters,
             need_to_check_mempool_before_validation,
         }
     }
 
-    /// Validate transaction signature, then via VM, and add it to Mempool if it passes VM check.
+    /// Validate transaction signature, then run it through the filters and the VM, and add it to
+    /// Mempool if it passes all checks. Filters keeping per-sender state only run once the VM has
+    /// validated the transaction. `peer` identifies the client submitting the transaction.
     pub(crate) fn submit_transaction_inner(
         &self,
-        req: SubmitTransactionRequest,
+        mut req: SubmitTransactionRequest,
+        peer: &str,
     ) -> Result<SubmitTransactionResponse> {
         // Drop requests first if mempool is full (validator is lagging behind) so not to consume
         // unnecessary resources.
         if !self.can_send_txn_to_mempool()? {
             debug!("Mempool is full");
             OP_COUNTERS.inc_by("submit_txn.rejected.mempool_full", 1);
-            let mut response = SubmitTransactionResponse::new();
-            let mut status = MempoolAddTransactionStatus::new();
-            status.set_code(MempoolIsFull);
-            status.set_message("Mempool is full".to_string());
-            response.set_mempool_status(status);
+            return Ok(mempool_full_response());
+        }
+
+        let signed_txn_proto = req.take_signed_txn();
+        let signed_txn = match self.check_transaction(&signed_txn_proto, peer) {
+            Ok(t) => t,
+            Err(response) => return Ok(response),
+        };
+
+        let validation_status = validate_transaction(self.vm_validator.as_ref(), &signed_txn)?;
+        if let Some(validation_status) = validation_status {
+            return Ok(vm_validation_failure_response(
+                validation_status,
+                &signed_txn,
+            ));
+        }
+        if let Err(response) = self.check_validated_transaction(&signed_txn, peer) {
             return Ok(response);
         }
+        let account_state = block_on(get_account_state(
+            self.storage_read_client.clone(),
+            signed_txn.sender(),
+        ));
+        self.add_txn_to_mempool(add_transaction_request(
+            signed_txn_proto,
+            &signed_txn,
+            account_state.ok(),
+        ))
+    }
 
-        let signed_txn_proto = req.get_signed_txn();
+    /// Submit a batch of transactions. Transactions are validated by the VM concurrently, then
+    /// added to Mempool ordered by sender and sequence number, so that a sender can submit
+    /// consecutive sequence numbers in one batch. Once a transaction of a sender is rejected,
+    /// the following transactions of that sender in the batch are rejected too since they could
+    /// not be executed anyway.
+    pub(crate) fn submit_transaction_batch_inner(
+        &self,
+        mut req: SubmitTransactionBatchRequest,
+        peer: &str,
+    ) -> Result<SubmitTransactionBatchResponse> {
+        let signed_txn_protos = req.take_signed_txns().into_vec();
+        ensure!(
+            signed_txn_protos.len() <= MAX_TXN_BATCH_SIZE,
+            "Batch of {} transactions exceeds limit {}",
+            signed_txn_protos.len(),
+            MAX_TXN_BATCH_SIZE
+        );
+        OP_COUNTERS.inc_by("submit_txn_batch.txns", signed_txn_protos.len());
 
+        if !self.can_send_txn_to_mempool()? {
+            debug!("Mempool is full");
+            OP_COUNTERS.inc_by("submit_txn.rejected.mempool_full", signed_txn_protos.len());
+            let mut response = SubmitTransactionBatchResponse::new();
+            response.set_responses(
+                signed_txn_protos
+                    .iter()
+                    .map(|_| mempool_full_response())
+                    .collect(),
+            );
+            return Ok(response);
+        }
+
+        // Filters may be stateful (e.g. rate limits), so they run in submission order.
+        let mut responses: Vec<Option<SubmitTransactionResponse>> = vec![];
+        let mut checked_txns = vec![];
+        for (index, signed_txn_proto) in signed_txn_protos.into_iter().enumerate() {
+            match self.check_transaction(&signed_txn_proto, peer) {
+                Ok(signed_txn) => {
+                    responses.push(None);
+                    checked_txns.push((index, signed_txn_proto, signed_txn));
+                }
+                Err(response) => responses.push(Some(response)),
+            }
+        }
+
+        let vm_validator = self.vm_validator.as_ref();
+        let validation_statuses = checked_txns
+            .iter()
+            .map(|(_, _, signed_txn)| signed_txn)
+            .collect::<Vec<_>>()
+            .par_iter()
+            .map(|signed_txn| validate_transaction(vm_validator, signed_txn))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut txns: Vec<_> = checked_txns.into_iter().zip(validation_statuses).collect();
+        txns.sort_by_key(|((_, _, signed_txn), _)| {
+            (signed_txn.sender(), signed_txn.sequence_number())
+        });
+
+        let mut account_states = HashMap::new();
+        let mut rejected_senders = HashSet::new();
+        for ((index, signed_txn_proto, signed_txn), validation_status) in txns {
+            let sender = signed_txn.sender();
+            let response = if rejected_senders.contains(&sender) {
+                OP_COUNTERS.inc_by("submit_txn_batch.rejected.preceding_txn", 1);
+                let mut response = SubmitTransactionResponse::new();
+                response.set_ac_status(
+                    AdmissionControlStatus::Rejected(
+                        "A preceding transaction of the sender was rejected".to_string(),
+                    )
+                    .into_proto(),
+                );
+                response
+            } else if let Some(validation_status) = validation_status {
+                vm_validation_failure_response(validation_status, &signed_txn)
+            } else if let Err(response) = self.check_validated_transaction(&signed_txn, peer) {
+                response
+            } else {
+                let account_state = *account_states.entry(sender).or_insert_with(|| {
+                    block_on(get_account_state(self.storage_read_client.clone(), sender)).ok()
+                });
+                self.add_txn_to_mempool(add_transaction_request(
+                    signed_txn_proto,
+                    &signed_txn,
+                    account_state,
+                ))?
+            };
+            if !response.has_ac_status()
+                || response.get_ac_status().get_code() != AdmissionControlStatusCode::Accepted
+            {
+                rejected_senders.insert(sender);
+            }
+            responses[index] = Some(response);
+        }
+
+        let mut response = SubmitTransactionBatchResponse::new();
+        response.set_responses(
+            responses
+                .into_iter()
+                .map(|response| response.expect("Every transaction must have a response"))
+                .collect(),
+        );
+        Ok(response)
+    }
+
+    /// Deserialize a submitted transaction and run it through the filters. Returns the response
+    /// to send back to the client if the transaction is rejected.
+    fn check_transaction(
+        &self,
+        signed_txn_proto: &ProtoSignedTransaction,
+        peer: &str,
+    ) -> std::result::Result<SignedTransaction, SubmitTransactionResponse> {
         let signed_txn = match SignedTransaction::from_proto(signed_txn_proto.clone()) {
             Ok(t) => t,
             Err(e) => {
@@ -107,44 +263,84 @@ where
                         .into_proto(),
                 );
                 OP_COUNTERS.inc_by("submit_txn.rejected.invalid_txn", 1);
-                return Ok(response);
+                return Err(response);
             }
         };
 
-        let gas_cost = signed_txn.max_gas_amount();
-        let validation_status = self
-            .vm_validator
-            .validate_transaction(signed_txn.clone())
-            .wait()
-            .map_err(|e| {
+        if let Some(filter_status) = self.transaction_filters.check(&signed_txn, peer) {
+            return Err(filter_rejection_response(filter_status, &signed_txn));
+        }
+        Ok(signed_txn)
+    }
+
+    /// Run a transaction that passed VM validation through the filters that need it. Returns the
+    /// response to send back to the client if the transaction is rejected.
+    fn check_validated_transaction(
+        &self,
+        signed_txn: &SignedTransaction,
+        peer: &str,
+    ) -> std::result::Result<(), SubmitTransactionResponse> {
+        match self.transaction_filters.check_validated(signed_txn, peer) {
+            Some(filter_status) => Err(filter_rejection_response(filter_status, signed_txn)),
+            None => Ok(()),
+        }
+    }
+
+    /// Execute transaction via VM against the latest committed state without committing it or
+    /// sending it to Mempool. Since simulation is as costly as execution, the transaction first
+    /// goes through the same filters as a submitted one does before VM validation, including the
+    /// per-client rate limit. `peer` identifies the client simulating the transaction.
+    pub(crate) fn simulate_transaction_inner(
+        &self,
+        mut req: SimulateTransactionRequest,
+        peer: &str,
+    ) -> Result<SimulateTransactionResponse> {
+        let skip_signature_check = req.get_skip_signature_check();
+        let mut signed_txn_proto = req.take_signed_txn();
+        if skip_signature_check && signed_txn_proto.get_sender_signature().is_empty() {
+            // Unsigned transactions still need a well-formed signature to be deserialized.
+            signed_txn_proto.set_sender_signature(vec![0; SIGNATURE_LENGTH]);
+        }
+
+        let signed_txn = match SignedTransaction::from_proto(signed_txn_proto.clone()) {
+            Ok(t) => t,
+            Err(e) => {
                 security_log(SecurityEvent::InvalidTransactionAC)
                     .error(&e)
-                    .data(&signed_txn)
+                    .data(&signed_txn_proto)
                     .log();
-                e
-            })?;
-        if let Some(validation_status) = validation_status {
-            let mut response = SubmitTransactionResponse::new();
-            OP_COUNTERS.inc_by("submit_txn.vm_validation.failure", 1);
+                let mut response = SimulateTransactionResponse::new();
+                response.set_ac_status(
+                    AdmissionControlStatus::Rejected("simulate txn rejected".to_string())
+                        .into_proto(),
+                );
+                OP_COUNTERS.inc_by("simulate_txn.rejected.invalid_txn", 1);
+                return Ok(response);
+            }
+        };
+        if let Some(filter_status) = self.transaction_filters.check(&signed_txn, peer) {
             debug!(
-                "txn failed in vm validation, status: {:?}, txn: {:?}",
-                validation_status, signed_txn
+                "txn simulation rejected by filter, status: {:?}, txn: {:?}",
+                filter_status, signed_txn
             );
-            response.set_vm_status(validation_status.into_proto());
+            let mut response = SimulateTransactionResponse::new();
+            response.set_ac_status(filter_status.into_proto());
             return Ok(response);
         }
-        let sender = signed_txn.sender();
-        let account_state = block_on(get_account_state(self.storage_read_client.clone(), sender));
-        let mut add_transaction_request = AddTransactionWithValidationRequest::new();
-        add_transaction_request.signed_txn = req.signed_txn.clone();
-        add_transaction_request.set_max_gas_cost(gas_cost);
-
-        if let Ok((sequence_number, balance)) = account_state {
-            add_transaction_request.set_account_balance(balance);
-            add_transaction_request.set_latest_sequence_number(sequence_number);
-        }
 
-        self.add_txn_to_mempool(add_transaction_request)
+        let txn_output = self
+            .vm_validator
+            .simulate_transaction(signed_txn, skip_signature_check)
+            .wait()?;
+        debug!(
+            "txn simulated, status: {:?}, gas used: {}",
+            txn_output.status(),
+            txn_output.gas_used()
+        );
+        OP_COUNTERS.inc_by("simulate_txn.simulated", 1);
+        let mut response = SimulateTransactionResponse::new();
+        response.set_txn_output(txn_output.into_proto());
+        Ok(response)
     }
 
     fn can_send_txn_to_mempool(&self) -> Result<bool> {
@@ -183,7 +379,7 @@ where
     }
 
     /// Pass the UpdateToLatestLedgerRequest to Storage for read query.
-    fn update_to_latest_ledger_inner(
+    pub(crate) fn update_to_latest_ledger_inner(
         &self,
         req: UpdateToLatestLedgerRequest,
     ) -> Result<UpdateToLatestLedgerResponse> {
@@ -200,6 +396,76 @@ where
     }
 }
 
+/// Validate a transaction via VM. Returns the VM status if the transaction is invalid.
+fn validate_transaction<V: TransactionValidation>(
+    vm_validator: &V,
+    signed_txn: &SignedTransaction,
+) -> Result<Option<VMStatus>> {
+    vm_validator
+        .validate_transaction(signed_txn.clone())
+        .wait()
+        .map_err(|e| {
+            security_log(SecurityEvent::InvalidTransactionAC)
+                .error(&e)
+                .data(signed_txn)
+                .log();
+            e
+        })
+}
+
+fn filter_rejection_response(
+    filter_status: AdmissionControlStatus,
+    signed_txn: &SignedTransaction,
+) -> SubmitTransactionResponse {
+    debug!(
+        "txn rejected by filter, status: {:?}, txn: {:?}",
+        filter_status, signed_txn
+    );
+    let mut response = SubmitTransactionResponse::new();
+    response.set_ac_status(filter_status.into_proto());
+    response
+}
+
+fn mempool_full_response() -> SubmitTransactionResponse {
+    let mut response = SubmitTransactionResponse::new();
+    let mut status = MempoolAddTransactionStatus::new();
+    status.set_code(MempoolIsFull);
+    status.set_message("Mempool is full".to_string());
+    response.set_mempool_status(status);
+    response
+}
+
+fn vm_validation_failure_response(
+    validation_status: VMStatus,
+    signed_txn: &SignedTransaction,
+) -> SubmitTransactionResponse {
+    OP_COUNTERS.inc_by("submit_txn.vm_validation.failure", 1);
+    debug!(
+        "txn failed in vm validation, status: {:?}, txn: {:?}",
+        validation_status, signed_txn
+    );
+    let mut response = SubmitTransactionResponse::new();
+    response.set_vm_status(validation_status.into_proto());
+    response
+}
+
+/// Builds the Mempool request for a validated transaction. `account_state` is the sender's
+/// sequence number and balance, if they could be read from storage.
+fn add_transaction_request(
+    signed_txn_proto: ProtoSignedTransaction,
+    signed_txn: &SignedTransaction,
+    account_state: Option<(u64, u64)>,
+) -> AddTransactionWithValidationRequest {
+    let mut add_transaction_request = AddTransactionWithValidationRequest::new();
+    add_transaction_request.set_signed_txn(signed_txn_proto);
+    add_transaction_request.set_max_gas_cost(signed_txn.max_gas_amount());
+    if let Some((sequence_number, balance)) = account_state {
+        add_transaction_request.set_account_balance(balance);
+        add_transaction_request.set_latest_sequence_number(sequence_number);
+    }
+    add_transaction_request
+}
+
 impl<M: 'static, V> AdmissionControl for AdmissionControlService<M, V>
 where
     M: MempoolClientTrait,
@@ -216,7 +482,36 @@ where
     ) {
         debug!("[GRPC] AdmissionControl::submit_transaction");
         let _timer = SVC_COUNTERS.req(&ctx);
-        let resp = self.submit_transaction_inner(req);
+        let resp = self.submit_transaction_inner(req, &ctx.peer());
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Submit multiple transactions in one request. Each transaction goes through the same
+    /// checks as in `submit_transaction`, and a status is returned for each of them.
+    fn submit_transaction_batch(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SubmitTransactionBatchRequest,
+        sink: ::grpcio::UnarySink<SubmitTransactionBatchResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::submit_transaction_batch");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.submit_transaction_batch_inner(req, &ctx.peer());
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Execute a transaction against the latest committed state without committing it. The
+    /// output (status, gas used, write set and events) is returned to the client, which can use
+    /// it to estimate `max_gas_amount` before submitting the transaction.
+    fn simulate_transaction(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SimulateTransactionRequest,
+        sink: ::grpcio::UnarySink<SimulateTransactionResponse>,
+    ) {
+        debug!("[GRPC] AdmissionControl::simulate_transaction");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.simulate_transaction_inner(req, &ctx.peer());
         provide_grpc_response(resp, ctx, sink);
     }
 
@@ -6,14 +6,20 @@
 //! Admission Control
 //!
 //! Admission Control (AC) is the public API end point taking public gRPC requests from clients.
-//! AC serves two types of request from clients:
+//! AC serves four types of request from clients:
 //! 1. SubmitTransaction, to submit transaction to associated validator.
-//! 2. UpdateToLatestLedger, to query storage, e.g. account state, transaction log, and proofs.
+//! 2. SubmitTransactionBatch, to submit multiple transactions to associated validator at once.
+//! 3. SimulateTransaction, to execute a transaction against the latest state without committing.
+//! 4. UpdateToLatestLedger, to query storage, e.g. account state, transaction log, and proofs.
 
 /// Wrapper to run AC in a separate process.
 pub mod admission_control_node;
 /// AC gRPC service.
 pub mod admission_control_service;
+/// Policies dropping unwanted transactions before they reach the VM and Mempool.
+pub mod transaction_filter;
+/// AC gRPC service of full nodes, forwarding submitted transactions to an upstream validator.
+pub mod upstream_proxy;
 use lazy_static::lazy_static;
 use metrics::OpMetrics;
 
@@ -0,0 +1,416 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pre-mempool transaction filters.
+//!
+//! Most filters run in order after a transaction is deserialized and before it is validated by the
+//! VM, so that unwanted transactions are dropped as cheaply as possible. Filters keeping state per
+//! sender run after the VM has validated the transaction instead, see
+//! [`TransactionFilter::after_validation`]. Each filter either lets a transaction through or
+//! rejects it with an [`AdmissionControlStatus`] explaining why.
+
+use crate::OP_COUNTERS;
+use admission_control_proto::AdmissionControlStatus;
+use config::config::TransactionFilterConfig;
+use crypto::HashValue;
+use failure::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionArgument, TransactionPayload},
+};
+
+#[cfg(test)]
+#[path = "unit_tests/transaction_filter_test.rs"]
+mod transaction_filter_test;
+
+/// Default length of the rate limiting window when only the limits are configured.
+const DEFAULT_RATE_LIMIT_WINDOW_MS: u64 = 1000;
+
+/// A policy deciding whether a transaction is admitted to mempool.
+pub trait TransactionFilter: Send + Sync {
+    /// Name of the filter, used for counters and logging.
+    fn name(&self) -> &'static str;
+
+    /// Returns the rejection status if `txn`, submitted by client `peer`, must be dropped.
+    fn check(&self, txn: &SignedTransaction, peer: &str) -> Option<AdmissionControlStatus>;
+
+    /// Whether the filter only sees transactions that passed VM validation. The VM checks that
+    /// the transaction is signed with the authentication key of its sender, so a filter keeping
+    /// state per sender needs this, or anyone could use up that state with transactions they
+    /// didn't sign.
+    fn after_validation(&self) -> bool {
+        false
+    }
+}
+
+/// Ordered list of filters applied to every submitted transaction. The first rejection wins.
+#[derive(Default)]
+pub struct TransactionFilterChain {
+    filters: Vec<Box<dyn TransactionFilter>>,
+}
+
+impl TransactionFilterChain {
+    /// Constructs a chain running `filters` in order.
+    pub fn new(filters: Vec<Box<dyn TransactionFilter>>) -> Self {
+        Self { filters }
+    }
+
+    /// Constructs the chain described by `config`. Stateless filters run first so that the
+    /// transactions they reject do not count towards the rate limits. The per-sender rate limit
+    /// only counts transactions that the VM validated, but transactions that mempool rejects
+    /// afterwards still count.
+    pub fn from_config(config: &TransactionFilterConfig) -> Result<Self> {
+        let mut filters: Vec<Box<dyn TransactionFilter>> = vec![];
+        if !config.sender_deny_list.is_empty() || !config.receiver_deny_list.is_empty() {
+            filters.push(Box::new(DenyListFilter::new(
+                parse_addresses(&config.sender_deny_list)?,
+                parse_addresses(&config.receiver_deny_list)?,
+            )));
+        }
+        if let Some(max_txn_size_bytes) = config.max_txn_size_bytes {
+            filters.push(Box::new(MaxTransactionSizeFilter::new(max_txn_size_bytes)));
+        }
+        if let Some(max_gas_unit_price) = config.max_gas_unit_price {
+            filters.push(Box::new(MaxGasPriceFilter::new(max_gas_unit_price)));
+        }
+        if let Some(script_allow_list) = &config.script_allow_list {
+            let allowed_scripts = script_allow_list
+                .iter()
+                .map(|script_hash| {
+                    let bytes = hex::decode(script_hash)?;
+                    HashValue::from_slice(&bytes)
+                })
+                .collect::<Result<_>>()?;
+            filters.push(Box::new(ScriptAllowListFilter::new(allowed_scripts)));
+        }
+        let window = Duration::from_millis(
+            config
+                .rate_limit_window_ms
+                .unwrap_or(DEFAULT_RATE_LIMIT_WINDOW_MS),
+        );
+        if let Some(max_txns_per_ip) = config.max_txns_per_ip {
+            filters.push(Box::new(RateLimitFilter::per_ip(max_txns_per_ip, window)));
+        }
+        if let Some(max_txns_per_sender) = config.max_txns_per_sender {
+            filters.push(Box::new(RateLimitFilter::per_sender(
+                max_txns_per_sender,
+                window,
+            )));
+        }
+        Ok(Self::new(filters))
+    }
+
+    /// Runs the filters that don't need a validated transaction against `txn` and returns the
+    /// status of the first rejection, if any.
+    pub fn check(&self, txn: &SignedTransaction, peer: &str) -> Option<AdmissionControlStatus> {
+        self.check_filters(txn, peer, false /* after_validation */)
+    }
+
+    /// Runs the remaining filters against `txn`, which the VM has validated, and returns the
+    /// status of the first rejection, if any.
+    pub fn check_validated(
+        &self,
+        txn: &SignedTransaction,
+        peer: &str,
+    ) -> Option<AdmissionControlStatus> {
+        self.check_filters(txn, peer, true /* after_validation */)
+    }
+
+    fn check_filters(
+        &self,
+        txn: &SignedTransaction,
+        peer: &str,
+        after_validation: bool,
+    ) -> Option<AdmissionControlStatus> {
+        for filter in self
+            .filters
+            .iter()
+            .filter(|filter| filter.after_validation() == after_validation)
+        {
+            if let Some(status) = filter.check(txn, peer) {
+                OP_COUNTERS.inc_by(&format!("submit_txn.rejected.filter.{}", filter.name()), 1);
+                return Some(status);
+            }
+        }
+        None
+    }
+}
+
+fn parse_addresses(addresses: &[String]) -> Result<HashSet<AccountAddress>> {
+    addresses
+        .iter()
+        .map(|address| {
+            ensure!(!address.is_empty(), "Empty address in deny list");
+            AccountAddress::from_str(address)
+        })
+        .collect()
+}
+
+/// Rejects transactions sent by, or passing as a script argument, a denied account.
+pub struct DenyListFilter {
+    senders: HashSet<AccountAddress>,
+    receivers: HashSet<AccountAddress>,
+}
+
+impl DenyListFilter {
+    /// Constructs a filter denying the given senders and receivers.
+    pub fn new(senders: HashSet<AccountAddress>, receivers: HashSet<AccountAddress>) -> Self {
+        Self { senders, receivers }
+    }
+}
+
+impl TransactionFilter for DenyListFilter {
+    fn name(&self) -> &'static str {
+        "deny_list"
+    }
+
+    fn check(&self, txn: &SignedTransaction, _peer: &str) -> Option<AdmissionControlStatus> {
+        if self.senders.contains(&txn.sender()) {
+            return Some(AdmissionControlStatus::Blacklisted(format!(
+                "Sender {} is denied",
+                txn.sender()
+            )));
+        }
+        if let TransactionPayload::Program(program) = txn.payload() {
+            for arg in program.args() {
+                if let TransactionArgument::Address(address) = arg {
+                    if self.receivers.contains(address) {
+                        return Some(AdmissionControlStatus::Blacklisted(format!(
+                            "Receiver {} is denied",
+                            address
+                        )));
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Rejects transactions whose serialized raw transaction is larger than a limit.
+pub struct MaxTransactionSizeFilter {
+    max_txn_size_bytes: usize,
+}
+
+impl MaxTransactionSizeFilter {
+    /// Constructs a filter accepting transactions of at most `max_txn_size_bytes`.
+    pub fn new(max_txn_size_bytes: usize) -> Self {
+        Self { max_txn_size_bytes }
+    }
+}
+
+impl TransactionFilter for MaxTransactionSizeFilter {
+    fn name(&self) -> &'static str {
+        "max_txn_size"
+    }
+
+    fn check(&self, txn: &SignedTransaction, _peer: &str) -> Option<AdmissionControlStatus> {
+        let txn_size = txn.raw_txn_bytes_len();
+        if txn_size > self.max_txn_size_bytes {
+            return Some(AdmissionControlStatus::TransactionTooLarge(format!(
+                "Transaction size {} exceeds limit {}",
+                txn_size, self.max_txn_size_bytes
+            )));
+        }
+        None
+    }
+}
+
+/// Rejects transactions whose gas unit price is higher than a limit.
+pub struct MaxGasPriceFilter {
+    max_gas_unit_price: u64,
+}
+
+impl MaxGasPriceFilter {
+    /// Constructs a filter accepting a gas unit price of at most `max_gas_unit_price`.
+    pub fn new(max_gas_unit_price: u64) -> Self {
+        Self { max_gas_unit_price }
+    }
+}
+
+impl TransactionFilter for MaxGasPriceFilter {
+    fn name(&self) -> &'static str {
+        "max_gas_price"
+    }
+
+    fn check(&self, txn: &SignedTransaction, _peer: &str) -> Option<AdmissionControlStatus> {
+        if txn.gas_unit_price() > self.max_gas_unit_price {
+            return Some(AdmissionControlStatus::GasPriceTooHigh(format!(
+                "Gas unit price {} exceeds limit {}",
+                txn.gas_unit_price(),
+                self.max_gas_unit_price
+            )));
+        }
+        None
+    }
+}
+
+/// Only admits programs whose script code hashes to one of the allowed values. Write set
+/// transactions are always rejected.
+pub struct ScriptAllowListFilter {
+    allowed_scripts: HashSet<HashValue>,
+}
+
+impl ScriptAllowListFilter {
+    /// Constructs a filter admitting the scripts with the given sha3-256 hashes.
+    pub fn new(allowed_scripts: HashSet<HashValue>) -> Self {
+        Self { allowed_scripts }
+    }
+}
+
+impl TransactionFilter for ScriptAllowListFilter {
+    fn name(&self) -> &'static str {
+        "script_allow_list"
+    }
+
+    fn check(&self, txn: &SignedTransaction, _peer: &str) -> Option<AdmissionControlStatus> {
+        match txn.payload() {
+            TransactionPayload::Program(program) => {
+                let script_hash = HashValue::from_iter_sha3(vec![program.code()]);
+                if self.allowed_scripts.contains(&script_hash) {
+                    None
+                } else {
+                    Some(AdmissionControlStatus::ScriptNotAllowed(format!(
+                        "Script {:x} is not allowed",
+                        script_hash
+                    )))
+                }
+            }
+            TransactionPayload::WriteSet(_) => Some(AdmissionControlStatus::ScriptNotAllowed(
+                "Write set transactions are not allowed".to_string(),
+            )),
+        }
+    }
+}
+
+/// Fixed window counter of the transactions seen per key.
+struct RateLimiter<K> {
+    max_txns: u64,
+    window: Duration,
+    state: Mutex<RateLimiterState<K>>,
+}
+
+struct RateLimiterState<K> {
+    // Start of the current window and the number of transactions seen in it, per key.
+    counts: HashMap<K, (Instant, u64)>,
+    // When the expired windows were last removed.
+    last_sweep: Instant,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    fn new(max_txns: u64, window: Duration) -> Self {
+        Self {
+            max_txns,
+            window,
+            state: Mutex::new(RateLimiterState {
+                counts: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    /// Records one transaction for `key` and returns whether it is within the limit.
+    fn try_acquire(&self, key: K, now: Instant) -> bool {
+        let window = self.window;
+        let mut state = self.state.lock().unwrap();
+        // Expired windows are removed at most once per window so that the map does not grow
+        // without bound, while each transaction only pays for its own key.
+        if now.duration_since(state.last_sweep) >= window {
+            state
+                .counts
+                .retain(|_, (start, _)| now.duration_since(*start) < window);
+            state.last_sweep = now;
+        }
+        let (start, count) = state.counts.entry(key).or_insert((now, 0));
+        if now.duration_since(*start) >= window {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.max_txns {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+}
+
+/// Rejects transactions once a client IP or a sender exceeds the number of transactions allowed
+/// within a time window. The per-IP limit counts every transaction the client submits, since
+/// nobody else can submit from its IP. The per-sender limit runs after VM validation and only
+/// counts transactions that the sender signed.
+pub struct RateLimitFilter {
+    limiter: RateLimitKey,
+}
+
+enum RateLimitKey {
+    Ip(RateLimiter<String>),
+    Sender(RateLimiter<AccountAddress>),
+}
+
+impl RateLimitFilter {
+    /// Constructs a filter allowing `max_txns` per client IP within `window`.
+    pub fn per_ip(max_txns: u64, window: Duration) -> Self {
+        Self {
+            limiter: RateLimitKey::Ip(RateLimiter::new(max_txns, window)),
+        }
+    }
+
+    /// Constructs a filter allowing `max_txns` per sender within `window`.
+    pub fn per_sender(max_txns: u64, window: Duration) -> Self {
+        Self {
+            limiter: RateLimitKey::Sender(RateLimiter::new(max_txns, window)),
+        }
+    }
+}
+
+impl TransactionFilter for RateLimitFilter {
+    fn name(&self) -> &'static str {
+        match self.limiter {
+            RateLimitKey::Ip(_) => "rate_limit_ip",
+            RateLimitKey::Sender(_) => "rate_limit_sender",
+        }
+    }
+
+    fn after_validation(&self) -> bool {
+        match self.limiter {
+            RateLimitKey::Ip(_) => false,
+            RateLimitKey::Sender(_) => true,
+        }
+    }
+
+    fn check(&self, txn: &SignedTransaction, peer: &str) -> Option<AdmissionControlStatus> {
+        let now = Instant::now();
+        match &self.limiter {
+            RateLimitKey::Ip(limiter) => {
+                let ip = peer_ip(peer);
+                if !limiter.try_acquire(ip.to_string(), now) {
+                    return Some(AdmissionControlStatus::RateLimited(format!(
+                        "Too many transactions from {}",
+                        ip
+                    )));
+                }
+            }
+            RateLimitKey::Sender(limiter) => {
+                if !limiter.try_acquire(txn.sender(), now) {
+                    return Some(AdmissionControlStatus::RateLimited(format!(
+                        "Too many transactions from sender {}",
+                        txn.sender()
+                    )));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Strips the port from a gRPC peer string such as `ipv4:127.0.0.1:52144`.
+fn peer_ip(peer: &str) -> &str {
+    peer.rsplitn(2, ':').last().unwrap_or(peer)
+}
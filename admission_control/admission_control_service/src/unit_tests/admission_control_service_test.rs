@@ -3,12 +3,18 @@
 
 use crate::{
     admission_control_service::{
-        AdmissionControlService, SubmitTransactionRequest,
-        SubmitTransactionResponse as ProtoSubmitTransactionResponse,
+        AdmissionControlService, SimulateTransactionRequest, SubmitTransactionBatchRequest,
+        SubmitTransactionRequest, SubmitTransactionResponse as ProtoSubmitTransactionResponse,
+    },
+    transaction_filter::{
+        DenyListFilter, MaxGasPriceFilter, RateLimitFilter, TransactionFilterChain,
     },
     unit_tests::LocalMockMempool,
 };
-use admission_control_proto::{AdmissionControlStatus, SubmitTransactionResponse};
+use admission_control_proto::{
+    AdmissionControlStatus, SimulateTransactionResponse, SubmitTransactionBatchResponse,
+    SubmitTransactionResponse,
+};
 
 use assert_matches::assert_matches;
 use crypto::{
@@ -18,21 +24,24 @@ use crypto::{
 use mempool::proto::shared::mempool_status::MempoolAddTransactionStatusCode;
 use proto_conv::FromProto;
 use protobuf::{Message, UnknownFields};
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc, time::Duration};
 use storage_service::mocks::mock_storage_client::MockStorageReadClient;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    test_helpers::transaction_test_helpers::get_test_signed_txn,
-    transaction::RawTransactionBytes,
+    test_helpers::transaction_test_helpers::{get_test_signed_transaction, get_test_signed_txn},
+    transaction::{RawTransactionBytes, TransactionStatus},
     vm_error::{ExecutionStatus, VMStatus, VMValidationStatus},
 };
 use vm_validator::mocks::mock_vm_validator::MockVMValidator;
 
+const TEST_PEER: &str = "ipv4:127.0.0.1:8000";
+
 fn create_ac_service_for_ut() -> AdmissionControlService<LocalMockMempool, MockVMValidator> {
     AdmissionControlService::new(
         Arc::new(LocalMockMempool::new()),
         Arc::new(MockStorageReadClient),
         Arc::new(MockVMValidator),
+        Arc::new(TransactionFilterChain::default()),
         false,
     )
 }
@@ -64,7 +73,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::SendingAccountDoesNotExist(
@@ -79,7 +90,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::InvalidSignature),
@@ -92,7 +105,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::InsufficientBalanceForTransactionFee),
@@ -105,7 +120,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::SequenceNumberTooNew),
@@ -118,7 +135,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::SequenceNumberTooOld),
@@ -131,7 +150,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::TransactionExpired),
@@ -144,7 +165,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::InvalidAuthKey),
@@ -157,7 +180,9 @@ fn test_submit_txn_inner_vm() {
         keypair.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(response, VMStatus::Execution(ExecutionStatus::Executed));
 
     let sender = AccountAddress::new([8; ADDRESS_LENGTH]);
@@ -169,13 +194,103 @@ fn test_submit_txn_inner_vm() {
         test_key.1,
         None,
     ));
-    let response = ac_service.submit_transaction_inner(req.clone()).unwrap();
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
     assert_status(
         response,
         VMStatus::Validation(VMValidationStatus::InvalidSignature),
     );
 }
 
+#[test]
+fn test_submit_txn_inner_filtered() {
+    let ac_service = AdmissionControlService::new(
+        Arc::new(LocalMockMempool::new()),
+        Arc::new(MockStorageReadClient),
+        Arc::new(MockVMValidator),
+        Arc::new(TransactionFilterChain::new(vec![Box::new(
+            MaxGasPriceFilter::new(0),
+        )])),
+        false,
+    );
+    let mut req: SubmitTransactionRequest = SubmitTransactionRequest::new();
+    let sender = AccountAddress::new([103; ADDRESS_LENGTH]);
+    let keypair = generate_keypair();
+    req.set_signed_txn(get_test_signed_transaction(
+        sender,
+        0,
+        keypair.0.clone(),
+        keypair.1,
+        None,
+        u64::max_value(),
+        1,
+        None,
+    ));
+    let response = SubmitTransactionResponse::from_proto(
+        ac_service.submit_transaction_inner(req, TEST_PEER).unwrap(),
+    )
+    .unwrap();
+    assert_matches!(
+        response.ac_status,
+        Some(AdmissionControlStatus::GasPriceTooHigh(_))
+    );
+}
+
+#[test]
+fn test_submit_txn_inner_rate_limited_after_validation() {
+    let ac_service = AdmissionControlService::new(
+        Arc::new(LocalMockMempool::new()),
+        Arc::new(MockStorageReadClient),
+        Arc::new(MockVMValidator),
+        Arc::new(TransactionFilterChain::new(vec![Box::new(
+            RateLimitFilter::per_sender(1, Duration::from_secs(60)),
+        )])),
+        false,
+    );
+    let sender = AccountAddress::new([8; ADDRESS_LENGTH]);
+    let keypair = generate_keypair();
+    let mut req: SubmitTransactionRequest = SubmitTransactionRequest::new();
+
+    // A transaction that doesn't pass validation doesn't use up the sender's quota.
+    let other_keypair = generate_keypair();
+    req.set_signed_txn(get_test_signed_txn(
+        sender,
+        0,
+        keypair.0.clone(),
+        other_keypair.1,
+        None,
+    ));
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
+    assert_status(
+        response,
+        VMStatus::Validation(VMValidationStatus::InvalidSignature),
+    );
+
+    req.set_signed_txn(get_test_signed_txn(
+        sender,
+        0,
+        keypair.0.clone(),
+        keypair.1,
+        None,
+    ));
+    let response = ac_service
+        .submit_transaction_inner(req.clone(), TEST_PEER)
+        .unwrap();
+    assert_status(response, VMStatus::Execution(ExecutionStatus::Executed));
+
+    let response = SubmitTransactionResponse::from_proto(
+        ac_service.submit_transaction_inner(req, TEST_PEER).unwrap(),
+    )
+    .unwrap();
+    assert_matches!(
+        response.ac_status,
+        Some(AdmissionControlStatus::RateLimited(_))
+    );
+}
+
 #[test]
 fn test_reject_unknown_fields() {
     let ac_service = create_ac_service_for_ut();
@@ -199,7 +314,9 @@ fn test_reject_unknown_fields() {
     signed_txn.set_sender_signature(signature.to_compact().to_vec());
     req.set_signed_txn(signed_txn);
     let response = SubmitTransactionResponse::from_proto(
-        ac_service.submit_transaction_inner(req.clone()).unwrap(),
+        ac_service
+            .submit_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
     )
     .unwrap();
     assert_matches!(
@@ -222,7 +339,9 @@ fn test_submit_txn_inner_mempool() {
         None,
     ));
     let response = SubmitTransactionResponse::from_proto(
-        ac_service.submit_transaction_inner(req.clone()).unwrap(),
+        ac_service
+            .submit_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
     )
     .unwrap();
     assert_eq!(
@@ -238,7 +357,9 @@ fn test_submit_txn_inner_mempool() {
         None,
     ));
     let response = SubmitTransactionResponse::from_proto(
-        ac_service.submit_transaction_inner(req.clone()).unwrap(),
+        ac_service
+            .submit_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
     )
     .unwrap();
     assert_eq!(
@@ -254,7 +375,9 @@ fn test_submit_txn_inner_mempool() {
         None,
     ));
     let response = SubmitTransactionResponse::from_proto(
-        ac_service.submit_transaction_inner(req.clone()).unwrap(),
+        ac_service
+            .submit_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
     )
     .unwrap();
     assert_eq!(
@@ -270,7 +393,9 @@ fn test_submit_txn_inner_mempool() {
         None,
     ));
     let response = SubmitTransactionResponse::from_proto(
-        ac_service.submit_transaction_inner(req.clone()).unwrap(),
+        ac_service
+            .submit_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
     )
     .unwrap();
     assert_eq!(
@@ -278,3 +403,183 @@ fn test_submit_txn_inner_mempool() {
         AdmissionControlStatus::Accepted,
     );
 }
+
+#[test]
+fn test_submit_txn_batch_inner() {
+    let ac_service = create_ac_service_for_ut();
+    let keypair = generate_keypair();
+    let accepted_add = AccountAddress::new([103; ADDRESS_LENGTH]);
+    let invalid_seq_add = AccountAddress::new([101; ADDRESS_LENGTH]);
+    let unknown_add = AccountAddress::new([0; ADDRESS_LENGTH]);
+    let mut req = SubmitTransactionBatchRequest::new();
+    for (sender, sequence_number) in vec![
+        (accepted_add, 1),
+        (invalid_seq_add, 0),
+        (accepted_add, 0),
+        (invalid_seq_add, 1),
+        (unknown_add, 0),
+    ] {
+        req.mut_signed_txns().push(get_test_signed_txn(
+            sender,
+            sequence_number,
+            keypair.0.clone(),
+            keypair.1,
+            None,
+        ));
+    }
+    let responses = SubmitTransactionBatchResponse::from_proto(
+        ac_service
+            .submit_transaction_batch_inner(req, TEST_PEER)
+            .unwrap(),
+    )
+    .unwrap()
+    .responses;
+    assert_eq!(responses.len(), 5);
+    // Consecutive sequence numbers of the same sender are accepted regardless of their order in
+    // the batch.
+    assert_eq!(
+        responses[0].ac_status,
+        Some(AdmissionControlStatus::Accepted)
+    );
+    assert_eq!(
+        responses[2].ac_status,
+        Some(AdmissionControlStatus::Accepted)
+    );
+    assert_eq!(
+        responses[1].mempool_error.as_ref().unwrap().code,
+        MempoolAddTransactionStatusCode::InvalidSeqNumber
+    );
+    // A rejected transaction causes the following ones of the same sender to be rejected.
+    assert_matches!(
+        responses[3].ac_status,
+        Some(AdmissionControlStatus::Rejected(_))
+    );
+    assert_eq!(
+        responses[4].vm_error,
+        Some(VMStatus::Validation(
+            VMValidationStatus::SendingAccountDoesNotExist("TEST".to_string())
+        ))
+    );
+}
+
+#[test]
+fn test_simulate_txn_inner() {
+    let ac_service = create_ac_service_for_ut();
+    let mut req = SimulateTransactionRequest::new();
+    let keypair = generate_keypair();
+    let sender = AccountAddress::new([8; ADDRESS_LENGTH]);
+    let mut signed_txn = get_test_signed_txn(sender, 0, keypair.0.clone(), keypair.1, None);
+    req.set_signed_txn(signed_txn.clone());
+    let response = SimulateTransactionResponse::from_proto(
+        ac_service
+            .simulate_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
+    )
+    .unwrap();
+    let txn_output = response.txn_output.unwrap();
+    assert_eq!(
+        txn_output.status(),
+        &TransactionStatus::Keep(VMStatus::Execution(ExecutionStatus::Executed))
+    );
+
+    // An unsigned transaction is rejected unless the signature check is skipped.
+    signed_txn.clear_sender_signature();
+    req.set_signed_txn(signed_txn);
+    let response = SimulateTransactionResponse::from_proto(
+        ac_service
+            .simulate_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_matches!(
+        response.ac_status.unwrap(),
+        AdmissionControlStatus::Rejected(_)
+    );
+    req.set_skip_signature_check(true);
+    let response = SimulateTransactionResponse::from_proto(
+        ac_service
+            .simulate_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
+    )
+    .unwrap();
+    let txn_output = response.txn_output.unwrap();
+    assert_eq!(
+        txn_output.status(),
+        &TransactionStatus::Keep(VMStatus::Execution(ExecutionStatus::Executed))
+    );
+
+    // The VM status is reported through the transaction output.
+    let sender = AccountAddress::new([2; ADDRESS_LENGTH]);
+    req.set_signed_txn(get_test_signed_txn(
+        sender,
+        0,
+        keypair.0.clone(),
+        keypair.1,
+        None,
+    ));
+    req.set_skip_signature_check(false);
+    let response = SimulateTransactionResponse::from_proto(
+        ac_service
+            .simulate_transaction_inner(req.clone(), TEST_PEER)
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        response.txn_output.unwrap().status(),
+        &TransactionStatus::Discard(VMStatus::Validation(
+            VMValidationStatus::InsufficientBalanceForTransactionFee
+        ))
+    );
+}
+
+#[test]
+fn test_simulate_txn_inner_filtered() {
+    let denied_sender = AccountAddress::new([3; ADDRESS_LENGTH]);
+    let ac_service = AdmissionControlService::new(
+        Arc::new(LocalMockMempool::new()),
+        Arc::new(MockStorageReadClient),
+        Arc::new(MockVMValidator),
+        Arc::new(TransactionFilterChain::new(vec![
+            Box::new(DenyListFilter::new(
+                vec![denied_sender].into_iter().collect(),
+                HashSet::new(),
+            )),
+            Box::new(RateLimitFilter::per_ip(1, Duration::from_secs(60))),
+        ])),
+        false,
+    );
+    let keypair = generate_keypair();
+    let simulate = |sender: AccountAddress, peer: &str| {
+        let mut req = SimulateTransactionRequest::new();
+        req.set_signed_txn(get_test_signed_txn(
+            sender,
+            0,
+            keypair.0.clone(),
+            keypair.1,
+            None,
+        ));
+        SimulateTransactionResponse::from_proto(
+            ac_service.simulate_transaction_inner(req, peer).unwrap(),
+        )
+        .unwrap()
+    };
+
+    // A denied sender is rejected before the transaction is executed.
+    let response = simulate(denied_sender, TEST_PEER);
+    assert!(response.txn_output.is_none());
+    assert_matches!(
+        response.ac_status,
+        Some(AdmissionControlStatus::Blacklisted(_))
+    );
+
+    // Simulations count towards the rate limit of the client.
+    let sender = AccountAddress::new([8; ADDRESS_LENGTH]);
+    assert!(simulate(sender, TEST_PEER).txn_output.is_some());
+    let response = simulate(sender, TEST_PEER);
+    assert!(response.txn_output.is_none());
+    assert_matches!(
+        response.ac_status,
+        Some(AdmissionControlStatus::RateLimited(_))
+    );
+    assert!(simulate(sender, "ipv4:127.0.0.2:8000").txn_output.is_some());
+}
@@ -0,0 +1,182 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::transaction_filter::{
+    DenyListFilter, MaxGasPriceFilter, MaxTransactionSizeFilter, RateLimitFilter,
+    ScriptAllowListFilter, TransactionFilter, TransactionFilterChain,
+};
+use admission_control_proto::AdmissionControlStatus;
+use assert_matches::assert_matches;
+use config::config::TransactionFilterConfig;
+use crypto::{signing::generate_keypair, HashValue};
+use std::{collections::HashSet, iter::FromIterator, thread, time::Duration};
+use types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    test_helpers::transaction_test_helpers::get_test_unchecked_transaction,
+    transaction::{Program, SignedTransaction, TransactionArgument},
+};
+
+const PEER: &str = "ipv4:127.0.0.1:8000";
+
+fn test_txn(
+    sender: AccountAddress,
+    program: Option<Program>,
+    gas_unit_price: u64,
+) -> SignedTransaction {
+    let (private_key, public_key) = generate_keypair();
+    get_test_unchecked_transaction(
+        sender,
+        0,
+        private_key,
+        public_key,
+        program,
+        u64::max_value(),
+        gas_unit_price,
+        None,
+    )
+}
+
+fn address(byte: u8) -> AccountAddress {
+    AccountAddress::new([byte; ADDRESS_LENGTH])
+}
+
+fn transfer_program(receiver: AccountAddress) -> Program {
+    Program::new(
+        vec![1, 2, 3],
+        vec![],
+        vec![
+            TransactionArgument::Address(receiver),
+            TransactionArgument::U64(100),
+        ],
+    )
+}
+
+#[test]
+fn test_deny_list_filter() {
+    let filter = DenyListFilter::new(
+        HashSet::from_iter(vec![address(1)]),
+        HashSet::from_iter(vec![address(2)]),
+    );
+    assert_matches!(
+        filter.check(&test_txn(address(1), None, 0), PEER),
+        Some(AdmissionControlStatus::Blacklisted(_))
+    );
+    assert_matches!(
+        filter.check(
+            &test_txn(address(3), Some(transfer_program(address(2))), 0),
+            PEER
+        ),
+        Some(AdmissionControlStatus::Blacklisted(_))
+    );
+    assert_eq!(
+        filter.check(
+            &test_txn(address(3), Some(transfer_program(address(4))), 0),
+            PEER
+        ),
+        None
+    );
+}
+
+#[test]
+fn test_max_txn_size_filter() {
+    let txn = test_txn(address(1), None, 0);
+    let txn_size = txn.raw_txn_bytes_len();
+    assert_eq!(
+        MaxTransactionSizeFilter::new(txn_size).check(&txn, PEER),
+        None
+    );
+    assert_matches!(
+        MaxTransactionSizeFilter::new(txn_size - 1).check(&txn, PEER),
+        Some(AdmissionControlStatus::TransactionTooLarge(_))
+    );
+}
+
+#[test]
+fn test_max_gas_price_filter() {
+    let filter = MaxGasPriceFilter::new(10);
+    assert_eq!(filter.check(&test_txn(address(1), None, 10), PEER), None);
+    assert_matches!(
+        filter.check(&test_txn(address(1), None, 11), PEER),
+        Some(AdmissionControlStatus::GasPriceTooHigh(_))
+    );
+}
+
+#[test]
+fn test_script_allow_list_filter() {
+    let program = transfer_program(address(2));
+    let script_hash = HashValue::from_iter_sha3(vec![program.code()]);
+    let filter = ScriptAllowListFilter::new(HashSet::from_iter(vec![script_hash]));
+    assert_eq!(
+        filter.check(&test_txn(address(1), Some(program), 0), PEER),
+        None
+    );
+    let other_program = Program::new(vec![4, 5, 6], vec![], vec![]);
+    assert_matches!(
+        filter.check(&test_txn(address(1), Some(other_program), 0), PEER),
+        Some(AdmissionControlStatus::ScriptNotAllowed(_))
+    );
+}
+
+#[test]
+fn test_rate_limit_per_sender() {
+    let filter = RateLimitFilter::per_sender(2, Duration::from_secs(60));
+    let txn = test_txn(address(1), None, 0);
+    assert_eq!(filter.check(&txn, PEER), None);
+    assert_eq!(filter.check(&txn, PEER), None);
+    assert_matches!(
+        filter.check(&txn, PEER),
+        Some(AdmissionControlStatus::RateLimited(_))
+    );
+    // Other senders have their own quota.
+    assert_eq!(filter.check(&test_txn(address(2), None, 0), PEER), None);
+}
+
+#[test]
+fn test_rate_limit_per_ip() {
+    let filter = RateLimitFilter::per_ip(1, Duration::from_millis(50));
+    let txn = test_txn(address(1), None, 0);
+    assert_eq!(filter.check(&txn, PEER), None);
+    // Another connection from the same IP shares the quota.
+    assert_matches!(
+        filter.check(&txn, "ipv4:127.0.0.1:8001"),
+        Some(AdmissionControlStatus::RateLimited(_))
+    );
+    assert_eq!(filter.check(&txn, "ipv4:127.0.0.2:8000"), None);
+    // The quota is reset once the window expires.
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(filter.check(&txn, PEER), None);
+}
+
+#[test]
+fn test_filter_chain_from_config() {
+    let config = TransactionFilterConfig {
+        sender_deny_list: vec![format!("{:x}", address(1))],
+        max_gas_unit_price: Some(10),
+        max_txns_per_sender: Some(1),
+        ..TransactionFilterConfig::default()
+    };
+    let chain = TransactionFilterChain::from_config(&config).unwrap();
+    assert_matches!(
+        chain.check(&test_txn(address(1), None, 0), PEER),
+        Some(AdmissionControlStatus::Blacklisted(_))
+    );
+    assert_matches!(
+        chain.check(&test_txn(address(2), None, 11), PEER),
+        Some(AdmissionControlStatus::GasPriceTooHigh(_))
+    );
+    // The per-sender rate limit only applies to validated transactions.
+    let txn = test_txn(address(2), None, 0);
+    assert_eq!(chain.check(&txn, PEER), None);
+    assert_eq!(chain.check(&txn, PEER), None);
+    assert_eq!(chain.check_validated(&txn, PEER), None);
+    assert_matches!(
+        chain.check_validated(&txn, PEER),
+        Some(AdmissionControlStatus::RateLimited(_))
+    );
+
+    let invalid_config = TransactionFilterConfig {
+        script_allow_list: Some(vec!["not hex".to_string()]),
+        ..TransactionFilterConfig::default()
+    };
+    assert!(TransactionFilterChain::from_config(&invalid_config).is_err());
+}
@@ -0,0 +1,128 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! AC service of a full node. A full node does not run consensus, so the transactions submitted
+//! to it are forwarded to the AC of an upstream validator, while reads and simulations are served
+//! from the local storage, which follows the validators via state synchronization.
+
+use crate::{admission_control_service::AdmissionControlService, OP_COUNTERS};
+use admission_control_proto::proto::{
+    admission_control::{
+        SimulateTransactionRequest, SimulateTransactionResponse, SubmitTransactionBatchRequest,
+        SubmitTransactionBatchResponse, SubmitTransactionRequest, SubmitTransactionResponse,
+    },
+    admission_control_grpc::{AdmissionControl, AdmissionControlClient},
+};
+use failure::prelude::*;
+use grpc_helpers::provide_grpc_response;
+use logger::prelude::*;
+use mempool::proto::mempool_client::MempoolClientTrait;
+use metrics::counters::SVC_COUNTERS;
+use std::sync::Arc;
+use types::proto::get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse};
+use vm_validator::vm_validator::TransactionValidation;
+
+/// Struct implementing trait (service handle) AdmissionControl for full nodes.
+#[derive(Clone)]
+pub struct UpstreamProxyService<M, V> {
+    /// Local AC serving the read-only requests.
+    local: AdmissionControlService<M, V>,
+    /// gRPC client connecting the AC of the upstream validator.
+    upstream_client: Arc<AdmissionControlClient>,
+}
+
+impl<M: 'static, V> UpstreamProxyService<M, V>
+where
+    M: MempoolClientTrait,
+    V: TransactionValidation,
+{
+    /// Constructs a new UpstreamProxyService instance.
+    pub fn new(
+        local: AdmissionControlService<M, V>,
+        upstream_client: Arc<AdmissionControlClient>,
+    ) -> Self {
+        UpstreamProxyService {
+            local,
+            upstream_client,
+        }
+    }
+
+    /// Forward the transaction to the upstream validator.
+    fn submit_transaction_inner(
+        &self,
+        req: SubmitTransactionRequest,
+    ) -> Result<SubmitTransactionResponse> {
+        OP_COUNTERS.inc_by("upstream_proxy.submit_txn.forwarded", 1);
+        Ok(self.upstream_client.submit_transaction(&req)?)
+    }
+
+    /// Forward the batch of transactions to the upstream validator.
+    fn submit_transaction_batch_inner(
+        &self,
+        req: SubmitTransactionBatchRequest,
+    ) -> Result<SubmitTransactionBatchResponse> {
+        OP_COUNTERS.inc_by(
+            "upstream_proxy.submit_txn.forwarded",
+            req.get_signed_txns().len(),
+        );
+        Ok(self.upstream_client.submit_transaction_batch(&req)?)
+    }
+}
+
+impl<M: 'static, V> AdmissionControl for UpstreamProxyService<M, V>
+where
+    M: MempoolClientTrait,
+    V: TransactionValidation,
+{
+    /// Submit a transaction to the upstream validator.
+    fn submit_transaction(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SubmitTransactionRequest,
+        sink: ::grpcio::UnarySink<SubmitTransactionResponse>,
+    ) {
+        debug!("[GRPC] UpstreamProxy::submit_transaction");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.submit_transaction_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Submit multiple transactions to the upstream validator in one request.
+    fn submit_transaction_batch(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SubmitTransactionBatchRequest,
+        sink: ::grpcio::UnarySink<SubmitTransactionBatchResponse>,
+    ) {
+        debug!("[GRPC] UpstreamProxy::submit_transaction_batch");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.submit_transaction_batch_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Simulate a transaction against the local state.
+    fn simulate_transaction(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        req: SimulateTransactionRequest,
+        sink: ::grpcio::UnarySink<SimulateTransactionResponse>,
+    ) {
+        debug!("[GRPC] UpstreamProxy::simulate_transaction");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.local.simulate_transaction_inner(req, &ctx.peer());
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    /// Serve the read query from the local storage.
+    fn update_to_latest_ledger(
+        &mut self,
+        ctx: grpcio::RpcContext<'_>,
+        req: UpdateToLatestLedgerRequest,
+        sink: grpcio::UnarySink<UpdateToLatestLedgerResponse>,
+    ) {
+        debug!("[GRPC] UpstreamProxy::update_to_latest_ledger");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.local.update_to_latest_ledger_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+}
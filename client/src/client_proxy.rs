@@ -2,9 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{commands::*, grpc_client::GRPCClient, AccountData, AccountStatus};
-use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
+use admission_control_proto::proto::admission_control::{
+    SimulateTransactionRequest, SubmitTransactionRequest,
+};
 use config::trusted_peers::TrustedPeersConfig;
-use crypto::signing::KeyPair;
+use crypto::{signing::KeyPair, HashValue};
 use failure::prelude::*;
 use futures::{future::Future, stream::Stream};
 use hyper;
@@ -37,7 +39,7 @@ use types::{
     },
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     contract_event::{ContractEvent, EventWithProof},
-    transaction::{Program, RawTransaction, SignedTransaction, Version},
+    transaction::{Program, RawTransaction, SignedTransaction, TransactionOutput, Version},
     transaction_helpers::{create_signed_txn, TransactionSigner},
     validator_verifier::ValidatorVerifier,
 };
@@ -51,6 +53,7 @@ const TX_EXPIRATION: i64 = 100;
 #[derive(Debug)]
 enum InputType {
     Bool,
+    Hash,
     UnsignedInt,
     Usize,
 }
@@ -371,6 +374,46 @@ impl ClientProxy {
         space_delim_strings: &[&str],
         is_blocking: bool,
     ) -> Result<IndexAndSequence> {
+        let (sender_account_ref_id, receiver_address, num_coins, gas_unit_price, max_gas_amount) =
+            self.parse_transfer_params(space_delim_strings)?;
+
+        self.transfer_coins_int(
+            sender_account_ref_id,
+            &receiver_address,
+            num_coins,
+            gas_unit_price,
+            max_gas_amount,
+            is_blocking,
+        )
+    }
+
+    /// Simulates a transfer of coins from sender to receiver against the latest committed state
+    /// of the validator. Nothing is committed and the sender's sequence number is left untouched.
+    pub fn simulate_transfer_coins(
+        &mut self,
+        space_delim_strings: &[&str],
+    ) -> Result<TransactionOutput> {
+        let (sender_account_ref_id, receiver_address, num_coins, gas_unit_price, max_gas_amount) =
+            self.parse_transfer_params(space_delim_strings)?;
+        let sender = self.accounts.get(sender_account_ref_id).ok_or_else(|| {
+            format_err!("Unable to find sender account: {}", sender_account_ref_id)
+        })?;
+
+        let program = vm_genesis::encode_transfer_program(&receiver_address, num_coins);
+        let submit_req =
+            self.create_submit_transaction_req(program, sender, max_gas_amount, gas_unit_price)?;
+        let mut req = SimulateTransactionRequest::new();
+        req.set_signed_txn(submit_req.get_signed_txn().clone());
+        self.client.simulate_transaction(&req)
+    }
+
+    /// Parses `<sender> <receiver> <number_of_coins> [gas_unit_price] [max_gas_amount]` into
+    /// the sender's account_ref_id, the receiver's address, the number of micro libras and the
+    /// optional gas parameters.
+    fn parse_transfer_params(
+        &self,
+        space_delim_strings: &[&str],
+    ) -> Result<(usize, AccountAddress, u64, Option<u64>, Option<u64>)> {
         ensure!(
             space_delim_strings.len() >= 4 && space_delim_strings.len() <= 6,
             "Invalid number of arguments for transfer"
@@ -410,14 +453,13 @@ impl ClientProxy {
 
         let sender_account_ref_id = self.get_account_ref_id(&sender_account_address)?;
 
-        self.transfer_coins_int(
+        Ok((
             sender_account_ref_id,
-            &receiver_address,
+            receiver_address,
             num_coins,
             gas_unit_price,
             max_gas_amount,
-            is_blocking,
-        )
+        ))
     }
 
     /// Submit a transaction to the network.
@@ -503,6 +545,50 @@ impl ClientProxy {
         self.get_account_state_and_update(account)
     }
 
+    /// Get the account state at a version from validator.
+    pub fn get_account_state_by_version(
+        &mut self,
+        space_delim_strings: &[&str],
+    ) -> Result<Option<AccountStateBlob>> {
+        ensure!(
+            space_delim_strings.len() == 3,
+            "Invalid number of arguments to get account state by version"
+        );
+        let account = self.get_account_address_from_parameter(space_delim_strings[1])?;
+        let version = space_delim_strings[2].parse::<u64>().map_err(|error| {
+            format_parse_data_error(
+                "version",
+                InputType::UnsignedInt,
+                space_delim_strings[2],
+                error,
+            )
+        })?;
+        self.client.get_account_blob_by_version(account, version)
+    }
+
+    /// Get committed txn by the hash of the signed transaction.
+    pub fn get_committed_txn_by_hash(
+        &mut self,
+        space_delim_strings: &[&str],
+    ) -> Result<Option<(Version, SignedTransaction, Option<Vec<ContractEvent>>)>> {
+        ensure!(
+            space_delim_strings.len() == 2,
+            "Invalid number of arguments to get transaction by hash"
+        );
+        let hash = hex::decode(space_delim_strings[1])
+            .map_err(Error::from)
+            .and_then(|bytes| HashValue::from_slice(&bytes))
+            .map_err(|error| {
+                format_parse_data_error(
+                    "transaction_hash",
+                    InputType::Hash,
+                    space_delim_strings[1],
+                    error,
+                )
+            })?;
+        self.client.get_txn_by_hash(hash)
+    }
+
     /// Get committed txn by account and sequnce number.
     pub fn get_committed_txn_by_acc_seq(
         &mut self,
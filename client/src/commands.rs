@@ -3,6 +3,7 @@
 
 use crate::{
     account_commands::AccountCommand, client_proxy::ClientProxy, query_commands::QueryCommand,
+    simulate_commands::SimulateCommand,
     submit_transaction_command::SubmitTransactionFromDiskCommand,
     transfer_commands::TransferCommand,
 };
@@ -63,6 +64,7 @@ pub fn get_commands() -> (
         Arc::new(AccountCommand {}),
         Arc::new(QueryCommand {}),
         Arc::new(TransferCommand {}),
+        Arc::new(SimulateCommand {}),
         Arc::new(SubmitTransactionFromDiskCommand {}),
     ];
     let mut alias_to_cmd = HashMap::new();
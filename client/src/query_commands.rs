@@ -20,7 +20,9 @@ impl Command for QueryCommand {
             Box::new(QueryCommandGetBalance {}),
             Box::new(QueryCommandGetSeqNum {}),
             Box::new(QueryCommandGetLatestAccountState {}),
+            Box::new(QueryCommandGetAccountStateByVersion {}),
             Box::new(QueryCommandGetTxnByAccountSeq {}),
+            Box::new(QueryCommandGetTxnByHash {}),
             Box::new(QueryCommandGetTxnByRange {}),
             Box::new(QueryCommandGetEvent {}),
         ];
@@ -112,6 +114,41 @@ impl Command for QueryCommandGetLatestAccountState {
     }
 }
 
+/// Command to query the account state at a version from validator.
+pub struct QueryCommandGetAccountStateByVersion {}
+
+impl Command for QueryCommandGetAccountStateByVersion {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["account_state_by_version", "asv"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<account_ref_id>|<account_address> <version>"
+    }
+    fn get_description(&self) -> &'static str {
+        "Get the state for an account at a version"
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        println!(">> Getting account state by version");
+        match client.get_account_state_by_version(&params) {
+            Ok(acc) => match get_account_resource_or_default(&acc) {
+                Ok(_) => println!(
+                    "Account state is: \n \
+                     Account: {:#?}\n \
+                     State: {:#?}\n \
+                     Blockchain Version: {}\n",
+                    client
+                        .get_account_address_from_parameter(params[1])
+                        .expect("Unable to parse account parameter"),
+                    acc,
+                    params[2],
+                ),
+                Err(e) => report_error("Error converting account blob to account resource", e),
+            },
+            Err(e) => report_error("Error getting account state by version", e),
+        }
+    }
+}
+
 /// Sub command  to get transaction by account and sequence number from validator.
 pub struct QueryCommandGetTxnByAccountSeq {}
 
@@ -154,6 +191,42 @@ impl Command for QueryCommandGetTxnByAccountSeq {
     }
 }
 
+/// Sub command to get transaction by hash from validator.
+pub struct QueryCommandGetTxnByHash {}
+
+impl Command for QueryCommandGetTxnByHash {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["txn_hash", "th"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "<transaction_hash>"
+    }
+    fn get_description(&self) -> &'static str {
+        "Get the committed transaction and the events emitted by it by the hex encoded hash of \
+         the signed transaction."
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        println!(">> Getting committed transaction by hash");
+        match client.get_committed_txn_by_hash(&params) {
+            Ok(Some((version, comm_txn, events))) => {
+                println!(
+                    "Committed transaction at version {}: {}",
+                    version,
+                    comm_txn.format_for_client(get_transaction_name)
+                );
+                if let Some(events_inner) = &events {
+                    println!("Events: ");
+                    for event in events_inner {
+                        println!("{}", event);
+                    }
+                }
+            }
+            Ok(None) => println!("Transaction not available"),
+            Err(e) => report_error("Error getting committed transaction by hash", e),
+        }
+    }
+}
+
 /// Sub command to query transactions by range from validator.
 pub struct QueryCommandGetTxnByRange {}
 
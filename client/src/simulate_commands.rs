@@ -0,0 +1,50 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{client_proxy::ClientProxy, commands::*};
+
+/// Command to simulate a transfer of coins between two accounts without committing it.
+pub struct SimulateCommand {}
+
+impl Command for SimulateCommand {
+    fn get_aliases(&self) -> Vec<&'static str> {
+        vec!["simulate", "sim"]
+    }
+    fn get_params_help(&self) -> &'static str {
+        "\n\t<sender_account_address>|<sender_account_ref_id> \
+         <receiver_account_address>|<receiver_account_ref_id> <number_of_coins> \
+         [gas_unit_price_in_micro_libras (default=0)] [max_gas_amount_in_micro_libras (default 10000)]"
+    }
+    fn get_description(&self) -> &'static str {
+        "Simulate a transfer of coins (in libra) against the latest state, e.g. to estimate gas. \
+         Nothing is committed."
+    }
+    fn execute(&self, client: &mut ClientProxy, params: &[&str]) {
+        if params.len() < 4 || params.len() > 6 {
+            println!("Invalid number of arguments for simulate");
+            println!(
+                "{} {}",
+                self.get_aliases().join(" | "),
+                self.get_params_help()
+            );
+            return;
+        }
+
+        println!(">> Simulating transfer");
+        match client.simulate_transfer_coins(&params) {
+            Ok(txn_output) => {
+                println!("Status: {:?}", txn_output.status());
+                println!("Gas used: {}", txn_output.gas_used());
+                println!("Write set:");
+                for (access_path, write_op) in txn_output.write_set() {
+                    println!("\t{:?}: {:?}", access_path, write_op);
+                }
+                println!("Events:");
+                for event in txn_output.events() {
+                    println!("\t{}", event);
+                }
+            }
+            Err(e) => report_error("Failed to simulate transaction", e),
+        }
+    }
+}
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::proto::{
-    node_debug_interface::{DumpJemallocHeapProfileRequest, GetNodeDetailsRequest},
+    node_debug_interface::{
+        DumpJemallocHeapProfileRequest, EquivocationEvidence, GetEquivocationEvidenceRequest,
+        GetNodeDetailsRequest, GetTransactionLifecycleRequest, TransactionLifecycleEvent,
+    },
     node_debug_interface_grpc::NodeDebugInterfaceClient,
 };
 use failure::prelude::*;
@@ -59,6 +62,31 @@ impl NodeDebugClient {
             .collect()
     }
 
+    /// Returns the lifecycle history of the transaction with the given hash in mempool.
+    pub fn get_transaction_lifecycle(
+        &self,
+        txn_hash: &[u8],
+    ) -> Result<Vec<TransactionLifecycleEvent>> {
+        let mut request = GetTransactionLifecycleRequest::new();
+        request.set_txn_hash(txn_hash.to_vec());
+        let mut response = self
+            .client
+            .get_transaction_lifecycle(&request)
+            .context("Unable to query transaction lifecycle")?;
+
+        Ok(response.take_events().into_vec())
+    }
+
+    /// Returns the evidence of the equivocating validators recorded by consensus.
+    pub fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence>> {
+        let mut response = self
+            .client
+            .get_equivocation_evidence(&GetEquivocationEvidenceRequest::new())
+            .context("Unable to query equivocation evidence")?;
+
+        Ok(response.take_evidence().into_vec())
+    }
+
     pub fn dump_heap_profile(&self) -> Result<i32> {
         let response = self
             .client
@@ -5,22 +5,61 @@
 
 use crate::proto::{
     node_debug_interface::{
-        DumpJemallocHeapProfileRequest, DumpJemallocHeapProfileResponse, GetNodeDetailsRequest,
-        GetNodeDetailsResponse,
+        DumpJemallocHeapProfileRequest, DumpJemallocHeapProfileResponse, EquivocationEvidence,
+        GetEquivocationEvidenceRequest, GetEquivocationEvidenceResponse, GetNodeDetailsRequest,
+        GetNodeDetailsResponse, GetTransactionLifecycleRequest, GetTransactionLifecycleResponse,
+        TransactionLifecycleEvent,
     },
     node_debug_interface_grpc::NodeDebugInterface,
 };
+use failure::prelude::*;
 use futures::Future;
+use grpcio::{RpcStatus, RpcStatusCode};
 use logger::prelude::*;
 use metrics::counters::COUNTER_ADMISSION_CONTROL_CANNOT_SEND_REPLY;
+use std::sync::Arc;
+
+/// Component keeping the lifecycle history of transactions, e.g. mempool.
+pub trait TransactionLifecycleSource: Send + Sync {
+    /// Returns the recorded events of the transaction with the given hash, oldest first.
+    fn get_transaction_lifecycle(&self, txn_hash: &[u8]) -> Result<Vec<TransactionLifecycleEvent>>;
+}
+
+/// Component recording the evidence of equivocating validators, e.g. consensus.
+pub trait EquivocationEvidenceSource: Send + Sync {
+    /// Returns all the recorded evidence, ordered by round.
+    fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence>>;
+}
 
 #[derive(Clone, Default)]
-pub struct NodeDebugService {}
+pub struct NodeDebugService {
+    transaction_lifecycle: Option<Arc<dyn TransactionLifecycleSource>>,
+    equivocation_evidence: Option<Arc<dyn EquivocationEvidenceSource>>,
+}
 
 impl NodeDebugService {
     pub fn new() -> Self {
         Default::default()
     }
+
+    /// Creates a service which can also answer transaction lifecycle queries.
+    pub fn with_transaction_lifecycle(
+        transaction_lifecycle: Arc<dyn TransactionLifecycleSource>,
+    ) -> Self {
+        Self {
+            transaction_lifecycle: Some(transaction_lifecycle),
+            equivocation_evidence: None,
+        }
+    }
+
+    /// Makes the service also answer equivocation evidence queries.
+    pub fn with_equivocation_evidence(
+        mut self,
+        equivocation_evidence: Arc<dyn EquivocationEvidenceSource>,
+    ) -> Self {
+        self.equivocation_evidence = Some(equivocation_evidence);
+        self
+    }
 }
 
 impl NodeDebugInterface for NodeDebugService {
@@ -52,6 +91,70 @@ impl NodeDebugInterface for NodeDebugService {
         let f = sink.success(resp).map_err(default_reply_error_logger);
         ctx.spawn(f)
     }
+
+    fn get_transaction_lifecycle(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        request: GetTransactionLifecycleRequest,
+        sink: ::grpcio::UnarySink<GetTransactionLifecycleResponse>,
+    ) {
+        trace!("[GRPC] get_transaction_lifecycle");
+        let source = match &self.transaction_lifecycle {
+            Some(source) => source,
+            None => {
+                let status = RpcStatus::new(
+                    RpcStatusCode::Unimplemented,
+                    Some("Transaction lifecycle is not available on this node".to_string()),
+                );
+                ctx.spawn(sink.fail(status).map_err(default_reply_error_logger));
+                return;
+            }
+        };
+        let f = match source.get_transaction_lifecycle(request.get_txn_hash()) {
+            Ok(events) => {
+                let mut resp = GetTransactionLifecycleResponse::new();
+                resp.set_events(events.into());
+                sink.success(resp)
+            }
+            Err(e) => sink.fail(RpcStatus::new(
+                RpcStatusCode::InvalidArgument,
+                Some(format!("{}", e)),
+            )),
+        };
+        ctx.spawn(f.map_err(default_reply_error_logger))
+    }
+
+    fn get_equivocation_evidence(
+        &mut self,
+        ctx: ::grpcio::RpcContext<'_>,
+        _request: GetEquivocationEvidenceRequest,
+        sink: ::grpcio::UnarySink<GetEquivocationEvidenceResponse>,
+    ) {
+        trace!("[GRPC] get_equivocation_evidence");
+        let source = match &self.equivocation_evidence {
+            Some(source) => source,
+            None => {
+                let status = RpcStatus::new(
+                    RpcStatusCode::Unimplemented,
+                    Some("Equivocation evidence is not available on this node".to_string()),
+                );
+                ctx.spawn(sink.fail(status).map_err(default_reply_error_logger));
+                return;
+            }
+        };
+        let f = match source.get_equivocation_evidence() {
+            Ok(evidence) => {
+                let mut resp = GetEquivocationEvidenceResponse::new();
+                resp.set_evidence(evidence.into());
+                sink.success(resp)
+            }
+            Err(e) => sink.fail(RpcStatus::new(
+                RpcStatusCode::Internal,
+                Some(format!("{}", e)),
+            )),
+        };
+        ctx.spawn(f.map_err(default_reply_error_logger))
+    }
 }
 
 fn default_reply_error_logger<T: ::std::fmt::Debug>(e: T) {
@@ -3,7 +3,7 @@
 
 use failure::{prelude::*, Result};
 use futures::{compat::Future01CompatExt, future::Future, prelude::*};
-use futures_01::future::Future as Future01;
+use futures_01::{future::Future as Future01, sink::Sink as Sink01, stream::Stream as Stream01};
 use grpcio::{EnvBuilder, ServerBuilder};
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
@@ -53,6 +53,30 @@ pub fn provide_grpc_response<ResponseType: std::fmt::Debug>(
     SVC_COUNTERS.resp(&ctx, success);
 }
 
+/// This is a helper method to stream `responses` to the GRPC context of a server-streaming call.
+/// The next response is only polled for after the previous one is sent, so the flow control of
+/// the call throttles producing them. An error from `responses` is logged and fails the call.
+pub fn provide_grpc_stream<ResponseType, S>(
+    responses: S,
+    ctx: ::grpcio::RpcContext<'_>,
+    sink: ::grpcio::ServerStreamingSink<ResponseType>,
+) where
+    ResponseType: Send + 'static,
+    S: Stream01<Item = ResponseType, Error = ::failure::Error> + Send + 'static,
+{
+    let method = from_utf8(ctx.method())
+        .expect("Unable to convert function name to string")
+        .to_string();
+    let responses = responses
+        .map(|resp| (resp, ::grpcio::WriteFlags::default()))
+        .map_err(move |e| ::grpcio::Error::RpcFailure(create_grpc_invalid_arg_status(&method, e)));
+    ctx.spawn(
+        sink.send_all(responses)
+            .map(|_| ())
+            .map_err(|e| error!("Failed to stream responses due to {:?}", e)),
+    );
+}
+
 pub fn spawn_service_thread(
     service: ::grpcio::Service,
     service_host_address: String,
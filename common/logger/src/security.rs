@@ -36,6 +36,12 @@ pub enum SecurityEvent {
     /// Consensus received an invalid new round message
     InvalidConsensusRound,
 
+    /// Consensus received two different votes of a validator for the same round
+    EquivocatingConsensusVote,
+
+    /// Consensus received two different proposals of a validator for the same round
+    EquivocatingConsensusProposal,
+
     /// A block being committed or executed is invalid
     InvalidBlock,
 
@@ -7,6 +7,7 @@ use std::convert::TryInto;
 
 const BASE_ARG: &str = "base";
 const NODES_ARG: &str = "nodes";
+const FULL_NODES_ARG: &str = "full-nodes";
 const OUTPUT_DIR_ARG: &str = "output-dir";
 const DISCOVERY_ARG: &str = "discovery";
 const KEY_SEED_ARG: &str = "key-seed";
@@ -33,6 +34,14 @@ fn main() {
                 .default_value("1")
                 .help("Specify the number of nodes to configure"),
         )
+        .arg(
+            Arg::with_name(FULL_NODES_ARG)
+                .short("f")
+                .long(FULL_NODES_ARG)
+                .takes_value(true)
+                .default_value("0")
+                .help("Specify the number of full nodes to configure in addition to the validators"),
+        )
         .arg(
             Arg::with_name(OUTPUT_DIR_ARG)
                 .short("o")
@@ -63,6 +72,7 @@ fn main() {
         .get_matches();
     let base_path = value_t!(args, BASE_ARG, String).expect("Path to base config");
     let nodes_count = value_t!(args, NODES_ARG, usize).unwrap();
+    let full_nodes_count = value_t!(args, FULL_NODES_ARG, usize).unwrap();
     let output_dir = if args.is_present(OUTPUT_DIR_ARG) {
         let dir = value_t!(args, OUTPUT_DIR_ARG, String).unwrap();
         dir.into()
@@ -77,6 +87,7 @@ fn main() {
     let mut config_builder = SwarmConfigBuilder::new();
     config_builder
         .with_nodes(nodes_count)
+        .with_full_nodes(full_nodes_count)
         .with_base(base_path)
         .with_output_dir(output_dir)
         .with_faucet_keypair(faucet_account_keypair);
@@ -4,7 +4,7 @@
 //! Convenience structs and functions for generating configuration for a swarm of libra nodes
 use crate::util::gen_genesis_transaction;
 use config::{
-    config::{KeyPairs, NodeConfig, NodeConfigHelpers},
+    config::{KeyPairs, NodeConfig, NodeConfigHelpers, RoleType},
     seed_peers::{SeedPeersConfig, SeedPeersConfigHelpers},
     trusted_peers::{TrustedPeersConfig, TrustedPeersConfigHelpers},
 };
@@ -24,6 +24,7 @@ impl SwarmConfig {
     pub fn new(
         mut template: NodeConfig,
         num_nodes: usize,
+        num_full_nodes: usize,
         faucet_key: KeyPair,
         prune_seed_peers_for_discovery: bool,
         is_ipv4: bool,
@@ -33,14 +34,24 @@ impl SwarmConfig {
     ) -> Result<Self> {
         // Generate trusted peer configs + their private keys.
         template.base.data_dir_path = output_dir.into();
-        let (peers_private_keys, trusted_peers_config) =
-            TrustedPeersConfigHelpers::get_test_config(num_nodes, key_seed);
-        trusted_peers_config.save_config(&output_dir.join(&template.base.trusted_peers_file));
+        let (peers_private_keys, mut trusted_peers_config) =
+            TrustedPeersConfigHelpers::get_test_config(num_nodes + num_full_nodes, key_seed);
         let mut seed_peers_config = SeedPeersConfigHelpers::get_test_config_with_ipver(
             &trusted_peers_config,
             None,
             is_ipv4,
         );
+        // The last peers in the sorted order become full nodes: they are trusted network peers,
+        // but are not part of the validator set.
+        let mut peer_ids: Vec<String> = trusted_peers_config.peers.keys().cloned().collect();
+        peer_ids.sort_unstable();
+        for peer_id in peer_ids.into_iter().skip(num_nodes) {
+            let trusted_peer = trusted_peers_config.peers.remove(&peer_id).unwrap();
+            trusted_peers_config
+                .full_nodes
+                .insert(peer_id, trusted_peer);
+        }
+        trusted_peers_config.save_config(&output_dir.join(&template.base.trusted_peers_file));
 
         gen_genesis_transaction(
             &output_dir.join(&template.execution.genesis_file_location),
@@ -74,13 +85,32 @@ impl SwarmConfig {
             config.network.advertised_address = addrs[0].clone();
 
             config.vm_config = default_config();
+            if trusted_peers_config.full_nodes.contains_key(node_id) {
+                config.base.role = RoleType::FullNode;
+            }
             configs.push(config);
         }
+        // Full nodes forward the submitted transactions to the AC of a validator.
+        let upstream_ac_port = configs
+            .iter()
+            .find(|config| {
+                trusted_peers_config
+                    .peers
+                    .contains_key(&config.base.peer_id)
+            })
+            .map(|config| config.admission_control.admission_control_service_port);
+        for config in configs.iter_mut() {
+            if let Some(port) = upstream_ac_port {
+                config.full_node.upstream_ac_port = port;
+            }
+        }
         if prune_seed_peers_for_discovery {
+            // Only a validator is kept as the seed peer.
             seed_peers_config.seed_peers = seed_peers_config
                 .seed_peers
                 .clone()
                 .into_iter()
+                .filter(|(peer_id, _)| trusted_peers_config.peers.contains_key(peer_id))
                 .take(1)
                 .collect();
         }
@@ -126,6 +156,7 @@ impl SwarmConfig {
 
 pub struct SwarmConfigBuilder {
     node_count: usize,
+    full_node_count: usize,
     template_path: PathBuf,
     static_ports: bool,
     output_dir: PathBuf,
@@ -139,6 +170,7 @@ impl Default for SwarmConfigBuilder {
     fn default() -> Self {
         SwarmConfigBuilder {
             node_count: 1,
+            full_node_count: 0,
             template_path: "config/data/configs/node.config.toml".into(),
             static_ports: false,
             output_dir: "configs".into(),
@@ -191,6 +223,11 @@ impl SwarmConfigBuilder {
         self
     }
 
+    pub fn with_full_nodes(&mut self, n: usize) -> &mut Self {
+        self.full_node_count = n;
+        self
+    }
+
     pub fn force_discovery(&mut self) -> &mut Self {
         self.force_discovery = true;
         self
@@ -256,6 +293,7 @@ impl SwarmConfigBuilder {
         SwarmConfig::new(
             template,
             self.node_count,
+            self.full_node_count,
             faucet_key,
             self.force_discovery,
             self.is_ipv4,
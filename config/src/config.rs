@@ -25,7 +25,7 @@ use proto_conv::FromProtoBytes;
 use types::transaction::{SignedTransaction, SCRIPT_HASH_LENGTH};
 
 use crate::{
-    config::ConsensusProposerType::{FixedProposer, RotatingProposer},
+    config::ConsensusProposerType::{FixedProposer, LeaderReputation, RotatingProposer},
     seed_peers::{SeedPeersConfig, SeedPeersConfigHelpers},
     trusted_peers::{
         deserialize_key, serialize_key, TrustedPeerPrivateKeys, TrustedPeersConfig,
@@ -64,11 +64,17 @@ pub struct NodeConfig {
     pub vm_config: VMConfig,
 
     pub secret_service: SecretServiceConfig,
+    #[serde(default)]
+    pub full_node: FullNodeConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct BaseConfig {
     pub peer_id: String,
+    // what the node does in the network: "validator" (default) takes part in consensus,
+    // "full_node" follows the ledger committed by the validators without voting
+    #[serde(default)]
+    pub role: RoleType,
     // peer_keypairs contains all the node's private keys,
     // it is filled later on from a different file
     #[serde(skip)]
@@ -89,6 +95,12 @@ pub struct BaseConfig {
     // Number of retries per chunk download
     pub node_sync_retries: usize,
 
+    // When a new node (with only the genesis transaction) is more than this many versions behind
+    // the sync target, it restores the state snapshot at the target instead of replaying every
+    // transaction. 0 disables snapshot sync, which is the default of the configs without it.
+    #[serde(default = "BaseConfig::default_node_sync_snapshot_threshold")]
+    pub node_sync_snapshot_threshold: u64,
+
     // Buffer size for sync_channel used for node syncing (number of elements that it can
     // hold before it blocks on sends)
     pub node_sync_channel_buffer_size: u64,
@@ -221,6 +233,7 @@ impl Clone for BaseConfig {
     fn clone(&self) -> Self {
         Self {
             peer_id: self.peer_id.clone(),
+            role: self.role,
             peer_keypairs: self.peer_keypairs.clone(),
             peer_keypairs_file: self.peer_keypairs_file.clone(),
             data_dir_path: self.data_dir_path.clone(),
@@ -229,12 +242,39 @@ impl Clone for BaseConfig {
             trusted_peers: self.trusted_peers.clone(),
             node_sync_batch_size: self.node_sync_batch_size,
             node_sync_retries: self.node_sync_retries,
+            node_sync_snapshot_threshold: self.node_sync_snapshot_threshold,
             node_sync_channel_buffer_size: self.node_sync_channel_buffer_size,
             node_async_log_chan_size: self.node_async_log_chan_size,
         }
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoleType {
+    // Takes part in consensus
+    Validator,
+    // Follows the ledger committed by the validators, serves reads locally and forwards the
+    // submitted transactions to a validator
+    FullNode,
+}
+
+impl Default for RoleType {
+    fn default() -> Self {
+        RoleType::Validator
+    }
+}
+
+impl BaseConfig {
+    pub fn get_role(&self) -> RoleType {
+        self.role
+    }
+
+    fn default_node_sync_snapshot_threshold() -> u64 {
+        0
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct MetricsConfig {
     pub dir: PathBuf,
@@ -293,11 +333,58 @@ pub struct SecretServiceConfig {
     pub secret_service_port: u16,
 }
 
+/// Settings only used by the nodes running as full nodes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FullNodeConfig {
+    // Admission control of the validator the submitted transactions are forwarded to.
+    pub upstream_ac_address: String,
+    pub upstream_ac_port: u16,
+    // How often the validators are asked for their latest committed ledger info.
+    pub ledger_info_poll_interval_ms: u64,
+}
+
+impl Default for FullNodeConfig {
+    fn default() -> Self {
+        Self {
+            upstream_ac_address: "localhost".to_string(),
+            upstream_ac_port: 30307,
+            ledger_info_poll_interval_ms: 1000,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct AdmissionControlConfig {
     pub address: String,
     pub admission_control_service_port: u16,
     pub need_to_check_mempool_before_validation: bool,
+    // Local policies applied to submitted transactions before they are validated by the VM.
+    #[serde(default)]
+    pub filters: TransactionFilterConfig,
+}
+
+/// Local policies that admission control applies to `SubmitTransaction` before a transaction
+/// reaches the VM. Each policy is disabled unless it is set, so an empty section filters nothing.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TransactionFilterConfig {
+    // Hex encoded addresses whose transactions are rejected.
+    pub sender_deny_list: Vec<String>,
+    // Hex encoded addresses that may not appear as a script argument, e.g. as the receiver of a
+    // payment.
+    pub receiver_deny_list: Vec<String>,
+    // Max number of transactions accepted from one client IP within `rate_limit_window_ms`.
+    pub max_txns_per_ip: Option<u64>,
+    // Max number of transactions accepted from one sender within `rate_limit_window_ms`.
+    pub max_txns_per_sender: Option<u64>,
+    pub rate_limit_window_ms: Option<u64>,
+    // Max size of the serialized raw transaction.
+    pub max_txn_size_bytes: Option<usize>,
+    pub max_gas_unit_price: Option<u64>,
+    // Hex encoded sha3 hashes of the only scripts that may be submitted. This is checked in
+    // addition to `VMPublishingOption`, and write set transactions are always rejected when set.
+    pub script_allow_list: Option<Vec<String>>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -315,12 +402,20 @@ pub struct StorageConfig {
     pub address: String,
     pub port: u16,
     pub dir: PathBuf,
+    // Number of versions before the latest one whose account states stay readable, older states
+    // are pruned in the background. Unset for archive nodes, which never prune.
+    #[serde(default)]
+    pub prune_window: Option<u64>,
 }
 
 impl StorageConfig {
     pub fn get_dir(&self) -> &Path {
         &self.dir
     }
+
+    pub fn get_prune_window(&self) -> Option<u64> {
+        self.prune_window
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -341,18 +436,59 @@ pub struct NetworkConfig {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ConsensusConfig {
     max_block_size: u64,
+    // number of slots of each proposed block reserved for mempool priority lane transactions
+    priority_lane_block_size: Option<u64>,
     proposer_type: String,
+    // number of the latest committed blocks whose proposers are favoured by the leader reputation
+    leader_reputation_proposer_window: Option<usize>,
+    // number of the latest committed blocks whose voters are considered alive by the leader
+    // reputation
+    leader_reputation_voter_window: Option<usize>,
     contiguous_rounds: u32,
     max_pruned_blocks_in_mem: Option<u64>,
     pacemaker_initial_timeout_ms: Option<u64>,
+    // if set, the round timeouts adapt to this percentile (1 to 100) of the latencies of the
+    // recently certified rounds instead of growing from the fixed initial timeout
+    pacemaker_latency_percentile: Option<u8>,
+    // bounds of the adaptive round timeouts
+    pacemaker_min_timeout_ms: Option<u64>,
+    pacemaker_max_timeout_ms: Option<u64>,
+    // where the safety rules and the consensus key live: "in_process" (default) or "remote" (the
+    // safety rules service of the secret service process)
+    #[serde(default)]
+    safety_rules: SafetyRulesType,
+    // if true, the speculative execution of the blocks runs in a pipeline: the blocks are voted
+    // for before they are executed and the commit waits for the execution results
+    pipelined_execution: Option<bool>,
 }
 
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum ConsensusProposerType {
     // Choose the smallest PeerId as the proposer
     FixedProposer,
     // Round robin rotation of proposers
     RotatingProposer,
+    // Choose the proposers based on the recent history of committed blocks: favour the
+    // validators that proposed successfully and skip the ones that did not vote
+    LeaderReputation {
+        proposer_window: usize,
+        voter_window: usize,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SafetyRulesType {
+    // Safety rules and signing run inside the consensus process
+    InProcess,
+    // Safety rules and signing run in the secret service process
+    Remote,
+}
+
+impl Default for SafetyRulesType {
+    fn default() -> Self {
+        SafetyRulesType::InProcess
+    }
 }
 
 impl ConsensusConfig {
@@ -360,10 +496,18 @@ impl ConsensusConfig {
         match self.proposer_type.as_str() {
             "fixed_proposer" => FixedProposer,
             "rotating_proposer" => RotatingProposer,
+            "leader_reputation" => LeaderReputation {
+                proposer_window: self.leader_reputation_proposer_window.unwrap_or(10),
+                voter_window: self.leader_reputation_voter_window.unwrap_or(10),
+            },
             &_ => unimplemented!("Invalid proposer type: {}", self.proposer_type),
         }
     }
 
+    pub fn get_safety_rules_type(&self) -> SafetyRulesType {
+        self.safety_rules
+    }
+
     pub fn contiguous_rounds(&self) -> u32 {
         self.contiguous_rounds
     }
@@ -372,6 +516,10 @@ impl ConsensusConfig {
         self.max_block_size
     }
 
+    pub fn priority_lane_block_size(&self) -> &Option<u64> {
+        &self.priority_lane_block_size
+    }
+
     pub fn max_pruned_blocks_in_mem(&self) -> &Option<u64> {
         &self.max_pruned_blocks_in_mem
     }
@@ -379,6 +527,22 @@ impl ConsensusConfig {
     pub fn pacemaker_initial_timeout_ms(&self) -> &Option<u64> {
         &self.pacemaker_initial_timeout_ms
     }
+
+    pub fn pacemaker_latency_percentile(&self) -> Option<u8> {
+        self.pacemaker_latency_percentile
+    }
+
+    pub fn pacemaker_min_timeout_ms(&self) -> &Option<u64> {
+        &self.pacemaker_min_timeout_ms
+    }
+
+    pub fn pacemaker_max_timeout_ms(&self) -> &Option<u64> {
+        &self.pacemaker_max_timeout_ms
+    }
+
+    pub fn pipelined_execution(&self) -> bool {
+        self.pipelined_execution.unwrap_or(false)
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -393,8 +557,45 @@ pub struct MempoolConfig {
     pub sequence_cache_capacity: usize,
     pub system_transaction_timeout_secs: u64,
     pub system_transaction_gc_interval_ms: u64,
+    // max number of transactions whose lifecycle history is kept for debugging
+    #[serde(default = "MempoolConfig::default_lifecycle_log_capacity")]
+    pub lifecycle_log_capacity: usize,
     pub mempool_service_port: u16,
     pub address: String,
+    // lanes for system and governance transactions, in descending order of priority
+    #[serde(default)]
+    pub priority_lanes: Vec<PriorityLaneConfig>,
+}
+
+impl MempoolConfig {
+    fn default_lifecycle_log_capacity() -> usize {
+        10_000
+    }
+}
+
+/// Transactions matching a priority lane are tracked in a separate capacity budget and are
+/// pulled first into the block space that Consensus reserves for priority lanes.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PriorityLaneConfig {
+    pub name: String,
+    // hex encoded addresses of the senders whose transactions belong to the lane
+    #[serde(default)]
+    pub senders: Vec<String>,
+    // hex encoded sha3-256 hashes of the scripts whose transactions belong to the lane
+    #[serde(default)]
+    pub script_hashes: Vec<String>,
+    // max number of transactions of the lane in Mempool
+    pub capacity: usize,
+    // max number of transactions of the lane in Mempool per sender that only matches by script
+    // hash, so that nobody can fill the lane by sending the designated scripts
+    #[serde(default = "PriorityLaneConfig::default_capacity_per_sender")]
+    pub capacity_per_sender: usize,
+}
+
+impl PriorityLaneConfig {
+    fn default_capacity_per_sender() -> usize {
+        1
+    }
 }
 
 impl NodeConfig {
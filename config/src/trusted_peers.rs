@@ -85,6 +85,10 @@ where
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TrustedPeersConfig {
     pub peers: HashMap<String, TrustedPeer>,
+    // Full nodes are trusted members of the network, but are not validators: their consensus
+    // keys are neither part of the validator set nor used to verify votes.
+    #[serde(default)]
+    pub full_nodes: HashMap<String, TrustedPeer>,
 }
 
 impl TrustedPeersConfig {
@@ -146,6 +150,7 @@ impl TrustedPeersConfig {
     ) -> HashMap<AccountAddress, (signing::PublicKey, X25519PublicKey)> {
         self.peers
             .iter()
+            .chain(self.full_nodes.iter())
             .map(|(account, keys)| {
                 (
                     AccountAddress::try_from(account.clone())
@@ -165,6 +170,7 @@ impl Default for TrustedPeersConfig {
     fn default() -> TrustedPeersConfig {
         Self {
             peers: HashMap::new(),
+            full_nodes: HashMap::new(),
         }
     }
 }
@@ -208,6 +214,12 @@ impl TrustedPeersConfigHelpers {
             };
             peers_private_keys.insert(peer_id.to_string(), private_keys);
         }
-        (peers_private_keys, TrustedPeersConfig { peers })
+        (
+            peers_private_keys,
+            TrustedPeersConfig {
+                peers,
+                full_nodes: HashMap::new(),
+            },
+        )
     }
 }
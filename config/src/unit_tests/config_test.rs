@@ -26,3 +26,66 @@ fn verify_all_configs() {
         }
     }
 }
+
+#[test]
+fn verify_role() {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(false);
+    assert_eq!(config.base.get_role(), RoleType::Validator);
+    config.base.role = RoleType::FullNode;
+    assert_eq!(config.base.get_role(), RoleType::FullNode);
+
+    let config_string =
+        fs::read_to_string("data/configs/node.config.toml").expect("cannot read node config");
+    let parse_role = |role_line: Option<&str>| {
+        let config_string = config_string
+            .lines()
+            .filter_map(|line| {
+                if line.starts_with("role =") {
+                    role_line
+                } else {
+                    Some(line)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        NodeConfig::parse(&config_string).map(|config| config.base.get_role())
+    };
+    // A config without a role describes a validator.
+    assert_eq!(parse_role(None).unwrap(), RoleType::Validator);
+    assert_eq!(
+        parse_role(Some("role = 'full_node'")).unwrap(),
+        RoleType::FullNode
+    );
+    // An unknown role is a parse error.
+    assert!(parse_role(Some("role = 'observer'")).is_err());
+}
+
+#[test]
+fn verify_mempool_config_defaults() {
+    // Configs written before a field was added must still load.
+    let config_string = fs::read_to_string("data/configs/node.config.toml")
+        .expect("cannot read node config")
+        .lines()
+        .filter(|line| !line.starts_with("lifecycle_log_capacity"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let config = NodeConfig::parse(&config_string).expect("NodeConfig");
+    assert_eq!(
+        config.mempool.lifecycle_log_capacity,
+        MempoolConfig::default_lifecycle_log_capacity()
+    );
+}
+
+#[test]
+fn verify_base_config_defaults() {
+    // Configs written before snapshot sync was added must still load, with snapshot sync
+    // disabled.
+    let config_string = fs::read_to_string("data/configs/node.config.toml")
+        .expect("cannot read node config")
+        .lines()
+        .filter(|line| !line.starts_with("node_sync_snapshot_threshold"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let config = NodeConfig::parse(&config_string).expect("NodeConfig");
+    assert_eq!(config.base.node_sync_snapshot_threshold, 0);
+}
@@ -0,0 +1,68 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Inspects the ConsensusDB of a validator and, explicitly asked, prunes it to its committed root.
+
+use consensus::consensusdb_inspector::ConsensusDBInspector;
+use crypto::HashValue;
+use failure::prelude::*;
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "consensusdb_tool",
+    author = "Libra",
+    about = "Tool to inspect and repair the ConsensusDB of a validator"
+)]
+struct Args {
+    /// Storage directory of the validator (`storage.dir` in its node config), which contains the
+    /// `consensusdb` directory.
+    #[structopt(short = "d", long = "db_dir", parse(from_os_str))]
+    db_dir: PathBuf,
+    /// Hex encoded block id of the latest ledger info in LibraDB. If unspecified, recovery starts
+    /// from the highest committed block found in the ConsensusDB.
+    #[structopt(short = "r", long = "root_block_id")]
+    root_block_id: Option<String>,
+    /// Delete every block and quorum cert but the committed root. The validator must be stopped.
+    #[structopt(long = "prune_to_committed_root")]
+    prune_to_committed_root: bool,
+    /// Actually apply the pruning, otherwise only print what would be deleted.
+    #[structopt(long = "confirm")]
+    confirm: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+    let root_from_storage = match &args.root_block_id {
+        Some(id) => Some(HashValue::from_slice(&hex::decode(id)?)?),
+        None => None,
+    };
+
+    if !args.prune_to_committed_root {
+        let inspector = ConsensusDBInspector::open_readonly(&args.db_dir)?;
+        print!("{}", inspector.report(root_from_storage)?);
+        return Ok(());
+    }
+
+    let inspector = ConsensusDBInspector::open_for_repair(&args.db_dir)?;
+    print!("{}", inspector.report(root_from_storage)?);
+    let dry_run = !args.confirm;
+    let summary = inspector.prune_to_committed_root(root_from_storage, dry_run)?;
+    println!(
+        "{} to committed root {}:",
+        if dry_run { "Would prune" } else { "Pruned" },
+        summary.root_id
+    );
+    println!("  blocks: {:?}", summary.pruned_blocks);
+    println!(
+        "  quorum certs of blocks: {:?}",
+        summary.pruned_quorum_certs
+    );
+    if dry_run {
+        println!("Nothing was deleted, rerun with --confirm to apply.");
+    } else {
+        println!("The consensus state and timeout certificates were kept.");
+    }
+    Ok(())
+}
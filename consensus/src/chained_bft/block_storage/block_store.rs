@@ -4,13 +4,17 @@
 use crate::{
     chained_bft::{
         block_storage::{
-            block_tree::BlockTree, BlockReader, BlockTreeError, InsertError, VoteReceptionResult,
+            block_tree::BlockTree, execution_pipeline::ExecutionPipeline, BlockReader,
+            BlockTreeError, InsertError, VoteReceptionResult,
         },
         common::{Payload, Round},
-        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        consensus_types::{
+            block::Block, equivocation_evidence::EquivocationEvidence, quorum_cert::QuorumCert,
+        },
         persistent_storage::PersistentStorage,
-        safety::vote_msg::VoteMsg,
+        safety::{consensus_signer::ConsensusSigner, vote_msg::VoteMsg},
     },
+    counters,
     state_replication::{ExecutedState, StateComputer},
 };
 use crypto::HashValue;
@@ -22,8 +26,9 @@ use mirai_annotations::checked_precondition;
 use std::{
     collections::{vec_deque::VecDeque, HashMap},
     sync::{Arc, RwLock},
+    time::Instant,
 };
-use types::{ledger_info::LedgerInfo, validator_signer::ValidatorSigner};
+use types::ledger_info::LedgerInfo;
 
 #[cfg(test)]
 #[path = "block_store_test.rs"]
@@ -56,22 +61,26 @@ pub enum NeedFetchResult {
 ///             | -------------> D3
 pub struct BlockStore<T> {
     inner: Arc<RwLock<BlockTree<T>>>,
-    validator_signer: ValidatorSigner,
+    validator_signer: Arc<dyn ConsensusSigner>,
     state_computer: Arc<dyn StateComputer<Payload = T>>,
     enforce_increasing_timestamps: bool,
     /// The persistent storage backing up the in-memory data structure, every write should go
     /// through this before in-memory tree.
     storage: Arc<dyn PersistentStorage<T>>,
+    /// If set, the blocks are inserted into the tree before their execution, which happens in
+    /// the background.
+    execution_pipeline: Option<ExecutionPipeline<T>>,
 }
 
 impl<T: Payload> BlockStore<T> {
     pub async fn new(
         storage: Arc<dyn PersistentStorage<T>>,
         initial_data: RecoveryData<T>,
-        validator_signer: ValidatorSigner,
+        validator_signer: Arc<dyn ConsensusSigner>,
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         enforce_increasing_timestamps: bool,
         max_pruned_blocks_in_mem: usize,
+        pipelined_execution: bool,
     ) -> Self {
         let (root, blocks, quorum_certs) = initial_data.take();
         let inner = Arc::new(RwLock::new(
@@ -84,12 +93,21 @@ impl<T: Payload> BlockStore<T> {
             )
             .await,
         ));
+        let execution_pipeline = if pipelined_execution {
+            Some(ExecutionPipeline::new(
+                Arc::clone(&inner),
+                Arc::clone(&state_computer),
+            ))
+        } else {
+            None
+        };
         BlockStore {
             inner,
             validator_signer,
             state_computer,
             enforce_increasing_timestamps,
             storage,
+            execution_pipeline,
         }
     }
 
@@ -119,8 +137,12 @@ impl<T: Payload> BlockStore<T> {
                 state_id: compute_res.new_state_id,
                 version,
             };
-            // if this block is certified, ensure we agree with the certified state.
-            if let Some(qc) = quorum_certs.get(&block.id()) {
+            // if this block is certified, ensure we agree with the certified state (unless the
+            // certificate only orders the block).
+            if let Some(qc) = quorum_certs
+                .get(&block.id())
+                .filter(|qc| !qc.certified_state().is_ordered_placeholder())
+            {
                 assert_eq!(
                     qc.certified_state(),
                     executed_state,
@@ -160,8 +182,13 @@ impl<T: Payload> BlockStore<T> {
         *self.inner.write().unwrap() = tree;
     }
 
-    pub fn signer(&self) -> &ValidatorSigner {
-        &self.validator_signer
+    pub fn signer(&self) -> &dyn ConsensusSigner {
+        self.validator_signer.as_ref()
+    }
+
+    /// Whether the blocks are executed in the background after their insertion.
+    pub fn pipelined_execution(&self) -> bool {
+        self.execution_pipeline.is_some()
     }
 
     /// Execute and insert a block if it passes all validation tests.
@@ -172,6 +199,9 @@ impl<T: Payload> BlockStore<T> {
     /// Duplicate inserts will return the previously inserted block (
     /// note that it is considered a valid non-error case, for example, it can happen if a validator
     /// receives a certificate for a block that is currently being added).
+    ///
+    /// With pipelined execution the block is inserted right away and executed in the background:
+    /// use `wait_for_execution` in order to get its execution result.
     pub async fn execute_and_insert_block(
         &self,
         block: Block<T>,
@@ -179,6 +209,19 @@ impl<T: Payload> BlockStore<T> {
         if let Some(existing_block) = self.inner.read().unwrap().get_block(block.id()) {
             return Ok(existing_block);
         }
+        if let Some(pipeline) = &self.execution_pipeline {
+            if let Err(e) = self.verify_block(&block) {
+                security_log(SecurityEvent::InvalidBlock)
+                    .error(&e)
+                    .data(&block)
+                    .log();
+                return Err(e);
+            }
+            self.storage
+                .save_tree(vec![block.clone()], vec![])
+                .map_err(|_| InsertError::StorageFailure)?;
+            return pipeline.insert_and_schedule(block).map_err(|e| e.into());
+        }
         let (parent_id, parent_exec_version) = match self.verify_and_get_parent_info(&block) {
             Ok(t) => t,
             Err(e) => {
@@ -214,6 +257,23 @@ impl<T: Payload> BlockStore<T> {
             .map_err(|e| e.into())
     }
 
+    /// Waits until the execution result of the given block is known.
+    /// Returns immediately unless the execution is pipelined and the block is not executed yet.
+    pub async fn wait_for_execution(&self, block_id: HashValue) -> Result<(), InsertError> {
+        match &self.execution_pipeline {
+            Some(pipeline) => {
+                let start = Instant::now();
+                let res = pipeline.wait_for_execution(block_id).await;
+                counters::EXECUTION_WAIT_MS.observe(start.elapsed().as_millis() as f64);
+                res
+            }
+            None => self
+                .get_state_for_block(block_id)
+                .map(|_| ())
+                .ok_or(InsertError::StateComputerError),
+        }
+    }
+
     /// Check if we're far away from this ledger info and need to sync.
     /// Returns false if we have this block in the tree or the root's round is higher than the
     /// block.
@@ -250,19 +310,26 @@ impl<T: Payload> BlockStore<T> {
 
     /// Validates quorum certificates and inserts it into block tree assuming dependencies exist.
     pub async fn insert_single_quorum_cert(&self, qc: QuorumCert) -> Result<(), InsertError> {
-        // Ensure executed state is consistent with Quorum Cert, otherwise persist the quorum's
-        // state and hopefully we restart and agree with it.
-        let executed_state = self
-            .get_state_for_block(qc.certified_block_id())
-            .ok_or_else(|| InsertError::MissingParentBlock(qc.certified_block_id()))?;
-        assert_eq!(
-            executed_state,
-            qc.certified_state(),
-            "We have inconsistent executed state with the executed state from the quorum \
-             certificate for block {}, will kill this validator and rely on state synchronization \
-             to try to achieve consistent state with the quorum certificate.",
-            qc.certified_block_id(),
-        );
+        if qc.certified_state().is_ordered_placeholder() {
+            // The quorum certificate only orders the block, which might not be executed yet.
+            if !self.block_exists(qc.certified_block_id()) {
+                return Err(InsertError::MissingParentBlock(qc.certified_block_id()));
+            }
+        } else {
+            // Ensure executed state is consistent with Quorum Cert, otherwise persist the quorum's
+            // state and hopefully we restart and agree with it.
+            let executed_state = self
+                .get_state_for_block(qc.certified_block_id())
+                .ok_or_else(|| InsertError::MissingParentBlock(qc.certified_block_id()))?;
+            assert_eq!(
+                executed_state,
+                qc.certified_state(),
+                "We have inconsistent executed state with the executed state from the quorum \
+                 certificate for block {}, will kill this validator and rely on state \
+                 synchronization to try to achieve consistent state with the quorum certificate.",
+                qc.certified_block_id(),
+            );
+        }
         self.storage
             .save_tree(vec![], vec![qc.clone()])
             .map_err(|_| InsertError::StorageFailure)?;
@@ -282,15 +349,51 @@ impl<T: Payload> BlockStore<T> {
     /// Different execution ids are treated as different blocks (e.g., if some proposal is
     /// executed in a non-deterministic fashion due to a bug, then the votes for execution result
     /// A and the votes for execution result B are aggregated separately).
+    /// If the author has already voted differently in the same round, the vote is not added and
+    /// the evidence of the equivocation is persisted.
     pub async fn insert_vote(
         &self,
         vote_msg: VoteMsg,
         min_votes_for_qc: usize,
     ) -> VoteReceptionResult {
-        self.inner
+        let result = self
+            .inner
             .write()
             .unwrap()
-            .insert_vote(&vote_msg, min_votes_for_qc)
+            .insert_vote(&vote_msg, min_votes_for_qc);
+        if let VoteReceptionResult::EquivocateVote(ref previous_vote) = result {
+            counters::EQUIVOCATING_VOTES_COUNT.inc();
+            self.save_equivocation_evidence(EquivocationEvidence::Vote {
+                first: previous_vote.as_ref().clone(),
+                second: vote_msg,
+            });
+        }
+        result
+    }
+
+    /// Keeps track of the proposals received from the valid proposers in order to detect
+    /// equivocation: if the author of the proposal has already proposed a different block in
+    /// the same round, the evidence of the equivocation is persisted and returned.
+    pub fn check_proposal_equivocation(
+        &self,
+        proposal: &Block<T>,
+    ) -> Option<EquivocationEvidence<T>> {
+        let previous_proposal = self.inner.write().unwrap().insert_proposal(proposal)?;
+        counters::EQUIVOCATING_PROPOSALS_COUNT.inc();
+        let evidence = EquivocationEvidence::Proposal {
+            first: previous_proposal,
+            second: proposal.clone(),
+        };
+        self.save_equivocation_evidence(evidence.clone());
+        Some(evidence)
+    }
+
+    fn save_equivocation_evidence(&self, evidence: EquivocationEvidence<T>) {
+        if let Err(e) = self.storage.save_equivocation_evidence(evidence) {
+            // The evidence is only used to identify the misbehaving validators after the fact,
+            // failing to persist it does not affect the safety.
+            error!("fail to save equivocation evidence: {:?}", e);
+        }
     }
 
     /// Prune the tree up to next_root_id (keep next_root_id's block).  Any branches not part of
@@ -328,21 +431,22 @@ impl<T: Payload> BlockStore<T> {
 
     /// If block id information is found, returns the ledger info placeholder, otherwise, return
     /// a placeholder with info of the genesis block.
-    pub fn ledger_info_placeholder(&self, id: Option<HashValue>) -> LedgerInfo {
+    /// The placeholder carries the epoch of the vote it is signed by.
+    pub fn ledger_info_placeholder(&self, id: Option<HashValue>, epoch: u64) -> LedgerInfo {
         let block_id = match id {
-            None => return Self::zero_ledger_info_placeholder(),
+            None => return Self::zero_ledger_info_placeholder(epoch),
             Some(id) => id,
         };
         let block = match self.get_block(block_id) {
             Some(b) => b,
             None => {
-                return Self::zero_ledger_info_placeholder();
+                return Self::zero_ledger_info_placeholder(epoch);
             }
         };
         let (state_id, version) = match self.get_state_for_block(block_id) {
             Some(state) => (state.state_id, state.version),
             None => {
-                return Self::zero_ledger_info_placeholder();
+                return Self::zero_ledger_info_placeholder(epoch);
             }
         };
         LedgerInfo::new(
@@ -350,7 +454,7 @@ impl<T: Payload> BlockStore<T> {
             state_id,
             HashValue::zero(),
             block_id,
-            0, // TODO [Reconfiguration] use the real epoch number.
+            epoch,
             block.timestamp_usecs(),
         )
     }
@@ -358,13 +462,13 @@ impl<T: Payload> BlockStore<T> {
     /// Used in case we're using a ledger info just as a placeholder for signing the votes / QCs
     /// and there is no real block committed.
     /// It's all pretty much zeroes.
-    fn zero_ledger_info_placeholder() -> LedgerInfo {
+    fn zero_ledger_info_placeholder(epoch: u64) -> LedgerInfo {
         LedgerInfo::new(
             0,
             HashValue::zero(),
             HashValue::zero(),
             HashValue::zero(),
-            0,
+            epoch,
             0,
         )
     }
@@ -373,6 +477,16 @@ impl<T: Payload> BlockStore<T> {
         &self,
         block: &Block<T>,
     ) -> Result<(HashValue, u64), InsertError> {
+        let parent = self.verify_block(block)?;
+        let parent_id = parent.id();
+        match self.inner.read().unwrap().get_state_for_block(parent_id) {
+            Some(ExecutedState { version, .. }) => Ok((parent.id(), version)),
+            None => Err(InsertError::ParentVersionNotFound),
+        }
+    }
+
+    /// Validates the block against its parent, which is returned.
+    fn verify_block(&self, block: &Block<T>) -> Result<Arc<Block<T>>, InsertError> {
         if block.round() <= self.inner.read().unwrap().root().round() {
             return Err(InsertError::OldBlock);
         }
@@ -398,15 +512,14 @@ impl<T: Payload> BlockStore<T> {
         if parent.round() >= block.round() {
             return Err(InsertError::InvalidBlockRound);
         }
+        if parent.epoch() > block.epoch() {
+            return Err(InsertError::InvalidBlockEpoch);
+        }
         if self.enforce_increasing_timestamps && parent.timestamp_usecs() >= block.timestamp_usecs()
         {
             return Err(InsertError::NonIncreasingTimestamp);
         }
-        let parent_id = parent.id();
-        match self.inner.read().unwrap().get_state_for_block(parent_id) {
-            Some(ExecutedState { version, .. }) => Ok((parent.id(), version)),
-            None => Err(InsertError::ParentVersionNotFound),
-        }
+        Ok(parent)
     }
 }
 
@@ -456,6 +569,7 @@ impl<T: Payload> BlockReader for BlockStore<T> {
         &self,
         parent: Arc<Block<Self::Payload>>,
         payload: Self::Payload,
+        epoch: u64,
         round: Round,
         timestamp_usecs: u64,
     ) -> Block<Self::Payload> {
@@ -467,13 +581,14 @@ impl<T: Payload> BlockReader for BlockStore<T> {
             .expect("Parent for the newly created block is not certified!")
             .as_ref()
             .clone();
-        Block::make_block(
+        Block::make_block_in_epoch(
             parent.as_ref(),
             payload,
+            epoch,
             round,
             timestamp_usecs,
             quorum_cert,
-            &self.validator_signer,
+            self.validator_signer.as_ref(),
         )
     }
 
@@ -1,24 +1,39 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::chained_bft::{
-    block_storage::{BlockReader, BlockStore, InsertError, NeedFetchResult, VoteReceptionResult},
-    common::Author,
-    consensus_types::{
-        block::{block_test, Block},
-        quorum_cert::QuorumCert,
-    },
-    safety::vote_msg::VoteMsg,
-    test_utils::{
-        build_empty_tree, build_empty_tree_with_custom_signing, placeholder_certificate_for_block,
-        placeholder_ledger_info, TreeInserter,
+use crate::{
+    chained_bft::{
+        block_storage::{
+            BlockReader, BlockStore, InsertError, NeedFetchResult, VoteReceptionResult,
+        },
+        common::Author,
+        consensus_types::{
+            block::{block_test, Block},
+            equivocation_evidence::EquivocationEvidence,
+            quorum_cert::QuorumCert,
+        },
+        persistent_storage::PersistentStorage,
+        safety::vote_msg::VoteMsg,
+        test_utils::{
+            build_empty_tree, build_empty_tree_with_custom_signing,
+            placeholder_certificate_for_block, placeholder_ledger_info, EmptyStorage,
+            ExecutionGate, MockStateComputer, MockStorage, TreeInserter,
+        },
     },
+    state_replication::ExecutedState,
 };
 use crypto::HashValue;
-use futures::executor::block_on;
+use futures::{channel::mpsc, executor::block_on};
 use proptest::prelude::*;
-use std::{cmp::min, collections::HashSet, sync::Arc};
-use types::{account_address::AccountAddress, validator_signer::ValidatorSigner};
+use std::{
+    cmp::min,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+use types::{
+    account_address::AccountAddress, ledger_info::LedgerInfoWithSignatures,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+};
 
 fn build_simple_tree() -> (Vec<Arc<Block<Vec<usize>>>>, Arc<BlockStore<Vec<usize>>>) {
     let block_store = build_empty_tree();
@@ -52,7 +67,7 @@ fn build_simple_tree() -> (Vec<Arc<Block<Vec<usize>>>>, Arc<BlockStore<Vec<usize
 fn test_block_store_create_block() {
     let block_store = build_empty_tree();
     let genesis = block_store.root();
-    let a1 = block_store.create_block(Arc::clone(&genesis), vec![1], 1, 1);
+    let a1 = block_store.create_block(Arc::clone(&genesis), vec![1], 0, 1, 1);
     assert_eq!(a1.parent_id(), genesis.id());
     assert_eq!(a1.round(), 1);
     assert_eq!(a1.height(), 1);
@@ -71,7 +86,7 @@ fn test_block_store_create_block() {
     );
     block_on(block_store.insert_vote_and_qc(vote_msg, 1));
 
-    let b1 = block_store.create_block(Arc::clone(&a1_ref), vec![2], 2, 2);
+    let b1 = block_store.create_block(Arc::clone(&a1_ref), vec![2], 0, 2, 2);
     assert_eq!(b1.parent_id(), a1_ref.id());
     assert_eq!(b1.round(), 2);
     assert_eq!(b1.height(), 2);
@@ -384,6 +399,7 @@ fn test_illegal_timestamp() {
     let block_with_illegal_timestamp = Block::<Vec<usize>>::new_internal(
         vec![],
         genesis.id(),
+        0,
         1,
         1,
         // This timestamp is illegal, it is the same as genesis
@@ -425,13 +441,13 @@ fn test_need_fetch_for_qc() {
     let a3 = inserter.insert_block(a2.as_ref(), 3);
     block_on(block_tree.prune_tree(a2.id()));
     let need_fetch_qc = placeholder_certificate_for_block(
-        vec![block_tree.signer().clone()],
+        vec![block_tree.signer()],
         HashValue::zero(),
         a3.round() + 1,
     );
     let too_old_qc = QuorumCert::certificate_for_genesis();
     let can_insert_qc =
-        placeholder_certificate_for_block(vec![block_tree.signer().clone()], a3.id(), a3.round());
+        placeholder_certificate_for_block(vec![block_tree.signer()], a3.id(), a3.round());
     let duplicate_qc = block_tree.get_quorum_cert_for_block(a2.id()).unwrap();
     assert_eq!(
         block_tree.need_fetch_for_quorum_cert(&need_fetch_qc),
@@ -464,7 +480,7 @@ fn test_need_sync_for_qc() {
     let a3 = inserter.insert_block(a2.as_ref(), 3);
     block_on(block_tree.prune_tree(a3.id()));
     let qc = placeholder_certificate_for_block(
-        vec![block_tree.signer().clone()],
+        vec![block_tree.signer()],
         HashValue::zero(),
         a3.round() + 3,
     );
@@ -473,7 +489,7 @@ fn test_need_sync_for_qc() {
         true
     );
     let qc = placeholder_certificate_for_block(
-        vec![block_tree.signer().clone()],
+        vec![block_tree.signer()],
         HashValue::zero(),
         a3.round() + 2,
     );
@@ -486,3 +502,203 @@ fn test_need_sync_for_qc() {
         false
     );
 }
+
+fn build_empty_tree_with_mock_storage(
+    my_signer: ValidatorSigner,
+) -> (Arc<BlockStore<Vec<usize>>>, Arc<MockStorage<Vec<usize>>>) {
+    let (commit_cb_sender, _commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+    let (storage, initial_data) = MockStorage::start_for_testing();
+    let block_store = Arc::new(block_on(BlockStore::new(
+        Arc::clone(&storage) as Arc<dyn PersistentStorage<Vec<usize>>>,
+        initial_data,
+        Arc::new(my_signer),
+        Arc::new(MockStateComputer::new(commit_cb_sender)),
+        true,
+        10,    // max pruned blocks in mem
+        false, // pipelined execution
+    )));
+    (block_store, storage)
+}
+
+#[test]
+fn test_pipelined_execution() {
+    let (commit_cb_sender, _commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+    let (storage, initial_data) = EmptyStorage::start_for_testing();
+    let execution_gate = Arc::new(ExecutionGate::default());
+    let state_computer =
+        MockStateComputer::new(commit_cb_sender).with_execution_gate(Arc::clone(&execution_gate));
+    let block_store = Arc::new(block_on(BlockStore::new(
+        storage,
+        initial_data,
+        Arc::new(ValidatorSigner::random()),
+        Arc::new(state_computer),
+        true,
+        10,   // max pruned blocks in mem
+        true, // pipelined execution
+    )));
+    assert!(block_store.pipelined_execution());
+    let genesis = block_store.root();
+
+    // The block is inserted before it is executed.
+    let a1 = Block::make_block(
+        genesis.as_ref(),
+        vec![1],
+        1,
+        genesis.timestamp_usecs() + 1,
+        QuorumCert::certificate_for_genesis(),
+        block_store.signer(),
+    );
+    let a1 = block_on(block_store.execute_and_insert_block(a1)).unwrap();
+    assert!(block_store.block_exists(a1.id()));
+    assert_eq!(block_store.get_state_for_block(a1.id()), None);
+
+    // The certificate ordering the block and the child of the block are inserted before the
+    // execution of the block as well.
+    let a1_qc = QuorumCert::new(
+        a1.id(),
+        ExecutedState::ordered_placeholder(),
+        a1.round(),
+        LedgerInfoWithSignatures::new(placeholder_ledger_info(), HashMap::new()),
+    );
+    block_on(block_store.insert_single_quorum_cert(a1_qc.clone())).unwrap();
+    let a2 = Block::make_block(
+        a1.as_ref(),
+        vec![2],
+        2,
+        a1.timestamp_usecs() + 1,
+        a1_qc,
+        block_store.signer(),
+    );
+    let a2 = block_on(block_store.execute_and_insert_block(a2)).unwrap();
+    assert_eq!(block_store.get_state_for_block(a2.id()), None);
+    assert_eq!(block_store.get_state_for_block(a1.id()), None);
+
+    // The blocks are executed in the order of their insertion.
+    execution_gate.open();
+    block_on(block_store.wait_for_execution(a2.id())).unwrap();
+    for block in &[a1, a2] {
+        assert_eq!(
+            block_store.get_state_for_block(block.id()),
+            Some(ExecutedState::state_for_genesis())
+        );
+        assert!(block_store.get_compute_result(block.id()).is_some());
+    }
+    assert_eq!(
+        block_on(block_store.wait_for_execution(HashValue::random())),
+        Err(InsertError::StateComputerError)
+    );
+}
+
+#[test]
+fn test_equivocating_votes() {
+    let voter = ValidatorSigner::random();
+    let (block_store, storage) = build_empty_tree_with_mock_storage(ValidatorSigner::random());
+    let genesis = block_store.root();
+    let mut inserter = TreeInserter::new(block_store.clone());
+    let a1 = inserter.insert_block(genesis.as_ref(), 1);
+    let b1 = inserter.insert_block(genesis.as_ref(), 1);
+    let a2 = inserter.insert_block(a1.as_ref(), 2);
+    let vote_for = |block: &Block<Vec<usize>>| {
+        VoteMsg::new(
+            block.id(),
+            block_store.get_state_for_block(block.id()).unwrap(),
+            block.round(),
+            voter.author(),
+            placeholder_ledger_info(),
+            &voter,
+        )
+    };
+
+    let vote_a1 = vote_for(a1.as_ref());
+    assert_eq!(
+        block_on(block_store.insert_vote(vote_a1.clone(), 10)),
+        VoteReceptionResult::VoteAdded(1)
+    );
+    // A different vote in the same round is an equivocation and it is not added.
+    let vote_b1 = vote_for(b1.as_ref());
+    assert_eq!(
+        block_on(block_store.insert_vote(vote_b1.clone(), 10)),
+        VoteReceptionResult::EquivocateVote(Box::new(vote_a1.clone()))
+    );
+    // A vote in a higher round is fine.
+    assert_eq!(
+        block_on(block_store.insert_vote(vote_for(a2.as_ref()), 10)),
+        VoteReceptionResult::VoteAdded(1)
+    );
+
+    let evidence = storage.get_equivocation_evidence().unwrap();
+    assert_eq!(
+        evidence,
+        vec![EquivocationEvidence::Vote {
+            first: vote_a1.clone(),
+            second: vote_b1,
+        }]
+    );
+    let validator = ValidatorVerifier::new_single(voter.author(), voter.public_key());
+    assert!(evidence[0].verify(&validator).is_ok());
+    // Two copies of the same vote are not an evidence.
+    let not_conflicting: EquivocationEvidence<Vec<usize>> = EquivocationEvidence::Vote {
+        first: vote_a1.clone(),
+        second: vote_a1,
+    };
+    assert!(not_conflicting.verify(&validator).is_err());
+}
+
+#[test]
+fn test_equivocating_proposals() {
+    let proposer = ValidatorSigner::random();
+    let (block_store, storage) = build_empty_tree_with_mock_storage(proposer.clone());
+    let genesis = block_store.root();
+    let make_proposal = |payload: Vec<usize>, round| {
+        Block::make_block(
+            genesis.as_ref(),
+            payload,
+            round,
+            genesis.timestamp_usecs() + 1,
+            QuorumCert::certificate_for_genesis(),
+            &proposer,
+        )
+    };
+
+    let proposal_a = make_proposal(vec![1], 1);
+    assert!(block_store
+        .check_proposal_equivocation(&proposal_a)
+        .is_none());
+    // Receiving the same proposal again is fine.
+    assert!(block_store
+        .check_proposal_equivocation(&proposal_a)
+        .is_none());
+    let proposal_b = make_proposal(vec![2], 1);
+    let evidence = block_store
+        .check_proposal_equivocation(&proposal_b)
+        .expect("Equivocation is not detected");
+    assert_eq!(
+        evidence,
+        EquivocationEvidence::Proposal {
+            first: proposal_a,
+            second: proposal_b,
+        }
+    );
+    assert_eq!(evidence.author(), proposer.author());
+    assert_eq!(evidence.round(), 1);
+    assert!(evidence
+        .verify(&ValidatorVerifier::new_single(
+            proposer.author(),
+            proposer.public_key()
+        ))
+        .is_ok());
+    // The evidence does not verify against the key of another validator.
+    let other = ValidatorSigner::random();
+    assert!(evidence
+        .verify(&ValidatorVerifier::new_single(
+            proposer.author(),
+            other.public_key()
+        ))
+        .is_err());
+    assert_eq!(storage.get_equivocation_evidence().unwrap(), vec![evidence]);
+
+    // A proposal for a higher round is fine.
+    assert!(block_store
+        .check_proposal_equivocation(&make_proposal(vec![3], 2))
+        .is_none());
+}
@@ -4,6 +4,7 @@
 use crate::{
     chained_bft::{
         block_storage::{BlockTreeError, VoteReceptionResult},
+        common::Author,
         consensus_types::{block::Block, quorum_cert::QuorumCert},
         safety::vote_msg::VoteMsg,
     },
@@ -12,7 +13,7 @@ use crate::{
     util::time_service::duration_since_epoch,
 };
 use canonical_serialization::CanonicalSerialize;
-use crypto::HashValue;
+use crypto::{hash::CryptoHash, HashValue};
 use logger::prelude::*;
 use mirai_annotations::checked_verify_eq;
 use serde::Serialize;
@@ -58,12 +59,17 @@ pub struct BlockTree<T> {
     /// `id_to_votes` might keep multiple LedgerInfos per proposed block in order
     /// to tolerate non-determinism in execution: given a proposal, a QuorumCertificate is going
     /// to be collected only for all the votes that have identical state id.
-    /// The vote digest is a hash that covers both the proposal id and the state id.
-    /// Thus, the structure of `id_to_votes` is as follows:
-    /// HashMap<proposed_block_id, HashMap<vote_digest, LedgerInfoWithSignatures>>
+    /// The digest is the hash of the signed ledger info, which covers both the proposal id and
+    /// the state id. Thus, the structure of `id_to_votes` is as follows:
+    /// HashMap<proposed_block_id, HashMap<ledger_info_digest, LedgerInfoWithSignatures>>
     id_to_votes: HashMap<HashValue, HashMap<HashValue, LedgerInfoWithSignatures>>,
     /// Map of block id to its completed quorum certificate (2f + 1 votes)
     id_to_quorum_cert: HashMap<HashValue, Arc<QuorumCert>>,
+    /// The vote of the highest round received from each author, to detect equivocating votes.
+    author_to_last_vote: HashMap<Author, VoteMsg>,
+    /// The proposal of the highest round received from each author, to detect equivocating
+    /// proposals.
+    author_to_last_proposal: HashMap<Author, Block<T>>,
     /// To keep the IDs of the elements that have been pruned from the tree but not cleaned up yet.
     pruned_block_ids: VecDeque<HashValue>,
     /// Num pruned blocks to keep in memory.
@@ -72,7 +78,7 @@ pub struct BlockTree<T> {
 
 impl<T> BlockTree<T>
 where
-    T: Serialize + Default + Debug + CanonicalSerialize,
+    T: Serialize + Default + Debug + CanonicalSerialize + Clone,
 {
     pub(super) fn new(
         root: Block<T>,
@@ -100,8 +106,19 @@ where
             Arc::clone(&root_quorum_cert),
         );
 
+        // With pipelined execution the quorum certificates carry no executed state: the state of
+        // the root is then taken from the ledger info that commits it.
+        let root_state = if root_quorum_cert.certified_state().is_ordered_placeholder() {
+            let ledger_info = root_ledger_info.ledger_info().ledger_info();
+            ExecutedState {
+                state_id: ledger_info.transaction_accumulator_hash(),
+                version: ledger_info.version(),
+            }
+        } else {
+            root_quorum_cert.certified_state()
+        };
         let mut id_to_state = HashMap::new();
-        id_to_state.insert(root.id(), root_quorum_cert.certified_state());
+        id_to_state.insert(root.id(), root_state);
 
         let pruned_block_ids = VecDeque::with_capacity(max_pruned_blocks_in_mem);
 
@@ -116,6 +133,8 @@ where
             highest_ledger_info: Arc::new(root_ledger_info),
             id_to_votes: HashMap::new(),
             id_to_quorum_cert,
+            author_to_last_vote: HashMap::new(),
+            author_to_last_proposal: HashMap::new(),
             pruned_block_ids,
             max_pruned_blocks_in_mem,
         }
@@ -195,6 +214,24 @@ where
         block: Block<T>,
         state: ExecutedState,
         compute_result: StateComputeResult,
+    ) -> Result<Arc<Block<T>>, BlockTreeError> {
+        let block = self.insert_ordered_block(block)?;
+        match self.id_to_state.get(&block.id()) {
+            Some(previous_state) => checked_verify_eq!(*previous_state, state),
+            None => {
+                self.id_to_state.insert(block.id(), state);
+                self.id_to_compute_result
+                    .insert(block.id(), Arc::new(compute_result));
+            }
+        }
+        Ok(block)
+    }
+
+    /// Inserts the block into the tree without its execution result, which is supposed to be
+    /// set later on via `set_execution_result`.
+    pub(super) fn insert_ordered_block(
+        &mut self,
+        block: Block<T>,
     ) -> Result<Arc<Block<T>>, BlockTreeError> {
         if !self.block_exists(block.parent_id()) {
             return Err(BlockTreeError::BlockNotFound {
@@ -209,7 +246,6 @@ where
                        previous_block,
                        block.id(),
                        block);
-                checked_verify_eq!(*self.id_to_state.get(&block.id()).unwrap(), state);
                 Ok(previous_block.clone())
             }
             _ => {
@@ -220,14 +256,34 @@ where
                 children.push(block.clone());
                 counters::NUM_BLOCKS_IN_TREE.inc();
                 self.id_to_block.insert(block.id(), block.clone());
-                self.id_to_state.insert(block.id(), state);
-                self.id_to_compute_result
-                    .insert(block.id(), Arc::new(compute_result));
                 Ok(block)
             }
         }
     }
 
+    /// Sets the execution result of a block inserted via `insert_ordered_block`: the version of
+    /// the executed state follows the version of the parent.
+    /// Returns the executed state, or None if the block or the state of its parent is unknown
+    /// (e.g., the block has been pruned in the meantime).
+    pub(super) fn set_execution_result(
+        &mut self,
+        block_id: HashValue,
+        compute_result: StateComputeResult,
+    ) -> Option<ExecutedState> {
+        if let Some(state) = self.id_to_state.get(&block_id) {
+            return Some(*state);
+        }
+        let parent_id = self.id_to_block.get(&block_id)?.parent_id();
+        let state = ExecutedState {
+            state_id: compute_result.new_state_id,
+            version: self.id_to_state.get(&parent_id)?.version + compute_result.num_successful_txns,
+        };
+        self.id_to_state.insert(block_id, state);
+        self.id_to_compute_result
+            .insert(block_id, Arc::new(compute_result));
+        Some(state)
+    }
+
     pub(super) fn insert_quorum_cert(&mut self, qc: QuorumCert) -> Result<(), BlockTreeError> {
         let block_id = qc.certified_block_id();
         let qc = Arc::new(qc);
@@ -276,20 +332,35 @@ where
             return VoteReceptionResult::OldQuorumCertificate(Arc::clone(old_qc));
         }
 
+        let author = vote_msg.author();
+        match self.author_to_last_vote.get(&author) {
+            Some(last_vote) if last_vote.round() > vote_msg.round() => (),
+            Some(last_vote)
+                if last_vote.round() == vote_msg.round()
+                    && last_vote.vote_hash() != vote_msg.vote_hash() =>
+            {
+                return VoteReceptionResult::EquivocateVote(Box::new(last_vote.clone()));
+            }
+            _ => {
+                self.author_to_last_vote.insert(author, vote_msg.clone());
+            }
+        }
+
         // All the votes collected for all the execution results of a given proposal.
         let block_votes = self
             .id_to_votes
             .entry(block_id)
             .or_insert_with(HashMap::new);
 
-        // Note that the digest covers not just the proposal id, but also the resulting
-        // state id as well as the round number. In other words, if two different voters have the
-        // same digest then they reached the same state following the same proposals.
-        let digest = vote_msg.vote_hash();
+        // Note that the digest is the hash of the signed ledger info, which covers the vote hash
+        // (the proposal id, the resulting state id and the round number) as well as the state of
+        // the potential commit. In other words, if two different voters have the same digest
+        // then they reached the same state following the same proposals, even if the execution
+        // is pipelined and the votes carry no state of the proposal.
+        let digest = vote_msg.ledger_info().hash();
         let li_with_sig = block_votes.entry(digest).or_insert_with(|| {
             LedgerInfoWithSignatures::new(vote_msg.ledger_info().clone(), HashMap::new())
         });
-        let author = vote_msg.author();
         if li_with_sig.signatures().contains_key(&author) {
             return VoteReceptionResult::DuplicateVote;
         }
@@ -317,6 +388,26 @@ where
         VoteReceptionResult::VoteAdded(num_votes)
     }
 
+    /// Keeps track of the proposal of the highest round of its author.
+    /// Returns the previous proposal of the author if it is a different proposal for the same
+    /// round.
+    pub(super) fn insert_proposal(&mut self, proposal: &Block<T>) -> Option<Block<T>> {
+        match self.author_to_last_proposal.get(&proposal.author()) {
+            Some(last_proposal) if last_proposal.round() > proposal.round() => None,
+            Some(last_proposal)
+                if last_proposal.round() == proposal.round()
+                    && last_proposal.id() != proposal.id() =>
+            {
+                Some(last_proposal.clone())
+            }
+            _ => {
+                self.author_to_last_proposal
+                    .insert(proposal.author(), proposal.clone());
+                None
+            }
+        }
+    }
+
     /// Find the blocks to prune up to next_root_id (keep next_root_id's block). Any branches not
     /// part of the next_root_id's tree should be removed as well.
     ///
@@ -0,0 +1,155 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::{block_tree::BlockTree, BlockTreeError, InsertError},
+        common::Payload,
+        consensus_types::block::Block,
+    },
+    counters,
+    state_replication::StateComputer,
+};
+use crypto::HashValue;
+use futures::{channel::oneshot, executor::block_on};
+use logger::prelude::*;
+use std::{
+    collections::HashMap,
+    sync::{mpsc, Arc, Mutex, RwLock},
+    thread,
+};
+
+/// The senders notifying about the execution of the pending blocks.
+type ExecutionWaiters = Mutex<HashMap<HashValue, Vec<oneshot::Sender<()>>>>;
+
+/// Executes the ordered blocks in the background, in the order of their insertion, which
+/// guarantees that a parent is always executed before its children.
+/// A block is pending from the moment it is inserted into the tree until its execution result is
+/// set: the callers interested in the execution result can wait for it via `wait_for_execution`.
+///
+/// Lock order: the waiters are always locked before the block tree.
+pub(super) struct ExecutionPipeline<T> {
+    inner: Arc<RwLock<BlockTree<T>>>,
+    sender: Mutex<mpsc::Sender<Arc<Block<T>>>>,
+    waiters: Arc<ExecutionWaiters>,
+}
+
+impl<T: Payload> ExecutionPipeline<T> {
+    pub(super) fn new(
+        inner: Arc<RwLock<BlockTree<T>>>,
+        state_computer: Arc<dyn StateComputer<Payload = T>>,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel::<Arc<Block<T>>>();
+        let waiters = Arc::new(Mutex::new(HashMap::new()));
+        let pipeline_inner = Arc::clone(&inner);
+        let pipeline_waiters = Arc::clone(&waiters);
+        // The thread exits once the pipeline (and hence the sender) is dropped.
+        thread::Builder::new()
+            .name("consensus-execution".into())
+            .spawn(move || {
+                for block in receiver {
+                    Self::execute(
+                        &pipeline_inner,
+                        &pipeline_waiters,
+                        state_computer.as_ref(),
+                        &block,
+                    );
+                }
+            })
+            .expect("Failed to spawn the execution pipeline thread");
+        ExecutionPipeline {
+            inner,
+            sender: Mutex::new(sender),
+            waiters,
+        }
+    }
+
+    /// Inserts the block into the tree without waiting for its execution and schedules the
+    /// execution. Duplicate inserts return the previously inserted block.
+    pub(super) fn insert_and_schedule(
+        &self,
+        block: Block<T>,
+    ) -> Result<Arc<Block<T>>, BlockTreeError> {
+        let mut waiters = self.waiters.lock().unwrap();
+        let block = {
+            let mut tree = self.inner.write().unwrap();
+            if let Some(existing_block) = tree.get_block(block.id()) {
+                return Ok(existing_block);
+            }
+            tree.insert_ordered_block(block)?
+        };
+        waiters.insert(block.id(), vec![]);
+        counters::PENDING_EXECUTION_BLOCKS.set(waiters.len() as i64);
+        if self
+            .sender
+            .lock()
+            .unwrap()
+            .send(Arc::clone(&block))
+            .is_err()
+        {
+            // Can only happen if the pipeline thread has panicked: the waiters are notified
+            // about the failure by dropping the senders.
+            error!(
+                "Execution pipeline is down, block {} is not executed",
+                block
+            );
+            waiters.remove(&block.id());
+        }
+        Ok(block)
+    }
+
+    /// Waits until the block is executed.
+    /// Returns an error if the block is neither executed nor pending, or its execution fails.
+    pub(super) async fn wait_for_execution(&self, block_id: HashValue) -> Result<(), InsertError> {
+        let receiver = {
+            let mut waiters = self.waiters.lock().unwrap();
+            if self
+                .inner
+                .read()
+                .unwrap()
+                .get_state_for_block(block_id)
+                .is_some()
+            {
+                return Ok(());
+            }
+            let (sender, receiver) = oneshot::channel();
+            waiters
+                .get_mut(&block_id)
+                .ok_or(InsertError::StateComputerError)?
+                .push(sender);
+            receiver
+        };
+        receiver.await.map_err(|_| InsertError::StateComputerError)
+    }
+
+    fn execute(
+        inner: &RwLock<BlockTree<T>>,
+        waiters: &ExecutionWaiters,
+        state_computer: &dyn StateComputer<Payload = T>,
+        block: &Block<T>,
+    ) {
+        let compute_res =
+            block_on(state_computer.compute(block.parent_id(), block.id(), block.get_payload()));
+        let mut waiters = waiters.lock().unwrap();
+        let executed = match compute_res {
+            Ok(compute_res) => inner
+                .write()
+                .unwrap()
+                .set_execution_result(block.id(), compute_res)
+                .is_some(),
+            Err(e) => {
+                error!("Execution failure for block {}: {:?}", block, e);
+                false
+            }
+        };
+        // Dropping the senders of a failed execution notifies the waiters about the failure.
+        if let Some(senders) = waiters.remove(&block.id()) {
+            if executed {
+                for sender in senders {
+                    let _ = sender.send(());
+                }
+            }
+        }
+        counters::PENDING_EXECUTION_BLOCKS.set(waiters.len() as i64);
+    }
+}
@@ -10,9 +10,10 @@ use std::sync::Arc;
 
 mod block_store;
 mod block_tree;
+mod execution_pipeline;
 
 use crate::{
-    chained_bft::safety::vote_msg::VoteMsgVerificationError,
+    chained_bft::safety::vote_msg::{VoteMsg, VoteMsgVerificationError},
     state_replication::{ExecutedState, StateComputeResult},
 };
 pub use block_store::{BlockStore, NeedFetchResult};
@@ -65,6 +66,9 @@ pub enum InsertError {
     /// The block round is not greater than that of the parent.
     #[fail(display = "InvalidBlockRound")]
     InvalidBlockRound,
+    /// The block epoch is lower than that of the parent.
+    #[fail(display = "InvalidBlockEpoch")]
+    InvalidBlockEpoch,
     /// The block's timestamp is not greater than that of the parent.
     #[fail(display = "InvalidTiemstamp")]
     NonIncreasingTimestamp,
@@ -131,6 +135,9 @@ pub enum VoteReceptionResult {
     OldQuorumCertificate(Arc<QuorumCert>),
     /// This block has just been certified after adding the vote.
     NewQuorumCertificate(Arc<QuorumCert>),
+    /// The author has already voted differently in the same round, the vote is not added.
+    /// Contains the previous vote of the author.
+    EquivocateVote(Box<VoteMsg>),
 }
 
 #[derive(Debug, Fail)]
@@ -197,10 +204,12 @@ pub trait BlockReader: Send + Sync {
     /// * If the parent or its quorum certificate are not present in the tree,
     /// * If the given round (which is typically calculated by Pacemaker) is not greater than that
     ///   of a parent.
+    /// * If the given epoch is lower than that of a parent.
     fn create_block(
         &self,
         parent: Arc<Block<Self::Payload>>,
         payload: Self::Payload,
+        epoch: u64,
         round: Round,
         timestamp_usecs: u64,
     ) -> Block<Self::Payload>;
@@ -3,8 +3,10 @@
 
 use crate::{
     chained_bft::{
-        chained_bft_smr::ChainedBftSMR, network::ConsensusNetworkImpl,
+        chained_bft_smr::ChainedBftSMR,
+        network::ConsensusNetworkImpl,
         persistent_storage::PersistentStorage,
+        safety::consensus_signer::{ConsensusSigner, RemoteSigner},
     },
     consensus_provider::ConsensusProvider,
     counters,
@@ -20,11 +22,17 @@ use crate::{
     },
     state_synchronizer::{setup_state_synchronizer, StateSynchronizer},
 };
-use config::config::{ConsensusProposerType::FixedProposer, NodeConfig};
+use config::config::{ConsensusProposerType::FixedProposer, NodeConfig, SafetyRulesType};
+use debug_interface::{
+    node_debug_service::EquivocationEvidenceSource,
+    proto::node_debug_interface::EquivocationEvidence as ProtoEquivocationEvidence,
+};
 use execution_proto::proto::execution_grpc::ExecutionClient;
 use failure::prelude::*;
 use logger::prelude::*;
 use mempool::proto::mempool_grpc::MempoolClient;
+use rmp_serde::to_vec_named;
+use secret_service::safety_rules_client::SafetyRulesClient;
 use std::{convert::TryFrom, sync::Arc};
 use tokio::runtime;
 use types::{
@@ -34,8 +42,7 @@ use types::{
 
 struct InitialSetup {
     author: Author,
-    signer: ValidatorSigner,
-    quorum_size: usize,
+    signer: Arc<dyn ConsensusSigner>,
     peers: Arc<Vec<Author>>,
     validator: Arc<ValidatorVerifier>,
 }
@@ -43,6 +50,7 @@ struct InitialSetup {
 /// Supports the implementation of ConsensusProvider using LibraBFT.
 pub struct ChainedBftProvider {
     smr: ChainedBftSMR<Vec<SignedTransaction>, Author>,
+    storage: Arc<dyn PersistentStorage<Vec<SignedTransaction>>>,
     mempool_client: Arc<MempoolClient>,
     execution_client: Arc<ExecutionClient>,
     synchronizer_client: Arc<StateSynchronizer>,
@@ -89,17 +97,17 @@ impl ChainedBftProvider {
         );
         let smr = ChainedBftSMR::new(
             initial_setup.author,
-            initial_setup.quorum_size,
             initial_setup.signer,
             proposer,
             network,
             runtime,
             config,
-            storage,
+            Arc::clone(&storage),
             initial_data,
         );
         Self {
             smr,
+            storage,
             mempool_client,
             execution_client,
             synchronizer_client: Arc::new(synchronizer),
@@ -114,9 +122,27 @@ impl ChainedBftProvider {
         let peer_id_str = node_config.base.peer_id.clone();
         let author =
             AccountAddress::try_from(peer_id_str).expect("Failed to parse peer id of a validator");
-        let private_key = node_config.base.peer_keypairs.get_consensus_private();
-        let public_key = node_config.base.peer_keypairs.get_consensus_public();
-        let signer = ValidatorSigner::new(author, public_key, private_key);
+        let signer: Arc<dyn ConsensusSigner> = match node_config.consensus.get_safety_rules_type() {
+            SafetyRulesType::InProcess => {
+                let private_key = node_config.base.peer_keypairs.get_consensus_private();
+                let public_key = node_config.base.peer_keypairs.get_consensus_public();
+                Arc::new(ValidatorSigner::new(author, public_key, private_key))
+            }
+            SafetyRulesType::Remote => {
+                info!(
+                    "[Consensus] Using the remote safety rules at {}:{}",
+                    node_config.secret_service.address,
+                    node_config.secret_service.secret_service_port
+                );
+                Arc::new(RemoteSigner::new(
+                    author,
+                    SafetyRulesClient::new(
+                        &node_config.secret_service.address,
+                        node_config.secret_service.secret_service_port,
+                    ),
+                ))
+            }
+        };
         let peers_with_public_keys = node_config.base.trusted_peers.get_trusted_consensus_peers();
         let peers = Arc::new(
             peers_with_public_keys
@@ -125,14 +151,15 @@ impl ChainedBftProvider {
                 .collect(),
         );
         let validator = Arc::new(ValidatorVerifier::new(peers_with_public_keys));
-        counters::EPOCH_NUM.set(0); // No reconfiguration yet, so it is always zero
+        // These are the validators of the genesis epoch, the ends of the later epochs are
+        // persisted in the ConsensusDB and replayed when the SMR starts.
+        counters::EPOCH_NUM.set(0);
         counters::CURRENT_EPOCH_NUM_VALIDATORS.set(validator.len() as i64);
         counters::CURRENT_EPOCH_QUORUM_SIZE.set(validator.quorum_size() as i64);
         debug!("[Consensus]: quorum_size = {:?}", validator.quorum_size());
         InitialSetup {
             author,
             signer,
-            quorum_size: validator.quorum_size(),
             peers,
             validator,
         }
@@ -165,4 +192,33 @@ impl ConsensusProvider for ChainedBftProvider {
         self.smr.stop();
         debug!("Consensus provider stopped.");
     }
+
+    fn equivocation_evidence(&self) -> Arc<dyn EquivocationEvidenceSource> {
+        Arc::new(EquivocationEvidenceReader {
+            storage: Arc::clone(&self.storage),
+        })
+    }
+}
+
+/// Reads the equivocation evidence persisted by consensus for the debug interface.
+struct EquivocationEvidenceReader {
+    storage: Arc<dyn PersistentStorage<Vec<SignedTransaction>>>,
+}
+
+impl EquivocationEvidenceSource for EquivocationEvidenceReader {
+    fn get_equivocation_evidence(&self) -> Result<Vec<ProtoEquivocationEvidence>> {
+        self.storage
+            .get_equivocation_evidence()?
+            .into_iter()
+            .map(|evidence| -> Result<ProtoEquivocationEvidence> {
+                let mut proto = ProtoEquivocationEvidence::new();
+                proto.set_kind(evidence.kind().to_string());
+                proto.set_round(evidence.round());
+                proto.set_author(evidence.author().to_vec());
+                proto.set_details(evidence.to_string());
+                proto.set_evidence(to_vec_named(&evidence)?);
+                Ok(proto)
+            })
+            .collect()
+    }
 }
@@ -5,21 +5,28 @@ use crate::{
     chained_bft::{
         block_storage::{BlockReader, BlockStore},
         common::{Payload, Round},
+        consensus_types::epoch_info::EpochChange,
         event_processor::{EventProcessor, ProcessProposalResult},
         liveness::{
-            local_pacemaker::{ExponentialTimeInterval, LocalPacemaker},
+            leader_reputation::LeaderReputation,
+            local_pacemaker::{
+                AdaptiveTimeInterval, ExponentialTimeInterval, LocalPacemaker,
+                PacemakerTimeInterval,
+            },
             pacemaker::{NewRoundEvent, Pacemaker},
             pacemaker_timeout_manager::HighestTimeoutCertificates,
             proposal_generator::ProposalGenerator,
             proposer_election::{ProposalInfo, ProposerElection, ProposerInfo},
             rotating_proposer_election::RotatingProposer,
-            timeout_msg::TimeoutMsg,
+            timeout_msg::{PacemakerTimeoutCertificate, TimeoutMsg},
         },
         network::{
-            BlockRetrievalRequest, ChunkRetrievalRequest, ConsensusNetworkImpl, NetworkReceivers,
+            AccountStateChunkRetrievalRequest, BlockRetrievalRequest, ChunkRetrievalRequest,
+            ConsensusNetworkImpl, EpochChangeRetrievalRequest, FutureEpochNotification,
+            HighestLedgerInfoRetrievalRequest, NetworkReceivers,
         },
         persistent_storage::{PersistentLivenessStorage, PersistentStorage, RecoveryData},
-        safety::{safety_rules::SafetyRules, vote_msg::VoteMsg},
+        safety::{consensus_signer::ConsensusSigner, safety_rules::SafetyRules, vote_msg::VoteMsg},
     },
     counters,
     state_replication::{StateComputer, StateMachineReplication, TxnManager},
@@ -34,9 +41,9 @@ use futures::{
     future::{FutureExt, TryFutureExt},
     stream::StreamExt,
 };
-use types::validator_signer::ValidatorSigner;
+use types::validator_verifier::ValidatorVerifier;
 
-use config::config::ConsensusConfig;
+use config::config::{ConsensusConfig, ConsensusProposerType};
 use logger::prelude::*;
 use std::{
     sync::{Arc, RwLock},
@@ -45,7 +52,7 @@ use std::{
 };
 use tokio::runtime::{Runtime, TaskExecutor};
 
-type ConcurrentEventProcessor<T, P> = Arc<futures_locks::RwLock<EventProcessor<T, P>>>;
+pub(super) type ConcurrentEventProcessor<T, P> = Arc<futures_locks::RwLock<EventProcessor<T, P>>>;
 
 /// Consensus configuration derived from ConsensusConfig
 pub struct ChainedBftSMRConfig {
@@ -53,10 +60,22 @@ pub struct ChainedBftSMRConfig {
     pub max_pruned_blocks_in_mem: usize,
     /// Initial timeout for pacemaker
     pub pacemaker_initial_timeout: Duration,
+    /// If set, the pacemaker timeouts adapt to this percentile of the observed round latencies
+    pub pacemaker_latency_percentile: Option<u8>,
+    /// Lower bound of the adaptive pacemaker timeouts
+    pub pacemaker_min_timeout: Duration,
+    /// Upper bound of the adaptive pacemaker timeouts
+    pub pacemaker_max_timeout: Duration,
     /// Contiguous rounds for proposer
     pub contiguous_rounds: u32,
     /// Max block size (number of transactions) that consensus pulls from mempool
     pub max_block_size: u64,
+    /// Part of the block reserved for transactions of mempool priority lanes
+    pub priority_lane_block_size: u64,
+    /// How the proposers are chosen among the validators of a new epoch
+    pub proposer_type: ConsensusProposerType,
+    /// Whether the blocks are executed in a pipeline, decoupled from voting
+    pub pipelined_execution: bool,
 }
 
 impl ChainedBftSMRConfig {
@@ -65,8 +84,20 @@ impl ChainedBftSMRConfig {
         ChainedBftSMRConfig {
             max_pruned_blocks_in_mem: cfg.max_pruned_blocks_in_mem().unwrap_or(10000) as usize,
             pacemaker_initial_timeout: Duration::from_millis(pacemaker_initial_timeout_ms),
+            pacemaker_latency_percentile: cfg.pacemaker_latency_percentile(),
+            pacemaker_min_timeout: Duration::from_millis(
+                cfg.pacemaker_min_timeout_ms()
+                    .unwrap_or(pacemaker_initial_timeout_ms / 4),
+            ),
+            pacemaker_max_timeout: Duration::from_millis(
+                cfg.pacemaker_max_timeout_ms()
+                    .unwrap_or(pacemaker_initial_timeout_ms * 12),
+            ),
             contiguous_rounds: cfg.contiguous_rounds(),
             max_block_size: cfg.max_block_size(),
+            priority_lane_block_size: cfg.priority_lane_block_size().unwrap_or(0),
+            proposer_type: cfg.get_proposer_type(),
+            pipelined_execution: cfg.pipelined_execution(),
         }
     }
 }
@@ -76,9 +107,7 @@ impl ChainedBftSMRConfig {
 /// ConsensusProvider for the e2e flow.
 pub struct ChainedBftSMR<T, P> {
     author: P,
-    // TODO [Reconfiguration] quorum size is just a function of current validator set.
-    quorum_size: usize,
-    signer: ValidatorSigner,
+    signer: Arc<dyn ConsensusSigner>,
     proposers: Vec<P>,
     runtime: Option<Runtime>,
     block_store: Option<Arc<BlockStore<T>>>,
@@ -92,8 +121,7 @@ pub struct ChainedBftSMR<T, P> {
 impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
     pub fn new(
         author: P,
-        quorum_size: usize,
-        signer: ValidatorSigner,
+        signer: Arc<dyn ConsensusSigner>,
         proposers: Vec<P>,
         network: ConsensusNetworkImpl,
         runtime: Runtime,
@@ -103,7 +131,6 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
     ) -> Self {
         Self {
             author,
-            quorum_size,
             signer,
             proposers,
             runtime: Some(runtime),
@@ -132,11 +159,25 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
     ) -> Arc<dyn Pacemaker> {
         // 1.5^6 ~= 11
         // Timeout goes from initial_timeout to initial_timeout*11 in 6 steps
-        let time_interval = Box::new(ExponentialTimeInterval::new(
-            self.config.pacemaker_initial_timeout,
-            1.5,
-            6,
-        ));
+        let time_interval: Box<dyn PacemakerTimeInterval> =
+            match self.config.pacemaker_latency_percentile {
+                // The base timeout is twice the latency percentile, it grows in the same steps
+                // but within the configured bounds.
+                Some(percentile) => Box::new(AdaptiveTimeInterval::new(
+                    self.config.pacemaker_initial_timeout,
+                    self.config.pacemaker_min_timeout,
+                    self.config.pacemaker_max_timeout,
+                    percentile,
+                    2.0,
+                    1.5,
+                    6,
+                )),
+                None => Box::new(ExponentialTimeInterval::new(
+                    self.config.pacemaker_initial_timeout,
+                    1.5,
+                    6,
+                )),
+            };
         Arc::new(LocalPacemaker::new(
             executor,
             persistent_liveness_storage,
@@ -146,25 +187,62 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
             time_service,
             new_round_events_sender,
             external_timeout_sender,
-            self.quorum_size,
+            self.network.epoch_info().validator.quorum_size(),
             highest_timeout_certificates,
         ))
     }
 
     /// Create a proposer election handler based on proposers
     fn create_proposer_election(
-        &self,
+        proposer_type: ConsensusProposerType,
+        proposers: Vec<P>,
+        contiguous_rounds: u32,
+        block_store: Arc<BlockStore<T>>,
         winning_proposals_sender: channel::Sender<ProposalInfo<T, P>>,
     ) -> Arc<dyn ProposerElection<T, P> + Send + Sync> {
-        assert!(!self.proposers.is_empty());
-        Arc::new(RotatingProposer::new(
-            self.proposers.clone(),
-            self.config.contiguous_rounds,
-            winning_proposals_sender,
-        ))
+        assert!(!proposers.is_empty());
+        match proposer_type {
+            ConsensusProposerType::LeaderReputation {
+                proposer_window,
+                voter_window,
+            } => Arc::new(LeaderReputation::new(
+                proposers,
+                contiguous_rounds,
+                proposer_window,
+                voter_window,
+                block_store,
+                winning_proposals_sender,
+            )),
+            _ => Arc::new(RotatingProposer::new(
+                proposers,
+                contiguous_rounds,
+                winning_proposals_sender,
+            )),
+        }
     }
 
-    async fn process_new_round_events(
+    /// Choose the proposers among the validators of a new epoch the same way the initial
+    /// proposers are chosen by the consensus provider.
+    fn choose_proposers(
+        proposer_type: ConsensusProposerType,
+        validator: &ValidatorVerifier,
+    ) -> Vec<P> {
+        let validators = validator.get_ordered_account_addresses();
+        match proposer_type {
+            ConsensusProposerType::FixedProposer => validators
+                .into_iter()
+                .max()
+                .map(P::from)
+                .into_iter()
+                .collect(),
+            ConsensusProposerType::RotatingProposer
+            | ConsensusProposerType::LeaderReputation { .. } => {
+                validators.into_iter().map(P::from).collect()
+            }
+        }
+    }
+
+    pub(super) async fn process_new_round_events(
         mut receiver: channel::Receiver<NewRoundEvent>,
         event_processor: ConcurrentEventProcessor<T, P>,
     ) {
@@ -211,7 +289,7 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
-    async fn fetch_and_process_proposal(
+    pub(super) async fn fetch_and_process_proposal(
         event_processor: ConcurrentEventProcessor<T, P>,
         deadline: Instant,
         proposal: ProposalInfo<T, P>,
@@ -220,7 +298,7 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         guard.fetch_and_process_proposal(deadline, proposal).await
     }
 
-    async fn sync_and_process_proposal(
+    pub(super) async fn sync_and_process_proposal(
         event_processor: ConcurrentEventProcessor<T, P>,
         deadline: Instant,
         proposal: ProposalInfo<T, P>,
@@ -229,7 +307,7 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         guard.sync_and_process_proposal(deadline, proposal).await
     }
 
-    async fn process_winning_proposals(
+    pub(super) async fn process_winning_proposals(
         mut receiver: channel::Receiver<ProposalInfo<T, P>>,
         event_processor: ConcurrentEventProcessor<T, P>,
     ) {
@@ -239,18 +317,99 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
-    async fn process_votes(
+    pub(super) async fn process_votes(
         mut receiver: channel::Receiver<VoteMsg>,
         event_processor: ConcurrentEventProcessor<T, P>,
-        quorum_size: usize,
+        network: ConsensusNetworkImpl,
     ) {
         while let Some(vote) = receiver.next().await {
             let guard = event_processor.read().compat().await.unwrap();
+            // The quorum size is a function of the validator set of the current epoch.
+            let quorum_size = network.epoch_info().validator.quorum_size();
             guard.process_vote(vote, quorum_size).await;
         }
     }
 
-    async fn process_timeout_msg(
+    /// Upon a committed validator set change the current epoch ends and a new epoch starts with
+    /// the new validator verifier, network membership and proposer election.
+    async fn process_epoch_changes(
+        mut receiver: channel::Receiver<EpochChange>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+        network: ConsensusNetworkImpl,
+        proposer_type: ConsensusProposerType,
+        contiguous_rounds: u32,
+        block_store: Arc<BlockStore<T>>,
+        winning_proposals_sender: channel::Sender<ProposalInfo<T, P>>,
+    ) {
+        while let Some(epoch_change) = receiver.next().await {
+            let mut guard = event_processor.write().compat().await.unwrap();
+            // The same end of epoch can be notified both by a commit and by a synchronization.
+            if epoch_change.epoch() <= network.epoch() {
+                continue;
+            }
+            let validator = ValidatorVerifier::from(epoch_change.validators());
+            let proposer_election = Self::create_proposer_election(
+                proposer_type,
+                Self::choose_proposers(proposer_type, &validator),
+                contiguous_rounds,
+                Arc::clone(&block_store),
+                winning_proposals_sender.clone(),
+            );
+            counters::EPOCH_NUM.set(epoch_change.epoch() as i64);
+            counters::CURRENT_EPOCH_NUM_VALIDATORS.set(validator.len() as i64);
+            counters::CURRENT_EPOCH_QUORUM_SIZE.set(validator.quorum_size() as i64);
+            guard.start_new_epoch(epoch_change, proposer_election).await;
+        }
+    }
+
+    pub(super) async fn process_future_epochs(
+        mut receiver: channel::Receiver<FutureEpochNotification>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+    ) {
+        while let Some(notification) = receiver.next().await {
+            let mut guard = event_processor.write().compat().await.unwrap();
+            guard.process_future_epoch(notification).await;
+        }
+    }
+
+    pub(super) async fn process_epoch_change_retrievals(
+        mut receiver: channel::Receiver<EpochChangeRetrievalRequest>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+    ) {
+        while let Some(request) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            guard.process_epoch_change_retrieval(request);
+        }
+    }
+
+    /// Replays the persisted ends of epochs so that a restarted validator resumes in its latest
+    /// epoch with the proposers chosen among its validators.
+    fn recover_epoch(&mut self) -> Result<()> {
+        for epoch_change in self.storage.get_epoch_changes()? {
+            if epoch_change.epoch() <= self.network.epoch() {
+                continue;
+            }
+            let epoch_info = self.network.epoch_info().next(&epoch_change);
+            info!(
+                "Recovering epoch {} with {} validators",
+                epoch_info.epoch,
+                epoch_info.validator.len()
+            );
+            self.proposers =
+                Self::choose_proposers(self.config.proposer_type, epoch_info.validator.as_ref());
+            counters::EPOCH_NUM.set(epoch_info.epoch as i64);
+            counters::CURRENT_EPOCH_NUM_VALIDATORS.set(epoch_info.validator.len() as i64);
+            counters::CURRENT_EPOCH_QUORUM_SIZE.set(epoch_info.validator.quorum_size() as i64);
+            self.network.start_new_epoch(epoch_info);
+            block_on(
+                self.network
+                    .update_eligible_nodes(epoch_change.validators().payload().to_vec()),
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn process_timeout_msg(
         mut receiver: channel::Receiver<TimeoutMsg>,
         event_processor: ConcurrentEventProcessor<T, P>,
     ) {
@@ -260,7 +419,17 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
-    async fn process_outgoing_pacemaker_timeouts(
+    pub(super) async fn process_timeout_certificates(
+        mut receiver: channel::Receiver<PacemakerTimeoutCertificate>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+    ) {
+        while let Some(timeout_certificate) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            guard.process_timeout_certificate(timeout_certificate).await;
+        }
+    }
+
+    pub(super) async fn process_outgoing_pacemaker_timeouts(
         mut receiver: channel::Receiver<Round>,
         event_processor: ConcurrentEventProcessor<T, P>,
         mut network: ConsensusNetworkImpl,
@@ -280,7 +449,7 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
-    async fn process_block_retrievals(
+    pub(super) async fn process_block_retrievals(
         mut receiver: channel::Receiver<BlockRetrievalRequest<T>>,
         event_processor: ConcurrentEventProcessor<T, P>,
     ) {
@@ -290,7 +459,7 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
-    async fn process_chunk_retrievals(
+    pub(super) async fn process_chunk_retrievals(
         mut receiver: channel::Receiver<ChunkRetrievalRequest>,
         event_processor: ConcurrentEventProcessor<T, P>,
     ) {
@@ -300,6 +469,26 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         }
     }
 
+    pub(super) async fn process_account_state_chunk_retrievals(
+        mut receiver: channel::Receiver<AccountStateChunkRetrievalRequest>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+    ) {
+        while let Some(request) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            guard.process_account_state_chunk_retrieval(request).await;
+        }
+    }
+
+    pub(super) async fn process_highest_ledger_info_retrievals(
+        mut receiver: channel::Receiver<HighestLedgerInfoRetrievalRequest>,
+        event_processor: ConcurrentEventProcessor<T, P>,
+    ) {
+        while let Some(request) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            guard.process_highest_ledger_info_retrieval(request);
+        }
+    }
+
     fn start_event_processing(
         &self,
         event_processor: ConcurrentEventProcessor<T, P>,
@@ -308,6 +497,9 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
         winning_proposals_receiver: channel::Receiver<ProposalInfo<T, P>>,
         network_receivers: NetworkReceivers<T, P>,
         pacemaker_timeout_sender_rx: channel::Receiver<Round>,
+        epoch_change_receiver: channel::Receiver<EpochChange>,
+        block_store: Arc<BlockStore<T>>,
+        winning_proposals_sender: channel::Sender<ProposalInfo<T, P>>,
     ) {
         executor.spawn(
             Self::process_new_round_events(new_round_events_receiver, event_processor.clone())
@@ -354,11 +546,31 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
             .compat(),
         );
 
+        executor.spawn(
+            Self::process_account_state_chunk_retrievals(
+                network_receivers.account_state_chunk_retrieval,
+                event_processor.clone(),
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
+
+        executor.spawn(
+            Self::process_highest_ledger_info_retrievals(
+                network_receivers.highest_ledger_info_retrieval,
+                event_processor.clone(),
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
+
         executor.spawn(
             Self::process_votes(
                 network_receivers.votes,
                 event_processor.clone(),
-                self.quorum_size,
+                self.network.clone(),
             )
             .boxed()
             .unit_error()
@@ -372,6 +584,16 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
                 .compat(),
         );
 
+        executor.spawn(
+            Self::process_timeout_certificates(
+                network_receivers.timeout_certificates,
+                event_processor.clone(),
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
+
         executor.spawn(
             Self::process_outgoing_pacemaker_timeouts(
                 pacemaker_timeout_sender_rx,
@@ -382,6 +604,38 @@ impl<T: Payload, P: ProposerInfo> ChainedBftSMR<T, P> {
             .unit_error()
             .compat(),
         );
+
+        executor.spawn(
+            Self::process_future_epochs(network_receivers.future_epochs, event_processor.clone())
+                .boxed()
+                .unit_error()
+                .compat(),
+        );
+
+        executor.spawn(
+            Self::process_epoch_change_retrievals(
+                network_receivers.epoch_change_retrieval,
+                event_processor.clone(),
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
+
+        executor.spawn(
+            Self::process_epoch_changes(
+                epoch_change_receiver,
+                event_processor.clone(),
+                self.network.clone(),
+                self.config.proposer_type,
+                self.config.contiguous_rounds,
+                block_store,
+                winning_proposals_sender,
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
     }
 }
 
@@ -410,6 +664,9 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
             .expect("already started, initial data is None");
         let consensus_state = initial_data.state();
         let highest_timeout_certificates = initial_data.highest_timeout_certificates().clone();
+        // The epoch has to be recovered before the pacemaker and the proposer election are
+        // created as they depend on the validators of the epoch.
+        self.recover_epoch()?;
         if initial_data.need_sync() {
             loop {
                 // make sure we sync to the root state in case we're not
@@ -440,10 +697,11 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
         let block_store = Arc::new(block_on(BlockStore::new(
             Arc::clone(&self.storage),
             initial_data,
-            self.signer.clone(),
+            Arc::clone(&self.signer),
             Arc::clone(&state_computer),
             true,
             self.config.max_pruned_blocks_in_mem,
+            self.config.pipelined_execution,
         )));
         self.block_store = Some(Arc::clone(&block_store));
 
@@ -454,6 +712,7 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
             Arc::clone(&txn_manager),
             time_service.clone(),
             self.config.max_block_size,
+            self.config.priority_lane_block_size,
             true,
         );
 
@@ -479,7 +738,15 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
 
         let (winning_proposals_sender, winning_proposals_receiver) =
             channel::new(1_024, &counters::PENDING_WINNING_PROPOSALS);
-        let proposer_election = self.create_proposer_election(winning_proposals_sender);
+        let proposer_election = Self::create_proposer_election(
+            self.config.proposer_type,
+            self.proposers.clone(),
+            self.config.contiguous_rounds,
+            Arc::clone(&block_store),
+            winning_proposals_sender.clone(),
+        );
+        let (epoch_change_sender, epoch_change_receiver) =
+            channel::new(1_024, &counters::PENDING_EPOCH_CHANGES);
         let event_processor = Arc::new(futures_locks::RwLock::new(EventProcessor::new(
             self.author,
             Arc::clone(&block_store),
@@ -490,6 +757,7 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
             state_computer,
             txn_manager,
             self.network.clone(),
+            epoch_change_sender,
             Arc::clone(&self.storage),
             time_service.clone(),
             true,
@@ -502,6 +770,9 @@ impl<T: Payload, P: ProposerInfo> StateMachineReplication for ChainedBftSMR<T, P
             winning_proposals_receiver,
             network_receivers,
             external_timeout_receiver,
+            epoch_change_receiver,
+            block_store,
+            winning_proposals_sender,
         );
 
         debug!("Chained BFT SMR started.");
@@ -10,17 +10,22 @@ use crate::{
         network::ConsensusNetworkImpl,
         network_tests::NetworkPlayground,
         safety::vote_msg::VoteMsg,
-        test_utils::{MockStateComputer, MockStorage, MockTransactionManager, TestPayload},
+        test_utils::{
+            ExecutionGate, MockStateComputer, MockStorage, MockTransactionManager, TestPayload,
+        },
     },
     state_replication::StateMachineReplication,
 };
 use channel;
-use crypto::hash::CryptoHash;
+use crypto::{hash::CryptoHash, x25519};
 use futures::{channel::mpsc, executor::block_on, prelude::*};
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 use proto_conv::FromProto;
 use std::sync::Arc;
-use types::{validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier};
+use types::{
+    validator_public_keys::ValidatorPublicKeys, validator_set::ValidatorSet,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+};
 
 use crate::chained_bft::{
     persistent_storage::RecoveryData,
@@ -31,6 +36,15 @@ use std::{collections::HashMap, time::Duration};
 use tokio::runtime;
 use types::ledger_info::LedgerInfoWithSignatures;
 
+/// How the nodes under test execute the blocks.
+#[derive(Clone, Default)]
+struct ExecutionOptions {
+    /// Vote for the blocks before executing them.
+    pipelined: bool,
+    /// Holds back the execution of the blocks until it is opened.
+    gate: Option<Arc<ExecutionGate>>,
+}
+
 /// Auxiliary struct that is preparing SMR for the test
 struct SMRNode {
     author: Author,
@@ -44,11 +58,11 @@ struct SMRNode {
     mempool: Arc<MockTransactionManager>,
     mempool_notif_receiver: mpsc::Receiver<usize>,
     storage: Arc<MockStorage<TestPayload>>,
+    execution: ExecutionOptions,
 }
 
 impl SMRNode {
     fn start(
-        quorum_size: usize,
         playground: &mut NetworkPlayground,
         signer: ValidatorSigner,
         validator: Arc<ValidatorVerifier>,
@@ -57,6 +71,8 @@ impl SMRNode {
         smr_id: usize,
         storage: Arc<MockStorage<TestPayload>>,
         initial_data: RecoveryData<TestPayload>,
+        reconfiguration: Option<ValidatorSet>,
+        execution: ExecutionOptions,
     ) -> Self {
         let author = signer.author();
 
@@ -81,13 +97,18 @@ impl SMRNode {
         let config = ChainedBftSMRConfig {
             max_pruned_blocks_in_mem: 10000,
             pacemaker_initial_timeout: Duration::from_secs(3),
+            pacemaker_latency_percentile: None,
+            pacemaker_min_timeout: Duration::from_secs(1),
+            pacemaker_max_timeout: Duration::from_secs(36),
             contiguous_rounds: 2,
             max_block_size: 50,
+            priority_lane_block_size: 0,
+            proposer_type: RotatingProposer,
+            pipelined_execution: execution.pipelined,
         };
         let mut smr = ChainedBftSMR::new(
             author,
-            quorum_size,
-            signer.clone(),
+            Arc::new(signer.clone()),
             proposer.clone(),
             network,
             runtime,
@@ -99,11 +120,15 @@ impl SMRNode {
         let mut mp = MockTransactionManager::new();
         let commit_receiver = mp.take_commit_receiver();
         let mempool = Arc::new(mp);
-        smr.start(
-            mempool.clone(),
-            Arc::new(MockStateComputer::new(commit_cb_sender.clone())),
-        )
-        .expect("Failed to start SMR!");
+        let mut state_computer = MockStateComputer::new(commit_cb_sender.clone());
+        if let Some(validators) = reconfiguration {
+            state_computer = state_computer.with_reconfiguration(validators);
+        }
+        if let Some(gate) = &execution.gate {
+            state_computer = state_computer.with_execution_gate(Arc::clone(gate));
+        }
+        smr.start(mempool.clone(), Arc::new(state_computer))
+            .expect("Failed to start SMR!");
         Self {
             author,
             signer,
@@ -116,17 +141,17 @@ impl SMRNode {
             mempool,
             mempool_notif_receiver: commit_receiver,
             storage,
+            execution,
         }
     }
 
-    fn restart(mut self, quorum_size: usize, playground: &mut NetworkPlayground) -> Self {
+    fn restart(mut self, playground: &mut NetworkPlayground) -> Self {
         self.smr.stop();
         let recover_data = self
             .storage
             .get_recovery_data()
             .unwrap_or_else(|e| panic!("fail to restart due to: {}", e));
         Self::start(
-            quorum_size,
             playground,
             self.signer,
             self.validator,
@@ -135,6 +160,8 @@ impl SMRNode {
             self.smr_id + 10,
             self.storage,
             recover_data,
+            None,
+            self.execution,
         )
     }
 
@@ -143,6 +170,26 @@ impl SMRNode {
         quorum_size: usize,
         playground: &mut NetworkPlayground,
         proposer_type: ConsensusProposerType,
+    ) -> Vec<Self> {
+        Self::start_num_nodes_with_options(
+            num_nodes,
+            quorum_size,
+            playground,
+            proposer_type,
+            None,
+            ExecutionOptions::default(),
+        )
+    }
+
+    /// If `next_epoch_validators` is set, the children of genesis emit the validator set made of
+    /// that many first nodes, which starts epoch 1 once they are committed.
+    fn start_num_nodes_with_options(
+        num_nodes: usize,
+        quorum_size: usize,
+        playground: &mut NetworkPlayground,
+        proposer_type: ConsensusProposerType,
+        next_epoch_validators: Option<usize>,
+        execution: ExecutionOptions,
     ) -> Vec<Self> {
         let mut signers = vec![];
         let mut author_to_public_keys = HashMap::new();
@@ -172,11 +219,25 @@ impl SMRNode {
                 RotatingProposer => validator_verifier.get_ordered_account_addresses(),
             }
         };
+        let validator_set = next_epoch_validators.map(|num_validators| {
+            ValidatorSet::new(
+                signers[..num_validators]
+                    .iter()
+                    .map(|signer| {
+                        ValidatorPublicKeys::new(
+                            signer.author(),
+                            signer.public_key(),
+                            signer.public_key(),
+                            x25519::generate_keypair().1,
+                        )
+                    })
+                    .collect(),
+            )
+        });
         let mut nodes = vec![];
         for smr_id in 0..num_nodes {
             let (storage, initial_data) = MockStorage::start_for_testing();
             nodes.push(Self::start(
-                quorum_size,
                 playground,
                 signers.remove(0),
                 Arc::clone(&validator_verifier),
@@ -185,6 +246,8 @@ impl SMRNode {
                 smr_id,
                 storage,
                 initial_data,
+                validator_set.clone(),
+                execution.clone(),
             ));
         }
         nodes
@@ -295,9 +358,30 @@ fn basic_full_round() {
 /// pruned, restart the node and we can still continue.
 #[test]
 fn basic_commit_and_restart() {
+    commit_and_restart(ExecutionOptions::default());
+}
+
+/// The same e2e flow with the execution decoupled from voting: the commits carry the executed
+/// state even though the votes and quorum certificates do not.
+#[test]
+fn basic_commit_and_restart_with_pipelined_execution() {
+    commit_and_restart(ExecutionOptions {
+        pipelined: true,
+        gate: None,
+    });
+}
+
+fn commit_and_restart(execution: ExecutionOptions) {
     let runtime = consensus_runtime();
     let mut playground = NetworkPlayground::new(runtime.executor());
-    let mut nodes = SMRNode::start_num_nodes(2, 2, &mut playground, RotatingProposer);
+    let mut nodes = SMRNode::start_num_nodes_with_options(
+        2,
+        2,
+        &mut playground,
+        RotatingProposer,
+        None,
+        execution,
+    );
     let mut block_ids = vec![];
 
     block_on(async {
@@ -352,7 +436,7 @@ fn basic_commit_and_restart() {
     playground = NetworkPlayground::new(runtime.executor());
     nodes = nodes
         .into_iter()
-        .map(|node| node.restart(2, &mut playground))
+        .map(|node| node.restart(&mut playground))
         .collect();
 
     block_on(async {
@@ -389,6 +473,82 @@ fn basic_commit_and_restart() {
     });
 }
 
+/// With pipelined execution the nodes vote for the proposals before executing them, while a
+/// commit waits for the execution of the committed block and carries its executed state.
+#[test]
+fn pipelined_execution_votes_before_commits() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let gate = Arc::new(ExecutionGate::default());
+    let mut nodes = SMRNode::start_num_nodes_with_options(
+        2,
+        2,
+        &mut playground,
+        RotatingProposer,
+        None,
+        ExecutionOptions {
+            pipelined: true,
+            gate: Some(Arc::clone(&gate)),
+        },
+    );
+
+    block_on(async {
+        let mut block_ids = vec![];
+        // The votes of the first two rounds do not commit anything: they go out although no
+        // block is executed and they only order the proposals.
+        for _ in 0..2 {
+            let _proposals = playground
+                .wait_for_messages(1, NetworkPlayground::exclude_timeout_msg)
+                .await;
+            let mut votes = playground
+                .wait_for_messages(1, NetworkPlayground::votes_only)
+                .await;
+            let vote_msg = VoteMsg::from_proto(votes[0].1.take_vote()).unwrap();
+            assert!(vote_msg.executed_state().is_ordered_placeholder());
+            block_ids.push(vote_msg.proposed_block_id());
+        }
+        for node in &nodes {
+            let block_store = node.smr.block_store().unwrap();
+            for block_id in &block_ids {
+                assert_eq!(block_store.get_state_for_block(*block_id), None);
+            }
+        }
+
+        // The vote of the third round would commit the first block: it waits for its execution.
+        let _proposals = playground
+            .wait_for_messages(1, NetworkPlayground::exclude_timeout_msg)
+            .await;
+        for node in nodes.iter_mut() {
+            assert!(node.commit_cb_receiver.try_next().is_err());
+        }
+        gate.open();
+        let _votes = playground
+            .wait_for_messages(1, NetworkPlayground::votes_only)
+            .await;
+        // The next proposal carries the certificate committing the first block.
+        let _proposals = playground
+            .wait_for_messages(1, NetworkPlayground::exclude_timeout_msg)
+            .await;
+        for node in nodes.iter_mut() {
+            let commit = node.commit_cb_receiver.next().await.unwrap();
+            let ledger_info = commit.ledger_info();
+            assert_eq!(ledger_info.consensus_block_id(), block_ids[0]);
+            let executed_state = node
+                .smr
+                .block_store()
+                .unwrap()
+                .get_state_for_block(block_ids[0])
+                .unwrap();
+            assert!(!executed_state.is_ordered_placeholder());
+            assert_eq!(ledger_info.version(), executed_state.version);
+            assert_eq!(
+                ledger_info.transaction_accumulator_hash(),
+                executed_state.state_id
+            );
+        }
+    });
+}
+
 #[test]
 fn basic_block_retrieval() {
     let runtime = consensus_runtime();
@@ -630,3 +790,88 @@ fn state_sync_on_timeout() {
         assert_eq!(node2_commits[0], proposals[6]);
     });
 }
+
+#[test]
+/// The commit of a block changing the validator set ends the epoch: the proposal of the ended
+/// epoch is not voted for, and after a timeout consensus continues in the new epoch, in which the
+/// last node is not a validator anymore.
+fn reconfiguration_test() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let mut nodes = SMRNode::start_num_nodes_with_options(
+        3,
+        3,
+        &mut playground,
+        RotatingProposer,
+        Some(2),
+        ExecutionOptions::default(),
+    );
+    let next_validator = ValidatorVerifier::new(
+        nodes[..2]
+            .iter()
+            .map(|node| (node.author, node.signer.public_key()))
+            .collect(),
+    );
+    let removed_author = nodes[2].author;
+
+    block_on(async move {
+        // Rounds 1 to 3 are voted in epoch 0.
+        let mut reconfiguration_block_id = None;
+        loop {
+            let mut votes = playground
+                .wait_for_messages(1, NetworkPlayground::votes_only)
+                .await;
+            let vote_msg = VoteMsg::from_proto(votes[0].1.take_vote()).unwrap();
+            assert_eq!(vote_msg.ledger_info().epoch_num(), 0);
+            if vote_msg.round() == 1 {
+                reconfiguration_block_id = Some(vote_msg.proposed_block_id());
+            }
+            if vote_msg.round() == 3 {
+                break;
+            }
+        }
+
+        // The proposal of round 4 commits the block that changes the validator set.
+        playground
+            .wait_for_messages(1, NetworkPlayground::proposals_only)
+            .await;
+        for node in nodes.iter_mut() {
+            let commit = node.commit_cb_receiver.next().await.unwrap();
+            assert_eq!(
+                Some(commit.ledger_info().consensus_block_id()),
+                reconfiguration_block_id
+            );
+            assert_eq!(commit.ledger_info().epoch_num(), 0);
+            verify_finality_proof(node, &commit);
+        }
+
+        // Nobody votes for the proposal of round 4 as its epoch has ended, the next vote is cast
+        // after the timeout in epoch 1.
+        let mut votes = playground
+            .wait_for_messages(1, NetworkPlayground::votes_only)
+            .await;
+        let vote_msg = VoteMsg::from_proto(votes[0].1.take_vote()).unwrap();
+        assert_eq!(vote_msg.ledger_info().epoch_num(), 1);
+        assert!(vote_msg.round() > 4);
+
+        // The blocks keep being committed by the validators of the new epoch only.
+        loop {
+            playground
+                .wait_for_messages(1, NetworkPlayground::take_all)
+                .await;
+            if let Ok(Some(commit)) = nodes[0].commit_cb_receiver.try_next() {
+                if commit.ledger_info().epoch_num() == 1 {
+                    assert!(!commit.signatures().contains_key(&removed_author));
+                    assert_eq!(
+                        Ok(()),
+                        next_validator.verify_aggregated_signature(
+                            commit.ledger_info().hash(),
+                            commit.signatures()
+                        )
+                    );
+                    break;
+                }
+            }
+        }
+    });
+}
@@ -4,16 +4,14 @@
 use crate::{
     chained_bft::{
         common::{Author, Height, Round},
-        consensus_types::quorum_cert::QuorumCert,
-        safety::vote_msg::VoteMsgVerificationError,
+        consensus_types::{epoch_info::EpochInfo, quorum_cert::QuorumCert},
+        safety::{consensus_signer::ConsensusSigner, vote_msg::VoteMsgVerificationError},
     },
     state_replication::ExecutedState,
 };
-use canonical_serialization::{
-    CanonicalDeserialize, CanonicalSerialize, CanonicalSerializer, SimpleSerializer,
-};
+use canonical_serialization::{CanonicalDeserialize, CanonicalSerialize, SimpleSerializer};
 use crypto::{
-    hash::{BlockHasher, CryptoHash, CryptoHasher, GENESIS_BLOCK_ID},
+    hash::{BlockHasher, CryptoHash, GENESIS_BLOCK_ID},
     HashValue, Signature,
 };
 use failure::Result;
@@ -21,6 +19,7 @@ use mirai_annotations::{checked_precondition, checked_precondition_eq};
 use network::proto::Block as ProtoBlock;
 use proto_conv::{FromProto, IntoProto};
 use rmp_serde::{from_slice, to_vec_named};
+use secret_service::safety_rules_data::BlockData;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -30,7 +29,6 @@ use std::{
 use types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     validator_signer::ValidatorSigner,
-    validator_verifier::ValidatorVerifier,
 };
 
 #[cfg(test)]
@@ -43,6 +41,10 @@ pub enum BlockVerificationError {
     QCVerificationError(VoteMsgVerificationError),
     /// The signature verification of this block failed.
     SigVerifyError,
+    /// The block or its quorum cert is signed in an epoch whose validators are unknown.
+    UnknownEpoch(u64),
+    /// The quorum cert is signed in a later epoch than the block.
+    EpochMismatch,
 }
 
 /// Blocks are managed in a speculative tree, the committed blocks form a chain.
@@ -58,6 +60,9 @@ pub struct Block<T> {
     /// The round of a block is an internal monotonically increasing counter used by Consensus
     /// protocol.
     round: Round,
+    /// The epoch of the validators proposing and voting for the block. The quorum cert of the
+    /// first blocks of an epoch is signed by the validators of the previous epoch.
+    epoch: u64,
     /// The height of a block is its position in the chain (block height = parent block height + 1)
     height: Height,
     /// The approximate physical time a block is proposed by a proposer.  This timestamp is used
@@ -134,6 +139,7 @@ where
             payload: T::default(),
             parent_id: HashValue::zero(),
             round: 0,
+            epoch: 0,
             height: 0,
             timestamp_usecs: 0, // The beginning of UNIX TIME
             quorum_cert: genesis_quorum_cert,
@@ -147,25 +153,26 @@ where
     pub fn new_internal(
         payload: T,
         parent_id: HashValue,
+        epoch: u64,
         round: Round,
         height: Height,
         timestamp_usecs: u64,
         quorum_cert: QuorumCert,
-        validator_signer: &ValidatorSigner,
+        validator_signer: &dyn ConsensusSigner,
     ) -> Self {
-        let block_internal = BlockSerializer {
-            parent_id,
-            payload: &payload,
+        let block_data = BlockData {
+            epoch,
             round,
             height,
             timestamp_usecs,
-            quorum_cert: &quorum_cert,
+            payload: serialize_payload(&payload),
+            parent_id,
+            quorum_cert: quorum_cert.certificate_data(),
             author: validator_signer.author(),
         };
-
-        let id = block_internal.hash();
+        let id = block_data.hash();
         let signature = validator_signer
-            .sign_message(id)
+            .sign_proposal(&block_data)
             .expect("Failed to sign message");
 
         Block {
@@ -173,6 +180,7 @@ where
             payload,
             parent_id,
             round,
+            epoch,
             height,
             timestamp_usecs,
             quorum_cert,
@@ -181,20 +189,45 @@ where
         }
     }
 
+    /// Makes a block of the same epoch as its parent.
     pub fn make_block(
         parent_block: &Block<T>,
         payload: T,
         round: Round,
         timestamp_usecs: u64,
         quorum_cert: QuorumCert,
-        validator_signer: &ValidatorSigner,
+        validator_signer: &dyn ConsensusSigner,
+    ) -> Self {
+        Block::make_block_in_epoch(
+            parent_block,
+            payload,
+            parent_block.epoch(),
+            round,
+            timestamp_usecs,
+            quorum_cert,
+            validator_signer,
+        )
+    }
+
+    /// Makes a block of the given epoch, which is either the epoch of its parent or a later one
+    /// for the first blocks of an epoch.
+    pub fn make_block_in_epoch(
+        parent_block: &Block<T>,
+        payload: T,
+        epoch: u64,
+        round: Round,
+        timestamp_usecs: u64,
+        quorum_cert: QuorumCert,
+        validator_signer: &dyn ConsensusSigner,
     ) -> Self {
         // A block must carry a QC to its parent.
         checked_precondition_eq!(quorum_cert.certified_block_id(), parent_block.id());
         checked_precondition!(round > parent_block.round());
+        checked_precondition!(epoch >= parent_block.epoch());
         Block::new_internal(
             payload,
             parent_block.id(),
+            epoch,
             round,
             // Height is always parent's height + 1 because it's just the position in the chain.
             parent_block.height() + 1,
@@ -208,18 +241,30 @@ where
         &self.payload
     }
 
+    /// Verifies the signature of the author against the validators of the epoch of the block
+    /// and the quorum cert against the validators of the epoch it is signed in.
     pub fn verify(
         &self,
-        validator: &ValidatorVerifier,
+        epoch_info: &EpochInfo,
     ) -> ::std::result::Result<(), BlockVerificationError> {
         if self.is_genesis_block() {
             return Ok(());
         }
+        let validator = epoch_info
+            .validator_for_epoch(self.epoch)
+            .ok_or(BlockVerificationError::UnknownEpoch(self.epoch))?;
         validator
             .verify_signature(self.author(), self.hash(), self.signature())
             .map_err(|_| BlockVerificationError::SigVerifyError)?;
+        let quorum_cert_epoch = self.quorum_cert.epoch();
+        if quorum_cert_epoch > self.epoch {
+            return Err(BlockVerificationError::EpochMismatch);
+        }
+        let quorum_cert_validator = epoch_info
+            .validator_for_epoch(quorum_cert_epoch)
+            .ok_or(BlockVerificationError::UnknownEpoch(quorum_cert_epoch))?;
         self.quorum_cert
-            .verify(validator)
+            .verify(quorum_cert_validator.as_ref())
             .map_err(BlockVerificationError::QCVerificationError)
     }
 
@@ -239,6 +284,10 @@ where
         self.round
     }
 
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
     pub fn timestamp_usecs(&self) -> u64 {
         self.timestamp_usecs
     }
@@ -260,68 +309,38 @@ where
     }
 }
 
-impl<T> CryptoHash for Block<T>
+impl<T> Block<T>
 where
-    T: canonical_serialization::CanonicalSerialize,
+    T: CanonicalSerialize,
 {
-    type Hasher = BlockHasher;
-
-    fn hash(&self) -> HashValue {
-        let block_internal = BlockSerializer {
-            parent_id: self.parent_id,
-            payload: &self.payload,
+    /// Returns the fields of the block its id is computed from.
+    pub fn block_data(&self) -> BlockData {
+        BlockData {
+            epoch: self.epoch,
             round: self.round,
             height: self.height,
             timestamp_usecs: self.timestamp_usecs,
-            quorum_cert: &self.quorum_cert,
+            payload: serialize_payload(&self.payload),
+            parent_id: self.parent_id,
+            quorum_cert: self.quorum_cert.certificate_data(),
             author: self.author,
-        };
-        block_internal.hash()
+        }
     }
 }
 
-// Internal use only. Contains all the fields in Block that contributes to the computation of
-// Block Id
-struct BlockSerializer<'a, T> {
-    parent_id: HashValue,
-    payload: &'a T,
-    round: Round,
-    height: Height,
-    timestamp_usecs: u64,
-    quorum_cert: &'a QuorumCert,
-    author: Author,
-}
-
-impl<'a, T> CryptoHash for BlockSerializer<'a, T>
+impl<T> CryptoHash for Block<T>
 where
-    T: CanonicalSerialize,
+    T: canonical_serialization::CanonicalSerialize,
 {
     type Hasher = BlockHasher;
 
     fn hash(&self) -> HashValue {
-        let bytes =
-            SimpleSerializer::<Vec<u8>>::serialize(self).expect("block serialization failed");
-        let mut state = Self::Hasher::default();
-        state.write(bytes.as_ref());
-        state.finish()
+        self.block_data().hash()
     }
 }
 
-impl<'a, T> CanonicalSerialize for BlockSerializer<'a, T>
-where
-    T: CanonicalSerialize,
-{
-    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
-        serializer
-            .encode_u64(self.timestamp_usecs)?
-            .encode_u64(self.round)?
-            .encode_u64(self.height)?
-            .encode_struct(self.payload)?
-            .encode_raw_bytes(self.parent_id.as_ref())?
-            .encode_raw_bytes(self.quorum_cert.certified_block_id().as_ref())?
-            .encode_struct(&self.author)?;
-        Ok(())
-    }
+fn serialize_payload<T: CanonicalSerialize>(payload: &T) -> Vec<u8> {
+    SimpleSerializer::<Vec<u8>>::serialize(payload).expect("block serialization failed")
 }
 
 #[cfg(test)]
@@ -352,6 +371,7 @@ where
                 .into(),
         );
         proto.set_round(self.round());
+        proto.set_epoch(self.epoch());
         proto.set_height(self.height());
         proto.set_quorum_cert(self.quorum_cert().clone().into_proto());
         proto.set_signature(self.signature().to_compact().as_ref().into());
@@ -372,6 +392,7 @@ where
         let payload = from_slice(object.get_payload())?;
         let timestamp_usecs = object.get_timestamp_usecs();
         let round = object.get_round();
+        let epoch = object.get_epoch();
         let height = object.get_height();
         let quorum_cert = QuorumCert::from_proto(object.take_quorum_cert())?;
         let author = Author::try_from(object.take_author())?;
@@ -381,6 +402,7 @@ where
             parent_id,
             payload,
             round,
+            epoch,
             timestamp_usecs,
             height,
             quorum_cert,
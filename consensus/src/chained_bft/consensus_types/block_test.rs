@@ -38,6 +38,7 @@ prop_compose! {
         Block::new_internal(
             vec![payload],
             parent_id,
+            0,
             round,
             height,
             get_current_timestamp().as_micros() as u64,
@@ -85,6 +86,7 @@ prop_compose! {
                 id: fake_id,
                 payload: block.get_payload().clone(),
                 round: block.round(),
+                epoch: block.epoch(),
                 height: block.height(),
                 parent_id: block.parent_id(),
                 quorum_cert: block.quorum_cert().clone(),
@@ -240,7 +242,7 @@ fn test_block_qc() {
         genesis_qc.clone(),
         &signer,
     );
-    let a1_qc = placeholder_certificate_for_block(vec![signer.clone()], a1.id(), a1.round());
+    let a1_qc = placeholder_certificate_for_block(vec![&signer], a1.id(), a1.round());
 
     let result = panic::catch_unwind(|| {
         // should panic because qc does not point to parent
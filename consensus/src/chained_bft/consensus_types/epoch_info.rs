@@ -0,0 +1,143 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{common::Author, consensus_types::quorum_cert::QuorumCert};
+use failure::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Formatter},
+    sync::Arc,
+};
+use types::{validator_set::ValidatorSet, validator_verifier::ValidatorVerifier};
+
+#[cfg(test)]
+#[path = "epoch_info_test.rs"]
+pub mod epoch_info_test;
+
+/// The end of an epoch: the commit certified by `proof`, which is signed by the validators of
+/// the ending epoch, changes the validator set to `validators` starting from `epoch`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EpochChange {
+    epoch: u64,
+    validators: ValidatorSet,
+    proof: QuorumCert,
+}
+
+impl Display for EpochChange {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "[epoch: {}, validators: {}, proof: {}]",
+            self.epoch,
+            self.validators.payload().len(),
+            self.proof
+        )
+    }
+}
+
+impl EpochChange {
+    pub fn new(proof: QuorumCert, validators: ValidatorSet) -> Self {
+        EpochChange {
+            epoch: proof.epoch() + 1,
+            validators,
+            proof,
+        }
+    }
+
+    /// The epoch that starts with the change.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// The validators of the new epoch.
+    pub fn validators(&self) -> &ValidatorSet {
+        &self.validators
+    }
+
+    /// The quorum certificate carrying the ledger info that committed the change.
+    pub fn proof(&self) -> &QuorumCert {
+        &self.proof
+    }
+}
+
+/// The validators of the current epoch. Messages from other epochs are dropped and the rest are
+/// verified against the validators of the current epoch, while the certificates they carry are
+/// verified against the validators of the epoch they are signed in.
+#[derive(Clone)]
+pub struct EpochInfo {
+    pub epoch: u64,
+    pub peers: Arc<Vec<Author>>,
+    pub validator: Arc<ValidatorVerifier>,
+    /// The validators of the previous epochs: e.g., the first proposal of an epoch carries a
+    /// quorum certificate signed in the previous one.
+    pub previous_validators: Arc<BTreeMap<u64, Arc<ValidatorVerifier>>>,
+}
+
+impl EpochInfo {
+    /// The first epoch, its validators are known from the configuration.
+    pub fn genesis(peers: Arc<Vec<Author>>, validator: Arc<ValidatorVerifier>) -> Self {
+        EpochInfo {
+            epoch: 0,
+            peers,
+            validator,
+            previous_validators: Arc::new(BTreeMap::new()),
+        }
+    }
+
+    /// Returns the epoch following the given change of the validator set of this epoch.
+    pub fn next(&self, epoch_change: &EpochChange) -> Self {
+        assert_eq!(
+            epoch_change.epoch(),
+            self.epoch + 1,
+            "Epoch change {} does not follow epoch {}",
+            epoch_change,
+            self.epoch
+        );
+        let validator = Arc::new(ValidatorVerifier::from(epoch_change.validators()));
+        let mut previous_validators = BTreeMap::clone(&self.previous_validators);
+        previous_validators.insert(self.epoch, Arc::clone(&self.validator));
+        EpochInfo {
+            epoch: epoch_change.epoch(),
+            peers: Arc::new(validator.get_ordered_account_addresses()),
+            validator,
+            previous_validators: Arc::new(previous_validators),
+        }
+    }
+
+    /// Returns the validators of the given epoch if it is the current epoch or a previous one.
+    pub fn validator_for_epoch(&self, epoch: u64) -> Option<Arc<ValidatorVerifier>> {
+        if epoch == self.epoch {
+            Some(Arc::clone(&self.validator))
+        } else {
+            self.previous_validators.get(&epoch).cloned()
+        }
+    }
+
+    /// Verifies the quorum certificate against the validators of the epoch it is signed in.
+    pub fn verify_quorum_cert(&self, quorum_cert: &QuorumCert) -> Result<()> {
+        let validator = self
+            .validator_for_epoch(quorum_cert.epoch())
+            .ok_or_else(|| {
+                format_err!(
+                    "Quorum certificate {} of epoch {} while the current epoch is {}",
+                    quorum_cert,
+                    quorum_cert.epoch(),
+                    self.epoch
+                )
+            })?;
+        quorum_cert
+            .verify(validator.as_ref())
+            .map_err(|e| format_err!("{:?}", e))
+    }
+
+    /// Whether the given validator set is the one of this epoch: the same authors with the same
+    /// consensus keys.
+    pub fn has_validators(&self, validators: &ValidatorSet) -> bool {
+        validators.payload().len() == self.validator.len()
+            && validators.payload().iter().all(|keys| {
+                self.validator.get_public_key(*keys.account_address())
+                    == Some(*keys.consensus_public_key())
+            })
+    }
+}
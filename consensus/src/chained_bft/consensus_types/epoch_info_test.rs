@@ -0,0 +1,104 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    consensus_types::epoch_info::{EpochChange, EpochInfo},
+    test_utils::placeholder_certificate_in_epoch,
+};
+use crypto::{x25519, HashValue};
+use std::sync::Arc;
+use types::{
+    validator_public_keys::ValidatorPublicKeys, validator_set::ValidatorSet,
+    validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier,
+};
+
+fn validator_set(signers: &[ValidatorSigner]) -> ValidatorSet {
+    ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorPublicKeys::new(
+                    signer.author(),
+                    signer.public_key(),
+                    signer.public_key(),
+                    x25519::generate_keypair().1,
+                )
+            })
+            .collect(),
+    )
+}
+
+fn genesis_epoch(signers: &[ValidatorSigner]) -> EpochInfo {
+    let validator = Arc::new(ValidatorVerifier::from(&validator_set(signers)));
+    EpochInfo::genesis(
+        Arc::new(validator.get_ordered_account_addresses()),
+        validator,
+    )
+}
+
+#[test]
+fn test_next_epoch() {
+    let signers: Vec<_> = (0..4).map(ValidatorSigner::from_int).collect();
+    let genesis = genesis_epoch(&signers[..3]);
+    let proof =
+        placeholder_certificate_in_epoch(signers[..3].iter().collect(), HashValue::random(), 1, 0);
+    let epoch_change = EpochChange::new(proof, validator_set(&signers[1..]));
+    assert_eq!(epoch_change.epoch(), 1);
+
+    let next = genesis.next(&epoch_change);
+    assert_eq!(next.epoch, 1);
+    assert_eq!(next.validator.len(), 3);
+    assert!(next.peers.contains(&signers[3].author()));
+    assert!(!next.peers.contains(&signers[0].author()));
+    assert!(next.has_validators(epoch_change.validators()));
+    assert!(!genesis.has_validators(epoch_change.validators()));
+    assert!(genesis.has_validators(&validator_set(&signers[..3])));
+
+    // The validators of the previous epoch are kept for the certificates signed in it.
+    assert_eq!(
+        next.validator_for_epoch(0)
+            .unwrap()
+            .get_ordered_account_addresses(),
+        genesis.validator.get_ordered_account_addresses()
+    );
+    assert!(next.validator_for_epoch(1).is_some());
+    assert!(next.validator_for_epoch(2).is_none());
+}
+
+#[test]
+#[should_panic]
+fn test_next_epoch_skipping_an_epoch() {
+    let signers: Vec<_> = (0..3).map(ValidatorSigner::from_int).collect();
+    let genesis = genesis_epoch(&signers);
+    let proof =
+        placeholder_certificate_in_epoch(signers.iter().collect(), HashValue::random(), 1, 1);
+    genesis.next(&EpochChange::new(proof, validator_set(&signers)));
+}
+
+#[test]
+fn test_verify_quorum_cert_per_epoch() {
+    let signers: Vec<_> = (0..4).map(ValidatorSigner::from_int).collect();
+    let genesis = genesis_epoch(&signers[..3]);
+    let proof =
+        placeholder_certificate_in_epoch(signers[..3].iter().collect(), HashValue::random(), 1, 0);
+    let next = genesis.next(&EpochChange::new(
+        proof.clone(),
+        validator_set(&signers[1..]),
+    ));
+
+    // A certificate of the previous epoch is verified against the previous validators.
+    assert!(next.verify_quorum_cert(&proof).is_ok());
+    // A certificate of the current epoch is verified against the current validators.
+    let current =
+        placeholder_certificate_in_epoch(signers[1..].iter().collect(), HashValue::random(), 2, 1);
+    assert!(next.verify_quorum_cert(&current).is_ok());
+    assert!(genesis.verify_quorum_cert(&current).is_err());
+    // The previous validators cannot certify in the current epoch.
+    let removed =
+        placeholder_certificate_in_epoch(signers[..3].iter().collect(), HashValue::random(), 2, 1);
+    assert!(next.verify_quorum_cert(&removed).is_err());
+    // Certificates of unknown epochs are rejected.
+    let future =
+        placeholder_certificate_in_epoch(signers[1..].iter().collect(), HashValue::random(), 3, 2);
+    assert!(next.verify_quorum_cert(&future).is_err());
+}
@@ -0,0 +1,114 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    common::{Author, Payload, Round},
+    consensus_types::block::Block,
+    safety::vote_msg::VoteMsg,
+};
+use crypto::hash::CryptoHash;
+use failure::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use types::validator_verifier::ValidatorVerifier;
+
+/// A proof that a validator signed two conflicting messages for the same round.
+/// The evidence is self-contained: it carries both signed messages, so that anyone knowing the
+/// public key of the validator can verify it after the fact.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+pub enum EquivocationEvidence<T> {
+    /// Two different votes of the same author for the same round.
+    Vote { first: VoteMsg, second: VoteMsg },
+    /// Two different proposals of the same author for the same round.
+    Proposal { first: Block<T>, second: Block<T> },
+}
+
+impl<T> Display for EquivocationEvidence<T> {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            EquivocationEvidence::Vote { first, second } => {
+                write!(f, "Equivocating votes: [{}, {}]", first, second)
+            }
+            EquivocationEvidence::Proposal { first, second } => write!(
+                f,
+                "Equivocating proposals of {}: [{}, {}]",
+                first.author().short_str(),
+                first,
+                second
+            ),
+        }
+    }
+}
+
+impl<T: Payload> EquivocationEvidence<T> {
+    /// The validator that signed the conflicting messages.
+    pub fn author(&self) -> Author {
+        match self {
+            EquivocationEvidence::Vote { first, .. } => first.author(),
+            EquivocationEvidence::Proposal { first, .. } => first.author(),
+        }
+    }
+
+    /// The round of the conflicting messages.
+    pub fn round(&self) -> Round {
+        match self {
+            EquivocationEvidence::Vote { first, .. } => first.round(),
+            EquivocationEvidence::Proposal { first, .. } => first.round(),
+        }
+    }
+
+    /// The kind of the conflicting messages ("vote" or "proposal").
+    pub fn kind(&self) -> &'static str {
+        match self {
+            EquivocationEvidence::Vote { .. } => "vote",
+            EquivocationEvidence::Proposal { .. } => "proposal",
+        }
+    }
+
+    /// Verifies that the two messages are different messages of the same author for the same
+    /// round and that both of them carry a valid signature of the author.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> Result<()> {
+        match self {
+            EquivocationEvidence::Vote { first, second } => {
+                ensure!(
+                    first.author() == second.author() && first.round() == second.round(),
+                    "Votes of different authors or rounds: {}, {}",
+                    first,
+                    second
+                );
+                ensure!(
+                    first.vote_hash() != second.vote_hash(),
+                    "Votes are not conflicting: {}, {}",
+                    first,
+                    second
+                );
+                for vote in &[first, second] {
+                    vote.verify(validator)
+                        .map_err(|e| format_err!("Invalid vote {}: {}", vote, e))?;
+                }
+            }
+            EquivocationEvidence::Proposal { first, second } => {
+                ensure!(
+                    first.author() == second.author() && first.round() == second.round(),
+                    "Proposals of different authors or rounds: {}, {}",
+                    first,
+                    second
+                );
+                ensure!(
+                    first.id() != second.id(),
+                    "Proposals are not conflicting: {}, {}",
+                    first,
+                    second
+                );
+                for block in &[first, second] {
+                    // The signature covers the id, the id has to match the content of the block.
+                    ensure!(block.id() == block.hash(), "Invalid id of block {}", block);
+                    validator
+                        .verify_signature(block.author(), block.id(), block.signature())
+                        .map_err(|e| format_err!("Invalid signature of block {}: {}", block, e))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
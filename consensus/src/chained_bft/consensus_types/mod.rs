@@ -2,4 +2,6 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub(crate) mod block;
+pub(crate) mod epoch_info;
+pub(crate) mod equivocation_evidence;
 pub(crate) mod quorum_cert;
@@ -15,6 +15,7 @@ use crypto::{
 use failure::Result;
 use network::proto::QuorumCert as ProtoQuorumCert;
 use proto_conv::{FromProto, IntoProto};
+use secret_service::safety_rules_data::QuorumCertData;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
@@ -80,6 +81,22 @@ impl QuorumCert {
         &self.signed_ledger_info
     }
 
+    /// The epoch of the validators that signed the ledger info.
+    pub fn epoch(&self) -> u64 {
+        self.signed_ledger_info.ledger_info().epoch_num()
+    }
+
+    /// The fields of the certificate the safety rules depend on.
+    pub fn certificate_data(&self) -> QuorumCertData {
+        QuorumCertData {
+            certified_block_id: self.certified_block_id,
+            certified_state_id: self.certified_state.state_id,
+            certified_state_version: self.certified_state.version,
+            certified_block_round: self.certified_block_round,
+            signed_ledger_info: self.signed_ledger_info.clone(),
+        }
+    }
+
     pub fn committed_block_id(&self) -> Option<HashValue> {
         let id = self.ledger_info().ledger_info().consensus_block_id();
         if id.is_zero() {
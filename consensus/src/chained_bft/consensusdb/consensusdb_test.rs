@@ -3,6 +3,7 @@
 
 use super::*;
 use tempfile::tempdir;
+use types::account_address::AccountAddress;
 
 #[test]
 fn test_put_get() {
@@ -53,3 +54,41 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_blocks::<i64>().unwrap().len(), 0);
     assert_eq!(db.get_quorum_certificates().unwrap().len(), 0);
 }
+
+#[test]
+fn test_equivocation_evidence() {
+    let tmp_dir = tempdir().unwrap();
+    let db = ConsensusDB::new(&tmp_dir);
+    assert!(db.get_equivocation_evidence().unwrap().is_empty());
+
+    let author = AccountAddress::random();
+    db.save_equivocation_evidence((5, EquivocationKind::Vote, author), vec![0x05])
+        .unwrap();
+    db.save_equivocation_evidence((2, EquivocationKind::Proposal, author), vec![0x02])
+        .unwrap();
+
+    // The evidence is ordered by round.
+    assert_eq!(
+        db.get_equivocation_evidence().unwrap(),
+        vec![
+            ((2, EquivocationKind::Proposal, author), vec![0x02]),
+            ((5, EquivocationKind::Vote, author), vec![0x05]),
+        ]
+    );
+}
+
+#[test]
+fn test_epoch_changes() {
+    let tmp_dir = tempdir().unwrap();
+    let db = ConsensusDB::new(&tmp_dir);
+    assert!(db.get_epoch_changes().unwrap().is_empty());
+
+    db.save_epoch_change(2, vec![0x02]).unwrap();
+    db.save_epoch_change(1, vec![0x01]).unwrap();
+
+    // The changes are ordered by epoch.
+    assert_eq!(
+        db.get_epoch_changes().unwrap(),
+        vec![(1, vec![0x01]), (2, vec![0x02])]
+    );
+}
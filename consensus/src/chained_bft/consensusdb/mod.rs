@@ -10,6 +10,8 @@ use crate::chained_bft::{
     consensus_types::{block::Block, quorum_cert::QuorumCert},
     consensusdb::schema::{
         block::BlockSchema,
+        epoch_change::EpochChangeSchema,
+        equivocation_evidence::{EquivocationEvidenceKey, EquivocationEvidenceSchema},
         quorum_certificate::QCSchema,
         single_entry::{SingleEntryKey, SingleEntrySchema},
     },
@@ -17,7 +19,11 @@ use crate::chained_bft::{
 use crypto::HashValue;
 use failure::prelude::*;
 use logger::prelude::*;
-use schema::{BLOCK_CF_NAME, QC_CF_NAME, SINGLE_ENTRY_CF_NAME};
+pub(crate) use schema::equivocation_evidence::EquivocationKind;
+use schema::{
+    BLOCK_CF_NAME, EPOCH_CHANGE_CF_NAME, EQUIVOCATION_EVIDENCE_CF_NAME, QC_CF_NAME,
+    SINGLE_ENTRY_CF_NAME,
+};
 use schemadb::{
     ColumnFamilyOptions, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, DB, DEFAULT_CF_NAME,
 };
@@ -32,24 +38,17 @@ pub struct ConsensusDB {
 
 impl ConsensusDB {
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let cf_opts_map: ColumnFamilyOptionsMap = [
-            (
-                /* UNUSED CF = */ DEFAULT_CF_NAME,
-                ColumnFamilyOptions::default(),
-            ),
-            (BLOCK_CF_NAME, ColumnFamilyOptions::default()),
-            (QC_CF_NAME, ColumnFamilyOptions::default()),
-            (SINGLE_ENTRY_CF_NAME, ColumnFamilyOptions::default()),
-        ]
-        .iter()
-        .cloned()
-        .collect();
+        Self::open(db_root_path).unwrap_or_else(|e| {
+            panic!("ConsensusDB open failed due to {:?}, unable to continue", e)
+        })
+    }
 
+    /// Opens (or creates) the ConsensusDB under `db_root_path` for reads and writes. It fails if
+    /// the db is held by another process, e.g. a running node.
+    pub fn open<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
         let path = db_root_path.as_ref().join("consensusdb");
         let instant = Instant::now();
-        let db = DB::open(path.clone(), cf_opts_map).unwrap_or_else(|e| {
-            panic!("ConsensusDB open failed due to {:?}, unable to continue", e)
-        });
+        let db = DB::open(path.clone(), Self::cf_opts_map())?;
 
         info!(
             "Opened ConsensusDB at {:?} in {} ms",
@@ -57,7 +56,34 @@ impl ConsensusDB {
             instant.elapsed().as_millis()
         );
 
-        Self { db }
+        Ok(Self { db })
+    }
+
+    /// Opens the ConsensusDB under `db_root_path` for inspection only: all the writes fail.
+    pub fn open_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("consensusdb");
+        let db = DB::open_readonly(path, Self::cf_opts_map())?;
+        Ok(Self { db })
+    }
+
+    fn cf_opts_map() -> ColumnFamilyOptionsMap {
+        [
+            (
+                /* UNUSED CF = */ DEFAULT_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
+            (BLOCK_CF_NAME, ColumnFamilyOptions::default()),
+            (EPOCH_CHANGE_CF_NAME, ColumnFamilyOptions::default()),
+            (
+                EQUIVOCATION_EVIDENCE_CF_NAME,
+                ColumnFamilyOptions::default(),
+            ),
+            (QC_CF_NAME, ColumnFamilyOptions::default()),
+            (SINGLE_ENTRY_CF_NAME, ColumnFamilyOptions::default()),
+        ]
+        .iter()
+        .cloned()
+        .collect()
     }
 
     pub fn get_data<T: Payload>(
@@ -145,6 +171,65 @@ impl ConsensusDB {
         self.commit(batch)
     }
 
+    /// Deletes the given blocks and, separately, the quorum certs certifying the given block ids
+    /// atomically. Unlike `delete_blocks_and_quorum_certificates`, a QC may be kept for a deleted
+    /// block (e.g. the QC carrying the ledger info of the committed root).
+    pub fn delete_blocks_and_quorum_certificates_separately<T: Payload>(
+        &self,
+        block_ids: Vec<HashValue>,
+        qc_block_ids: Vec<HashValue>,
+    ) -> Result<()> {
+        ensure!(
+            !block_ids.is_empty() || !qc_block_ids.is_empty(),
+            "Consensus block and qc ids are empty!"
+        );
+        let mut batch = SchemaBatch::new();
+        block_ids
+            .iter()
+            .map(|hash| batch.delete::<BlockSchema<T>>(hash))
+            .collect::<Result<()>>()?;
+        qc_block_ids
+            .iter()
+            .map(|hash| batch.delete::<QCSchema>(hash))
+            .collect::<Result<()>>()?;
+        self.commit(batch)
+    }
+
+    /// Persists the serialized evidence of an equivocation, a later evidence for the same key
+    /// replaces the previous one.
+    pub fn save_equivocation_evidence(
+        &self,
+        key: EquivocationEvidenceKey,
+        evidence: Vec<u8>,
+    ) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<EquivocationEvidenceSchema>(&key, &evidence)?;
+        self.commit(batch)
+    }
+
+    /// Get all the serialized equivocation evidence ordered by round.
+    pub fn get_equivocation_evidence(&self) -> Result<Vec<(EquivocationEvidenceKey, Vec<u8>)>> {
+        let mut iter = self
+            .db
+            .iter::<EquivocationEvidenceSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.collect::<Result<Vec<_>>>()
+    }
+
+    /// Persists the serialized change of the validator set that starts the given epoch.
+    pub fn save_epoch_change(&self, epoch: u64, epoch_change: Vec<u8>) -> Result<()> {
+        let mut batch = SchemaBatch::new();
+        batch.put::<EpochChangeSchema>(&epoch, &epoch_change)?;
+        self.commit(batch)
+    }
+
+    /// Get all the serialized epoch changes ordered by epoch.
+    pub fn get_epoch_changes(&self) -> Result<Vec<(u64, Vec<u8>)>> {
+        let mut iter = self.db.iter::<EpochChangeSchema>(ReadOptions::default())?;
+        iter.seek_to_first();
+        iter.collect::<Result<Vec<_>>>()
+    }
+
     /// Write the whole schema batch including all data necessary to mutate the ledge
     /// state of some transaction by leveraging rocksdb atomicity support.
     fn commit(&self, batch: SchemaBatch) -> Result<()> {
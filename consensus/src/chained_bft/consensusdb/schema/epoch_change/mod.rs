@@ -0,0 +1,49 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the changes of the validator set that end the
+//! consensus epochs.
+//!
+//! Serialized epoch change bytes identified by the epoch starting with the change, so that the
+//! changes are ordered by epoch.
+//! ```text
+//! |<--key-->|<-------value------>|
+//! |  epoch  | epoch change bytes |
+//! ```
+
+use super::{ensure_slice_len_eq, EPOCH_CHANGE_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use failure::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+
+define_schema!(EpochChangeSchema, u64, Vec<u8>, EPOCH_CHANGE_CF_NAME);
+
+impl KeyCodec<EpochChangeSchema> for u64 {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded_key = Vec::with_capacity(size_of::<u64>());
+        encoded_key.write_u64::<BigEndian>(*self)?;
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<u64>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+impl ValueCodec<EpochChangeSchema> for Vec<u8> {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,10 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use schemadb::schema::assert_encode_decode;
+
+#[test]
+fn test_epoch_change_schema() {
+    assert_encode_decode::<EpochChangeSchema>(&3, &vec![1u8, 2u8, 3u8]);
+}
@@ -0,0 +1,83 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the evidence of equivocating validators.
+//!
+//! Serialized evidence bytes identified by the round, the kind of the conflicting messages and
+//! the author of the messages, so that the evidence is ordered by round.
+//! ```text
+//! |<-----------key----------->|<-----value----->|
+//! | round | kind |   author   | evidence bytes  |
+//! ```
+
+use super::{ensure_slice_len_eq, EQUIVOCATION_EVIDENCE_CF_NAME};
+use crate::chained_bft::common::{Author, Round};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use failure::prelude::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::{convert::TryFrom, mem::size_of};
+use types::account_address::ADDRESS_LENGTH;
+
+define_schema!(
+    EquivocationEvidenceSchema,
+    EquivocationEvidenceKey,
+    Vec<u8>,
+    EQUIVOCATION_EVIDENCE_CF_NAME
+);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, FromPrimitive, ToPrimitive)]
+#[repr(u8)]
+pub enum EquivocationKind {
+    // Two different votes for the same round
+    Vote = 0,
+    // Two different proposals for the same round
+    Proposal = 1,
+}
+
+pub type EquivocationEvidenceKey = (Round, EquivocationKind, Author);
+
+impl KeyCodec<EquivocationEvidenceSchema> for EquivocationEvidenceKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (round, kind, author) = *self;
+
+        let mut encoded_key =
+            Vec::with_capacity(size_of::<Round>() + size_of::<u8>() + ADDRESS_LENGTH);
+        encoded_key.write_u64::<BigEndian>(round)?;
+        encoded_key.write_u8(
+            kind.to_u8()
+                .ok_or_else(|| format_err!("ToPrimitive failed."))?,
+        )?;
+        encoded_key.extend_from_slice(author.as_ref());
+        Ok(encoded_key)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Round>() + size_of::<u8>() + ADDRESS_LENGTH)?;
+
+        let round_size = size_of::<Round>();
+
+        let round = (&data[..round_size]).read_u64::<BigEndian>()?;
+        let kind = EquivocationKind::from_u8((&data[round_size..]).read_u8()?)
+            .ok_or_else(|| format_err!("FromPrimitive failed."))?;
+        let author = Author::try_from(&data[round_size + size_of::<u8>()..])?;
+        Ok((round, kind, author))
+    }
+}
+
+impl ValueCodec<EquivocationEvidenceSchema> for Vec<u8> {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.clone())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(data.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,14 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use schemadb::schema::assert_encode_decode;
+use types::account_address::AccountAddress;
+
+#[test]
+fn test_equivocation_evidence_schema() {
+    assert_encode_decode::<EquivocationEvidenceSchema>(
+        &(7, EquivocationKind::Proposal, AccountAddress::random()),
+        &vec![1u8, 2u8, 3u8],
+    );
+}
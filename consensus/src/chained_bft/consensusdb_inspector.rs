@@ -0,0 +1,244 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Offline inspection and repair of the ConsensusDB of a validator.
+//!
+//! The inspector reads the `block`, `quorum_certificate` and `single_entry` schemas, prints the
+//! persisted block tree together with the consensus state and the timeout certificates, and runs
+//! the recovery logic executed upon restart in dry-run mode. As a last resort for a corrupted tree
+//! it can prune everything but the committed root, keeping the consensus state untouched so that
+//! the validator cannot vote twice in the same round after the repair.
+
+use crate::chained_bft::{
+    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    consensusdb::ConsensusDB,
+    liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
+    persistent_storage::RecoveryData,
+    safety::safety_rules::ConsensusState,
+};
+use crypto::HashValue;
+use failure::prelude::*;
+use rmp_serde::from_slice;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    path::Path,
+};
+use types::transaction::SignedTransaction;
+
+type Payload = Vec<SignedTransaction>;
+
+/// The content of a ConsensusDB decoded the same way as upon the restart of a validator.
+struct ConsensusData {
+    state: ConsensusState,
+    highest_timeout_certificates: HighestTimeoutCertificates,
+    blocks: Vec<Block<Payload>>,
+    quorum_certs: Vec<QuorumCert>,
+}
+
+/// The blocks and quorum certs deleted by pruning the tree to its committed root.
+#[derive(Debug, Default)]
+pub struct PruneSummary {
+    /// The root the tree is pruned to.
+    pub root_id: HashValue,
+    /// Ids of the deleted blocks.
+    pub pruned_blocks: Vec<HashValue>,
+    /// Certified block ids of the deleted quorum certs.
+    pub pruned_quorum_certs: Vec<HashValue>,
+}
+
+/// Reads (and, if opened for repair, repairs) the ConsensusDB under a storage directory.
+pub struct ConsensusDBInspector {
+    db: ConsensusDB,
+}
+
+impl ConsensusDBInspector {
+    /// Opens the ConsensusDB under `db_root_path` read-only, which is safe while the node is
+    /// running.
+    pub fn open_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        Ok(Self {
+            db: ConsensusDB::open_readonly(db_root_path)?,
+        })
+    }
+
+    /// Opens the ConsensusDB under `db_root_path` for a repair. It fails while the node holding
+    /// the db is running.
+    pub fn open_for_repair<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        ensure!(
+            db_root_path.as_ref().join("consensusdb").is_dir(),
+            "No ConsensusDB found under {:?}",
+            db_root_path.as_ref()
+        );
+        Ok(Self {
+            db: ConsensusDB::open(db_root_path)?,
+        })
+    }
+
+    /// Returns a human readable report of the persisted consensus data and of the recovery run
+    /// against it. `root_from_storage` is the block id of the latest ledger info in LibraDB; when
+    /// unknown, recovery starts from the highest committed block found in the ConsensusDB.
+    pub fn report(&self, root_from_storage: Option<HashValue>) -> Result<String> {
+        let data = self.read_data()?;
+        let mut report = String::new();
+        writeln!(report, "{}", data.state)?;
+        writeln!(
+            report,
+            "HighestTimeoutCertificates: {}",
+            data.highest_timeout_certificates
+        )?;
+        match data
+            .quorum_certs
+            .iter()
+            .max_by_key(|qc| qc.certified_block_round())
+        {
+            Some(qc) => writeln!(report, "Highest {}", qc)?,
+            None => writeln!(report, "Highest QuorumCert: none")?,
+        }
+
+        writeln!(
+            report,
+            "Block tree ({} blocks, {} quorum certs):",
+            data.blocks.len(),
+            data.quorum_certs.len()
+        )?;
+        let certified: HashSet<_> = data
+            .quorum_certs
+            .iter()
+            .map(QuorumCert::certified_block_id)
+            .collect();
+        for (depth, block) in Self::block_tree(&data.blocks) {
+            writeln!(
+                report,
+                "{}{}{}",
+                "  ".repeat(depth + 1),
+                block,
+                if certified.contains(&block.id()) {
+                    " (certified)"
+                } else {
+                    ""
+                }
+            )?;
+        }
+
+        writeln!(report, "Recovery (dry-run):")?;
+        match Self::recover(data, root_from_storage) {
+            Ok(mut recovery_data) => {
+                let need_sync = recovery_data.need_sync();
+                let blocks_to_prune = recovery_data.take_blocks_to_prune();
+                let ((root, root_qc, root_li), blocks, _) = recovery_data.take();
+                writeln!(report, "  root: {}", root)?;
+                writeln!(report, "  root {}", root_qc)?;
+                writeln!(report, "  root ledger info {}", root_li)?;
+                writeln!(report, "  blocks kept: {}", blocks.len())?;
+                writeln!(
+                    report,
+                    "  blocks pruned upon restart: {:?}",
+                    blocks_to_prune
+                )?;
+                writeln!(report, "  state sync needed: {}", need_sync)?;
+            }
+            Err(e) => writeln!(report, "  failed: {}", e)?,
+        }
+        Ok(report)
+    }
+
+    /// Deletes every block but the committed root found by the recovery, together with every
+    /// quorum cert but the ones certifying the root and carrying its ledger info. The consensus
+    /// state and the timeout certificates are kept. With `dry_run` nothing is deleted and the
+    /// returned summary describes what would be.
+    pub fn prune_to_committed_root(
+        &self,
+        root_from_storage: Option<HashValue>,
+        dry_run: bool,
+    ) -> Result<PruneSummary> {
+        let data = self.read_data()?;
+        let blocks: Vec<_> = data.blocks.iter().map(Block::id).collect();
+        let quorum_certs: Vec<_> = data
+            .quorum_certs
+            .iter()
+            .map(QuorumCert::certified_block_id)
+            .collect();
+        let ((root, root_qc, root_li), _, _) = Self::recover(data, root_from_storage)?.take();
+        let summary = PruneSummary {
+            root_id: root.id(),
+            pruned_blocks: blocks.into_iter().filter(|id| *id != root.id()).collect(),
+            pruned_quorum_certs: quorum_certs
+                .into_iter()
+                .filter(|id| {
+                    *id != root_qc.certified_block_id() && *id != root_li.certified_block_id()
+                })
+                .collect(),
+        };
+        if !dry_run
+            && (!summary.pruned_blocks.is_empty() || !summary.pruned_quorum_certs.is_empty())
+        {
+            self.db
+                .delete_blocks_and_quorum_certificates_separately::<Payload>(
+                    summary.pruned_blocks.clone(),
+                    summary.pruned_quorum_certs.clone(),
+                )?;
+        }
+        Ok(summary)
+    }
+
+    fn read_data(&self) -> Result<ConsensusData> {
+        let (state, highest_timeout_certificates, blocks, quorum_certs) =
+            self.db.get_data::<Payload>()?;
+        Ok(ConsensusData {
+            state: match state {
+                Some(s) => from_slice(&s[..])?,
+                None => ConsensusState::default(),
+            },
+            highest_timeout_certificates: match highest_timeout_certificates {
+                Some(s) => from_slice(&s[..])?,
+                None => HighestTimeoutCertificates::default(),
+            },
+            blocks,
+            quorum_certs,
+        })
+    }
+
+    fn recover(
+        data: ConsensusData,
+        root_from_storage: Option<HashValue>,
+    ) -> Result<RecoveryData<Payload>> {
+        ensure!(
+            !data.blocks.is_empty(),
+            "ConsensusDB is empty, it is bootstrapped with genesis upon start"
+        );
+        // A block id that is not in the tree makes the recovery pick the highest committed block
+        // found in the ConsensusDB as the root.
+        RecoveryData::new(
+            data.state,
+            data.blocks,
+            data.quorum_certs,
+            root_from_storage.unwrap_or_else(HashValue::zero),
+            data.highest_timeout_certificates,
+        )
+    }
+
+    /// Orders the blocks depth-first from the blocks whose parent is not persisted, returning
+    /// the depth of every block.
+    fn block_tree(blocks: &[Block<Payload>]) -> Vec<(usize, &Block<Payload>)> {
+        let ids: HashSet<_> = blocks.iter().map(Block::id).collect();
+        let mut children: HashMap<HashValue, Vec<&Block<Payload>>> = HashMap::new();
+        let mut stack = vec![];
+        for block in blocks {
+            if ids.contains(&block.parent_id()) {
+                children.entry(block.parent_id()).or_default().push(block);
+            } else {
+                stack.push((0, block));
+            }
+        }
+        let mut tree = vec![];
+        stack.sort_by_key(|(_, block)| std::cmp::Reverse(block.round()));
+        while let Some((depth, block)) = stack.pop() {
+            tree.push((depth, block));
+            if let Some(children) = children.get_mut(&block.id()) {
+                children.sort_by_key(|child| std::cmp::Reverse(child.round()));
+                stack.extend(children.iter().map(|child| (depth + 1, *child)));
+            }
+        }
+        tree
+    }
+}
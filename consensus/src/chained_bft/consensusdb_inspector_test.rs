@@ -0,0 +1,72 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    consensusdb::ConsensusDB,
+    consensusdb_inspector::ConsensusDBInspector,
+    test_utils::placeholder_certificate_for_block,
+};
+use tempfile::tempdir;
+use types::{transaction::SignedTransaction, validator_signer::ValidatorSigner};
+
+/// Persists genesis <- a1 <- a2, where only genesis is committed.
+fn setup_db(db: &ConsensusDB) -> Vec<Block<Vec<SignedTransaction>>> {
+    let signer = ValidatorSigner::random();
+    let genesis = Block::make_genesis_block();
+    let genesis_qc = QuorumCert::certificate_for_genesis();
+    let a1 = Block::make_block(&genesis, vec![], 1, 1, genesis_qc.clone(), &signer);
+    let a1_qc = placeholder_certificate_for_block(vec![&signer], a1.id(), a1.round());
+    let a2 = Block::make_block(&a1, vec![], 2, 2, a1_qc.clone(), &signer);
+    let blocks = vec![genesis, a1, a2];
+    db.save_blocks_and_quorum_certificates(blocks.clone(), vec![genesis_qc, a1_qc])
+        .unwrap();
+    blocks
+}
+
+#[test]
+fn test_report() {
+    let tmp_dir = tempdir().unwrap();
+    let blocks = setup_db(&ConsensusDB::new(&tmp_dir));
+
+    let report = ConsensusDBInspector::open_readonly(&tmp_dir)
+        .unwrap()
+        .report(None)
+        .unwrap();
+    assert!(report.contains("Block tree (3 blocks, 2 quorum certs)"));
+    for block in &blocks {
+        assert!(report.contains(&block.to_string()));
+    }
+    assert!(report.contains(&format!("root: {}", blocks[0])));
+}
+
+#[test]
+fn test_prune_to_committed_root() {
+    let tmp_dir = tempdir().unwrap();
+    let blocks = setup_db(&ConsensusDB::new(&tmp_dir));
+
+    {
+        let inspector = ConsensusDBInspector::open_for_repair(&tmp_dir).unwrap();
+        let summary = inspector
+            .prune_to_committed_root(None, true /* dry_run */)
+            .unwrap();
+        assert_eq!(summary.root_id, blocks[0].id());
+        assert_eq!(summary.pruned_blocks.len(), 2);
+        assert_eq!(summary.pruned_quorum_certs, vec![blocks[1].id()]);
+        let (_, _, remaining_blocks, _) = ConsensusDB::open_readonly(&tmp_dir)
+            .unwrap()
+            .get_data::<Vec<SignedTransaction>>()
+            .unwrap();
+        assert_eq!(remaining_blocks.len(), 3);
+
+        inspector
+            .prune_to_committed_root(None, false /* dry_run */)
+            .unwrap();
+    }
+
+    let (_, _, remaining_blocks, remaining_qcs) = ConsensusDB::new(&tmp_dir)
+        .get_data::<Vec<SignedTransaction>>()
+        .unwrap();
+    assert_eq!(remaining_blocks, vec![blocks[0].clone()]);
+    assert_eq!(remaining_qcs, vec![QuorumCert::certificate_for_genesis()]);
+}
@@ -7,28 +7,31 @@ use crate::{
     chained_bft::{
         block_storage::{BlockReader, BlockStore, NeedFetchResult, VoteReceptionResult},
         common::{Author, Payload, Round},
-        consensus_types::block::Block,
+        consensus_types::{block::Block, epoch_info::EpochChange, quorum_cert::QuorumCert},
         liveness::{
             pacemaker::{NewRoundEvent, NewRoundReason, Pacemaker},
             proposal_generator::ProposalGenerator,
             proposer_election::{ProposalInfo, ProposerElection, ProposerInfo},
-            timeout_msg::{PacemakerTimeout, TimeoutMsg},
+            timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate, TimeoutMsg},
         },
         network::{
-            BlockRetrievalRequest, BlockRetrievalResponse, ChunkRetrievalRequest,
-            ConsensusNetworkImpl,
+            AccountStateChunkRetrievalRequest, BlockRetrievalRequest, BlockRetrievalResponse,
+            ChunkRetrievalRequest, ConsensusNetworkImpl, EpochChangeRetrievalRequest,
+            FutureEpochNotification, HighestLedgerInfoRetrievalRequest,
         },
         persistent_storage::PersistentStorage,
         safety::{safety_rules::SafetyRules, vote_msg::VoteMsg},
         sync_manager::{SyncInfo, SyncManager},
     },
     counters,
-    state_replication::{StateComputer, TxnManager},
+    state_replication::{ExecutedState, StateComputer, TxnManager},
     util::time_service::{
         duration_since_epoch, wait_if_possible, TimeService, WaitingError, WaitingSuccess,
     },
 };
+use channel;
 use crypto::HashValue;
+use futures::SinkExt;
 use logger::prelude::*;
 use network::proto::BlockRetrievalStatus;
 use std::{
@@ -36,7 +39,6 @@ use std::{
     time::{Duration, Instant},
 };
 use termion::color::*;
-use types::ledger_info::LedgerInfoWithSignatures;
 
 /// Result of initial proposal processing
 /// NeedFetch means separate task mast be spawned for fetching block
@@ -62,6 +64,8 @@ pub struct EventProcessor<T, P> {
     state_computer: Arc<dyn StateComputer<Payload = T>>,
     txn_manager: Arc<dyn TxnManager<Payload = T>>,
     network: ConsensusNetworkImpl,
+    // To notify about the committed validator set changes that end the current epoch.
+    epoch_change_sender: channel::Sender<EpochChange>,
     storage: Arc<dyn PersistentStorage<T>>,
     sync_manager: SyncManager<T>,
     time_service: Arc<dyn TimeService>,
@@ -79,6 +83,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         state_computer: Arc<dyn StateComputer<Payload = T>>,
         txn_manager: Arc<dyn TxnManager<Payload = T>>,
         network: ConsensusNetworkImpl,
+        epoch_change_sender: channel::Sender<EpochChange>,
         storage: Arc<dyn PersistentStorage<T>>,
         time_service: Arc<dyn TimeService>,
         enforce_increasing_timestamps: bool,
@@ -99,6 +104,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             state_computer,
             txn_manager,
             network,
+            epoch_change_sender,
             storage,
             sync_manager,
             time_service,
@@ -130,10 +136,13 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
                 counters::TIMEOUT_ROUNDS_COUNT.inc();
             }
         };
-        let proposer_info = match self
-            .proposer_election
-            .is_valid_proposer(self.author, new_round_event.round)
-        {
+        // The proposal extends the highest certified block.
+        let parent_id = self.block_store.highest_certified_block().id();
+        let proposer_info = match self.proposer_election.is_valid_proposer(
+            self.author,
+            new_round_event.round,
+            parent_id,
+        ) {
             Some(pi) => pi,
             None => {
                 return;
@@ -144,6 +153,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         let proposal = match self
             .proposal_generator
             .generate_proposal(
+                self.network.epoch(),
                 new_round_event.round,
                 self.pacemaker.current_round_deadline(),
             )
@@ -155,6 +165,15 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             }
             Ok(proposal) => proposal,
         };
+        // A higher certified block might have been inserted in the meantime.
+        if proposal.parent_id() != parent_id
+            && self
+                .proposer_election
+                .is_valid_proposer(self.author, proposal.round(), proposal.parent_id())
+                .is_none()
+        {
+            return;
+        }
         let mut network = self.network.clone();
         debug!("Propose {}", proposal);
         let timeout_certificate = match new_round_event.reason {
@@ -200,16 +219,14 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             );
             return ProcessProposalResult::Done;
         }
-        if self
-            .proposer_election
-            .is_valid_proposer(proposal.proposer_info, proposal.proposal.round())
-            .is_none()
+        if let Some(evidence) = self
+            .block_store
+            .check_proposal_equivocation(&proposal.proposal)
         {
-            warn!(
-                "Proposer {} for block {} is not a valid proposer for this round",
-                proposal.proposal.author(),
-                proposal.proposal
-            );
+            security_log(SecurityEvent::EquivocatingConsensusProposal)
+                .error("Equivocation")
+                .data(evidence)
+                .log();
             return ProcessProposalResult::Done;
         }
 
@@ -262,6 +279,24 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
     /// to the proposal election.
     /// This function is invoked when all the dependencies for the given proposal are ready.
     async fn finish_proposal_processing(&self, proposal: ProposalInfo<T, P>) {
+        // The proposer is checked once the parent of the proposal is available, as the choice of
+        // the proposer might depend on the ancestors of the parent.
+        if self
+            .proposer_election
+            .is_valid_proposer(
+                proposal.proposer_info,
+                proposal.proposal.round(),
+                proposal.proposal.parent_id(),
+            )
+            .is_none()
+        {
+            warn!(
+                "Proposer {} for block {} is not a valid proposer for this round",
+                proposal.proposal.author(),
+                proposal.proposal
+            );
+            return;
+        }
         let qc = proposal.proposal.quorum_cert();
         self.pacemaker
             .process_certificates(
@@ -316,6 +351,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         proposal: ProposalInfo<T, P>,
     ) {
         // check if we still need sync
+        let root_id = self.block_store.root().id();
         if let Err(e) = self
             .sync_manager
             .sync_to(
@@ -334,6 +370,13 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             );
             return;
         }
+        if self.block_store.root().id() != root_id
+            && self
+                .process_synced_epoch_change(&proposal.highest_ledger_info)
+                .await
+        {
+            return;
+        }
         self.finish_proposal_processing(proposal).await;
     }
 
@@ -348,6 +391,15 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             timeout_msg.pacemaker_timeout().round(),
             timeout_msg.author().short_str()
         );
+        if timeout_msg.epoch() != self.network.epoch() {
+            // The epoch has changed since the message was received from the network.
+            debug!(
+                "Ignore the new round msg of epoch {} in epoch {}",
+                timeout_msg.epoch(),
+                self.network.epoch()
+            );
+            return;
+        }
         let current_highest_quorum_cert_round = self
             .block_store
             .highest_quorum_cert()
@@ -360,6 +412,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             // The timeout message carries a QC higher than what this node has seen before:
             // run state synchronization.
             let deadline = self.pacemaker.current_round_deadline();
+            let root_id = self.block_store.root().id();
             match self
                 .sync_manager
                 .sync_to(
@@ -383,10 +436,46 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
                         e
                     ),
                 }
+            if self.block_store.root().id() != root_id
+                && self
+                    .process_synced_epoch_change(timeout_msg.highest_ledger_info())
+                    .await
+            {
+                return;
+            }
         }
         self.pacemaker
             .process_remote_timeout(timeout_msg.pacemaker_timeout().clone())
             .await;
+        // The author is still timing out a round that is already certified to have timed out:
+        // forward the certificate to let it jump to the next round.
+        if let Some(timeout_certificate) = self.pacemaker.highest_timeout_certificate() {
+            if timeout_certificate.round() >= timeout_msg.pacemaker_timeout().round() {
+                counters::TIMEOUT_CERTIFICATES_FORWARDED_COUNT.inc();
+                self.network
+                    .send_timeout_certificate(timeout_certificate, vec![timeout_msg.author()])
+                    .await;
+            }
+        }
+    }
+
+    /// Upon receiving a timeout certificate forwarded by another validator, move to the round
+    /// following the certificate (the certificate is persisted by the pacemaker).
+    pub async fn process_timeout_certificate(
+        &self,
+        timeout_certificate: PacemakerTimeoutCertificate,
+    ) {
+        debug!(
+            "Received a timeout certificate for round {}",
+            timeout_certificate.round()
+        );
+        let highest_quorum_cert_round = self
+            .block_store
+            .highest_quorum_cert()
+            .certified_block_round();
+        self.pacemaker
+            .process_certificates(highest_quorum_cert_round, Some(&timeout_certificate))
+            .await;
     }
 
     /// The replica stops voting for this round and saves its consensus state.  Voting is halted
@@ -419,13 +508,14 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             "Round {} timed out and {}, expected round proposer was {:?}, broadcasting new round to all replicas",
             round,
             if last_vote_round == round { "already executed and voted at this round" } else { "will never vote at this round" },
-            self.proposer_election.get_valid_proposers(round),
+            self.proposer_election
+                .get_valid_proposers(round, self.block_store.highest_certified_block().id()),
         );
 
         Some(TimeoutMsg::new(
             self.block_store.highest_quorum_cert().as_ref().clone(),
             self.block_store.highest_ledger_info().as_ref().clone(),
-            PacemakerTimeout::new(round, self.block_store.signer()),
+            PacemakerTimeout::new(self.network.epoch(), round, self.block_store.signer()),
             self.block_store.signer(),
         ))
     }
@@ -439,8 +529,24 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         let qc = proposal.proposal.quorum_cert();
         let update_res = self.safety_rules.write().unwrap().update(qc);
         if let Some(new_commit) = update_res {
-            let finality_proof = qc.ledger_info().clone();
-            self.process_commit(new_commit, finality_proof).await;
+            if self.process_commit(new_commit, qc.clone()).await {
+                // The proposal belongs to the epoch that has just ended: voting resumes in the
+                // new epoch after the round times out.
+                debug!(
+                    "Skip voting for proposal {} as its epoch has ended",
+                    proposal.proposal
+                );
+                return;
+            }
+        }
+        if proposal.proposal.epoch() != self.network.epoch() {
+            debug!(
+                "Skip voting for proposal {} of epoch {} in epoch {}",
+                proposal.proposal,
+                proposal.proposal.epoch(),
+                self.network.epoch()
+            );
+            return;
         }
 
         if let Some(time_to_receival) = duration_since_epoch()
@@ -549,26 +655,57 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             return;
         }
         let proposal_id = vote_info.proposal_id();
-        let executed_state = self
-            .block_store
-            .get_state_for_block(proposal_id)
-            .expect("Block proposal: no execution state found for inserted block.");
+        // With pipelined execution the vote only orders the proposal, while the ledger info
+        // carries the executed state of the block it would commit.
+        let executed_state = if self.block_store.pipelined_execution() {
+            ExecutedState::ordered_placeholder()
+        } else {
+            self.block_store
+                .get_state_for_block(proposal_id)
+                .expect("Block proposal: no execution state found for inserted block.")
+        };
+        if let Some(potential_commit_id) = vote_info.potential_commit_id() {
+            if let Err(e) = self
+                .block_store
+                .wait_for_execution(potential_commit_id)
+                .await
+            {
+                error!(
+                    "Skip voting for {}, the potential commit {} is not executed: {:?}",
+                    block, potential_commit_id, e
+                );
+                return;
+            }
+        }
 
         let ledger_info_placeholder = self
             .block_store
-            .ledger_info_placeholder(vote_info.potential_commit_id());
-        let vote_msg = VoteMsg::new(
-            proposal_id,
+            .ledger_info_placeholder(vote_info.potential_commit_id(), self.network.epoch());
+        let parent = match self.block_store.get_block(block.parent_id()) {
+            Some(parent) => parent,
+            None => {
+                error!("Skip voting for {}, its parent is not found", block);
+                return;
+            }
+        };
+        let vote_msg = match VoteMsg::try_new(
+            block.as_ref(),
+            parent.as_ref(),
             executed_state,
-            block.round(),
             self.author.get_author(),
             ledger_info_placeholder,
             self.block_store.signer(),
-        );
+        ) {
+            Ok(vote_msg) => vote_msg,
+            Err(e) => {
+                error!("Signer refused to vote for {}: {:?}", block, e);
+                return;
+            }
+        };
 
         let recipients: Vec<Author> = self
             .proposer_election
-            .get_valid_proposers(block.round() + 1)
+            .get_valid_proposers(block.round() + 1, block.id())
             .iter()
             .map(ProposerInfo::get_author)
             .collect();
@@ -595,7 +732,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         let next_round = vote.round() + 1;
         if self
             .proposer_election
-            .is_valid_proposer(self.author, next_round)
+            .is_valid_proposer(self.author, next_round, vote.proposed_block_id())
             .is_none()
         {
             debug!(
@@ -610,8 +747,23 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
             return;
         }
 
+        let epoch = self.network.epoch();
+        if vote.ledger_info().epoch_num() != epoch {
+            debug!(
+                "Received {} of epoch {}, but the current epoch is {}, ignore.",
+                vote,
+                vote.ledger_info().epoch_num(),
+                epoch
+            );
+            security_log(SecurityEvent::InvalidConsensusVote)
+                .error("InvalidEpoch")
+                .data(vote)
+                .data(epoch)
+                .log();
+            return;
+        }
+
         let deadline = self.pacemaker.current_round_deadline();
-        // TODO [Reconfiguration] Verify epoch of the vote message.
         // Add the vote and check whether it completes a new QC.
         match self
             .block_store
@@ -625,6 +777,13 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
                     .data(vote)
                     .log();
             }
+            VoteReceptionResult::EquivocateVote(previous_vote) => {
+                security_log(SecurityEvent::EquivocatingConsensusVote)
+                    .error("Equivocation")
+                    .data(previous_vote)
+                    .data(vote)
+                    .log();
+            }
             VoteReceptionResult::NewQuorumCertificate(qc) => {
                 if self.block_store.need_fetch_for_quorum_cert(&qc) == NeedFetchResult::NeedFetch {
                     if let Err(e) = self
@@ -660,15 +819,21 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
     /// 2. After the state is finalized, update the txn manager with the status of the committed
     /// transactions.
     /// 3. Prune the tree.
+    /// 4. If one of the committed blocks changes the validator set, persist the end of the epoch
+    /// (before the commit, so that the new epoch is recovered upon restart) and notify about it.
+    /// Returns true if the current epoch has ended.
     async fn process_commit(
         &self,
         committed_block: Arc<Block<T>>,
-        finality_proof: LedgerInfoWithSignatures,
-    ) {
+        finality_proof: QuorumCert,
+    ) -> bool {
         // Verify that the ledger info is indeed for the block we're planning to
         // commit.
         assert_eq!(
-            finality_proof.ledger_info().consensus_block_id(),
+            finality_proof
+                .ledger_info()
+                .ledger_info()
+                .consensus_block_id(),
             committed_block.id()
         );
 
@@ -677,7 +842,67 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         self.pacemaker
             .update_highest_committed_round(committed_block.round());
 
-        if let Err(e) = self.state_computer.commit(finality_proof).await {
+        // With pipelined execution the commit waits for the execution of the committed block.
+        if let Err(e) = self
+            .block_store
+            .wait_for_execution(committed_block.id())
+            .await
+        {
+            error!(
+                "Failed to execute {}, it is not committed: {:?}",
+                committed_block, e
+            );
+            return false;
+        }
+        if self.block_store.pipelined_execution() {
+            let executed_state = self
+                .block_store
+                .get_state_for_block(committed_block.id())
+                .expect("Executed block has no state");
+            let ledger_info = finality_proof.ledger_info().ledger_info();
+            assert!(
+                executed_state.version == ledger_info.version()
+                    && executed_state.state_id == ledger_info.transaction_accumulator_hash(),
+                "We have inconsistent executed state with the finality proof for block {}, will \
+                 kill this validator and rely on state synchronization to try to achieve \
+                 consistent state with the quorum.",
+                committed_block.id(),
+            );
+        }
+
+        // Multiple blocks might be committed at once: the path from the old root to the new root.
+        let committed_blocks = self
+            .block_store
+            .path_from_root(Arc::clone(&committed_block))
+            .unwrap_or_else(Vec::new);
+        let next_validators = committed_blocks
+            .iter()
+            .filter_map(|committed| {
+                self.block_store
+                    .get_compute_result(committed.id())
+                    .and_then(|compute_result| compute_result.validators.clone())
+            })
+            .last();
+        let epoch_change = match next_validators {
+            Some(validators) if !self.network.epoch_info().has_validators(&validators) => {
+                let epoch_change = EpochChange::new(finality_proof.clone(), validators);
+                if let Err(e) = self.storage.save_epoch_change(epoch_change.clone()) {
+                    error!(
+                        "Failed to persist {}, {} is not committed: {:?}",
+                        epoch_change, committed_block, e
+                    );
+                    return false;
+                }
+                Some(epoch_change)
+            }
+            _ => None,
+        };
+
+        if let Err(e) = self
+            .state_computer
+            .commit(finality_proof.ledger_info().clone())
+            .await
+        {
             // We assume that state computer cannot enter an inconsistent state that might
             // violate safety of the protocol. Specifically, an executor service is going to panic
             // if it fails to persist the commit requests, which would crash the whole process
@@ -686,16 +911,11 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
                 "Failed to persist commit, mempool will not be notified: {:?}",
                 e
             );
-            return;
+            return false;
         }
-        // At this moment the new state is persisted and we can notify the clients.
-        // Multiple blocks might be committed at once: notify about all the transactions in the
-        // path from the old root to the new root.
-        for committed in self
-            .block_store
-            .path_from_root(Arc::clone(&committed_block))
-            .unwrap_or_else(Vec::new)
-        {
+        // At this moment the new state is persisted and we can notify the clients about all the
+        // committed transactions.
+        for committed in committed_blocks {
             if let Some(time_to_commit) = duration_since_epoch()
                 .checked_sub(Duration::from_micros(committed.timestamp_usecs()))
             {
@@ -720,6 +940,162 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         counters::LAST_COMMITTED_ROUND.set(committed_block.round() as i64);
         debug!("{}Committed{} {}", Fg(Blue), Fg(Reset), *committed_block);
         self.block_store.prune_tree(committed_block.id()).await;
+        match epoch_change {
+            Some(epoch_change) => {
+                info!(
+                    "Epoch {} ends with the commit of {}",
+                    self.network.epoch(),
+                    *committed_block
+                );
+                self.notify_epoch_change(epoch_change).await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// After a state synchronization to the given ledger info of the current epoch the committed
+    /// state might have changed the validator set: persist the end of the epoch and notify about
+    /// it.
+    /// Returns true if the current epoch has ended.
+    async fn process_synced_epoch_change(&self, highest_ledger_info: &QuorumCert) -> bool {
+        let epoch_info = self.network.epoch_info();
+        if highest_ledger_info.epoch() != epoch_info.epoch {
+            return false;
+        }
+        let validators = match self.state_computer.get_validator_set().await {
+            Ok(Some(validators)) => validators,
+            Ok(None) => return false,
+            Err(e) => {
+                error!("Failed to read the synchronized validator set: {:?}", e);
+                return false;
+            }
+        };
+        if epoch_info.has_validators(&validators) {
+            return false;
+        }
+        let epoch_change = EpochChange::new(highest_ledger_info.clone(), validators);
+        if let Err(e) = self.storage.save_epoch_change(epoch_change.clone()) {
+            error!("Failed to persist {}: {:?}", epoch_change, e);
+            return false;
+        }
+        info!(
+            "Epoch {} ends with the synchronization to {}",
+            epoch_info.epoch, highest_ledger_info
+        );
+        self.notify_epoch_change(epoch_change).await;
+        true
+    }
+
+    async fn notify_epoch_change(&self, epoch_change: EpochChange) {
+        let mut sender = self.epoch_change_sender.clone();
+        if let Err(e) = sender.send(epoch_change).await {
+            error!("Failed to notify about the end of the epoch: {:?}", e);
+        }
+    }
+
+    /// A peer has sent a message of a later epoch: retrieve from it the quorum certificate that
+    /// ended the current epoch and synchronize to it, the validators of the next epoch are read
+    /// from the synchronized state. The epochs in between (if any) are caught up with one by one
+    /// upon the next messages of the peers.
+    pub async fn process_future_epoch(&mut self, notification: FutureEpochNotification) {
+        let epoch = self.network.epoch();
+        if notification.epoch <= epoch {
+            return;
+        }
+        let deadline = self.pacemaker.current_round_deadline();
+        let timeout = match deadline.checked_duration_since(Instant::now()) {
+            Some(timeout) => timeout,
+            None => return,
+        };
+        let proof = match self
+            .network
+            .request_epoch_change(epoch, notification.peer, timeout)
+            .await
+        {
+            Ok(proof) => proof,
+            Err(e) => {
+                warn!(
+                    "Failed to retrieve the end of epoch {} from {}: {:?}",
+                    epoch,
+                    notification.peer.short_str(),
+                    e
+                );
+                return;
+            }
+        };
+        if let Err(e) = self
+            .sync_manager
+            .sync_to(
+                deadline,
+                SyncInfo {
+                    highest_ledger_info: proof.clone(),
+                    highest_quorum_cert: proof.clone(),
+                    peer: notification.peer,
+                },
+            )
+            .await
+        {
+            warn!(
+                "Failed to synchronize to the end of epoch {}: {:?}",
+                epoch, e
+            );
+            return;
+        }
+        // The blocks up to the end of the epoch might have been retrieved without a state
+        // synchronization: commit them.
+        let update_res = self
+            .safety_rules
+            .write()
+            .unwrap()
+            .process_ledger_info(proof.ledger_info());
+        if let Some(block) = update_res {
+            if self.process_commit(block, proof.clone()).await {
+                return;
+            }
+        }
+        self.process_synced_epoch_change(&proof).await;
+    }
+
+    /// Send back the quorum certificate that ended the requested epoch.
+    pub fn process_epoch_change_retrieval(&self, request: EpochChangeRetrievalRequest) {
+        let response = self.storage.get_epoch_changes().and_then(|epoch_changes| {
+            epoch_changes
+                .into_iter()
+                .find(|epoch_change| epoch_change.epoch() == request.epoch + 1)
+                .map(|epoch_change| epoch_change.proof().clone())
+                .ok_or_else(|| format_err!("The end of epoch {} is unknown", request.epoch))
+        });
+        if let Err(e) = request.response_sender.send(response) {
+            error!("Failed to return the end of the epoch: {:?}", e);
+        }
+    }
+
+    /// Starts the new epoch: the network switches to the new validator set and trusted peers,
+    /// the pacemaker forms timeout certificates with the new quorum size and the proposers are
+    /// elected among the new validators.
+    pub async fn start_new_epoch(
+        &mut self,
+        epoch_change: EpochChange,
+        proposer_election: Arc<dyn ProposerElection<T, P> + Send + Sync>,
+    ) {
+        let epoch_info = self.network.epoch_info().next(&epoch_change);
+        info!(
+            "Starting epoch {} with {} validators",
+            epoch_info.epoch,
+            epoch_info.validator.len()
+        );
+        self.pacemaker
+            .start_new_epoch(epoch_info.validator.quorum_size());
+        self.network.start_new_epoch(epoch_info);
+        if let Err(e) = self
+            .network
+            .update_eligible_nodes(epoch_change.validators().payload().to_vec())
+            .await
+        {
+            error!("Failed to update the trusted peers: {:?}", e);
+        }
+        self.proposer_election = proposer_election;
     }
 
     /// Retrieve a n chained blocks from the block store starting from
@@ -784,8 +1160,7 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
                 .process_ledger_info(&request.target.ledger_info());
 
             if let Some(block) = update_res {
-                self.process_commit(block, request.target.ledger_info().clone())
-                    .await;
+                self.process_commit(block, request.target.clone()).await;
             }
         }
 
@@ -801,6 +1176,37 @@ impl<T: Payload, P: ProposerInfo> EventProcessor<T, P> {
         }
     }
 
+    /// Retrieve the chunk of account states at the target version from storage and send it back.
+    pub async fn process_account_state_chunk_retrieval(
+        &self,
+        request: AccountStateChunkRetrievalRequest,
+    ) {
+        let version = request.target.ledger_info().ledger_info().version();
+
+        let response = self
+            .sync_manager
+            .get_account_state_chunk(version, request.start_key, request.batch_size)
+            .await;
+
+        if let Err(e) = request.response_sender.send(response) {
+            error!(
+                "Failed to return the requested account state chunk: {:?}",
+                e
+            );
+        }
+    }
+
+    /// Send back the highest committed ledger info, which full nodes follow.
+    pub fn process_highest_ledger_info_retrieval(
+        &self,
+        request: HighestLedgerInfoRetrievalRequest,
+    ) {
+        let highest_ledger_info = self.block_store.highest_ledger_info().as_ref().clone();
+        if let Err(e) = request.response_sender.send(highest_ledger_info) {
+            error!("Failed to return the highest ledger info: {:?}", e);
+        }
+    }
+
     /// Inspect the current consensus state.
     #[cfg(test)]
     pub fn consensus_state(&self) -> ConsensusState {
@@ -87,10 +87,11 @@ impl NodeSetup {
         Arc::new(block_on(BlockStore::new(
             storage,
             initial_data,
-            signer,
+            Arc::new(signer),
             Arc::new(MockStateComputer::new(commit_cb_sender)),
             true,
-            10, // max pruned blocks in mem
+            10,    // max pruned blocks in mem
+            false, // pipelined execution
         )))
     }
 
@@ -203,6 +204,7 @@ impl NodeSetup {
             Arc::new(MockTransactionManager::new()),
             time_service.clone(),
             1,
+            0,
             true,
         );
         let safety_rules = Arc::new(RwLock::new(SafetyRules::new(
@@ -216,6 +218,7 @@ impl NodeSetup {
         let (proposer_election, winning_proposals_receiver) =
             Self::create_proposer_election(proposer_author);
         let (commit_cb_sender, commit_cb_receiver) = mpsc::unbounded::<LedgerInfoWithSignatures>();
+        let (epoch_change_sender, _) = channel::new_test(8);
         let event_processor = EventProcessor::new(
             author,
             Arc::clone(&block_store),
@@ -226,6 +229,7 @@ impl NodeSetup {
             Arc::new(MockStateComputer::new(commit_cb_sender)),
             Arc::new(MockTransactionManager::new()),
             network,
+            epoch_change_sender,
             storage.clone(),
             time_service,
             true,
@@ -526,7 +530,7 @@ fn process_new_round_msg_test() {
     let genesis = non_proposer.block_store.root();
     let block_0 = non_proposer
         .block_store
-        .create_block(genesis, vec![1], 1, 1);
+        .create_block(genesis, vec![1], 0, 1, 1);
     let block_0_id = block_0.id();
     block_on(
         non_proposer
@@ -543,7 +547,7 @@ fn process_new_round_msg_test() {
 
     // Populate block_0 and a quorum certificate for block_0 on non_proposer
     let block_0_quorum_cert = placeholder_certificate_for_block(
-        vec![static_proposer.signer.clone(), non_proposer.signer.clone()],
+        vec![&static_proposer.signer, &non_proposer.signer],
         block_0_id,
         1,
     );
@@ -576,7 +580,7 @@ fn process_new_round_msg_test() {
             .process_timeout_msg(TimeoutMsg::new(
                 block_0_quorum_cert,
                 QuorumCert::certificate_for_genesis(),
-                PacemakerTimeout::new(2, &non_proposer.signer),
+                PacemakerTimeout::new(0, 2, &non_proposer.signer),
                 &non_proposer.signer,
             )),
     );
@@ -676,7 +680,7 @@ fn process_timeout_certificate_test() {
         node.block_store.signer(),
     );
     let block_skip_round_id = block_skip_round.id();
-    let tc = PacemakerTimeoutCertificate::new(1, vec![PacemakerTimeout::new(1, &node.signer)]);
+    let tc = PacemakerTimeoutCertificate::new(1, vec![PacemakerTimeout::new(0, 1, &node.signer)]);
     block_on(async move {
         node.event_processor
             .process_proposal(ProposalInfo::<TestPayload, Author> {
@@ -0,0 +1,198 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::{
+    block_storage::BlockReader,
+    common::{Author, Payload, Round},
+    liveness::proposer_election::{ProposalInfo, ProposerElection, ProposerInfo},
+};
+use channel;
+use crypto::HashValue;
+use futures::{Future, FutureExt, SinkExt};
+use logger::prelude::*;
+use std::{cmp, collections::HashSet, pin::Pin, sync::Arc};
+
+/// The participants of a committed block.
+struct CommittedBlockInfo {
+    proposer: Author,
+    // Authors of the votes aggregated in the quorum certificate carried by the block
+    voters: Vec<Author>,
+}
+
+/// Leader reputation chooses the proposers deterministically from the recent history of the
+/// committed blocks, so that an unresponsive validator does not cost a round timeout every time
+/// its turn comes up:
+/// * The validators that did not vote for any of the latest `voter_window` committed blocks
+/// (nor proposed any of them) are skipped.
+/// * The validators that proposed one of the latest `proposer_window` committed blocks get twice
+/// as many turns as the other validators.
+/// Until `voter_window` blocks are committed nobody is skipped.
+///
+/// The history is read from the chain the proposal extends: the committed ancestors of its
+/// parent, starting from the latest block committed by a quorum certificate of that chain. All
+/// the replicas hence agree on the proposer of a given round and parent, regardless of what they
+/// have committed locally.
+pub struct LeaderReputation<T, P> {
+    // Ordering of proposers to rotate through (all honest replicas must agree on this)
+    proposers: Vec<P>,
+    // Number of contiguous rounds (i.e. round numbers increase by 1) a proposer is active
+    // in a row
+    contiguous_rounds: u32,
+    proposer_window: usize,
+    voter_window: usize,
+    // The source of the ancestors of the proposals
+    block_reader: Arc<dyn BlockReader<Payload = T>>,
+    // Output stream to send the chosen proposals
+    winning_proposals_sender: channel::Sender<ProposalInfo<T, P>>,
+}
+
+impl<T: Payload, P: ProposerInfo> LeaderReputation<T, P> {
+    pub fn new(
+        proposers: Vec<P>,
+        contiguous_rounds: u32,
+        proposer_window: usize,
+        voter_window: usize,
+        block_reader: Arc<dyn BlockReader<Payload = T>>,
+        winning_proposals_sender: channel::Sender<ProposalInfo<T, P>>,
+    ) -> Self {
+        assert!(!proposers.is_empty());
+        Self {
+            proposers,
+            contiguous_rounds,
+            proposer_window,
+            voter_window,
+            block_reader,
+            winning_proposals_sender,
+        }
+    }
+
+    /// The latest committed blocks of the chain ending with `parent_id`, from the newest one.
+    /// Returns None if some of the blocks are not available locally (e.g., they have been
+    /// pruned from memory or the replica has restarted since they were committed).
+    fn committed_history(&self, parent_id: HashValue) -> Option<Vec<CommittedBlockInfo>> {
+        // The latest committed block of the chain is the one committed by the latest quorum
+        // certificate carried by the chain that commits a block.
+        let mut block = self.block_reader.get_block(parent_id)?;
+        let mut committed_id = loop {
+            if block.is_genesis_block() {
+                return Some(vec![]);
+            }
+            if let Some(committed_id) = block.quorum_cert().committed_block_id() {
+                break committed_id;
+            }
+            block = self.block_reader.get_block(block.parent_id())?;
+        };
+        let length = cmp::max(self.proposer_window, self.voter_window);
+        let mut history = vec![];
+        while history.len() < length {
+            let block = self.block_reader.get_block(committed_id)?;
+            if block.is_genesis_block() {
+                break;
+            }
+            history.push(CommittedBlockInfo {
+                proposer: block.author(),
+                voters: block
+                    .quorum_cert()
+                    .ledger_info()
+                    .signatures()
+                    .keys()
+                    .cloned()
+                    .collect(),
+            });
+            committed_id = block.parent_id();
+        }
+        Some(history)
+    }
+
+    /// The weight of each proposer (the number of its turns in a full rotation).
+    fn get_weights(&self, parent_id: HashValue) -> Vec<u64> {
+        let recent = match self.committed_history(parent_id) {
+            Some(recent) => recent,
+            None => {
+                debug!(
+                    "The committed history of {} is not available, rotating the proposers",
+                    parent_id
+                );
+                return vec![1; self.proposers.len()];
+            }
+        };
+        let successful_proposers: HashSet<Author> = recent
+            .iter()
+            .take(self.proposer_window)
+            .map(|info| info.proposer)
+            .collect();
+        let enough_history = recent.len() >= self.voter_window;
+        let mut active_validators: HashSet<Author> = recent
+            .iter()
+            .take(self.voter_window)
+            .flat_map(|info| info.voters.iter().cloned())
+            .collect();
+        active_validators.extend(successful_proposers.iter().cloned());
+
+        let weights: Vec<u64> = self
+            .proposers
+            .iter()
+            .map(|proposer| {
+                let author = proposer.get_author();
+                if successful_proposers.contains(&author) {
+                    2
+                } else if !enough_history || active_validators.contains(&author) {
+                    1
+                } else {
+                    0
+                }
+            })
+            .collect();
+        if weights.iter().all(|weight| *weight == 0) {
+            // Nobody is known to be alive: fall back to the plain rotation.
+            return vec![1; self.proposers.len()];
+        }
+        weights
+    }
+
+    fn get_proposer(&self, round: Round, parent_id: HashValue) -> P {
+        let weights = self.get_weights(parent_id);
+        let total_weight: u64 = weights.iter().sum();
+        let mut slot = (round / u64::from(self.contiguous_rounds)) % total_weight;
+        for (proposer, weight) in self.proposers.iter().zip(weights) {
+            if slot < weight {
+                return *proposer;
+            }
+            slot -= weight;
+        }
+        unreachable!("The slot is smaller than the total weight of the proposers");
+    }
+}
+
+impl<T: Payload, P: ProposerInfo> ProposerElection<T, P> for LeaderReputation<T, P> {
+    fn is_valid_proposer(&self, author: P, round: Round, parent_id: HashValue) -> Option<P> {
+        if self.get_proposer(round, parent_id).get_author() == author.get_author() {
+            Some(author)
+        } else {
+            None
+        }
+    }
+
+    fn get_valid_proposers(&self, round: Round, parent_id: HashValue) -> Vec<P> {
+        vec![self.get_proposer(round, parent_id)]
+    }
+
+    fn process_proposal(
+        &self,
+        proposal: ProposalInfo<T, P>,
+    ) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let round_author = self
+            .get_proposer(proposal.proposal.round(), proposal.proposal.parent_id())
+            .get_author();
+        if round_author != proposal.proposer_info.get_author() {
+            return async {}.boxed();
+        }
+        let mut sender = self.winning_proposals_sender.clone();
+        async move {
+            if let Err(e) = sender.send(proposal).await {
+                debug!("Error in sending the winning proposal: {:?}", e);
+            }
+        }
+            .boxed()
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::{BlockReader, BlockStore},
+        common::Author,
+        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        liveness::{
+            leader_reputation::LeaderReputation,
+            proposer_election::{ProposalInfo, ProposerElection},
+        },
+        safety::vote_msg::VoteMsg,
+        test_utils::{build_empty_tree_with_custom_signing, TestPayload},
+    },
+    state_replication::ExecutedState,
+};
+use channel;
+use crypto::{hash::CryptoHash, HashValue};
+use futures::{executor::block_on, StreamExt};
+use std::{collections::HashMap, sync::Arc};
+use types::{
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+};
+
+/// A quorum certificate for `block` signed by `signers`, whose ledger info commits
+/// `committed_block_id` (if any).
+fn certificate_for_block(
+    signers: &[&ValidatorSigner],
+    block: &Block<TestPayload>,
+    committed_block_id: Option<HashValue>,
+) -> QuorumCert {
+    let state = ExecutedState::state_for_genesis();
+    let ledger_info = LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        VoteMsg::vote_digest(block.id(), state, block.round()),
+        committed_block_id.unwrap_or_else(HashValue::zero),
+        0,
+        0,
+    );
+    let signatures: HashMap<_, _> = signers
+        .iter()
+        .map(|signer| {
+            (
+                signer.author(),
+                signer.sign_message(ledger_info.hash()).unwrap(),
+            )
+        })
+        .collect();
+    QuorumCert::new(
+        block.id(),
+        state,
+        block.round(),
+        LedgerInfoWithSignatures::new(ledger_info, signatures),
+    )
+}
+
+fn insert_block(
+    block_store: &BlockStore<TestPayload>,
+    parent: &Block<TestPayload>,
+    round: u64,
+    quorum_cert: QuorumCert,
+    author: &ValidatorSigner,
+) -> Arc<Block<TestPayload>> {
+    block_on(block_store.insert_block_with_qc(Block::make_block(
+        parent,
+        vec![round as usize],
+        round,
+        round,
+        quorum_cert,
+        author,
+    )))
+    .unwrap()
+}
+
+#[test]
+fn test_leader_reputation() {
+    let signers: Vec<_> = (0..3).map(|_| ValidatorSigner::random()).collect();
+    let authors: Vec<Author> = signers.iter().map(ValidatorSigner::author).collect();
+    let block_store = build_empty_tree_with_custom_signing(signers[0].clone());
+    let (winning_proposals_sender, mut winning_proposals_receiver) = channel::new_test(1_024);
+    // Proposer window of 1 block and voter window of 2 blocks
+    let pe = LeaderReputation::<TestPayload, Author>::new(
+        authors.clone(),
+        1,
+        1,
+        2,
+        Arc::clone(&block_store),
+        winning_proposals_sender,
+    );
+
+    // The first validator proposes the blocks of rounds 1 and 2, the third validator does not
+    // vote for any block. The block of round 4 carries the certificate committing the block of
+    // round 1, the block of round 5 carries the one committing the block of round 2.
+    let voters = [&signers[0], &signers[1]];
+    let genesis = block_store.root();
+    let b1 = insert_block(
+        &block_store,
+        &genesis,
+        1,
+        QuorumCert::certificate_for_genesis(),
+        &signers[0],
+    );
+    let b2 = insert_block(
+        &block_store,
+        &b1,
+        2,
+        certificate_for_block(&voters, &b1, None),
+        &signers[0],
+    );
+    let b3 = insert_block(
+        &block_store,
+        &b2,
+        3,
+        certificate_for_block(&voters, &b2, None),
+        &signers[1],
+    );
+    let b4 = insert_block(
+        &block_store,
+        &b3,
+        4,
+        certificate_for_block(&voters, &b3, Some(b1.id())),
+        &signers[1],
+    );
+    let b5 = insert_block(
+        &block_store,
+        &b4,
+        5,
+        certificate_for_block(&voters, &b4, Some(b2.id())),
+        &signers[1],
+    );
+
+    // Without any committed history the proposers simply rotate, which is also the case when the
+    // parent is unknown.
+    for round in 2..5 {
+        let expected = vec![authors[round as usize % 3]];
+        assert_eq!(pe.get_valid_proposers(round, b1.id()), expected);
+        assert_eq!(pe.get_valid_proposers(round, b3.id()), expected);
+        assert_eq!(pe.get_valid_proposers(round, HashValue::random()), expected);
+    }
+
+    // Extending the block of round 4 only takes the block of round 1 into account: that is not
+    // enough history to skip anybody, the first validator gets two turns out of four.
+    for round in 5..9 {
+        let expected = match round % 4 {
+            0 | 1 => authors[0],
+            2 => authors[1],
+            _ => authors[2],
+        };
+        assert_eq!(pe.get_valid_proposers(round, b4.id()), vec![expected]);
+    }
+
+    // Extending the block of round 5 the third validator is skipped, even though nothing is
+    // committed locally.
+    for round in 6..20 {
+        let proposer = pe.get_valid_proposers(round, b5.id())[0];
+        let expected = if round % 3 == 2 {
+            authors[1]
+        } else {
+            authors[0]
+        };
+        assert_eq!(proposer, expected);
+        assert_eq!(pe.is_valid_proposer(authors[2], round, b5.id()), None);
+    }
+
+    // Only the proposals of the chosen proposer win.
+    let b5_qc = certificate_for_block(&voters, &b5, Some(b3.id()));
+    let bad_proposal = ProposalInfo {
+        proposal: Block::make_block(b5.as_ref(), vec![], 8, 8, b5_qc.clone(), &signers[2]),
+        proposer_info: authors[2],
+        timeout_certificate: None,
+        highest_ledger_info: b5_qc.clone(),
+    };
+    let good_proposal = ProposalInfo {
+        proposal: Block::make_block(b5.as_ref(), vec![], 8, 8, b5_qc.clone(), &signers[1]),
+        proposer_info: authors[1],
+        timeout_certificate: None,
+        highest_ledger_info: b5_qc.clone(),
+    };
+    block_on(async move {
+        pe.process_proposal(bad_proposal).await;
+        pe.process_proposal(good_proposal.clone()).await;
+        assert_eq!(
+            winning_proposals_receiver.next().await.unwrap(),
+            good_proposal
+        );
+    });
+}
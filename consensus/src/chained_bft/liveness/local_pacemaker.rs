@@ -19,8 +19,9 @@ use futures::{Future, FutureExt, SinkExt, StreamExt, TryFutureExt};
 use logger::prelude::*;
 use std::{
     cmp::{self, max},
+    collections::VecDeque,
     pin::Pin,
-    sync::{Arc, RwLock},
+    sync::{Arc, Mutex, RwLock},
     time::{Duration, Instant},
 };
 use termion::color::*;
@@ -39,6 +40,10 @@ pub trait PacemakerTimeInterval: Send + Sync + 'static {
     /// to calculate the round duration of round 6 and the highest committed round is 3 (meaning
     /// the highest round to commit a block is round 5, then the round index is 0.
     fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration;
+
+    /// Observe the latency of a round that gathered a quorum certificate: the time between
+    /// entering the round and certifying it.
+    fn observe_round_latency(&self, _latency: Duration) {}
 }
 
 /// Round durations increase exponentially
@@ -90,6 +95,93 @@ impl PacemakerTimeInterval for ExponentialTimeInterval {
     }
 }
 
+/// Number of the latest certified rounds whose latencies are taken into account by
+/// `AdaptiveTimeInterval`.
+const ROUND_LATENCY_WINDOW: usize = 100;
+
+/// Round durations adapt to the observed round latencies and increase exponentially after
+/// the committed round.
+/// The base interval is `latency_multiplier` times the given percentile of the latencies of the
+/// latest certified rounds (or the initial interval before any round is certified); the
+/// interval is base * mul^power bounded by [min, max], where power=min(rounds_since_qc,
+/// max_exponent).
+pub struct AdaptiveTimeInterval {
+    initial: Duration,
+    min: Duration,
+    max: Duration,
+    // Percentile (1 to 100) of the observed latencies
+    percentile: u8,
+    // The base interval leaves room for the latency variance above the percentile
+    latency_multiplier: f64,
+    exponent_base: f64,
+    max_exponent: usize,
+    latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl AdaptiveTimeInterval {
+    pub fn new(
+        initial: Duration,
+        min: Duration,
+        max: Duration,
+        percentile: u8,
+        latency_multiplier: f64,
+        exponent_base: f64,
+        max_exponent: usize,
+    ) -> Self {
+        assert!(min <= max, "min timeout should not exceed max timeout");
+        assert!(
+            percentile > 0 && percentile <= 100,
+            "latency percentile should be within 1 and 100"
+        );
+        assert!(
+            max_exponent < 32,
+            "max_exponent for PacemakerTimeInterval should be <32"
+        );
+        AdaptiveTimeInterval {
+            initial,
+            min,
+            max,
+            percentile,
+            latency_multiplier,
+            exponent_base,
+            max_exponent,
+            latencies: Mutex::new(VecDeque::with_capacity(ROUND_LATENCY_WINDOW)),
+        }
+    }
+
+    /// The base interval derived from the observed latencies.
+    fn base_interval(&self) -> Duration {
+        let mut latencies: Vec<Duration> = self.latencies.lock().unwrap().iter().cloned().collect();
+        if latencies.is_empty() {
+            return self.initial.max(self.min).min(self.max);
+        }
+        latencies.sort();
+        // Nearest-rank percentile
+        let rank = (latencies.len() * self.percentile as usize + 99) / 100;
+        let latency = latencies[rank.max(1) - 1];
+        let base_ms = (latency.as_millis() as f64 * self.latency_multiplier).ceil() as u64;
+        Duration::from_millis(base_ms).max(self.min).min(self.max)
+    }
+}
+
+impl PacemakerTimeInterval for AdaptiveTimeInterval {
+    fn get_round_duration(&self, round_index_after_committed_qc: usize) -> Duration {
+        let pow = round_index_after_committed_qc.min(self.max_exponent) as u32;
+        let base_multiplier = self.exponent_base.powf(f64::from(pow));
+        let duration_ms = ((self.base_interval().as_millis() as f64) * base_multiplier).ceil();
+        Duration::from_millis(duration_ms.min(self.max.as_millis() as f64) as u64)
+    }
+
+    fn observe_round_latency(&self, latency: Duration) {
+        counters::ROUND_LATENCY_MS.observe(latency.as_millis() as f64);
+        let mut latencies = self.latencies.lock().unwrap();
+        if latencies.len() == ROUND_LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(latency);
+    }
+}
+
 /// `LocalPacemakerInner` is a Pacemaker implementation that relies on increasing local timeouts
 /// in order to eventually come up with the timeout that is large enough to guarantee overlap of the
 /// "current round" of multiple participants.
@@ -122,6 +214,8 @@ struct LocalPacemakerInner {
     current_round: Round,
     // Approximate deadline when current round ends
     current_round_deadline: Instant,
+    // Time service timestamp of entering the current round
+    current_round_start: Duration,
     // Service for timer
     time_service: Arc<dyn TimeService>,
     // To send new round events.
@@ -173,6 +267,7 @@ impl LocalPacemakerInner {
             highest_qc_round,
             current_round,
             current_round_deadline: Instant::now(),
+            current_round_start: time_service.get_current_timestamp(),
             time_service,
             new_round_events_sender,
             local_timeout_sender,
@@ -310,7 +405,20 @@ impl LocalPacemakerInner {
             self.current_round,
             new_round
         );
+        let now = self.time_service.get_current_timestamp();
+        // Only the rounds certified while being the current round tell the round latency.
+        let current_round_certified = match best_reason {
+            NewRoundReason::QCReady => best_round == self.current_round,
+            NewRoundReason::Timeout { .. } => false,
+        };
+        if current_round_certified {
+            self.time_interval.observe_round_latency(
+                now.checked_sub(self.current_round_start)
+                    .unwrap_or_default(),
+            );
+        }
         self.current_round = new_round;
+        self.current_round_start = now;
         self.create_new_round_task(best_reason).boxed()
     }
 
@@ -346,6 +454,34 @@ impl LocalPacemaker {
         pacemaker_timeout_quorum_size: usize,
         highest_timeout_certificates: HighestTimeoutCertificates,
     ) -> Self {
+        let (pacemaker, timeout_processing_loop) = Self::new_without_executor(
+            persistent_liveness_storage,
+            time_interval,
+            highest_committed_round,
+            highest_qc_round,
+            time_service,
+            new_round_events_sender,
+            external_timeout_sender,
+            pacemaker_timeout_quorum_size,
+            highest_timeout_certificates,
+        );
+        executor.spawn(timeout_processing_loop.unit_error().compat());
+        pacemaker
+    }
+
+    /// Same as `new`, but instead of spawning the loop processing the local timeouts returns it
+    /// to the caller, which is responsible for driving it.
+    pub fn new_without_executor(
+        persistent_liveness_storage: Box<dyn PersistentLivenessStorage>,
+        time_interval: Box<dyn PacemakerTimeInterval>,
+        highest_committed_round: Round,
+        highest_qc_round: Round,
+        time_service: Arc<dyn TimeService>,
+        new_round_events_sender: channel::Sender<NewRoundEvent>,
+        external_timeout_sender: channel::Sender<Round>,
+        pacemaker_timeout_quorum_size: usize,
+        highest_timeout_certificates: HighestTimeoutCertificates,
+    ) -> (Self, Pin<Box<dyn Future<Output = ()> + Send>>) {
         let (local_timeouts_sender, mut local_timeouts_receiver) =
             channel::new(1_024, &counters::PENDING_PACEMAKER_TIMEOUTS);
         let inner = Arc::new(RwLock::new(LocalPacemakerInner::new(
@@ -375,9 +511,8 @@ impl LocalPacemaker {
                 Self::process_local_timeout(Arc::clone(&inner_ref), round).await;
             }
         };
-        executor.spawn(timeout_processing_loop.boxed().unit_error().compat());
 
-        Self { inner }
+        (Self { inner }, timeout_processing_loop.boxed())
     }
 
     async fn process_local_timeout(inner: Arc<RwLock<LocalPacemakerInner>>, round: Round) {
@@ -434,4 +569,22 @@ impl Pacemaker for LocalPacemaker {
             guard.highest_committed_round = highest_committed_round;
         }
     }
+
+    fn highest_timeout_certificate(&self) -> Option<PacemakerTimeoutCertificate> {
+        self.inner
+            .read()
+            .unwrap()
+            .pacemaker_timeout_manager
+            .highest_timeout_certificate()
+            .cloned()
+    }
+
+    fn start_new_epoch(&self, timeout_quorum_size: usize) {
+        assert!(timeout_quorum_size > 0);
+        self.inner
+            .write()
+            .unwrap()
+            .pacemaker_timeout_manager
+            .start_new_epoch(timeout_quorum_size);
+    }
 }
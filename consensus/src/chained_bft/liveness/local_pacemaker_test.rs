@@ -4,10 +4,13 @@
 use crate::{
     chained_bft::{
         liveness::{
-            local_pacemaker::{ExponentialTimeInterval, LocalPacemaker, PacemakerTimeInterval},
+            local_pacemaker::{
+                AdaptiveTimeInterval, ExponentialTimeInterval, LocalPacemaker,
+                PacemakerTimeInterval,
+            },
             pacemaker::{NewRoundEvent, NewRoundReason, Pacemaker},
             pacemaker_timeout_manager::HighestTimeoutCertificates,
-            timeout_msg::PacemakerTimeout,
+            timeout_msg::{PacemakerTimeout, PacemakerTimeoutCertificate},
         },
         persistent_storage::PersistentStorage,
         test_utils::{consensus_runtime, MockStorage, TestPayload},
@@ -33,6 +36,43 @@ fn test_pacemaker_time_interval() {
     assert_eq!(6750, interval.get_round_duration(1000).as_millis());
 }
 
+#[test]
+fn test_adaptive_time_interval() {
+    let interval = AdaptiveTimeInterval::new(
+        Duration::from_millis(1000),
+        Duration::from_millis(100),
+        Duration::from_millis(5000),
+        90,
+        2.0,
+        1.5,
+        6,
+    );
+    // No latency observed yet: start from the initial interval
+    assert_eq!(1000, interval.get_round_duration(0).as_millis());
+    assert_eq!(1500, interval.get_round_duration(1).as_millis());
+
+    // 90th percentile of 10..=100ms is 90ms, doubled to 180ms
+    for latency_ms in 1..=10 {
+        interval.observe_round_latency(Duration::from_millis(latency_ms * 10));
+    }
+    assert_eq!(180, interval.get_round_duration(0).as_millis());
+    assert_eq!(270, interval.get_round_duration(1).as_millis());
+
+    // Fast rounds are bounded by the min timeout
+    for _ in 0..100 {
+        interval.observe_round_latency(Duration::from_millis(1));
+    }
+    assert_eq!(100, interval.get_round_duration(0).as_millis());
+
+    // Slow rounds and the exponential growth are bounded by the max timeout
+    for _ in 0..100 {
+        interval.observe_round_latency(Duration::from_millis(2000));
+    }
+    assert_eq!(4000, interval.get_round_duration(0).as_millis());
+    assert_eq!(5000, interval.get_round_duration(1).as_millis());
+    assert_eq!(5000, interval.get_round_duration(1000).as_millis());
+}
+
 #[test]
 /// Verify that LocalPacemaker properly outputs PacemakerTimeoutMsg upon timeout
 fn test_basic_timeout() {
@@ -87,7 +127,7 @@ fn test_timeout_certificate() {
         // accumulated into single timeout certificate
         for round in 1..rounds {
             let signer = &signers[round - 1];
-            let pacemaker_timeout = PacemakerTimeout::new(round as u64, signer);
+            let pacemaker_timeout = PacemakerTimeout::new(0, round as u64, signer);
             pm.process_remote_timeout(pacemaker_timeout).await;
         }
         // Then timeout quorum for previous round (1,2,3) generates new round event for round 2
@@ -97,6 +137,58 @@ fn test_timeout_certificate() {
     });
 }
 
+#[test]
+/// Verify that a lagging LocalPacemaker jumps to the round after a forwarded timeout certificate
+/// and persists it
+fn test_forwarded_timeout_certificate() {
+    let runtime = consensus_runtime();
+    let (storage, _) = MockStorage::<TestPayload>::start_for_testing();
+    let time_interval = Box::new(ExponentialTimeInterval::fixed(Duration::from_millis(2)));
+    let (new_round_events_sender, mut new_round_events_receiver) = channel::new_test(1_024);
+    let (pacemaker_timeout_tx, _) = channel::new_test(1_024);
+    let pm = LocalPacemaker::new(
+        runtime.executor(),
+        storage.persistent_liveness_storage(),
+        time_interval,
+        0,
+        0,
+        Arc::new(SimulatedTimeService::new()),
+        new_round_events_sender,
+        pacemaker_timeout_tx,
+        3,
+        HighestTimeoutCertificates::new(None, None),
+    );
+    let timeouts = (0..3)
+        .map(|_| PacemakerTimeout::new(0, 5, &ValidatorSigner::random()))
+        .collect();
+    let tc = PacemakerTimeoutCertificate::new(5, timeouts);
+
+    block_on(async move {
+        expect_qc(1, &mut new_round_events_receiver).await;
+
+        pm.process_certificates(0, Some(&tc)).await;
+        expect_timeout(6, &mut new_round_events_receiver).await;
+        assert_eq!(Some(tc.clone()), pm.highest_timeout_certificate());
+        assert_eq!(
+            Some(&tc),
+            storage
+                .shared_storage
+                .highest_timeout_certificates
+                .lock()
+                .unwrap()
+                .highest_timeout_certificate()
+        );
+
+        // An older certificate does not move the round back
+        let timeouts = (0..3)
+            .map(|_| PacemakerTimeout::new(0, 3, &ValidatorSigner::random()))
+            .collect();
+        pm.process_certificates(0, Some(&PacemakerTimeoutCertificate::new(3, timeouts)))
+            .await;
+        assert_eq!(Some(tc), pm.highest_timeout_certificate());
+    });
+}
+
 #[test]
 fn test_basic_qc() {
     let runtime = consensus_runtime();
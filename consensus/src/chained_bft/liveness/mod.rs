@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod leader_reputation;
 pub(crate) mod local_pacemaker;
 pub(crate) mod pacemaker;
 pub(crate) mod pacemaker_timeout_manager;
@@ -9,6 +10,8 @@ pub(crate) mod proposer_election;
 pub(crate) mod rotating_proposer_election;
 pub(crate) mod timeout_msg;
 
+#[cfg(test)]
+mod leader_reputation_test;
 #[cfg(test)]
 mod local_pacemaker_test;
 #[cfg(test)]
@@ -81,4 +81,12 @@ pub trait Pacemaker: Send + Sync {
 
     /// Update the highest committed round
     fn update_highest_committed_round(&self, highest_committed_round: Round);
+
+    /// Returns the highest timeout certificate (gathered locally or received): it can be
+    /// forwarded to the lagging validators to let them jump to the next round.
+    fn highest_timeout_certificate(&self) -> Option<PacemakerTimeoutCertificate>;
+
+    /// Upon a new epoch, update the number of timeouts required to form a timeout certificate
+    /// and drop the timeouts signed in the previous epoch
+    fn start_new_epoch(&self, timeout_quorum_size: usize);
 }
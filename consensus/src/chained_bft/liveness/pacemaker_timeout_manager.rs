@@ -8,7 +8,10 @@ use crate::chained_bft::{
 };
 use logger::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter},
+};
 
 #[cfg(test)]
 #[path = "pacemaker_timeout_manager_test.rs"]
@@ -59,6 +62,21 @@ impl HighestTimeoutCertificates {
     }
 }
 
+impl Display for HighestTimeoutCertificates {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        let display = |tc: &Option<PacemakerTimeoutCertificate>| match tc {
+            Some(tc) => tc.to_string(),
+            None => "None".to_string(),
+        };
+        write!(
+            f,
+            "[local: {}, received: {}]",
+            display(&self.highest_local_timeout_certificate),
+            display(&self.highest_received_timeout_certificate)
+        )
+    }
+}
+
 /// Manages the PacemakerTimeout structs received from replicas.
 ///
 /// A replica can generate and track TimeoutCertificates of the highest round (locally and received)
@@ -179,6 +197,14 @@ impl PacemakerTimeoutManager {
         highest_round > prev_highest_round.unwrap_or(0)
     }
 
+    /// Updates the minimum quorum to generate a timeout certificate upon a new epoch. The
+    /// timeouts received so far are signed by the validators of the previous epoch: they are
+    /// dropped as a certificate can not mix the timeouts of different epochs.
+    pub fn start_new_epoch(&mut self, timeout_certificate_quorum_size: usize) {
+        self.timeout_certificate_quorum_size = timeout_certificate_quorum_size;
+        self.author_to_received_timeouts.clear();
+    }
+
     /// Attempts to update highest_received_timeout_certificate when receiving a new remote
     /// timeout certificate.  Returns true if highest_received_timeout_certificate has changed
     pub fn update_highest_received_timeout_certificate(
@@ -59,6 +59,8 @@ pub struct ProposalGenerator<T> {
     time_service: Arc<dyn TimeService>,
     // Max number of transactions to be added to a proposed block.
     max_block_size: u64,
+    // Number of transactions of a proposed block reserved for mempool priority lanes.
+    priority_lane_block_size: u64,
     // Support increasing block timestamps
     enforce_increasing_timestamps: bool,
     // Last round that a proposal was generated
@@ -71,6 +73,7 @@ impl<T: Payload> ProposalGenerator<T> {
         txn_manager: Arc<dyn TxnManager<Payload = T>>,
         time_service: Arc<dyn TimeService>,
         max_block_size: u64,
+        priority_lane_block_size: u64,
         enforce_increasing_timestamps: bool,
     ) -> Self {
         Self {
@@ -78,6 +81,7 @@ impl<T: Payload> ProposalGenerator<T> {
             txn_manager,
             time_service,
             max_block_size,
+            priority_lane_block_size,
             enforce_increasing_timestamps,
             last_round_generated: Mutex::new(0),
         }
@@ -94,8 +98,11 @@ impl<T: Payload> ProposalGenerator<T> {
     /// by the caller.
     /// 3. In case a given round is not greater than the calculated parent, return an OldRound
     /// error.
+    /// 4. The epoch is the current one of the caller, the first proposal of an epoch extends a
+    /// block of the previous epoch.
     pub async fn generate_proposal(
         &self,
+        epoch: u64,
         round: Round,
         round_deadline: Instant,
     ) -> Result<Block<T>, ProposalGenerationError> {
@@ -204,12 +211,17 @@ impl<T: Payload> ProposalGenerator<T> {
         let block_store = Arc::clone(&self.block_store);
         match self
             .txn_manager
-            .pull_txns(self.max_block_size, exclude_payload)
+            .pull_txns(
+                self.max_block_size,
+                self.priority_lane_block_size,
+                exclude_payload,
+            )
             .await
         {
             Ok(txns) => Ok(block_store.create_block(
                 hqc_block,
                 txns,
+                epoch,
                 round,
                 block_timestamp.as_micros() as u64,
             )),
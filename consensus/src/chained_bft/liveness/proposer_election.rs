@@ -3,22 +3,23 @@
 
 use crate::chained_bft::{
     common::{Author, Payload, Round},
-    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    consensus_types::{block::Block, epoch_info::EpochInfo, quorum_cert::QuorumCert},
     liveness::timeout_msg::PacemakerTimeoutCertificate,
 };
-use failure::Result;
+use crypto::HashValue;
+use failure::prelude::*;
 use futures::Future;
 use network::proto::Proposal as ProtoProposal;
 use proto_conv::{FromProto, IntoProto};
 use rmp_serde::{from_slice, to_vec_named};
 use serde::{de::DeserializeOwned, Serialize};
 use std::{fmt, pin::Pin};
-use types::validator_verifier::ValidatorVerifier;
 
 /// ProposerInfo is a general trait that can include various proposer characteristics
 /// relevant to a specific protocol implementation. The author is the only common thing for now.
+/// ProposerInfo can be derived from the author (e.g., for the validators of a new epoch).
 pub trait ProposerInfo:
-    Send + Sync + Clone + Copy + fmt::Debug + DeserializeOwned + Serialize + 'static
+    Send + Sync + Clone + Copy + fmt::Debug + DeserializeOwned + Serialize + From<Author> + 'static
 {
     fn get_author(&self) -> Author;
 }
@@ -43,20 +44,37 @@ pub struct ProposalInfo<T, P> {
 }
 
 impl<T: Payload, P: ProposerInfo> ProposalInfo<T, P> {
-    pub fn verify(&self, validator: &ValidatorVerifier) -> Result<()> {
+    /// Verifies the proposal against the validators of its epoch, while the certificates it
+    /// carries are verified against the validators of the (possibly previous) epochs they are
+    /// signed in.
+    pub fn verify(&self, epoch_info: &EpochInfo) -> Result<()> {
         self.proposal
-            .verify(validator)
+            .verify(epoch_info)
             .map_err(|e| format_err!("{:?}", e))?;
         if let Some(tc) = &self.timeout_certificate {
-            tc.verify(validator).map_err(|e| format_err!("{:?}", e))?;
+            ensure!(
+                tc.epoch() <= self.proposal.epoch(),
+                "Proposal {} carries a timeout certificate of the later epoch {}",
+                self.proposal,
+                tc.epoch()
+            );
+            let validator = epoch_info
+                .validator_for_epoch(tc.epoch())
+                .ok_or_else(|| format_err!("Unknown epoch {} of {}", tc.epoch(), tc))?;
+            tc.verify(validator.as_ref())
+                .map_err(|e| format_err!("{:?}", e))?;
         }
         if self.proposal.author() != self.proposer_info.get_author() {
             return Err(format_err!("Proposal for {} has mismatching author of block and proposer info: block={}, proposer={}", self.proposal,
             self.proposal.author(), self.proposer_info.get_author()));
         }
-        self.highest_ledger_info
-            .verify(validator)
-            .map_err(|e| format_err!("{:?}", e))?;
+        ensure!(
+            self.highest_ledger_info.epoch() <= self.proposal.epoch(),
+            "Proposal {} carries a highest ledger info of the later epoch {}",
+            self.proposal,
+            self.highest_ledger_info.epoch()
+        );
+        epoch_info.verify_quorum_cert(&self.highest_ledger_info)?;
 
         Ok(())
     }
@@ -80,14 +98,15 @@ where
 /// We are open to a possibility for having multiple proposers per round, the ultimate choice
 /// of a proposal is exposed by the election protocol via the stream of proposals.
 pub trait ProposerElection<T, P> {
-    /// If a given author is a valid candidate for being a proposer, generate the info,
-    /// otherwise return None.
+    /// If a given author is a valid candidate for being a proposer of the block of the given
+    /// round extending `parent_id`, generate the info, otherwise return None.
     /// Note that this function is synchronous.
-    fn is_valid_proposer(&self, author: P, round: Round) -> Option<P>;
+    fn is_valid_proposer(&self, author: P, round: Round, parent_id: HashValue) -> Option<P>;
 
-    /// Return all the possible valid proposers for a given round (this information can be
-    /// used by e.g., voters for choosing the destinations for sending their votes to).
-    fn get_valid_proposers(&self, round: Round) -> Vec<P>;
+    /// Return all the possible valid proposers for the block of a given round extending
+    /// `parent_id` (this information can be used by e.g., voters for choosing the destinations
+    /// for sending their votes to).
+    fn get_valid_proposers(&self, round: Round, parent_id: HashValue) -> Vec<P>;
 
     /// Notify proposer election about a new proposal. The function doesn't return any information:
     /// proposer election is going to notify the client about the chosen proposal via a dedicated
@@ -6,6 +6,7 @@ use crate::chained_bft::{
     liveness::proposer_election::{ProposalInfo, ProposerElection, ProposerInfo},
 };
 use channel;
+use crypto::HashValue;
 use futures::{Future, FutureExt, SinkExt};
 use logger::prelude::*;
 use std::pin::Pin;
@@ -44,7 +45,7 @@ impl<T, P: ProposerInfo> RotatingProposer<T, P> {
 }
 
 impl<T: Payload, P: ProposerInfo> ProposerElection<T, P> for RotatingProposer<T, P> {
-    fn is_valid_proposer(&self, author: P, round: Round) -> Option<P> {
+    fn is_valid_proposer(&self, author: P, round: Round, _parent_id: HashValue) -> Option<P> {
         if self.get_proposer(round).get_author() == author.get_author() {
             Some(author)
         } else {
@@ -52,7 +53,7 @@ impl<T: Payload, P: ProposerInfo> ProposerElection<T, P> for RotatingProposer<T,
         }
     }
 
-    fn get_valid_proposers(&self, round: Round) -> Vec<P> {
+    fn get_valid_proposers(&self, round: Round, _parent_id: HashValue) -> Vec<P> {
         vec![self.get_proposer(round)]
     }
 
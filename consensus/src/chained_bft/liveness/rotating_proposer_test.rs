@@ -10,6 +10,7 @@ use crate::chained_bft::{
     },
 };
 use channel;
+use crypto::HashValue;
 use futures::{executor::block_on, StreamExt};
 use std::sync::Arc;
 use types::validator_signer::ValidatorSigner;
@@ -83,15 +84,30 @@ fn test_rotating_proposer() {
             winning_proposals_receiver.take(2).collect::<Vec<_>>().await,
             vec![good_proposal, next_good_proposal],
         );
-        assert_eq!(pe.is_valid_proposer(chosen_author, 1), None);
         assert_eq!(
-            pe.is_valid_proposer(another_author, 1),
+            pe.is_valid_proposer(chosen_author, 1, HashValue::zero()),
+            None
+        );
+        assert_eq!(
+            pe.is_valid_proposer(another_author, 1, HashValue::zero()),
             Some(another_author)
         );
-        assert_eq!(pe.is_valid_proposer(chosen_author, 2), Some(chosen_author));
-        assert_eq!(pe.is_valid_proposer(another_author, 2), None);
-        assert_eq!(pe.get_valid_proposers(1), vec![another_author]);
-        assert_eq!(pe.get_valid_proposers(2), vec![chosen_author]);
+        assert_eq!(
+            pe.is_valid_proposer(chosen_author, 2, HashValue::zero()),
+            Some(chosen_author)
+        );
+        assert_eq!(
+            pe.is_valid_proposer(another_author, 2, HashValue::zero()),
+            None
+        );
+        assert_eq!(
+            pe.get_valid_proposers(1, HashValue::zero()),
+            vec![another_author]
+        );
+        assert_eq!(
+            pe.get_valid_proposers(2, HashValue::zero()),
+            vec![chosen_author]
+        );
     });
 }
 
@@ -164,12 +180,30 @@ fn test_rotating_proposer_with_three_contiguous_rounds() {
             winning_proposals_receiver.take(2).collect::<Vec<_>>().await,
             vec![good_proposal, next_good_proposal],
         );
-        assert_eq!(pe.is_valid_proposer(another_author, 1), None);
-        assert_eq!(pe.is_valid_proposer(chosen_author, 1), Some(chosen_author));
-        assert_eq!(pe.is_valid_proposer(chosen_author, 2), Some(chosen_author));
-        assert_eq!(pe.is_valid_proposer(another_author, 2), None);
-        assert_eq!(pe.get_valid_proposers(1), vec![chosen_author]);
-        assert_eq!(pe.get_valid_proposers(2), vec![chosen_author]);
+        assert_eq!(
+            pe.is_valid_proposer(another_author, 1, HashValue::zero()),
+            None
+        );
+        assert_eq!(
+            pe.is_valid_proposer(chosen_author, 1, HashValue::zero()),
+            Some(chosen_author)
+        );
+        assert_eq!(
+            pe.is_valid_proposer(chosen_author, 2, HashValue::zero()),
+            Some(chosen_author)
+        );
+        assert_eq!(
+            pe.is_valid_proposer(another_author, 2, HashValue::zero()),
+            None
+        );
+        assert_eq!(
+            pe.get_valid_proposers(1, HashValue::zero()),
+            vec![chosen_author]
+        );
+        assert_eq!(
+            pe.get_valid_proposers(2, HashValue::zero()),
+            vec![chosen_author]
+        );
     });
 }
 
@@ -241,8 +275,17 @@ fn test_fixed_proposer() {
             winning_proposals_receiver.take(2).collect::<Vec<_>>().await,
             vec![good_proposal, next_good_proposal],
         );
-        assert_eq!(pe.is_valid_proposer(chosen_author, 1), Some(chosen_author));
-        assert_eq!(pe.is_valid_proposer(another_author, 1), None);
-        assert_eq!(pe.get_valid_proposers(1), vec![chosen_author]);
+        assert_eq!(
+            pe.is_valid_proposer(chosen_author, 1, HashValue::zero()),
+            Some(chosen_author)
+        );
+        assert_eq!(
+            pe.is_valid_proposer(another_author, 1, HashValue::zero()),
+            None
+        );
+        assert_eq!(
+            pe.get_valid_proposers(1, HashValue::zero()),
+            vec![chosen_author]
+        );
     });
 }
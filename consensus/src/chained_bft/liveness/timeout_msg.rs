@@ -5,47 +5,16 @@ use crate::chained_bft::{
     common::{Author, Round},
     consensus_types::quorum_cert::QuorumCert,
     liveness::timeout_msg::PacemakerTimeoutCertificateVerificationError::*,
+    safety::consensus_signer::ConsensusSigner,
 };
-use canonical_serialization::{CanonicalSerialize, CanonicalSerializer, SimpleSerializer};
-use crypto::{
-    hash::{CryptoHash, CryptoHasher, PacemakerTimeoutHasher, TimeoutMsgHasher},
-    HashValue, Signature,
-};
+use crypto::{hash::CryptoHash, HashValue, Signature};
 use network;
 use proto_conv::{FromProto, IntoProto};
 use protobuf::RepeatedField;
+use secret_service::safety_rules_data::{TimeoutData, TimeoutMsgData};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashSet, convert::TryFrom, fmt, iter::FromIterator};
-use types::{
-    account_address::AccountAddress,
-    validator_signer::ValidatorSigner,
-    validator_verifier::{ValidatorVerifier, VerifyError},
-};
-
-// Internal use only. Contains all the fields in PaceMakerTimeout that contributes to the
-// computation of its hash.
-struct PacemakerTimeoutSerializer {
-    round: Round,
-    author: Author,
-}
-
-impl CanonicalSerialize for PacemakerTimeoutSerializer {
-    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> failure::Result<()> {
-        serializer.encode_u64(self.round)?;
-        serializer.encode_struct(&self.author)?;
-        Ok(())
-    }
-}
-
-impl CryptoHash for PacemakerTimeoutSerializer {
-    type Hasher = PacemakerTimeoutHasher;
-
-    fn hash(&self) -> HashValue {
-        let mut state = Self::Hasher::default();
-        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
-        state.finish()
-    }
-}
+use types::validator_verifier::{ValidatorVerifier, VerifyError};
 
 /// This message will be broadcast by a pacemaker as part of TimeoutMsg when its local
 /// timeout for a round is reached.  Once f+1 PacemakerTimeout structs
@@ -53,6 +22,7 @@ impl CryptoHash for PacemakerTimeoutSerializer {
 /// a proof that will cause a replica to advance to the minimum round in the TimeoutCertificate.
 #[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
 pub struct PacemakerTimeout {
+    epoch: u64,
     round: Round,
     author: Author,
     signature: Signature,
@@ -60,26 +30,39 @@ pub struct PacemakerTimeout {
 
 impl PacemakerTimeout {
     /// Creates new PacemakerTimeout
-    pub fn new(round: Round, validator_signer: &ValidatorSigner) -> Self {
+    pub fn new(epoch: u64, round: Round, validator_signer: &dyn ConsensusSigner) -> Self {
         let author = validator_signer.author();
-        let digest = PacemakerTimeoutSerializer { round, author }.hash();
         let signature = validator_signer
-            .sign_message(digest)
+            .sign_timeout(&TimeoutData {
+                epoch,
+                round,
+                author,
+            })
             .expect("Failed to sign PacemakerTimeout");
         PacemakerTimeout {
+            epoch,
             round,
             author,
             signature,
         }
     }
 
-    fn pacemaker_timeout_digest(author: AccountAddress, round: Round) -> HashValue {
-        PacemakerTimeoutSerializer { round, author }.hash()
+    fn timeout_data(&self) -> TimeoutData {
+        TimeoutData {
+            epoch: self.epoch,
+            round: self.round,
+            author: self.author,
+        }
     }
 
     /// Calculates digest for this struct
     pub fn digest(&self) -> HashValue {
-        Self::pacemaker_timeout_digest(self.author, self.round)
+        self.timeout_data().hash()
+    }
+
+    /// Returns the epoch of the validators the author belongs to
+    pub fn epoch(&self) -> u64 {
+        self.epoch
     }
 
     pub fn round(&self) -> Round {
@@ -107,6 +90,7 @@ impl IntoProto for PacemakerTimeout {
 
     fn into_proto(self) -> Self::ProtoType {
         let mut proto = Self::ProtoType::new();
+        proto.set_epoch(self.epoch);
         proto.set_round(self.round);
         proto.set_author(self.author.into());
         proto.set_signature(self.signature.to_compact().as_ref().into());
@@ -118,10 +102,12 @@ impl FromProto for PacemakerTimeout {
     type ProtoType = network::proto::PacemakerTimeout;
 
     fn from_proto(mut object: Self::ProtoType) -> failure::Result<Self> {
+        let epoch = object.get_epoch();
         let round = object.get_round();
         let author = Author::try_from(object.take_author())?;
         let signature = Signature::from_compact(object.get_signature())?;
         Ok(PacemakerTimeout {
+            epoch,
             round,
             author,
             signature,
@@ -129,31 +115,6 @@ impl FromProto for PacemakerTimeout {
     }
 }
 
-// Internal use only. Contains all the fields in TimeoutMsg that contributes to the computation of
-// its hash.
-struct TimeoutMsgSerializer {
-    highest_quorum_certificate_block_id: HashValue,
-    pacemaker_timeout_digest: HashValue,
-}
-
-impl CanonicalSerialize for TimeoutMsgSerializer {
-    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> failure::Result<()> {
-        serializer.encode_raw_bytes(self.highest_quorum_certificate_block_id.as_ref())?;
-        serializer.encode_raw_bytes(self.pacemaker_timeout_digest.as_ref())?;
-        Ok(())
-    }
-}
-
-impl CryptoHash for TimeoutMsgSerializer {
-    type Hasher = TimeoutMsgHasher;
-
-    fn hash(&self) -> HashValue {
-        let mut state = Self::Hasher::default();
-        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
-        state.finish()
-    }
-}
-
 /// This message will be broadcast by a pacemaker when its local timeout for a round is reached.
 /// Once the broadcasts start, retries will continue for every timeout until the round changes.
 /// Retries are required since, say if a proposer for a round r was unresponsive, it might not
@@ -180,15 +141,14 @@ impl TimeoutMsg {
         highest_quorum_certificate: QuorumCert,
         highest_ledger_info: QuorumCert,
         pacemaker_timeout: PacemakerTimeout,
-        validator_signer: &ValidatorSigner,
+        validator_signer: &dyn ConsensusSigner,
     ) -> TimeoutMsg {
         let author = validator_signer.author();
-        let digest = Self::new_round_digest(
-            highest_quorum_certificate.certified_block_id(),
-            pacemaker_timeout.digest(),
-        );
         let signature = validator_signer
-            .sign_message(digest)
+            .sign_timeout_msg(&TimeoutMsgData {
+                timeout: pacemaker_timeout.timeout_data(),
+                highest_certified_block_id: highest_quorum_certificate.certified_block_id(),
+            })
             .expect("Failed to sign PacemakerTimeoutMsg");
         TimeoutMsg {
             highest_quorum_certificate,
@@ -199,23 +159,13 @@ impl TimeoutMsg {
         }
     }
 
-    fn new_round_digest(
-        highest_quorum_certificate_block_id: HashValue,
-        pacemaker_timeout_digest: HashValue,
-    ) -> HashValue {
-        TimeoutMsgSerializer {
-            highest_quorum_certificate_block_id,
-            pacemaker_timeout_digest,
-        }
-        .hash()
-    }
-
     /// Calculates digest for this message
     pub fn digest(&self) -> HashValue {
-        Self::new_round_digest(
-            self.highest_quorum_certificate.certified_block_id(),
-            self.pacemaker_timeout.digest(),
-        )
+        TimeoutMsgData {
+            timeout: self.pacemaker_timeout.timeout_data(),
+            highest_certified_block_id: self.highest_quorum_certificate.certified_block_id(),
+        }
+        .hash()
     }
 
     /// Highest QC carried by the new round message.
@@ -233,6 +183,11 @@ impl TimeoutMsg {
         &self.pacemaker_timeout
     }
 
+    /// Returns the epoch of the author, which is signed as part of the PacemakerTimeout
+    pub fn epoch(&self) -> u64 {
+        self.pacemaker_timeout.epoch()
+    }
+
     /// Verifies that this message has valid signature
     pub fn verify(&self, validator: &ValidatorVerifier) -> Result<(), VerifyError> {
         validator.verify_signature(self.author, self.digest(), &self.signature)?;
@@ -298,6 +253,8 @@ pub enum PacemakerTimeoutCertificateVerificationError {
     NoQuorum,
     /// Round in message does not match calculated rounds based on signed timeouts
     RoundMismatch { expected: Round },
+    /// The timeouts are signed in different epochs
+    EpochMismatch,
     /// The signature on one of timeouts doesn't pass verification
     SigVerifyError(Author, VerifyError),
 }
@@ -329,6 +286,9 @@ impl PacemakerTimeoutCertificate {
         let mut min_round: Option<Round> = None;
         let mut unique_authors = HashSet::new();
         for timeout in &self.timeouts {
+            if timeout.epoch() != self.epoch() {
+                return Err(EpochMismatch);
+            }
             if let Err(e) =
                 validator.verify_signature(timeout.author(), timeout.digest(), timeout.signature())
             {
@@ -355,6 +315,11 @@ impl PacemakerTimeoutCertificate {
         self.round
     }
 
+    /// Returns the epoch of the validators that signed the timeouts
+    pub fn epoch(&self) -> u64 {
+        self.timeouts.first().map_or(0, PacemakerTimeout::epoch)
+    }
+
     /// Returns the timeouts that certify the PacemakerTimeoutCertificate
     #[allow(dead_code)]
     pub fn timeouts(&self) -> &Vec<PacemakerTimeout> {
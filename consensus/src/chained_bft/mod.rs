@@ -4,6 +4,7 @@
 mod common;
 mod consensus_types;
 mod consensusdb;
+pub mod consensusdb_inspector;
 mod liveness;
 mod safety;
 
@@ -20,10 +21,16 @@ mod sync_manager;
 #[cfg(test)]
 mod chained_bft_smr_test;
 #[cfg(test)]
+mod consensusdb_inspector_test;
+#[cfg(test)]
 mod event_processor_test;
 #[cfg(test)]
 mod network_tests;
 #[cfg(test)]
 mod proto_test;
 #[cfg(test)]
+mod simulator;
+#[cfg(test)]
+mod simulator_test;
+#[cfg(test)]
 pub mod test_utils;
@@ -5,10 +5,10 @@ use crate::{
     chained_bft::{
         block_storage::BlockRetrievalFailure,
         common::{Author, Payload},
-        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        consensus_types::{block::Block, epoch_info::EpochInfo, quorum_cert::QuorumCert},
         liveness::{
             proposer_election::{ProposalInfo, ProposerInfo},
-            timeout_msg::TimeoutMsg,
+            timeout_msg::{PacemakerTimeoutCertificate, TimeoutMsg},
         },
         safety::vote_msg::VoteMsg,
     },
@@ -19,22 +19,30 @@ use channel;
 use crypto::HashValue;
 use failure;
 use futures::{
-    channel::oneshot, stream::select, FutureExt, SinkExt, Stream, StreamExt, TryFutureExt,
+    channel::oneshot, stream::select, Future, FutureExt, SinkExt, Stream, StreamExt, TryFutureExt,
     TryStreamExt,
 };
 use logger::prelude::*;
 use network::{
-    proto::{BlockRetrievalStatus, ConsensusMsg, RequestBlock, RespondBlock, RespondChunk},
+    proto::{
+        BlockRetrievalStatus, ConsensusMsg, RequestBlock, RequestEpochChange,
+        RespondAccountStateChunk, RespondBlock, RespondChunk, RespondEpochChange,
+        RespondHighestLedgerInfo, TimeoutCertificateMsg,
+    },
     validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender, Event, RpcError},
 };
 use proto_conv::{FromProto, IntoProto};
 use protobuf::Message;
 use std::{
-    sync::Arc,
+    pin::Pin,
+    sync::{Arc, RwLock},
     time::{Duration, Instant},
 };
 use tokio::runtime::TaskExecutor;
-use types::{transaction::TransactionListWithProof, validator_verifier::ValidatorVerifier};
+use types::{
+    account_state_blob::AccountStateChunkWithProof, transaction::TransactionListWithProof,
+    validator_public_keys::ValidatorPublicKeys, validator_verifier::ValidatorVerifier,
+};
 
 /// The response sent back from event_processor for the BlockRetrievalRequest.
 #[derive(Debug)]
@@ -84,11 +92,46 @@ pub struct ChunkRetrievalRequest {
     pub response_sender: oneshot::Sender<Result<TransactionListWithProof, failure::Error>>,
 }
 
+/// Represents a request to get up to batch_size account states of the state at the target version,
+/// starting right after start_key, with the oneshot sender to deliver the response.
+pub struct AccountStateChunkRetrievalRequest {
+    pub target: QuorumCert,
+    pub start_key: Option<HashValue>,
+    pub batch_size: u64,
+    pub response_sender: oneshot::Sender<Result<AccountStateChunkWithProof, failure::Error>>,
+}
+
+/// Represents a request of a full node for the highest committed ledger info, with the oneshot
+/// sender to deliver the quorum certificate carrying it.
+pub struct HighestLedgerInfoRetrievalRequest {
+    pub response_sender: oneshot::Sender<QuorumCert>,
+}
+
+/// Represents a request of a validator lagging behind for the quorum certificate that ended the
+/// given epoch, with the oneshot sender to deliver it.
+pub struct EpochChangeRetrievalRequest {
+    pub epoch: u64,
+    pub response_sender: oneshot::Sender<Result<QuorumCert, failure::Error>>,
+}
+
+/// Notifies that a peer has sent a message of a later epoch: this validator has missed the end
+/// of its current epoch and can retrieve it from the peer.
+pub struct FutureEpochNotification {
+    pub peer: Author,
+    pub epoch: u64,
+}
+
 /// Just a convenience struct to keep all the network proxy receiving queues in one place.
 /// 1. proposals
 /// 2. votes
 /// 3. block retrieval requests (the request carries a oneshot sender for returning the Block)
 /// 4. pacemaker timeouts
+/// 5. chunk retrieval requests
+/// 6. timeout certificates forwarded by the peers
+/// 7. account state chunk retrieval requests
+/// 8. highest ledger info retrieval requests of the full nodes
+/// 9. epoch change retrieval requests of the validators lagging behind
+/// 10. notifications about the messages of future epochs
 /// Will be returned by the networking trait upon startup.
 pub struct NetworkReceivers<T, P> {
     pub proposals: channel::Receiver<ProposalInfo<T, P>>,
@@ -96,6 +139,11 @@ pub struct NetworkReceivers<T, P> {
     pub block_retrieval: channel::Receiver<BlockRetrievalRequest<T>>,
     pub timeout_msgs: channel::Receiver<TimeoutMsg>,
     pub chunk_retrieval: channel::Receiver<ChunkRetrievalRequest>,
+    pub timeout_certificates: channel::Receiver<PacemakerTimeoutCertificate>,
+    pub account_state_chunk_retrieval: channel::Receiver<AccountStateChunkRetrievalRequest>,
+    pub highest_ledger_info_retrieval: channel::Receiver<HighestLedgerInfoRetrievalRequest>,
+    pub epoch_change_retrieval: channel::Receiver<EpochChangeRetrievalRequest>,
+    pub future_epochs: channel::Receiver<FutureEpochNotification>,
 }
 
 /// Implements the actual networking support for all consensus messaging.
@@ -108,8 +156,8 @@ pub struct ConsensusNetworkImpl {
     // Note that we do not support self rpc requests as it might cause infinite recursive calls.
     self_sender: channel::Sender<Result<Event<ConsensusMsg>, failure::Error>>,
     self_receiver: Option<channel::Receiver<Result<Event<ConsensusMsg>, failure::Error>>>,
-    peers: Arc<Vec<Author>>,
-    validator: Arc<ValidatorVerifier>,
+    // Shared by all the clones of the network and the network task.
+    epoch_info: Arc<RwLock<EpochInfo>>,
 }
 
 impl Clone for ConsensusNetworkImpl {
@@ -120,8 +168,7 @@ impl Clone for ConsensusNetworkImpl {
             network_events: None,
             self_sender: self.self_sender.clone(),
             self_receiver: None,
-            peers: self.peers.clone(),
-            validator: Arc::clone(&self.validator),
+            epoch_info: Arc::clone(&self.epoch_info),
         }
     }
 }
@@ -141,16 +188,63 @@ impl ConsensusNetworkImpl {
             network_events: Some(network_events),
             self_sender,
             self_receiver: Some(self_receiver),
-            peers,
-            validator,
+            epoch_info: Arc::new(RwLock::new(EpochInfo::genesis(peers, validator))),
         }
     }
 
+    /// Returns the current epoch together with its validators.
+    pub fn epoch_info(&self) -> EpochInfo {
+        self.epoch_info.read().unwrap().clone()
+    }
+
+    /// Returns the current epoch.
+    pub fn epoch(&self) -> u64 {
+        self.epoch_info.read().unwrap().epoch
+    }
+
+    /// Switches to a new epoch: from now on only the messages of the new epoch are accepted and
+    /// the broadcasts are sent to the validators of the new epoch.
+    pub fn start_new_epoch(&self, epoch_info: EpochInfo) {
+        let mut guard = self.epoch_info.write().unwrap();
+        assert!(
+            epoch_info.epoch > guard.epoch,
+            "Epoch is going down from {} to {}",
+            guard.epoch,
+            epoch_info.epoch
+        );
+        *guard = epoch_info;
+    }
+
+    /// Rebuilds the trusted peers of the network upon a new epoch: the connections are only
+    /// accepted from (and established with) the given validators.
+    pub async fn update_eligible_nodes(
+        &mut self,
+        validators: Vec<ValidatorPublicKeys>,
+    ) -> failure::Result<()> {
+        self.network_sender
+            .update_eligible_nodes(validators)
+            .await?;
+        Ok(())
+    }
+
     /// Establishes the initial connections with the peers and returns the receivers.
     pub fn start<T: Payload, P: ProposerInfo>(
         &mut self,
         executor: &TaskExecutor,
     ) -> NetworkReceivers<T, P> {
+        let (receivers, network_task) = self.start_without_executor();
+        executor.spawn(network_task.unit_error().compat());
+        receivers
+    }
+
+    /// Same as `start`, but instead of spawning the task routing the incoming messages into the
+    /// receivers returns it to the caller, which is responsible for driving it.
+    pub fn start_without_executor<T: Payload, P: ProposerInfo>(
+        &mut self,
+    ) -> (
+        NetworkReceivers<T, P>,
+        Pin<Box<dyn Future<Output = ()> + Send>>,
+    ) {
         let (proposal_tx, proposal_rx) = channel::new(1_024, &counters::PENDING_PROPOSAL);
         let (vote_tx, vote_rx) = channel::new(1_024, &counters::PENDING_VOTES);
         let (block_request_tx, block_request_rx) =
@@ -159,6 +253,16 @@ impl ConsensusNetworkImpl {
             channel::new(1_024, &counters::PENDING_CHUNK_REQUESTS);
         let (new_round_tx, new_round_rx) =
             channel::new(1_024, &counters::PENDING_NEW_ROUND_MESSAGES);
+        let (timeout_certificate_tx, timeout_certificate_rx) =
+            channel::new(1_024, &counters::PENDING_TIMEOUT_CERTIFICATES);
+        let (account_state_chunk_request_tx, account_state_chunk_request_rx) =
+            channel::new(1_024, &counters::PENDING_ACCOUNT_STATE_CHUNK_REQUESTS);
+        let (highest_ledger_info_request_tx, highest_ledger_info_request_rx) =
+            channel::new(1_024, &counters::PENDING_HIGHEST_LEDGER_INFO_REQUESTS);
+        let (epoch_change_request_tx, epoch_change_request_rx) =
+            channel::new(1_024, &counters::PENDING_EPOCH_CHANGE_REQUESTS);
+        let (future_epoch_tx, future_epoch_rx) =
+            channel::new(1_024, &counters::PENDING_FUTURE_EPOCH_NOTIFICATIONS);
         let network_events = self
             .network_events
             .take()
@@ -169,29 +273,38 @@ impl ConsensusNetworkImpl {
             .take()
             .expect("[consensus]: self receiver is already taken");
         let all_events = select(network_events, own_msgs);
-        let validator = Arc::clone(&self.validator);
-        executor.spawn(
-            NetworkTask {
-                proposal_tx,
-                vote_tx,
-                block_request_tx,
-                chunk_request_tx,
-                timeout_msg_tx: new_round_tx,
-                all_events,
-                validator,
-            }
-            .run()
-            .boxed()
-            .unit_error()
-            .compat(),
-        );
-        NetworkReceivers {
-            proposals: proposal_rx,
-            votes: vote_rx,
-            block_retrieval: block_request_rx,
-            timeout_msgs: new_round_rx,
-            chunk_retrieval: chunk_request_rx,
+        let epoch_info = Arc::clone(&self.epoch_info);
+        let network_task = NetworkTask {
+            proposal_tx,
+            vote_tx,
+            block_request_tx,
+            chunk_request_tx,
+            timeout_msg_tx: new_round_tx,
+            timeout_certificate_tx,
+            account_state_chunk_request_tx,
+            highest_ledger_info_request_tx,
+            epoch_change_request_tx,
+            future_epoch_tx,
+            all_events,
+            epoch_info,
         }
+        .run()
+        .boxed();
+        (
+            NetworkReceivers {
+                proposals: proposal_rx,
+                votes: vote_rx,
+                block_retrieval: block_request_rx,
+                timeout_msgs: new_round_rx,
+                chunk_retrieval: chunk_request_rx,
+                timeout_certificates: timeout_certificate_rx,
+                account_state_chunk_retrieval: account_state_chunk_request_rx,
+                highest_ledger_info_retrieval: highest_ledger_info_request_rx,
+                epoch_change_retrieval: epoch_change_request_rx,
+                future_epochs: future_epoch_rx,
+            },
+            network_task,
+        )
     }
 
     /// Tries to retrieve num of blocks backwards starting from id from the given peer: the function
@@ -218,9 +331,10 @@ impl ConsensusNetworkImpl {
             .request_block(from, req_msg, timeout)
             .await?;
         let mut blocks = vec![];
+        let epoch_info = self.epoch_info();
         for block in res_block.take_blocks().into_iter() {
             if let Ok(block) = Block::from_proto(block) {
-                if block.verify(self.validator.as_ref()).is_err() {
+                if block.verify(&epoch_info).is_err() {
                     return Err(BlockRetrievalFailure::InvalidSignature);
                 }
                 blocks.push(block);
@@ -240,6 +354,35 @@ impl ConsensusNetworkImpl {
         Ok(response)
     }
 
+    /// Retrieves from the given peer the quorum certificate that ended the given epoch: the
+    /// certificate is verified against the validators of the epoch, which must be the current one.
+    pub async fn request_epoch_change(
+        &mut self,
+        epoch: u64,
+        from: Author,
+        timeout: Duration,
+    ) -> failure::Result<QuorumCert> {
+        ensure!(
+            from != self.author,
+            "Can not request an epoch change from self"
+        );
+        let mut req_msg = RequestEpochChange::new();
+        req_msg.set_epoch(epoch);
+        let mut response: RespondEpochChange = self
+            .network_sender
+            .request_epoch_change(from, req_msg, timeout)
+            .await?;
+        let proof = QuorumCert::from_proto(response.take_proof())?;
+        ensure!(
+            proof.epoch() == epoch,
+            "Requested the end of epoch {}, got a certificate of epoch {}",
+            epoch,
+            proof.epoch()
+        );
+        self.epoch_info().verify_quorum_cert(&proof)?;
+        Ok(proof)
+    }
+
     /// Tries to send the given proposal (block and proposer metadata) to all the participants.
     /// A validator on the receiving end is going to be notified about a new proposal in the
     /// proposal queue.
@@ -258,7 +401,8 @@ impl ConsensusNetworkImpl {
     }
 
     async fn broadcast(&mut self, msg: ConsensusMsg) {
-        for peer in self.peers.iter() {
+        let peers = Arc::clone(&self.epoch_info.read().unwrap().peers);
+        for peer in peers.iter() {
             if self.author == *peer {
                 let self_msg = Event::Message((self.author, msg.clone()));
                 if let Err(err) = self.self_sender.send(Ok(self_msg)).await {
@@ -302,6 +446,31 @@ impl ConsensusNetworkImpl {
         }
     }
 
+    /// Sends the timeout certificate to the validators lagging behind its round: the recipients
+    /// are going to be notified about a new certificate in the timeout certificate queue.
+    pub async fn send_timeout_certificate(
+        &self,
+        timeout_certificate: PacemakerTimeoutCertificate,
+        recipients: Vec<Author>,
+    ) {
+        let mut network_sender = self.network_sender.clone();
+        let mut timeout_certificate_msg = TimeoutCertificateMsg::new();
+        timeout_certificate_msg.set_timeout_certificate(timeout_certificate.into_proto());
+        let mut msg = ConsensusMsg::new();
+        msg.set_timeout_certificate(timeout_certificate_msg);
+        for peer in recipients {
+            if self.author == peer {
+                continue;
+            }
+            if let Err(e) = network_sender.send_to(peer, msg.clone()).await {
+                error!(
+                    "Failed to send a timeout certificate to peer {:?}: {:?}",
+                    peer, e
+                );
+            }
+        }
+    }
+
     /// Broadcasts timeout message to all validators
     pub async fn broadcast_timeout_msg(&mut self, timeout_msg: TimeoutMsg) {
         let mut msg = ConsensusMsg::new();
@@ -316,8 +485,13 @@ struct NetworkTask<T, P, S> {
     block_request_tx: channel::Sender<BlockRetrievalRequest<T>>,
     chunk_request_tx: channel::Sender<ChunkRetrievalRequest>,
     timeout_msg_tx: channel::Sender<TimeoutMsg>,
+    timeout_certificate_tx: channel::Sender<PacemakerTimeoutCertificate>,
+    account_state_chunk_request_tx: channel::Sender<AccountStateChunkRetrievalRequest>,
+    highest_ledger_info_request_tx: channel::Sender<HighestLedgerInfoRetrievalRequest>,
+    epoch_change_request_tx: channel::Sender<EpochChangeRetrievalRequest>,
+    future_epoch_tx: channel::Sender<FutureEpochNotification>,
     all_events: S,
-    validator: Arc<ValidatorVerifier>,
+    epoch_info: Arc<RwLock<EpochInfo>>,
 }
 
 impl<T, P, S> NetworkTask<T, P, S>
@@ -331,11 +505,13 @@ where
             match message {
                 Event::Message((peer_id, mut msg)) => {
                     let r = if msg.has_proposal() {
-                        self.process_proposal(&mut msg).await
+                        self.process_proposal(peer_id, &mut msg).await
                     } else if msg.has_vote() {
                         self.process_vote(&mut msg).await
                     } else if msg.has_timeout_msg() {
-                        self.process_timeout_msg(&mut msg).await
+                        self.process_timeout_msg(peer_id, &mut msg).await
+                    } else if msg.has_timeout_certificate() {
+                        self.process_timeout_certificate(&mut msg).await
                     } else {
                         warn!("Unexpected msg from {}: {:?}", peer_id, msg);
                         continue;
@@ -349,6 +525,13 @@ where
                         self.process_request_block(&mut msg, callback).await
                     } else if msg.has_request_chunk() {
                         self.process_request_chunk(&mut msg, callback).await
+                    } else if msg.has_request_account_state_chunk() {
+                        self.process_request_account_state_chunk(&mut msg, callback)
+                            .await
+                    } else if msg.has_request_highest_ledger_info() {
+                        self.process_request_highest_ledger_info(callback).await
+                    } else if msg.has_request_epoch_change() {
+                        self.process_request_epoch_change(&mut msg, callback).await
                     } else {
                         warn!("Unexpected RPC from {}: {:?}", peer_id, msg);
                         continue;
@@ -367,9 +550,51 @@ where
         }
     }
 
-    async fn process_proposal<'a>(&'a mut self, msg: &'a mut ConsensusMsg) -> failure::Result<()> {
+    /// Returns the current epoch if a message of the given epoch is to be processed.
+    fn epoch_info_for_epoch(&self, epoch: u64) -> failure::Result<EpochInfo> {
+        let epoch_info = self.epoch_info.read().unwrap();
+        ensure!(
+            epoch == epoch_info.epoch,
+            "Message of epoch {} while the current epoch is {}",
+            epoch,
+            epoch_info.epoch
+        );
+        Ok(epoch_info.clone())
+    }
+
+    /// Same as `epoch_info_for_epoch`, but a message of a later epoch is reported: the peer
+    /// that sent it has moved to an epoch this validator has missed the start of.
+    async fn epoch_info_for_peer_epoch(
+        &mut self,
+        peer: Author,
+        epoch: u64,
+    ) -> failure::Result<EpochInfo> {
+        let current_epoch = self.epoch_info.read().unwrap().epoch;
+        if epoch > current_epoch {
+            self.future_epoch_tx
+                .send(FutureEpochNotification { peer, epoch })
+                .await?;
+        }
+        self.epoch_info_for_epoch(epoch)
+    }
+
+    async fn process_proposal<'a>(
+        &'a mut self,
+        peer: Author,
+        msg: &'a mut ConsensusMsg,
+    ) -> failure::Result<()> {
         let proposal = ProposalInfo::<T, P>::from_proto(msg.take_proposal())?;
-        proposal.verify(self.validator.as_ref()).map_err(|e| {
+        let epoch_info = self
+            .epoch_info_for_peer_epoch(peer, proposal.proposal.epoch())
+            .await
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusProposal)
+                    .error(&e)
+                    .data(&proposal)
+                    .log();
+                e
+            })?;
+        proposal.verify(&epoch_info).map_err(|e| {
             security_log(SecurityEvent::InvalidConsensusProposal)
                 .error(&e)
                 .data(&proposal)
@@ -384,7 +609,17 @@ where
     async fn process_vote<'a>(&'a mut self, msg: &'a mut ConsensusMsg) -> failure::Result<()> {
         let vote = VoteMsg::from_proto(msg.take_vote())?;
         debug!("Received {}", vote);
-        vote.verify(self.validator.as_ref()).map_err(|e| {
+        // The epoch of a vote is the one of the ledger info it signs.
+        let epoch_info = self
+            .epoch_info_for_epoch(vote.ledger_info().epoch_num())
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusVote)
+                    .error(&e)
+                    .data(&vote)
+                    .log();
+                e
+            })?;
+        vote.verify(epoch_info.validator.as_ref()).map_err(|e| {
             security_log(SecurityEvent::InvalidConsensusVote)
                 .error(&e)
                 .data(&vote)
@@ -397,17 +632,63 @@ where
 
     async fn process_timeout_msg<'a>(
         &'a mut self,
+        peer: Author,
         msg: &'a mut ConsensusMsg,
     ) -> failure::Result<()> {
         let timeout_msg = TimeoutMsg::from_proto(msg.take_timeout_msg())?;
-        timeout_msg.verify(self.validator.as_ref()).map_err(|e| {
+        let epoch_info = self
+            .epoch_info_for_peer_epoch(peer, timeout_msg.epoch())
+            .await
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusRound)
+                    .error(&e)
+                    .data(&timeout_msg)
+                    .log();
+                e
+            })?;
+        timeout_msg
+            .verify(epoch_info.validator.as_ref())
+            .map_err(|e| format_err!("{:?}", e))
+            .and_then(|_| epoch_info.verify_quorum_cert(timeout_msg.highest_quorum_certificate()))
+            .and_then(|_| epoch_info.verify_quorum_cert(timeout_msg.highest_ledger_info()))
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusRound)
+                    .error(&e)
+                    .data(&timeout_msg)
+                    .log();
+                e
+            })?;
+        self.timeout_msg_tx.send(timeout_msg).await?;
+        Ok(())
+    }
+
+    async fn process_timeout_certificate<'a>(
+        &'a mut self,
+        msg: &'a mut ConsensusMsg,
+    ) -> failure::Result<()> {
+        let mut timeout_certificate_msg = msg.take_timeout_certificate();
+        let timeout_certificate = PacemakerTimeoutCertificate::from_proto(
+            timeout_certificate_msg.take_timeout_certificate(),
+        )?;
+        let epoch_info = self
+            .epoch_info_for_epoch(timeout_certificate.epoch())
+            .map_err(|e| {
+                security_log(SecurityEvent::InvalidConsensusRound)
+                    .error(&e)
+                    .data(&timeout_certificate)
+                    .log();
+                e
+            })?;
+        if let Err(e) = timeout_certificate.verify(epoch_info.validator.as_ref()) {
             security_log(SecurityEvent::InvalidConsensusRound)
                 .error(&e)
-                .data(&timeout_msg)
+                .data(&timeout_certificate)
                 .log();
-            e
-        })?;
-        self.timeout_msg_tx.send(timeout_msg).await?;
+            bail!("Invalid timeout certificate: {:?}", e);
+        }
+        self.timeout_certificate_tx
+            .send(timeout_certificate)
+            .await?;
         Ok(())
     }
 
@@ -425,7 +706,10 @@ where
         );
         let (tx, rx) = oneshot::channel();
         let target = QuorumCert::from_proto(req.take_target())?;
-        target.verify(self.validator.as_ref())?;
+        self.epoch_info
+            .read()
+            .unwrap()
+            .verify_quorum_cert(&target)?;
         let request = ChunkRetrievalRequest {
             start_version: req.start_version,
             target,
@@ -452,6 +736,114 @@ where
             .map_err(|_| format_err!("handling inbound rpc call timed out"))
     }
 
+    async fn process_request_account_state_chunk<'a>(
+        &'a mut self,
+        msg: &'a mut ConsensusMsg,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> failure::Result<()> {
+        let mut req = msg.take_request_account_state_chunk();
+        debug!(
+            "Received request_account_state_chunk RPC for target: {:?} start_key: {:?} batch_size: {}",
+            req.get_target(),
+            req.get_start_key(),
+            req.batch_size
+        );
+        let (tx, rx) = oneshot::channel();
+        let target = QuorumCert::from_proto(req.take_target())?;
+        self.epoch_info
+            .read()
+            .unwrap()
+            .verify_quorum_cert(&target)?;
+        let start_key = if req.get_start_key().is_empty() {
+            None
+        } else {
+            Some(HashValue::from_slice(req.get_start_key())?)
+        };
+        let request = AccountStateChunkRetrievalRequest {
+            target,
+            start_key,
+            batch_size: req.batch_size,
+            response_sender: tx,
+        };
+        self.account_state_chunk_request_tx.send(request).await?;
+        callback
+            .send(match rx.await? {
+                Ok(account_state_chunk_with_proof) => {
+                    let mut response_msg = ConsensusMsg::new();
+                    let mut response = RespondAccountStateChunk::new();
+                    response.set_account_state_chunk_with_proof(
+                        account_state_chunk_with_proof.into_proto(),
+                    );
+                    response_msg.set_respond_account_state_chunk(response);
+                    let response_data = Bytes::from(
+                        response_msg
+                            .write_to_bytes()
+                            .expect("fail to serialize proto"),
+                    );
+                    Ok(response_data)
+                }
+                Err(err) => Err(RpcError::ApplicationError(err)),
+            })
+            .map_err(|_| format_err!("handling inbound rpc call timed out"))
+    }
+
+    async fn process_request_highest_ledger_info(
+        &mut self,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> failure::Result<()> {
+        debug!("Received request_highest_ledger_info RPC");
+        let (tx, rx) = oneshot::channel();
+        let request = HighestLedgerInfoRetrievalRequest {
+            response_sender: tx,
+        };
+        self.highest_ledger_info_request_tx.send(request).await?;
+        let highest_ledger_info = rx.await?;
+        let mut response_msg = ConsensusMsg::new();
+        let mut response = RespondHighestLedgerInfo::new();
+        response.set_highest_ledger_info(highest_ledger_info.into_proto());
+        response_msg.set_respond_highest_ledger_info(response);
+        let response_data = Bytes::from(
+            response_msg
+                .write_to_bytes()
+                .expect("fail to serialize proto"),
+        );
+        callback
+            .send(Ok(response_data))
+            .map_err(|_| format_err!("handling inbound rpc call timed out"))
+    }
+
+    async fn process_request_epoch_change<'a>(
+        &'a mut self,
+        msg: &'a mut ConsensusMsg,
+        callback: oneshot::Sender<Result<Bytes, RpcError>>,
+    ) -> failure::Result<()> {
+        let epoch = msg.get_request_epoch_change().get_epoch();
+        debug!("Received request_epoch_change RPC for epoch {}", epoch);
+        let (tx, rx) = oneshot::channel();
+        let request = EpochChangeRetrievalRequest {
+            epoch,
+            response_sender: tx,
+        };
+        self.epoch_change_request_tx.send(request).await?;
+        callback
+            .send(match rx.await? {
+                Ok(proof) => {
+                    let mut response_msg = ConsensusMsg::new();
+                    let mut response = RespondEpochChange::new();
+                    response.set_proof(proof.into_proto());
+                    response_msg.set_respond_epoch_change(response);
+                    let response_data = Bytes::from(
+                        response_msg
+                            .write_to_bytes()
+                            .expect("fail to serialize proto"),
+                    );
+                    Ok(response_data)
+                }
+                Err(err) => Err(RpcError::ApplicationError(err)),
+            })
+            .map_err(|_| format_err!("handling inbound rpc call timed out"))
+    }
+
     async fn process_request_block<'a>(
         &'a mut self,
         msg: &'a mut ConsensusMsg,
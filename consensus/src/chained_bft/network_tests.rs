@@ -4,20 +4,25 @@
 use crate::{
     chained_bft::{
         common::Author,
-        consensus_types::{block::Block, quorum_cert::QuorumCert},
+        consensus_types::{block::Block, epoch_info::EpochChange, quorum_cert::QuorumCert},
         liveness::proposer_election::ProposalInfo,
         network::{BlockRetrievalResponse, ConsensusNetworkImpl, NetworkReceivers},
         safety::vote_msg::VoteMsg,
-        test_utils::{consensus_runtime, placeholder_ledger_info},
+        test_utils::{
+            consensus_runtime, placeholder_certificate_in_epoch, placeholder_ledger_info,
+        },
     },
     state_replication::ExecutedState,
 };
 use channel;
-use crypto::{signing::generate_keypair, HashValue};
+use crypto::{signing::generate_keypair, x25519, HashValue};
 use futures::{channel::mpsc, executor::block_on, FutureExt, SinkExt, StreamExt, TryFutureExt};
 use network::{
     interface::{NetworkNotification, NetworkRequest},
-    proto::{BlockRetrievalStatus, ConsensusMsg, QuorumCert as ProtoQuorumCert, RequestChunk},
+    proto::{
+        BlockRetrievalStatus, ConsensusMsg, QuorumCert as ProtoQuorumCert, RequestChunk,
+        RequestHighestLedgerInfo,
+    },
     protocols::rpc::InboundRpcRequest,
     validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender},
 };
@@ -33,6 +38,8 @@ use types::{
     proto::ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{SignedTransaction, TransactionInfo, TransactionListWithProof},
+    validator_public_keys::ValidatorPublicKeys,
+    validator_set::ValidatorSet,
     validator_signer::ValidatorSigner,
     validator_verifier::ValidatorVerifier,
 };
@@ -127,6 +134,9 @@ impl NetworkPlayground {
                         .await
                         .unwrap();
                 }
+                // All the nodes of the playground are connected to each other
+                // regardless of the trusted peers of their epochs.
+                NetworkRequest::UpdateEligibleNodes(_) => {}
                 // Other NetworkRequest get buffered for `deliver_messages` to
                 // synchronously drain.
                 net_req => {
@@ -387,6 +397,136 @@ fn test_network_api() {
     });
 }
 
+#[test]
+fn test_epoch_filter() {
+    let runtime = consensus_runtime();
+    let num_nodes = 2;
+    let mut peers = Vec::new();
+    let mut receivers: Vec<NetworkReceivers<u64, Author>> = Vec::new();
+    let mut playground = NetworkPlayground::new(runtime.executor());
+    let mut nodes = Vec::new();
+    let mut author_to_public_keys = HashMap::new();
+    let mut signers = Vec::new();
+    for _ in 0..num_nodes {
+        let random_validator_signer = ValidatorSigner::random();
+        author_to_public_keys.insert(
+            random_validator_signer.author(),
+            random_validator_signer.public_key(),
+        );
+        peers.push(random_validator_signer.author());
+        signers.push(random_validator_signer);
+    }
+    let validator = Arc::new(ValidatorVerifier::new(author_to_public_keys));
+    for peer in peers.iter() {
+        let (network_reqs_tx, network_reqs_rx) = channel::new_test(8);
+        let (consensus_tx, consensus_rx) = channel::new_test(8);
+        let network_sender = ConsensusNetworkSender::new(network_reqs_tx);
+        let network_events = ConsensusNetworkEvents::new(consensus_rx);
+
+        playground.add_node(*peer, consensus_tx, network_reqs_rx);
+        let mut node = ConsensusNetworkImpl::new(
+            *peer,
+            network_sender,
+            network_events,
+            Arc::new(peers.clone()),
+            Arc::clone(&validator),
+        );
+        receivers.push(node.start(&runtime.executor()));
+        nodes.push(node);
+    }
+    let validator_set = ValidatorSet::new(
+        signers
+            .iter()
+            .map(|signer| {
+                ValidatorPublicKeys::new(
+                    signer.author(),
+                    signer.public_key(),
+                    signer.public_key(),
+                    x25519::generate_keypair().1,
+                )
+            })
+            .collect(),
+    );
+    let proof =
+        placeholder_certificate_in_epoch(signers.iter().collect(), HashValue::random(), 1, 0);
+    let epoch_change = EpochChange::new(proof.clone(), validator_set);
+    nodes[1].start_new_epoch(nodes[1].epoch_info().next(&epoch_change));
+    let stale_vote = VoteMsg::new(
+        HashValue::random(),
+        ExecutedState::state_for_genesis(),
+        1,
+        peers[0],
+        placeholder_ledger_info(),
+        &signers[0],
+    );
+    let epoch_1_ledger_info = types::ledger_info::LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        HashValue::zero(),
+        HashValue::zero(),
+        1,
+        0,
+    );
+    let vote = VoteMsg::new(
+        HashValue::random(),
+        ExecutedState::state_for_genesis(),
+        2,
+        peers[0],
+        epoch_1_ledger_info.clone(),
+        &signers[0],
+    );
+    let future_vote = VoteMsg::new(
+        HashValue::random(),
+        ExecutedState::state_for_genesis(),
+        2,
+        peers[1],
+        epoch_1_ledger_info,
+        &signers[1],
+    );
+
+    // The node in epoch 1 sends back the end of epoch 0.
+    let receiver_1 = receivers.remove(1);
+    let mut epoch_change_retrieval = receiver_1.epoch_change_retrieval;
+    let on_request_epoch_change = async move {
+        while let Some(request) = epoch_change_retrieval.next().await {
+            assert_eq!(request.epoch, 0);
+            request
+                .response_sender
+                .send(Ok(epoch_change.proof().clone()))
+                .unwrap();
+        }
+    };
+    runtime
+        .executor()
+        .spawn(on_request_epoch_change.boxed().unit_error().compat());
+    let mut receiver_1_votes = receiver_1.votes;
+    block_on(async move {
+        // The vote of epoch 0 is dropped by the node in epoch 1.
+        nodes[0].send_vote(stale_vote, vec![peers[1]]).await;
+        nodes[0].send_vote(vote.clone(), vec![peers[1]]).await;
+        playground
+            .wait_for_messages(2, NetworkPlayground::take_all)
+            .await;
+        let v = receiver_1_votes.next().await.unwrap();
+        assert_eq!(v, vote);
+
+        // The vote of epoch 1 is dropped by the node in epoch 0, which is notified about the
+        // epoch it has missed and retrieves its end from the sender.
+        nodes[1].send_vote(future_vote, vec![peers[0]]).await;
+        playground
+            .wait_for_messages(1, NetworkPlayground::take_all)
+            .await;
+        let notification = receivers[0].future_epochs.next().await.unwrap();
+        assert_eq!(notification.peer, peers[1]);
+        assert_eq!(notification.epoch, 1);
+        let retrieved_proof = nodes[0]
+            .request_epoch_change(0, peers[1], Duration::from_secs(5))
+            .await
+            .unwrap();
+        assert_eq!(retrieved_proof, proof);
+    });
+}
+
 #[test]
 fn test_rpc() {
     let runtime = consensus_runtime();
@@ -481,6 +621,7 @@ fn test_rpc() {
         .executor()
         .spawn(on_request_chunk.boxed().unit_error().compat());
 
+    let mut sender = senders[0].clone();
     block_on(async move {
         let mut ledger_info = LedgerInfo::new();
         ledger_info.set_transaction_accumulator_hash(HashValue::zero().to_vec());
@@ -513,4 +654,31 @@ fn test_rpc() {
             .unwrap();
         assert_eq!(chunk.get_txn_list_with_proof().get_transactions().len(), 1);
     });
+
+    // verify request highest ledger info rpc
+    let mut highest_ledger_info_retrieval = receiver_1.highest_ledger_info_retrieval;
+    let on_request_highest_ledger_info = async move {
+        while let Some(request) = highest_ledger_info_retrieval.next().await {
+            request
+                .response_sender
+                .send(QuorumCert::certificate_for_genesis())
+                .unwrap();
+        }
+    };
+    runtime
+        .executor()
+        .spawn(on_request_highest_ledger_info.boxed().unit_error().compat());
+
+    block_on(async move {
+        let response = sender
+            .request_highest_ledger_info(
+                peer,
+                RequestHighestLedgerInfo::new(),
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        let qc = QuorumCert::from_proto(response.get_highest_ledger_info().clone()).unwrap();
+        assert_eq!(qc, QuorumCert::certificate_for_genesis());
+    });
 }
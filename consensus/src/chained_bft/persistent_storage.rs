@@ -4,8 +4,11 @@
 use crate::{
     chained_bft::{
         common::Payload,
-        consensus_types::{block::Block, quorum_cert::QuorumCert},
-        consensusdb::ConsensusDB,
+        consensus_types::{
+            block::Block, epoch_info::EpochChange, equivocation_evidence::EquivocationEvidence,
+            quorum_cert::QuorumCert,
+        },
+        consensusdb::{ConsensusDB, EquivocationKind},
         liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
         safety::safety_rules::ConsensusState,
     },
@@ -51,6 +54,19 @@ pub trait PersistentStorage<T>: PersistentLivenessStorage + Send + Sync {
     /// Persist the consensus state.
     fn save_consensus_state(&self, state: ConsensusState) -> Result<()>;
 
+    /// Persist the evidence of an equivocating validator.
+    fn save_equivocation_evidence(&self, evidence: EquivocationEvidence<T>) -> Result<()>;
+
+    /// Get all the persisted equivocation evidence ordered by round.
+    fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence<T>>>;
+
+    /// Persist the change of the validator set that ends an epoch, so that the current epoch is
+    /// recovered upon restart.
+    fn save_epoch_change(&self, epoch_change: EpochChange) -> Result<()>;
+
+    /// Get all the persisted epoch changes ordered by epoch.
+    fn get_epoch_changes(&self) -> Result<Vec<EpochChange>>;
+
     /// When the node restart, construct the instance and returned the data read from db.
     /// This could guarantee we only read once during start, and we would panic if the
     /// read fails.
@@ -307,6 +323,40 @@ impl<T: Payload> PersistentStorage<T> for StorageWriteProxy {
         self.db.save_state(to_vec_named(&state)?)
     }
 
+    fn save_equivocation_evidence(&self, evidence: EquivocationEvidence<T>) -> Result<()> {
+        let kind = match evidence {
+            EquivocationEvidence::Vote { .. } => EquivocationKind::Vote,
+            EquivocationEvidence::Proposal { .. } => EquivocationKind::Proposal,
+        };
+        self.db.save_equivocation_evidence(
+            (evidence.round(), kind, evidence.author()),
+            to_vec_named(&evidence)?,
+        )
+    }
+
+    fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence<T>>> {
+        self.db
+            .get_equivocation_evidence()?
+            .into_iter()
+            .map(|(_, evidence)| -> Result<EquivocationEvidence<T>> {
+                Ok(from_slice(&evidence[..])?)
+            })
+            .collect()
+    }
+
+    fn save_epoch_change(&self, epoch_change: EpochChange) -> Result<()> {
+        self.db
+            .save_epoch_change(epoch_change.epoch(), to_vec_named(&epoch_change)?)
+    }
+
+    fn get_epoch_changes(&self) -> Result<Vec<EpochChange>> {
+        self.db
+            .get_epoch_changes()?
+            .into_iter()
+            .map(|(_, epoch_change)| -> Result<EpochChange> { Ok(from_slice(&epoch_change[..])?) })
+            .collect()
+    }
+
     fn start(config: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         info!("Start consensus recovery.");
         let read_client = create_storage_read_client(config);
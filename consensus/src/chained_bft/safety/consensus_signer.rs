@@ -0,0 +1,108 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::common::Author;
+use crypto::{hash::CryptoHash, Signature};
+use failure::Result;
+use secret_service::{
+    safety_rules_client::SafetyRulesClient,
+    safety_rules_data::{BlockData, TimeoutData, TimeoutMsgData, VoteData},
+};
+use types::validator_signer::ValidatorSigner;
+
+/// ConsensusSigner signs the consensus messages of a validator.
+/// The signer is given the blocks, votes and timeouts themselves and computes the ids and digests
+/// it signs. The rules that require the knowledge of the block tree are always checked by
+/// SafetyRules in the consensus process. A signer can additionally enforce the rules that only
+/// depend on the signed data (e.g., a single vote per round) in a separate process holding the
+/// consensus key.
+pub trait ConsensusSigner: Send + Sync {
+    /// The author of the signed messages.
+    fn author(&self) -> Author;
+
+    /// Signs the id of a proposed block.
+    fn sign_proposal(&self, block: &BlockData) -> Result<Signature>;
+
+    /// Signs the LedgerInfo of a vote for a proposed block.
+    /// Might refuse to sign if the validator has already voted or timed out in this round, or if
+    /// the proposed block does not extend the preferred block.
+    fn sign_vote(&self, vote: &VoteData) -> Result<Signature>;
+
+    /// Signs the digest of the timeout of a round.
+    fn sign_timeout(&self, timeout: &TimeoutData) -> Result<Signature>;
+
+    /// Signs the digest of the timeout message of a round.
+    fn sign_timeout_msg(&self, timeout_msg: &TimeoutMsgData) -> Result<Signature>;
+
+    /// The in-process key the tests sign placeholder certificates and votes with.
+    #[cfg(test)]
+    fn validator_signer(&self) -> Option<&ValidatorSigner> {
+        None
+    }
+}
+
+/// In-process signer: the voting rules are enforced by SafetyRules and the consensus state is
+/// persisted in ConsensusDB.
+impl ConsensusSigner for ValidatorSigner {
+    fn author(&self) -> Author {
+        ValidatorSigner::author(self)
+    }
+
+    fn sign_proposal(&self, block: &BlockData) -> Result<Signature> {
+        self.sign_message(block.hash())
+    }
+
+    fn sign_vote(&self, vote: &VoteData) -> Result<Signature> {
+        self.sign_message(vote.ledger_info().hash())
+    }
+
+    fn sign_timeout(&self, timeout: &TimeoutData) -> Result<Signature> {
+        self.sign_message(timeout.hash())
+    }
+
+    fn sign_timeout_msg(&self, timeout_msg: &TimeoutMsgData) -> Result<Signature> {
+        self.sign_message(timeout_msg.hash())
+    }
+
+    #[cfg(test)]
+    fn validator_signer(&self) -> Option<&ValidatorSigner> {
+        Some(self)
+    }
+}
+
+/// Signer delegating to the safety rules service of the secret service process, which holds the
+/// consensus key, refuses to sign a second vote for a round (or a vote for a round that timed
+/// out) or a vote for a block that does not extend the preferred block, and persists the last
+/// vote round and the preferred block round.
+pub struct RemoteSigner {
+    author: Author,
+    client: SafetyRulesClient,
+}
+
+impl RemoteSigner {
+    pub fn new(author: Author, client: SafetyRulesClient) -> Self {
+        Self { author, client }
+    }
+}
+
+impl ConsensusSigner for RemoteSigner {
+    fn author(&self) -> Author {
+        self.author
+    }
+
+    fn sign_proposal(&self, block: &BlockData) -> Result<Signature> {
+        self.client.sign_proposal(block.clone())
+    }
+
+    fn sign_vote(&self, vote: &VoteData) -> Result<Signature> {
+        self.client.construct_vote(vote.clone())
+    }
+
+    fn sign_timeout(&self, timeout: &TimeoutData) -> Result<Signature> {
+        self.client.sign_timeout(*timeout)
+    }
+
+    fn sign_timeout_msg(&self, timeout_msg: &TimeoutMsgData) -> Result<Signature> {
+        self.client.sign_timeout_msg(*timeout_msg)
+    }
+}
@@ -1,5 +1,6 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod consensus_signer;
 pub(crate) mod safety_rules;
 pub(crate) mod vote_msg;
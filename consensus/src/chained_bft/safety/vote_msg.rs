@@ -2,17 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    chained_bft::common::{Author, Round},
+    chained_bft::{
+        common::{Author, Round},
+        consensus_types::block::Block,
+        safety::consensus_signer::ConsensusSigner,
+    },
     state_replication::ExecutedState,
 };
-use canonical_serialization::{CanonicalSerialize, CanonicalSerializer, SimpleSerializer};
-use crypto::{
-    hash::{CryptoHash, CryptoHasher, VoteMsgHasher},
-    HashValue, Signature,
-};
+use canonical_serialization::CanonicalSerialize;
+use crypto::{hash::CryptoHash, HashValue, Signature};
 use failure::Result as ProtoResult;
 use network::proto::Vote as ProtoVote;
 use proto_conv::{FromProto, IntoProto};
+use secret_service::safety_rules_data::{self, VoteData};
 use serde::{Deserialize, Serialize};
 use std::{
     convert::TryFrom,
@@ -20,7 +22,6 @@ use std::{
 };
 use types::{
     ledger_info::LedgerInfo,
-    validator_signer::ValidatorSigner,
     validator_verifier::{ValidatorVerifier, VerifyError},
 };
 
@@ -35,37 +36,6 @@ pub enum VoteMsgVerificationError {
     SigVerifyError(VerifyError),
 }
 
-// Internal use only. Contains all the fields in VoteMsgSerializer that contributes to the
-// computation of its hash.
-struct VoteMsgSerializer {
-    proposed_block_id: HashValue,
-    executed_state: ExecutedState,
-    round: Round,
-}
-
-impl CanonicalSerialize for VoteMsgSerializer {
-    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> failure::Result<()> {
-        serializer.encode_raw_bytes(self.proposed_block_id.as_ref())?;
-        serializer.encode_struct(&self.executed_state)?;
-        serializer.encode_u64(self.round)?;
-        Ok(())
-    }
-}
-
-impl CryptoHash for VoteMsgSerializer {
-    type Hasher = VoteMsgHasher;
-
-    fn hash(&self) -> HashValue {
-        let mut state = Self::Hasher::default();
-        state.write(
-            SimpleSerializer::<Vec<u8>>::serialize(self)
-                .expect("Should serialize.")
-                .as_ref(),
-        );
-        state.finish()
-    }
-}
-
 /// VoteMsg is the struct that is ultimately sent by the voter in response for
 /// receiving a proposal.
 /// VoteMsg carries the `LedgerInfo` of a block that is going to be committed in case this vote
@@ -100,20 +70,24 @@ impl Display for VoteMsg {
 }
 
 impl VoteMsg {
+    /// Creates a vote for the block of the given id, signed with the in-process key of the signer.
+    #[cfg(test)]
     pub fn new(
         proposed_block_id: HashValue,
         executed_state: ExecutedState,
         round: Round,
         author: Author,
         mut ledger_info_placeholder: LedgerInfo,
-        validator_signer: &ValidatorSigner,
+        validator_signer: &dyn ConsensusSigner,
     ) -> Self {
         ledger_info_placeholder.set_consensus_data_hash(Self::vote_digest(
             proposed_block_id,
             executed_state,
             round,
         ));
-        let li_sig = validator_signer
+        let signature = validator_signer
+            .validator_signer()
+            .expect("The votes of the tests are signed in-process")
             .sign_message(ledger_info_placeholder.hash())
             .expect("Failed to sign LedgerInfo");
         Self {
@@ -122,10 +96,44 @@ impl VoteMsg {
             round,
             author,
             ledger_info: ledger_info_placeholder,
-            signature: li_sig,
+            signature,
         }
     }
 
+    /// Creates a vote for a block extending the given parent, fails if the signer refuses to
+    /// sign it (e.g., the remote safety rules have already signed a vote for this round).
+    pub fn try_new<T: CanonicalSerialize>(
+        block: &Block<T>,
+        parent: &Block<T>,
+        executed_state: ExecutedState,
+        author: Author,
+        ledger_info_placeholder: LedgerInfo,
+        validator_signer: &dyn ConsensusSigner,
+    ) -> failure::Result<Self> {
+        // The id of the genesis block is not the hash of its data.
+        let parent = if parent.is_genesis_block() {
+            None
+        } else {
+            Some(parent.block_data())
+        };
+        let vote = VoteData {
+            block: block.block_data(),
+            parent,
+            executed_state_id: executed_state.state_id,
+            executed_state_version: executed_state.version,
+            ledger_info: ledger_info_placeholder,
+        };
+        let signature = validator_signer.sign_vote(&vote)?;
+        Ok(Self {
+            proposed_block_id: block.id(),
+            executed_state,
+            round: block.round(),
+            author,
+            ledger_info: vote.ledger_info(),
+            signature,
+        })
+    }
+
     /// Return the proposed block id
     pub fn proposed_block_id(&self) -> HashValue {
         self.proposed_block_id
@@ -178,12 +186,12 @@ impl VoteMsg {
         executed_state: ExecutedState,
         round: Round,
     ) -> HashValue {
-        VoteMsgSerializer {
+        safety_rules_data::vote_digest(
             proposed_block_id,
-            executed_state,
+            executed_state.state_id,
+            executed_state.version,
             round,
-        }
-        .hash()
+        )
     }
 }
 
@@ -0,0 +1,720 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::{
+        block_storage::{BlockReader, BlockStore},
+        chained_bft_smr::{ChainedBftSMR, ConcurrentEventProcessor},
+        common::{Author, Round},
+        event_processor::{EventProcessor, ProcessProposalResult},
+        liveness::{
+            local_pacemaker::{ExponentialTimeInterval, LocalPacemaker},
+            proposal_generator::ProposalGenerator,
+            proposer_election::{ProposalInfo, ProposerElection},
+            rotating_proposer_election::RotatingProposer,
+        },
+        network::ConsensusNetworkImpl,
+        persistent_storage::PersistentStorage,
+        safety::safety_rules::SafetyRules,
+        test_utils::{MockStateComputer, MockStorage, MockTransactionManager, TestPayload},
+    },
+    util::time_service::{ScheduledTask, TimeService},
+};
+use channel;
+use crypto::{signing::generate_keypair_for_testing, HashValue};
+use futures::{
+    channel::{mpsc, oneshot},
+    compat::Future01CompatExt,
+    executor::{block_on, LocalPool, LocalSpawner},
+    future,
+    task::LocalSpawnExt,
+    Future, FutureExt, SinkExt, StreamExt,
+};
+use network::{
+    interface::{NetworkNotification, NetworkRequest},
+    proto::ConsensusMsg,
+    protocols::rpc::InboundRpcRequest,
+    validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender},
+};
+use proto_conv::FromProto;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{BTreeMap, HashMap},
+    pin::Pin,
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use types::{
+    account_address::AccountAddress, validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+type SMR = ChainedBftSMR<TestPayload, Author>;
+
+/// The network and timing conditions of a simulated run.
+///
+/// Before GST (global stabilization time) messages can be dropped, arbitrarily delayed and
+/// reordered, and the nodes of different partitions cannot talk to each other.
+/// After GST every message is delivered within `max_delay_after_gst`.
+#[derive(Clone, Debug)]
+pub struct SimulatorConfig {
+    pub num_nodes: usize,
+    /// Seeds the validator keys and every decision of the message scheduler: the same seed
+    /// reproduces the same run.
+    pub seed: u64,
+    /// Initial pacemaker round timeout.
+    pub round_timeout: Duration,
+    pub gst: Duration,
+    pub min_delay: Duration,
+    pub max_delay_before_gst: Duration,
+    pub max_delay_after_gst: Duration,
+    /// Probability of a message to be dropped before GST.
+    pub drop_probability: f64,
+    /// Groups of node indices isolated from each other before GST. The nodes missing from all
+    /// the groups form a group of their own.
+    pub partitions: Vec<Vec<usize>>,
+}
+
+impl Default for SimulatorConfig {
+    fn default() -> Self {
+        Self {
+            num_nodes: 4,
+            seed: 0,
+            round_timeout: Duration::from_secs(1),
+            gst: Duration::from_secs(0),
+            min_delay: Duration::from_millis(1),
+            max_delay_before_gst: Duration::from_millis(500),
+            max_delay_after_gst: Duration::from_millis(50),
+            drop_probability: 0.0,
+            partitions: vec![],
+        }
+    }
+}
+
+/// An event of a simulated run: two runs with the same config produce the same trace.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TraceEvent {
+    /// A message from `src` to `dst`, delivered at `delivery` or dropped if it's `None`.
+    Send {
+        time: Duration,
+        src: usize,
+        dst: usize,
+        kind: &'static str,
+        delivery: Option<Duration>,
+    },
+    /// A block committed by `node` (either by consensus or by state synchronization).
+    Commit {
+        time: Duration,
+        node: usize,
+        block_id: HashValue,
+    },
+}
+
+enum ScheduledEvent {
+    Task(Box<dyn ScheduledTask>),
+    Wakeup(oneshot::Sender<()>),
+    Delivery {
+        dst: usize,
+        notification: NetworkNotification,
+    },
+}
+
+struct VirtualClockInner {
+    now: Duration,
+    // Ordered by time, the events scheduled at the same time are ordered by their creation.
+    events: BTreeMap<(Duration, u64), ScheduledEvent>,
+    next_seq: u64,
+}
+
+/// The time service shared by all the simulated nodes: the time stands still until the
+/// simulator advances it to the next scheduled event.
+struct VirtualClock {
+    inner: Mutex<VirtualClockInner>,
+}
+
+impl VirtualClock {
+    fn new() -> Self {
+        Self {
+            inner: Mutex::new(VirtualClockInner {
+                now: Duration::from_secs(0),
+                events: BTreeMap::new(),
+                next_seq: 0,
+            }),
+        }
+    }
+
+    fn now(&self) -> Duration {
+        self.inner.lock().unwrap().now
+    }
+
+    fn schedule(&self, delay: Duration, event: ScheduledEvent) -> Duration {
+        let mut inner = self.inner.lock().unwrap();
+        let time = inner.now + delay;
+        let seq = inner.next_seq;
+        inner.next_seq += 1;
+        inner.events.insert((time, seq), event);
+        time
+    }
+
+    /// Advances the time to the next event scheduled not later than `end` and returns it.
+    fn advance(&self, end: Duration) -> Option<ScheduledEvent> {
+        let mut inner = self.inner.lock().unwrap();
+        let key = *inner.events.keys().next()?;
+        if key.0 > end {
+            return None;
+        }
+        inner.now = key.0;
+        inner.events.remove(&key)
+    }
+
+    fn advance_to(&self, end: Duration) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.now < end {
+            inner.now = end;
+        }
+    }
+}
+
+impl TimeService for VirtualClock {
+    fn run_after(&self, timeout: Duration, task: Box<dyn ScheduledTask>) {
+        self.schedule(timeout, ScheduledEvent::Task(task));
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        self.now()
+    }
+
+    fn sleep(&self, t: Duration) -> Pin<Box<dyn Future<Output = ()> + Send>> {
+        let (sender, receiver) = oneshot::channel();
+        self.schedule(t, ScheduledEvent::Wakeup(sender));
+        async move {
+            let _ = receiver.await;
+        }
+            .boxed()
+    }
+}
+
+/// Single threaded simulator of a network of chained BFT nodes.
+///
+/// Every node runs the production `EventProcessor` and event loops on top of
+/// `MockStateComputer`, `MockTransactionManager` and `MockStorage`, all the tasks are driven by a
+/// single `LocalPool`. The messages between the nodes go through a scheduler, which decides
+/// (using a seeded random generator) whether each of them is dropped and when it's delivered.
+/// The time is virtual: the simulator runs the tasks until they're stalled and then advances
+/// the time to the next timer or message delivery.
+///
+/// Limitations: RPC responses are delivered without a delay (the delay of an RPC covers the
+/// round trip), and the round deadlines (`Instant`) still follow the wall clock: a simulated
+/// round completes well within a real round timeout, so they never expire in practice.
+pub struct Simulator {
+    config: SimulatorConfig,
+    pool: LocalPool,
+    clock: Arc<VirtualClock>,
+    rng: StdRng,
+    authors: Vec<Author>,
+    consensus_txs: Vec<channel::Sender<NetworkNotification>>,
+    outbox: mpsc::UnboundedReceiver<(Author, NetworkRequest)>,
+    // The round and the parent of every proposed block.
+    blocks: HashMap<HashValue, (Round, HashValue)>,
+    trace: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl Simulator {
+    pub fn new(config: SimulatorConfig) -> Self {
+        assert!(
+            config.num_nodes > 1,
+            "Simulator requires at least two nodes"
+        );
+        let mut seed = [0u8; 32];
+        seed[..8].copy_from_slice(&config.seed.to_le_bytes());
+        let mut rng = StdRng::from_seed(seed);
+        let signers: Vec<_> = (0..config.num_nodes)
+            .map(|_| {
+                let (private_key, public_key) = generate_keypair_for_testing(&mut rng);
+                ValidatorSigner::new(AccountAddress::from(public_key), public_key, private_key)
+            })
+            .collect();
+        let authors: Vec<_> = signers.iter().map(ValidatorSigner::author).collect();
+        let validator = Arc::new(ValidatorVerifier::new(
+            signers
+                .iter()
+                .map(|signer| (signer.author(), signer.public_key()))
+                .collect(),
+        ));
+
+        let pool = LocalPool::new();
+        let clock = Arc::new(VirtualClock::new());
+        let (outbox_tx, outbox) = mpsc::unbounded();
+        let trace = Arc::new(Mutex::new(vec![]));
+        let consensus_txs = signers
+            .into_iter()
+            .enumerate()
+            .map(|(index, signer)| {
+                Self::start_node(
+                    &pool.spawner(),
+                    &config,
+                    index,
+                    signer,
+                    Arc::new(authors.clone()),
+                    Arc::clone(&validator),
+                    Arc::clone(&clock),
+                    outbox_tx.clone(),
+                    Arc::clone(&trace),
+                )
+            })
+            .collect();
+        Self {
+            config,
+            pool,
+            clock,
+            rng,
+            authors,
+            consensus_txs,
+            outbox,
+            blocks: HashMap::new(),
+            trace,
+        }
+    }
+
+    /// Runs the simulation until the virtual time reaches `end`.
+    pub fn run_until(&mut self, end: Duration) {
+        loop {
+            self.pool.run_until_stalled();
+            self.route_outgoing_messages();
+            let event = match self.clock.advance(end) {
+                Some(event) => event,
+                None => break,
+            };
+            let mut spawner = self.pool.spawner();
+            match event {
+                ScheduledEvent::Task(mut task) => spawn(&mut spawner, task.run()),
+                ScheduledEvent::Wakeup(sender) => {
+                    let _ = sender.send(());
+                }
+                ScheduledEvent::Delivery { dst, notification } => {
+                    let mut consensus_tx = self.consensus_txs[dst].clone();
+                    spawn(&mut spawner, async move {
+                        let _ = consensus_tx.send(notification).await;
+                    });
+                }
+            }
+        }
+        self.clock.advance_to(end);
+    }
+
+    pub fn trace(&self) -> Vec<TraceEvent> {
+        self.trace.lock().unwrap().clone()
+    }
+
+    /// The ids of the blocks committed by the given node with the commit time.
+    pub fn commits(&self, node: usize) -> Vec<(Duration, HashValue)> {
+        self.trace
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|event| match event {
+                TraceEvent::Commit {
+                    time,
+                    node: commit_node,
+                    block_id,
+                } if *commit_node == node => Some((*time, *block_id)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Asserts that all the committed blocks form a single chain: the nodes commit with
+    /// increasing rounds, no two different blocks are committed at the same round and every
+    /// committed block extends the committed blocks of the lower rounds.
+    pub fn assert_safety(&self) {
+        let mut committed = BTreeMap::new();
+        for node in 0..self.config.num_nodes {
+            let mut last_commit: Option<(Round, HashValue)> = None;
+            for (_, block_id) in self.commits(node) {
+                let round = self
+                    .blocks
+                    .get(&block_id)
+                    .unwrap_or_else(|| panic!("Unknown block {} is committed", block_id))
+                    .0;
+                if let Some((last_round, last_block_id)) = last_commit {
+                    assert!(
+                        round > last_round || block_id == last_block_id,
+                        "Node {} commits block {} at round {} after round {}",
+                        node,
+                        block_id,
+                        round,
+                        last_round
+                    );
+                }
+                last_commit = Some((round, block_id));
+                if let Some(other_block_id) = committed.insert(round, block_id) {
+                    assert_eq!(
+                        other_block_id, block_id,
+                        "Conflicting blocks are committed at round {}",
+                        round
+                    );
+                }
+            }
+        }
+        let mut previous: Option<(Round, HashValue)> = None;
+        for (round, block_id) in committed {
+            if let Some((previous_round, previous_block_id)) = previous {
+                let mut ancestor = block_id;
+                while let Some((ancestor_round, parent_id)) = self.blocks.get(&ancestor) {
+                    if *ancestor_round <= previous_round {
+                        break;
+                    }
+                    ancestor = *parent_id;
+                }
+                assert_eq!(
+                    ancestor, previous_block_id,
+                    "Committed block {} at round {} does not extend committed block {} at round {}",
+                    block_id, round, previous_block_id, previous_round
+                );
+            }
+            previous = Some((round, block_id));
+        }
+    }
+
+    fn start_node(
+        spawner: &LocalSpawner,
+        config: &SimulatorConfig,
+        index: usize,
+        signer: ValidatorSigner,
+        peers: Arc<Vec<Author>>,
+        validator: Arc<ValidatorVerifier>,
+        clock: Arc<VirtualClock>,
+        outbox: mpsc::UnboundedSender<(Author, NetworkRequest)>,
+        trace: Arc<Mutex<Vec<TraceEvent>>>,
+    ) -> channel::Sender<NetworkNotification> {
+        let author = signer.author();
+        let quorum_size = validator.quorum_size();
+        let mut spawner = spawner.clone();
+
+        let (network_reqs_tx, mut network_reqs_rx) = channel::new_test(1_024);
+        let (consensus_tx, consensus_rx) = channel::new_test(1_024);
+        let mut network = ConsensusNetworkImpl::new(
+            author,
+            ConsensusNetworkSender::new(network_reqs_tx),
+            ConsensusNetworkEvents::new(consensus_rx),
+            Arc::clone(&peers),
+            validator,
+        );
+        let (network_receivers, network_task) =
+            network.start_without_executor::<TestPayload, Author>();
+        spawn(&mut spawner, network_task);
+        spawn(&mut spawner, async move {
+            while let Some(request) = network_reqs_rx.next().await {
+                let _ = outbox.unbounded_send((author, request));
+            }
+        });
+
+        let (storage, initial_data) = MockStorage::<TestPayload>::start_for_testing();
+        let consensus_state = initial_data.state();
+        let highest_timeout_certificates = initial_data.highest_timeout_certificates().clone();
+        let (commit_cb_sender, mut commit_cb_receiver) = mpsc::unbounded();
+        let state_computer = Arc::new(MockStateComputer::new(commit_cb_sender));
+        let block_store = Arc::new(block_on(BlockStore::new(
+            storage.clone(),
+            initial_data,
+            Arc::new(signer),
+            state_computer.clone(),
+            true,
+            10,    // max pruned blocks in mem
+            false, // pipelined execution
+        )));
+        let commit_clock = Arc::clone(&clock);
+        spawn(&mut spawner, async move {
+            while let Some(ledger_info) = commit_cb_receiver.next().await {
+                trace.lock().unwrap().push(TraceEvent::Commit {
+                    time: commit_clock.now(),
+                    node: index,
+                    block_id: ledger_info.ledger_info().consensus_block_id(),
+                });
+            }
+        });
+
+        let mut txn_manager = MockTransactionManager::new();
+        let txn_commits = txn_manager.take_commit_receiver();
+        spawn(&mut spawner, txn_commits.for_each(|_| future::ready(())));
+        let txn_manager = Arc::new(txn_manager);
+        let proposal_generator = ProposalGenerator::new(
+            block_store.clone(),
+            txn_manager.clone(),
+            clock.clone(),
+            1,
+            0,
+            true,
+        );
+        let safety_rules = Arc::new(RwLock::new(SafetyRules::new(
+            block_store.clone(),
+            consensus_state,
+        )));
+
+        let (external_timeout_sender, external_timeout_receiver) = channel::new_test(1_024);
+        let (new_round_events_sender, new_round_events_receiver) = channel::new_test(1_024);
+        let (pacemaker, timeout_processing_loop) = LocalPacemaker::new_without_executor(
+            storage.persistent_liveness_storage(),
+            Box::new(ExponentialTimeInterval::new(config.round_timeout, 1.5, 6)),
+            safety_rules.read().unwrap().last_committed_round(),
+            block_store.highest_certified_block().round(),
+            clock.clone(),
+            new_round_events_sender,
+            external_timeout_sender,
+            quorum_size,
+            highest_timeout_certificates,
+        );
+        spawn(&mut spawner, timeout_processing_loop);
+
+        let (winning_proposals_sender, winning_proposals_receiver) = channel::new_test(1_024);
+        let proposer_election: Arc<dyn ProposerElection<TestPayload, Author> + Send + Sync> =
+            Arc::new(RotatingProposer::new(
+                peers.to_vec(),
+                1,
+                winning_proposals_sender,
+            ));
+        let (epoch_change_sender, _) = channel::new_test(1_024);
+        let event_processor = Arc::new(futures_locks::RwLock::new(EventProcessor::new(
+            author,
+            block_store,
+            Arc::new(pacemaker),
+            proposer_election,
+            proposal_generator,
+            safety_rules,
+            state_computer,
+            txn_manager,
+            network.clone(),
+            epoch_change_sender,
+            storage,
+            clock,
+            true,
+        )));
+
+        spawn(
+            &mut spawner,
+            SMR::process_new_round_events(new_round_events_receiver, event_processor.clone()),
+        );
+        let process_proposals = Self::process_proposals(
+            spawner.clone(),
+            network_receivers.proposals,
+            event_processor.clone(),
+        );
+        spawn(&mut spawner, process_proposals);
+        spawn(
+            &mut spawner,
+            SMR::process_winning_proposals(winning_proposals_receiver, event_processor.clone()),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_block_retrievals(
+                network_receivers.block_retrieval,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_chunk_retrievals(
+                network_receivers.chunk_retrieval,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_account_state_chunk_retrievals(
+                network_receivers.account_state_chunk_retrieval,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_highest_ledger_info_retrievals(
+                network_receivers.highest_ledger_info_retrieval,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_votes(
+                network_receivers.votes,
+                event_processor.clone(),
+                network.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_timeout_msg(network_receivers.timeout_msgs, event_processor.clone()),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_timeout_certificates(
+                network_receivers.timeout_certificates,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_future_epochs(network_receivers.future_epochs, event_processor.clone()),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_epoch_change_retrievals(
+                network_receivers.epoch_change_retrieval,
+                event_processor.clone(),
+            ),
+        );
+        spawn(
+            &mut spawner,
+            SMR::process_outgoing_pacemaker_timeouts(
+                external_timeout_receiver,
+                event_processor,
+                network,
+            ),
+        );
+        consensus_tx
+    }
+
+    /// The same as `ChainedBftSMR::process_proposals`, but spawns the block retrieval and state
+    /// synchronization tasks on the simulator pool.
+    async fn process_proposals(
+        mut spawner: LocalSpawner,
+        mut receiver: channel::Receiver<ProposalInfo<TestPayload, Author>>,
+        event_processor: ConcurrentEventProcessor<TestPayload, Author>,
+    ) {
+        while let Some(proposal_info) = receiver.next().await {
+            let guard = event_processor.read().compat().await.unwrap();
+            match guard.process_proposal(proposal_info).await {
+                ProcessProposalResult::Done => (),
+                ProcessProposalResult::NeedFetch(deadline, proposal) => spawn(
+                    &mut spawner,
+                    SMR::fetch_and_process_proposal(
+                        Arc::clone(&event_processor),
+                        deadline,
+                        proposal,
+                    ),
+                ),
+                ProcessProposalResult::NeedSync(deadline, proposal) => spawn(
+                    &mut spawner,
+                    SMR::sync_and_process_proposal(
+                        Arc::clone(&event_processor),
+                        deadline,
+                        proposal,
+                    ),
+                ),
+            }
+        }
+    }
+
+    /// Moves the messages sent by the nodes into the schedule of deliveries.
+    fn route_outgoing_messages(&mut self) {
+        while let Ok(Some((src, request))) = self.outbox.try_next() {
+            let src = self.index_of(src);
+            let (dst, notification, kind) = match request {
+                NetworkRequest::SendMessage(dst, msg) => {
+                    let mut consensus_msg: ConsensusMsg =
+                        ::protobuf::parse_from_bytes(msg.mdata.as_ref()).unwrap();
+                    if consensus_msg.has_proposal() {
+                        let proposal = ProposalInfo::<TestPayload, Author>::from_proto(
+                            consensus_msg.take_proposal(),
+                        )
+                        .unwrap();
+                        self.blocks.insert(
+                            proposal.proposal.id(),
+                            (proposal.proposal.round(), proposal.proposal.parent_id()),
+                        );
+                    }
+                    (
+                        self.index_of(dst.into()),
+                        NetworkNotification::RecvMessage(self.authors[src].into(), msg),
+                        message_kind(&consensus_msg),
+                    )
+                }
+                // Dropping an RPC request fails the RPC of the sender.
+                NetworkRequest::SendRpc(dst, request) => {
+                    let consensus_msg: ConsensusMsg =
+                        ::protobuf::parse_from_bytes(request.data.as_ref()).unwrap();
+                    (
+                        self.index_of(dst.into()),
+                        NetworkNotification::RecvRpc(
+                            self.authors[src].into(),
+                            InboundRpcRequest {
+                                protocol: request.protocol,
+                                data: request.data,
+                                res_tx: request.res_tx,
+                            },
+                        ),
+                        message_kind(&consensus_msg),
+                    )
+                }
+                request => panic!("[simulator] Unexpected NetworkRequest: {:?}", request),
+            };
+            let delivery = self.link_delay(src, dst).map(|delay| {
+                self.clock
+                    .schedule(delay, ScheduledEvent::Delivery { dst, notification })
+            });
+            self.trace.lock().unwrap().push(TraceEvent::Send {
+                time: self.clock.now(),
+                src,
+                dst,
+                kind,
+                delivery,
+            });
+        }
+    }
+
+    /// Returns the delay of a message sent now from `src` to `dst`, `None` if it's dropped.
+    fn link_delay(&mut self, src: usize, dst: usize) -> Option<Duration> {
+        let max_delay = if self.clock.now() < self.config.gst {
+            if self.partition_of(src) != self.partition_of(dst)
+                || self.rng.gen_bool(self.config.drop_probability)
+            {
+                return None;
+            }
+            self.config.max_delay_before_gst
+        } else {
+            self.config.max_delay_after_gst
+        };
+        let delay_us = self.rng.gen_range(
+            self.config.min_delay.as_micros() as u64,
+            max_delay.as_micros() as u64 + 1,
+        );
+        Some(Duration::from_micros(delay_us))
+    }
+
+    fn partition_of(&self, node: usize) -> Option<usize> {
+        self.config
+            .partitions
+            .iter()
+            .position(|partition| partition.contains(&node))
+    }
+
+    fn index_of(&self, author: Author) -> usize {
+        self.authors
+            .iter()
+            .position(|a| *a == author)
+            .unwrap_or_else(|| panic!("[simulator] Unknown author {}", author))
+    }
+}
+
+fn spawn(spawner: &mut LocalSpawner, task: impl Future<Output = ()> + 'static) {
+    spawner
+        .spawn_local(task)
+        .expect("Failed to spawn a simulator task")
+}
+
+fn message_kind(msg: &ConsensusMsg) -> &'static str {
+    if msg.has_proposal() {
+        "proposal"
+    } else if msg.has_vote() {
+        "vote"
+    } else if msg.has_timeout_msg() {
+        "timeout_msg"
+    } else if msg.has_request_block() {
+        "request_block"
+    } else if msg.has_request_chunk() {
+        "request_chunk"
+    } else if msg.has_timeout_certificate() {
+        "timeout_certificate"
+    } else {
+        "unknown"
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::chained_bft::simulator::{Simulator, SimulatorConfig, TraceEvent};
+use std::time::Duration;
+
+#[test]
+/// Without network faults every node keeps committing.
+fn simulator_synchronous_network() {
+    let config = SimulatorConfig::default();
+    let mut simulator = Simulator::new(config.clone());
+    simulator.run_until(Duration::from_secs(30));
+    simulator.assert_safety();
+    for node in 0..config.num_nodes {
+        assert!(simulator.commits(node).len() >= 10);
+    }
+}
+
+#[test]
+/// Drops, long delays (longer than the round timeout) and reordering never break safety.
+fn simulator_safety_under_message_loss_and_reordering() {
+    for seed in 0..5 {
+        let config = SimulatorConfig {
+            seed,
+            gst: Duration::from_secs(1_000),
+            max_delay_before_gst: Duration::from_secs(3),
+            drop_probability: 0.3,
+            ..SimulatorConfig::default()
+        };
+        let mut simulator = Simulator::new(config);
+        simulator.run_until(Duration::from_secs(120));
+        simulator.assert_safety();
+    }
+}
+
+#[test]
+/// No quorum can be formed while the network is split in halves, the nodes start committing
+/// again once the partition heals at GST.
+fn simulator_liveness_after_partition_heals() {
+    let gst = Duration::from_secs(20);
+    let config = SimulatorConfig {
+        seed: 7,
+        gst,
+        drop_probability: 0.1,
+        partitions: vec![vec![0, 1], vec![2, 3]],
+        ..SimulatorConfig::default()
+    };
+    let mut simulator = Simulator::new(config.clone());
+    simulator.run_until(gst);
+    for node in 0..config.num_nodes {
+        assert!(simulator.commits(node).is_empty());
+    }
+    simulator.run_until(gst + Duration::from_secs(60));
+    simulator.assert_safety();
+    for node in 0..config.num_nodes {
+        assert!(simulator.commits(node).iter().any(|(time, _)| *time > gst));
+    }
+}
+
+#[test]
+/// Running the same config twice reproduces exactly the same messages and commits.
+fn simulator_same_seed_reproduces_run() {
+    let config = SimulatorConfig {
+        seed: 42,
+        gst: Duration::from_secs(10),
+        max_delay_before_gst: Duration::from_secs(2),
+        drop_probability: 0.2,
+        ..SimulatorConfig::default()
+    };
+    let run = || {
+        let mut simulator = Simulator::new(config.clone());
+        simulator.run_until(Duration::from_secs(30));
+        simulator.assert_safety();
+        simulator.trace()
+    };
+    let trace = run();
+    assert!(trace.iter().any(|event| match event {
+        TraceEvent::Commit { .. } => true,
+        _ => false,
+    }));
+    assert_eq!(trace, run());
+}
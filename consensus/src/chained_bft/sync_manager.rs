@@ -18,14 +18,17 @@ use crypto::HashValue;
 use failure::{Fail, Result};
 use logger::prelude::*;
 use network::proto::BlockRetrievalStatus;
-use rand::{prelude::*, Rng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::{
     clone::Clone,
     sync::Arc,
     time::{Duration, Instant},
 };
 use termion::color::*;
-use types::{account_address::AccountAddress, transaction::TransactionListWithProof};
+use types::{
+    account_address::AccountAddress, account_state_blob::AccountStateChunkWithProof,
+    transaction::TransactionListWithProof,
+};
 
 /// SyncManager is responsible for fetching dependencies and 'catching up' for given qc/ledger info
 pub struct SyncManager<T> {
@@ -104,6 +107,18 @@ where
             .await
     }
 
+    /// Get a chunk of account states at the given version
+    pub async fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: Option<HashValue>,
+        batch_size: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        self.state_computer
+            .get_account_state_chunk(version, start_key, batch_size)
+            .await
+    }
+
     pub async fn execute_and_insert_block(
         &self,
         block: Block<T>,
@@ -130,6 +145,7 @@ where
             network,
             deadline,
             preferred_peer,
+            author: self.block_store.signer().author(),
         };
         let mut retrieve_qc = qc.clone();
         loop {
@@ -201,6 +217,7 @@ where
             network,
             deadline,
             preferred_peer: peer,
+            author: self.block_store.signer().author(),
         };
         let mut blocks = retriever
             .retrieve_block_for_qc(&highest_ledger_info, 3)
@@ -256,6 +273,8 @@ struct BlockRetriever {
     network: ConsensusNetworkImpl,
     deadline: Instant,
     preferred_peer: Author,
+    // Together with the block id seeds the order in which the other peers are tried.
+    author: Author,
 }
 
 #[derive(Debug, Fail)]
@@ -284,7 +303,9 @@ impl BlockRetriever {
     /// The first attempt of block retrieval will always be sent to preferred_peer to allow the
     /// leader to drive quorum certificate creation The other peers from the quorum certificate
     /// will be randomly tried next.  If all members of the quorum certificate are exhausted, an
-    /// error is returned
+    /// error is returned.
+    /// The random order is seeded by the block id and the local author: the retries of a
+    /// validator are reproducible, while different validators still spread their requests.
     pub async fn retrieve_block_for_qc<'a, T>(
         &'a mut self,
         qc: &'a QuorumCert,
@@ -295,6 +316,9 @@ impl BlockRetriever {
     {
         let block_id = qc.certified_block_id();
         let mut peers: Vec<&AccountAddress> = qc.ledger_info().signatures().keys().collect();
+        // The order of the signatures is arbitrary.
+        peers.sort();
+        let mut rng = self.peer_order_rng(block_id);
         let mut attempt = 0_u32;
         loop {
             if peers.is_empty() {
@@ -304,7 +328,7 @@ impl BlockRetriever {
                 );
                 return Err(BlockRetrieverError::AllPeersFailed);
             }
-            let peer = self.pick_peer(attempt, &mut peers);
+            let peer = self.pick_peer(attempt, &mut peers, &mut rng);
             attempt += 1;
 
             let timeout = retrieval_timeout(&self.deadline, attempt);
@@ -349,7 +373,20 @@ impl BlockRetriever {
         }
     }
 
-    fn pick_peer(&self, attempt: u32, peers: &mut Vec<&AccountAddress>) -> AccountAddress {
+    fn peer_order_rng(&self, block_id: HashValue) -> StdRng {
+        let mut seed = [0u8; 32];
+        for (i, byte) in seed.iter_mut().enumerate() {
+            *byte = block_id.as_ref()[i] ^ self.author.as_ref()[i];
+        }
+        StdRng::from_seed(seed)
+    }
+
+    fn pick_peer(
+        &self,
+        attempt: u32,
+        peers: &mut Vec<&AccountAddress>,
+        rng: &mut StdRng,
+    ) -> AccountAddress {
         assert!(!peers.is_empty(), "pick_peer on empty peer list");
 
         if attempt == 0 {
@@ -363,7 +400,7 @@ impl BlockRetriever {
             }
             return self.preferred_peer;
         }
-        let peer_idx = thread_rng().gen_range(0, peers.len());
+        let peer_idx = rng.gen_range(0, peers.len());
         *peers.remove(peer_idx)
     }
 }
@@ -6,21 +6,87 @@ use crate::{
     state_replication::{StateComputeResult, StateComputer},
     state_synchronizer::SyncStatus,
 };
-use crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, HashValue};
+use crypto::{
+    hash::{ACCUMULATOR_PLACEHOLDER_HASH, GENESIS_BLOCK_ID},
+    HashValue,
+};
 use failure::Result;
 use futures::{channel::mpsc, Future, FutureExt};
 use logger::prelude::*;
-use std::pin::Pin;
+use std::{
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Condvar, Mutex,
+    },
+};
 use termion::color::*;
-use types::{ledger_info::LedgerInfoWithSignatures, transaction::TransactionListWithProof};
+use types::{
+    account_state_blob::AccountStateChunkWithProof, ledger_info::LedgerInfoWithSignatures,
+    transaction::TransactionListWithProof, validator_set::ValidatorSet,
+};
+
+/// Holds back the execution of the blocks until it is opened: lets the tests check what happens
+/// before the blocks are executed without depending on timing.
+#[derive(Default)]
+pub struct ExecutionGate {
+    open: Mutex<bool>,
+    opened: Condvar,
+}
+
+impl ExecutionGate {
+    /// Lets the pending and the future executions run.
+    pub fn open(&self) {
+        *self.open.lock().unwrap() = true;
+        self.opened.notify_all();
+    }
+
+    fn wait(&self) {
+        let mut open = self.open.lock().unwrap();
+        while !*open {
+            open = self.opened.wait(open).unwrap();
+        }
+    }
+}
 
 pub struct MockStateComputer {
     commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures>,
+    // The blocks extending the genesis block change the validator set.
+    reconfiguration: Option<ValidatorSet>,
+    // Whether a block following the genesis block is committed (or synced to), i.e., whether
+    // the committed state includes the reconfiguration.
+    past_genesis: AtomicBool,
+    // Holds back the execution of the blocks.
+    execution_gate: Option<Arc<ExecutionGate>>,
 }
 
 impl MockStateComputer {
     pub fn new(commit_callback: mpsc::UnboundedSender<LedgerInfoWithSignatures>) -> Self {
-        MockStateComputer { commit_callback }
+        MockStateComputer {
+            commit_callback,
+            reconfiguration: None,
+            past_genesis: AtomicBool::new(false),
+            execution_gate: None,
+        }
+    }
+
+    /// The execution of the blocks extending the genesis block emits `validators` for the next
+    /// epoch: every later committed state publishes `validators`.
+    pub fn with_reconfiguration(mut self, validators: ValidatorSet) -> Self {
+        self.reconfiguration = Some(validators);
+        self
+    }
+
+    fn record_commit(&self, commit: &LedgerInfoWithSignatures) {
+        if commit.ledger_info().consensus_block_id() != *GENESIS_BLOCK_ID {
+            self.past_genesis.store(true, Ordering::SeqCst);
+        }
+    }
+
+    /// The execution of every block blocks the executing thread until `execution_gate` is open.
+    pub fn with_execution_gate(mut self, execution_gate: Arc<ExecutionGate>) -> Self {
+        self.execution_gate = Some(execution_gate);
+        self
     }
 }
 
@@ -28,16 +94,23 @@ impl StateComputer for MockStateComputer {
     type Payload = Vec<usize>;
     fn compute(
         &self,
-        _parent_id: HashValue,
+        parent_id: HashValue,
         _block_id: HashValue,
         _transactions: &Self::Payload,
     ) -> Pin<Box<dyn Future<Output = Result<StateComputeResult>> + Send>> {
+        let validators = match &self.reconfiguration {
+            Some(validators) if parent_id == *GENESIS_BLOCK_ID => Some(validators.clone()),
+            _ => None,
+        };
+        if let Some(execution_gate) = &self.execution_gate {
+            execution_gate.wait();
+        }
         async move {
             Ok(StateComputeResult {
                 new_state_id: *ACCUMULATOR_PLACEHOLDER_HASH,
                 compute_status: vec![],
                 num_successful_txns: 0,
-                validators: None,
+                validators,
             })
         }
             .boxed()
@@ -47,6 +120,7 @@ impl StateComputer for MockStateComputer {
         &self,
         commit: LedgerInfoWithSignatures,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.record_commit(&commit);
         self.commit_callback
             .unbounded_send(commit)
             .expect("Fail to notify about commit.");
@@ -63,6 +137,7 @@ impl StateComputer for MockStateComputer {
             Fg(Reset),
             commit.ledger_info().ledger_info().consensus_block_id()
         );
+        self.record_commit(commit.ledger_info());
         self.commit_callback
             .unbounded_send(commit.ledger_info().clone())
             .expect("Fail to notify about sync");
@@ -77,4 +152,24 @@ impl StateComputer for MockStateComputer {
     ) -> Pin<Box<dyn Future<Output = Result<TransactionListWithProof>> + Send>> {
         async move { Err(format_err!("not implemented")) }.boxed()
     }
+
+    fn get_account_state_chunk(
+        &self,
+        _: u64,
+        _: Option<HashValue>,
+        _: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>> {
+        async move { Err(format_err!("not implemented")) }.boxed()
+    }
+
+    fn get_validator_set(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<ValidatorSet>>> + Send>> {
+        let validators = if self.past_genesis.load(Ordering::SeqCst) {
+            self.reconfiguration.clone()
+        } else {
+            None
+        };
+        async move { Ok(validators) }.boxed()
+    }
 }
@@ -3,7 +3,10 @@
 
 use crate::chained_bft::{
     common::Payload,
-    consensus_types::{block::Block, quorum_cert::QuorumCert},
+    consensus_types::{
+        block::Block, epoch_info::EpochChange, equivocation_evidence::EquivocationEvidence,
+        quorum_cert::QuorumCert,
+    },
     liveness::pacemaker_timeout_manager::HighestTimeoutCertificates,
     persistent_storage::{PersistentLivenessStorage, PersistentStorage, RecoveryData},
     safety::safety_rules::ConsensusState,
@@ -21,6 +24,8 @@ pub struct MockSharedStorage<T> {
     pub block: Mutex<HashMap<HashValue, Block<T>>>,
     pub qc: Mutex<HashMap<HashValue, QuorumCert>>,
     pub state: Mutex<ConsensusState>,
+    pub equivocation_evidence: Mutex<Vec<EquivocationEvidence<T>>>,
+    pub epoch_changes: Mutex<Vec<EpochChange>>,
 
     // Liveness state
     pub highest_timeout_certificates: Mutex<HighestTimeoutCertificates>,
@@ -141,11 +146,43 @@ impl<T: Payload> PersistentStorage<T> for MockStorage<T> {
         Ok(())
     }
 
+    fn save_equivocation_evidence(&self, evidence: EquivocationEvidence<T>) -> Result<()> {
+        self.shared_storage
+            .equivocation_evidence
+            .lock()
+            .unwrap()
+            .push(evidence);
+        Ok(())
+    }
+
+    fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence<T>>> {
+        Ok(self
+            .shared_storage
+            .equivocation_evidence
+            .lock()
+            .unwrap()
+            .clone())
+    }
+
+    fn save_epoch_change(&self, epoch_change: EpochChange) -> Result<()> {
+        let mut epoch_changes = self.shared_storage.epoch_changes.lock().unwrap();
+        epoch_changes.retain(|change| change.epoch() != epoch_change.epoch());
+        epoch_changes.push(epoch_change);
+        epoch_changes.sort_by_key(EpochChange::epoch);
+        Ok(())
+    }
+
+    fn get_epoch_changes(&self) -> Result<Vec<EpochChange>> {
+        Ok(self.shared_storage.epoch_changes.lock().unwrap().clone())
+    }
+
     fn start(_config: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         let shared_storage = Arc::new(MockSharedStorage {
             block: Mutex::new(HashMap::new()),
             qc: Mutex::new(HashMap::new()),
             state: Mutex::new(ConsensusState::default()),
+            equivocation_evidence: Mutex::new(vec![]),
+            epoch_changes: Mutex::new(vec![]),
             highest_timeout_certificates: Mutex::new(HighestTimeoutCertificates::new(None, None)),
         });
         let storage = MockStorage {
@@ -198,6 +235,22 @@ impl<T: Payload> PersistentStorage<T> for EmptyStorage {
         Ok(())
     }
 
+    fn save_equivocation_evidence(&self, _: EquivocationEvidence<T>) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_equivocation_evidence(&self) -> Result<Vec<EquivocationEvidence<T>>> {
+        Ok(vec![])
+    }
+
+    fn save_epoch_change(&self, _: EpochChange) -> Result<()> {
+        Ok(())
+    }
+
+    fn get_epoch_changes(&self) -> Result<Vec<EpochChange>> {
+        Ok(vec![])
+    }
+
     fn start(_: &NodeConfig) -> (Arc<Self>, RecoveryData<T>) {
         let genesis = Block::make_genesis_block();
         let genesis_qc = QuorumCert::certificate_for_genesis();
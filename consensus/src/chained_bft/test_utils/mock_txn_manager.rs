@@ -53,6 +53,7 @@ impl TxnManager for MockTransactionManager {
     fn pull_txns(
         &self,
         max_size: u64,
+        _max_priority_size: u64,
         _exclude_txns: Vec<&Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<Self::Payload>> + Send>> {
         let next_value = self.next_val.load(Ordering::SeqCst);
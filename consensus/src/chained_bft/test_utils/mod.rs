@@ -6,7 +6,7 @@ use crate::{
         block_storage::BlockStore,
         common::Round,
         consensus_types::{block::Block, quorum_cert::QuorumCert},
-        safety::vote_msg::VoteMsg,
+        safety::{consensus_signer::ConsensusSigner, vote_msg::VoteMsg},
     },
     state_replication::ExecutedState,
 };
@@ -26,7 +26,7 @@ mod mock_state_computer;
 mod mock_storage;
 mod mock_txn_manager;
 
-pub use mock_state_computer::MockStateComputer;
+pub use mock_state_computer::{ExecutionGate, MockStateComputer};
 pub use mock_storage::{EmptyStorage, MockStorage};
 pub use mock_txn_manager::MockTransactionManager;
 
@@ -45,10 +45,11 @@ pub fn build_empty_tree_with_custom_signing(
     Arc::new(block_on(BlockStore::new(
         storage,
         initial_data,
-        my_signer,
+        Arc::new(my_signer),
         Arc::new(MockStateComputer::new(commit_cb_sender)),
         true,
-        10, // max pruned blocks in mem
+        10,    // max pruned blocks in mem
+        false, // pipelined execution
     )))
 }
 
@@ -75,7 +76,7 @@ impl TreeInserter {
     ) -> Arc<Block<Vec<usize>>> {
         // Node must carry a QC to its parent
         let parent_qc = placeholder_certificate_for_block(
-            vec![self.block_store.signer().clone()],
+            vec![self.block_store.signer()],
             parent.id(),
             parent.round(),
         );
@@ -113,10 +114,15 @@ impl TreeInserter {
         } else {
             0
         };
-        let parent_qc = placeholder_certificate_for_block(qc_signers, block.parent_id(), new_round);
+        let parent_qc = placeholder_certificate_for_block(
+            qc_signers.iter().collect(),
+            block.parent_id(),
+            new_round,
+        );
         let new_block = Block::new_internal(
             block.get_payload().clone(),
             block.parent_id(),
+            block.epoch(),
             block.round(),
             block.height(),
             block.timestamp_usecs(),
@@ -138,10 +144,20 @@ pub fn placeholder_ledger_info() -> LedgerInfo {
     )
 }
 
-pub fn placeholder_certificate_for_block(
-    signers: Vec<ValidatorSigner>,
+pub fn placeholder_certificate_for_block<S: ConsensusSigner + ?Sized>(
+    signers: Vec<&S>,
     certified_block_id: HashValue,
     certified_block_round: u64,
+) -> QuorumCert {
+    placeholder_certificate_in_epoch(signers, certified_block_id, certified_block_round, 0)
+}
+
+/// A placeholder certificate signed by the validators of the given epoch.
+pub fn placeholder_certificate_in_epoch<S: ConsensusSigner + ?Sized>(
+    signers: Vec<&S>,
+    certified_block_id: HashValue,
+    certified_block_round: u64,
+    epoch: u64,
 ) -> QuorumCert {
     // Assuming executed state to be Genesis state.
     let certified_block_state = ExecutedState::state_for_genesis();
@@ -153,12 +169,20 @@ pub fn placeholder_certificate_for_block(
 
     // This ledger info doesn't carry any meaningful information: it is all zeros except for
     // the consensus data hash that carries the actual vote.
-    let mut ledger_info_placeholder = placeholder_ledger_info();
-    ledger_info_placeholder.set_consensus_data_hash(consensus_data_hash);
+    let ledger_info_placeholder = LedgerInfo::new(
+        0,
+        HashValue::zero(),
+        consensus_data_hash,
+        HashValue::zero(),
+        epoch,
+        0,
+    );
 
     let mut signatures = HashMap::new();
     for signer in signers {
         let li_sig = signer
+            .validator_signer()
+            .expect("Placeholder certificates are signed in-process")
             .sign_message(ledger_info_placeholder.hash())
             .expect("Failed to sign LedgerInfo");
         signatures.insert(signer.author(), li_sig);
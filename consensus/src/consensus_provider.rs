@@ -1,11 +1,15 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use config::config::NodeConfig;
+use config::config::{NodeConfig, RoleType};
+use debug_interface::node_debug_service::EquivocationEvidenceSource;
 use failure::prelude::*;
 use network::validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender};
 
-use crate::chained_bft::chained_bft_consensus_provider::ChainedBftProvider;
+use crate::{
+    chained_bft::chained_bft_consensus_provider::ChainedBftProvider,
+    full_node_consensus_provider::FullNodeProvider,
+};
 use execution_proto::proto::execution_grpc::ExecutionClient;
 use grpcio::{ChannelBuilder, EnvBuilder};
 use mempool::proto::mempool_grpc::MempoolClient;
@@ -23,21 +27,32 @@ pub trait ConsensusProvider {
 
     /// Stop the consensus operations. The function returns after graceful shutdown.
     fn stop(&mut self);
+
+    /// Returns the source of the evidence of the equivocating validators detected by consensus.
+    fn equivocation_evidence(&self) -> Arc<dyn EquivocationEvidenceSource>;
 }
 
-/// Helper function to create a ConsensusProvider based on configuration
+/// Helper function to create a ConsensusProvider based on configuration: validators run
+/// LibraBFT, full nodes follow the validators via the state synchronizer.
 pub fn make_consensus_provider(
     node_config: &NodeConfig,
     network_sender: ConsensusNetworkSender,
     network_receiver: ConsensusNetworkEvents,
 ) -> Box<dyn ConsensusProvider> {
-    Box::new(ChainedBftProvider::new(
-        node_config,
-        network_sender,
-        network_receiver,
-        create_mempool_client(node_config),
-        create_execution_client(node_config),
-    ))
+    match node_config.base.get_role() {
+        RoleType::Validator => Box::new(ChainedBftProvider::new(
+            node_config,
+            network_sender,
+            network_receiver,
+            create_mempool_client(node_config),
+            create_execution_client(node_config),
+        )),
+        RoleType::FullNode => Box::new(FullNodeProvider::new(
+            node_config,
+            network_sender,
+            network_receiver,
+        )),
+    }
 }
 /// Create a mempool client assuming the mempool is running on localhost
 fn create_mempool_client(config: &NodeConfig) -> Arc<MempoolClient> {
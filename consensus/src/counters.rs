@@ -44,6 +44,14 @@ pub static ref SUCCESS_TXNS_COUNT: IntCounter = OP_COUNTERS.counter("success_txn
 /// FAILED_TXNS_COUNT + SUCCESS_TXN_COUNT == COMMITTED_TXNS_COUNT
 pub static ref FAILED_TXNS_COUNT: IntCounter = OP_COUNTERS.counter("failed_txns_count");
 
+/// Count of the detected equivocating votes (two different votes of a validator in the same
+/// round) since last restart.
+pub static ref EQUIVOCATING_VOTES_COUNT: IntCounter = OP_COUNTERS.counter("equivocating_votes_count");
+
+/// Count of the detected equivocating proposals (two different proposals of a validator in the
+/// same round) since last restart.
+pub static ref EQUIVOCATING_PROPOSALS_COUNT: IntCounter = OP_COUNTERS.counter("equivocating_proposals_count");
+
 //////////////////////
 // PACEMAKER COUNTERS
 //////////////////////
@@ -62,6 +70,12 @@ pub static ref TIMEOUT_COUNT: IntCounter = OP_COUNTERS.counter("timeout_count");
 /// The timeout of the current round.
 pub static ref ROUND_TIMEOUT_MS: IntGauge = OP_COUNTERS.gauge("round_timeout_ms");
 
+/// Latency of the rounds that gathered a QC (drives the adaptive round timeouts).
+pub static ref ROUND_LATENCY_MS: Histogram = OP_COUNTERS.histogram("round_latency_ms");
+
+/// Count of the timeout certificates forwarded to the lagging validators.
+pub static ref TIMEOUT_CERTIFICATES_FORWARDED_COUNT: IntCounter = OP_COUNTERS.counter("timeout_certificates_forwarded_count");
+
 ////////////////////////
 // SYNCMANAGER COUNTERS
 ////////////////////////
@@ -72,6 +86,10 @@ pub static ref STATE_SYNC_COUNT: IntCounter = OP_COUNTERS.counter("state_sync_co
 /// Large values mean that a node has been significantly behind and had to replay a lot of txns.
 pub static ref STATE_SYNC_TXN_REPLAYED: IntCounter = OP_COUNTERS.counter("state_sync_txns_replayed");
 
+/// Count the overall number of account states state synchronizer has restored from state
+/// snapshots since last restart.
+pub static ref STATE_SYNC_ACCOUNT_STATES_RESTORED: IntCounter = OP_COUNTERS.counter("state_sync_account_states_restored");
+
 /// Count the number of block retrieval requests issued since last restart.
 pub static ref BLOCK_RETRIEVAL_COUNT: IntCounter = OP_COUNTERS.counter("block_retrieval_count");
 
@@ -81,6 +99,15 @@ pub static ref BLOCK_RETRIEVAL_DURATION_MS: Histogram = OP_COUNTERS.histogram("b
 /// Histogram of state sync duration.
 pub static ref STATE_SYNC_DURATION_MS: Histogram = OP_COUNTERS.histogram("state_sync_duration_ms");
 
+//////////////////////
+// FULL NODE COUNTERS
+//////////////////////
+/// The latest version a full node has synced to from the validators.
+pub static ref FULL_NODE_SYNCED_VERSION: IntGauge = OP_COUNTERS.gauge("full_node_synced_version");
+
+/// Count the number of failed attempts of a full node to fetch a verified highest ledger info.
+pub static ref FULL_NODE_LEDGER_INFO_POLL_FAILURES: IntCounter = OP_COUNTERS.counter("full_node_ledger_info_poll_failures");
+
 //////////////////////
 // RECONFIGURATION COUNTERS
 //////////////////////
@@ -99,6 +126,9 @@ pub static ref CURRENT_EPOCH_QUORUM_SIZE: IntGauge = OP_COUNTERS.gauge("current_
 /// In a "happy path" with no collisions and timeouts, should be equal to 3 or 4.
 pub static ref NUM_BLOCKS_IN_TREE: IntGauge = OP_COUNTERS.gauge("num_blocks_in_tree");
 
+/// Count of the blocks inserted into the block tree whose pipelined execution is not finished.
+pub static ref PENDING_EXECUTION_BLOCKS: IntGauge = OP_COUNTERS.gauge("pending_execution_blocks");
+
 //////////////////////
 // PERFORMANCE COUNTERS
 //////////////////////
@@ -109,6 +139,9 @@ pub static ref BLOCK_EXECUTION_DURATION_MS: Histogram = OP_COUNTERS.histogram("b
 /// commit a block once we decide to do so).
 pub static ref BLOCK_COMMIT_DURATION_MS: Histogram = OP_COUNTERS.histogram("block_commit_duration_ms");
 
+/// Histogram of the time (ms) voting and committing wait for the pipelined execution of a block.
+pub static ref EXECUTION_WAIT_MS: Histogram = OP_COUNTERS.histogram("execution_wait_ms");
+
 /// Histogram for the number of txns per (committed) blocks.
 pub static ref NUM_TXNS_PER_BLOCK: Histogram = OP_COUNTERS.histogram("num_txns_per_block");
 
@@ -187,9 +220,24 @@ pub static ref PENDING_BLOCK_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_blo
 /// Count of the pending inbound chunk requests
 pub static ref PENDING_CHUNK_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_chunk_requests");
 
+/// Count of the pending inbound account state chunk requests
+pub static ref PENDING_ACCOUNT_STATE_CHUNK_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_account_state_chunk_requests");
+
+/// Count of the pending inbound highest ledger info requests of the full nodes
+pub static ref PENDING_HIGHEST_LEDGER_INFO_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_highest_ledger_info_requests");
+
+/// Count of the pending inbound epoch change requests
+pub static ref PENDING_EPOCH_CHANGE_REQUESTS: IntGauge = OP_COUNTERS.gauge("pending_epoch_change_requests");
+
+/// Count of the pending notifications about the messages of future epochs
+pub static ref PENDING_FUTURE_EPOCH_NOTIFICATIONS: IntGauge = OP_COUNTERS.gauge("pending_future_epoch_notifications");
+
 /// Count of the pending inbound new round messages
 pub static ref PENDING_NEW_ROUND_MESSAGES: IntGauge = OP_COUNTERS.gauge("pending_new_round_messages");
 
+/// Count of the pending inbound timeout certificates
+pub static ref PENDING_TIMEOUT_CERTIFICATES: IntGauge = OP_COUNTERS.gauge("pending_timeout_certificates");
+
 /// Count of the pending outbound pacemaker timeouts
 pub static ref PENDING_PACEMAKER_TIMEOUTS: IntGauge = OP_COUNTERS.gauge("pending_pacemaker_timeouts");
 
@@ -198,4 +246,7 @@ pub static ref PENDING_NEW_ROUND_EVENTS: IntGauge = OP_COUNTERS.gauge("pending_n
 
 /// Count of the pending winning proposals.
 pub static ref PENDING_WINNING_PROPOSALS: IntGauge = OP_COUNTERS.gauge("pending_winning_proposals");
+
+/// Count of the pending epoch changes.
+pub static ref PENDING_EPOCH_CHANGES: IntGauge = OP_COUNTERS.gauge("pending_epoch_changes");
 }
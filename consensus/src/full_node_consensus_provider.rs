@@ -0,0 +1,233 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    chained_bft::QuorumCert,
+    consensus_provider::{create_storage_read_client, ConsensusProvider},
+    counters,
+    state_synchronizer::{setup_state_synchronizer, StateSynchronizer, SyncStatus},
+};
+use config::config::NodeConfig;
+use debug_interface::{
+    node_debug_service::EquivocationEvidenceSource,
+    proto::node_debug_interface::EquivocationEvidence as ProtoEquivocationEvidence,
+};
+use failure::prelude::*;
+use futures::{compat::Future01CompatExt, executor::block_on, FutureExt, StreamExt, TryFutureExt};
+use logger::prelude::*;
+use network::{
+    proto::RequestHighestLedgerInfo,
+    validator_network::{ConsensusNetworkEvents, ConsensusNetworkSender, Event},
+};
+use proto_conv::FromProto;
+use rand::{thread_rng, Rng};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use storage_client::StorageRead;
+use tokio::{
+    runtime::{self, Runtime},
+    timer::Delay,
+};
+use types::{account_address::AccountAddress, validator_verifier::ValidatorVerifier};
+
+/// The timeout of a single highest ledger info request to a validator.
+const HIGHEST_LEDGER_INFO_REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Supports the implementation of ConsensusProvider for full nodes: a full node does not
+/// participate in consensus, it periodically fetches the highest ledger info from the validators
+/// and follows them via the state synchronizer.
+pub struct FullNodeProvider {
+    runtime: Option<Runtime>,
+    network_sender: ConsensusNetworkSender,
+    network_events: Option<ConsensusNetworkEvents>,
+    synchronizer: Arc<StateSynchronizer>,
+    storage_read_client: Arc<dyn StorageRead>,
+    validators: Vec<AccountAddress>,
+    validator_verifier: Arc<ValidatorVerifier>,
+    poll_interval: Duration,
+}
+
+impl FullNodeProvider {
+    pub fn new(
+        node_config: &NodeConfig,
+        network_sender: ConsensusNetworkSender,
+        network_events: ConsensusNetworkEvents,
+    ) -> Self {
+        let runtime = runtime::Builder::new()
+            .name_prefix("full-node-")
+            .build()
+            .expect("Failed to create Tokio runtime!");
+        let synchronizer =
+            setup_state_synchronizer(network_sender.clone(), runtime.executor(), node_config);
+        let validators_with_public_keys =
+            node_config.base.trusted_peers.get_trusted_consensus_peers();
+        let validators = validators_with_public_keys.keys().cloned().collect();
+        let validator_verifier = Arc::new(ValidatorVerifier::new(validators_with_public_keys));
+        Self {
+            runtime: Some(runtime),
+            network_sender,
+            network_events: Some(network_events),
+            synchronizer: Arc::new(synchronizer),
+            storage_read_client: create_storage_read_client(node_config),
+            validators,
+            validator_verifier,
+            poll_interval: Duration::from_millis(
+                node_config.full_node.ledger_info_poll_interval_ms,
+            ),
+        }
+    }
+
+    /// Fetches the highest ledger info from a random validator and verifies it against the
+    /// trusted validator set.
+    async fn fetch_highest_ledger_info(
+        mut network_sender: ConsensusNetworkSender,
+        validators: Vec<AccountAddress>,
+        validator_verifier: Arc<ValidatorVerifier>,
+    ) -> Result<QuorumCert> {
+        ensure!(!validators.is_empty(), "No trusted validators found");
+        let peer = validators[thread_rng().gen_range(0, validators.len())];
+        let mut response = network_sender
+            .request_highest_ledger_info(
+                peer,
+                RequestHighestLedgerInfo::new(),
+                HIGHEST_LEDGER_INFO_REQUEST_TIMEOUT,
+            )
+            .await?;
+        let qc = QuorumCert::from_proto(response.take_highest_ledger_info())?;
+        if let Err(e) = qc.verify(validator_verifier.as_ref()) {
+            bail!("Highest ledger info from {} is invalid: {:?}", peer, e);
+        }
+        Ok(qc)
+    }
+
+    /// Polls the validators for their highest ledger info every `poll_interval` and syncs the
+    /// local state up to it whenever it is ahead of the local version.
+    async fn follow_validators(
+        network_sender: ConsensusNetworkSender,
+        synchronizer: Arc<StateSynchronizer>,
+        validators: Vec<AccountAddress>,
+        validator_verifier: Arc<ValidatorVerifier>,
+        poll_interval: Duration,
+        mut synced_version: u64,
+    ) {
+        loop {
+            if let Err(e) = Delay::new(Instant::now() + poll_interval).compat().await {
+                error!("Error on delay: {:?}", e);
+            }
+            let qc = match Self::fetch_highest_ledger_info(
+                network_sender.clone(),
+                validators.clone(),
+                Arc::clone(&validator_verifier),
+            )
+            .await
+            {
+                Ok(qc) => qc,
+                Err(e) => {
+                    warn!("Failed to fetch the highest ledger info: {:?}", e);
+                    counters::FULL_NODE_LEDGER_INFO_POLL_FAILURES.inc();
+                    continue;
+                }
+            };
+            let version = qc.ledger_info().ledger_info().version();
+            if version <= synced_version {
+                continue;
+            }
+            debug!(
+                "[FullNode] Syncing from version {} to version {}",
+                synced_version, version
+            );
+            match synchronizer.sync_to(qc).await {
+                Ok(SyncStatus::Finished) => {
+                    synced_version = version;
+                    counters::FULL_NODE_SYNCED_VERSION.set(version as i64);
+                }
+                Ok(status) => warn!("[FullNode] Failed to sync to {}: {:?}", version, status),
+                Err(e) => warn!("[FullNode] Failed to sync to {}: {:?}", version, e),
+            }
+        }
+    }
+
+    /// A full node does not serve consensus, so the inbound consensus messages are dropped.
+    async fn drain_network_events(mut network_events: ConsensusNetworkEvents) {
+        while let Some(event) = network_events.next().await {
+            match event {
+                Ok(Event::Message((peer_id, _))) => {
+                    debug!("[FullNode] Dropping a consensus message from {}", peer_id)
+                }
+                Ok(Event::RpcRequest((peer_id, _, _))) => debug!(
+                    "[FullNode] Dropping a consensus rpc request from {}",
+                    peer_id
+                ),
+                Ok(_) => (),
+                Err(e) => warn!("[FullNode] Failed to receive a network event: {:?}", e),
+            }
+        }
+    }
+}
+
+impl ConsensusProvider for FullNodeProvider {
+    fn start(&mut self) -> Result<()> {
+        let executor = self
+            .runtime
+            .as_ref()
+            .ok_or_else(|| format_err!("Full node provider has been stopped"))?
+            .executor();
+        let network_events = self
+            .network_events
+            .take()
+            .ok_or_else(|| format_err!("Full node provider has already been started"))?;
+        let (_, ledger_info, _) = self
+            .storage_read_client
+            .update_to_latest_ledger(0, vec![])?;
+        let synced_version = ledger_info.ledger_info().version();
+        counters::FULL_NODE_SYNCED_VERSION.set(synced_version as i64);
+
+        executor.spawn(
+            Self::drain_network_events(network_events)
+                .boxed()
+                .unit_error()
+                .compat(),
+        );
+        executor.spawn(
+            Self::follow_validators(
+                self.network_sender.clone(),
+                Arc::clone(&self.synchronizer),
+                self.validators.clone(),
+                Arc::clone(&self.validator_verifier),
+                self.poll_interval,
+                synced_version,
+            )
+            .boxed()
+            .unit_error()
+            .compat(),
+        );
+        debug!(
+            "Full node provider started at version {}, following {} validators.",
+            synced_version,
+            self.validators.len()
+        );
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(rt) = self.runtime.take() {
+            block_on(rt.shutdown_now().compat()).unwrap();
+            debug!("Full node provider stopped.");
+        }
+    }
+
+    fn equivocation_evidence(&self) -> Arc<dyn EquivocationEvidenceSource> {
+        Arc::new(NoEquivocationEvidence)
+    }
+}
+
+/// Full nodes do not vote, hence they never record any equivocation evidence.
+struct NoEquivocationEvidence;
+
+impl EquivocationEvidenceSource for NoEquivocationEvidence {
+    fn get_equivocation_evidence(&self) -> Result<Vec<ProtoEquivocationEvidence>> {
+        Ok(vec![])
+    }
+}
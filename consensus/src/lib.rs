@@ -19,11 +19,15 @@ extern crate failure;
 mod chained_bft;
 mod util;
 
+/// Offline inspection and repair of the ConsensusDB of a validator.
+pub use chained_bft::consensusdb_inspector;
+
 /// Defines the public consensus provider traits to implement for
 /// use in the Libra Core blockchain.
 pub mod consensus_provider;
 
 mod counters;
+mod full_node_consensus_provider;
 
 mod state_computer;
 mod state_replication;
@@ -17,8 +17,10 @@ use futures::{compat::Future01CompatExt, Future, FutureExt};
 use proto_conv::{FromProto, IntoProto};
 use std::{pin::Pin, sync::Arc, time::Instant};
 use types::{
+    account_state_blob::AccountStateChunkWithProof,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{SignedTransaction, TransactionListWithProof, TransactionStatus},
+    validator_set::ValidatorSet,
 };
 
 /// Basic communication with the Execution module;
@@ -172,4 +174,21 @@ impl StateComputer for ExecutionProxy {
             .get_chunk(start_version, target_version, batch_size)
             .boxed()
     }
+
+    fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: Option<HashValue>,
+        batch_size: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>> {
+        self.synchronizer
+            .get_account_state_chunk(version, start_key, batch_size)
+            .boxed()
+    }
+
+    fn get_validator_set(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<ValidatorSet>>> + Send>> {
+        self.synchronizer.get_validator_set().boxed()
+    }
 }
@@ -9,6 +9,7 @@ use futures::Future;
 use serde::{Deserialize, Serialize};
 use std::{pin::Pin, sync::Arc};
 use types::{
+    account_state_blob::AccountStateChunkWithProof,
     ledger_info::LedgerInfoWithSignatures,
     transaction::{TransactionListWithProof, Version},
     validator_set::ValidatorSet,
@@ -33,7 +34,6 @@ pub struct StateComputeResult {
     pub num_successful_txns: u64,
     /// If set, these are the validator public keys that will be used to start the next epoch
     /// immediately after this state is committed
-    /// TODO [Reconfiguration] the validators are currently ignored, no reconfiguration yet.
     pub validators: Option<ValidatorSet>,
 }
 
@@ -42,11 +42,14 @@ pub trait TxnManager: Send + Sync {
     type Payload;
 
     /// Brings new transactions to be applied.
+    /// Up to `max_priority_size` of the `max_size` transactions are reserved for transactions of
+    /// priority lanes (e.g., system and governance transactions).
     /// The `exclude_txns` list includes the transactions that are already pending in the
     /// branch of blocks consensus is trying to extend.
     fn pull_txns(
         &self,
         max_size: u64,
+        max_priority_size: u64,
         exclude_txns: Vec<&Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<Self::Payload>> + Send>>;
 
@@ -74,6 +77,19 @@ impl ExecutedState {
             version: 0,
         }
     }
+
+    /// With pipelined execution the votes and quorum certificates only order the blocks: they
+    /// carry this placeholder instead of the state the block is executed to.
+    pub fn ordered_placeholder() -> Self {
+        ExecutedState {
+            state_id: HashValue::zero(),
+            version: 0,
+        }
+    }
+
+    pub fn is_ordered_placeholder(&self) -> bool {
+        *self == Self::ordered_placeholder()
+    }
 }
 
 impl CanonicalSerialize for ExecutedState {
@@ -124,6 +140,20 @@ pub trait StateComputer: Send + Sync {
         target_version: u64,
         batch_size: u64,
     ) -> Pin<Box<dyn Future<Output = Result<TransactionListWithProof>> + Send>>;
+
+    /// Get a chunk of account states at the given version, starting right after `start_key`
+    fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: Option<HashValue>,
+        batch_size: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>>;
+
+    /// Get the validator set published in the latest committed state (e.g., after a
+    /// synchronization), or None if no validator set is published.
+    fn get_validator_set(
+        &self,
+    ) -> Pin<Box<dyn Future<Output = Result<Option<ValidatorSet>>> + Send>>;
 }
 
 pub trait StateMachineReplication {
@@ -1,24 +1,37 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{chained_bft::QuorumCert, counters, state_synchronizer::downloader::FetchChunkMsg};
+use crate::{
+    chained_bft::QuorumCert,
+    counters,
+    state_synchronizer::downloader::{DownloaderMsg, FetchAccountStateChunkMsg, FetchChunkMsg},
+};
 use config::config::NodeConfig;
-use execution_proto::proto::{
-    execution::{ExecuteChunkRequest, ExecuteChunkResponse},
-    execution_grpc::ExecutionClient,
+use crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
+use execution_proto::{
+    proto::{
+        execution::{ExecuteChunkRequest, ExecuteChunkResponse},
+        execution_grpc::ExecutionClient,
+    },
+    FinishStateSnapshotRequest,
 };
 use failure::prelude::*;
 use futures::{
     channel::{mpsc, oneshot},
-    Future, FutureExt, SinkExt, StreamExt,
+    Future, FutureExt, SinkExt, StreamExt, TryFutureExt,
 };
 use grpc_helpers::convert_grpc_response;
 use grpcio::{ChannelBuilder, EnvBuilder};
 use logger::prelude::*;
 use proto_conv::IntoProto;
 use std::{collections::BTreeMap, pin::Pin, sync::Arc};
-use storage_client::{StorageRead, StorageReadServiceClient};
-use types::proto::transaction::TransactionListWithProof;
+use storage_client::{
+    StorageRead, StorageReadServiceClient, StorageWrite, StorageWriteServiceClient,
+};
+use types::{
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
+    proto::transaction::TransactionListWithProof,
+};
 
 /// unified message used for communication with Coordinator
 pub enum CoordinatorMsg {
@@ -26,6 +39,9 @@ pub enum CoordinatorMsg {
     Requested(QuorumCert, oneshot::Sender<SyncStatus>),
     // is sent from Downloader to Coordinator to indicate that new batch is ready
     Fetched(Result<TransactionListWithProof>, QuorumCert),
+    // is sent from Downloader to Coordinator to indicate that new batch of account states is
+    // ready
+    FetchedAccountStates(Result<AccountStateChunkWithProof>, QuorumCert),
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -36,6 +52,15 @@ pub enum SyncStatus {
     DownloadFailed,
     DownloaderNotAvailable,
     ChunkIsEmpty,
+    SnapshotRestoreFailed,
+}
+
+/// progress of a state snapshot restore
+struct SnapshotProgress {
+    // last account key restored so far
+    last_key: Option<HashValue>,
+    // root hash of the partial state tree restored so far
+    state_root_hash: HashValue,
 }
 
 /// used to coordinate synchronization process
@@ -44,7 +69,7 @@ pub struct SyncCoordinator<T> {
     // communication with SyncCoordinator is done via this channel
     receiver: mpsc::UnboundedReceiver<CoordinatorMsg>,
     // connection to transaction fetcher
-    sender_to_downloader: mpsc::Sender<FetchChunkMsg>,
+    sender_to_downloader: mpsc::Sender<DownloaderMsg>,
 
     // last committed version that validator is aware of
     known_version: u64,
@@ -55,13 +80,20 @@ pub struct SyncCoordinator<T> {
     // subscribers of synchronization
     // each of them will be notified once their target version is ready
     subscribers: BTreeMap<u64, Vec<oneshot::Sender<SyncStatus>>>,
+    // when the target of a new node is more than this many versions ahead, the state snapshot at
+    // the target is restored instead of replaying all the transactions; 0 disables state snapshot
+    // sync
+    snapshot_threshold: u64,
+    // set while a state snapshot is being restored
+    snapshot: Option<SnapshotProgress>,
     executor_proxy: T,
 }
 
 impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
     pub fn new(
         receiver: mpsc::UnboundedReceiver<CoordinatorMsg>,
-        sender_to_downloader: mpsc::Sender<FetchChunkMsg>,
+        sender_to_downloader: mpsc::Sender<DownloaderMsg>,
+        snapshot_threshold: u64,
         executor_proxy: T,
     ) -> Self {
         Self {
@@ -72,6 +104,8 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
             target: None,
             sync_position: 0,
             subscribers: BTreeMap::new(),
+            snapshot_threshold,
+            snapshot: None,
             executor_proxy,
         }
     }
@@ -90,6 +124,13 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                 CoordinatorMsg::Fetched(Err(_), _) => {
                     self.notify_subscribers(SyncStatus::DownloadFailed);
                 }
+                CoordinatorMsg::FetchedAccountStates(Ok(account_state_chunk), qc) => {
+                    self.process_account_states(account_state_chunk, qc).await;
+                }
+                CoordinatorMsg::FetchedAccountStates(Err(_), _) => {
+                    self.snapshot = None;
+                    self.notify_subscribers(SyncStatus::DownloadFailed);
+                }
             }
         }
     }
@@ -142,9 +183,25 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
                     self.known_version = version;
                     self.sync_position = self.known_version + 1;
                     // send request to Downloader
-                    let fetch_request = FetchChunkMsg {
-                        start_version: self.sync_position,
-                        target: qc,
+                    // A state snapshot is only restored by a new node, whose DB holds nothing
+                    // but the genesis transaction.
+                    let fetch_request = if self.snapshot_threshold > 0
+                        && version == 0
+                        && requested_version > self.snapshot_threshold
+                    {
+                        self.snapshot = Some(SnapshotProgress {
+                            last_key: None,
+                            state_root_hash: *SPARSE_MERKLE_PLACEHOLDER_HASH,
+                        });
+                        DownloaderMsg::FetchAccountStateChunk(FetchAccountStateChunkMsg {
+                            target: qc,
+                            start_key: None,
+                        })
+                    } else {
+                        DownloaderMsg::FetchChunk(FetchChunkMsg {
+                            start_version: self.sync_position,
+                            target: qc,
+                        })
                     };
                     if self.sender_to_downloader.send(fetch_request).await.is_err() {
                         self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
@@ -172,10 +229,10 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
 
         if let Some(target) = self.target.clone() {
             if self.sync_position <= self.target_version() {
-                let fetch_msg = FetchChunkMsg {
+                let fetch_msg = DownloaderMsg::FetchChunk(FetchChunkMsg {
                     start_version: self.sync_position,
                     target,
-                };
+                });
                 // start download of next batch
                 if self.sender_to_downloader.send(fetch_msg).await.is_err() {
                     self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
@@ -192,6 +249,104 @@ impl<T: ExecutorProxyTrait> SyncCoordinator<T> {
         self.notify_subscribers(status);
     }
 
+    /// processes batch of account states downloaded by fetcher
+    /// restores them, and once the whole snapshot is restored continues to sync transactions on
+    /// top of it if the target moved further
+    async fn process_account_states(
+        &mut self,
+        account_state_chunk: AccountStateChunkWithProof,
+        qc: QuorumCert,
+    ) {
+        let fetch_msg = match self.restore_account_states(account_state_chunk, &qc).await {
+            Ok(Some(last_key)) => {
+                // snapshot is not complete yet, start download of next batch
+                DownloaderMsg::FetchAccountStateChunk(FetchAccountStateChunkMsg {
+                    target: qc,
+                    start_key: Some(last_key),
+                })
+            }
+            Ok(None) => {
+                self.snapshot = None;
+                self.known_version = qc.ledger_info().ledger_info().version();
+                self.sync_position = self.known_version + 1;
+                if let Some(target) = self.target.clone() {
+                    if self.sync_position <= self.target_version() {
+                        let fetch_msg = DownloaderMsg::FetchChunk(FetchChunkMsg {
+                            start_version: self.sync_position,
+                            target,
+                        });
+                        if self.sender_to_downloader.send(fetch_msg).await.is_err() {
+                            self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
+                            return;
+                        }
+                    }
+                }
+                self.notify_subscribers(SyncStatus::Finished);
+                return;
+            }
+            Err(e) => {
+                log_collector_error!(
+                    "[state synchronizer] failed to restore state snapshot: {:?}",
+                    e
+                );
+                self.snapshot = None;
+                self.notify_subscribers(SyncStatus::SnapshotRestoreFailed);
+                return;
+            }
+        };
+        if self.sender_to_downloader.send(fetch_msg).await.is_err() {
+            self.snapshot = None;
+            self.notify_subscribers(SyncStatus::DownloaderNotAvailable);
+        }
+    }
+
+    /// verifies and restores a chunk of account states
+    /// returns the last restored key if the snapshot is not complete yet, and `None` once the
+    /// restored state matches the one of the target and the snapshot has been finished
+    async fn restore_account_states(
+        &mut self,
+        account_state_chunk: AccountStateChunkWithProof,
+        qc: &QuorumCert,
+    ) -> Result<Option<HashValue>> {
+        let snapshot = self
+            .snapshot
+            .as_mut()
+            .ok_or_else(|| format_err!("No state snapshot restore in progress."))?;
+        account_state_chunk.verify(qc.ledger_info().ledger_info(), snapshot.last_key)?;
+        let last_key = match account_state_chunk.account_states.last() {
+            Some((key, _)) => *key,
+            None => bail!("Account state chunk is empty."),
+        };
+
+        let new_state_root_hash = self
+            .executor_proxy
+            .restore_account_state_chunk(
+                account_state_chunk.version,
+                account_state_chunk.account_states.clone(),
+                snapshot.state_root_hash,
+            )
+            .await?;
+        counters::STATE_SYNC_ACCOUNT_STATES_RESTORED
+            .inc_by(account_state_chunk.account_states.len() as i64);
+        snapshot.last_key = Some(last_key);
+        snapshot.state_root_hash = new_state_root_hash;
+        // the partial tree has the same root as the full one only once all the accounts are in
+        if new_state_root_hash != account_state_chunk.transaction_info.state_root_hash() {
+            return Ok(Some(last_key));
+        }
+
+        self.executor_proxy
+            .finish_state_snapshot(FinishStateSnapshotRequest {
+                ledger_info_with_sigs: qc.ledger_info().clone(),
+                transaction_info: account_state_chunk.transaction_info,
+                ledger_info_to_transaction_info_proof: account_state_chunk
+                    .ledger_info_to_transaction_info_proof,
+                ledger_frozen_subtree_hashes: account_state_chunk.ledger_frozen_subtree_hashes,
+            })
+            .await?;
+        Ok(None)
+    }
+
     fn notify_subscribers(&mut self, result: SyncStatus) {
         let mut active_subscribers = match result {
             SyncStatus::Finished => self.subscribers.split_off(&self.sync_position),
@@ -238,10 +393,26 @@ pub trait ExecutorProxyTrait: Sync + Send {
         &self,
         request: ExecuteChunkRequest,
     ) -> Pin<Box<dyn Future<Output = Result<ExecuteChunkResponse>> + Send>>;
+
+    /// Write a chunk of account states of the state snapshot being restored on top of the partial
+    /// state tree with root `state_root_hash`, and return the new root hash
+    fn restore_account_state_chunk(
+        &self,
+        version: u64,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Pin<Box<dyn Future<Output = Result<HashValue>> + Send>>;
+
+    /// Commit a fully restored state snapshot, so that execution resumes on top of it
+    fn finish_state_snapshot(
+        &self,
+        request: FinishStateSnapshotRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 }
 
 pub(crate) struct ExecutorProxy {
     storage_client: Arc<StorageReadServiceClient>,
+    storage_write_client: Arc<StorageWriteServiceClient>,
     execution_client: Arc<ExecutionClient>,
 }
 
@@ -253,12 +424,18 @@ impl ExecutorProxy {
             ChannelBuilder::new(Arc::clone(&env)).connect(&connection_str),
         ));
         let storage_client = Arc::new(StorageReadServiceClient::new(
+            Arc::clone(&env),
+            &config.storage.address,
+            config.storage.port,
+        ));
+        let storage_write_client = Arc::new(StorageWriteServiceClient::new(
             env,
             &config.storage.address,
             config.storage.port,
         ));
         Self {
             storage_client,
+            storage_write_client,
             execution_client,
         }
     }
@@ -281,4 +458,27 @@ impl ExecutorProxyTrait for ExecutorProxy {
         let client = Arc::clone(&self.execution_client);
         convert_grpc_response(client.execute_chunk_async(&request)).boxed()
     }
+
+    fn restore_account_state_chunk(
+        &self,
+        version: u64,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Pin<Box<dyn Future<Output = Result<HashValue>> + Send>> {
+        self.storage_write_client.restore_account_state_chunk_async(
+            version,
+            account_states,
+            state_root_hash,
+        )
+    }
+
+    fn finish_state_snapshot(
+        &self,
+        request: FinishStateSnapshotRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let client = Arc::clone(&self.execution_client);
+        convert_grpc_response(client.finish_state_snapshot_async(&request.into_proto()))
+            .map_ok(|_| ())
+            .boxed()
+    }
 }
@@ -6,14 +6,20 @@ use crate::{
     counters::OP_COUNTERS,
     state_synchronizer::{coordinator::CoordinatorMsg, PeerId},
 };
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{channel::mpsc, SinkExt, StreamExt};
 use logger::prelude::*;
-use network::{proto::RequestChunk, validator_network::ConsensusNetworkSender};
-use proto_conv::IntoProto;
+use network::{
+    proto::{RequestAccountStateChunk, RequestChunk},
+    validator_network::ConsensusNetworkSender,
+};
+use proto_conv::{FromProto, IntoProto};
 use rand::{thread_rng, Rng};
 use std::time::Duration;
-use types::proto::transaction::TransactionListWithProof;
+use types::{
+    account_state_blob::AccountStateChunkWithProof, proto::transaction::TransactionListWithProof,
+};
 
 /// Used for communication between coordinator and downloader
 /// and represents a single fetch request
@@ -25,9 +31,26 @@ pub struct FetchChunkMsg {
     pub start_version: u64,
 }
 
+/// Used for communication between coordinator and downloader
+/// and represents a single fetch request of account states for a state snapshot
+#[derive(Clone)]
+pub struct FetchAccountStateChunkMsg {
+    // the version of the snapshot is the version of the target
+    pub target: QuorumCert,
+    // last account key fetched so far, `None` if the snapshot restore just started
+    pub start_key: Option<HashValue>,
+}
+
+/// Fetch requests that Downloader can serve
+#[derive(Clone)]
+pub enum DownloaderMsg {
+    FetchChunk(FetchChunkMsg),
+    FetchAccountStateChunk(FetchAccountStateChunkMsg),
+}
+
 /// Used to download chunks of transactions from peers
 pub struct Downloader {
-    receiver_from_coordinator: mpsc::Receiver<FetchChunkMsg>,
+    receiver_from_coordinator: mpsc::Receiver<DownloaderMsg>,
     sender_to_coordinator: mpsc::UnboundedSender<CoordinatorMsg>,
     network: ConsensusNetworkSender,
     batch_size: u64,
@@ -36,7 +59,7 @@ pub struct Downloader {
 
 impl Downloader {
     pub fn new(
-        receiver_from_coordinator: mpsc::Receiver<FetchChunkMsg>,
+        receiver_from_coordinator: mpsc::Receiver<DownloaderMsg>,
         sender_to_coordinator: mpsc::UnboundedSender<CoordinatorMsg>,
         network: ConsensusNetworkSender,
         batch_size: u64,
@@ -51,22 +74,44 @@ impl Downloader {
         }
     }
 
-    /// Starts chunk downloader that listens to FetchChunkMsgs
+    /// Starts chunk downloader that listens to DownloaderMsgs
     pub async fn start(mut self) {
         while let Some(msg) = self.receiver_from_coordinator.next().await {
-            for attempt in 0..self.retries {
-                let peer_id = self.pick_peer_id(&msg);
-                let download_result = self.download_chunk(peer_id, msg.clone()).await;
-                if download_result.is_ok() || attempt == self.retries - 1 {
-                    let send_result = self
-                        .sender_to_coordinator
-                        .send(CoordinatorMsg::Fetched(download_result, msg.target))
-                        .await;
-                    if send_result.is_err() {
-                        log_collector_error!("[state synchronizer] failed to send chunk from downloader to coordinator");
+            let coordinator_msg = match msg {
+                DownloaderMsg::FetchChunk(msg) => {
+                    let mut download_result = Err(format_err!("no download attempt"));
+                    for _ in 0..self.retries {
+                        let peer_id = self.pick_peer_id(&msg.target);
+                        download_result = self.download_chunk(peer_id, msg.clone()).await;
+                        if download_result.is_ok() {
+                            break;
+                        }
+                    }
+                    CoordinatorMsg::Fetched(download_result, msg.target)
+                }
+                DownloaderMsg::FetchAccountStateChunk(msg) => {
+                    let mut download_result = Err(format_err!("no download attempt"));
+                    for _ in 0..self.retries {
+                        let peer_id = self.pick_peer_id(&msg.target);
+                        download_result = self
+                            .download_account_state_chunk(peer_id, msg.clone())
+                            .await;
+                        if download_result.is_ok() {
+                            break;
+                        }
                     }
-                    break;
+                    CoordinatorMsg::FetchedAccountStates(download_result, msg.target)
                 }
+            };
+            if self
+                .sender_to_coordinator
+                .send(coordinator_msg)
+                .await
+                .is_err()
+            {
+                log_collector_error!(
+                    "[state synchronizer] failed to send chunk from downloader to coordinator"
+                );
             }
         }
     }
@@ -97,8 +142,35 @@ impl Downloader {
         Ok(resp.take_txn_list_with_proof())
     }
 
-    fn pick_peer_id(&self, msg: &FetchChunkMsg) -> PeerId {
-        let signatures = msg.target.ledger_info().signatures();
+    /// Downloads a chunk of account states of the snapshot at the target version.
+    /// The chunk is verified by coordinator, which keeps track of the restore progress.
+    async fn download_account_state_chunk(
+        &mut self,
+        peer_id: PeerId,
+        msg: FetchAccountStateChunkMsg,
+    ) -> Result<AccountStateChunkWithProof> {
+        let mut req = RequestAccountStateChunk::new();
+        req.set_target(msg.target.into_proto());
+        if let Some(start_key) = msg.start_key {
+            req.set_start_key(start_key.to_vec());
+        }
+        req.set_batch_size(self.batch_size);
+        let mut resp = self
+            .network
+            .request_account_state_chunk(peer_id, req, Duration::from_millis(1000))
+            .await?;
+        let account_state_chunk_with_proof =
+            AccountStateChunkWithProof::from_proto(resp.take_account_state_chunk_with_proof())?;
+
+        OP_COUNTERS.inc_by(
+            "download_account_states",
+            account_state_chunk_with_proof.account_states.len(),
+        );
+        Ok(account_state_chunk_with_proof)
+    }
+
+    fn pick_peer_id(&self, target: &QuorumCert) -> PeerId {
+        let signatures = target.ledger_info().signatures();
         let idx = thread_rng().gen_range(0, signatures.len());
         signatures
             .keys()
@@ -2,9 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::state_synchronizer::coordinator::ExecutorProxyTrait;
-use crypto::HashValue;
-use execution_proto::proto::execution::{ExecuteChunkRequest, ExecuteChunkResponse};
-use failure::Result;
+use crypto::{
+    hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH},
+    HashValue,
+};
+use execution_proto::{
+    proto::execution::{ExecuteChunkRequest, ExecuteChunkResponse},
+    FinishStateSnapshotRequest,
+};
+use failure::prelude::*;
 use futures::{Future, FutureExt};
 use proto_conv::FromProto;
 use std::{
@@ -13,7 +19,12 @@ use std::{
 };
 use types::{
     account_address::AccountAddress,
-    proof::AccumulatorProof,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
+    ledger_info::LedgerInfo,
+    proof::{
+        AccumulatorProof, SparseMerkleLeafNode, SparseMerkleProof,
+        TransactionAccumulatorInternalNode,
+    },
     test_helpers::transaction_test_helpers::get_test_signed_txn,
     transaction::{SignedTransaction, TransactionInfo, TransactionListWithProof},
 };
@@ -37,6 +48,67 @@ impl ExecutorProxyTrait for MockExecutorProxy {
         self.version.fetch_add(1, Ordering::Relaxed);
         async move { Ok(ExecuteChunkResponse::new()) }.boxed()
     }
+
+    fn restore_account_state_chunk(
+        &self,
+        _version: u64,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Pin<Box<dyn Future<Output = Result<HashValue>> + Send>> {
+        // Only snapshots of a single account are supported, whose state root is the leaf itself.
+        let result =
+            if state_root_hash == *SPARSE_MERKLE_PLACEHOLDER_HASH && account_states.len() == 1 {
+                let (key, blob) = &account_states[0];
+                Ok(SparseMerkleLeafNode::new(*key, blob.hash()).hash())
+            } else {
+                Err(format_err!("unsupported snapshot"))
+            };
+        async move { result }.boxed()
+    }
+
+    fn finish_state_snapshot(
+        &self,
+        request: FinishStateSnapshotRequest,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        self.version.store(
+            request.ledger_info_with_sigs.ledger_info().version(),
+            Ordering::Relaxed,
+        );
+        async move { Ok(()) }.boxed()
+    }
+}
+
+/// Generates the snapshot of a state with a single account at version 2, along with the ledger
+/// info it can be verified against.
+pub fn gen_account_state_chunk() -> (LedgerInfo, AccountStateChunkWithProof) {
+    let key = HashValue::new([0x11; HashValue::LENGTH]);
+    let blob = AccountStateBlob::from(vec![0x22; 10]);
+    let state_root_hash = SparseMerkleLeafNode::new(key, blob.hash()).hash();
+
+    let txn_info = TransactionInfo::new(HashValue::zero(), state_root_hash, HashValue::zero(), 0);
+    let txn_info0_hash = HashValue::new([0x33; HashValue::LENGTH]);
+    let txn_info1_hash = HashValue::new([0x44; HashValue::LENGTH]);
+    let internal_a_hash =
+        TransactionAccumulatorInternalNode::new(txn_info0_hash, txn_info1_hash).hash();
+    let internal_b_hash =
+        TransactionAccumulatorInternalNode::new(txn_info.hash(), *ACCUMULATOR_PLACEHOLDER_HASH)
+            .hash();
+    let root_hash =
+        TransactionAccumulatorInternalNode::new(internal_a_hash, internal_b_hash).hash();
+
+    let ledger_info = LedgerInfo::new(2, root_hash, HashValue::zero(), HashValue::zero(), 0, 0);
+    let chunk = AccountStateChunkWithProof {
+        version: 2,
+        account_states: vec![(key, blob.clone())],
+        proofs: vec![SparseMerkleProof::new(Some((key, blob.hash())), vec![])],
+        ledger_info_to_transaction_info_proof: AccumulatorProof::new(vec![
+            internal_a_hash,
+            *ACCUMULATOR_PLACEHOLDER_HASH,
+        ]),
+        ledger_frozen_subtree_hashes: vec![internal_a_hash, txn_info.hash()],
+        transaction_info: txn_info,
+    };
+    (ledger_info, chunk)
 }
 
 pub fn gen_txn_list(sequence_number: u64) -> TransactionListWithProof {
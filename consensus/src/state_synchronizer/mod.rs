@@ -6,10 +6,11 @@
 //!
 //! It consists of three components: `SyncCoordinator`, `Downloader` and `StateSynchronizer`
 //!
-//! `Downloader` is used to download chunks of transactions from peers
+//! `Downloader` is used to download chunks of transactions and of account states from peers
 //!
 //! `SyncCoordinator` drives synchronization process. It handles new requests from Consensus and
-//! drives whole sync flow
+//! drives whole sync flow. When the target is too far ahead (see `node_sync_snapshot_threshold`),
+//! it restores the verified state snapshot at the target instead of replaying every transaction
 //!
 //! `StateSynchronizer` is an external interface for module.
 //! It's used for convenient communication with `SyncCoordinator`.
@@ -6,7 +6,7 @@ use crate::{
     state_replication::ExecutedState,
     state_synchronizer::{
         coordinator::SyncStatus,
-        mocks::{gen_txn_list, MockExecutorProxy},
+        mocks::{gen_account_state_chunk, gen_txn_list, MockExecutorProxy},
         PeerId, StateSynchronizer,
     },
 };
@@ -23,7 +23,7 @@ use futures::{
 };
 use metrics::get_all_metrics;
 use network::{
-    proto::{ConsensusMsg, RespondChunk},
+    proto::{ConsensusMsg, RespondAccountStateChunk, RespondChunk},
     validator_network::{
         network_builder::{NetworkBuilder, TransportType},
         Event, RpcError, CONSENSUS_RPC_PROTOCOL,
@@ -141,7 +141,18 @@ impl SynchronizerEnv {
 
         let rpc_handler = async move {
             while let Some(event) = events_b.next().await {
-                if let Ok(Event::RpcRequest((_, _, callback))) = event {
+                if let Ok(Event::RpcRequest((_, msg, callback))) = event {
+                    if msg.has_request_account_state_chunk() {
+                        let mut response_msg = ConsensusMsg::new();
+                        let mut response = RespondAccountStateChunk::new();
+                        response.set_account_state_chunk_with_proof(
+                            gen_account_state_chunk().1.into_proto(),
+                        );
+                        response_msg.set_respond_account_state_chunk(response);
+                        let response_data = Bytes::from(response_msg.write_to_bytes().unwrap());
+                        callback.send(Ok(response_data)).unwrap();
+                        continue;
+                    }
                     match handler() {
                         Ok(txn_list) => {
                             let mut response_msg = ConsensusMsg::new();
@@ -176,6 +187,10 @@ impl SynchronizerEnv {
             0,
             0,
         );
+        self.gen_commit_for(ledger_info)
+    }
+
+    fn gen_commit_for(&self, ledger_info: LedgerInfo) -> QuorumCert {
         let mut signatures = HashMap::new();
         let private_key = signing::generate_genesis_keypair().0;
         let signature = signing::sign_message(HashValue::zero(), &private_key).unwrap();
@@ -248,3 +263,20 @@ fn test_download_retry() {
     let status = block_on(env.synchronizers[0].sync_to(env.gen_commit(1)));
     assert_eq!(status.unwrap(), SyncStatus::Finished);
 }
+
+#[test]
+fn test_state_snapshot_sync() {
+    let handler = Box::new(|| -> Result<TransactionListWithProof> { Ok(gen_txn_list(0)) });
+    let (mut config, _) = get_test_config();
+    config.base.node_sync_snapshot_threshold = 1;
+    let env = SynchronizerEnv::new_with(handler, Some(config));
+
+    // a snapshot that doesn't match the target fails to restore
+    let status = block_on(env.synchronizers[0].sync_to(env.gen_commit(2)));
+    assert_eq!(status.unwrap(), SyncStatus::SnapshotRestoreFailed);
+
+    // the target is far enough to restore its state snapshot instead of replaying transactions
+    let (ledger_info, _) = gen_account_state_chunk();
+    let status = block_on(env.synchronizers[0].sync_to(env.gen_commit_for(ledger_info)));
+    assert_eq!(status.unwrap(), SyncStatus::Finished);
+}
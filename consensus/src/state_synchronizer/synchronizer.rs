@@ -11,6 +11,7 @@ use crate::{
     },
 };
 use config::config::NodeConfig;
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::{
     channel::{mpsc, oneshot},
@@ -20,10 +21,14 @@ use futures::{
 use grpcio::EnvBuilder;
 use logger::prelude::*;
 use network::validator_network::ConsensusNetworkSender;
-use std::sync::Arc;
+use std::{collections::BTreeMap, convert::TryInto, sync::Arc};
 use storage_client::{StorageRead, StorageReadServiceClient};
 use tokio::runtime::TaskExecutor;
-use types::transaction::TransactionListWithProof;
+use types::{
+    account_config::association_address, account_state_blob::AccountStateChunkWithProof,
+    get_with_proof::RequestItem, transaction::TransactionListWithProof,
+    validator_set::ValidatorSet,
+};
 
 /// Used for synchronization between validators for committed states
 pub struct StateSynchronizer {
@@ -42,8 +47,12 @@ impl StateSynchronizer {
         let (coordinator_sender, coordinator_receiver) = mpsc::unbounded();
         let (fetcher_sender, fetcher_receiver) = mpsc::channel(1);
 
-        let coordinator =
-            SyncCoordinator::new(coordinator_receiver, fetcher_sender, executor_proxy);
+        let coordinator = SyncCoordinator::new(
+            coordinator_receiver,
+            fetcher_sender,
+            config.base.node_sync_snapshot_threshold,
+            executor_proxy,
+        );
         let downloader = Downloader::new(
             fetcher_receiver,
             coordinator_sender.clone(),
@@ -109,6 +118,46 @@ impl StateSynchronizer {
             Ok(txn_list_with_proof)
         }
     }
+
+    /// Get a batch of account states of the state at `version`
+    pub fn get_account_state_chunk(
+        &self,
+        version: u64,
+        start_key: Option<HashValue>,
+        batch_size: u64,
+    ) -> impl Future<Output = Result<AccountStateChunkWithProof>> {
+        self.storage_read_client
+            .get_account_state_chunk_with_proof_async(version, start_key, batch_size)
+    }
+
+    /// Get the validator set published under the association account in the latest state: the
+    /// genesis publishes an empty set when the validators are only known from the configuration.
+    pub fn get_validator_set(&self) -> impl Future<Output = Result<Option<ValidatorSet>>> {
+        let client = Arc::clone(&self.storage_read_client);
+        async move {
+            let (mut response_items, _, _) = client
+                .update_to_latest_ledger_async(
+                    0,
+                    vec![RequestItem::GetAccountState {
+                        address: association_address(),
+                    }],
+                )
+                .await?;
+            let account_state_with_proof = response_items
+                .pop()
+                .ok_or_else(|| format_err!("Missing the association account state"))?
+                .into_get_account_state_response()?;
+            let blob = match account_state_with_proof.blob {
+                Some(blob) => blob,
+                None => return Ok(None),
+            };
+            let account_map: BTreeMap<Vec<u8>, Vec<u8>> = (&blob).try_into()?;
+            match ValidatorSet::make_from(&account_map) {
+                Ok(validators) if !validators.payload().is_empty() => Ok(Some(validators)),
+                _ => Ok(None),
+            }
+        }
+    }
 }
 
 /// Make the state synchronizer
@@ -82,6 +82,7 @@ impl TxnManager for MempoolProxy {
     fn pull_txns(
         &self,
         max_size: u64,
+        max_priority_size: u64,
         exclude_payloads: Vec<&Self::Payload>,
     ) -> Pin<Box<dyn Future<Output = Result<Self::Payload>> + Send>> {
         let mut exclude_txns = vec![];
@@ -95,6 +96,7 @@ impl TxnManager for MempoolProxy {
         }
         let mut get_block_request = GetBlockRequest::new();
         get_block_request.set_max_block_size(max_size);
+        get_block_request.set_max_priority_block_size(max_priority_size);
         get_block_request.set_transactions(::protobuf::RepeatedField::from_vec(exclude_txns));
         match self.mempool.get_block_async(&get_block_request) {
             Ok(receiver) => async move {
@@ -169,7 +169,9 @@ impl HashValue {
         HashValue::from_keccak(sha3)
     }
 
-    #[cfg(test)]
+    /// Computes the plain SHA3-256 hash of the concatenation of `buffers`, without the domain
+    /// separation of a [`CryptoHasher`]. Only meant for fingerprinting raw bytes, like the code of
+    /// a script, that aren't hashed into any authenticated data structure.
     pub fn from_iter_sha3<'a, I>(buffers: I) -> Self
     where
         I: IntoIterator<Item = &'a [u8]>,
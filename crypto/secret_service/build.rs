@@ -8,10 +8,11 @@
 
 fn main() {
     let proto_root = "src/proto";
+    let dependent_root = "../../types/src/proto";
 
     build_helpers::build_helpers::compile_proto(
         proto_root,
-        vec![],
+        vec![dependent_root],
         true, /* generate_client_stub */
     );
 }
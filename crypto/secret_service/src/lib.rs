@@ -6,6 +6,9 @@
 //! releases.
 pub mod crypto_wrappers;
 pub mod proto;
+pub mod safety_rules_client;
+pub mod safety_rules_data;
+pub mod safety_rules_server;
 pub mod secret_service_client;
 
 pub mod secret_service_node;
@@ -3,6 +3,9 @@
 
 #![allow(bare_trait_objects)]
 #![allow(missing_docs)]
-// use types::proto::*;
+use types::proto::ledger_info;
+
+pub mod safety_rules;
+pub mod safety_rules_grpc;
 pub mod secret_service;
 pub mod secret_service_grpc;
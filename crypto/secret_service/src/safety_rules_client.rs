@@ -0,0 +1,77 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! SafetyRulesClient gives a simple interface for consensus to interact with a remote safety rules
+//! service: it submits the consensus data to sign and converts the signatures to the types used by
+//! consensus.
+
+use crate::{
+    proto::{
+        safety_rules::{GetSafetyStateRequest, SignProposalRequest, SignTimeoutRequest},
+        safety_rules_grpc,
+    },
+    safety_rules_data::{BlockData, TimeoutData, TimeoutMsgData, VoteData},
+    safety_rules_server::SafetyState,
+};
+use crypto::Signature;
+use failure::prelude::*;
+use grpcio::{ChannelBuilder, EnvBuilder};
+use proto_conv::IntoProto;
+use std::sync::Arc;
+
+/// A client of the safety rules service.
+pub struct SafetyRulesClient {
+    client: safety_rules_grpc::SafetyRulesClient,
+}
+
+impl SafetyRulesClient {
+    /// Connects to the safety rules service at the given address and port.
+    pub fn new(address: &str, port: u16) -> Self {
+        let env = Arc::new(EnvBuilder::new().name_prefix("grpc-safety-rules-").build());
+        let channel = ChannelBuilder::new(env).connect(&format!("{}:{}", address, port));
+        Self {
+            client: safety_rules_grpc::SafetyRulesClient::new(channel),
+        }
+    }
+
+    /// Asks the service to sign a vote for a proposed block, fails if the service refuses to
+    /// vote.
+    pub fn construct_vote(&self, vote: VoteData) -> Result<Signature> {
+        let response = self.client.construct_vote(&vote.into_proto())?;
+        Signature::from_compact(response.get_signature())
+    }
+
+    /// Asks the service to sign a proposed block.
+    pub fn sign_proposal(&self, block: BlockData) -> Result<Signature> {
+        let mut req = SignProposalRequest::new();
+        req.set_block(block.into_proto());
+        let response = self.client.sign_proposal(&req)?;
+        Signature::from_compact(response.get_signature())
+    }
+
+    /// Asks the service to sign the timeout of a round.
+    pub fn sign_timeout(&self, timeout: TimeoutData) -> Result<Signature> {
+        let mut req = SignTimeoutRequest::new();
+        req.set_timeout(timeout.into_proto());
+        let response = self.client.sign_timeout(&req)?;
+        Signature::from_compact(response.get_signature())
+    }
+
+    /// Asks the service to sign the timeout message of a round.
+    pub fn sign_timeout_msg(&self, timeout_msg: TimeoutMsgData) -> Result<Signature> {
+        let response = self.client.sign_timeout_msg(&timeout_msg.into_proto())?;
+        Signature::from_compact(response.get_signature())
+    }
+
+    /// Returns the safety state persisted by the service.
+    pub fn safety_state(&self) -> Result<SafetyState> {
+        let response = self
+            .client
+            .get_safety_state(&GetSafetyStateRequest::new())?;
+        Ok(SafetyState {
+            last_vote_round: response.get_last_vote_round(),
+            preferred_block_round: response.get_preferred_block_round(),
+            last_proposed_round: response.get_last_proposed_round(),
+        })
+    }
+}
@@ -0,0 +1,482 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The consensus data signed by the safety rules. Consensus and the safety rules service compute
+//! the ids and digests of blocks, votes and timeouts from these structures, each with its own
+//! hasher, so that the service signs exactly what consensus verifies.
+
+use crate::proto::safety_rules::{
+    Block as ProtoBlock, ConstructVoteRequest, LedgerInfo as ProtoLedgerInfo,
+    PacemakerTimeout as ProtoPacemakerTimeout, QuorumCert as ProtoQuorumCert,
+    SignTimeoutMsgRequest,
+};
+use canonical_serialization::{CanonicalSerialize, CanonicalSerializer, SimpleSerializer};
+use crypto::{
+    hash::{
+        BlockHasher, CryptoHash, CryptoHasher, PacemakerTimeoutHasher, TimeoutMsgHasher,
+        VoteMsgHasher, ACCUMULATOR_PLACEHOLDER_HASH, GENESIS_BLOCK_ID,
+    },
+    HashValue,
+};
+use failure::prelude::*;
+use proto_conv::{FromProto, IntoProto};
+use std::convert::TryFrom;
+use types::{
+    account_address::AccountAddress,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_verifier::ValidatorVerifier,
+};
+
+/// Returns the digest of a vote for a block: the consensus data hash of the voted LedgerInfo.
+pub fn vote_digest(
+    proposed_block_id: HashValue,
+    executed_state_id: HashValue,
+    executed_state_version: u64,
+    round: u64,
+) -> HashValue {
+    VoteDigestSerializer {
+        proposed_block_id,
+        executed_state_id,
+        executed_state_version,
+        round,
+    }
+    .hash()
+}
+
+// Internal use only. Contains all the fields of a vote that contribute to the computation of its
+// digest.
+struct VoteDigestSerializer {
+    proposed_block_id: HashValue,
+    executed_state_id: HashValue,
+    executed_state_version: u64,
+    round: u64,
+}
+
+impl CanonicalSerialize for VoteDigestSerializer {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer
+            .encode_raw_bytes(self.proposed_block_id.as_ref())?
+            .encode_raw_bytes(self.executed_state_id.as_ref())?
+            .encode_u64(self.executed_state_version)?
+            .encode_u64(self.round)?;
+        Ok(())
+    }
+}
+
+impl CryptoHash for VoteDigestSerializer {
+    type Hasher = VoteMsgHasher;
+
+    fn hash(&self) -> HashValue {
+        let mut state = Self::Hasher::default();
+        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
+        state.finish()
+    }
+}
+
+/// The fields of a quorum certificate the safety rules depend on.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QuorumCertData {
+    /// Id of the certified block
+    pub certified_block_id: HashValue,
+    /// State id of the certified block after its execution
+    pub certified_state_id: HashValue,
+    /// Version of the certified block after its execution
+    pub certified_state_version: u64,
+    /// Round of the certified block
+    pub certified_block_round: u64,
+    /// LedgerInfo signed by the quorum, its consensus data hash is the digest of the votes
+    pub signed_ledger_info: LedgerInfoWithSignatures,
+}
+
+impl QuorumCertData {
+    /// Checks that the signed consensus data hash is the digest of the votes for the certified
+    /// block, which binds the certified round to the certified block, and that the LedgerInfo is
+    /// signed by a quorum of the given validators. The certificate of the genesis block is
+    /// implicitly agreed upon and carries no quorum signatures.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> Result<()> {
+        let digest = vote_digest(
+            self.certified_block_id,
+            self.certified_state_id,
+            self.certified_state_version,
+            self.certified_block_round,
+        );
+        let ledger_info = self.signed_ledger_info.ledger_info();
+        ensure!(
+            digest == ledger_info.consensus_data_hash(),
+            "The consensus data hash of the certificate of block {} does not match its votes",
+            self.certified_block_id
+        );
+        if self.is_genesis() {
+            return Ok(());
+        }
+        // Unlike `LedgerInfoWithSignatures::verify`, the signatures of a LedgerInfo that does not
+        // commit anything are checked as well: the rounds of any certificate move the rules.
+        validator
+            .verify_aggregated_signature(ledger_info.hash(), self.signed_ledger_info.signatures())
+            .map_err(|e| {
+                format_err!(
+                    "The certificate of block {} is not signed by a quorum: {}",
+                    self.certified_block_id,
+                    e
+                )
+            })
+    }
+
+    fn is_genesis(&self) -> bool {
+        self.certified_block_round == 0
+            && self.certified_block_id == *GENESIS_BLOCK_ID
+            && self.certified_state_id == *ACCUMULATOR_PLACEHOLDER_HASH
+            && self.certified_state_version == 0
+    }
+}
+
+impl IntoProto for QuorumCertData {
+    type ProtoType = ProtoQuorumCert;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_certified_block_id(self.certified_block_id.to_vec());
+        proto.set_certified_state_id(self.certified_state_id.to_vec());
+        proto.set_certified_state_version(self.certified_state_version);
+        proto.set_certified_block_round(self.certified_block_round);
+        proto.set_signed_ledger_info(self.signed_ledger_info.into_proto());
+        proto
+    }
+}
+
+impl FromProto for QuorumCertData {
+    type ProtoType = ProtoQuorumCert;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            certified_block_id: HashValue::from_slice(object.get_certified_block_id())?,
+            certified_state_id: HashValue::from_slice(object.get_certified_state_id())?,
+            certified_state_version: object.get_certified_state_version(),
+            certified_block_round: object.get_certified_block_round(),
+            signed_ledger_info: LedgerInfoWithSignatures::from_proto(
+                object.take_signed_ledger_info(),
+            )?,
+        })
+    }
+}
+
+/// The fields of a block its id is computed from.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BlockData {
+    /// Epoch of the validators proposing and voting for the block
+    pub epoch: u64,
+    /// Round of the block
+    pub round: u64,
+    /// Position of the block in the chain
+    pub height: u64,
+    /// Time the block is proposed at
+    pub timestamp_usecs: u64,
+    /// Canonical serialization of the payload of the block
+    pub payload: Vec<u8>,
+    /// Id of the parent block
+    pub parent_id: HashValue,
+    /// Certificate of the parent block carried by the block
+    pub quorum_cert: QuorumCertData,
+    /// Proposer of the block
+    pub author: AccountAddress,
+}
+
+impl BlockData {
+    /// Checks that the block carries a certificate of its parent signed by a quorum of the given
+    /// validators.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> Result<()> {
+        self.quorum_cert.verify(validator)?;
+        ensure!(
+            self.quorum_cert.certified_block_id == self.parent_id,
+            "Block of round {} does not carry the certificate of its parent",
+            self.round
+        );
+        ensure!(
+            self.round > self.quorum_cert.certified_block_round,
+            "Block of round {} is not newer than its parent of round {}",
+            self.round,
+            self.quorum_cert.certified_block_round
+        );
+        Ok(())
+    }
+}
+
+impl CanonicalSerialize for BlockData {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        // The payload is already serialized, its bytes are written as is.
+        serializer
+            .encode_u64(self.timestamp_usecs)?
+            .encode_u64(self.round)?
+            .encode_u64(self.epoch)?
+            .encode_u64(self.height)?
+            .encode_raw_bytes(&self.payload)?
+            .encode_raw_bytes(self.parent_id.as_ref())?
+            .encode_raw_bytes(self.quorum_cert.certified_block_id.as_ref())?
+            .encode_struct(&self.author)?;
+        Ok(())
+    }
+}
+
+/// The hash of a block is its id.
+impl CryptoHash for BlockData {
+    type Hasher = BlockHasher;
+
+    fn hash(&self) -> HashValue {
+        let mut state = Self::Hasher::default();
+        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
+        state.finish()
+    }
+}
+
+impl IntoProto for BlockData {
+    type ProtoType = ProtoBlock;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_epoch(self.epoch);
+        proto.set_round(self.round);
+        proto.set_height(self.height);
+        proto.set_timestamp_usecs(self.timestamp_usecs);
+        proto.set_payload(self.payload);
+        proto.set_parent_id(self.parent_id.to_vec());
+        proto.set_quorum_cert(self.quorum_cert.into_proto());
+        proto.set_author(self.author.to_vec());
+        proto
+    }
+}
+
+impl FromProto for BlockData {
+    type ProtoType = ProtoBlock;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            epoch: object.get_epoch(),
+            round: object.get_round(),
+            height: object.get_height(),
+            timestamp_usecs: object.get_timestamp_usecs(),
+            payload: object.take_payload(),
+            parent_id: HashValue::from_slice(object.get_parent_id())?,
+            quorum_cert: QuorumCertData::from_proto(object.take_quorum_cert())?,
+            author: AccountAddress::try_from(object.get_author())?,
+        })
+    }
+}
+
+/// A vote for a proposed block.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VoteData {
+    /// The proposed block
+    pub block: BlockData,
+    /// The parent of the proposed block, `None` if the parent is the genesis block
+    pub parent: Option<BlockData>,
+    /// State id of the proposed block after its execution
+    pub executed_state_id: HashValue,
+    /// Version of the proposed block after its execution
+    pub executed_state_version: u64,
+    /// The LedgerInfo signed by the vote, its consensus data hash is ignored
+    pub ledger_info: LedgerInfo,
+}
+
+impl VoteData {
+    /// Returns the id of the proposed block.
+    pub fn proposed_block_id(&self) -> HashValue {
+        self.block.hash()
+    }
+
+    /// Returns the LedgerInfo signed by the vote: its consensus data hash is the vote digest.
+    pub fn ledger_info(&self) -> LedgerInfo {
+        let mut ledger_info = self.ledger_info.clone();
+        ledger_info.set_consensus_data_hash(vote_digest(
+            self.proposed_block_id(),
+            self.executed_state_id,
+            self.executed_state_version,
+            self.block.round,
+        ));
+        ledger_info
+    }
+
+    /// Checks that the proposed block extends the given parent, both carrying certificates signed
+    /// by a quorum of the given validators, and returns the round of the 2-chain head certified
+    /// by the parent (the grandparent of the proposed block).
+    pub fn verify(&self, validator: &ValidatorVerifier) -> Result<u64> {
+        self.block.verify(validator)?;
+        match &self.parent {
+            Some(parent) => {
+                parent.verify(validator)?;
+                ensure!(
+                    parent.hash() == self.block.parent_id,
+                    "The parent of the block of round {} does not match its parent id",
+                    self.block.round
+                );
+                Ok(parent.quorum_cert.certified_block_round)
+            }
+            None => {
+                ensure!(
+                    self.block.parent_id == *GENESIS_BLOCK_ID,
+                    "The parent of the block of round {} is missing",
+                    self.block.round
+                );
+                Ok(0)
+            }
+        }
+    }
+}
+
+impl IntoProto for VoteData {
+    type ProtoType = ConstructVoteRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut ledger_info = ProtoLedgerInfo::new();
+        ledger_info.set_version(self.ledger_info.version());
+        ledger_info.set_transaction_accumulator_hash(
+            self.ledger_info.transaction_accumulator_hash().to_vec(),
+        );
+        ledger_info.set_consensus_block_id(self.ledger_info.consensus_block_id().to_vec());
+        ledger_info.set_epoch_num(self.ledger_info.epoch_num());
+        ledger_info.set_timestamp_usecs(self.ledger_info.timestamp_usecs());
+
+        let mut proto = Self::ProtoType::new();
+        proto.set_block(self.block.into_proto());
+        if let Some(parent) = self.parent {
+            proto.set_parent(parent.into_proto());
+        }
+        proto.set_executed_state_id(self.executed_state_id.to_vec());
+        proto.set_executed_state_version(self.executed_state_version);
+        proto.set_ledger_info(ledger_info);
+        proto
+    }
+}
+
+impl FromProto for VoteData {
+    type ProtoType = ConstructVoteRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let parent = if object.has_parent() {
+            Some(BlockData::from_proto(object.take_parent())?)
+        } else {
+            None
+        };
+        let ledger_info = object.take_ledger_info();
+        Ok(Self {
+            block: BlockData::from_proto(object.take_block())?,
+            parent,
+            executed_state_id: HashValue::from_slice(object.get_executed_state_id())?,
+            executed_state_version: object.get_executed_state_version(),
+            ledger_info: LedgerInfo::new(
+                ledger_info.get_version(),
+                HashValue::from_slice(ledger_info.get_transaction_accumulator_hash())?,
+                HashValue::zero(),
+                HashValue::from_slice(ledger_info.get_consensus_block_id())?,
+                ledger_info.get_epoch_num(),
+                ledger_info.get_timestamp_usecs(),
+            ),
+        })
+    }
+}
+
+/// The fields of a pacemaker timeout its digest is computed from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TimeoutData {
+    /// Epoch of the author
+    pub epoch: u64,
+    /// The round that timed out
+    pub round: u64,
+    /// The validator whose round timed out
+    pub author: AccountAddress,
+}
+
+impl CanonicalSerialize for TimeoutData {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer
+            .encode_u64(self.epoch)?
+            .encode_u64(self.round)?
+            .encode_struct(&self.author)?;
+        Ok(())
+    }
+}
+
+impl CryptoHash for TimeoutData {
+    type Hasher = PacemakerTimeoutHasher;
+
+    fn hash(&self) -> HashValue {
+        let mut state = Self::Hasher::default();
+        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
+        state.finish()
+    }
+}
+
+impl IntoProto for TimeoutData {
+    type ProtoType = ProtoPacemakerTimeout;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_epoch(self.epoch);
+        proto.set_round(self.round);
+        proto.set_author(self.author.to_vec());
+        proto
+    }
+}
+
+impl FromProto for TimeoutData {
+    type ProtoType = ProtoPacemakerTimeout;
+
+    fn from_proto(object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            epoch: object.get_epoch(),
+            round: object.get_round(),
+            author: AccountAddress::try_from(object.get_author())?,
+        })
+    }
+}
+
+/// The fields of a timeout message its digest is computed from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TimeoutMsgData {
+    /// The timeout carried by the message
+    pub timeout: TimeoutData,
+    /// Id of the block certified by the highest quorum certificate of the author
+    pub highest_certified_block_id: HashValue,
+}
+
+impl CanonicalSerialize for TimeoutMsgData {
+    fn serialize(&self, serializer: &mut impl CanonicalSerializer) -> Result<()> {
+        serializer
+            .encode_raw_bytes(self.highest_certified_block_id.as_ref())?
+            .encode_raw_bytes(self.timeout.hash().as_ref())?;
+        Ok(())
+    }
+}
+
+impl CryptoHash for TimeoutMsgData {
+    type Hasher = TimeoutMsgHasher;
+
+    fn hash(&self) -> HashValue {
+        let mut state = Self::Hasher::default();
+        state.write(&SimpleSerializer::<Vec<u8>>::serialize(self).expect("Should serialize."));
+        state.finish()
+    }
+}
+
+impl IntoProto for TimeoutMsgData {
+    type ProtoType = SignTimeoutMsgRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_timeout(self.timeout.into_proto());
+        proto.set_highest_certified_block_id(self.highest_certified_block_id.to_vec());
+        proto
+    }
+}
+
+impl FromProto for TimeoutMsgData {
+    type ProtoType = SignTimeoutMsgRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(Self {
+            timeout: TimeoutData::from_proto(object.take_timeout())?,
+            highest_certified_block_id: HashValue::from_slice(
+                object.get_highest_certified_block_id(),
+            )?,
+        })
+    }
+}
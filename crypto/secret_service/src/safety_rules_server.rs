@@ -0,0 +1,327 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The safety rules server holds the consensus signing key of a validator and signs consensus
+//! messages only if that cannot violate the safety of consensus:
+//! * a vote is signed only if its round is higher than the last vote round and if the parent of the
+//!   proposed block is not older than the preferred block round,
+//! * once the timeout of a round is signed, no vote is signed for that round,
+//! * a proposal is signed only if its round is higher than the last proposed round.
+//! The requests carry the blocks, certificates and timeouts themselves: the server computes the
+//! ids and digests it signs and the rounds the rules are checked against. The rounds of a
+//! certificate are used only once its signatures are verified against the validators given to the
+//! server: a certificate signed by other validators (e.g., after a change of the validator set) is
+//! refused until the server is restarted with the new validators.
+//! The rounds are persisted before a signature is returned, so that the rules hold across
+//! restarts of both consensus and the service.
+
+use crate::{
+    proto::{
+        safety_rules::{
+            ConstructVoteRequest, GetSafetyStateRequest, SafetyState as ProtoSafetyState,
+            SignProposalRequest, SignTimeoutMsgRequest, SignTimeoutRequest, SignatureResponse,
+        },
+        safety_rules_grpc,
+    },
+    safety_rules_data::{BlockData, TimeoutData, TimeoutMsgData, VoteData},
+};
+use crypto::{hash::CryptoHash, signing, PrivateKey, Signature};
+use failure::prelude::*;
+use grpc_helpers::provide_grpc_response;
+use logger::prelude::*;
+use proto_conv::FromProto;
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Read, Write},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use types::validator_verifier::ValidatorVerifier;
+
+#[cfg(test)]
+#[path = "unit_tests/safety_rules_test.rs"]
+mod safety_rules_test;
+
+// Length of the serialized safety state: the last vote round, the preferred block round and the
+// last proposed round.
+const SAFETY_STATE_LENGTH: usize = 24;
+
+/// The state the safety rules need to persist.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct SafetyState {
+    /// The highest round a vote or a timeout was signed for.
+    pub last_vote_round: u64,
+    /// The round of the highest known 2-chain head.
+    pub preferred_block_round: u64,
+    /// The highest round a proposal was signed for.
+    pub last_proposed_round: u64,
+}
+
+impl SafetyState {
+    fn to_bytes(self) -> [u8; SAFETY_STATE_LENGTH] {
+        let mut bytes = [0u8; SAFETY_STATE_LENGTH];
+        bytes[..8].copy_from_slice(&self.last_vote_round.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.preferred_block_round.to_le_bytes());
+        bytes[16..].copy_from_slice(&self.last_proposed_round.to_le_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        ensure!(
+            bytes.len() == SAFETY_STATE_LENGTH,
+            "Unexpected length of the safety state: {}",
+            bytes.len()
+        );
+        let mut last_vote_round = [0u8; 8];
+        last_vote_round.copy_from_slice(&bytes[..8]);
+        let mut preferred_block_round = [0u8; 8];
+        preferred_block_round.copy_from_slice(&bytes[8..16]);
+        let mut last_proposed_round = [0u8; 8];
+        last_proposed_round.copy_from_slice(&bytes[16..]);
+        Ok(Self {
+            last_vote_round: u64::from_le_bytes(last_vote_round),
+            preferred_block_round: u64::from_le_bytes(preferred_block_round),
+            last_proposed_round: u64::from_le_bytes(last_proposed_round),
+        })
+    }
+}
+
+/// Crash-safe file storage of the safety state: a new state is written to a temporary file,
+/// synced to disk and atomically renamed over the previous state.
+pub struct SafetyStateStorage {
+    path: PathBuf,
+}
+
+impl SafetyStateStorage {
+    /// Storage of the safety state at the given path.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// Reads the persisted state, the default state is returned if nothing was persisted yet.
+    pub fn load(&self) -> Result<SafetyState> {
+        let mut file = match File::open(&self.path) {
+            Ok(file) => file,
+            Err(ref e) if e.kind() == ErrorKind::NotFound => return Ok(SafetyState::default()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+        SafetyState::from_bytes(&bytes)
+    }
+
+    /// Persists the state, returns only after the state reaches the disk.
+    pub fn save(&self, state: SafetyState) -> Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        {
+            let mut file = File::create(&tmp_path)?;
+            file.write_all(&state.to_bytes())?;
+            file.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        if let Some(dir) = self.path.parent() {
+            // Sync the directory so that the rename survives a crash as well.
+            if let Ok(dir) = File::open(dir) {
+                dir.sync_all()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Safety rules server that holds the consensus signing key of a validator and signs consensus
+/// messages following the safety rules.
+#[derive(Clone)]
+pub struct SafetyRulesServer {
+    private_key: PrivateKey,
+    // The validators signing the certificates the rounds of the rules are taken from.
+    validator: Arc<ValidatorVerifier>,
+    // The mutex makes sure the state is persisted before the next request is processed.
+    state: Arc<Mutex<SafetyState>>,
+    storage: Arc<SafetyStateStorage>,
+}
+
+impl SafetyRulesServer {
+    /// Creates the server with the given key, verifying the certificates against the given
+    /// validators, and recovers the state from the storage.
+    pub fn new(
+        private_key: PrivateKey,
+        validator: ValidatorVerifier,
+        storage: SafetyStateStorage,
+    ) -> Result<Self> {
+        let state = storage.load()?;
+        info!("Safety rules recovered state {:?}", state);
+        Ok(Self {
+            private_key,
+            validator: Arc::new(validator),
+            state: Arc::new(Mutex::new(state)),
+            storage: Arc::new(storage),
+        })
+    }
+
+    /// Returns the current safety state.
+    pub fn safety_state(&self) -> SafetyState {
+        *self
+            .state
+            .lock()
+            .expect("[safety state] acquire state lock")
+    }
+
+    /// Signs the LedgerInfo of a vote for the proposed block if the certificates of the block and
+    /// of its parent are signed by a quorum and if
+    /// * its round is higher than the last vote round,
+    /// * the round of its parent is not lower than the preferred block round, which is first
+    ///   updated with the round of the 2-chain head certified by the parent.
+    pub fn construct_vote_inner(&self, vote: &VoteData) -> Result<Signature> {
+        let two_chain_round = vote.verify(&self.validator)?;
+        let round = vote.block.round;
+        let parent_round = vote.block.quorum_cert.certified_block_round;
+        let mut state = self
+            .state
+            .lock()
+            .expect("[construct vote] acquire state lock");
+        ensure!(
+            round > state.last_vote_round,
+            "Vote round {} is not higher than the last vote round {}",
+            round,
+            state.last_vote_round
+        );
+        let preferred_block_round = std::cmp::max(state.preferred_block_round, two_chain_round);
+        ensure!(
+            parent_round >= preferred_block_round,
+            "Parent round {} of the proposal is lower than the preferred block round {}",
+            parent_round,
+            preferred_block_round
+        );
+        let new_state = SafetyState {
+            last_vote_round: round,
+            preferred_block_round,
+            ..*state
+        };
+        self.storage.save(new_state)?;
+        *state = new_state;
+        signing::sign_message(vote.ledger_info().hash(), &self.private_key)
+    }
+
+    /// Signs the id of a proposed block carrying a certificate of its parent signed by a quorum if
+    /// its round is higher than the last proposed round.
+    pub fn sign_proposal_inner(&self, block: &BlockData) -> Result<Signature> {
+        block.verify(&self.validator)?;
+        let mut state = self
+            .state
+            .lock()
+            .expect("[sign proposal] acquire state lock");
+        ensure!(
+            block.round > state.last_proposed_round,
+            "Proposal round {} is not higher than the last proposed round {}",
+            block.round,
+            state.last_proposed_round
+        );
+        let new_state = SafetyState {
+            last_proposed_round: block.round,
+            ..*state
+        };
+        self.storage.save(new_state)?;
+        *state = new_state;
+        signing::sign_message(block.hash(), &self.private_key)
+    }
+
+    /// Signs the digest of a timeout: no vote is going to be signed for the round of the timeout.
+    pub fn sign_timeout_inner(&self, timeout: &TimeoutData) -> Result<Signature> {
+        self.observe_timeout(timeout.round)?;
+        signing::sign_message(timeout.hash(), &self.private_key)
+    }
+
+    /// Signs the digest of a timeout message: no vote is going to be signed for the round of its
+    /// timeout.
+    pub fn sign_timeout_msg_inner(&self, timeout_msg: &TimeoutMsgData) -> Result<Signature> {
+        self.observe_timeout(timeout_msg.timeout.round)?;
+        signing::sign_message(timeout_msg.hash(), &self.private_key)
+    }
+
+    // Raises the last vote round to the round of a signed timeout.
+    fn observe_timeout(&self, round: u64) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .expect("[sign timeout] acquire state lock");
+        if round > state.last_vote_round {
+            let new_state = SafetyState {
+                last_vote_round: round,
+                ..*state
+            };
+            self.storage.save(new_state)?;
+            *state = new_state;
+        }
+        Ok(())
+    }
+}
+
+fn signature_response(signature: Result<Signature>) -> Result<SignatureResponse> {
+    let mut response = SignatureResponse::new();
+    response.set_signature(signature?.to_compact().to_vec());
+    Ok(response)
+}
+
+/// SafetyRulesServer implements the proto trait safety_rules_grpc::SafetyRules.
+/// The methods below wrap around inner methods of SafetyRulesServer and operate on grpc's
+/// requests/responses, the requests violating the rules are answered with an error.
+impl safety_rules_grpc::SafetyRules for SafetyRulesServer {
+    fn construct_vote(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: ConstructVoteRequest,
+        sink: ::grpcio::UnarySink<SignatureResponse>,
+    ) {
+        let response = VoteData::from_proto(req)
+            .and_then(|vote| signature_response(self.construct_vote_inner(&vote)));
+        provide_grpc_response(response, ctx, sink);
+    }
+
+    fn sign_proposal(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        mut req: SignProposalRequest,
+        sink: ::grpcio::UnarySink<SignatureResponse>,
+    ) {
+        let response = BlockData::from_proto(req.take_block())
+            .and_then(|block| signature_response(self.sign_proposal_inner(&block)));
+        provide_grpc_response(response, ctx, sink);
+    }
+
+    fn sign_timeout(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        mut req: SignTimeoutRequest,
+        sink: ::grpcio::UnarySink<SignatureResponse>,
+    ) {
+        let response = TimeoutData::from_proto(req.take_timeout())
+            .and_then(|timeout| signature_response(self.sign_timeout_inner(&timeout)));
+        provide_grpc_response(response, ctx, sink);
+    }
+
+    fn sign_timeout_msg(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        req: SignTimeoutMsgRequest,
+        sink: ::grpcio::UnarySink<SignatureResponse>,
+    ) {
+        let response = TimeoutMsgData::from_proto(req)
+            .and_then(|timeout_msg| signature_response(self.sign_timeout_msg_inner(&timeout_msg)));
+        provide_grpc_response(response, ctx, sink);
+    }
+
+    fn get_safety_state(
+        &mut self,
+        ctx: ::grpcio::RpcContext,
+        _req: GetSafetyStateRequest,
+        sink: ::grpcio::UnarySink<ProtoSafetyState>,
+    ) {
+        let state = self.safety_state();
+        let mut response = ProtoSafetyState::new();
+        response.set_last_vote_round(state.last_vote_round);
+        response.set_preferred_block_round(state.preferred_block_round);
+        response.set_last_proposed_round(state.last_proposed_round);
+        provide_grpc_response(Ok(response), ctx, sink);
+    }
+}
@@ -6,14 +6,26 @@
 //! accepts connections on port node_config.secret_service.secret_service_port.
 //! The proto/secret_service.proto file shows the requests that the service accepts and the
 //! responses that it gives back. For an example on how to run the secret service see main.rs.
+//! The same server runs the safety rules service (proto/safety_rules.proto) that signs consensus
+//! messages with the consensus key of the validator, verifies the certificates against the trusted
+//! consensus peers and persists its state in the data directory.
 
-use crate::{proto::secret_service_grpc, secret_service_server::SecretServiceServer};
+use crate::{
+    proto::{safety_rules_grpc, secret_service_grpc},
+    safety_rules_server::{SafetyRulesServer, SafetyStateStorage},
+    secret_service_server::SecretServiceServer,
+};
 use config::config::NodeConfig;
 use debug_interface::{node_debug_service::NodeDebugService, proto::node_debug_interface_grpc};
 use failure::prelude::*;
-use grpc_helpers::spawn_service_thread;
+use grpc_helpers::{spawn_service_thread, ServerHandle};
+use grpcio::{EnvBuilder, ServerBuilder};
 use logger::prelude::*;
-use std::thread;
+use std::{sync::Arc, thread};
+use types::validator_verifier::ValidatorVerifier;
+
+/// Name of the file in the data directory that keeps the state of the safety rules.
+pub const SAFETY_RULES_STATE_FILE: &str = "safety_rules_state";
 
 #[cfg(test)]
 #[path = "unit_tests/secret_service_node_test.rs"]
@@ -36,12 +48,33 @@ impl SecretServiceNode {
 
         let handle = SecretServiceServer::new();
         let service = secret_service_grpc::create_secret_service(handle);
-        let _ss_service_handle = spawn_service_thread(
-            service,
-            self.node_config.secret_service.address.clone(),
-            self.node_config.secret_service.secret_service_port,
-            "secret_service",
-        );
+        let safety_rules = SafetyRulesServer::new(
+            self.node_config.base.peer_keypairs.get_consensus_private(),
+            ValidatorVerifier::new(
+                self.node_config
+                    .base
+                    .trusted_peers
+                    .get_trusted_consensus_peers(),
+            ),
+            SafetyStateStorage::new(
+                self.node_config
+                    .base
+                    .data_dir_path
+                    .join(SAFETY_RULES_STATE_FILE),
+            ),
+        )?;
+        let safety_rules_service = safety_rules_grpc::create_safety_rules(safety_rules);
+        let env = Arc::new(EnvBuilder::new().name_prefix("secret_service").build());
+        let server = ServerBuilder::new(env)
+            .register_service(service)
+            .register_service(safety_rules_service)
+            .bind(
+                self.node_config.secret_service.address.clone(),
+                self.node_config.secret_service.secret_service_port,
+            )
+            .build()
+            .expect("Unable to create grpc server");
+        let _ss_service_handle = ServerHandle::setup(server);
 
         // Start Debug interface
         let debug_service =
@@ -0,0 +1,402 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    safety_rules_data::{
+        vote_digest, BlockData, QuorumCertData, TimeoutData, TimeoutMsgData, VoteData,
+    },
+    safety_rules_server::{SafetyRulesServer, SafetyState, SafetyStateStorage},
+};
+use crypto::{
+    hash::{CryptoHash, ACCUMULATOR_PLACEHOLDER_HASH, GENESIS_BLOCK_ID},
+    signing, HashValue,
+};
+use std::collections::HashMap;
+use tempfile::TempDir;
+use types::{
+    account_address::AccountAddress,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    validator_signer::ValidatorSigner,
+    validator_verifier::ValidatorVerifier,
+};
+
+/// The validators signing the certificates of the blocks.
+struct Validators(Vec<ValidatorSigner>);
+
+impl Validators {
+    fn new() -> Self {
+        Validators((0..4).map(|_| ValidatorSigner::random()).collect())
+    }
+
+    fn verifier(&self) -> ValidatorVerifier {
+        ValidatorVerifier::new(
+            self.0
+                .iter()
+                .map(|signer| (signer.author(), signer.public_key()))
+                .collect(),
+        )
+    }
+
+    /// A certificate of the given block signed by the given number of validators.
+    fn certificate_with_signatures(
+        &self,
+        certified_block_id: HashValue,
+        certified_block_round: u64,
+        num_signatures: usize,
+    ) -> QuorumCertData {
+        let ledger_info = LedgerInfo::new(
+            0,
+            HashValue::zero(),
+            vote_digest(
+                certified_block_id,
+                HashValue::zero(),
+                0,
+                certified_block_round,
+            ),
+            HashValue::zero(),
+            0,
+            0,
+        );
+        let signatures: HashMap<_, _> = self.0[..num_signatures]
+            .iter()
+            .map(|signer| {
+                (
+                    signer.author(),
+                    signer.sign_message(ledger_info.hash()).unwrap(),
+                )
+            })
+            .collect();
+        QuorumCertData {
+            certified_block_id,
+            certified_state_id: HashValue::zero(),
+            certified_state_version: 0,
+            certified_block_round,
+            signed_ledger_info: LedgerInfoWithSignatures::new(ledger_info, signatures),
+        }
+    }
+
+    /// A certificate of the given block signed by a quorum of the validators.
+    fn certificate(
+        &self,
+        certified_block_id: HashValue,
+        certified_block_round: u64,
+    ) -> QuorumCertData {
+        self.certificate_with_signatures(certified_block_id, certified_block_round, 3)
+    }
+
+    /// A block of the given round extending the given parent (the genesis block if `None`).
+    fn make_block(&self, parent: Option<&BlockData>, round: u64) -> BlockData {
+        let (parent_id, parent_round, height) = match parent {
+            Some(parent) => (parent.hash(), parent.round, parent.height + 1),
+            None => (*GENESIS_BLOCK_ID, 0, 1),
+        };
+        BlockData {
+            epoch: 0,
+            round,
+            height,
+            timestamp_usecs: round,
+            payload: vec![round as u8],
+            parent_id,
+            quorum_cert: self.certificate(parent_id, parent_round),
+            author: AccountAddress::random(),
+        }
+    }
+}
+
+fn create_server(
+    dir: &TempDir,
+    private_key: signing::PrivateKey,
+    validators: &Validators,
+) -> SafetyRulesServer {
+    SafetyRulesServer::new(
+        private_key,
+        validators.verifier(),
+        SafetyStateStorage::new(dir.path().join("safety_rules_state")),
+    )
+    .unwrap()
+}
+
+fn make_vote(block: &BlockData, parent: Option<&BlockData>) -> VoteData {
+    VoteData {
+        block: block.clone(),
+        parent: parent.cloned(),
+        executed_state_id: HashValue::random(),
+        executed_state_version: block.round,
+        ledger_info: LedgerInfo::new(
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+        ),
+    }
+}
+
+#[test]
+fn test_vote_once_per_round() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, public_key) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+
+    let vote = make_vote(&b1, None);
+    let signature = server.construct_vote_inner(&vote).unwrap();
+    // The signed LedgerInfo carries the digest of the vote for the block.
+    let ledger_info = vote.ledger_info();
+    assert_eq!(
+        ledger_info.consensus_data_hash(),
+        vote_digest(b1.hash(), vote.executed_state_id, 1, 1)
+    );
+    assert!(signing::verify_message(ledger_info.hash(), &signature, &public_key).is_ok());
+    // Another vote in the same round is refused.
+    assert!(server
+        .construct_vote_inner(&make_vote(&validators.make_block(None, 1), None))
+        .is_err());
+    let b2 = validators.make_block(Some(&b1), 2);
+    assert!(server
+        .construct_vote_inner(&make_vote(&b2, Some(&b1)))
+        .is_ok());
+    assert_eq!(server.safety_state().last_vote_round, 2);
+}
+
+#[test]
+fn test_preferred_block_round() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, _) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+    let b2 = validators.make_block(Some(&b1), 2);
+    let b3 = validators.make_block(Some(&b2), 3);
+
+    // The parent of b3 certifies b1: b1 becomes the preferred block.
+    server
+        .construct_vote_inner(&make_vote(&b3, Some(&b2)))
+        .unwrap();
+    assert_eq!(
+        server.safety_state(),
+        SafetyState {
+            last_vote_round: 3,
+            preferred_block_round: 1,
+            last_proposed_round: 0,
+        }
+    );
+    // A fork extending the genesis block is older than the preferred block.
+    let fork = validators.make_block(None, 4);
+    assert!(server
+        .construct_vote_inner(&make_vote(&fork, None))
+        .is_err());
+    // A fork extending the preferred block is fine.
+    let fork = validators.make_block(Some(&b1), 4);
+    assert!(server
+        .construct_vote_inner(&make_vote(&fork, Some(&b1)))
+        .is_ok());
+}
+
+#[test]
+fn test_forged_data_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, _) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+    let b2 = validators.make_block(Some(&b1), 2);
+
+    // The round of a certificate must match the digest signed by the quorum.
+    let mut forged = validators.make_block(Some(&b1), 3);
+    forged.quorum_cert.certified_block_round = 2;
+    assert!(server.sign_proposal_inner(&forged).is_err());
+    assert!(server
+        .construct_vote_inner(&make_vote(&forged, Some(&b1)))
+        .is_err());
+    // The parent must match the parent id of the block.
+    let other_parent = validators.make_block(None, 1);
+    assert!(server
+        .construct_vote_inner(&make_vote(&b2, Some(&other_parent)))
+        .is_err());
+    // Only the parent of the genesis block can be omitted.
+    assert!(server.construct_vote_inner(&make_vote(&b2, None)).is_err());
+    assert_eq!(server.safety_state(), SafetyState::default());
+}
+
+#[test]
+fn test_forged_certificate_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, _) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+    let b2 = validators.make_block(Some(&b1), 2);
+
+    // A certificate made up by other validators, e.g., by a compromised consensus process.
+    let impostors = Validators::new();
+    let forged_b2 = impostors.make_block(Some(&b1), 2);
+    assert!(server.sign_proposal_inner(&forged_b2).is_err());
+    assert!(server
+        .construct_vote_inner(&make_vote(&forged_b2, Some(&b1)))
+        .is_err());
+    // A certificate signed by less than a quorum of the validators.
+    let mut b3 = validators.make_block(Some(&b2), 3);
+    b3.quorum_cert = validators.certificate_with_signatures(b2.hash(), 2, 2);
+    assert!(server.sign_proposal_inner(&b3).is_err());
+    // The certificate carried by the parent is verified as well: a made up 2-chain cannot raise
+    // the preferred block round.
+    let mut forged_b2 = b2.clone();
+    forged_b2.quorum_cert = impostors.certificate(b1.hash(), 1);
+    let b3 = validators.make_block(Some(&forged_b2), 3);
+    assert!(server
+        .construct_vote_inner(&make_vote(&b3, Some(&forged_b2)))
+        .is_err());
+    assert_eq!(server.safety_state(), SafetyState::default());
+
+    // The certificate of the genesis block is implicitly agreed upon.
+    let mut genesis_certificate = validators.certificate_with_signatures(*GENESIS_BLOCK_ID, 0, 0);
+    genesis_certificate.certified_state_id = *ACCUMULATOR_PLACEHOLDER_HASH;
+    let mut ledger_info = genesis_certificate.signed_ledger_info.ledger_info().clone();
+    ledger_info.set_consensus_data_hash(vote_digest(
+        *GENESIS_BLOCK_ID,
+        *ACCUMULATOR_PLACEHOLDER_HASH,
+        0,
+        0,
+    ));
+    genesis_certificate.signed_ledger_info =
+        LedgerInfoWithSignatures::new(ledger_info, HashMap::new());
+    let mut b1 = validators.make_block(None, 1);
+    b1.quorum_cert = genesis_certificate;
+    assert!(server.sign_proposal_inner(&b1).is_ok());
+    let b2 = validators.make_block(Some(&b1), 2);
+    assert!(server
+        .construct_vote_inner(&make_vote(&b2, Some(&b1)))
+        .is_ok());
+}
+
+#[test]
+fn test_sign_proposal() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, public_key) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+
+    let signature = server.sign_proposal_inner(&b1).unwrap();
+    assert!(signing::verify_message(b1.hash(), &signature, &public_key).is_ok());
+    // A block must carry the certificate of its parent.
+    let mut b2 = validators.make_block(Some(&b1), 2);
+    b2.quorum_cert = validators.certificate(HashValue::random(), 1);
+    assert!(server.sign_proposal_inner(&b2).is_err());
+    assert_eq!(server.safety_state().last_proposed_round, 1);
+}
+
+#[test]
+fn test_propose_once_per_round() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, _) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let b1 = validators.make_block(None, 1);
+    let b2 = validators.make_block(Some(&b1), 2);
+
+    server.sign_proposal_inner(&b2).unwrap();
+    // Another proposal for the same round (or an older one) is refused.
+    let other_b2 = validators.make_block(Some(&b1), 2);
+    assert!(server.sign_proposal_inner(&other_b2).is_err());
+    assert!(server.sign_proposal_inner(&b2).is_err());
+    assert!(server.sign_proposal_inner(&b1).is_err());
+    assert_eq!(server.safety_state().last_proposed_round, 2);
+    // Proposing does not prevent voting for the proposal.
+    assert!(server
+        .construct_vote_inner(&make_vote(&b2, Some(&b1)))
+        .is_ok());
+    let b3 = validators.make_block(Some(&b2), 3);
+    assert!(server.sign_proposal_inner(&b3).is_ok());
+    assert_eq!(
+        server.safety_state(),
+        SafetyState {
+            last_vote_round: 2,
+            preferred_block_round: 0,
+            last_proposed_round: 3,
+        }
+    );
+}
+
+#[test]
+fn test_no_vote_after_timeout() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, public_key) = signing::generate_keypair();
+    let validators = Validators::new();
+    let server = create_server(&dir, private_key, &validators);
+    let timeout = |round| TimeoutData {
+        epoch: 0,
+        round,
+        author: AccountAddress::random(),
+    };
+
+    let timeout_3 = timeout(3);
+    let signature = server.sign_timeout_inner(&timeout_3).unwrap();
+    assert!(signing::verify_message(timeout_3.hash(), &signature, &public_key).is_ok());
+    let b3 = validators.make_block(None, 3);
+    assert!(server.construct_vote_inner(&make_vote(&b3, None)).is_err());
+    // Timeouts of older rounds are still signed without decreasing the last vote round.
+    assert!(server.sign_timeout_inner(&timeout(2)).is_ok());
+    assert_eq!(server.safety_state().last_vote_round, 3);
+
+    // Signing a timeout message raises the last vote round as well.
+    let timeout_msg = TimeoutMsgData {
+        timeout: timeout(4),
+        highest_certified_block_id: *GENESIS_BLOCK_ID,
+    };
+    let signature = server.sign_timeout_msg_inner(&timeout_msg).unwrap();
+    assert!(signing::verify_message(timeout_msg.hash(), &signature, &public_key).is_ok());
+    assert_eq!(server.safety_state().last_vote_round, 4);
+    let b5 = validators.make_block(None, 5);
+    assert!(server.construct_vote_inner(&make_vote(&b5, None)).is_ok());
+}
+
+#[test]
+fn test_state_survives_restart() {
+    let dir = TempDir::new().unwrap();
+    let (private_key, _) = signing::generate_keypair();
+    let validators = Validators::new();
+    let b1 = validators.make_block(None, 1);
+    let b2 = validators.make_block(Some(&b1), 2);
+    let b3 = validators.make_block(Some(&b2), 3);
+    {
+        let server = create_server(&dir, private_key.clone(), &validators);
+        assert_eq!(server.safety_state(), SafetyState::default());
+        server
+            .construct_vote_inner(&make_vote(&b3, Some(&b2)))
+            .unwrap();
+        server.sign_proposal_inner(&b3).unwrap();
+    }
+
+    let server = create_server(&dir, private_key, &validators);
+    assert_eq!(
+        server.safety_state(),
+        SafetyState {
+            last_vote_round: 3,
+            preferred_block_round: 1,
+            last_proposed_round: 3,
+        }
+    );
+    assert!(server.sign_proposal_inner(&b3).is_err());
+    assert!(server
+        .construct_vote_inner(&make_vote(&b3, Some(&b2)))
+        .is_err());
+    assert!(server
+        .construct_vote_inner(&make_vote(&validators.make_block(None, 4), None))
+        .is_err());
+    let b4 = validators.make_block(Some(&b3), 4);
+    assert!(server
+        .construct_vote_inner(&make_vote(&b4, Some(&b3)))
+        .is_ok());
+}
+
+#[test]
+fn test_corrupted_state_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("safety_rules_state");
+    std::fs::write(&path, b"garbage").unwrap();
+    assert!(SafetyStateStorage::new(path).load().is_err());
+}
@@ -8,16 +8,26 @@ use crate::proto::{
 use config::config::{NodeConfig, NodeConfigHelpers};
 use debug_interface::node_debug_helpers::{check_node_up, create_debug_client};
 use grpcio::{ChannelBuilder, EnvBuilder};
-use std::{sync::Arc, thread};
+use std::{collections::HashMap, convert::TryFrom, sync::Arc, thread};
 
 use crate::{
-    proto::secret_service::KeyType, secret_service_client::ConsensusKeyManager,
+    proto::secret_service::KeyType,
+    safety_rules_client::SafetyRulesClient,
+    safety_rules_data::{vote_digest, BlockData, QuorumCertData, TimeoutData, VoteData},
+    secret_service_client::ConsensusKeyManager,
     secret_service_node::SecretServiceNode,
 };
-use crypto::hash::HashValue;
+use crypto::{
+    hash::{CryptoHash, HashValue, GENESIS_BLOCK_ID},
+    signing,
+};
 use logger::prelude::*;
 // use crate::crypto_wrappers::GenericSignature;
 use nextgen_crypto::traits::Signature;
+use types::{
+    account_address::AccountAddress,
+    ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+};
 
 /////////////////////////////////////////////////////////////////////////////////////
 // These tests check interoperability of key_generation,                           //
@@ -149,3 +159,93 @@ fn test_generate_and_retrieve_key() {
     let is_successful = response.get_code() == ErrorCode::KeyIdNotFound;
     assert!(is_successful);
 }
+
+#[test]
+fn test_safety_rules_service() {
+    let node_config = NodeConfigHelpers::get_single_node_test_config(true);
+    let public_key = node_config.base.peer_keypairs.get_consensus_public();
+    let private_key = node_config.base.peer_keypairs.get_consensus_private();
+    let author = AccountAddress::try_from(node_config.base.peer_id.clone()).unwrap();
+    let _client = create_secret_service_node_and_client(node_config.clone());
+    let safety_rules = SafetyRulesClient::new(
+        &node_config.secret_service.address,
+        node_config.secret_service.secret_service_port,
+    );
+
+    // The certificates are signed by the single validator of the test configuration.
+    let certificate = |certified_block_id, certified_block_round| {
+        let ledger_info = LedgerInfo::new(
+            0,
+            HashValue::zero(),
+            vote_digest(
+                certified_block_id,
+                HashValue::zero(),
+                0,
+                certified_block_round,
+            ),
+            HashValue::zero(),
+            0,
+            0,
+        );
+        let mut signatures = HashMap::new();
+        signatures.insert(
+            author,
+            signing::sign_message(ledger_info.hash(), &private_key).unwrap(),
+        );
+        QuorumCertData {
+            certified_block_id,
+            certified_state_id: HashValue::zero(),
+            certified_state_version: 0,
+            certified_block_round,
+            signed_ledger_info: LedgerInfoWithSignatures::new(ledger_info, signatures),
+        }
+    };
+    let block = |parent_id, parent_round, round| BlockData {
+        epoch: 0,
+        round,
+        height: round,
+        timestamp_usecs: round,
+        payload: vec![],
+        parent_id,
+        quorum_cert: certificate(parent_id, parent_round),
+        author: AccountAddress::random(),
+    };
+    let vote = |block: &BlockData, parent: Option<&BlockData>| VoteData {
+        block: block.clone(),
+        parent: parent.cloned(),
+        executed_state_id: HashValue::zero(),
+        executed_state_version: 0,
+        ledger_info: LedgerInfo::new(
+            0,
+            HashValue::zero(),
+            HashValue::zero(),
+            HashValue::zero(),
+            0,
+            0,
+        ),
+    };
+    let b1 = block(*GENESIS_BLOCK_ID, 0, 1);
+    let b2 = block(b1.hash(), 1, 2);
+
+    // The votes are signed with the consensus key of the node, at most once per round.
+    let vote_b1 = vote(&b1, None);
+    let signature = safety_rules.construct_vote(vote_b1.clone()).unwrap();
+    assert!(signing::verify_message(vote_b1.ledger_info().hash(), &signature, &public_key).is_ok());
+    assert!(safety_rules.construct_vote(vote_b1).is_err());
+
+    // No vote is signed for a round that timed out.
+    let timeout = TimeoutData {
+        epoch: 0,
+        round: 2,
+        author: AccountAddress::random(),
+    };
+    safety_rules.sign_timeout(timeout).unwrap();
+    assert!(safety_rules.construct_vote(vote(&b2, Some(&b1))).is_err());
+
+    // The parent of a block of round 3 extending b2 certifies b1.
+    let b3 = block(b2.hash(), 2, 3);
+    safety_rules.construct_vote(vote(&b3, Some(&b2))).unwrap();
+    let state = safety_rules.safety_state().unwrap();
+    assert_eq!(state.last_vote_round, 3);
+    assert_eq!(state.preferred_block_round, 1);
+}
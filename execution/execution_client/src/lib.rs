@@ -3,7 +3,7 @@
 
 use execution_proto::{
     proto::{execution::CommitBlockRequest, execution_grpc},
-    ExecuteBlockRequest, ExecuteBlockResponse,
+    ExecuteBlockRequest, ExecuteBlockResponse, FinishStateSnapshotRequest,
 };
 use failure::{bail, Result};
 use grpcio::{ChannelBuilder, Environment};
@@ -39,4 +39,12 @@ impl ExecutionClient {
             Err(err) => bail!("GRPC error: {}", err),
         }
     }
+
+    pub fn finish_state_snapshot(&self, request: FinishStateSnapshotRequest) -> Result<()> {
+        let proto_request = request.into_proto();
+        match self.client.finish_state_snapshot(&proto_request) {
+            Ok(_proto_response) => Ok(()),
+            Err(err) => bail!("GRPC error: {}", err),
+        }
+    }
 }
@@ -14,7 +14,10 @@ use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 use types::{
     ledger_info::LedgerInfoWithSignatures,
-    transaction::{SignedTransaction, TransactionListWithProof, TransactionStatus, Version},
+    proof::AccumulatorProof,
+    transaction::{
+        SignedTransaction, TransactionInfo, TransactionListWithProof, TransactionStatus, Version,
+    },
     validator_set::ValidatorSet,
     vm_error::VMStatus,
 };
@@ -192,3 +195,56 @@ pub struct ExecuteChunkRequest {
 #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
 #[ProtoType(crate::proto::execution::ExecuteChunkResponse)]
 pub struct ExecuteChunkResponse {}
+
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct FinishStateSnapshotRequest {
+    pub ledger_info_with_sigs: LedgerInfoWithSignatures,
+    pub transaction_info: TransactionInfo,
+    pub ledger_info_to_transaction_info_proof: AccumulatorProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl FromProto for FinishStateSnapshotRequest {
+    type ProtoType = crate::proto::execution::FinishStateSnapshotRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(FinishStateSnapshotRequest {
+            ledger_info_with_sigs: LedgerInfoWithSignatures::from_proto(
+                object.take_ledger_info_with_sigs(),
+            )?,
+            transaction_info: TransactionInfo::from_proto(object.take_transaction_info())?,
+            ledger_info_to_transaction_info_proof: AccumulatorProof::from_proto(
+                object.take_ledger_info_to_transaction_info_proof(),
+            )?,
+            ledger_frozen_subtree_hashes: object
+                .take_ledger_frozen_subtree_hashes()
+                .into_iter()
+                .map(HashValue::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+impl IntoProto for FinishStateSnapshotRequest {
+    type ProtoType = crate::proto::execution::FinishStateSnapshotRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_ledger_info_with_sigs(self.ledger_info_with_sigs.into_proto());
+        out.set_transaction_info(self.transaction_info.into_proto());
+        out.set_ledger_info_to_transaction_info_proof(
+            self.ledger_info_to_transaction_info_proof.into_proto(),
+        );
+        out.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+            self.ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(HashValue::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        out
+    }
+}
+
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::execution::FinishStateSnapshotResponse)]
+pub struct FinishStateSnapshotResponse {}
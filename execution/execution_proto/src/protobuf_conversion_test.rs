@@ -3,7 +3,8 @@
 
 use crate::{
     CommitBlockRequest, CommitBlockResponse, ExecuteBlockRequest, ExecuteBlockResponse,
-    ExecuteChunkRequest, ExecuteChunkResponse,
+    ExecuteChunkRequest, ExecuteChunkResponse, FinishStateSnapshotRequest,
+    FinishStateSnapshotResponse,
 };
 use proptest::prelude::*;
 use proptest_helpers::with_stack_size;
@@ -26,6 +27,13 @@ proptest! {
     fn test_execute_chunk_request_roundtrip(execute_chunk_request in any::<ExecuteChunkRequest>()) {
         assert_protobuf_encode_decode(&execute_chunk_request);
     }
+
+    #[test]
+    fn test_finish_state_snapshot_request_roundtrip(
+        finish_state_snapshot_request in any::<FinishStateSnapshotRequest>(),
+    ) {
+        assert_protobuf_encode_decode(&finish_state_snapshot_request);
+    }
 }
 
 proptest! {
@@ -38,6 +46,13 @@ proptest! {
     fn test_execute_chunk_response_roundtrip(execute_chunk_response in any::<ExecuteChunkResponse>()) {
         assert_protobuf_encode_decode(&execute_chunk_response);
     }
+
+    #[test]
+    fn test_finish_state_snapshot_response_roundtrip(
+        finish_state_snapshot_response in any::<FinishStateSnapshotResponse>(),
+    ) {
+        assert_protobuf_encode_decode(&finish_state_snapshot_response);
+    }
 }
 
 #[test]
@@ -4,7 +4,9 @@
 #![feature(async_await)]
 
 use config::config::NodeConfig;
-use execution_proto::{CommitBlockRequest, ExecuteBlockRequest, ExecuteChunkRequest};
+use execution_proto::{
+    CommitBlockRequest, ExecuteBlockRequest, ExecuteChunkRequest, FinishStateSnapshotRequest,
+};
 use executor::Executor;
 use failure::Result;
 use futures01::future::Future;
@@ -117,6 +119,35 @@ impl execution_proto::proto::execution_grpc::Execution for ExecutionService {
             }
         }
     }
+
+    fn finish_state_snapshot(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        request: execution_proto::proto::execution::FinishStateSnapshotRequest,
+        sink: grpcio::UnarySink<execution_proto::proto::execution::FinishStateSnapshotResponse>,
+    ) {
+        match FinishStateSnapshotRequest::from_proto(request) {
+            Ok(req) => {
+                let fut = process_response(
+                    self.executor.finish_state_snapshot(
+                        req.ledger_info_with_sigs,
+                        req.transaction_info,
+                        req.ledger_info_to_transaction_info_proof,
+                        req.ledger_frozen_subtree_hashes,
+                    ),
+                    sink,
+                )
+                .boxed()
+                .unit_error()
+                .compat();
+                ctx.spawn(fut);
+            }
+            Err(err) => {
+                let fut = process_conversion_error(err, sink);
+                ctx.spawn(fut);
+            }
+        }
+    }
 }
 
 async fn process_response<T>(
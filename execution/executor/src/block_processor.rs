@@ -12,7 +12,9 @@ use crypto::{
     hash::{CryptoHash, EventAccumulatorHasher, TransactionAccumulatorHasher},
     HashValue,
 };
-use execution_proto::{CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse};
+use execution_proto::{
+    CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse, FinishStateSnapshotResponse,
+};
 use failure::prelude::*;
 use futures::channel::oneshot;
 use logger::prelude::*;
@@ -29,7 +31,7 @@ use types::{
     account_address::AccountAddress,
     account_state_blob::AccountStateBlob,
     ledger_info::LedgerInfoWithSignatures,
-    proof::SparseMerkleProof,
+    proof::{AccumulatorProof, SparseMerkleProof},
     transaction::{
         SignedTransaction, TransactionInfo, TransactionListWithProof, TransactionOutput,
         TransactionPayload, TransactionStatus, TransactionToCommit, Version,
@@ -261,6 +263,23 @@ where
                     .send(res.map(|_| ExecuteChunkResponse {}))
                     .expect("Failed to send execute chunk response.");
             }
+            Command::FinishStateSnapshot {
+                ledger_info_with_sigs,
+                transaction_info,
+                ledger_info_to_transaction_info_proof,
+                ledger_frozen_subtree_hashes,
+                resp_sender,
+            } => {
+                let res = self.finish_state_snapshot(
+                    ledger_info_with_sigs,
+                    transaction_info,
+                    ledger_info_to_transaction_info_proof,
+                    ledger_frozen_subtree_hashes,
+                );
+                resp_sender
+                    .send(res.map(|_| FinishStateSnapshotResponse {}))
+                    .expect("Failed to send finish state snapshot response.");
+            }
         }
     }
 
@@ -419,6 +438,51 @@ where
         Ok(())
     }
 
+    /// Finishes restoring the state snapshot in storage, then resets the committed state to the
+    /// snapshot so that syncing can continue with the transactions after it.
+    fn finish_state_snapshot(
+        &mut self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info().clone();
+        ensure!(
+            ledger_info.version() >= self.committed_transaction_accumulator.num_elements(),
+            "State snapshot at version {} is not newer than the local version {}.",
+            ledger_info.version(),
+            self.committed_transaction_accumulator.num_elements() - 1,
+        );
+        ensure!(
+            self.blocks_to_store.is_empty(),
+            "Can't finish state snapshot while blocks are being committed."
+        );
+
+        self.storage_write_client.finish_state_snapshot(
+            ledger_info_with_sigs,
+            transaction_info.clone(),
+            ledger_info_to_transaction_info_proof,
+            ledger_frozen_subtree_hashes.clone(),
+        )?;
+
+        self.committed_state_tree =
+            Rc::new(SparseMerkleTree::new(transaction_info.state_root_hash()));
+        self.committed_transaction_accumulator = Rc::new(Accumulator::new(
+            ledger_frozen_subtree_hashes,
+            ledger_info.version() + 1,
+        ));
+        self.committed_timestamp_usecs = ledger_info.timestamp_usecs();
+        self.block_tree.reset(ledger_info.consensus_block_id());
+        self.mode = Mode::Normal;
+        info!(
+            "Restored state snapshot at version {}.",
+            ledger_info.version()
+        );
+
+        Ok(())
+    }
+
     /// Verifies the proofs using provided ledger info. Also verifies that the version of the first
     /// transaction matches the lastest committed transaction. If the first few transaction happens
     /// to be older, returns how many need to be skipped and the first version to be committed.
@@ -16,7 +16,9 @@ use crypto::{
     hash::{GENESIS_BLOCK_ID, PRE_GENESIS_BLOCK_ID, SPARSE_MERKLE_PLACEHOLDER_HASH},
     HashValue,
 };
-use execution_proto::{CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse};
+use execution_proto::{
+    CommitBlockResponse, ExecuteBlockResponse, ExecuteChunkResponse, FinishStateSnapshotResponse,
+};
 use failure::{format_err, Result};
 use futures::{channel::oneshot, executor::block_on};
 use lazy_static::lazy_static;
@@ -29,7 +31,8 @@ use std::{
 use storage_client::{StorageRead, StorageWrite};
 use types::{
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    transaction::{SignedTransaction, TransactionListWithProof},
+    proof::AccumulatorProof,
+    transaction::{SignedTransaction, TransactionInfo, TransactionListWithProof},
 };
 use vm_runtime::VMExecutor;
 
@@ -259,6 +262,43 @@ where
         }
         resp_receiver
     }
+
+    /// Finishes restoring the state snapshot at the version of `ledger_info_with_sigs`, whose
+    /// account states have already been written to storage, and continues from that version.
+    pub fn finish_state_snapshot(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> oneshot::Receiver<Result<FinishStateSnapshotResponse>> {
+        debug!(
+            "Received request to finish state snapshot at version {}.",
+            ledger_info_with_sigs.ledger_info().version(),
+        );
+
+        let (resp_sender, resp_receiver) = oneshot::channel();
+        match self
+            .command_sender
+            .lock()
+            .expect("Failed to lock mutex.")
+            .as_ref()
+        {
+            Some(sender) => sender
+                .send(Command::FinishStateSnapshot {
+                    ledger_info_with_sigs,
+                    transaction_info,
+                    ledger_info_to_transaction_info_proof,
+                    ledger_frozen_subtree_hashes,
+                    resp_sender,
+                })
+                .expect("Did block processor thread panic?"),
+            None => resp_sender
+                .send(Err(format_err!("Executor is shutting down.")))
+                .expect("Failed to send error message."),
+        }
+        resp_receiver
+    }
 }
 
 impl<V> Drop for Executor<V> {
@@ -295,4 +335,11 @@ enum Command {
         ledger_info_with_sigs: LedgerInfoWithSignatures,
         resp_sender: oneshot::Sender<Result<ExecuteChunkResponse>>,
     },
+    FinishStateSnapshot {
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+        resp_sender: oneshot::Sender<Result<FinishStateSnapshotResponse>>,
+    },
 }
@@ -2,7 +2,12 @@ use chrono::Utc;
 use futures::{stream::Stream, Future};
 use protobuf::Message;
 
-use admission_control_proto::proto::admission_control::SubmitTransactionRequest;
+use admission_control_proto::{
+    proto::admission_control::{
+        SimulateTransactionRequest, SubmitTransactionBatchRequest, SubmitTransactionRequest,
+    },
+    SubmitTransactionResponse,
+};
 use crypto::{
     hash::CryptoHash,
     signing::{sign_message, KeyPair},
@@ -13,7 +18,9 @@ use libra_wallet::{key_factory::ChildNumber, Mnemonic, WalletLibrary};
 use proto_conv::IntoProto;
 use types::{
     account_address::AccountAddress,
-    transaction::{Program, RawTransaction, RawTransactionBytes, SignedTransaction},
+    transaction::{
+        Program, RawTransaction, RawTransactionBytes, SignedTransaction, TransactionOutput,
+    },
 };
 
 use crate::state::AppState;
@@ -92,6 +99,64 @@ impl Client {
         Ok(sequence_number)
     }
 
+    /// Submits one transfer per `(receiver, num_coins)` in a single batch, using consecutive
+    /// sequence numbers starting at the sender's current one. Returns the sequence number and
+    /// status of each transfer.
+    pub fn transfer_coins_batch(
+        &mut self,
+        state: &AppState,
+        sender: AccountAddress,
+        transfers: Vec<(AccountAddress, u64)>,
+        gas_unit_price: Option<u64>,
+        max_gas_amount: Option<u64>,
+    ) -> Result<Vec<(u64, SubmitTransactionResponse)>> {
+        let first_sequence_number = state.client.get_sequence_number(sender)?;
+        let mut req = SubmitTransactionBatchRequest::new();
+        for (sequence_number, (receiver, num_coins)) in
+            (first_sequence_number..).zip(transfers.into_iter())
+        {
+            let program = vm_genesis::encode_transfer_program(&receiver, num_coins);
+            let mut submit_req = self.create_submit_transaction_req(
+                sender,
+                sequence_number,
+                program,
+                gas_unit_price,
+                max_gas_amount,
+            )?;
+            req.mut_signed_txns().push(submit_req.take_signed_txn());
+        }
+
+        let responses = state.client.submit_transaction_batch(&req)?;
+
+        Ok((first_sequence_number..).zip(responses.into_iter()).collect())
+    }
+
+    /// Simulates a transfer against the latest committed state without submitting it.
+    pub fn simulate_transfer_coins(
+        &mut self,
+        state: &AppState,
+        sender: AccountAddress,
+        receiver: AccountAddress,
+        num_coins: u64,
+        gas_unit_price: Option<u64>,
+        max_gas_amount: Option<u64>,
+    ) -> Result<TransactionOutput> {
+        let program = vm_genesis::encode_transfer_program(&receiver, num_coins);
+        let sequence_number = state.client.get_sequence_number(sender)?;
+        let mut submit_req = self.create_submit_transaction_req(
+            sender,
+            sequence_number,
+            program,
+            gas_unit_price,
+            max_gas_amount,
+        )?;
+
+        let mut req = SimulateTransactionRequest::new();
+        req.set_signed_txn(submit_req.take_signed_txn());
+
+        state.client.simulate_transaction(&req)
+    }
+
     /// Craft a transaction request.
     pub fn create_submit_transaction_req(
         &mut self,
@@ -5,12 +5,18 @@
 use admission_control_proto::{
     proto::{
         admission_control::{
+            SimulateTransactionRequest,
+            SimulateTransactionResponse as ProtoSimulateTransactionResponse,
+            SubmitTransactionBatchRequest,
+            SubmitTransactionBatchResponse as ProtoSubmitTransactionBatchResponse,
             SubmitTransactionRequest, SubmitTransactionResponse as ProtoSubmitTransactionResponse,
         },
         admission_control_grpc::AdmissionControlClient,
     },
-    AdmissionControlStatus, SubmitTransactionResponse,
+    AdmissionControlStatus, SimulateTransactionResponse, SubmitTransactionBatchResponse,
+    SubmitTransactionResponse,
 };
+use crypto::HashValue;
 use failure_ext::prelude::*;
 use futures::Future;
 use grpcio::{CallOption, ChannelBuilder, EnvBuilder};
@@ -26,7 +32,7 @@ use types::{
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
-    transaction::{SignedTransaction, Version},
+    transaction::{SignedTransaction, TransactionOutput, Version},
     validator_verifier::ValidatorVerifier,
     vm_error::{VMStatus, VMValidationStatus},
 };
@@ -109,6 +115,73 @@ impl GRPCClient {
         Ok(resp)
     }
 
+    /// Submits multiple transactions in one request and returns the status of each of them, in
+    /// the order of the request. Transactions of one sender may use consecutive sequence numbers.
+    pub fn submit_transaction_batch(
+        &self,
+        req: &SubmitTransactionBatchRequest,
+    ) -> Result<Vec<SubmitTransactionResponse>> {
+        let mut resp = self.submit_transaction_batch_opt(req);
+
+        let mut try_cnt = 0_u64;
+        while Self::need_to_retry(&mut try_cnt, &resp) {
+            resp = self.submit_transaction_batch_opt(&req);
+        }
+
+        let completed_resp = SubmitTransactionBatchResponse::from_proto(resp?)?;
+        ensure!(
+            completed_resp.responses.len() == req.get_signed_txns().len(),
+            "Expected {} statuses in SubmitTransactionBatchResponse, got {}",
+            req.get_signed_txns().len(),
+            completed_resp.responses.len(),
+        );
+        Ok(completed_resp.responses)
+    }
+
+    fn submit_transaction_batch_opt(
+        &self,
+        req: &SubmitTransactionBatchRequest,
+    ) -> Result<ProtoSubmitTransactionBatchResponse> {
+        Ok(self
+            .client
+            .submit_transaction_batch_opt(req, Self::get_default_grpc_call_option())?)
+    }
+
+    /// Executes a transaction against the latest committed state of the validator without
+    /// committing it and returns the output of the execution.
+    pub fn simulate_transaction(
+        &self,
+        req: &SimulateTransactionRequest,
+    ) -> Result<TransactionOutput> {
+        let mut resp = self.simulate_transaction_opt(req);
+
+        let mut try_cnt = 0_u64;
+        while Self::need_to_retry(&mut try_cnt, &resp) {
+            resp = self.simulate_transaction_opt(&req);
+        }
+
+        let completed_resp = SimulateTransactionResponse::from_proto(resp?)?;
+        if let Some(txn_output) = completed_resp.txn_output {
+            Ok(txn_output)
+        } else if let Some(ac_status) = completed_resp.ac_status {
+            bail!("Simulation failed with AC status: {:?}", ac_status)
+        } else {
+            bail!(
+                "Malformed SimulateTransactionResponse which has no status set, {:?}",
+                completed_resp,
+            )
+        }
+    }
+
+    fn simulate_transaction_opt(
+        &self,
+        req: &SimulateTransactionRequest,
+    ) -> Result<ProtoSimulateTransactionResponse> {
+        Ok(self
+            .client
+            .simulate_transaction_opt(req, Self::get_default_grpc_call_option())?)
+    }
+
     fn submit_transaction_opt(
         &self,
         resp: &SubmitTransactionRequest,
@@ -217,6 +290,23 @@ impl GRPCClient {
         Ok(signed_txn_with_proof.map(|t| (t.signed_transaction, t.events)))
     }
 
+    /// Get committed transaction from validator by the hash of the signed transaction, together
+    /// with its version and events.
+    pub fn get_txn_by_hash(
+        &self,
+        hash: HashValue,
+    ) -> Result<Option<(Version, SignedTransaction, Option<Vec<ContractEvent>>)>> {
+        let req_item = RequestItem::GetTransactionByHash { hash };
+
+        let mut response = self.get_with_proof_sync(vec![req_item])?;
+        let signed_txn_with_proof = response
+            .response_items
+            .remove(0)
+            .into_get_transaction_by_hash_response()?;
+
+        Ok(signed_txn_with_proof.map(|t| (t.version, t.signed_transaction, t.events)))
+    }
+
     /// Get transactions in range (start_version..start_version + limit - 1) from validator.
     pub fn get_txn_by_range(
         &self,
@@ -106,6 +106,74 @@ pub fn transfer_coins(
     })))
 }
 
+#[derive(Deserialize)]
+pub struct TransferData {
+    receiver_addr: String,
+    num_coins: u64,
+}
+
+#[derive(Deserialize)]
+pub struct TransferCoinsBatchData {
+    sender_addr: String,
+    transfers: Vec<TransferData>,
+    gas_unit_price: Option<u64>,
+    max_gas_amount: Option<u64>,
+
+    // authorization
+    #[serde(flatten)]
+    raw_client: RawClient,
+}
+
+#[post("/transfer_coins_batch", data = "<data>")]
+pub fn transfer_coins_batch(
+    state: State<AppState>,
+    data: Json<TransferCoinsBatchData>,
+) -> Result<Json<Vec<SubmitTxnStatusSer>>> {
+    let mut client =
+        Client::from_raw(&data.raw_client)?;
+    let sender = utils::address_from_strings(&data.sender_addr)?;
+    let transfers = data
+        .transfers
+        .iter()
+        .map(|transfer| {
+            utils::address_from_strings(&transfer.receiver_addr)
+                .map(|receiver| (receiver, transfer.num_coins))
+        })
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+
+    let results = client.transfer_coins_batch(
+        &state,
+        sender,
+        transfers,
+        data.gas_unit_price,
+        data.max_gas_amount,
+    )?;
+
+    Ok(Json(results.into_iter().map(SubmitTxnStatusSer::from).collect()))
+}
+
+#[post("/simulate_transfer_coins", data = "<data>")]
+pub fn simulate_transfer_coins(
+    state: State<AppState>,
+    data: Json<TransferCoinsData>,
+) -> Result<Json<TxOutputSer>> {
+    let mut client =
+        Client::from_raw(&data.raw_client)?;
+    let sender = utils::address_from_strings(&data.sender_addr)?;
+    let receiver = utils::address_from_strings(&data.receiver_addr)?;
+
+    let output = client.simulate_transfer_coins(
+        &state,
+        sender,
+        receiver,
+        data.num_coins,
+        data.gas_unit_price,
+        data.max_gas_amount,
+    )?;
+
+    Ok(Json(output.into()))
+}
+
 #[get("/get_committed_txn_by_acc_seq/<addr>?<sequence_number>&<fetch_events>")]
 pub fn get_committed_txn_by_acc_seq(
     state: State<AppState>,
@@ -131,6 +199,26 @@ pub fn get_committed_txn_by_acc_seq(
         })
         .map_err(|err| From::from(err))
 }
+
+#[get("/get_committed_txn_by_hash/<hash>")]
+pub fn get_committed_txn_by_hash(
+    state: State<AppState>,
+    hash: String,
+) -> Result<Json<Option<CommittedTxWithEvents>>> {
+    let hash = utils::hash_from_string(&hash)?;
+
+    state
+        .client
+        .get_txn_by_hash(hash)
+        .map(|val| {
+            Json(val.map(|(version, tx, events)| CommittedTxWithEvents {
+                version,
+                transaction: tx,
+                events,
+            }))
+        })
+        .map_err(|err| From::from(err))
+}
 //
 #[get("/get_committed_txn_by_range?<start_version>&<limit>&<fetch_events>")]
 pub fn get_committed_txn_by_range(
@@ -98,7 +98,10 @@ fn main() -> std::io::Result<()> {
                 handlers::get_latest_account_state,
                 handlers::mint_coins,
                 handlers::transfer_coins,
+                handlers::transfer_coins_batch,
+                handlers::simulate_transfer_coins,
                 handlers::get_committed_txn_by_acc_seq,
+                handlers::get_committed_txn_by_hash,
                 handlers::get_committed_txn_by_range,
                 handlers::get_events_by_account_and_type,
             ],
@@ -1,6 +1,7 @@
 //! Implementations of [Serialize](serde::Serialize) for various remote types.
 use serde::{ser::Serialize, Serializer};
 
+use admission_control_proto::{AdmissionControlStatus, SubmitTransactionResponse};
 use crypto::HashValue;
 use types::{
     access_path::AccessPath,
@@ -8,7 +9,10 @@ use types::{
     account_state_blob::{AccountStateBlob, AccountStateWithProof},
     contract_event::{ContractEvent, EventWithProof},
     proof::{AccountStateProof, AccumulatorProof, EventProof, SparseMerkleProof},
-    transaction::{SignedTransaction, TransactionInfo, Version},
+    transaction::{
+        SignedTransaction, TransactionInfo, TransactionOutput, TransactionStatus, Version,
+    },
+    write_set::WriteSet,
 };
 
 // Pure insanity. Might be better to add derive(Serialize) to all definitions.
@@ -41,6 +45,14 @@ pub struct TxWithEvents {
     pub events: Option<Vec<ContractEvent>>,
 }
 
+#[derive(Serialize)]
+pub struct CommittedTxWithEvents {
+    pub version: Version,
+    pub transaction: SignedTransaction,
+    #[serde(serialize_with = "serialize_contract_events")]
+    pub events: Option<Vec<ContractEvent>>,
+}
+
 pub fn serialize_contract_events<S>(
     value: &Option<Vec<ContractEvent>>,
     serializer: S,
@@ -57,6 +69,65 @@ where
         .serialize(serializer)
 }
 
+#[derive(Serialize)]
+pub struct TxOutputSer {
+    /// Whether the output would be kept, i.e. the transaction would be included in the ledger.
+    pub keep: bool,
+    pub vm_status: String,
+    pub gas_used: u64,
+    pub write_set: WriteSet,
+    #[serde(serialize_with = "serialize_contract_events")]
+    pub events: Option<Vec<ContractEvent>>,
+}
+
+impl From<TransactionOutput> for TxOutputSer {
+    fn from(output: TransactionOutput) -> Self {
+        let (keep, vm_status) = match output.status() {
+            TransactionStatus::Keep(vm_status) => (true, format!("{:?}", vm_status)),
+            TransactionStatus::Discard(vm_status) => (false, format!("{:?}", vm_status)),
+        };
+
+        TxOutputSer {
+            keep,
+            vm_status,
+            gas_used: output.gas_used(),
+            write_set: output.write_set().clone(),
+            events: Some(output.events().to_vec()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct SubmitTxnStatusSer {
+    pub sequence: u64,
+    /// Whether the transaction was accepted into mempool.
+    pub accepted: bool,
+    pub status: String,
+}
+
+impl From<(u64, SubmitTransactionResponse)> for SubmitTxnStatusSer {
+    fn from((sequence, response): (u64, SubmitTransactionResponse)) -> Self {
+        let (accepted, status) = if let Some(ac_status) = response.ac_status {
+            (
+                ac_status == AdmissionControlStatus::Accepted,
+                format!("{:?}", ac_status),
+            )
+        } else if let Some(vm_error) = response.vm_error {
+            (false, format!("{:?}", vm_error))
+        } else if let Some(mempool_error) = response.mempool_error {
+            (false, format!("{:?}", mempool_error))
+        } else {
+            (false, "Unknown".to_string())
+        };
+
+        SubmitTxnStatusSer {
+            sequence,
+            accepted,
+            status,
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct AccWithEvents {
     #[serde(serialize_with = "serialize_account")]
@@ -1,5 +1,6 @@
 use std::convert::{TryFrom, TryInto};
 
+use crypto::HashValue;
 use failure_ext::prelude::*;
 use types::{
     account_address::AccountAddress, account_config::AccountResource,
@@ -22,6 +23,11 @@ pub fn address_from_strings(data: &str) -> Result<AccountAddress> {
     Ok(account)
 }
 
+/// Converts hex representation of a hash into binary.
+pub fn hash_from_string(data: &str) -> Result<HashValue> {
+    HashValue::from_slice(&hex::decode(data)?)
+}
+
 pub fn get_account_resource_or_default(
     account_state: &Option<AccountStateBlob>,
 ) -> Result<AccountResource> {
@@ -90,6 +90,49 @@ pub fn execute_block<'alloc>(
     result
 }
 
+/// Execute a single transaction against `data_view` and return its output. The write set of the
+/// output is not applied anywhere. If `skip_signature_check` is set, the transaction is processed
+/// as if its signature was valid.
+pub fn simulate_transaction<'alloc>(
+    txn: SignedTransaction,
+    code_cache: &VMModuleCache<'alloc>,
+    script_cache: &ScriptCache<'alloc>,
+    data_view: &dyn StateView,
+    publishing_option: &VMPublishingOption,
+    skip_signature_check: bool,
+) -> TransactionOutput {
+    trace!("[VM] Simulate transaction: {:?}", txn);
+    let mode = if data_view.is_genesis() {
+        ValidationMode::Genesis
+    } else {
+        ValidationMode::Executing
+    };
+
+    let signature_verified_txn = if skip_signature_check {
+        SignatureCheckedTransaction::new_unchecked_for_simulation(txn)
+    } else {
+        match txn.check_signature() {
+            Ok(t) => t,
+            Err(_) => {
+                return ExecutedTransaction::discard_error_output(VMStatus::Validation(
+                    VMValidationStatus::InvalidSignature,
+                ))
+            }
+        }
+    };
+
+    let module_cache = BlockModuleCache::new(code_cache, ModuleFetcherImpl::new(data_view));
+    let data_cache = BlockDataCache::new(data_view);
+    transaction_flow(
+        signature_verified_txn,
+        &module_cache,
+        script_cache,
+        &data_cache,
+        mode,
+        publishing_option,
+    )
+}
+
 /// Process a transaction and emit a TransactionOutput.
 ///
 /// A successful execution will have `TransactionStatus::Keep` in the TransactionOutput and a
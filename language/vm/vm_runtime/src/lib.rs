@@ -174,3 +174,16 @@ pub trait VMExecutor {
         state_view: &dyn StateView,
     ) -> Vec<TransactionOutput>;
 }
+
+/// This trait describes the VM's simulation (dry-run) interface.
+pub trait VMSimulator {
+    /// Executes a single transaction against `state_view` and returns its output without
+    /// committing it anywhere. If `skip_signature_check` is set, the signature of the transaction
+    /// is not verified, which lets clients estimate gas before signing.
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+        skip_signature_check: bool,
+    ) -> TransactionOutput;
+}
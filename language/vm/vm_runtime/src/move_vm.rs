@@ -1,7 +1,10 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{loaded_data::loaded_module::LoadedModule, runtime::VMRuntime, VMExecutor, VMVerifier};
+use crate::{
+    loaded_data::loaded_module::LoadedModule, runtime::VMRuntime, VMExecutor, VMSimulator,
+    VMVerifier,
+};
 use state_view::StateView;
 use std::sync::Arc;
 use types::{
@@ -69,6 +72,24 @@ impl VMExecutor for MoveVM {
     }
 }
 
+impl VMSimulator for MoveVM {
+    fn simulate_transaction(
+        transaction: SignedTransaction,
+        config: &VMConfig,
+        state_view: &dyn StateView,
+        skip_signature_check: bool,
+    ) -> TransactionOutput {
+        // A fresh runtime is used for every simulation so that modules published by the simulated
+        // transaction never leak into a cache shared with real executions.
+        let vm = MoveVMImpl::new(Box::new(Arena::new()), |arena| {
+            VMRuntime::new(&*arena, config)
+        });
+        vm.rent(|runtime| {
+            runtime.simulate_transaction(transaction, state_view, skip_signature_check)
+        })
+    }
+}
+
 #[test]
 fn vm_thread_safe() {
     fn assert_send<T: Send>() {}
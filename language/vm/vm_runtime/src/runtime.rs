@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    block_processor::execute_block,
+    block_processor::{execute_block, simulate_transaction},
     code_cache::{
         module_adapter::ModuleFetcherImpl,
         module_cache::{BlockModuleCache, VMModuleCache},
@@ -120,4 +120,23 @@ impl<'alloc> VMRuntime<'alloc> {
             &self.publishing_option,
         )
     }
+
+    /// Execute a single transaction without committing its output. This goes through the same
+    /// flow as `execute_block_transactions`, so the returned output contains the gas used, the
+    /// write set and the events the transaction would produce if it were included in a block.
+    pub fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        data_view: &dyn StateView,
+        skip_signature_check: bool,
+    ) -> TransactionOutput {
+        simulate_transaction(
+            txn,
+            &self.code_cache,
+            &self.script_cache,
+            data_view,
+            &self.publishing_option,
+            skip_signature_check,
+        )
+    }
 }
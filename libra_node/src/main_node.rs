@@ -4,10 +4,18 @@
 use admission_control_proto::proto::admission_control_grpc::{
     create_admission_control, AdmissionControlClient,
 };
-use admission_control_service::admission_control_service::AdmissionControlService;
-use config::config::NodeConfig;
+use admission_control_service::{
+    admission_control_service::AdmissionControlService, transaction_filter::TransactionFilterChain,
+    upstream_proxy::UpstreamProxyService,
+};
+use config::config::{NodeConfig, RoleType};
 use consensus::consensus_provider::{make_consensus_provider, ConsensusProvider};
-use debug_interface::{node_debug_service::NodeDebugService, proto::node_debug_interface_grpc};
+use debug_interface::{
+    node_debug_service::{
+        EquivocationEvidenceSource, NodeDebugService, TransactionLifecycleSource,
+    },
+    proto::node_debug_interface_grpc,
+};
 use execution_proto::proto::execution_grpc;
 use execution_service::ExecutionService;
 use grpc_helpers::ServerHandle;
@@ -78,15 +86,35 @@ fn setup_ac(config: &NodeConfig) -> (::grpcio::Server, AdmissionControlClient) {
 
     let vm_validator = Arc::new(VMValidator::new(&config, Arc::clone(&storage_client)));
 
+    let transaction_filters = Arc::new(
+        TransactionFilterChain::from_config(&config.admission_control.filters)
+            .expect("Invalid admission control filter config"),
+    );
+
     let handle = AdmissionControlService::new(
         mempool_client,
         storage_client,
         vm_validator,
+        transaction_filters,
         config
             .admission_control
             .need_to_check_mempool_before_validation,
     );
-    let service = create_admission_control(handle);
+    let service = match config.base.get_role() {
+        RoleType::Validator => create_admission_control(handle),
+        RoleType::FullNode => {
+            // Full nodes forward the submitted transactions to the AC of a validator
+            let upstream_connection_str = format!(
+                "{}:{}",
+                config.full_node.upstream_ac_address, config.full_node.upstream_ac_port
+            );
+            let upstream_env = Arc::new(EnvBuilder::new().name_prefix("grpc-ac-up-").build());
+            let upstream_client = Arc::new(AdmissionControlClient::new(
+                ChannelBuilder::new(upstream_env).connect(&upstream_connection_str),
+            ));
+            create_admission_control(UpstreamProxyService::new(handle, upstream_client))
+        }
+    };
     let server = ServerBuilder::new(Arc::clone(&env))
         .register_service(service)
         .bind(config.admission_control.address.clone(), port)
@@ -120,11 +148,17 @@ fn setup_executor(config: &NodeConfig) -> ::grpcio::Server {
         .expect("Unable to create grpc server")
 }
 
-fn setup_debug_interface(config: &NodeConfig) -> ::grpcio::Server {
+fn setup_debug_interface(
+    config: &NodeConfig,
+    transaction_lifecycle: Arc<dyn TransactionLifecycleSource>,
+    equivocation_evidence: Arc<dyn EquivocationEvidenceSource>,
+) -> ::grpcio::Server {
     let env = Arc::new(EnvBuilder::new().name_prefix("grpc-debug-").build());
     // Start Debug interface
-    let debug_service =
-        node_debug_interface_grpc::create_node_debug_interface(NodeDebugService::new());
+    let debug_service = node_debug_interface_grpc::create_node_debug_interface(
+        NodeDebugService::with_transaction_lifecycle(transaction_lifecycle)
+            .with_equivocation_evidence(equivocation_evidence),
+    );
     ::grpcio::ServerBuilder::new(env)
         .register_service(debug_service)
         .bind(
@@ -246,8 +280,6 @@ pub fn setup_environment(node_config: &NodeConfig) -> (AdmissionControlClient, L
         MempoolRuntime::bootstrap(&node_config, mempool_network_sender, mempool_network_events);
     debug!("Mempool started in {} ms", instant.elapsed().as_millis());
 
-    let debug_if = ServerHandle::setup(setup_debug_interface(&node_config));
-
     let metrics_port = node_config.debug_interface.metrics_server_port;
     let metric_host = node_config.debug_interface.address.clone();
     thread::spawn(move || metric_server::start_server(metric_host, metrics_port));
@@ -258,6 +290,11 @@ pub fn setup_environment(node_config: &NodeConfig) -> (AdmissionControlClient, L
         consensus_network_sender,
         consensus_network_events,
     );
+    let debug_if = ServerHandle::setup(setup_debug_interface(
+        &node_config,
+        Arc::clone(&mempool.transaction_lifecycle),
+        consensus_provider.equivocation_evidence(),
+    ));
     consensus_provider
         .start()
         .expect("Failed to start consensus. Can't proceed.");
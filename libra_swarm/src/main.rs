@@ -1,6 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+use config::config::RoleType;
 use libra_swarm::{client, swarm::LibraSwarm};
 use std::path::Path;
 use structopt::StructOpt;
@@ -15,6 +16,9 @@ struct Args {
     /// Number of nodes to start (1 by default)
     #[structopt(short = "n", long = "num_nodes")]
     pub num_nodes: Option<usize>,
+    /// Number of full nodes to start in addition to the validators (0 by default)
+    #[structopt(long = "num_full_nodes")]
+    pub num_full_nodes: Option<usize>,
     /// Disable logging (for performance testing)"
     #[structopt(short = "d", long = "disable_logging")]
     pub disable_logging: bool,
@@ -34,6 +38,7 @@ struct Args {
 fn main() {
     let args = Args::from_args();
     let num_nodes = args.num_nodes.unwrap_or(1);
+    let num_full_nodes = args.num_full_nodes.unwrap_or(0);
 
     let (faucet_account_keypair, faucet_key_file_path, _temp_dir) =
         generate_keypair::load_faucet_key_or_create_default(args.faucet_key_path);
@@ -43,15 +48,22 @@ fn main() {
         faucet_key_file_path
     );
 
-    let swarm = LibraSwarm::launch_swarm(
+    let swarm = LibraSwarm::launch_swarm_with_full_nodes(
         num_nodes,
+        num_full_nodes,
         args.disable_logging,
         faucet_account_keypair,
         false, /* tee_logs */
         args.config_dir.clone(),
     );
 
-    let config = &swarm.config.get_configs()[0].1;
+    let config = &swarm
+        .config
+        .get_configs()
+        .iter()
+        .find(|(_, config)| config.base.get_role() == RoleType::Validator)
+        .expect("No validator launched")
+        .1;
     let validator_set_file = &config.base.trusted_peers_file;
     println!("To run the Libra CLI client in a separate process and connect to the local cluster of nodes you just spawned, use this command:");
     println!(
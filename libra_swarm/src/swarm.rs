@@ -5,7 +5,7 @@ use crate::{
     output_tee::{OutputTee, OutputTeeGuard},
     utils,
 };
-use config::config::NodeConfig;
+use config::config::{NodeConfig, RoleType};
 use config_builder::swarm_config::{SwarmConfig, SwarmConfigBuilder};
 use crypto::signing::KeyPair;
 use debug_interface::NodeDebugClient;
@@ -231,6 +231,8 @@ pub struct LibraSwarm {
     pub dir: Option<LibraSwarmDir>,
     // Maps the peer id of a node to the LibraNode struct
     pub nodes: HashMap<String, LibraNode>,
+    // Maps the peer id of a full node to the LibraNode struct
+    pub full_nodes: HashMap<String, LibraNode>,
     pub config: SwarmConfig,
     tee_logs: bool,
 }
@@ -255,12 +257,32 @@ impl LibraSwarm {
         faucet_account_keypair: KeyPair,
         tee_logs: bool,
         config_dir: Option<String>,
+    ) -> Self {
+        Self::launch_swarm_with_full_nodes(
+            num_nodes,
+            0, /* num_full_nodes */
+            disable_logging,
+            faucet_account_keypair,
+            tee_logs,
+            config_dir,
+        )
+    }
+
+    /// Launches `num_nodes` validators and `num_full_nodes` full nodes following them.
+    pub fn launch_swarm_with_full_nodes(
+        num_nodes: usize,
+        num_full_nodes: usize,
+        disable_logging: bool,
+        faucet_account_keypair: KeyPair,
+        tee_logs: bool,
+        config_dir: Option<String>,
     ) -> Self {
         let num_launch_attempts = 5;
         for i in 0..num_launch_attempts {
             info!("Launch swarm attempt: {} of {}", i, num_launch_attempts);
             match Self::launch_swarm_attempt(
                 num_nodes,
+                num_full_nodes,
                 disable_logging,
                 faucet_account_keypair.clone(),
                 tee_logs,
@@ -277,6 +299,7 @@ impl LibraSwarm {
 
     fn launch_swarm_attempt(
         num_nodes: usize,
+        num_full_nodes: usize,
         disable_logging: bool,
         faucet_account_keypair: KeyPair,
         tee_logs: bool,
@@ -301,6 +324,7 @@ impl LibraSwarm {
         config_builder
             .with_ipv4()
             .with_nodes(num_nodes)
+            .with_full_nodes(num_full_nodes)
             .with_base(base)
             .with_output_dir(&dir)
             .with_faucet_keypair(faucet_account_keypair)
@@ -310,6 +334,7 @@ impl LibraSwarm {
         let mut swarm = Self {
             dir: Some(dir),
             nodes: HashMap::new(),
+            full_nodes: HashMap::new(),
             config,
             tee_logs,
         };
@@ -323,7 +348,7 @@ impl LibraSwarm {
                 tee_logs,
             )
             .unwrap();
-            swarm.nodes.insert(node.peer_id(), node);
+            swarm.insert_node(node_config, node);
         }
 
         swarm.wait_for_startup()?;
@@ -334,9 +359,19 @@ impl LibraSwarm {
         Ok(swarm)
     }
 
+    /// Keeps the full nodes apart from the validators.
+    fn insert_node(&mut self, config: &NodeConfig, node: LibraNode) {
+        match config.base.get_role() {
+            RoleType::Validator => self.nodes.insert(node.peer_id(), node),
+            RoleType::FullNode => self.full_nodes.insert(node.peer_id(), node),
+        };
+    }
+
     fn wait_for_connectivity(&self) -> std::result::Result<(), SwarmLaunchFailure> {
+        // Every node, either a validator or a full node, is connected to all the other nodes
+        let num_nodes = self.nodes.len() + self.full_nodes.len();
         // Early return if we're only launching a single node
-        if self.nodes.len() == 1 {
+        if num_nodes == 1 {
             return Ok(());
         }
 
@@ -348,7 +383,8 @@ impl LibraSwarm {
             if self
                 .nodes
                 .values()
-                .all(|node| node.check_connectivity(self.nodes.len() as i64 - 1))
+                .chain(self.full_nodes.values())
+                .all(|node| node.check_connectivity(num_nodes as i64 - 1))
             {
                 return Ok(());
             }
@@ -361,11 +397,12 @@ impl LibraSwarm {
 
     fn wait_for_startup(&mut self) -> std::result::Result<(), SwarmLaunchFailure> {
         let num_attempts = 120;
-        let mut done = vec![false; self.nodes.len()];
+        let mut done = vec![false; self.nodes.len() + self.full_nodes.len()];
 
         for i in 0..num_attempts {
             debug!("Wait for startup attempt: {} of {}", i, num_attempts);
-            for (node, done) in self.nodes.values_mut().zip(done.iter_mut()) {
+            let nodes = self.nodes.values_mut().chain(self.full_nodes.values_mut());
+            for (node, done) in nodes.zip(done.iter_mut()) {
                 if *done {
                     continue;
                 }
@@ -474,6 +511,14 @@ impl LibraSwarm {
         self.nodes.values().map(|node| node.ac_port()).collect()
     }
 
+    /// Vector with the public AC ports of the full nodes.
+    pub fn get_full_nodes_public_ports(&self) -> Vec<u16> {
+        self.full_nodes
+            .values()
+            .map(|node| node.ac_port())
+            .collect()
+    }
+
     /// Vector with the peer ids of the validators in the swarm.
     pub fn get_validators_ids(&self) -> Vec<String> {
         self.nodes.keys().cloned().collect()
@@ -484,6 +529,7 @@ impl LibraSwarm {
         self.config
             .get_configs()
             .iter()
+            .filter(|(_, c)| c.base.get_role() == RoleType::Validator)
             .map(|(_, c)| c.debug_interface.admission_control_node_debug_port)
             .collect()
     }
@@ -509,7 +555,7 @@ impl LibraSwarm {
         }
         for (path, config) in configs {
             if config.base.peer_id == peer_id {
-                return self.launch_node(peer_id, &path, &config, disable_logging);
+                return self.launch_node(&path, &config, disable_logging);
             }
         }
         panic!(
@@ -520,7 +566,6 @@ impl LibraSwarm {
 
     fn launch_node(
         &mut self,
-        peer_id: String,
         path: &PathBuf,
         config: &NodeConfig,
         disable_logging: bool,
@@ -531,7 +576,7 @@ impl LibraSwarm {
                 .unwrap();
         for _ in 0..60 {
             if let HealthStatus::Healthy = node.health_check() {
-                self.nodes.insert(peer_id, node);
+                self.insert_node(config, node);
                 return self.wait_for_connectivity();
             }
             ::std::thread::sleep(::std::time::Duration::from_millis(1000));
@@ -5,7 +5,7 @@
 use crate::core_mempool::transaction::{MempoolTransaction, TimelineState};
 use std::{
     cmp::Ordering,
-    collections::{btree_set::Iter, BTreeMap, BTreeSet},
+    collections::{btree_set::Iter, BTreeMap, BTreeSet, HashSet},
     iter::Rev,
     ops::Bound,
     time::Duration,
@@ -232,28 +232,44 @@ impl TimelineIndex {
 /// e.g. transactions that can't be included in next block
 /// (because their sequence number is too high)
 /// we keep separate index to be able to efficiently evict them when Mempool is full
+/// Transactions of priority lanes don't take up regular capacity, so they are kept apart and
+/// never evicted
 pub struct ParkingLotIndex {
     data: BTreeSet<TxnPointer>,
+    priority_lanes: HashSet<TxnPointer>,
 }
 
 impl ParkingLotIndex {
     pub(crate) fn new() -> Self {
         Self {
             data: BTreeSet::new(),
+            priority_lanes: HashSet::new(),
         }
     }
 
     /// add transaction to index
     pub(crate) fn insert(&mut self, txn: &MempoolTransaction) {
-        self.data.insert(TxnPointer::from(txn));
+        if txn.priority_lane.is_some() {
+            self.priority_lanes.insert(TxnPointer::from(txn));
+        } else {
+            self.data.insert(TxnPointer::from(txn));
+        }
     }
 
     /// remove transaction from index
     pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
-        self.data.remove(&TxnPointer::from(txn));
+        let pointer = TxnPointer::from(txn);
+        self.data.remove(&pointer);
+        self.priority_lanes.remove(&pointer);
+    }
+
+    pub(crate) fn contains(&self, txn: &MempoolTransaction) -> bool {
+        let pointer = TxnPointer::from(txn);
+        self.data.contains(&pointer) || self.priority_lanes.contains(&pointer)
     }
 
     /// returns random "non-ready" transaction (with highest sequence number for that account)
+    /// that doesn't belong to a priority lane
     pub(crate) fn pop(&mut self) -> Option<TxnPointer> {
         self.data.iter().rev().next().cloned()
     }
@@ -0,0 +1,122 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounded in-memory history of the states each transaction went through in Mempool. It is used
+//! to debug why a particular transaction never made it into a block.
+
+use crypto::HashValue;
+use lru_cache::LruCache;
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use types::PeerId;
+
+/// Max number of events kept per transaction. Older events are dropped first.
+const MAX_EVENTS_PER_TXN: usize = 32;
+
+/// State change of a transaction in Mempool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// received from a client through AC
+    ReceivedFromClient,
+    /// received from another validator through shared mempool
+    ReceivedFromPeer(PeerId),
+    /// passed VM validation
+    Validated,
+    /// was not added to Mempool
+    Rejected(String),
+    /// can be included in the next block
+    Ready,
+    /// waits for a preceding sequence number of the account
+    Parked,
+    /// sent to a peer through shared mempool
+    Broadcast(PeerId),
+    /// returned to Consensus in a GetBlock response
+    IncludedInBlock,
+    /// committed to storage
+    Committed,
+    /// processed by Consensus but rejected at execution
+    Discarded,
+    /// removed from Mempool without being committed
+    Evicted(String),
+}
+
+impl LifecycleEvent {
+    /// Name of the state the transaction entered
+    pub(crate) fn state(&self) -> &'static str {
+        match self {
+            LifecycleEvent::ReceivedFromClient | LifecycleEvent::ReceivedFromPeer(_) => "Received",
+            LifecycleEvent::Validated => "Validated",
+            LifecycleEvent::Rejected(_) => "Rejected",
+            LifecycleEvent::Ready => "Ready",
+            LifecycleEvent::Parked => "Parked",
+            LifecycleEvent::Broadcast(_) => "Broadcast",
+            LifecycleEvent::IncludedInBlock => "IncludedInBlock",
+            LifecycleEvent::Committed => "Committed",
+            LifecycleEvent::Discarded => "Discarded",
+            LifecycleEvent::Evicted(_) => "Evicted",
+        }
+    }
+
+    /// Additional details of the event, e.g. the peer involved or the reason of an eviction
+    pub(crate) fn details(&self) -> String {
+        match self {
+            LifecycleEvent::ReceivedFromClient => "client".to_string(),
+            LifecycleEvent::ReceivedFromPeer(peer_id) | LifecycleEvent::Broadcast(peer_id) => {
+                format!("peer {}", peer_id)
+            }
+            LifecycleEvent::Rejected(reason) | LifecycleEvent::Evicted(reason) => reason.clone(),
+            _ => "".to_string(),
+        }
+    }
+}
+
+/// Event with the time it was recorded at
+#[derive(Clone, Debug)]
+pub struct LifecycleRecord {
+    pub timestamp_usecs: u64,
+    pub event: LifecycleEvent,
+}
+
+/// Keeps the last `MAX_EVENTS_PER_TXN` events of the most recently updated transactions
+pub struct TransactionLifecycleLog {
+    history: LruCache<HashValue, VecDeque<LifecycleRecord>>,
+}
+
+impl TransactionLifecycleLog {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            history: LruCache::new(capacity),
+        }
+    }
+
+    pub(crate) fn record(&mut self, txn_hash: HashValue, event: LifecycleEvent) {
+        let timestamp_usecs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("init timestamp failure")
+            .as_micros() as u64;
+        let record = LifecycleRecord {
+            timestamp_usecs,
+            event,
+        };
+        if let Some(records) = self.history.get_mut(&txn_hash) {
+            if records.len() >= MAX_EVENTS_PER_TXN {
+                records.pop_front();
+            }
+            records.push_back(record);
+            return;
+        }
+        let mut records = VecDeque::new();
+        records.push_back(record);
+        self.history.insert(txn_hash, records);
+    }
+
+    /// returns events of given transaction, oldest first
+    pub(crate) fn get(&mut self, txn_hash: &HashValue) -> Vec<LifecycleRecord> {
+        self.history
+            .get_mut(txn_hash)
+            .map(|records| records.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
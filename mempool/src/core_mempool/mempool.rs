@@ -7,7 +7,8 @@ use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::{
     core_mempool::{
-        index::TxnPointer,
+        index::{OrderedQueueKey, TxnPointer},
+        lifecycle::{LifecycleEvent, LifecycleRecord},
         transaction::{MempoolAddTransactionStatus, MempoolTransaction, TimelineState},
         transaction_store::TransactionStore,
     },
@@ -16,6 +17,7 @@ use crate::{
 };
 use chrono::Utc;
 use config::config::NodeConfig;
+use crypto::{hash::CryptoHash, HashValue};
 use logger::prelude::*;
 use lru_cache::LruCache;
 use std::{
@@ -63,6 +65,14 @@ impl Mempool {
         );
         self.log_latency(sender.clone(), sequence_number, "e2e.latency");
         self.metrics_cache.remove(&(*sender, sequence_number));
+        if let Some(txn) = self.transactions.get(sender, sequence_number) {
+            let event = if is_rejected {
+                LifecycleEvent::Discarded
+            } else {
+                LifecycleEvent::Committed
+            };
+            self.transactions.record_lifecycle_event(txn.hash(), event);
+        }
 
         // update current cached sequence number for account
         let cached_value = self
@@ -111,13 +121,17 @@ impl Mempool {
             db_sequence_number
         );
 
+        let txn_hash = txn.hash();
         let required_balance = self.get_required_balance(&txn, gas_amount);
         if balance < required_balance {
-            return MempoolAddTransactionStatus::new(
-                MempoolAddTransactionStatusCode::InsufficientBalance,
-                format!(
-                    "balance: {}, required_balance: {}, gas_amount: {}",
-                    balance, required_balance, gas_amount
+            return self.reject_txn(
+                txn_hash,
+                MempoolAddTransactionStatus::new(
+                    MempoolAddTransactionStatusCode::InsufficientBalance,
+                    format!(
+                        "balance: {}, required_balance: {}, gas_amount: {}",
+                        balance, required_balance, gas_amount
+                    ),
                 ),
             );
         }
@@ -132,12 +146,15 @@ impl Mempool {
 
         // don't accept old transactions (e.g. seq is less than account's current seq_number)
         if txn.sequence_number() < sequence_number {
-            return MempoolAddTransactionStatus::new(
-                MempoolAddTransactionStatusCode::InvalidSeqNumber,
-                format!(
-                    "transaction sequence number is {}, current sequence number is  {}",
-                    txn.sequence_number(),
-                    sequence_number,
+            return self.reject_txn(
+                txn_hash,
+                MempoolAddTransactionStatus::new(
+                    MempoolAddTransactionStatusCode::InvalidSeqNumber,
+                    format!(
+                        "transaction sequence number is {}, current sequence number is  {}",
+                        txn.sequence_number(),
+                        sequence_number,
+                    ),
                 ),
             );
         }
@@ -156,16 +173,33 @@ impl Mempool {
 
         let status = self.transactions.insert(txn_info, sequence_number);
         OP_COUNTERS.inc(&format!("insert.{:?}", status));
+        if status.code != MempoolAddTransactionStatusCode::Valid {
+            return self.reject_txn(txn_hash, status);
+        }
+        status
+    }
+
+    fn reject_txn(
+        &mut self,
+        txn_hash: HashValue,
+        status: MempoolAddTransactionStatus,
+    ) -> MempoolAddTransactionStatus {
+        self.transactions.record_lifecycle_event(
+            txn_hash,
+            LifecycleEvent::Rejected(format!("{:?}: {}", status.code, status.message)),
+        );
         status
     }
 
     /// Fetches next block of transactions for consensus
     /// `batch_size` - size of requested block
+    /// `priority_batch_size` - part of the block reserved for transactions of priority lanes
     /// `seen_txns` - transactions that were sent to Consensus but were not committed yet
     ///  Mempool should filter out such transactions
     pub(crate) fn get_block(
         &mut self,
         batch_size: u64,
+        priority_batch_size: u64,
         mut seen: HashSet<TxnPointer>,
     ) -> Vec<SignedTransaction> {
         let mut result = vec![];
@@ -177,13 +211,61 @@ impl Mempool {
         // `skipped` DS and rechecked once it's ancestor becomes available
         let mut skipped = HashSet::new();
 
+        // fill reserved space with transactions of priority lanes first
+        // they stay in the main queue too, so they can also take up the rest of the block
+        Self::fill_block(
+            self.transactions.iter_priority_lanes(),
+            &mut self.sequence_number_cache,
+            min(priority_batch_size, batch_size),
+            &mut seen,
+            &mut skipped,
+            &mut result,
+        );
         // iterate over the queue of transactions based on gas price
-        'main: for txn in self.transactions.iter_queue() {
+        Self::fill_block(
+            self.transactions.iter_queue(),
+            &mut self.sequence_number_cache,
+            batch_size,
+            &mut seen,
+            &mut skipped,
+            &mut result,
+        );
+
+        // convert transaction pointers to real values
+        let block: Vec<_> = result
+            .into_iter()
+            .filter_map(|(address, seq)| self.transactions.get(&address, seq))
+            .collect();
+        for transaction in &block {
+            self.log_latency(
+                transaction.sender(),
+                transaction.sequence_number(),
+                "txn_pre_consensus_ms",
+            );
+            self.transactions
+                .record_lifecycle_event(transaction.hash(), LifecycleEvent::IncludedInBlock);
+        }
+        block
+    }
+
+    /// Adds transactions from `queue` to `result` until it has `batch_size` transactions
+    fn fill_block<'a>(
+        queue: impl Iterator<Item = &'a OrderedQueueKey>,
+        sequence_number_cache: &mut LruCache<AccountAddress, u64>,
+        batch_size: u64,
+        seen: &mut HashSet<TxnPointer>,
+        skipped: &mut HashSet<TxnPointer>,
+        result: &mut Vec<TxnPointer>,
+    ) {
+        if (result.len() as u64) >= batch_size {
+            return;
+        }
+        'main: for txn in queue {
             if seen.contains(&TxnPointer::from(txn)) {
                 continue;
             }
             let mut seq = txn.sequence_number;
-            let account_sequence_number = self.sequence_number_cache.get_mut(&txn.address);
+            let account_sequence_number = sequence_number_cache.get_mut(&txn.address);
             let seen_previous = seq > 0 && seen.contains(&(txn.address, seq - 1));
             // include transaction if it's "next" for given account or
             // we've already sent its ancestor to Consensus
@@ -198,7 +280,7 @@ impl Mempool {
                 // check if we can now include some transactions
                 // that were skipped before for given account
                 let mut skipped_txn = (txn.address, seq + 1);
-                while skipped.contains(&skipped_txn) {
+                while skipped.contains(&skipped_txn) && !seen.contains(&skipped_txn) {
                     seen.insert(skipped_txn);
                     result.push(skipped_txn);
                     if (result.len() as u64) == batch_size {
@@ -210,19 +292,6 @@ impl Mempool {
                 skipped.insert(TxnPointer::from(txn));
             }
         }
-        // convert transaction pointers to real values
-        let block: Vec<_> = result
-            .into_iter()
-            .filter_map(|(address, seq)| self.transactions.get(&address, seq))
-            .collect();
-        for transaction in &block {
-            self.log_latency(
-                transaction.sender(),
-                transaction.sequence_number(),
-                "txn_pre_consensus_ms",
-            );
-        }
-        block
     }
 
     /// TTL based garbage collection. Remove all transactions that got expired
@@ -245,6 +314,17 @@ impl Mempool {
         self.transactions.read_timeline(timeline_id, count)
     }
 
+    /// Records a state change of transaction with given hash that happened outside of core
+    /// mempool, e.g. its validation or broadcast by shared mempool
+    pub(crate) fn record_lifecycle_event(&mut self, txn_hash: HashValue, event: LifecycleEvent) {
+        self.transactions.record_lifecycle_event(txn_hash, event);
+    }
+
+    /// Returns the recorded state changes of transaction with given hash, oldest first
+    pub(crate) fn get_lifecycle(&mut self, txn_hash: &HashValue) -> Vec<LifecycleRecord> {
+        self.transactions.get_lifecycle(txn_hash)
+    }
+
     /// Check the health of core mempool.
     pub(crate) fn health_check(&self) -> bool {
         self.transactions.health_check()
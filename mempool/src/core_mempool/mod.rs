@@ -2,12 +2,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod index;
+mod lifecycle;
 mod mempool;
+mod priority_lane;
 mod transaction;
 mod transaction_store;
 
 pub use self::{
     index::TxnPointer,
+    lifecycle::{LifecycleEvent, LifecycleRecord},
     mempool::Mempool as CoreMempool,
     transaction::{MempoolAddTransactionStatus, TimelineState},
 };
@@ -0,0 +1,181 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Priority lanes keep system and governance transactions from being starved by ordinary
+//! payments. Each lane has its own capacity budget and its own priority queue, which is drained
+//! first into the block space Consensus reserves for priority lanes.
+//!
+//! Anyone can send a transaction running a designated script, so senders that only match a lane
+//! by script hash get a small share of its capacity each. Designated senders are authenticated by
+//! the VM before their transactions reach Mempool and aren't limited that way.
+
+use crate::core_mempool::{
+    index::{OrderedQueueKey, PriorityIndex},
+    transaction::MempoolTransaction,
+};
+use config::config::PriorityLaneConfig;
+use crypto::HashValue;
+use failure::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+use types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+
+struct PriorityLane {
+    name: String,
+    senders: HashSet<AccountAddress>,
+    script_hashes: HashSet<HashValue>,
+    capacity: usize,
+    // max number of transactions in the lane per sender that isn't designated
+    capacity_per_sender: usize,
+    // number of transactions of the lane in Mempool (both ready and parked)
+    size: usize,
+    // number of transactions of the lane in Mempool per sender that isn't designated
+    size_per_sender: HashMap<AccountAddress, usize>,
+    // ready transactions of the lane
+    index: PriorityIndex,
+}
+
+impl PriorityLane {
+    fn new(config: &PriorityLaneConfig) -> Result<Self> {
+        let senders = config
+            .senders
+            .iter()
+            .map(|address| AccountAddress::from_str(address))
+            .collect::<Result<_>>()?;
+        let script_hashes = config
+            .script_hashes
+            .iter()
+            .map(|script_hash| HashValue::from_slice(&hex::decode(script_hash)?))
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            name: config.name.clone(),
+            senders,
+            script_hashes,
+            capacity: config.capacity,
+            capacity_per_sender: config.capacity_per_sender,
+            size: 0,
+            size_per_sender: HashMap::new(),
+            index: PriorityIndex::new(),
+        })
+    }
+
+    fn matches(&self, txn: &SignedTransaction) -> bool {
+        if self.senders.contains(&txn.sender()) {
+            return true;
+        }
+        match txn.payload() {
+            TransactionPayload::Program(program) => self
+                .script_hashes
+                .contains(&HashValue::from_iter_sha3(vec![program.code()])),
+            TransactionPayload::WriteSet(_) => false,
+        }
+    }
+}
+
+/// Configured priority lanes, in descending order of priority
+pub struct PriorityLanes {
+    lanes: Vec<PriorityLane>,
+}
+
+impl PriorityLanes {
+    pub(crate) fn new(configs: &[PriorityLaneConfig]) -> Self {
+        let lanes = configs
+            .iter()
+            .map(|config| {
+                PriorityLane::new(config).unwrap_or_else(|e| {
+                    panic!("Invalid config of priority lane {}: {}", config.name, e)
+                })
+            })
+            .collect();
+        Self { lanes }
+    }
+
+    /// returns the lane of given transaction. A transaction belongs to the first matching lane
+    pub(crate) fn classify(&self, txn: &SignedTransaction) -> Option<usize> {
+        self.lanes.iter().position(|lane| lane.matches(txn))
+    }
+
+    /// checks if lane has room for one more transaction of `sender`
+    /// returns description of the lane's usage if it's full
+    pub(crate) fn check_if_full(&self, lane: usize, sender: &AccountAddress) -> Option<String> {
+        let lane = &self.lanes[lane];
+        if lane.size >= lane.capacity {
+            return Some(format!(
+                "priority lane {} size: {}, capacity: {}",
+                lane.name, lane.size, lane.capacity
+            ));
+        }
+        if !lane.senders.contains(sender) {
+            let sender_size = lane.size_per_sender.get(sender).cloned().unwrap_or(0);
+            if sender_size >= lane.capacity_per_sender {
+                return Some(format!(
+                    "priority lane {} sender {} size: {}, capacity per sender: {}",
+                    lane.name, sender, sender_size, lane.capacity_per_sender
+                ));
+            }
+        }
+        None
+    }
+
+    /// name of the lane, used for counters
+    pub(crate) fn name(&self, lane: usize) -> &str {
+        &self.lanes[lane].name
+    }
+
+    /// total number of transactions of all lanes
+    pub(crate) fn size(&self) -> usize {
+        self.lanes.iter().map(|lane| lane.size).sum()
+    }
+
+    /// accounts for transaction added to Mempool
+    pub(crate) fn add(&mut self, txn: &MempoolTransaction) {
+        if let Some(lane) = txn.priority_lane {
+            let lane = &mut self.lanes[lane];
+            lane.size += 1;
+            let sender = txn.get_sender();
+            if !lane.senders.contains(&sender) {
+                *lane.size_per_sender.entry(sender).or_insert(0) += 1;
+            }
+        }
+    }
+
+    /// accounts for transaction removed from Mempool
+    pub(crate) fn remove(&mut self, txn: &MempoolTransaction) {
+        if let Some(lane) = txn.priority_lane {
+            let lane = &mut self.lanes[lane];
+            lane.size -= 1;
+            lane.index.remove(txn);
+            let sender = txn.get_sender();
+            if let Some(sender_size) = lane.size_per_sender.get_mut(&sender) {
+                *sender_size -= 1;
+                if *sender_size == 0 {
+                    lane.size_per_sender.remove(&sender);
+                }
+            }
+        }
+    }
+
+    /// add transaction to priority queue of its lane
+    pub(crate) fn index_insert(&mut self, txn: &MempoolTransaction) {
+        if let Some(lane) = txn.priority_lane {
+            self.lanes[lane].index.insert(txn);
+        }
+    }
+
+    /// remove transaction from priority queue of its lane
+    pub(crate) fn index_remove(&mut self, txn: &MempoolTransaction) {
+        if let Some(lane) = txn.priority_lane {
+            self.lanes[lane].index.remove(txn);
+        }
+    }
+
+    /// returns iterator over ready transactions of all lanes, lane by lane
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &OrderedQueueKey> {
+        self.lanes.iter().flat_map(|lane| lane.index.iter())
+    }
+}
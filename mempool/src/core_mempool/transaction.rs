@@ -14,6 +14,8 @@ pub struct MempoolTransaction {
     pub expiration_time: Duration,
     pub gas_amount: u64,
     pub timeline_state: TimelineState,
+    // index of the priority lane the transaction belongs to, if any
+    pub priority_lane: Option<usize>,
 }
 
 impl MempoolTransaction {
@@ -28,6 +30,7 @@ impl MempoolTransaction {
             gas_amount,
             expiration_time,
             timeline_state,
+            priority_lane: None,
         }
     }
     pub(crate) fn get_sequence_number(&self) -> u64 {
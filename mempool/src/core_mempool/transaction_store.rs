@@ -4,15 +4,18 @@
 use crate::{
     core_mempool::{
         index::{
-            AccountTransactions, ParkingLotIndex, PriorityIndex, PriorityQueueIter, TTLIndex,
-            TimelineIndex,
+            AccountTransactions, OrderedQueueKey, ParkingLotIndex, PriorityIndex,
+            PriorityQueueIter, TTLIndex, TimelineIndex,
         },
+        lifecycle::{LifecycleEvent, LifecycleRecord, TransactionLifecycleLog},
+        priority_lane::PriorityLanes,
         transaction::{MempoolAddTransactionStatus, MempoolTransaction, TimelineState},
     },
     proto::shared::mempool_status::MempoolAddTransactionStatusCode,
     OP_COUNTERS,
 };
 use config::config::MempoolConfig;
+use crypto::{hash::CryptoHash, HashValue};
 use std::{
     collections::HashMap,
     ops::Bound,
@@ -36,6 +39,11 @@ pub struct TransactionStore {
     timeline_index: TimelineIndex,
     // keeps track of "non-ready" txns (transactions that can't be included in next block)
     parking_lot_index: ParkingLotIndex,
+    // priority queues and capacity budgets of system and governance transactions
+    priority_lanes: PriorityLanes,
+
+    // history of transaction state changes, for debugging
+    lifecycle_log: TransactionLifecycleLog,
 
     // configuration
     capacity: usize,
@@ -56,6 +64,9 @@ impl TransactionStore {
             priority_index: PriorityIndex::new(),
             timeline_index: TimelineIndex::new(),
             parking_lot_index: ParkingLotIndex::new(),
+            priority_lanes: PriorityLanes::new(&config.priority_lanes),
+
+            lifecycle_log: TransactionLifecycleLog::new(config.lifecycle_log_capacity),
 
             // configuration
             capacity: config.capacity,
@@ -81,19 +92,28 @@ impl TransactionStore {
     /// performs validation checks and updates indexes
     pub(crate) fn insert(
         &mut self,
-        txn: MempoolTransaction,
+        mut txn: MempoolTransaction,
         current_sequence_number: u64,
     ) -> MempoolAddTransactionStatus {
         let (is_update, status) = self.check_for_update(&txn);
         if is_update {
             return status;
         }
-        if self.check_if_full() {
+        // transactions of priority lanes have separate capacity budgets
+        txn.priority_lane = self.priority_lanes.classify(&txn.txn);
+        if let Some(lane) = txn.priority_lane {
+            if let Some(message) = self.priority_lanes.check_if_full(lane, &txn.get_sender()) {
+                return MempoolAddTransactionStatus::new(
+                    MempoolAddTransactionStatusCode::MempoolIsFull,
+                    message,
+                );
+            }
+        } else if self.check_if_full() {
             return MempoolAddTransactionStatus::new(
                 MempoolAddTransactionStatusCode::MempoolIsFull,
                 format!(
                     "mempool size: {}, capacity: {}",
-                    self.system_ttl_index.size(),
+                    self.regular_size(),
                     self.capacity,
                 ),
             );
@@ -122,6 +142,13 @@ impl TransactionStore {
             // insert into storage and other indexes
             self.system_ttl_index.insert(&txn);
             self.expiration_time_index.insert(&txn);
+            self.priority_lanes.add(&txn);
+            if let Some(lane) = txn.priority_lane {
+                OP_COUNTERS.inc(&format!(
+                    "insert.priority_lane.{}",
+                    self.priority_lanes.name(lane)
+                ));
+            }
             txns.insert(sequence_number, txn);
             OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
         }
@@ -131,23 +158,41 @@ impl TransactionStore {
 
     /// Check whether the queue size >= threshold in config.
     pub(crate) fn health_check(&self) -> bool {
-        self.system_ttl_index.size() <= self.capacity
+        self.regular_size() <= self.capacity
+    }
+
+    /// number of transactions in Mempool that don't belong to any priority lane
+    fn regular_size(&self) -> usize {
+        let priority_lanes_size = self.priority_lanes.size();
+        debug_assert!(
+            priority_lanes_size <= self.system_ttl_index.size(),
+            "priority lanes size {} exceeds mempool size {}",
+            priority_lanes_size,
+            self.system_ttl_index.size()
+        );
+        self.system_ttl_index
+            .size()
+            .saturating_sub(priority_lanes_size)
     }
 
     /// checks if Mempool is full
     /// If it's full, tries to free some space by evicting transactions from ParkingLot
     fn check_if_full(&mut self) -> bool {
-        if self.system_ttl_index.size() >= self.capacity {
+        if self.regular_size() >= self.capacity {
             // try to free some space in Mempool from ParkingLot
             if let Some((address, sequence_number)) = self.parking_lot_index.pop() {
                 if let Some(txns) = self.transactions.get_mut(&address) {
                     if let Some(txn) = txns.remove(&sequence_number) {
                         self.index_remove(&txn);
+                        self.lifecycle_log.record(
+                            txn.txn.hash(),
+                            LifecycleEvent::Evicted("mempool is full".to_string()),
+                        );
                     }
                 }
             }
         }
-        self.system_ttl_index.size() >= self.capacity
+        self.regular_size() >= self.capacity
     }
 
     /// check if transaction is already present in Mempool
@@ -177,9 +222,40 @@ impl TransactionStore {
                         ),
                     );
                 } else {
+                    // the new version may belong to another priority lane, which needs room for it
+                    let priority_lane = self.priority_lanes.classify(&txn.txn);
+                    if priority_lane != current_version.priority_lane {
+                        if let Some(lane) = priority_lane {
+                            if let Some(message) =
+                                self.priority_lanes.check_if_full(lane, &txn.get_sender())
+                            {
+                                return (
+                                    is_update,
+                                    MempoolAddTransactionStatus::new(
+                                        MempoolAddTransactionStatusCode::MempoolIsFull,
+                                        message,
+                                    ),
+                                );
+                            }
+                        }
+                    }
+                    let is_ready = self.priority_index.contains(&current_version);
+                    let is_parked = self.parking_lot_index.contains(&current_version);
                     self.priority_index.remove(&current_version);
+                    self.parking_lot_index.remove(&current_version);
+                    self.priority_lanes.remove(&current_version);
+
                     current_version.txn = txn.txn.clone();
-                    self.priority_index.insert(&current_version);
+                    current_version.priority_lane = priority_lane;
+
+                    self.priority_lanes.add(&current_version);
+                    if is_ready {
+                        self.priority_index.insert(&current_version);
+                        self.priority_lanes.index_insert(&current_version);
+                    }
+                    if is_parked {
+                        self.parking_lot_index.insert(&current_version);
+                    }
                 }
             }
         }
@@ -199,7 +275,12 @@ impl TransactionStore {
         if let Some(txns) = self.transactions.get_mut(&address) {
             let mut sequence_number = current_sequence_number;
             while let Some(txn) = txns.get_mut(&sequence_number) {
+                if !self.priority_index.contains(txn) {
+                    self.lifecycle_log
+                        .record(txn.txn.hash(), LifecycleEvent::Ready);
+                }
                 self.priority_index.insert(txn);
+                self.priority_lanes.index_insert(txn);
 
                 if txn.timeline_state == TimelineState::NotReady {
                     self.timeline_index.insert(txn);
@@ -210,6 +291,10 @@ impl TransactionStore {
                 match txn.timeline_state {
                     TimelineState::Ready(_) => {}
                     _ => {
+                        if !self.parking_lot_index.contains(&txn) {
+                            self.lifecycle_log
+                                .record(txn.txn.hash(), LifecycleEvent::Parked);
+                        }
                         self.parking_lot_index.insert(&txn);
                     }
                 }
@@ -233,6 +318,15 @@ impl TransactionStore {
 
             for transaction in txns_for_removal.values() {
                 self.index_remove(transaction);
+                if transaction.get_sequence_number() < sequence_number {
+                    self.lifecycle_log.record(
+                        transaction.txn.hash(),
+                        LifecycleEvent::Evicted(format!(
+                            "sequence number {} was committed",
+                            sequence_number
+                        )),
+                    );
+                }
             }
         }
         self.process_ready_transactions(account, sequence_number + 1);
@@ -245,6 +339,7 @@ impl TransactionStore {
         self.priority_index.remove(&txn);
         self.timeline_index.remove(&txn);
         self.parking_lot_index.remove(&txn);
+        self.priority_lanes.remove(&txn);
         OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
     }
 
@@ -306,8 +401,13 @@ impl TransactionStore {
             if let Some(txns) = self.transactions.get_mut(&key.address) {
                 // mark all following transactions as non-ready
                 for (_, t) in txns.range((Bound::Excluded(key.sequence_number), Bound::Unbounded)) {
+                    if !self.parking_lot_index.contains(&t) {
+                        self.lifecycle_log
+                            .record(t.txn.hash(), LifecycleEvent::Parked);
+                    }
                     self.parking_lot_index.insert(&t);
                     self.priority_index.remove(&t);
+                    self.priority_lanes.index_remove(&t);
                     self.timeline_index.remove(&t);
                 }
                 if let Some(txn) = txns.remove(&key.sequence_number) {
@@ -315,13 +415,35 @@ impl TransactionStore {
                     let status = if is_active { "active" } else { "parked" };
                     OP_COUNTERS.inc(&format!("{}.{}", index_name, status));
                     self.index_remove(&txn);
+                    let reason = if by_system_ttl {
+                        "system TTL expired"
+                    } else {
+                        "transaction expired"
+                    };
+                    self.lifecycle_log
+                        .record(txn.txn.hash(), LifecycleEvent::Evicted(reason.to_string()));
                 }
             }
         }
         OP_COUNTERS.set("txn.system_ttl_index", self.system_ttl_index.size());
     }
 
+    /// records state change of transaction with given hash
+    pub(crate) fn record_lifecycle_event(&mut self, txn_hash: HashValue, event: LifecycleEvent) {
+        self.lifecycle_log.record(txn_hash, event);
+    }
+
+    /// returns state changes of transaction with given hash, oldest first
+    pub(crate) fn get_lifecycle(&mut self, txn_hash: &HashValue) -> Vec<LifecycleRecord> {
+        self.lifecycle_log.get(txn_hash)
+    }
+
     pub(crate) fn iter_queue(&self) -> PriorityQueueIter {
         self.priority_index.iter()
     }
+
+    /// returns iterator over ready transactions of priority lanes, in descending lane priority
+    pub(crate) fn iter_priority_lanes(&self) -> impl Iterator<Item = &OrderedQueueKey> {
+        self.priority_lanes.iter()
+    }
 }
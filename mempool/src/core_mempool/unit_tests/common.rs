@@ -65,15 +65,39 @@ impl TestTransaction {
         self.make_signed_transaction_impl(100, std::time::Duration::from_secs(u64::max_value()))
     }
 
+    pub(crate) fn make_signed_transaction_with_program(
+        &self,
+        program: Program,
+    ) -> SignedTransaction {
+        self.make_signed_transaction_with_program_impl(
+            program,
+            100,
+            std::time::Duration::from_secs(u64::max_value()),
+        )
+    }
+
     fn make_signed_transaction_impl(
         &self,
         max_gas_amount: u64,
         exp_time: std::time::Duration,
+    ) -> SignedTransaction {
+        self.make_signed_transaction_with_program_impl(
+            Program::new(vec![], vec![], vec![]),
+            max_gas_amount,
+            exp_time,
+        )
+    }
+
+    fn make_signed_transaction_with_program_impl(
+        &self,
+        program: Program,
+        max_gas_amount: u64,
+        exp_time: std::time::Duration,
     ) -> SignedTransaction {
         let raw_txn = RawTransaction::new(
             TestTransaction::get_address(self.address),
             self.sequence_number,
-            Program::new(vec![], vec![], vec![]),
+            program,
             max_gas_amount,
             self.gas_price,
             exp_time,
@@ -131,7 +155,7 @@ impl ConsensusMock {
         mempool: &mut CoreMempool,
         block_size: u64,
     ) -> Vec<SignedTransaction> {
-        let block = mempool.get_block(block_size, self.0.clone());
+        let block = mempool.get_block(block_size, 0, self.0.clone());
         self.0 = self
             .0
             .union(&HashSet::from_iter(
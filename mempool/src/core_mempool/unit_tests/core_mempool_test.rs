@@ -4,13 +4,14 @@
 use crate::{
     core_mempool::{
         unit_tests::common::{add_txn, add_txns_to_mempool, setup_mempool, TestTransaction},
-        CoreMempool, TimelineState,
+        CoreMempool, LifecycleEvent, TimelineState,
     },
     proto::shared::mempool_status::MempoolAddTransactionStatusCode,
 };
-use config::config::NodeConfigHelpers;
+use config::config::{NodeConfig, NodeConfigHelpers, PriorityLaneConfig};
+use crypto::{hash::CryptoHash, HashValue};
 use std::{collections::HashSet, time::Duration};
-use types::transaction::SignedTransaction;
+use types::transaction::{Program, SignedTransaction};
 
 #[test]
 fn test_transaction_ordering() {
@@ -162,7 +163,7 @@ fn test_system_ttl() {
 
     // gc routine should clear transaction from first insert but keep last one
     mempool.gc_by_system_ttl();
-    let batch = mempool.get_block(1, HashSet::new());
+    let batch = mempool.get_block(1, 0, HashSet::new());
     assert_eq!(vec![transaction.make_signed_transaction()], batch);
 }
 
@@ -174,11 +175,11 @@ fn test_commit_callback() {
     let txns = add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
 
     // check that pool is empty
-    assert!(pool.get_block(1, HashSet::new()).is_empty());
+    assert!(pool.get_block(1, 0, HashSet::new()).is_empty());
     // transaction 5 got back from consensus
     pool.remove_transaction(&TestTransaction::get_address(1), 5, false);
     // verify that we can execute transaction 6
-    assert_eq!(pool.get_block(1, HashSet::new())[0], txns[0]);
+    assert_eq!(pool.get_block(1, 0, HashSet::new())[0], txns[0]);
 }
 
 #[test]
@@ -192,7 +193,7 @@ fn test_sequence_number_cache() {
     // for AC is 0)
     add_txns_to_mempool(&mut pool, vec![TestTransaction::new(1, 6, 1)]);
     // verify that we can execute transaction 6
-    assert_eq!(pool.get_block(1, HashSet::new()).len(), 1);
+    assert_eq!(pool.get_block(1, 0, HashSet::new()).len(), 1);
 }
 
 #[test]
@@ -283,7 +284,7 @@ fn test_parking_lot_eviction() {
     }
     // Make sure that we have correct txns in Mempool
     let mut txns: Vec<_> = pool
-        .get_block(5, HashSet::new())
+        .get_block(5, 0, HashSet::new())
         .iter()
         .map(SignedTransaction::sequence_number)
         .collect();
@@ -317,7 +318,7 @@ fn test_gc_ready_transaction() {
     pool.gc_by_expiration_time(Duration::from_secs(1));
 
     // make sure txns 2 and 3 became not ready and we can't read them from any API
-    let block = pool.get_block(10, HashSet::new());
+    let block = pool.get_block(10, 0, HashSet::new());
     assert_eq!(block.len(), 1);
     assert_eq!(block[0].sequence_number(), 0);
 
@@ -325,3 +326,186 @@ fn test_gc_ready_transaction() {
     assert_eq!(timeline.len(), 1);
     assert_eq!(timeline[0].sequence_number(), 0);
 }
+
+fn lifecycle_events(pool: &mut CoreMempool, txn: &SignedTransaction) -> Vec<LifecycleEvent> {
+    pool.get_lifecycle(&txn.hash())
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+#[test]
+fn test_transaction_lifecycle() {
+    let mut pool = setup_mempool().0;
+    // transaction 1 is parked until transaction 0 arrives
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![TestTransaction::new(1, 1, 1), TestTransaction::new(1, 0, 1)],
+    );
+    assert_eq!(
+        lifecycle_events(&mut pool, &txns[0]),
+        vec![LifecycleEvent::Parked, LifecycleEvent::Ready]
+    );
+    assert_eq!(
+        lifecycle_events(&mut pool, &txns[1]),
+        vec![LifecycleEvent::Ready]
+    );
+
+    assert_eq!(pool.get_block(10, 0, HashSet::new()).len(), 2);
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    pool.remove_transaction(&TestTransaction::get_address(1), 1, true);
+    assert_eq!(
+        lifecycle_events(&mut pool, &txns[0]),
+        vec![
+            LifecycleEvent::Parked,
+            LifecycleEvent::Ready,
+            LifecycleEvent::IncludedInBlock,
+            LifecycleEvent::Discarded,
+        ]
+    );
+    assert_eq!(
+        lifecycle_events(&mut pool, &txns[1]),
+        vec![
+            LifecycleEvent::Ready,
+            LifecycleEvent::IncludedInBlock,
+            LifecycleEvent::Committed,
+        ]
+    );
+}
+
+#[test]
+fn test_transaction_lifecycle_rejected_and_evicted() {
+    let mut pool = setup_mempool().0;
+    let txn = TestTransaction::new(0, 0, 1).make_signed_transaction_with_max_gas_amount(10);
+    pool.add_txn(txn.clone(), 10, 0, 0, TimelineState::NotReady);
+    match lifecycle_events(&mut pool, &txn).as_slice() {
+        [LifecycleEvent::Rejected(_)] => {}
+        events => panic!("unexpected lifecycle {:?}", events),
+    }
+
+    let txn = TestTransaction::new(0, 0, 1)
+        .make_signed_transaction_with_expiration_time(Duration::from_secs(0));
+    pool.add_txn(txn.clone(), 0, 0, 100, TimelineState::NotReady);
+    pool.gc_by_expiration_time(Duration::from_secs(1));
+    assert_eq!(
+        lifecycle_events(&mut pool, &txn),
+        vec![
+            LifecycleEvent::Ready,
+            LifecycleEvent::Evicted("transaction expired".to_string()),
+        ]
+    );
+}
+
+// config with a priority lane for transactions of account 1
+fn priority_lane_config(lane_capacity: usize) -> NodeConfig {
+    let mut config = NodeConfigHelpers::get_single_node_test_config(true);
+    config.mempool.priority_lanes = vec![PriorityLaneConfig {
+        name: "system".to_string(),
+        senders: vec![format!("{:x}", TestTransaction::get_address(1))],
+        script_hashes: vec![],
+        capacity: lane_capacity,
+        capacity_per_sender: 1,
+    }];
+    config
+}
+
+#[test]
+fn test_priority_lane_reserved_block_space() {
+    let mut pool = CoreMempool::new(&priority_lane_config(10));
+    let txns = add_txns_to_mempool(
+        &mut pool,
+        vec![
+            TestTransaction::new(0, 0, 10),
+            TestTransaction::new(0, 1, 10),
+            TestTransaction::new(0, 2, 10),
+            TestTransaction::new(1, 0, 1),
+            TestTransaction::new(1, 1, 1),
+        ],
+    );
+
+    // without reserved space, lane transactions compete by gas price
+    assert_eq!(
+        pool.get_block(3, 0, HashSet::new()),
+        vec![txns[0].clone(), txns[1].clone(), txns[2].clone()]
+    );
+    // reserved space is filled with lane transactions first
+    assert_eq!(
+        pool.get_block(3, 1, HashSet::new()),
+        vec![txns[3].clone(), txns[0].clone(), txns[1].clone()]
+    );
+    // reserved space is capped by block size
+    assert_eq!(
+        pool.get_block(2, 5, HashSet::new()),
+        vec![txns[3].clone(), txns[4].clone()]
+    );
+    // unused reserved space is given to other transactions
+    let mut seen = HashSet::new();
+    seen.insert((TestTransaction::get_address(1), 0));
+    seen.insert((TestTransaction::get_address(1), 1));
+    assert_eq!(
+        pool.get_block(2, 2, seen),
+        vec![txns[0].clone(), txns[1].clone()]
+    );
+}
+
+#[test]
+fn test_priority_lane_capacity() {
+    let mut config = priority_lane_config(1);
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+
+    // lane transactions don't take up regular capacity and vice versa
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 1)).is_err());
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_err());
+
+    // commit transaction and free space in lane
+    pool.remove_transaction(&TestTransaction::get_address(1), 0, false);
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 1, 1)).is_ok());
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 1)).is_err());
+}
+
+// config with a priority lane for transactions of account 1 and for those running `program`
+fn priority_lane_config_with_script(program: &Program, lane_capacity: usize) -> NodeConfig {
+    let mut config = priority_lane_config(lane_capacity);
+    config.mempool.priority_lanes[0].script_hashes = vec![format!(
+        "{:x}",
+        HashValue::from_iter_sha3(vec![program.code()])
+    )];
+    config
+}
+
+#[test]
+fn test_priority_lane_capacity_per_sender() {
+    let program = Program::new(vec![], vec![], vec![]);
+    let mut pool = CoreMempool::new(&priority_lane_config_with_script(&program, 10));
+
+    // senders matching by script hash only get a share of the lane
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 1)).is_err());
+
+    // designated senders aren't limited
+    add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+    add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+}
+
+#[test]
+fn test_priority_lane_update() {
+    let program = Program::new(vec![1, 2, 3], vec![], vec![]);
+    let mut config = priority_lane_config_with_script(&program, 1);
+    config.mempool.capacity = 1;
+    let mut pool = CoreMempool::new(&config);
+
+    // regular transaction is replaced by one running the lane's script
+    add_txn(&mut pool, TestTransaction::new(0, 0, 1)).unwrap();
+    let txn = TestTransaction::new(0, 0, 2).make_signed_transaction_with_program(program);
+    assert_eq!(
+        pool.add_txn(txn, 0, 0, 1000, TimelineState::NotReady).code,
+        MempoolAddTransactionStatusCode::Valid
+    );
+
+    // the replacement moved from regular capacity to the lane
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 0, 1)).is_err());
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 1)).is_ok());
+}
@@ -137,7 +137,7 @@ impl SharedMempoolNetwork {
 
                 // verify transaction was inserted into Mempool
                 let mempool = self.mempools.get(&peer).unwrap();
-                let block = mempool.lock().unwrap().get_block(100, HashSet::new());
+                let block = mempool.lock().unwrap().get_block(100, 0, HashSet::new());
                 assert!(block.iter().any(|t| t == &transaction));
                 (transaction, peer_id)
             }
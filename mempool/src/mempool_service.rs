@@ -2,10 +2,16 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState, TxnPointer},
+    core_mempool::{CoreMempool, LifecycleEvent, TimelineState, TxnPointer},
     proto::mempool_grpc::Mempool,
     OP_COUNTERS,
 };
+use crypto::{hash::CryptoHash, HashValue};
+use debug_interface::{
+    node_debug_service::TransactionLifecycleSource,
+    proto::node_debug_interface::TransactionLifecycleEvent,
+};
+use failure::prelude::*;
 use futures::Future;
 use grpc_helpers::{create_grpc_invalid_arg_status, default_reply_error_logger};
 use logger::prelude::*;
@@ -51,17 +57,22 @@ impl Mempool for MempoolService {
                 );
             }
             Ok(transaction) => {
-                let insertion_result = self
+                let mut pool = self
                     .core_mempool
                     .lock()
-                    .expect("[add txn] acquire mempool lock")
-                    .add_txn(
-                        transaction,
-                        req.max_gas_cost,
-                        req.latest_sequence_number,
-                        req.account_balance,
-                        TimelineState::NotReady,
-                    );
+                    .expect("[add txn] acquire mempool lock");
+                // transactions from clients are validated by AC before being sent to Mempool
+                let txn_hash = transaction.hash();
+                pool.record_lifecycle_event(txn_hash, LifecycleEvent::ReceivedFromClient);
+                pool.record_lifecycle_event(txn_hash, LifecycleEvent::Validated);
+                let insertion_result = pool.add_txn(
+                    transaction,
+                    req.max_gas_cost,
+                    req.latest_sequence_number,
+                    req.account_balance,
+                    TimelineState::NotReady,
+                );
+                drop(pool);
 
                 let mut response =
                     crate::proto::mempool::AddTransactionWithValidationResponse::new();
@@ -95,7 +106,11 @@ impl Mempool for MempoolService {
             .core_mempool
             .lock()
             .expect("[get_block] acquire mempool lock")
-            .get_block(block_size, exclude_transactions);
+            .get_block(
+                block_size,
+                req.get_max_priority_block_size(),
+                exclude_transactions,
+            );
 
         let transactions = txns.drain(..).map(SignedTransaction::into_proto).collect();
 
@@ -155,3 +170,29 @@ impl Mempool for MempoolService {
         ctx.spawn(sink.success(response).map_err(default_reply_error_logger));
     }
 }
+
+/// Gives access to the lifecycle history of transactions to the node debug interface
+pub(crate) struct MempoolLifecycleSource {
+    pub(crate) core_mempool: Arc<Mutex<CoreMempool>>,
+}
+
+impl TransactionLifecycleSource for MempoolLifecycleSource {
+    fn get_transaction_lifecycle(&self, txn_hash: &[u8]) -> Result<Vec<TransactionLifecycleEvent>> {
+        let txn_hash = HashValue::from_slice(txn_hash)?;
+        let records = self
+            .core_mempool
+            .lock()
+            .expect("[get_transaction_lifecycle] acquire mempool lock")
+            .get_lifecycle(&txn_hash);
+        Ok(records
+            .into_iter()
+            .map(|record| {
+                let mut event = TransactionLifecycleEvent::new();
+                event.set_timestamp_usecs(record.timestamp_usecs);
+                event.set_state(record.event.state().to_string());
+                event.set_details(record.event.details());
+                event
+            })
+            .collect())
+    }
+}
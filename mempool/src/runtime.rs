@@ -2,10 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::CoreMempool, mempool_service::MempoolService, proto::mempool_grpc,
+    core_mempool::CoreMempool,
+    mempool_service::{MempoolLifecycleSource, MempoolService},
+    proto::mempool_grpc,
     shared_mempool::start_shared_mempool,
 };
 use config::config::NodeConfig;
+use debug_interface::node_debug_service::TransactionLifecycleSource;
 use grpc_helpers::ServerHandle;
 use grpcio::EnvBuilder;
 use grpcio_sys;
@@ -24,6 +27,8 @@ pub struct MempoolRuntime {
     pub grpc_server: ServerHandle,
     /// separate shared mempool runtime
     pub shared_mempool: Runtime,
+    /// lifecycle history of transactions, to be served by the node debug interface
+    pub transaction_lifecycle: Arc<dyn TransactionLifecycleSource>,
 }
 
 impl MempoolRuntime {
@@ -45,6 +50,9 @@ impl MempoolRuntime {
         let handle = MempoolService {
             core_mempool: Arc::clone(&mempool),
         };
+        let transaction_lifecycle = Arc::new(MempoolLifecycleSource {
+            core_mempool: Arc::clone(&mempool),
+        });
         let service = mempool_grpc::create_mempool(handle);
         let grpc_server = ::grpcio::ServerBuilder::new(env)
             .register_service(service)
@@ -75,6 +83,7 @@ impl MempoolRuntime {
         Self {
             grpc_server: ServerHandle::setup(grpc_server),
             shared_mempool,
+            transaction_lifecycle,
         }
     }
 }
@@ -2,11 +2,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    core_mempool::{CoreMempool, TimelineState},
+    core_mempool::{CoreMempool, LifecycleEvent, TimelineState},
     proto::shared::mempool_status::MempoolAddTransactionStatusCode,
     OP_COUNTERS,
 };
 use config::config::{MempoolConfig, NodeConfig};
+use crypto::hash::CryptoHash;
 use failure::prelude::*;
 use futures::sync::mpsc::UnboundedSender;
 use futures_preview::{
@@ -157,10 +158,20 @@ async fn sync_with_peers<'a>(
         if peer_state.is_alive {
             let timeline_id = peer_state.timeline_id;
 
-            let (transactions, new_timeline_id) = mempool
-                .lock()
-                .expect("[shared mempool] failed to acquire mempool lock")
-                .read_timeline(timeline_id, batch_size);
+            let (transactions, new_timeline_id) = {
+                let mut mempool = mempool
+                    .lock()
+                    .expect("[shared mempool] failed to acquire mempool lock");
+                let (transactions, new_timeline_id) =
+                    mempool.read_timeline(timeline_id, batch_size);
+                for transaction in &transactions {
+                    mempool.record_lifecycle_event(
+                        transaction.hash(),
+                        LifecycleEvent::Broadcast(peer_id),
+                    );
+                }
+                (transactions, new_timeline_id)
+            };
 
             if !transactions.is_empty() {
                 OP_COUNTERS.inc_by("smp.sync_with_peers", transactions.len());
@@ -228,6 +239,19 @@ async fn process_incoming_transactions<V>(
         .expect("[shared mempool] failed to acquire mempool lock");
 
     for (idx, transaction) in transactions.into_iter().enumerate() {
+        let txn_hash = transaction.hash();
+        mempool.record_lifecycle_event(txn_hash, LifecycleEvent::ReceivedFromPeer(peer_id));
+        match &validations[idx] {
+            Ok(None) => mempool.record_lifecycle_event(txn_hash, LifecycleEvent::Validated),
+            Ok(Some(vm_status)) => mempool.record_lifecycle_event(
+                txn_hash,
+                LifecycleEvent::Rejected(format!("{:?}", vm_status)),
+            ),
+            Err(e) => mempool.record_lifecycle_event(
+                txn_hash,
+                LifecycleEvent::Rejected(format!("validation failed: {}", e)),
+            ),
+        }
         if let Ok(None) = validations[idx] {
             if let Ok((sequence_number, balance)) = account_states[idx] {
                 let gas_cost = transaction.max_gas_amount();
@@ -8,13 +8,15 @@ mod consensus;
 mod mempool;
 mod network;
 
-use types::proto::{ledger_info, transaction};
+use types::proto::{account_state_blob, ledger_info, transaction};
 
 pub use self::{
     consensus::{
         Block, BlockRetrievalStatus, ConsensusMsg, PacemakerTimeout, PacemakerTimeoutCertificate,
-        Proposal, QuorumCert, RequestBlock, RequestChunk, RespondBlock, RespondChunk, TimeoutMsg,
-        Vote,
+        Proposal, QuorumCert, RequestAccountStateChunk, RequestBlock, RequestChunk,
+        RequestEpochChange, RequestHighestLedgerInfo, RespondAccountStateChunk, RespondBlock,
+        RespondChunk, RespondEpochChange, RespondHighestLedgerInfo, TimeoutCertificateMsg,
+        TimeoutMsg, Vote,
     },
     mempool::MempoolSyncMsg,
     network::{DiscoveryMsg, IdentityMsg, Note, PeerInfo, Ping, Pong},
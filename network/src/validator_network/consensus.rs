@@ -6,7 +6,11 @@
 use crate::{
     error::NetworkError,
     interface::{NetworkNotification, NetworkRequest},
-    proto::{ConsensusMsg, RequestBlock, RequestChunk, RespondBlock, RespondChunk},
+    proto::{
+        ConsensusMsg, RequestAccountStateChunk, RequestBlock, RequestChunk, RequestEpochChange,
+        RequestHighestLedgerInfo, RespondAccountStateChunk, RespondBlock, RespondChunk,
+        RespondEpochChange, RespondHighestLedgerInfo,
+    },
     protocols::{
         direct_send::Message,
         rpc::{error::RpcError, OutboundRpcRequest},
@@ -178,6 +182,87 @@ impl ConsensusNetworkSender {
         }
     }
 
+    /// Send a RequestAccountStateChunk RPC request to remote peer `recipient`. Returns the
+    /// future `RespondAccountStateChunk` returned by the remote peer.
+    ///
+    /// The rpc request can be canceled at any point by dropping the returned
+    /// future.
+    pub async fn request_account_state_chunk(
+        &mut self,
+        recipient: PeerId,
+        req_msg: RequestAccountStateChunk,
+        timeout: Duration,
+    ) -> Result<RespondAccountStateChunk, RpcError> {
+        let protocol = ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL);
+        let mut req_msg_enum = ConsensusMsg::new();
+        req_msg_enum.set_request_account_state_chunk(req_msg);
+
+        let mut res_msg_enum = self
+            .unary_rpc(recipient, protocol, req_msg_enum, timeout)
+            .await?;
+
+        if res_msg_enum.has_respond_account_state_chunk() {
+            Ok(res_msg_enum.take_respond_account_state_chunk())
+        } else {
+            // TODO: context
+            Err(RpcError::InvalidRpcResponse)
+        }
+    }
+
+    /// Send a RequestHighestLedgerInfo RPC request to remote peer `recipient`. Returns the
+    /// future `RespondHighestLedgerInfo` returned by the remote peer.
+    ///
+    /// The rpc request can be canceled at any point by dropping the returned
+    /// future.
+    pub async fn request_highest_ledger_info(
+        &mut self,
+        recipient: PeerId,
+        req_msg: RequestHighestLedgerInfo,
+        timeout: Duration,
+    ) -> Result<RespondHighestLedgerInfo, RpcError> {
+        let protocol = ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL);
+        let mut req_msg_enum = ConsensusMsg::new();
+        req_msg_enum.set_request_highest_ledger_info(req_msg);
+
+        let mut res_msg_enum = self
+            .unary_rpc(recipient, protocol, req_msg_enum, timeout)
+            .await?;
+
+        if res_msg_enum.has_respond_highest_ledger_info() {
+            Ok(res_msg_enum.take_respond_highest_ledger_info())
+        } else {
+            // TODO: context
+            Err(RpcError::InvalidRpcResponse)
+        }
+    }
+
+    /// Send a RequestEpochChange RPC request to remote peer `recipient`. Returns the
+    /// future `RespondEpochChange` returned by the remote peer.
+    ///
+    /// The rpc request can be canceled at any point by dropping the returned
+    /// future.
+    pub async fn request_epoch_change(
+        &mut self,
+        recipient: PeerId,
+        req_msg: RequestEpochChange,
+        timeout: Duration,
+    ) -> Result<RespondEpochChange, RpcError> {
+        let protocol = ProtocolId::from_static(CONSENSUS_RPC_PROTOCOL);
+        let mut req_msg_enum = ConsensusMsg::new();
+        req_msg_enum.set_request_epoch_change(req_msg);
+
+        let mut res_msg_enum = self
+            .unary_rpc(recipient, protocol, req_msg_enum, timeout)
+            .await?;
+
+        if res_msg_enum.has_respond_epoch_change() {
+            Ok(res_msg_enum.take_respond_epoch_change())
+        } else {
+            // TODO: context
+            Err(RpcError::InvalidRpcResponse)
+        }
+    }
+
     pub async fn update_eligible_nodes(
         &mut self,
         validators: Vec<ValidatorPublicKeys>,
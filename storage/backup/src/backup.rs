@@ -0,0 +1,80 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    format::{BackupWriter, Record},
+    BackupSummary,
+};
+use failure::prelude::*;
+use libradb::LibraDB;
+use std::io::Write;
+
+/// Number of ledger infos, account states or transactions in one record, the max number LibraDB
+/// serves in one request.
+const CHUNK_SIZE: u64 = 1000;
+
+/// Writes a backup of `db` at its latest ledger info to `writer`.
+pub fn backup<W: Write>(db: &LibraDB, writer: W) -> Result<BackupSummary> {
+    let mut writer = BackupWriter::new(writer)?;
+    let mut summary = BackupSummary::default();
+
+    // Ledger infos, the last one is the one everything else is verified against.
+    let mut start_version = 0;
+    let mut latest_ledger_info_with_sigs = None;
+    loop {
+        let ledger_infos_with_sigs = db.get_ledger_infos(start_version, CHUNK_SIZE)?;
+        let num_ledger_infos = ledger_infos_with_sigs.len();
+        for ledger_info_with_sigs in ledger_infos_with_sigs {
+            start_version = ledger_info_with_sigs.ledger_info().version() + 1;
+            writer.write_record(Record::LedgerInfo(ledger_info_with_sigs.clone()))?;
+            latest_ledger_info_with_sigs = Some(ledger_info_with_sigs);
+        }
+        summary.num_ledger_infos += num_ledger_infos;
+        if (num_ledger_infos as u64) < CHUNK_SIZE {
+            break;
+        }
+    }
+    let ledger_info = latest_ledger_info_with_sigs
+        .ok_or_else(|| format_err!("LibraDB is not bootstrapped."))?
+        .ledger_info()
+        .clone();
+    let version = ledger_info.version();
+    summary.version = version;
+
+    // The state snapshot.
+    let mut start_key = None;
+    loop {
+        let chunk = db.get_account_state_chunk_with_proof(version, start_key, CHUNK_SIZE)?;
+        chunk.verify(&ledger_info, start_key)?;
+        let num_account_states = chunk.account_states.len();
+        if num_account_states == 0 {
+            break;
+        }
+        start_key = chunk.account_states.last().map(|(key, _)| *key);
+        writer.write_record(Record::AccountStateChunk(chunk))?;
+        summary.num_account_states += num_account_states;
+        if (num_account_states as u64) < CHUNK_SIZE {
+            break;
+        }
+    }
+
+    // Transactions.
+    let mut first_version = 0;
+    while first_version <= version {
+        let txn_list_with_proof = db.get_transactions(
+            first_version,
+            CHUNK_SIZE,
+            version,
+            true, /* fetch_events */
+        )?;
+        txn_list_with_proof.verify(&ledger_info, Some(first_version))?;
+        let num_txns = txn_list_with_proof.transaction_and_infos.len();
+        ensure!(num_txns > 0, "No transaction at version {}.", first_version);
+        writer.write_record(Record::TransactionChunk(txn_list_with_proof))?;
+        first_version += num_txns as u64;
+        summary.num_transactions += num_txns;
+    }
+
+    writer.finish()?;
+    Ok(summary)
+}
@@ -0,0 +1,159 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup,
+    format::{BackupReader, BackupWriter, Record},
+    restore,
+};
+use failure::prelude::*;
+use libradb::{mock_genesis::db_with_mock_genesis, test_helper::arb_blocks_to_commit, LibraDB};
+use proptest::prelude::*;
+use tempfile::{tempdir, TempDir};
+use types::{
+    ledger_info::LedgerInfoWithSignatures,
+    transaction::{TransactionInfo, TransactionToCommit},
+};
+
+fn db_with_blocks(
+    dir: &TempDir,
+    blocks_to_commit: &[(Vec<TransactionToCommit>, LedgerInfoWithSignatures)],
+) -> LibraDB {
+    let db = db_with_mock_genesis(dir).unwrap();
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in blocks_to_commit {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    db
+}
+
+fn backup_bytes(db: &LibraDB) -> Vec<u8> {
+    let mut bytes = vec![];
+    backup(db, &mut bytes).unwrap();
+    bytes
+}
+
+fn restore_to_new_db(bytes: &[u8]) -> Result<()> {
+    let tmp_dir = tempdir().unwrap();
+    let db = LibraDB::new(&tmp_dir);
+    restore(bytes, &db, None /* validator_verifier */)?;
+    Ok(())
+}
+
+fn test_backup_restore_impl(
+    blocks_to_commit: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    let src_dir = tempdir().unwrap();
+    let src_db = db_with_blocks(&src_dir, &blocks_to_commit);
+    let mut bytes = vec![];
+    let summary = backup(&src_db, &mut bytes).unwrap();
+
+    let dst_dir = tempdir().unwrap();
+    let dst_db = LibraDB::new(&dst_dir);
+    assert_eq!(restore(&bytes[..], &dst_db, None).unwrap(), summary);
+
+    let version = summary.version;
+    assert_eq!(
+        summary.num_transactions,
+        1 + blocks_to_commit
+            .iter()
+            .map(|(txns_to_commit, _)| txns_to_commit.len())
+            .sum::<usize>()
+    );
+    assert_eq!(
+        dst_db.get_executor_startup_info().unwrap(),
+        src_db.get_executor_startup_info().unwrap()
+    );
+    assert_eq!(
+        dst_db.get_ledger_infos(0, 1000).unwrap(),
+        src_db.get_ledger_infos(0, 1000).unwrap()
+    );
+    assert_eq!(
+        dst_db
+            .get_account_state_chunk_with_proof(version, None, 1000)
+            .unwrap(),
+        src_db
+            .get_account_state_chunk_with_proof(version, None, 1000)
+            .unwrap()
+    );
+    assert_eq!(
+        dst_db
+            .get_transactions(0, version + 1, version, true /* fetch_events */)
+            .unwrap(),
+        src_db
+            .get_transactions(0, version + 1, version, true /* fetch_events */)
+            .unwrap()
+    );
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_backup_restore(blocks_to_commit in arb_blocks_to_commit()) {
+        test_backup_restore_impl(blocks_to_commit);
+    }
+}
+
+#[test]
+fn test_restore_corrupted_backup() {
+    let tmp_dir = tempdir().unwrap();
+    let db = db_with_mock_genesis(&tmp_dir).unwrap();
+    let bytes = backup_bytes(&db);
+    restore_to_new_db(&bytes).unwrap();
+
+    // Flipped bits anywhere are detected by the header check or the checksums.
+    for pos in (0..bytes.len()).step_by(13) {
+        let mut corrupted = bytes.clone();
+        corrupted[pos] ^= 0x01;
+        assert!(restore_to_new_db(&corrupted).is_err());
+    }
+    // So is a truncated file.
+    assert!(restore_to_new_db(&bytes[..bytes.len() - 1]).is_err());
+}
+
+#[test]
+fn test_restore_tampered_backup() {
+    let tmp_dir = tempdir().unwrap();
+    let db = db_with_mock_genesis(&tmp_dir).unwrap();
+    let bytes = backup_bytes(&db);
+
+    // Rewrite the backup with valid checksums but a tampered transaction info, which fails
+    // verification against the ledger info.
+    let mut reader = BackupReader::new(&bytes[..]).unwrap();
+    let mut writer = BackupWriter::new(vec![]).unwrap();
+    loop {
+        match reader.read_record().unwrap() {
+            Record::End { .. } => break,
+            Record::TransactionChunk(mut txn_list_with_proof) => {
+                let (_, txn_info) = &mut txn_list_with_proof.transaction_and_infos[0];
+                *txn_info = TransactionInfo::new(
+                    txn_info.signed_transaction_hash(),
+                    txn_info.state_root_hash(),
+                    txn_info.event_root_hash(),
+                    txn_info.gas_used() + 1,
+                );
+                writer
+                    .write_record(Record::TransactionChunk(txn_list_with_proof))
+                    .unwrap();
+            }
+            record => writer.write_record(record).unwrap(),
+        }
+    }
+    let tampered = writer.finish().unwrap();
+    assert!(restore_to_new_db(&tampered).is_err());
+}
+
+#[test]
+fn test_restore_into_non_empty_db() {
+    let tmp_dir = tempdir().unwrap();
+    let db = db_with_mock_genesis(&tmp_dir).unwrap();
+    let bytes = backup_bytes(&db);
+    assert!(restore(&bytes[..], &db, None /* validator_verifier */).is_err());
+}
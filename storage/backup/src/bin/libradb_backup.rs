@@ -0,0 +1,103 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backs up the LibraDB of a node, which may keep running, to a file, and restores a fresh
+//! LibraDB from such a file.
+
+use backup::BackupSummary;
+use config::trusted_peers::TrustedPeersConfig;
+use failure::prelude::*;
+use libradb::LibraDB;
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+};
+use structopt::StructOpt;
+use types::validator_verifier::ValidatorVerifier;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "libradb_backup",
+    author = "Libra",
+    about = "Tool to back up and restore the LibraDB of a node"
+)]
+enum Command {
+    /// Backs up the LibraDB at its latest ledger info. The node doesn't need to be stopped.
+    #[structopt(name = "backup")]
+    Backup {
+        /// Storage directory of the node (`storage.dir` in its node config), which contains the
+        /// `libradb` directory.
+        #[structopt(short = "d", long = "db_dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        /// The backup file to create, it must not exist.
+        #[structopt(short = "o", long = "output", parse(from_os_str))]
+        output: PathBuf,
+    },
+    /// Restores a fresh LibraDB from a backup.
+    #[structopt(name = "restore")]
+    Restore {
+        /// The backup file.
+        #[structopt(short = "i", long = "input", parse(from_os_str))]
+        input: PathBuf,
+        /// Storage directory to restore to, where a `libradb` directory is created.
+        #[structopt(short = "d", long = "db_dir", parse(from_os_str))]
+        db_dir: PathBuf,
+        /// Trusted peers config of the validator set the backup is taken with. If given, the
+        /// signatures of the ledger info the backup is taken at are verified.
+        #[structopt(short = "t", long = "trusted_peers", parse(from_os_str))]
+        trusted_peers: Option<PathBuf>,
+    },
+}
+
+fn print_summary(action: &str, summary: &BackupSummary) {
+    println!("{} LibraDB at version {}:", action, summary.version);
+    println!("  ledger infos: {}", summary.num_ledger_infos);
+    println!("  account states: {}", summary.num_account_states);
+    println!("  transactions: {}", summary.num_transactions);
+}
+
+fn main() -> Result<()> {
+    match Command::from_args() {
+        Command::Backup { db_dir, output } => {
+            let db = LibraDB::open_readonly(&db_dir)?;
+            let file = OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&output)?;
+            let summary = backup::backup(&db, BufWriter::new(file))?;
+            print_summary("Backed up", &summary);
+        }
+        Command::Restore {
+            input,
+            db_dir,
+            trusted_peers,
+        } => {
+            ensure!(
+                !db_dir.join("libradb").exists(),
+                "LibraDB already exists under {:?}.",
+                db_dir
+            );
+            let validator_verifier = match trusted_peers {
+                Some(path) => {
+                    let validators =
+                        TrustedPeersConfig::load_config(path).get_trusted_consensus_peers();
+                    ensure!(
+                        !validators.is_empty(),
+                        "Not able to load validators from trusted peers config!"
+                    );
+                    Some(ValidatorVerifier::new(validators))
+                }
+                None => None,
+            };
+            let db = LibraDB::new(&db_dir);
+            let summary = backup::restore(
+                BufReader::new(File::open(&input)?),
+                &db,
+                validator_verifier.as_ref(),
+            )?;
+            print_summary("Restored", &summary);
+        }
+    }
+    Ok(())
+}
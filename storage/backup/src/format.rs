@@ -0,0 +1,217 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the portable file format of a LibraDB backup.
+//!
+//! A backup file starts with the 8-byte [`MAGIC`] and the format version as a big-endian `u32`,
+//! followed by a sequence of records. Each record is laid out as:
+//!
+//! ```text
+//! | kind (u8) | payload length (u64, big-endian) | payload | SHA3-256 checksum (32 bytes) |
+//! ```
+//!
+//! where the checksum covers the kind, the length and the payload. Payloads are the Protobuf
+//! encodings of the [`types`] structures, except for [`Record::End`] which carries the number of
+//! records before it, so a truncated file is always detected.
+
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use crypto::HashValue;
+use failure::prelude::*;
+use proto_conv::{FromProtoBytes, IntoProtoBytes};
+use std::io::{Read, Write};
+use types::{
+    account_state_blob::AccountStateChunkWithProof, ledger_info::LedgerInfoWithSignatures,
+    transaction::TransactionListWithProof,
+};
+
+/// The leading bytes of every backup file.
+pub const MAGIC: &[u8; 8] = b"LIBRABAK";
+
+/// The version of the format written by [`BackupWriter`], bumped on incompatible changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Records larger than this are treated as corruption instead of being allocated.
+const MAX_RECORD_SIZE: u64 = 1 << 30;
+
+const LEDGER_INFO: u8 = 1;
+const ACCOUNT_STATE_CHUNK: u8 = 2;
+const TRANSACTION_CHUNK: u8 = 3;
+const END: u8 = 4;
+
+/// A record in a backup file. A backup consists of the ledger infos in ascending order of
+/// version, the last one being the ledger info the backup is taken at, then the chunks of the
+/// state snapshot at that version, then the chunks of all the transactions up to that version,
+/// and finally an `End`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Record {
+    LedgerInfo(LedgerInfoWithSignatures),
+    AccountStateChunk(AccountStateChunkWithProof),
+    TransactionChunk(TransactionListWithProof),
+    End { num_records: u64 },
+}
+
+impl Record {
+    fn into_kind_and_payload(self) -> Result<(u8, Vec<u8>)> {
+        Ok(match self {
+            Record::LedgerInfo(ledger_info_with_sigs) => {
+                (LEDGER_INFO, ledger_info_with_sigs.into_proto_bytes()?)
+            }
+            Record::AccountStateChunk(chunk) => (ACCOUNT_STATE_CHUNK, chunk.into_proto_bytes()?),
+            Record::TransactionChunk(txn_list) => (TRANSACTION_CHUNK, txn_list.into_proto_bytes()?),
+            Record::End { num_records } => {
+                let mut payload = vec![];
+                payload.write_u64::<BigEndian>(num_records)?;
+                (END, payload)
+            }
+        })
+    }
+
+    fn from_kind_and_payload(kind: u8, payload: &[u8]) -> Result<Self> {
+        Ok(match kind {
+            LEDGER_INFO => Record::LedgerInfo(LedgerInfoWithSignatures::from_proto_bytes(payload)?),
+            ACCOUNT_STATE_CHUNK => {
+                Record::AccountStateChunk(AccountStateChunkWithProof::from_proto_bytes(payload)?)
+            }
+            TRANSACTION_CHUNK => {
+                Record::TransactionChunk(TransactionListWithProof::from_proto_bytes(payload)?)
+            }
+            END => {
+                ensure!(
+                    payload.len() == 8,
+                    "Bad End record of {} bytes.",
+                    payload.len()
+                );
+                Record::End {
+                    num_records: (&payload[..]).read_u64::<BigEndian>()?,
+                }
+            }
+            _ => bail!("Unknown record kind {}.", kind),
+        })
+    }
+}
+
+fn checksum(kind: u8, len: u64, payload: &[u8]) -> HashValue {
+    let mut len_bytes = [0u8; 8];
+    (&mut len_bytes[..])
+        .write_u64::<BigEndian>(len)
+        .expect("Writing to an array of the same size should succeed.");
+    HashValue::from_iter_sha3(vec![&[kind][..], &len_bytes[..], payload])
+}
+
+/// Writes a backup file record by record.
+pub struct BackupWriter<W> {
+    writer: W,
+    num_records: u64,
+}
+
+impl<W: Write> BackupWriter<W> {
+    /// Writes the file header to `writer`.
+    pub fn new(mut writer: W) -> Result<Self> {
+        writer.write_all(MAGIC)?;
+        writer.write_u32::<BigEndian>(FORMAT_VERSION)?;
+        Ok(Self {
+            writer,
+            num_records: 0,
+        })
+    }
+
+    /// Appends `record`, which must not be an `End`.
+    pub fn write_record(&mut self, record: Record) -> Result<()> {
+        ensure!(
+            match record {
+                Record::End { .. } => false,
+                _ => true,
+            },
+            "The End record is written by finish().",
+        );
+        self.write_record_impl(record)?;
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// Appends the `End` record and returns the underlying writer after flushing it.
+    pub fn finish(mut self) -> Result<W> {
+        self.write_record_impl(Record::End {
+            num_records: self.num_records,
+        })?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn write_record_impl(&mut self, record: Record) -> Result<()> {
+        let (kind, payload) = record.into_kind_and_payload()?;
+        let len = payload.len() as u64;
+        self.writer.write_u8(kind)?;
+        self.writer.write_u64::<BigEndian>(len)?;
+        self.writer.write_all(&payload)?;
+        self.writer
+            .write_all(checksum(kind, len, &payload).as_ref())?;
+        Ok(())
+    }
+}
+
+/// Reads a backup file record by record, checking the checksum of each.
+pub struct BackupReader<R> {
+    reader: R,
+    num_records: u64,
+    finished: bool,
+}
+
+impl<R: Read> BackupReader<R> {
+    /// Reads and checks the file header from `reader`.
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic)?;
+        ensure!(&magic == MAGIC, "Not a LibraDB backup file.");
+        let format_version = reader.read_u32::<BigEndian>()?;
+        ensure!(
+            format_version == FORMAT_VERSION,
+            "Unsupported backup format version {}, expected {}.",
+            format_version,
+            FORMAT_VERSION,
+        );
+        Ok(Self {
+            reader,
+            num_records: 0,
+            finished: false,
+        })
+    }
+
+    /// Reads the next record. When it is the `End`, checks that no record is missing before it.
+    pub fn read_record(&mut self) -> Result<Record> {
+        ensure!(!self.finished, "Reading past the End record.");
+
+        let kind = self.reader.read_u8()?;
+        let len = self.reader.read_u64::<BigEndian>()?;
+        ensure!(
+            len <= MAX_RECORD_SIZE,
+            "Record #{} claims {} bytes, larger than the max {}.",
+            self.num_records,
+            len,
+            MAX_RECORD_SIZE,
+        );
+        let mut payload = vec![0u8; len as usize];
+        self.reader.read_exact(&mut payload)?;
+        let mut expected_checksum = [0u8; HashValue::LENGTH];
+        self.reader.read_exact(&mut expected_checksum)?;
+        ensure!(
+            checksum(kind, len, &payload) == HashValue::new(expected_checksum),
+            "Checksum mismatch in record #{}.",
+            self.num_records,
+        );
+
+        let record = Record::from_kind_and_payload(kind, &payload)?;
+        if let Record::End { num_records } = record {
+            ensure!(
+                num_records == self.num_records,
+                "The backup claims {} records, but {} were read.",
+                num_records,
+                self.num_records,
+            );
+            self.finished = true;
+        } else {
+            self.num_records += 1;
+        }
+        Ok(record)
+    }
+}
@@ -0,0 +1,32 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This crate backs up a [`LibraDB`](libradb::LibraDB) to a portable file and restores a fresh
+//! one from it.
+//!
+//! [`backup`] exports the ledger infos, the state snapshot and all the transactions with their
+//! infos and events up to the latest ledger info, each verified against it, to the format defined
+//! in [`format`]. It reads a db opened by [`LibraDB::open_readonly`](libradb::LibraDB), so it
+//! can run alongside the node holding the db and sees a consistent view of it. [`restore`]
+//! verifies every record against the same ledger info again before writing it.
+
+pub mod format;
+
+mod backup;
+mod restore;
+
+#[cfg(test)]
+mod backup_test;
+
+pub use crate::{backup::backup, restore::restore};
+use types::transaction::Version;
+
+/// What a backup contains, returned by both [`backup`] and [`restore`].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct BackupSummary {
+    /// The version of the ledger info the backup is taken at.
+    pub version: Version,
+    pub num_ledger_infos: usize,
+    pub num_account_states: usize,
+    pub num_transactions: usize,
+}
@@ -0,0 +1,111 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    format::{BackupReader, Record},
+    BackupSummary,
+};
+use crypto::hash::SPARSE_MERKLE_PLACEHOLDER_HASH;
+use failure::prelude::*;
+use libradb::LibraDB;
+use std::io::Read;
+use types::validator_verifier::ValidatorVerifier;
+
+/// Restores the backup read from `reader` into `db`, which must be empty. Every chunk is verified
+/// against the ledger info the backup is taken at, whose signatures are checked as well when
+/// `validator_verifier` is given.
+///
+/// The ledger infos are written last, so a failed restore leaves a db that a node refuses to
+/// start from, which should be discarded.
+pub fn restore<R: Read>(
+    reader: R,
+    db: &LibraDB,
+    validator_verifier: Option<&ValidatorVerifier>,
+) -> Result<BackupSummary> {
+    ensure!(
+        db.get_executor_startup_info()?.is_none(),
+        "Can only restore into an empty LibraDB."
+    );
+    let mut reader = BackupReader::new(reader)?;
+    let mut summary = BackupSummary::default();
+
+    // Ledger infos.
+    let mut ledger_infos_with_sigs = vec![];
+    let mut record = reader.read_record()?;
+    while let Record::LedgerInfo(ledger_info_with_sigs) = record {
+        if let Some(prev) = ledger_infos_with_sigs.last() {
+            ensure!(
+                ledger_info_with_sigs.ledger_info().version() > prev.ledger_info().version(),
+                "Ledger infos out of order at version {}.",
+                ledger_info_with_sigs.ledger_info().version(),
+            );
+        }
+        ledger_infos_with_sigs.push(ledger_info_with_sigs);
+        record = reader.read_record()?;
+    }
+    let ledger_info_with_sigs = ledger_infos_with_sigs
+        .last()
+        .ok_or_else(|| format_err!("No ledger info in the backup."))?;
+    if let Some(validator_verifier) = validator_verifier {
+        ledger_info_with_sigs
+            .verify(validator_verifier)
+            .map_err(|e| format_err!("Failed to verify the ledger info: {:?}", e))?;
+    }
+    let ledger_info = ledger_info_with_sigs.ledger_info();
+    let version = ledger_info.version();
+    summary.version = version;
+    summary.num_ledger_infos = ledger_infos_with_sigs.len();
+
+    // The state snapshot.
+    let mut start_key = None;
+    let mut state_root_hash = *SPARSE_MERKLE_PLACEHOLDER_HASH;
+    let mut expected_state_root_hash = None;
+    while let Record::AccountStateChunk(chunk) = record {
+        chunk.verify(ledger_info, start_key)?;
+        start_key = chunk.account_states.last().map(|(key, _)| *key);
+        summary.num_account_states += chunk.account_states.len();
+        expected_state_root_hash = Some(chunk.transaction_info.state_root_hash());
+        state_root_hash =
+            db.restore_account_state_chunk(version, chunk.account_states, state_root_hash)?;
+        record = reader.read_record()?;
+    }
+    if let Some(expected_state_root_hash) = expected_state_root_hash {
+        ensure!(
+            state_root_hash == expected_state_root_hash,
+            "Restored state root {:x} doesn't match the expected {:x}.",
+            state_root_hash,
+            expected_state_root_hash,
+        );
+    }
+
+    // Transactions.
+    let mut first_version = 0;
+    while let Record::TransactionChunk(txn_list_with_proof) = record {
+        txn_list_with_proof.verify(ledger_info, Some(first_version))?;
+        let events = txn_list_with_proof
+            .events
+            .ok_or_else(|| format_err!("No events at version {}.", first_version))?;
+        db.restore_transaction_chunk(
+            first_version,
+            &txn_list_with_proof.transaction_and_infos,
+            &events,
+        )?;
+        let num_txns = txn_list_with_proof.transaction_and_infos.len();
+        first_version += num_txns as u64;
+        summary.num_transactions += num_txns;
+        record = reader.read_record()?;
+    }
+    match record {
+        Record::End { .. } => (),
+        _ => bail!("Unexpected record after the transactions."),
+    }
+    ensure!(
+        first_version == version + 1,
+        "The backup has {} transactions, expected {}.",
+        first_version,
+        version + 1,
+    );
+
+    db.finish_restore(&ledger_infos_with_sigs)?;
+    Ok(summary)
+}
@@ -0,0 +1,56 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Checks the LibraDB of a node for internal consistency and prints a JSON report. Exits with 1
+//! if any inconsistency is found.
+
+use config::trusted_peers::TrustedPeersConfig;
+use failure::prelude::*;
+use libradb::{db_verifier::DbVerifier, LibraDB};
+use std::path::PathBuf;
+use structopt::StructOpt;
+use types::validator_verifier::ValidatorVerifier;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "db_verify",
+    author = "Libra",
+    about = "Tool to check the LibraDB of a node for internal consistency"
+)]
+struct Args {
+    /// Storage directory of the node (`storage.dir` in its node config), which contains the
+    /// `libradb` directory.
+    #[structopt(short = "d", long = "db_dir", parse(from_os_str))]
+    db_dir: PathBuf,
+    /// `storage.prune_window` in the node config, if set. Missing account states older than the
+    /// window are not reported.
+    #[structopt(short = "p", long = "prune_window")]
+    prune_window: Option<u64>,
+    /// Trusted peers config of the current validator set. If given, the signatures of the ledger
+    /// infos of the latest epoch are verified.
+    #[structopt(short = "t", long = "trusted_peers", parse(from_os_str))]
+    trusted_peers: Option<PathBuf>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::from_args();
+    let validator_verifier = match &args.trusted_peers {
+        Some(path) => {
+            let validators = TrustedPeersConfig::load_config(path).get_trusted_consensus_peers();
+            ensure!(
+                !validators.is_empty(),
+                "Not able to load validators from trusted peers config!"
+            );
+            Some(ValidatorVerifier::new(validators))
+        }
+        None => None,
+    };
+
+    let db = LibraDB::open_readonly(&args.db_dir)?;
+    let report = DbVerifier::new(&db, args.prune_window, validator_verifier.as_ref()).verify()?;
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.is_consistent() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
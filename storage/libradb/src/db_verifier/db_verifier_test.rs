@@ -0,0 +1,342 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    mock_genesis::{db_with_mock_genesis, save_mock_genesis, GENESIS_INFO},
+    schema::{event::EventSchema, transaction_accumulator::TransactionAccumulatorSchema},
+    test_helper::{arb_blocks_to_commit, for_each_backend},
+    MAX_LIMIT,
+};
+use crypto::hash::ACCUMULATOR_PLACEHOLDER_HASH;
+use proptest::prelude::*;
+use schemadb::SchemaBatch;
+use std::collections::{HashMap, HashSet};
+use tempfile::tempdir;
+use types::{
+    access_path::AccessPath, account_address::AccountAddress, contract_event::ContractEvent,
+    ledger_info::LedgerInfo, proof::position::Position, transaction::TransactionToCommit,
+    validator_signer::ValidatorSigner,
+};
+
+/// Saves the mock genesis at version 0 and two more transactions without ledger info into the
+/// empty `db`.
+fn save_three_txns(db: &LibraDB) {
+    save_mock_genesis(db).unwrap();
+    let genesis_txn = GENESIS_INFO.2.clone();
+    db.save_transactions(
+        &[genesis_txn.clone(), genesis_txn],
+        1,     /* first_version */
+        &None, /* ledger_info_with_sigs */
+    )
+    .unwrap();
+}
+
+/// Verifies `db` without a prune window or a validator set.
+fn verify(db: &LibraDB) -> DbVerifyReport {
+    DbVerifier::new(db, None, None).verify().unwrap()
+}
+
+fn delete<S: schemadb::schema::Schema>(db: &LibraDB, key: &S::Key) {
+    let mut batch = SchemaBatch::new();
+    batch.delete::<S>(key).unwrap();
+    db.db.write_schemas(batch).unwrap();
+}
+
+fn test_verify_consistent_db_impl(
+    db: &LibraDB,
+    blocks_to_commit: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    save_mock_genesis(db).unwrap();
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &blocks_to_commit {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    let report = verify(db);
+    assert!(report.is_consistent(), "{:?}", report.inconsistencies);
+    assert_eq!(report.first_version, Some(0));
+    assert_eq!(report.latest_version, Some(cur_ver));
+    assert_eq!(report.num_transactions_checked, cur_ver + 1);
+    // Ledger infos of empty blocks overwrite the earlier ones at the same version.
+    let ledger_info_versions = blocks_to_commit
+        .iter()
+        .map(|(_, ledger_info_with_sigs)| ledger_info_with_sigs.ledger_info().version())
+        .chain(std::iter::once(0))
+        .collect::<HashSet<_>>();
+    assert_eq!(
+        report.num_ledger_infos_checked,
+        ledger_info_versions.len() as u64
+    );
+    assert!(report.num_account_states_checked > 0);
+}
+
+fn test_verify_restored_db_impl(
+    db: &LibraDB,
+    blocks_to_commit: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+) {
+    save_mock_genesis(db).unwrap();
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &blocks_to_commit {
+        db.save_transactions(
+            txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )
+        .unwrap();
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    if cur_ver == 0 {
+        // Nothing is committed after the genesis transaction, there is no snapshot to restore.
+        return;
+    }
+    let ledger_info_with_sigs = db.ledger_store.get_latest_ledger_info().unwrap();
+    let chunk = db
+        .get_account_state_chunk_with_proof(cur_ver, None, MAX_LIMIT)
+        .unwrap();
+
+    // Restored by the restore tool: the state snapshot first, then the whole history of
+    // transactions, whose older states are never written.
+    let restore_dir = tempdir().unwrap();
+    let restore_db = LibraDB::new(&restore_dir);
+    restore_db
+        .restore_account_state_chunk(
+            cur_ver,
+            chunk.account_states.clone(),
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        )
+        .unwrap();
+    let (txn_and_infos, events): (Vec<_>, Vec<_>) = db
+        .get_transactions_with_events(0, cur_ver + 1)
+        .unwrap()
+        .into_iter()
+        .map(|(signed_txn, txn_info, events)| ((signed_txn, txn_info), events))
+        .unzip();
+    restore_db
+        .restore_transaction_chunk(0, &txn_and_infos, &events)
+        .unwrap();
+    restore_db
+        .finish_restore(&db.get_ledger_infos(0, MAX_LIMIT).unwrap())
+        .unwrap();
+
+    let report = verify(&restore_db);
+    assert!(report.is_consistent(), "{:?}", report.inconsistencies);
+    assert_eq!(report.first_version, Some(0));
+    assert_eq!(report.state_snapshot_version, Some(cur_ver));
+    assert_eq!(report.num_transactions_checked, cur_ver + 1);
+
+    // Restored by state sync: the state snapshot on top of the genesis transaction.
+    let sync_dir = tempdir().unwrap();
+    let sync_db = db_with_mock_genesis(&sync_dir).unwrap();
+    sync_db
+        .restore_account_state_chunk(
+            cur_ver,
+            chunk.account_states.clone(),
+            *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        )
+        .unwrap();
+    sync_db
+        .finish_state_snapshot(
+            &ledger_info_with_sigs,
+            &chunk.transaction_info,
+            &chunk.ledger_info_to_transaction_info_proof,
+            &chunk.ledger_frozen_subtree_hashes,
+        )
+        .unwrap();
+
+    let report = verify(&sync_db);
+    assert!(report.is_consistent(), "{:?}", report.inconsistencies);
+    assert_eq!(report.first_version, Some(0));
+    assert_eq!(report.state_snapshot_version, Some(cur_ver));
+    assert_eq!(report.num_transactions_checked, 2);
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_verify_consistent_db(blocks_to_commit in arb_blocks_to_commit()) {
+        for_each_backend(|db| test_verify_consistent_db_impl(db, blocks_to_commit.clone()));
+    }
+
+    #[test]
+    fn test_verify_restored_db(blocks_to_commit in arb_blocks_to_commit()) {
+        for_each_backend(|db| test_verify_restored_db_impl(db, blocks_to_commit.clone()));
+    }
+}
+
+#[test]
+fn test_verify_empty_db() {
+    for_each_backend(|db| assert_eq!(verify(db), DbVerifyReport::default()));
+}
+
+#[test]
+fn test_missing_transaction() {
+    for_each_backend(test_missing_transaction_impl);
+}
+
+fn test_missing_transaction_impl(db: &LibraDB) {
+    save_three_txns(db);
+    delete::<SignedTransactionSchema>(db, &1);
+
+    let report = verify(db);
+    assert_eq!(report.inconsistencies.len(), 1);
+    match &report.inconsistencies[0] {
+        Inconsistency::MissingTransaction { version, .. } => assert_eq!(*version, 1),
+        e => panic!("Unexpected inconsistency: {:?}", e),
+    }
+}
+
+#[test]
+fn test_extra_event() {
+    for_each_backend(test_extra_event_impl);
+}
+
+fn test_extra_event_impl(db: &LibraDB) {
+    save_three_txns(db);
+    let event = ContractEvent::new(
+        AccessPath::new(AccountAddress::random(), vec![]),
+        0,      /* sequence_number */
+        vec![], /* event_data */
+    );
+    db.db.put::<EventSchema>(&(2, 0), &event).unwrap();
+
+    let report = verify(db);
+    assert_eq!(report.inconsistencies.len(), 1);
+    match &report.inconsistencies[0] {
+        Inconsistency::EventRootMismatch { version, .. } => assert_eq!(*version, 2),
+        e => panic!("Unexpected inconsistency: {:?}", e),
+    }
+}
+
+#[test]
+fn test_version_gap() {
+    for_each_backend(test_version_gap_impl);
+}
+
+fn test_version_gap_impl(db: &LibraDB) {
+    save_three_txns(db);
+    delete::<TransactionInfoSchema>(db, &1);
+
+    let report = verify(db);
+    assert!(report.inconsistencies.contains(&Inconsistency::VersionGap {
+        expected: 1,
+        found: 2,
+    }));
+    assert_eq!(report.num_transactions_checked, 1);
+}
+
+#[test]
+fn test_corrupted_accumulator() {
+    for_each_backend(test_corrupted_accumulator_impl);
+}
+
+fn test_corrupted_accumulator_impl(db: &LibraDB) {
+    save_three_txns(db);
+    // The frozen subtree of the first two leaves.
+    let position = Position::from_inorder_index(1);
+    db.db
+        .put::<TransactionAccumulatorSchema>(&position, &HashValue::random())
+        .unwrap();
+
+    let report = verify(db);
+    assert_eq!(
+        report.inconsistencies,
+        vec![Inconsistency::StoredAccumulatorMismatch { version: 2 }]
+    );
+}
+
+#[test]
+fn test_missing_state_nodes() {
+    for_each_backend(test_missing_state_nodes_impl);
+}
+
+fn test_missing_state_nodes_impl(db: &LibraDB) {
+    save_three_txns(db);
+    let (_, txn_info) = db.ledger_store.get_latest_transaction_info().unwrap();
+    let root_hash = txn_info.state_root_hash();
+    delete::<StateMerkleNodeSchema>(db, &root_hash);
+
+    // The same state root is shared by all the versions.
+    let report = verify(db);
+    assert_eq!(
+        report
+            .inconsistencies
+            .iter()
+            .filter(|e| match e {
+                Inconsistency::MissingStateRoot { .. } => true,
+                _ => false,
+            })
+            .count(),
+        3
+    );
+    assert!(report
+        .inconsistencies
+        .contains(&Inconsistency::MissingStateNode {
+            hash: format!("{:x}", root_hash)
+        }));
+
+    // Missing roots of pruned versions are expected.
+    let report = DbVerifier::new(db, Some(0), None /* validator_verifier */)
+        .verify()
+        .unwrap();
+    assert_eq!(report.inconsistencies.len(), 2);
+}
+
+#[test]
+fn test_bad_signatures() {
+    for_each_backend(test_bad_signatures_impl);
+}
+
+fn test_bad_signatures_impl(db: &LibraDB) {
+    save_three_txns(db);
+    // Commit an unsigned ledger info at version 2.
+    let leaf = |version| {
+        db.ledger_store
+            .get_transaction_info(version)
+            .unwrap()
+            .hash()
+    };
+    let internal_node = |left, right| {
+        MerkleTreeInternalNode::<TransactionAccumulatorHasher>::new(left, right).hash()
+    };
+    let root_hash = internal_node(
+        internal_node(leaf(0), leaf(1)),
+        internal_node(leaf(2), *ACCUMULATOR_PLACEHOLDER_HASH),
+    );
+    let ledger_info_with_sigs = LedgerInfoWithSignatures::new(
+        LedgerInfo::new(
+            2, /* version */
+            root_hash,
+            HashValue::random(),
+            HashValue::zero(),
+            0, /* epoch_num */
+            0, /* timestamp_usecs */
+        ),
+        HashMap::new(), /* signatures */
+    );
+    let mut batch = SchemaBatch::new();
+    db.ledger_store
+        .put_ledger_info(&ledger_info_with_sigs, &mut batch)
+        .unwrap();
+    db.commit(batch).unwrap();
+    assert!(verify(db).is_consistent());
+
+    let signer = ValidatorSigner::random();
+    let validator_verifier = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    let report = DbVerifier::new(db, None /* prune_window */, Some(&validator_verifier))
+        .verify()
+        .unwrap();
+    // The genesis ledger info carries no signature to verify.
+    assert_eq!(report.num_signatures_verified, 1);
+    match &report.inconsistencies[..] {
+        [Inconsistency::BadSignatures { version, .. }] => assert_eq!(*version, 2),
+        e => panic!("Unexpected inconsistencies: {:?}", e),
+    }
+}
@@ -0,0 +1,537 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides [`DbVerifier`] which checks a [`LibraDB`] for internal consistency, e.g.
+//! after a disk incident, and reports every inconsistency found.
+//!
+//! It rebuilds the transaction accumulator from the stored transaction infos and checks it against
+//! the stored accumulator and every ledger info, checks each transaction info against the stored
+//! transaction, events and state root, walks the state Merkle tree of the latest version, and
+//! verifies the ledger info signatures of the latest epoch when given the validator set.
+
+#[cfg(test)]
+mod db_verifier_test;
+
+use crate::{
+    schema::{
+        account_state::AccountStateSchema, ledger_info::LedgerInfoSchema,
+        signed_transaction::SignedTransactionSchema, state_merkle_node::StateMerkleNodeSchema,
+        transaction_info::TransactionInfoSchema,
+    },
+    LibraDB,
+};
+use crypto::{
+    hash::{CryptoHash, TransactionAccumulatorHasher, SPARSE_MERKLE_PLACEHOLDER_HASH},
+    HashValue,
+};
+use failure::prelude::*;
+use schemadb::ReadOptions;
+use serde::Serialize;
+use sparse_merkle::node_type::Node;
+use types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{verify_ledger_frozen_subtree_hashes, MerkleTreeInternalNode},
+    transaction::{TransactionInfo, Version},
+    validator_verifier::ValidatorVerifier,
+};
+
+/// The machine-readable result of [`DbVerifier::verify`]. Hashes are hex encoded.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct DbVerifyReport {
+    /// The first version with a transaction info. `None` if the db is empty.
+    pub first_version: Option<Version>,
+    /// The version of the state snapshot the db is restored from, if any. State roots are only
+    /// checked from this version on.
+    pub state_snapshot_version: Option<Version>,
+    /// The latest version with a transaction info.
+    pub latest_version: Option<Version>,
+    pub num_transactions_checked: u64,
+    pub num_ledger_infos_checked: u64,
+    /// Number of ledger infos whose signatures are verified, 0 if no validator set is given.
+    pub num_signatures_verified: u64,
+    /// Number of nodes reached when walking the state Merkle tree of the latest version.
+    pub num_state_nodes_checked: u64,
+    pub num_account_states_checked: u64,
+    pub inconsistencies: Vec<Inconsistency>,
+}
+
+impl DbVerifyReport {
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+}
+
+/// An inconsistency found by [`DbVerifier`].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Inconsistency {
+    /// Transaction infos are not at consecutive versions, other than the jump from the genesis
+    /// transaction to a state snapshot restored by state sync. Checking the transactions stops
+    /// here.
+    VersionGap {
+        expected: Version,
+        found: Version,
+    },
+    /// The transaction is missing or can't be decoded.
+    MissingTransaction {
+        version: Version,
+        error: String,
+    },
+    TransactionHashMismatch {
+        version: Version,
+    },
+    /// The events or their accumulator nodes are missing, corrupted or don't match the event
+    /// root hash in the transaction info.
+    EventRootMismatch {
+        version: Version,
+        error: String,
+    },
+    /// The root node of the state Merkle tree of a version expected to be readable is missing.
+    MissingStateRoot {
+        version: Version,
+        hash: String,
+    },
+    /// The ledger info doesn't carry the root hash of the rebuilt transaction accumulator.
+    LedgerInfoAccumulatorMismatch {
+        version: Version,
+        error: String,
+    },
+    /// The frozen subtree hashes of the stored transaction accumulator don't match the rebuilt
+    /// ones.
+    StoredAccumulatorMismatch {
+        version: Version,
+    },
+    /// There is no transaction info at the version of the ledger info.
+    DanglingLedgerInfo {
+        version: Version,
+    },
+    BadSignatures {
+        version: Version,
+        error: String,
+    },
+    MissingStateNode {
+        hash: String,
+    },
+    /// The state Merkle tree node can't be decoded or doesn't match its hash.
+    CorruptedStateNode {
+        hash: String,
+    },
+    MissingAccountStateBlob {
+        hash: String,
+    },
+    /// The account state blob can't be decoded or doesn't match its hash.
+    CorruptedAccountStateBlob {
+        hash: String,
+    },
+}
+
+/// Checks a [`LibraDB`] for internal consistency, see the [module level doc](self).
+pub struct DbVerifier<'a> {
+    db: &'a LibraDB,
+    /// Only the account states of the latest version and the `prune_window` versions before it
+    /// are expected to be readable, as configured for the node the db belongs to. Those older than
+    /// a restored state snapshot are never readable.
+    prune_window: Option<u64>,
+    validator_verifier: Option<&'a ValidatorVerifier>,
+}
+
+impl<'a> DbVerifier<'a> {
+    /// Creates a verifier of `db`. Ledger info signatures are verified only when
+    /// `validator_verifier`, the validator set of the latest epoch, is given.
+    pub fn new(
+        db: &'a LibraDB,
+        prune_window: Option<u64>,
+        validator_verifier: Option<&'a ValidatorVerifier>,
+    ) -> Self {
+        Self {
+            db,
+            prune_window,
+            validator_verifier,
+        }
+    }
+
+    /// Runs all the checks. Inconsistencies are collected in the report, while an error is only
+    /// returned when the db can't be read at all.
+    pub fn verify(&self) -> Result<DbVerifyReport> {
+        let mut report = DbVerifyReport::default();
+        if let Some((latest_version, latest_txn_info)) =
+            self.db.ledger_store.get_latest_transaction_info_option()?
+        {
+            report.latest_version = Some(latest_version);
+            self.verify_ledger(latest_version, &mut report)?;
+            self.verify_state_tree(latest_txn_info.state_root_hash(), &mut report);
+        } else if self
+            .db
+            .ledger_store
+            .get_latest_ledger_info_option()?
+            .is_some()
+        {
+            let mut iter = self
+                .db
+                .db
+                .iter::<LedgerInfoSchema>(ReadOptions::default())?;
+            iter.seek_to_first();
+            for item in iter {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::DanglingLedgerInfo { version: item?.0 });
+            }
+        }
+        Ok(report)
+    }
+
+    fn verify_ledger(&self, latest_version: Version, report: &mut DbVerifyReport) -> Result<()> {
+        let state_snapshot_version = self.db.get_state_snapshot_version()?;
+        report.state_snapshot_version = state_snapshot_version;
+        let least_readable_version = std::cmp::max(
+            self.prune_window
+                .map_or(0, |window| latest_version.saturating_sub(window)),
+            state_snapshot_version.unwrap_or(0),
+        );
+        let latest_epoch_num = self
+            .db
+            .ledger_store
+            .get_latest_ledger_info_option()?
+            .map(|ledger_info_with_sigs| ledger_info_with_sigs.ledger_info().epoch_num());
+
+        let mut ledger_info_iter = self
+            .db
+            .db
+            .iter::<LedgerInfoSchema>(ReadOptions::default())?;
+        ledger_info_iter.seek_to_first();
+        let mut ledger_infos = ledger_info_iter.peekable();
+        let mut txn_info_iter = self
+            .db
+            .db
+            .iter::<TransactionInfoSchema>(ReadOptions::default())?;
+        txn_info_iter.seek_to_first();
+
+        let mut accumulator: Option<FrozenSubtrees> = None;
+        let mut last_checked_ledger_info_version = None;
+        let mut has_gap = false;
+        for item in txn_info_iter {
+            let (version, txn_info) = item?;
+            // State sync only stores the info of the transaction at the state snapshot it restores,
+            // on top of the frozen subtrees of the accumulator up to it.
+            let mut bootstrapped = Some(version) == state_snapshot_version
+                && self
+                    .db
+                    .db
+                    .get::<SignedTransactionSchema>(&version)?
+                    .is_none();
+            match &mut accumulator {
+                Some(accumulator) if bootstrapped => {
+                    *accumulator = self.stored_accumulator(version)?;
+                }
+                Some(accumulator) => {
+                    if version != accumulator.num_leaves {
+                        report.inconsistencies.push(Inconsistency::VersionGap {
+                            expected: accumulator.num_leaves,
+                            found: version,
+                        });
+                        has_gap = true;
+                        break;
+                    }
+                    accumulator.append(txn_info.hash());
+                }
+                None => {
+                    report.first_version = Some(version);
+                    // A db bootstrapped from a state snapshot has no history before its first
+                    // version, so the accumulator is rebuilt on top of the stored one.
+                    accumulator = Some(if version == 0 {
+                        let mut accumulator = FrozenSubtrees::default();
+                        accumulator.append(txn_info.hash());
+                        accumulator
+                    } else {
+                        bootstrapped = true;
+                        self.stored_accumulator(version)?
+                    });
+                }
+            }
+            let accumulator = accumulator
+                .as_ref()
+                .expect("Accumulator must have been initialized.");
+
+            self.verify_transaction(
+                version,
+                &txn_info,
+                bootstrapped,
+                least_readable_version,
+                report,
+            );
+            report.num_transactions_checked += 1;
+
+            // Ledger infos are stored at a subset of the versions in ascending order.
+            loop {
+                match ledger_infos.peek() {
+                    Some(Ok((ledger_info_version, _))) if *ledger_info_version > version => break,
+                    None => break,
+                    _ => (),
+                }
+                let (ledger_info_version, ledger_info_with_sigs) = ledger_infos
+                    .next()
+                    .expect("Peeked ledger info must exist.")?;
+                if ledger_info_version < version {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::DanglingLedgerInfo {
+                            version: ledger_info_version,
+                        });
+                    continue;
+                }
+                self.verify_ledger_info(
+                    &ledger_info_with_sigs,
+                    accumulator,
+                    latest_epoch_num,
+                    report,
+                );
+                last_checked_ledger_info_version = Some(version);
+            }
+        }
+        if !has_gap {
+            for item in ledger_infos {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::DanglingLedgerInfo { version: item?.0 });
+            }
+        }
+
+        // The latest version may not have a ledger info, e.g. after state sync.
+        if let Some(accumulator) = &accumulator {
+            let version = accumulator.num_leaves - 1;
+            if last_checked_ledger_info_version != Some(version) {
+                self.verify_stored_accumulator(version, accumulator, report);
+            }
+        }
+        Ok(())
+    }
+
+    /// The stored transaction accumulator with `version + 1` leaves.
+    fn stored_accumulator(&self, version: Version) -> Result<FrozenSubtrees> {
+        Ok(FrozenSubtrees {
+            num_leaves: version + 1,
+            hashes: self
+                .db
+                .ledger_store
+                .get_ledger_frozen_subtree_hashes(version)?,
+        })
+    }
+
+    fn verify_transaction(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        bootstrapped: bool,
+        least_readable_version: Version,
+        report: &mut DbVerifyReport,
+    ) {
+        if !bootstrapped {
+            self.verify_transaction_and_events(version, txn_info, report);
+        }
+
+        let state_root_hash = txn_info.state_root_hash();
+        if version >= least_readable_version
+            && state_root_hash != *SPARSE_MERKLE_PLACEHOLDER_HASH
+            && !self
+                .db
+                .db
+                .get::<StateMerkleNodeSchema>(&state_root_hash)
+                .map(|node| node.is_some())
+                .unwrap_or(false)
+        {
+            report
+                .inconsistencies
+                .push(Inconsistency::MissingStateRoot {
+                    version,
+                    hash: format!("{:x}", state_root_hash),
+                });
+        }
+    }
+
+    fn verify_transaction_and_events(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        report: &mut DbVerifyReport,
+    ) {
+        match self.db.transaction_store.get_transaction(version) {
+            Ok(signed_txn) => {
+                if signed_txn.hash() != txn_info.signed_transaction_hash() {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::TransactionHashMismatch { version });
+                }
+            }
+            Err(e) => report
+                .inconsistencies
+                .push(Inconsistency::MissingTransaction {
+                    version,
+                    error: e.to_string(),
+                }),
+        }
+
+        match self.db.event_store.recompute_event_root_hash(version) {
+            Ok(event_root_hash) if event_root_hash == txn_info.event_root_hash() => (),
+            Ok(event_root_hash) => report
+                .inconsistencies
+                .push(Inconsistency::EventRootMismatch {
+                    version,
+                    error: format!(
+                        "Recomputed {:x}, expected {:x}.",
+                        event_root_hash,
+                        txn_info.event_root_hash()
+                    ),
+                }),
+            Err(e) => report
+                .inconsistencies
+                .push(Inconsistency::EventRootMismatch {
+                    version,
+                    error: e.to_string(),
+                }),
+        }
+    }
+
+    fn verify_ledger_info(
+        &self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+        accumulator: &FrozenSubtrees,
+        latest_epoch_num: Option<u64>,
+        report: &mut DbVerifyReport,
+    ) {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let version = ledger_info.version();
+        if let Err(e) = verify_ledger_frozen_subtree_hashes(ledger_info, &accumulator.hashes) {
+            report
+                .inconsistencies
+                .push(Inconsistency::LedgerInfoAccumulatorMismatch {
+                    version,
+                    error: e.to_string(),
+                });
+        }
+        self.verify_stored_accumulator(version, accumulator, report);
+
+        // Ledger infos of earlier epochs are signed by earlier validator sets.
+        if let Some(validator_verifier) = self.validator_verifier {
+            if Some(ledger_info.epoch_num()) == latest_epoch_num {
+                match ledger_info_with_sigs.verify(validator_verifier) {
+                    Ok(()) => report.num_signatures_verified += 1,
+                    Err(e) => report.inconsistencies.push(Inconsistency::BadSignatures {
+                        version,
+                        error: format!("{:?}", e),
+                    }),
+                }
+            }
+        }
+        report.num_ledger_infos_checked += 1;
+    }
+
+    fn verify_stored_accumulator(
+        &self,
+        version: Version,
+        accumulator: &FrozenSubtrees,
+        report: &mut DbVerifyReport,
+    ) {
+        match self
+            .db
+            .ledger_store
+            .get_ledger_frozen_subtree_hashes(version)
+        {
+            Ok(ref hashes) if *hashes == accumulator.hashes => (),
+            _ => report
+                .inconsistencies
+                .push(Inconsistency::StoredAccumulatorMismatch { version }),
+        }
+    }
+
+    fn verify_state_tree(&self, root_hash: HashValue, report: &mut DbVerifyReport) {
+        if root_hash == *SPARSE_MERKLE_PLACEHOLDER_HASH {
+            return;
+        }
+        let mut stack = vec![root_hash];
+        while let Some(node_hash) = stack.pop() {
+            let node = match self.db.db.get::<StateMerkleNodeSchema>(&node_hash) {
+                Ok(Some(node)) => node,
+                Ok(None) => {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::MissingStateNode {
+                            hash: format!("{:x}", node_hash),
+                        });
+                    continue;
+                }
+                Err(_) => {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::CorruptedStateNode {
+                            hash: format!("{:x}", node_hash),
+                        });
+                    continue;
+                }
+            };
+            report.num_state_nodes_checked += 1;
+            if node.hash() != node_hash {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::CorruptedStateNode {
+                        hash: format!("{:x}", node_hash),
+                    });
+                continue;
+            }
+
+            match node {
+                // Push the children in reverse order so that the tree is walked in key order.
+                Node::Branch(branch_node) => stack.extend(
+                    (0..16u8)
+                        .rev()
+                        .filter_map(|nibble| branch_node.child(nibble)),
+                ),
+                Node::Extension(extension_node) => stack.push(extension_node.child()),
+                Node::Leaf(leaf_node) => {
+                    self.verify_account_state_blob(leaf_node.value_hash(), report)
+                }
+            }
+        }
+    }
+
+    fn verify_account_state_blob(&self, blob_hash: HashValue, report: &mut DbVerifyReport) {
+        let hash = format!("{:x}", blob_hash);
+        match self.db.db.get::<AccountStateSchema>(&blob_hash) {
+            Ok(Some(blob)) if blob.hash() == blob_hash => (),
+            Ok(Some(_)) | Err(_) => report
+                .inconsistencies
+                .push(Inconsistency::CorruptedAccountStateBlob { hash }),
+            Ok(None) => report
+                .inconsistencies
+                .push(Inconsistency::MissingAccountStateBlob { hash }),
+        }
+        report.num_account_states_checked += 1;
+    }
+}
+
+/// The root hashes of the frozen subtrees of a transaction accumulator being rebuilt leaf by
+/// leaf, from left to right. There is one for each bit set in `num_leaves`.
+#[derive(Default)]
+struct FrozenSubtrees {
+    num_leaves: u64,
+    hashes: Vec<HashValue>,
+}
+
+impl FrozenSubtrees {
+    fn append(&mut self, leaf_hash: HashValue) {
+        // The new leaf merges with a frozen subtree of the same size on its left for each
+        // trailing bit set in `num_leaves`.
+        let mut hash = leaf_hash;
+        let mut bits = self.num_leaves;
+        while bits & 1 == 1 {
+            let left_hash = self
+                .hashes
+                .pop()
+                .expect("There is a frozen subtree for each bit set.");
+            hash =
+                MerkleTreeInternalNode::<TransactionAccumulatorHasher>::new(left_hash, hash).hash();
+            bits >>= 1;
+        }
+        self.hashes.push(hash);
+        self.num_leaves += 1;
+    }
+}
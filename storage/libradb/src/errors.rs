@@ -14,4 +14,16 @@ pub enum LibraDbError {
     /// Requested too many items.
     #[fail(display = "Too many items requested: {}, max is {}", _0, _1)]
     TooManyRequested(u64, u64),
+    /// The requested version has been pruned.
+    #[fail(
+        display = "Version {} has been pruned, the least readable version is {}.",
+        _0, _1
+    )]
+    Pruned(u64, u64),
+    /// The events of the requested version were committed before they were indexed by type.
+    #[fail(
+        display = "Events of version {} aren't indexed by type, the index starts at version {}.",
+        _0, _1
+    )]
+    NotIndexedByType(u64, u64),
 }
@@ -9,9 +9,12 @@ use super::LibraDB;
 use crate::{
     errors::LibraDbError,
     schema::{
-        event::EventSchema, event_accumulator::EventAccumulatorSchema,
+        event::EventSchema,
+        event_accumulator::EventAccumulatorSchema,
         event_by_access_path::EventByAccessPathSchema,
+        event_by_type::{EventByTypeSchema, PathEnd, PathPrefix},
     },
+    schema_reader::SchemaReader,
 };
 use accumulator::{HashReader, MerkleAccumulator};
 use crypto::{
@@ -19,8 +22,7 @@ use crypto::{
     HashValue,
 };
 use failure::prelude::*;
-use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch, DB};
-use std::sync::Arc;
+use schemadb::{schema::ValueCodec, ReadOptions, SchemaBatch};
 use types::{
     access_path::AccessPath,
     contract_event::ContractEvent,
@@ -29,11 +31,11 @@ use types::{
 };
 
 pub(crate) struct EventStore {
-    db: Arc<DB>,
+    db: SchemaReader,
 }
 
 impl EventStore {
-    pub fn new(db: Arc<DB>) -> Self {
+    pub fn new(db: SchemaReader) -> Self {
         Self { db }
     }
 
@@ -41,19 +43,12 @@ impl EventStore {
     /// We don't need a proof for this because it's only used to get all events
     /// for a version which can be proved from the root hash of the event tree.
     pub fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
-        let mut events = vec![];
-
-        let mut iter = self.db.iter::<EventSchema>(ReadOptions::default())?;
-        // Grab the first event and then iterate until we get all events for this version.
-        iter.seek(&version)?;
-        while let Some(((ver, index), event)) = iter.next().transpose()? {
-            if ver != version {
-                break;
-            }
-            events.push(event);
-        }
-
-        Ok(events)
+        // The version is the key prefix of the events, so the iterator stops right after the last
+        // event of this version.
+        self.db
+            .prefix_iter::<EventSchema, _>(&version)?
+            .map(|res| res.map(|(_key, event)| event))
+            .collect()
     }
 
     /// Get the event raw data given transaction version and the index of the event queried.
@@ -182,6 +177,47 @@ impl EventStore {
         Ok(result)
     }
 
+    /// Given a prefix of event types, i.e. of the paths of the access paths events are emitted to
+    /// under any account, and a start position, return up to `limit` events of the types starting
+    /// with the prefix from that position on, identified by transaction version and index among
+    /// all events yielded by the same transaction. The position is the transaction version plus
+    /// the event index to start with within it. Result won't contain records with a txn_version >
+    /// `end_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        path_prefix: &[u8],
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+    ) -> Result<Vec<(Version, u64)>> {
+        let mut iter = self.db.iter::<EventByTypeSchema>(ReadOptions::default())?;
+        iter.seek(&PathPrefix(path_prefix.to_vec()))?;
+
+        // Take up to `limit` events of each type starting with the prefix, and then the first
+        // `limit` of them all.
+        let mut result = Vec::new();
+        loop {
+            let path = match iter.next().transpose()? {
+                Some(((path, _, _), ())) if path.starts_with(path_prefix) => path,
+                _ => break,
+            };
+            iter.seek(&(path.clone(), start_version, start_event_index))?;
+            for res in iter.by_ref().take(limit as usize) {
+                let ((event_path, ver, idx), ()) = res?;
+                if event_path != path || ver > end_version {
+                    break;
+                }
+                result.push((ver, idx));
+            }
+            iter.seek(&PathEnd(path))?;
+        }
+        result.sort();
+        result.truncate(limit as usize);
+
+        Ok(result)
+    }
+
     /// Save contract events yielded by the transaction at `version` and return root hash of the
     /// event accumulator formed by these events.
     pub fn put_events(
@@ -190,7 +226,7 @@ impl EventStore {
         events: &[ContractEvent],
         batch: &mut SchemaBatch,
     ) -> Result<HashValue> {
-        // EventSchema and EventByAccessPathSchema updates
+        // EventSchema, EventByAccessPathSchema and EventByTypeSchema updates
         events
             .iter()
             .enumerate()
@@ -200,6 +236,10 @@ impl EventStore {
                     &(event.access_path().clone(), event.sequence_number()),
                     &(version, idx as u64),
                 )?;
+                batch.put::<EventByTypeSchema>(
+                    &(event.access_path().path.clone(), version, idx as u64),
+                    &(),
+                )?;
                 Ok(())
             })
             .collect::<Result<()>>()?;
@@ -214,6 +254,26 @@ impl EventStore {
 
         Ok(root_hash)
     }
+
+    /// Recomputes the root hash of the event accumulator of the transaction at `version` out of
+    /// the events stored, ensuring the stored accumulator nodes match the recomputed ones.
+    pub fn recompute_event_root_hash(&self, version: Version) -> Result<HashValue> {
+        let event_hashes: Vec<HashValue> = self
+            .get_events_by_version(version)?
+            .iter()
+            .map(ContractEvent::hash)
+            .collect();
+        let (root_hash, writes) = EmptyAccumulator::append(&EmptyReader, 0, &event_hashes)?;
+        for (pos, hash) in writes {
+            ensure!(
+                self.db.get::<EventAccumulatorSchema>(&(version, pos))? == Some(hash),
+                "Event accumulator node at version {} position {:?} mismatches.",
+                version,
+                pos,
+            );
+        }
+        Ok(root_hash)
+    }
 }
 
 type Accumulator<'a> = MerkleAccumulator<EventHashReader<'a>, EventAccumulatorHasher>;
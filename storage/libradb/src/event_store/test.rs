@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::LibraDB;
+use crate::{test_helper::for_each_backend, LibraDB};
 use crypto::{hash::ACCUMULATOR_PLACEHOLDER_HASH, utils::keypair_strategy};
 use itertools::Itertools;
 use proptest::{
@@ -11,42 +11,44 @@ use proptest::{
     strategy::Union,
 };
 use rand::{Rng, StdRng};
-use std::collections::HashMap;
-use tempfile::tempdir;
+use std::collections::{HashMap, HashSet};
 use types::{
     account_address::AccountAddress, contract_event::ContractEvent,
     proof::verify_event_accumulator_element, proptest_types::renumber_events,
 };
 
-fn save(store: &EventStore, version: Version, events: &[ContractEvent]) -> HashValue {
+fn save(db: &LibraDB, version: Version, events: &[ContractEvent]) -> HashValue {
     let mut batch = SchemaBatch::new();
-    let root_hash = store.put_events(version, events, &mut batch).unwrap();
-    store.db.write_schemas(batch).unwrap();
+    let root_hash = db
+        .event_store
+        .put_events(version, events, &mut batch)
+        .unwrap();
+    db.db.write_schemas(batch).unwrap();
 
     root_hash
 }
 
 #[test]
 fn test_put_empty() {
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
-    let store = &db.event_store;
-    let mut batch = SchemaBatch::new();
-    assert_eq!(
-        store.put_events(0, &[], &mut batch).unwrap(),
-        *ACCUMULATOR_PLACEHOLDER_HASH
-    );
+    for_each_backend(|db| {
+        let store = &db.event_store;
+        let mut batch = SchemaBatch::new();
+        assert_eq!(
+            store.put_events(0, &[], &mut batch).unwrap(),
+            *ACCUMULATOR_PLACEHOLDER_HASH
+        );
+    });
 }
 
 #[test]
 fn test_error_on_get_from_empty() {
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
-    let store = &db.event_store;
+    for_each_backend(|db| {
+        let store = &db.event_store;
 
-    assert!(store
-        .get_event_with_proof_by_version_and_index(100, 0)
-        .is_err());
+        assert!(store
+            .get_event_with_proof_by_version_and_index(100, 0)
+            .is_err());
+    });
 }
 
 proptest! {
@@ -54,24 +56,25 @@ proptest! {
 
     #[test]
     fn test_put_get_verify(events in vec(any::<ContractEvent>().no_shrink(), 1..100)) {
-        let tmp_dir = tempdir().unwrap();
-        let db = LibraDB::new(&tmp_dir);
-        let store = &db.event_store;
-
-        let root_hash = save(store, 100, &events);
-
-        // get and verify each and every event with proof
-        for (idx, expected_event) in events.iter().enumerate() {
-            let (event, proof) = store
-                .get_event_with_proof_by_version_and_index(100, idx as u64)
-                .unwrap();
-            prop_assert_eq!(&event, expected_event);
-            verify_event_accumulator_element(root_hash, event.hash(),  idx as u64, &proof).unwrap();
-        }
-        // error on index >= num_events
-        prop_assert!(store
-            .get_event_with_proof_by_version_and_index(100, events.len() as u64)
-            .is_err());
+        for_each_backend(|db| {
+            let store = &db.event_store;
+
+            let root_hash = save(db, 100, &events);
+
+            // get and verify each and every event with proof
+            for (idx, expected_event) in events.iter().enumerate() {
+                let (event, proof) = store
+                    .get_event_with_proof_by_version_and_index(100, idx as u64)
+                    .unwrap();
+                assert_eq!(&event, expected_event);
+                verify_event_accumulator_element(root_hash, event.hash(), idx as u64, &proof)
+                    .unwrap();
+            }
+            // error on index >= num_events
+            assert!(store
+                .get_event_with_proof_by_version_and_index(100, events.len() as u64)
+                .is_err());
+        });
     }
 
 }
@@ -85,28 +88,27 @@ proptest! {
         events2 in vec(any::<ContractEvent>().no_shrink(), 1..100),
         events3 in vec(any::<ContractEvent>().no_shrink(), 1..100),
     ) {
-
-        let tmp_dir = tempdir().unwrap();
-        let db = LibraDB::new(&tmp_dir);
-        let store = &db.event_store;
-        // Save 3 chunks at different versions
-        save(store, 99 /*version*/, &events1);
-        save(store, 100 /*version*/, &events2);
-        save(store, 101 /*version*/, &events3);
-
-        // Now get all events at each version and verify that it matches what is expected.
-        let events_99 = store.get_events_by_version(99 /*version*/).unwrap();
-        prop_assert_eq!(events_99, events1);
-
-        let events_100 = store.get_events_by_version(100 /*version*/).unwrap();
-        prop_assert_eq!(events_100, events2);
-
-        let events_101 = store.get_events_by_version(101 /*version*/).unwrap();
-        prop_assert_eq!(events_101, events3);
-
-        // Now query a version that doesn't exist and verify that no results come back
-        let events_102 = store.get_events_by_version(102 /*version*/).unwrap();
-        prop_assert_eq!(events_102.len(), 0);
+        for_each_backend(|db| {
+            let store = &db.event_store;
+            // Save 3 chunks at different versions
+            save(db, 99 /*version*/, &events1);
+            save(db, 100 /*version*/, &events2);
+            save(db, 101 /*version*/, &events3);
+
+            // Now get all events at each version and verify that it matches what is expected.
+            let events_99 = store.get_events_by_version(99 /*version*/).unwrap();
+            assert_eq!(events_99, events1);
+
+            let events_100 = store.get_events_by_version(100 /*version*/).unwrap();
+            assert_eq!(events_100, events2);
+
+            let events_101 = store.get_events_by_version(101 /*version*/).unwrap();
+            assert_eq!(events_101, events3);
+
+            // Now query a version that doesn't exist and verify that no results come back
+            let events_102 = store.get_events_by_version(102 /*version*/).unwrap();
+            assert_eq!(events_102.len(), 0);
+        });
     }
 }
 
@@ -194,17 +196,18 @@ proptest! {
 
     #[test]
     fn test_get_events_by_access_path((addresses, event_batches) in arb_event_batches().no_shrink()) {
-        test_get_events_by_access_path_impl(addresses, event_batches);
+        for_each_backend(|db| {
+            test_get_events_by_access_path_impl(db, addresses.clone(), event_batches.clone())
+        });
     }
 }
 
 fn test_get_events_by_access_path_impl(
+    db: &LibraDB,
     access_paths: Vec<AccessPath>,
     event_batches: Vec<Vec<ContractEvent>>,
 ) {
     // Put into db.
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
     let store = &db.event_store;
 
     let mut batch = SchemaBatch::new();
@@ -234,3 +237,91 @@ fn test_get_events_by_access_path_impl(
             assert_eq!(events, traversed);
         });
 }
+
+fn traverse_events_by_type(
+    store: &EventStore,
+    path_prefix: &[u8],
+    end_version: Version,
+) -> Vec<ContractEvent> {
+    const LIMIT: u64 = 3;
+
+    let (mut start_version, mut start_event_index) = (0, 0);
+
+    let mut event_keys = Vec::new();
+    loop {
+        let batch = store
+            .lookup_events_by_type(
+                path_prefix,
+                start_version,
+                start_event_index,
+                end_version,
+                LIMIT,
+            )
+            .unwrap();
+        assert!(batch.len() as u64 <= LIMIT);
+        let (ver, idx) = match batch.last() {
+            Some(last) => *last,
+            None => break,
+        };
+
+        event_keys.extend(batch);
+        start_version = ver;
+        start_event_index = idx + 1;
+    }
+
+    event_keys
+        .into_iter()
+        .map(|(ver, idx)| {
+            store
+                .get_event_with_proof_by_version_and_index(ver, idx)
+                .unwrap()
+                .0
+        })
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_get_events_by_type((_, event_batches) in arb_event_batches().no_shrink()) {
+        for_each_backend(|db| test_get_events_by_type_impl(db, event_batches.clone()));
+    }
+}
+
+fn test_get_events_by_type_impl(db: &LibraDB, event_batches: Vec<Vec<ContractEvent>>) {
+    // Put into db.
+    let store = &db.event_store;
+
+    let mut batch = SchemaBatch::new();
+    event_batches.iter().enumerate().for_each(|(ver, events)| {
+        store.put_events(ver as u64, events, &mut batch).unwrap();
+    });
+    db.commit(batch);
+
+    // Calculate expected events per prefix of types, taking every type as well as the empty prefix
+    // of all of them, in the order of transactions. The end version leaves out the last
+    // transaction unless it's the only one.
+    let end_version = (event_batches.len() as u64).saturating_sub(2);
+    let events = event_batches
+        .into_iter()
+        .take(end_version as usize + 1)
+        .flatten()
+        .collect::<Vec<_>>();
+    let path_prefixes = events
+        .iter()
+        .map(|e| e.access_path().path.clone())
+        .chain(std::iter::once(vec![]))
+        .collect::<HashSet<_>>();
+
+    // Fetch and check.
+    path_prefixes.into_iter().for_each(|path_prefix| {
+        let expected = events
+            .iter()
+            .filter(|e| e.access_path().path.starts_with(&path_prefix))
+            .cloned()
+            .collect::<Vec<_>>();
+        let traversed = traverse_events_by_type(&store, &path_prefix, end_version);
+        assert_eq!(expected, traversed);
+    });
+}
@@ -2,9 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::LibraDB;
+use crate::test_helper::for_each_backend;
 use proptest::{collection::vec, prelude::*};
-use tempfile::tempdir;
 use types::ledger_info::LedgerInfo;
 
 prop_compose! {
@@ -50,18 +49,23 @@ proptest! {
     fn test_ledger_info_put_get_verify(
         ledger_infos_with_sigs in arb_ledger_infos_with_sigs()
     ) {
-        let tmp_dir = tempdir().unwrap();
-        let db = LibraDB::new(&tmp_dir);
-        let store = &db.ledger_store;
-        let start_version = ledger_infos_with_sigs.first().unwrap().ledger_info().version();
+        for_each_backend(|db| {
+            let store = &db.ledger_store;
+            let start_version = ledger_infos_with_sigs.first().unwrap().ledger_info().version();
 
-        let mut batch = SchemaBatch::new();
-        ledger_infos_with_sigs
-            .iter()
-            .map(|info| store.put_ledger_info(info, &mut batch))
-            .collect::<Result<Vec<_>>>()
-            .unwrap();
-        db.commit(batch).unwrap();
-        prop_assert_eq!(db.ledger_store.get_ledger_infos(start_version).unwrap(), ledger_infos_with_sigs);
+            let mut batch = SchemaBatch::new();
+            ledger_infos_with_sigs
+                .iter()
+                .map(|info| store.put_ledger_info(info, &mut batch))
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+            db.commit(batch).unwrap();
+            assert_eq!(
+                db.ledger_store
+                    .get_ledger_infos(start_version, ledger_infos_with_sigs.len())
+                    .unwrap(),
+                ledger_infos_with_sigs
+            );
+        });
     }
 }
@@ -10,6 +10,7 @@ use crate::{
         ledger_info::LedgerInfoSchema, transaction_accumulator::TransactionAccumulatorSchema,
         transaction_info::TransactionInfoSchema,
     },
+    schema_reader::SchemaReader,
 };
 use accumulator::{HashReader, MerkleAccumulator};
 use crypto::{
@@ -18,8 +19,7 @@ use crypto::{
 };
 use failure::prelude::*;
 use itertools::Itertools;
-use schemadb::{ReadOptions, SchemaBatch, DB};
-use std::sync::Arc;
+use schemadb::{ReadOptions, SchemaBatch};
 use types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::{
@@ -30,23 +30,29 @@ use types::{
 };
 
 pub(crate) struct LedgerStore {
-    db: Arc<DB>,
+    db: SchemaReader,
 }
 
 impl LedgerStore {
-    pub fn new(db: Arc<DB>) -> Self {
+    pub fn new(db: SchemaReader) -> Self {
         Self { db }
     }
 
-    /// Return the ledger infos with their least 2f+1 signatures starting from `start_version` to
-    /// the most recent one.
+    /// Return up to `limit` ledger infos with their least 2f+1 signatures starting from
+    /// `start_version`, in ascending order of version.
     /// Note: ledger infos and signatures are only available at the last version of each earlier
     /// epoch and at the latest version of current epoch.
-    #[cfg(test)]
-    fn get_ledger_infos(&self, start_version: Version) -> Result<Vec<LedgerInfoWithSignatures>> {
+    pub fn get_ledger_infos(
+        &self,
+        start_version: Version,
+        limit: usize,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
         let mut iter = self.db.iter::<LedgerInfoSchema>(ReadOptions::default())?;
         iter.seek(&start_version)?;
-        Ok(iter.map(|kv| Ok(kv?.1)).collect::<Result<Vec<_>>>()?)
+        Ok(iter
+            .take(limit)
+            .map(|kv| Ok(kv?.1))
+            .collect::<Result<Vec<_>>>()?)
     }
 
     pub fn get_latest_ledger_info_option(&self) -> Result<Option<LedgerInfoWithSignatures>> {
@@ -131,6 +137,24 @@ impl LedgerStore {
         Ok(root_hash)
     }
 
+    /// Write `txn_info` at `version` and the frozen subtree root hashes of the transaction
+    /// accumulator with `version + 1` leaves to `batch`. This is how a ledger restored from a state
+    /// snapshot at `version` is bootstrapped, so that transactions after `version` can be appended
+    /// without any earlier transaction infos or accumulator nodes.
+    pub fn put_snapshot_transaction_info(
+        &self,
+        version: Version,
+        txn_info: &TransactionInfo,
+        frozen_subtree_hashes: &[HashValue],
+        batch: &mut SchemaBatch,
+    ) -> Result<()> {
+        batch.put::<TransactionInfoSchema>(&version, txn_info)?;
+        FrozenSubTreeIterator::new(version + 1)
+            .zip_eq(frozen_subtree_hashes.iter())
+            .map(|(pos, hash)| batch.put::<TransactionAccumulatorSchema>(&pos, hash))
+            .collect::<Result<()>>()
+    }
+
     /// Write `ledger_info` to `batch`.
     pub fn put_ledger_info(
         &self,
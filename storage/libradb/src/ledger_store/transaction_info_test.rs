@@ -2,9 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::LibraDB;
+use crate::{test_helper::for_each_backend, LibraDB};
 use proptest::{collection::vec, prelude::*};
-use tempfile::tempdir;
 use types::proof::verify_transaction_accumulator_element;
 
 fn verify(
@@ -49,21 +48,19 @@ proptest! {
         batch1 in vec(any::<TransactionInfo>(), 1..100),
         batch2 in vec(any::<TransactionInfo>(), 1..100),
     ) {
+        for_each_backend(|db| {
+            // insert two batches of transaction infos
+            let root_hash1 = save(db, 0, &batch1);
+            let ledger_version1 = batch1.len() as u64 - 1;
+            let root_hash2 = save(db, batch1.len() as u64, &batch2);
+            let ledger_version2 = batch1.len() as u64 + batch2.len() as u64 - 1;
 
-        let tmp_dir = tempdir().unwrap();
-        let db = LibraDB::new(&tmp_dir);
+            // retrieve all leaves and verify against latest root hash
+            verify(db, &batch1, 0, ledger_version2, root_hash2);
+            verify(db, &batch2, batch1.len() as u64, ledger_version2, root_hash2);
 
-        // insert two batches of transaction infos
-        let root_hash1 = save(&db, 0, &batch1);
-        let ledger_version1 = batch1.len() as u64 - 1;
-        let root_hash2 = save(&db, batch1.len() as u64, &batch2);
-        let ledger_version2 = batch1.len() as u64 + batch2.len() as u64 - 1;
-
-        // retrieve all leaves and verify against latest root hash
-        verify(&db, &batch1, 0, ledger_version2, root_hash2);
-        verify(&db, &batch2, batch1.len() as u64, ledger_version2, root_hash2);
-
-        // retrieve batch1 and verify against root_hash after batch1 was interted
-        verify(&db, &batch1, 0, ledger_version1, root_hash1);
+            // retrieve batch1 and verify against root_hash after batch1 was interted
+            verify(db, &batch1, 0, ledger_version1, root_hash1);
+        });
     }
 }
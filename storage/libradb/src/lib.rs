@@ -12,11 +12,14 @@ pub mod mock_genesis;
 // Used in this and other crates for testing.
 pub mod test_helper;
 
+pub mod db_verifier;
 pub mod errors;
 
 mod event_store;
 mod ledger_store;
+mod pruner;
 pub mod schema;
+mod schema_reader;
 mod state_store;
 mod transaction_store;
 
@@ -24,8 +27,9 @@ mod transaction_store;
 mod libradb_test;
 
 use crate::{
-    errors::LibraDbError, event_store::EventStore, ledger_store::LedgerStore, schema::*,
-    state_store::StateStore, transaction_store::TransactionStore,
+    errors::LibraDbError, event_store::EventStore, ledger_store::LedgerStore, pruner::Pruner,
+    schema::*, schema_reader::SchemaReader, state_store::StateStore,
+    transaction_store::TransactionStore,
 };
 use crypto::{
     hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH},
@@ -36,21 +40,24 @@ use itertools::{izip, zip_eq};
 use lazy_static::lazy_static;
 use logger::prelude::*;
 use metrics::OpMetrics;
-use schemadb::{ColumnFamilyOptions, ColumnFamilyOptionsMap, SchemaBatch, DB, DEFAULT_CF_NAME};
+use schemadb::{column_family_options, ColumnFamilyOptionsMap, SchemaBatch, DB};
 use std::{iter::Iterator, path::Path, sync::Arc, time::Instant};
 use storage_proto::ExecutorStartupInfo;
 use types::{
     access_path::AccessPath,
     account_address::AccountAddress,
     account_config::get_account_resource_or_default,
-    account_state_blob::{AccountStateBlob, AccountStateWithProof},
-    contract_event::EventWithProof,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof, AccountStateWithProof},
+    contract_event::{ContractEvent, EventWithProof},
     get_with_proof::{RequestItem, ResponseItem},
     ledger_info::LedgerInfoWithSignatures,
-    proof::{AccountStateProof, EventProof, SignedTransactionProof, SparseMerkleProof},
+    proof::{
+        verify_ledger_frozen_subtree_hashes, verify_transaction_info, AccountStateProof,
+        AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    },
     transaction::{
-        SignedTransactionWithProof, TransactionInfo, TransactionListWithProof, TransactionToCommit,
-        Version,
+        SignedTransaction, SignedTransactionWithProof, TransactionInfo, TransactionListWithProof,
+        TransactionToCommit, Version,
     },
     validator_change::ValidatorChangeEventWithProof,
 };
@@ -74,42 +81,35 @@ fn error_if_too_many_requested(num_requested: u64, max_allowed: u64) -> Result<(
 /// access to the core Libra data structures.
 pub struct LibraDB {
     db: Arc<DB>,
+    /// What all the reads go through: the db itself, or a snapshot of it.
+    reader: SchemaReader,
     ledger_store: LedgerStore,
     transaction_store: TransactionStore,
     state_store: StateStore,
     event_store: EventStore,
+    pruner: Option<Arc<Pruner>>,
 }
 
 impl LibraDB {
-    /// This creates an empty LibraDB instance on disk or opens one if it already exists.
+    /// This creates an empty LibraDB instance on disk or opens one if it already exists. It keeps
+    /// the whole history of account states, see
+    /// [`new_with_prune_window`](LibraDB::new_with_prune_window).
     pub fn new<P: AsRef<Path> + Clone>(db_root_path: P) -> Self {
-        let cf_opts_map: ColumnFamilyOptionsMap = [
-            (
-                /* LedgerInfo CF = */ DEFAULT_CF_NAME,
-                ColumnFamilyOptions::default(),
-            ),
-            (ACCOUNT_STATE_CF_NAME, ColumnFamilyOptions::default()),
-            (EVENT_ACCUMULATOR_CF_NAME, ColumnFamilyOptions::default()),
-            (EVENT_BY_ACCESS_PATH_CF_NAME, ColumnFamilyOptions::default()),
-            (EVENT_CF_NAME, ColumnFamilyOptions::default()),
-            (RETIRED_STATE_RECORD_CF_NAME, ColumnFamilyOptions::default()),
-            (SIGNED_TRANSACTION_CF_NAME, ColumnFamilyOptions::default()),
-            (STATE_MERKLE_NODE_CF_NAME, ColumnFamilyOptions::default()),
-            (
-                TRANSACTION_ACCUMULATOR_CF_NAME,
-                ColumnFamilyOptions::default(),
-            ),
-            (TRANSACTION_INFO_CF_NAME, ColumnFamilyOptions::default()),
-            (VALIDATOR_CF_NAME, ColumnFamilyOptions::default()),
-        ]
-        .iter()
-        .cloned()
-        .collect();
+        Self::new_with_prune_window(db_root_path, None)
+    }
 
+    /// Same as [`new`](LibraDB::new), but when `prune_window` is set only the account states of
+    /// the latest version and the `prune_window` versions before it stay readable, the older ones
+    /// are purged in the background and reads targeting them fail with [`LibraDbError::Pruned`].
+    pub fn new_with_prune_window<P: AsRef<Path> + Clone>(
+        db_root_path: P,
+        prune_window: Option<u64>,
+    ) -> Self {
         let path = db_root_path.as_ref().join("libradb");
         let instant = Instant::now();
         let db = Arc::new(
-            DB::open(path.clone(), cf_opts_map)
+            Self::cf_opts_map()
+                .and_then(|cf_opts_map| DB::open(path.clone(), cf_opts_map))
                 .unwrap_or_else(|e| panic!("LibraDB open failed: {:?}", e)),
         );
 
@@ -119,15 +119,105 @@ impl LibraDB {
             instant.elapsed().as_millis()
         );
 
+        let pruner = Self::create_pruner(&db, prune_window)
+            .unwrap_or_else(|e| panic!("Failed to create the pruner: {:?}", e));
+        let libradb = Self::new_with_db(db, pruner);
+        libradb
+            .record_event_by_type_index_start_version()
+            .unwrap_or_else(|e| panic!("Failed to record the index start version: {:?}", e));
+        // Catch up with the history committed before the restart.
+        if let Some(pruner) = &libradb.pruner {
+            if let Some((latest_version, _)) = libradb
+                .ledger_store
+                .get_latest_transaction_info_option()
+                .unwrap_or_else(|e| panic!("Failed to get the latest version: {:?}", e))
+            {
+                pruner.wake(latest_version);
+            }
+        }
+        libradb
+    }
+
+    /// Opens the LibraDB under `db_root_path` for reads only, all the writes fail. It can be
+    /// opened while a running node holds the db, and sees the data committed by the time of
+    /// opening.
+    pub fn open_readonly<P: AsRef<Path>>(db_root_path: P) -> Result<Self> {
+        let path = db_root_path.as_ref().join("libradb");
+        let db = Arc::new(DB::open_readonly(path, Self::cf_opts_map()?)?);
+        Ok(Self::new_with_db(db, None /* pruner */))
+    }
+
+    /// Creates an empty LibraDB kept in memory, which is gone once it's dropped. It keeps the
+    /// whole history of account states and is only meant for tests.
+    #[cfg(any(test, feature = "fuzzing"))]
+    pub fn new_in_memory() -> Result<Self> {
+        Self::new_in_memory_with_prune_window(None)
+    }
+
+    #[cfg(any(test, feature = "fuzzing"))]
+    fn new_in_memory_with_prune_window(prune_window: Option<u64>) -> Result<Self> {
+        let db = Arc::new(DB::open_in_memory(Self::cf_opts_map()?));
+        let pruner = Self::create_pruner(&db, prune_window)?;
+        let libradb = Self::new_with_db(db, pruner);
+        libradb.record_event_by_type_index_start_version()?;
+        Ok(libradb)
+    }
+
+    fn create_pruner(db: &Arc<DB>, prune_window: Option<u64>) -> Result<Option<Arc<Pruner>>> {
+        prune_window
+            .map(|window| Pruner::new(Arc::clone(db), window).map(Arc::new))
+            .transpose()
+    }
+
+    fn new_with_db(db: Arc<DB>, pruner: Option<Arc<Pruner>>) -> Self {
+        let reader = SchemaReader::Db(Arc::clone(&db));
+        Self::new_with_reader(db, reader, pruner)
+    }
+
+    fn new_with_reader(db: Arc<DB>, reader: SchemaReader, pruner: Option<Arc<Pruner>>) -> Self {
         LibraDB {
-            db: Arc::clone(&db),
-            event_store: EventStore::new(Arc::clone(&db)),
-            ledger_store: LedgerStore::new(Arc::clone(&db)),
-            state_store: StateStore::new(Arc::clone(&db)),
-            transaction_store: TransactionStore::new(Arc::clone(&db)),
+            db,
+            event_store: EventStore::new(reader.clone()),
+            ledger_store: LedgerStore::new(reader.clone()),
+            state_store: StateStore::new(reader.clone()),
+            transaction_store: TransactionStore::new(reader.clone()),
+            reader,
+            pruner,
         }
     }
 
+    /// Returns a view of the db as of now, through a single schemadb snapshot shared by all the
+    /// stores, so that reads spanning several stores are consistent with each other. It's only
+    /// meant for reads, which don't observe any later write, including those made through it.
+    fn snapshot(&self) -> Self {
+        Self::new_with_reader(
+            Arc::clone(&self.db),
+            SchemaReader::Snapshot(Arc::new(self.db.snapshot())),
+            self.pruner.clone(),
+        )
+    }
+
+    fn cf_opts_map() -> Result<ColumnFamilyOptionsMap> {
+        Ok(vec![
+            column_family_options::<account_state::AccountStateSchema>()?,
+            column_family_options::<db_metadata::DbMetadataSchema>()?,
+            column_family_options::<event_accumulator::EventAccumulatorSchema>()?,
+            column_family_options::<event_by_access_path::EventByAccessPathSchema>()?,
+            column_family_options::<event_by_type::EventByTypeSchema>()?,
+            column_family_options::<event::EventSchema>()?,
+            column_family_options::<ledger_info::LedgerInfoSchema>()?,
+            column_family_options::<retired_state_record::RetiredStateRecordSchema>()?,
+            column_family_options::<signed_transaction::SignedTransactionSchema>()?,
+            column_family_options::<state_merkle_node::StateMerkleNodeSchema>()?,
+            column_family_options::<transaction_accumulator::TransactionAccumulatorSchema>()?,
+            column_family_options::<transaction_by_hash::TransactionByHashSchema>()?,
+            column_family_options::<transaction_info::TransactionInfoSchema>()?,
+            column_family_options::<validator::ValidatorSchema>()?,
+        ]
+        .into_iter()
+        .collect())
+    }
+
     // ================================== Public API ==================================
     /// Returns the account state corresponding to the given version and account address with proof
     /// based on `ledger_version`
@@ -137,6 +227,7 @@ impl LibraDB {
         version: Version,
         ledger_version: Version,
     ) -> Result<AccountStateWithProof> {
+        self.error_if_pruned(version)?;
         ensure!(
             version <= ledger_version,
             "The queried version {} should be equal to or older than ledger version {}.",
@@ -217,20 +308,14 @@ impl LibraDB {
         let mut events_with_proof = event_keys
             .into_iter()
             .map(|(seq, ver, idx)| {
-                let (event, event_proof) = self
-                    .event_store
-                    .get_event_with_proof_by_version_and_index(ver, idx)?;
+                let event_with_proof = self.get_event_with_proof(ver, idx, ledger_version)?;
                 ensure!(
-                    seq == event.sequence_number(),
+                    seq == event_with_proof.event.sequence_number(),
                     "Index broken, expected seq:{}, actual:{}",
                     seq,
-                    event.sequence_number()
+                    event_with_proof.event.sequence_number()
                 );
-                let (txn_info, txn_info_proof) = self
-                    .ledger_store
-                    .get_transaction_info_with_proof(ver, ledger_version)?;
-                let proof = EventProof::new(txn_info_proof, txn_info, event_proof);
-                Ok(EventWithProof::new(ver, idx, event, proof))
+                Ok(event_with_proof)
             })
             .collect::<Result<Vec<_>>>()?;
         if !ascending {
@@ -273,7 +358,9 @@ impl LibraDB {
         // If txn with seq_num n is at some version, the corresponding account state at the
         // same version will be the first account state that has seq_num n + 1.
         let seq_num = seq_num + 1;
-        let (mut start_version, mut end_version) = (0, ledger_version);
+        // Account states older than the least readable version are gone, search the rest.
+        let least_readable_version = self.least_readable_version();
+        let (mut start_version, mut end_version) = (least_readable_version, ledger_version);
         while start_version < end_version {
             let mid_version = start_version + (end_version - start_version) / 2;
             let account_seq_num = self.get_account_seq_num_by_version(address, mid_version)?;
@@ -292,6 +379,11 @@ impl LibraDB {
             // log error
             bail!("internal error: seq_num is not continuous.")
         }
+        // The transaction is committed at or before the least readable version, the exact version
+        // can't be told without the pruned account states.
+        if start_version == least_readable_version && least_readable_version > 0 {
+            return Err(LibraDbError::Pruned(start_version - 1, least_readable_version).into());
+        }
         // start_version cannot be 0 (genesis version).
         assert_eq!(
             self.get_account_seq_num_by_version(address, start_version - 1)?,
@@ -301,6 +393,85 @@ impl LibraDB {
             .map(Some)
     }
 
+    /// Returns up to `limit` events of the types whose paths start with `event_path_prefix` under
+    /// all accounts, starting with event `start_event_index` of the transaction at `start_version`
+    /// and ending with the transaction at `end_version`, in ascending order of version and event
+    /// index, each with proof based on `ledger_version`.
+    fn get_events_by_event_type(
+        &self,
+        event_path_prefix: &[u8],
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithProof>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        let index_start_version = self.get_event_by_type_index_start_version()?;
+        if start_version < index_start_version {
+            return Err(LibraDbError::NotIndexedByType(start_version, index_start_version).into());
+        }
+
+        self.event_store
+            .lookup_events_by_type(
+                event_path_prefix,
+                start_version,
+                start_event_index,
+                std::cmp::min(end_version, ledger_version),
+                limit,
+            )?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event_with_proof = self.get_event_with_proof(ver, idx, ledger_version)?;
+                ensure!(
+                    event_with_proof
+                        .event
+                        .access_path()
+                        .path
+                        .starts_with(event_path_prefix),
+                    "Index broken, the path of event {} of txn {} doesn't start with the prefix.",
+                    idx,
+                    ver
+                );
+                Ok(event_with_proof)
+            })
+            .collect()
+    }
+
+    /// Returns event `index` of the transaction at `version` with proof based on
+    /// `ledger_version`.
+    fn get_event_with_proof(
+        &self,
+        version: Version,
+        index: u64,
+        ledger_version: Version,
+    ) -> Result<EventWithProof> {
+        let (event, event_proof) = self
+            .event_store
+            .get_event_with_proof_by_version_and_index(version, index)?;
+        let (txn_info, txn_info_proof) = self
+            .ledger_store
+            .get_transaction_info_with_proof(version, ledger_version)?;
+        let proof = EventProof::new(txn_info_proof, txn_info, event_proof);
+        Ok(EventWithProof::new(version, index, event, proof))
+    }
+
+    /// Returns the committed signed transaction with `hash`, together with its events and proof
+    /// based on `ledger_version`. Returns `None` if no such transaction is committed at or before
+    /// `ledger_version`.
+    fn get_txn_by_hash(
+        &self,
+        hash: &HashValue,
+        ledger_version: Version,
+    ) -> Result<Option<SignedTransactionWithProof>> {
+        match self.transaction_store.get_version_by_hash(hash)? {
+            Some(version) if version <= ledger_version => self
+                .get_transaction_with_proof(version, ledger_version, true /* fetch_events */)
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
     /// Gets the latest version number available in the ledger.
     fn get_latest_version(&self) -> Result<Version> {
         Ok(self
@@ -377,6 +548,7 @@ impl LibraDB {
         // Only increment counter if commit(batch) succeeds.
         OP_COUNTER.inc_by("committed_txns", txns_to_commit.len());
         OP_COUNTER.set("latest_transaction_version", last_version as usize);
+        self.wake_pruner(last_version);
         Ok(())
     }
 
@@ -457,8 +629,21 @@ impl LibraDB {
     )> {
         error_if_too_many_requested(request_items.len() as u64, MAX_REQUEST_ITEMS)?;
 
+        // The reads of several items go through one snapshot, so that the response items are
+        // consistent with the ledger info and each other even if more transactions are committed
+        // meanwhile. A commit made while a snapshot is alive copies each column family it writes
+        // to in an in-memory db though, so a single item, which is read at the version of the
+        // ledger info anyway, is read from the db directly.
+        let snapshot;
+        let db = if request_items.len() > 1 {
+            snapshot = self.snapshot();
+            &snapshot
+        } else {
+            self
+        };
+
         // Get the latest ledger info and signatures
-        let ledger_info_with_sigs = self.ledger_store.get_latest_ledger_info()?;
+        let ledger_info_with_sigs = db.ledger_store.get_latest_ledger_info()?;
         let ledger_version = ledger_info_with_sigs.ledger_info().version();
 
         // Fulfill all request items
@@ -466,7 +651,7 @@ impl LibraDB {
             .into_iter()
             .map(|request_item| match request_item {
                 RequestItem::GetAccountState { address } => Ok(ResponseItem::GetAccountState {
-                    account_state_with_proof: self.get_account_state_with_proof(
+                    account_state_with_proof: db.get_account_state_with_proof(
                         address,
                         ledger_version,
                         ledger_version,
@@ -477,7 +662,7 @@ impl LibraDB {
                     sequence_number,
                     fetch_events,
                 } => {
-                    let signed_transaction_with_proof = self.get_txn_by_account_and_seq(
+                    let signed_transaction_with_proof = db.get_txn_by_account_and_seq(
                         account,
                         sequence_number,
                         ledger_version,
@@ -486,7 +671,7 @@ impl LibraDB {
 
                     let proof_of_current_sequence_number = match signed_transaction_with_proof {
                         Some(_) => None,
-                        None => Some(self.get_account_state_with_proof(
+                        None => Some(db.get_account_state_with_proof(
                             account,
                             ledger_version,
                             ledger_version,
@@ -505,7 +690,7 @@ impl LibraDB {
                     ascending,
                     limit,
                 } => {
-                    let (events_with_proof, proof_of_latest_event) = self
+                    let (events_with_proof, proof_of_latest_event) = db
                         .get_events_by_event_access_path(
                             &access_path,
                             start_event_seq_num,
@@ -524,12 +709,42 @@ impl LibraDB {
                     fetch_events,
                 } => {
                     let txn_list_with_proof =
-                        self.get_transactions(start_version, limit, ledger_version, fetch_events)?;
+                        db.get_transactions(start_version, limit, ledger_version, fetch_events)?;
 
                     Ok(ResponseItem::GetTransactions {
                         txn_list_with_proof,
                     })
                 }
+                RequestItem::GetAccountStateByVersion { address, version } => {
+                    Ok(ResponseItem::GetAccountStateByVersion {
+                        account_state_with_proof: db.get_account_state_with_proof(
+                            address,
+                            version,
+                            ledger_version,
+                        )?,
+                    })
+                }
+                RequestItem::GetTransactionByHash { hash } => {
+                    Ok(ResponseItem::GetTransactionByHash {
+                        signed_transaction_with_proof: db.get_txn_by_hash(&hash, ledger_version)?,
+                    })
+                }
+                RequestItem::GetEventsByEventType {
+                    event_path_prefix,
+                    start_version,
+                    start_event_index,
+                    end_version,
+                    limit,
+                } => Ok(ResponseItem::GetEventsByEventType {
+                    events_with_proof: db.get_events_by_event_type(
+                        &event_path_prefix,
+                        start_version,
+                        start_event_index,
+                        end_version,
+                        limit,
+                        ledger_version,
+                    )?,
+                }),
             })
             .collect::<Result<Vec<_>>>()?;
 
@@ -641,6 +856,306 @@ impl LibraDB {
         ))
     }
 
+    /// Gets a chunk of up to `limit` account states of the state snapshot at `version`, in
+    /// ascending order of the account address hashes and right after `start_key`, with proofs
+    /// relative to the ledger at `version`.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        self.error_if_pruned(version)?;
+
+        let (transaction_info, ledger_info_to_transaction_info_proof) = self
+            .ledger_store
+            .get_transaction_info_with_proof(version, version)?;
+        let (account_states, proofs) = self.state_store.get_account_state_chunk_with_proof(
+            transaction_info.state_root_hash(),
+            start_key,
+            limit as usize,
+        )?;
+        let ledger_frozen_subtree_hashes = self
+            .ledger_store
+            .get_ledger_frozen_subtree_hashes(version)?;
+
+        Ok(AccountStateChunkWithProof {
+            version,
+            account_states,
+            proofs,
+            ledger_info_to_transaction_info_proof,
+            transaction_info,
+            ledger_frozen_subtree_hashes,
+        })
+    }
+
+    /// Writes a chunk of account states of a state snapshot at `version` being restored into the
+    /// partially restored state Merkle tree with `state_root_hash`, and returns the new root hash.
+    /// Restoring starts from [`SPARSE_MERKLE_PLACEHOLDER_HASH`] and the chunks must already be
+    /// verified by the caller.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn restore_account_state_chunk(
+        &self,
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Result<HashValue> {
+        // A snapshot is only restored into a fresh DB, holding at most the genesis transaction, so
+        // that no history older than the snapshot is left mixed with it.
+        if let Some((latest_version, _)) = self.ledger_store.get_latest_transaction_info_option()? {
+            ensure!(
+                latest_version == 0,
+                "Can't restore a state snapshot into a DB with transactions up to version {}.",
+                latest_version,
+            );
+            ensure!(
+                version > latest_version,
+                "Can't restore a state snapshot at the version of the genesis transaction.",
+            );
+        }
+
+        let mut batch = SchemaBatch::new();
+        let new_root_hash = self.state_store.put_account_state_chunk(
+            account_states,
+            version,
+            state_root_hash,
+            &mut batch,
+        )?;
+        self.commit(batch)?;
+        Ok(new_root_hash)
+    }
+
+    /// Finishes restoring the state snapshot at the version of `ledger_info_with_sigs`, after all
+    /// its account states are written by
+    /// [`restore_account_state_chunk`](LibraDB::restore_account_state_chunk). `transaction_info`
+    /// is the info of the transaction at that version, and `frozen_subtree_hashes` are the frozen
+    /// subtree root hashes of the transaction accumulator, which let later transactions be
+    /// appended.
+    ///
+    /// This is used by the State Synchronizer module internally.
+    pub fn finish_state_snapshot(
+        &self,
+        ledger_info_with_sigs: &LedgerInfoWithSignatures,
+        transaction_info: &TransactionInfo,
+        ledger_info_to_transaction_info_proof: &AccumulatorProof,
+        frozen_subtree_hashes: &[HashValue],
+    ) -> Result<()> {
+        let ledger_info = ledger_info_with_sigs.ledger_info();
+        let version = ledger_info.version();
+        verify_transaction_info(
+            ledger_info,
+            version,
+            transaction_info,
+            ledger_info_to_transaction_info_proof,
+        )?;
+        verify_ledger_frozen_subtree_hashes(ledger_info, frozen_subtree_hashes)?;
+        self.ensure_state_root_restored(version, transaction_info.state_root_hash())?;
+
+        let mut batch = SchemaBatch::new();
+        self.ledger_store.put_snapshot_transaction_info(
+            version,
+            transaction_info,
+            frozen_subtree_hashes,
+            &mut batch,
+        )?;
+        self.ledger_store
+            .put_ledger_info(ledger_info_with_sigs, &mut batch)?;
+        batch.put::<db_metadata::DbMetadataSchema>(
+            &db_metadata::DbMetadataKey::StateSnapshotVersion,
+            &version,
+        )?;
+        self.commit(batch)?;
+        OP_COUNTER.set("latest_transaction_version", version as usize);
+        self.wake_pruner(version);
+        Ok(())
+    }
+
+    // ================================== Backup APIs ==================================
+    /// Gets up to `limit` ledger infos with signatures in ascending order of version, starting
+    /// from `start_version`.
+    ///
+    /// This is used by the backup tool.
+    pub fn get_ledger_infos(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Vec<LedgerInfoWithSignatures>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        self.ledger_store
+            .get_ledger_infos(start_version, limit as usize)
+    }
+
+    /// Writes a chunk of transactions starting at `first_version` together with their infos and
+    /// events, right after the latest restored transaction, and returns the root hash of the
+    /// transaction accumulator after the chunk. The chunk must already be verified by the caller.
+    ///
+    /// The account states are not replayed: the state snapshot at the version being restored to
+    /// must be restored by [`restore_account_state_chunk`](LibraDB::restore_account_state_chunk)
+    /// in advance, and the account states of older versions are not readable.
+    ///
+    /// This is used by the restore tool.
+    pub fn restore_transaction_chunk(
+        &self,
+        first_version: Version,
+        txn_and_infos: &[(SignedTransaction, TransactionInfo)],
+        events: &[Vec<ContractEvent>],
+    ) -> Result<HashValue> {
+        ensure!(!txn_and_infos.is_empty(), "Empty transaction chunk.");
+        ensure!(
+            txn_and_infos.len() == events.len(),
+            "Got events of {} transactions for {} transactions.",
+            events.len(),
+            txn_and_infos.len(),
+        );
+        let expected_first_version = self
+            .ledger_store
+            .get_latest_transaction_info_option()?
+            .map_or(0, |(latest_version, _)| latest_version + 1);
+        ensure!(
+            first_version == expected_first_version,
+            "Transaction chunk not applicable: first_version {}, expected {}.",
+            first_version,
+            expected_first_version,
+        );
+
+        let mut batch = SchemaBatch::new();
+        for (version, (signed_txn, txn_info), events) in
+            izip!(first_version.., txn_and_infos, events)
+        {
+            ensure!(
+                signed_txn.hash() == txn_info.signed_transaction_hash(),
+                "Transaction hash mismatch at version {}.",
+                version,
+            );
+            let event_root_hash = self.event_store.put_events(version, events, &mut batch)?;
+            ensure!(
+                event_root_hash == txn_info.event_root_hash(),
+                "Event root hash mismatch at version {}.",
+                version,
+            );
+            self.transaction_store
+                .put_transaction(version, signed_txn, &mut batch)?;
+        }
+        let txn_infos = txn_and_infos
+            .iter()
+            .map(|(_, txn_info)| txn_info.clone())
+            .collect::<Vec<_>>();
+        let root_hash =
+            self.ledger_store
+                .put_transaction_infos(first_version, &txn_infos, &mut batch)?;
+        self.commit(batch)?;
+        Ok(root_hash)
+    }
+
+    /// Finishes a restore by writing `ledger_infos_with_sigs`, after the state snapshot and all the
+    /// transactions up to the version of the last ledger info are restored. Each ledger info is
+    /// checked against the restored transaction accumulator, and the last one must be at the
+    /// latest restored version.
+    ///
+    /// This is used by the restore tool.
+    pub fn finish_restore(
+        &self,
+        ledger_infos_with_sigs: &[LedgerInfoWithSignatures],
+    ) -> Result<()> {
+        let target_version = ledger_infos_with_sigs
+            .last()
+            .ok_or_else(|| format_err!("No ledger info to restore."))?
+            .ledger_info()
+            .version();
+        let (latest_version, txn_info) = self.ledger_store.get_latest_transaction_info()?;
+        ensure!(
+            latest_version == target_version,
+            "Transactions are restored up to version {}, while the ledger info is at version {}.",
+            latest_version,
+            target_version,
+        );
+        self.ensure_state_root_restored(latest_version, txn_info.state_root_hash())?;
+
+        let mut batch = SchemaBatch::new();
+        for ledger_info_with_sigs in ledger_infos_with_sigs {
+            let ledger_info = ledger_info_with_sigs.ledger_info();
+            ensure!(
+                ledger_info.version() <= latest_version,
+                "Ledger info at version {} is newer than the latest version {}.",
+                ledger_info.version(),
+                latest_version,
+            );
+            verify_ledger_frozen_subtree_hashes(
+                ledger_info,
+                &self
+                    .ledger_store
+                    .get_ledger_frozen_subtree_hashes(ledger_info.version())?,
+            )?;
+            self.ledger_store
+                .put_ledger_info(ledger_info_with_sigs, &mut batch)?;
+        }
+        batch.put::<db_metadata::DbMetadataSchema>(
+            &db_metadata::DbMetadataKey::StateSnapshotVersion,
+            &latest_version,
+        )?;
+        self.commit(batch)?;
+        OP_COUNTER.set("latest_transaction_version", latest_version as usize);
+        self.wake_pruner(latest_version);
+        Ok(())
+    }
+
+    // ================================== Export APIs ==================================
+    /// Gets up to `limit` transactions starting from `start_version`, each with its info and
+    /// events, without proofs. Only the transactions covered by the latest ledger info are
+    /// returned, so an empty result means there is no newer committed transaction yet.
+    ///
+    /// This is used by the storage service to stream transactions to downstream indexers.
+    pub fn get_transactions_with_events(
+        &self,
+        start_version: Version,
+        limit: u64,
+    ) -> Result<Vec<(SignedTransaction, TransactionInfo, Vec<ContractEvent>)>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        let ledger_version = match self.ledger_store.get_latest_ledger_info_option()? {
+            Some(ledger_info_with_sigs) => ledger_info_with_sigs.ledger_info().version(),
+            None => return Ok(vec![]),
+        };
+        if start_version > ledger_version {
+            return Ok(vec![]);
+        }
+
+        let limit = std::cmp::min(limit, ledger_version - start_version + 1);
+        (start_version..start_version + limit)
+            .map(|version| {
+                Ok((
+                    self.transaction_store.get_transaction(version)?,
+                    self.ledger_store.get_transaction_info(version)?,
+                    self.event_store.get_events_by_version(version)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// Gets up to `limit` account states of the state snapshot at `version`, in ascending order of
+    /// the account address hashes and right after `start_key`, without proofs.
+    ///
+    /// This is used by the storage service to stream account states to downstream indexers.
+    pub fn get_account_states(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+        error_if_too_many_requested(limit, MAX_LIMIT)?;
+        self.error_if_pruned(version)?;
+
+        let state_root_hash = self
+            .ledger_store
+            .get_transaction_info(version)?
+            .state_root_hash();
+        self.state_store
+            .get_account_states(state_root_hash, start_key, limit as usize)
+    }
+
     // ================================== Private APIs ==================================
     /// Write the whole schema batch including all data necessary to mutate the ledge
     /// state of some transaction by leveraging rocksdb atomicity support.
@@ -662,6 +1177,88 @@ impl LibraDB {
         Ok(())
     }
 
+    /// Returns the least version whose account states are readable, which is 0 unless pruning is
+    /// enabled.
+    fn least_readable_version(&self) -> Version {
+        self.pruner
+            .as_ref()
+            .map_or(0, |pruner| pruner.least_readable_version())
+    }
+
+    /// Returns the version of the state snapshot this db is restored from, if any. The account
+    /// states of earlier versions were never written.
+    pub(crate) fn get_state_snapshot_version(&self) -> Result<Option<Version>> {
+        self.reader
+            .get::<db_metadata::DbMetadataSchema>(&db_metadata::DbMetadataKey::StateSnapshotVersion)
+    }
+
+    /// Returns the first version whose events are indexed by type. Until it's recorded, the index
+    /// only starts with the next version committed.
+    fn get_event_by_type_index_start_version(&self) -> Result<Version> {
+        match self.reader.get::<db_metadata::DbMetadataSchema>(
+            &db_metadata::DbMetadataKey::EventByTypeIndexStartVersion,
+        )? {
+            Some(version) => Ok(version),
+            None => Ok(self
+                .ledger_store
+                .get_latest_transaction_info_option()?
+                .map_or(0, |(latest_version, _)| latest_version + 1)),
+        }
+    }
+
+    /// Records the first version whose events are indexed by type once the db is opened for
+    /// writes, so that queries of the events committed before the index was introduced fail
+    /// instead of silently missing them.
+    fn record_event_by_type_index_start_version(&self) -> Result<()> {
+        let key = db_metadata::DbMetadataKey::EventByTypeIndexStartVersion;
+        if self
+            .db
+            .get::<db_metadata::DbMetadataSchema>(&key)?
+            .is_none()
+        {
+            let mut batch = SchemaBatch::new();
+            batch.put::<db_metadata::DbMetadataSchema>(
+                &key,
+                &self.get_event_by_type_index_start_version()?,
+            )?;
+            self.commit(batch)?;
+        }
+        Ok(())
+    }
+
+    fn error_if_pruned(&self, version: Version) -> Result<()> {
+        let least_readable_version = self.least_readable_version();
+        if version < least_readable_version {
+            Err(LibraDbError::Pruned(version, least_readable_version).into())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn ensure_state_root_restored(
+        &self,
+        version: Version,
+        state_root_hash: HashValue,
+    ) -> Result<()> {
+        ensure!(
+            state_root_hash == *SPARSE_MERKLE_PLACEHOLDER_HASH
+                || self
+                    .db
+                    .get::<state_merkle_node::StateMerkleNodeSchema>(&state_root_hash)?
+                    .is_some(),
+            "State root {:x} at version {} is not restored.",
+            state_root_hash,
+            version,
+        );
+        Ok(())
+    }
+
+    fn wake_pruner(&self, latest_version: Version) {
+        if let Some(pruner) = &self.pruner {
+            pruner.wake(latest_version);
+        }
+    }
+
     fn get_account_seq_num_by_version(
         &self,
         address: AccountAddress,
@@ -3,10 +3,13 @@
 
 use super::*;
 use crate::{
-    mock_genesis::{db_with_mock_genesis, GENESIS_INFO},
-    test_helper::arb_blocks_to_commit,
+    mock_genesis::{
+        db_with_mock_genesis, in_memory_db_with_mock_genesis, save_mock_genesis, GENESIS_INFO,
+    },
+    test_helper::{arb_blocks_to_commit, for_each_backend},
 };
 use crypto::hash::CryptoHash;
+use itertools::izip;
 use proptest::prelude::*;
 use rusty_fork::{rusty_fork_id, rusty_fork_test, rusty_fork_test_name};
 use std::collections::HashMap;
@@ -14,13 +17,13 @@ use types::{contract_event::ContractEvent, ledger_info::LedgerInfo};
 
 fn test_save_blocks_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    db: &LibraDB,
 ) -> Result<()> {
-    let tmp_dir = tempfile::tempdir()?;
-    let db = db_with_mock_genesis(&tmp_dir)?;
-
     let num_batches = input.len();
     let mut cur_ver = 0;
     for (batch_idx, (txns_to_commit, ledger_info_with_sigs)) in input.iter().enumerate() {
+        let snapshot = db.snapshot();
+        let prev_ledger_info_with_sigs = db.ledger_store.get_latest_ledger_info()?;
         db.save_transactions(
             &txns_to_commit,
             cur_ver + 1, /* first_version */
@@ -31,8 +34,13 @@ fn test_save_blocks_impl(
             db.ledger_store.get_latest_ledger_info()?,
             *ledger_info_with_sigs
         );
+        // A snapshot taken before the commit doesn't see it.
+        assert_eq!(
+            snapshot.ledger_store.get_latest_ledger_info()?,
+            prev_ledger_info_with_sigs
+        );
         verify_committed_transactions(
-            &db,
+            db,
             &txns_to_commit,
             cur_ver,
             ledger_info_with_sigs,
@@ -47,7 +55,7 @@ fn test_save_blocks_impl(
     let latest_ledger_info = input.last().unwrap().1.clone();
     // Verify an old batch with the latest LedgerInfo.
     verify_committed_transactions(
-        &db,
+        db,
         &first_batch,
         0,
         &latest_ledger_info,
@@ -55,7 +63,7 @@ fn test_save_blocks_impl(
     )?;
     // Verify an old batch with an old LedgerInfo.
     verify_committed_transactions(
-        &db,
+        db,
         &first_batch,
         0,
         &first_batch_ledger_info,
@@ -67,10 +75,8 @@ fn test_save_blocks_impl(
 
 fn test_sync_transactions_impl(
     input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    db: &LibraDB,
 ) -> Result<()> {
-    let tmp_dir = tempfile::tempdir()?;
-    let db = db_with_mock_genesis(&tmp_dir)?;
-
     let num_batches = input.len();
     let mut cur_ver = 0;
     for (batch_idx, (txns_to_commit, ledger_info_with_sigs)) in input.into_iter().enumerate() {
@@ -90,7 +96,7 @@ fn test_sync_transactions_impl(
         )?;
 
         verify_committed_transactions(
-            &db,
+            db,
             &txns_to_commit,
             cur_ver,
             &ledger_info_with_sigs,
@@ -102,6 +108,201 @@ fn test_sync_transactions_impl(
     Ok(())
 }
 
+fn test_restore_state_snapshot_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    db: &LibraDB,
+    restore_db: &LibraDB,
+) -> Result<()> {
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+    let ledger_info_with_sigs = db.ledger_store.get_latest_ledger_info()?;
+    let version = ledger_info_with_sigs.ledger_info().version();
+    if version == 0 {
+        // Nothing is committed after the genesis transaction, there is no snapshot to restore.
+        return Ok(());
+    }
+
+    // A snapshot can't be restored into a DB with transactions.
+    assert!(db
+        .restore_account_state_chunk(version + 1, vec![], *SPARSE_MERKLE_PLACEHOLDER_HASH)
+        .is_err());
+
+    // Copy the latest state snapshot chunk by chunk to a fresh DB.
+    let mut start_key = None;
+    let mut state_root_hash = *SPARSE_MERKLE_PLACEHOLDER_HASH;
+    let chunk = loop {
+        let chunk =
+            db.get_account_state_chunk_with_proof(version, start_key, 3 /* limit */)?;
+        chunk.verify(ledger_info_with_sigs.ledger_info(), start_key)?;
+        start_key = chunk.account_states.last().map(|(key, _)| *key);
+        state_root_hash = restore_db.restore_account_state_chunk(
+            version,
+            chunk.account_states.clone(),
+            state_root_hash,
+        )?;
+        if chunk.account_states.len() < 3 {
+            break chunk;
+        }
+    };
+    assert_eq!(state_root_hash, chunk.transaction_info.state_root_hash());
+
+    // Can't finish with a wrong transaction info.
+    let bad_txn_info = TransactionInfo::new(
+        chunk.transaction_info.signed_transaction_hash(),
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        chunk.transaction_info.event_root_hash(),
+        chunk.transaction_info.gas_used(),
+    );
+    assert!(restore_db
+        .finish_state_snapshot(
+            &ledger_info_with_sigs,
+            &bad_txn_info,
+            &chunk.ledger_info_to_transaction_info_proof,
+            &chunk.ledger_frozen_subtree_hashes,
+        )
+        .is_err());
+
+    restore_db.finish_state_snapshot(
+        &ledger_info_with_sigs,
+        &chunk.transaction_info,
+        &chunk.ledger_info_to_transaction_info_proof,
+        &chunk.ledger_frozen_subtree_hashes,
+    )?;
+    assert_eq!(
+        restore_db.get_executor_startup_info()?,
+        db.get_executor_startup_info()?
+    );
+
+    // Nor can another snapshot be restored on top of the restored one.
+    assert!(restore_db
+        .restore_account_state_chunk(version + 1, vec![], *SPARSE_MERKLE_PLACEHOLDER_HASH)
+        .is_err());
+
+    Ok(())
+}
+
+fn test_get_account_state_by_version_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    db: &LibraDB,
+) -> Result<()> {
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    let mut version = 0;
+    for txn_to_commit in input.iter().flat_map(|(txns_to_commit, _)| txns_to_commit) {
+        version += 1;
+        for (address, expected_blob) in txn_to_commit.account_states() {
+            let (mut response_items, ledger_info_with_sigs, _) = db.update_to_latest_ledger(
+                0, /* client_known_version */
+                vec![RequestItem::GetAccountStateByVersion {
+                    address: *address,
+                    version,
+                }],
+            )?;
+            let account_state_with_proof = response_items
+                .remove(0)
+                .into_get_account_state_by_version_response()?;
+            assert_eq!(account_state_with_proof.blob, Some(expected_blob.clone()));
+            account_state_with_proof.verify(
+                ledger_info_with_sigs.ledger_info(),
+                version,
+                *address,
+            )?;
+        }
+    }
+
+    // Versions newer than the latest ledger info can't be queried.
+    assert!(db
+        .update_to_latest_ledger(
+            0, /* client_known_version */
+            vec![RequestItem::GetAccountStateByVersion {
+                address: AccountAddress::random(),
+                version: cur_ver + 1,
+            }],
+        )
+        .is_err());
+
+    Ok(())
+}
+
+fn test_export_impl(
+    input: Vec<(Vec<TransactionToCommit>, LedgerInfoWithSignatures)>,
+    db: &LibraDB,
+) -> Result<()> {
+    let mut cur_ver = 0;
+    for (txns_to_commit, ledger_info_with_sigs) in &input {
+        db.save_transactions(
+            &txns_to_commit,
+            cur_ver + 1, /* first_version */
+            &Some(ledger_info_with_sigs.clone()),
+        )?;
+        cur_ver += txns_to_commit.len() as u64;
+    }
+
+    // Transactions from version 1 on, in batches of 3.
+    let mut txns = vec![];
+    loop {
+        let batch = db.get_transactions_with_events(txns.len() as u64 + 1, 3 /* limit */)?;
+        if batch.is_empty() {
+            break;
+        }
+        txns.extend(batch);
+    }
+    let txns_to_commit = input
+        .iter()
+        .flat_map(|(txns_to_commit, _)| txns_to_commit)
+        .collect::<Vec<_>>();
+    assert_eq!(txns.len(), txns_to_commit.len());
+    for (version, (signed_txn, txn_info, events), txn_to_commit) in izip!(1.., txns, txns_to_commit)
+    {
+        assert_eq!(&signed_txn, txn_to_commit.signed_txn());
+        assert_eq!(txn_info, db.ledger_store.get_transaction_info(version)?);
+        assert_eq!(events, txn_to_commit.events());
+    }
+
+    // All the account states of the latest version, in chunks of 3.
+    let mut account_states = vec![];
+    loop {
+        let start_key = account_states.last().map(|(key, _)| *key);
+        let chunk = db.get_account_states(cur_ver, start_key, 3 /* limit */)?;
+        if chunk.is_empty() {
+            break;
+        }
+        account_states.extend(chunk);
+    }
+    let expected = db
+        .get_account_state_chunk_with_proof(cur_ver, None /* start_key */, MAX_LIMIT)?
+        .account_states;
+    assert_eq!(account_states, expected);
+
+    Ok(())
+}
+
+/// Runs `test` against a LibraDB with mock genesis on RocksDB, and then against one in memory.
+fn for_each_backend_with_mock_genesis<F>(test: F)
+where
+    F: Fn(&LibraDB) -> Result<()>,
+{
+    for_each_backend(|db| {
+        save_mock_genesis(db).unwrap();
+        test(db).unwrap();
+    })
+}
+
 fn get_events_by_access_path(
     db: &LibraDB,
     ledger_info: &LedgerInfo,
@@ -285,6 +486,45 @@ fn verify_committed_transactions(
             db.get_transactions(cur_ver, 1, ledger_version, true /* fetch_events */)?;
         txn_list_with_proof.verify(ledger_info, Some(cur_ver))?;
 
+        // Look up the transaction by hash.
+        let signed_txn = txn_to_commit.signed_txn();
+        let signed_txn_with_proof = db
+            .get_txn_by_hash(&signed_txn.hash(), ledger_version)?
+            .expect("Committed transaction should be found by hash.");
+        assert_eq!(signed_txn_with_proof.signed_transaction, *signed_txn);
+        assert_eq!(
+            signed_txn_with_proof.events.as_ref().map(Vec::as_slice),
+            Some(txn_to_commit.events())
+        );
+        signed_txn_with_proof.verify(
+            ledger_info,
+            cur_ver,
+            signed_txn.sender(),
+            signed_txn.sequence_number(),
+        )?;
+
+        // Look up each event by its type, starting right at it.
+        for (idx, event) in txn_to_commit.events().iter().enumerate() {
+            let events_with_proof = db.get_events_by_event_type(
+                &event.access_path().path,
+                cur_ver,
+                idx as u64,
+                cur_ver, /* end_version */
+                1,       /* limit */
+                ledger_version,
+            )?;
+            assert_eq!(events_with_proof.len(), 1);
+            let event_with_proof = &events_with_proof[0];
+            assert_eq!(event_with_proof.event, *event);
+            event_with_proof.verify(
+                ledger_info,
+                event.access_path(),
+                event.sequence_number(),
+                cur_ver,
+                idx as u64,
+            )?;
+        }
+
         // Fetch and verify account states.
         for (addr, expected_blob) in txn_to_commit.account_states() {
             let account_state_with_proof =
@@ -314,12 +554,42 @@ proptest! {
 
     #[test]
     fn test_save_blocks(input in arb_blocks_to_commit()) {
-        test_save_blocks_impl(input).unwrap();
+        for_each_backend_with_mock_genesis(|db| test_save_blocks_impl(input.clone(), db));
     }
 
     #[test]
     fn test_sync_transactions(input in arb_blocks_to_commit()) {
-        test_sync_transactions_impl(input).unwrap();
+        for_each_backend_with_mock_genesis(|db| test_sync_transactions_impl(input.clone(), db));
+    }
+
+    #[test]
+    fn test_restore_state_snapshot(input in arb_blocks_to_commit()) {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let restore_tmp_dir = tempfile::tempdir().unwrap();
+        test_restore_state_snapshot_impl(
+            input.clone(),
+            &db_with_mock_genesis(&tmp_dir).unwrap(),
+            &db_with_mock_genesis(&restore_tmp_dir).unwrap(),
+        )
+        .unwrap();
+        test_restore_state_snapshot_impl(
+            input,
+            &in_memory_db_with_mock_genesis().unwrap(),
+            &in_memory_db_with_mock_genesis().unwrap(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_get_account_state_by_version(input in arb_blocks_to_commit()) {
+        for_each_backend_with_mock_genesis(|db| {
+            test_get_account_state_by_version_impl(input.clone(), db)
+        });
+    }
+
+    #[test]
+    fn test_export(input in arb_blocks_to_commit()) {
+        for_each_backend_with_mock_genesis(|db| test_export_impl(input.clone(), db));
     }
 }
 
@@ -384,6 +654,56 @@ fn test_bootstrapping_already_bootstrapped_db() {
     );
 }
 
+#[test]
+fn test_events_by_type_before_index() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    {
+        let db = db_with_mock_genesis(&tmp_dir).unwrap();
+        assert_eq!(db.get_event_by_type_index_start_version().unwrap(), 0);
+        // As if the genesis transaction was committed before the events were indexed by type.
+        let mut batch = SchemaBatch::new();
+        batch
+            .delete::<db_metadata::DbMetadataSchema>(
+                &db_metadata::DbMetadataKey::EventByTypeIndexStartVersion,
+            )
+            .unwrap();
+        db.commit(batch).unwrap();
+    }
+
+    let db = LibraDB::new(&tmp_dir);
+    assert_eq!(db.get_event_by_type_index_start_version().unwrap(), 1);
+    match db
+        .get_events_by_event_type(
+            &[],
+            0,
+            0,
+            0,
+            1, /* limit */
+            0, /* ledger_version */
+        )
+        .unwrap_err()
+        .downcast::<LibraDbError>()
+        .unwrap()
+    {
+        LibraDbError::NotIndexedByType(version, index_start_version) => {
+            assert_eq!(version, 0);
+            assert_eq!(index_start_version, 1);
+        }
+        e => panic!("Unexpected error: {:?}", e),
+    }
+    assert!(db
+        .get_events_by_event_type(
+            &[],
+            1,
+            0,
+            1,
+            1, /* limit */
+            0  /* ledger_version */
+        )
+        .unwrap()
+        .is_empty());
+}
+
 #[test]
 fn test_get_first_seq_num_and_limit() {
     assert!(get_first_seq_num_and_limit(true, 0, 0).is_err());
@@ -418,6 +738,9 @@ fn test_too_many_requested() {
         )
         .is_err());
     assert!(db.get_transactions(0, 1001 /* limit */, 0, true).is_err());
+    assert!(db
+        .get_transactions_with_events(0, 1001 /* limit */)
+        .is_err());
     assert!(db
         .get_events_by_event_access_path(
             &AccessPath::new_for_sent_event(AccountAddress::random()),
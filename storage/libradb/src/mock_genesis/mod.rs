@@ -99,14 +99,27 @@ lazy_static! {
 /// The resulting db will have only one transaction at version 0 (the mock genesis transaction) and
 /// related outputs (the mock genesis state) in it.
 pub fn db_with_mock_genesis<P: AsRef<std::path::Path>>(dir: &P) -> Result<LibraDB> {
+    let db = LibraDB::new(dir);
+    save_mock_genesis(&db)?;
+    Ok(db)
+}
+
+/// Same as [`db_with_mock_genesis`], but the db is kept in memory.
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn in_memory_db_with_mock_genesis() -> Result<LibraDB> {
+    let db = LibraDB::new_in_memory()?;
+    save_mock_genesis(&db)?;
+    Ok(db)
+}
+
+/// Initializes the empty `db` with mock genesis info, see [`db_with_mock_genesis`].
+pub fn save_mock_genesis(db: &LibraDB) -> Result<()> {
     let genesis_ledger_info_with_sigs = GENESIS_INFO.1.clone();
     let genesis_txn = GENESIS_INFO.2.clone();
 
-    let db = LibraDB::new(dir);
     db.save_transactions(
         &[genesis_txn],
         0, /* first_version */
         &Some(genesis_ledger_info_with_sigs),
-    )?;
-    Ok(db)
+    )
 }
@@ -0,0 +1,188 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides [`Pruner`] which purges the account states that retired before the
+//! history window kept by [`LibraDB`](crate::LibraDB), in a background thread. The least readable
+//! version is persisted along with the purges, so that the purged versions stay unreadable after
+//! a restart, even with a larger window.
+
+#[cfg(test)]
+mod pruner_test;
+
+use crate::{
+    schema::db_metadata::{DbMetadataKey, DbMetadataSchema},
+    schema_reader::SchemaReader,
+    state_store::StateStore,
+    OP_COUNTER,
+};
+use failure::prelude::*;
+use logger::prelude::*;
+use schemadb::{SchemaBatch, DB};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{channel, Receiver, Sender, TryRecvError},
+        Arc, Mutex,
+    },
+    thread::{Builder, JoinHandle},
+};
+use types::transaction::Version;
+
+/// Max number of retired records purged in one DB commit, so that a large backlog, e.g. when the
+/// pruner is turned on for a node with a long history, never makes a giant batch.
+const MAX_PURGE_BATCH_SIZE: usize = 10_000;
+
+/// The pruner keeps the account states of the latest version and the `prune_window` versions
+/// before it readable and purges the older ones in a background thread, woken up after every
+/// commit.
+pub(crate) struct Pruner {
+    /// Number of versions before the latest one whose account states stay readable.
+    prune_window: u64,
+    /// The least version whose account states are readable. Reads targeting older versions are
+    /// rejected even before the pruner worker catches up.
+    least_readable_version: AtomicU64,
+    /// Sends commands to the worker thread.
+    command_sender: Mutex<Sender<Command>>,
+    /// The worker thread handle, joined upon drop.
+    worker_thread: Option<JoinHandle<()>>,
+}
+
+enum Command {
+    Quit,
+    Prune { least_readable_version: Version },
+}
+
+impl Pruner {
+    /// Creates a pruner over `db`, resuming from the least readable version persisted by the
+    /// previous pruner if any, and spawns its worker thread.
+    pub fn new(db: Arc<DB>, prune_window: u64) -> Result<Self> {
+        let least_readable_version = db
+            .get::<DbMetadataSchema>(&DbMetadataKey::LeastReadableStateVersion)?
+            .unwrap_or(0);
+        let (command_sender, command_receiver) = channel();
+        let worker = Worker {
+            db: Arc::clone(&db),
+            state_store: StateStore::new(SchemaReader::Db(db)),
+            command_receiver,
+        };
+        let worker_thread = Builder::new()
+            .name("libradb_pruner".into())
+            .spawn(move || worker.work_loop())
+            .expect("Creating pruner thread should succeed.");
+
+        Ok(Self {
+            prune_window,
+            least_readable_version: AtomicU64::new(least_readable_version),
+            command_sender: Mutex::new(command_sender),
+            worker_thread: Some(worker_thread),
+        })
+    }
+
+    /// Returns the least version whose account states are readable.
+    pub fn least_readable_version(&self) -> Version {
+        self.least_readable_version.load(Ordering::Relaxed)
+    }
+
+    /// Advances the history window to end at `latest_version` and wakes up the worker to purge
+    /// the account states that fall out of it.
+    pub fn wake(&self, latest_version: Version) {
+        let least_readable_version = latest_version.saturating_sub(self.prune_window);
+        if least_readable_version <= self.least_readable_version() {
+            return;
+        }
+        self.least_readable_version
+            .store(least_readable_version, Ordering::Relaxed);
+        OP_COUNTER.set(
+            "pruner.least_readable_version",
+            least_readable_version as usize,
+        );
+        self.command_sender
+            .lock()
+            .expect("command_sender to pruner thread should lock.")
+            .send(Command::Prune {
+                least_readable_version,
+            })
+            .expect("Receiver should not destruct prematurely.");
+    }
+}
+
+impl Drop for Pruner {
+    fn drop(&mut self) {
+        self.command_sender
+            .lock()
+            .expect("Locking command_sender should not fail.")
+            .send(Command::Quit)
+            .expect("Receiver should not destruct.");
+        self.worker_thread
+            .take()
+            .expect("Worker thread must exist.")
+            .join()
+            .expect("Worker thread should join peacefully.");
+    }
+}
+
+struct Worker {
+    db: Arc<DB>,
+    state_store: StateStore,
+    command_receiver: Receiver<Command>,
+}
+
+impl Worker {
+    fn work_loop(self) {
+        // The version the worker is purging up to, None when it has caught up.
+        let mut target = None;
+        loop {
+            // Block for a command only when there is nothing left to purge, otherwise pick up the
+            // commands between batches.
+            let command = match target {
+                None => match self.command_receiver.recv() {
+                    Ok(command) => Some(command),
+                    Err(_) => break,
+                },
+                Some(_) => match self.command_receiver.try_recv() {
+                    Ok(command) => Some(command),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => break,
+                },
+            };
+            match command {
+                Some(Command::Quit) => break,
+                Some(Command::Prune {
+                    least_readable_version,
+                }) => target = Some(least_readable_version),
+                None => (),
+            }
+
+            if let Some(least_readable_version) = target {
+                match self.purge_batch(least_readable_version) {
+                    Ok(num_purged) if num_purged == MAX_PURGE_BATCH_SIZE => (),
+                    Ok(_) => target = None,
+                    Err(e) => {
+                        error!(
+                            "Failed to purge retired records up to version {}: {:?}",
+                            least_readable_version, e
+                        );
+                        target = None;
+                    }
+                }
+            }
+        }
+    }
+
+    fn purge_batch(&self, least_readable_version: Version) -> Result<usize> {
+        let mut batch = SchemaBatch::new();
+        let num_purged = self.state_store.purge_retired_records(
+            least_readable_version,
+            MAX_PURGE_BATCH_SIZE,
+            &mut batch,
+        )?;
+        // Persisted with the purge, so that the purged versions are known after a restart.
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::LeastReadableStateVersion,
+            &least_readable_version,
+        )?;
+        self.db.write_schemas(batch)?;
+        OP_COUNTER.inc_by("pruner.purged_records", num_purged);
+        Ok(num_purged)
+    }
+}
@@ -0,0 +1,177 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{
+    errors::LibraDbError,
+    mock_genesis::GENESIS_INFO,
+    test_helper::{for_each_backend, for_each_backend_with_prune_window},
+    LibraDB,
+};
+use crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
+use std::{
+    collections::HashMap,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+use types::{
+    account_address::{AccountAddress, ADDRESS_LENGTH},
+    account_state_blob::AccountStateBlob,
+};
+
+fn put_account_state_set(
+    db: &LibraDB,
+    account_state_set: Vec<(AccountAddress, AccountStateBlob)>,
+    version: Version,
+    root_hash: HashValue,
+) -> HashValue {
+    let mut batch = SchemaBatch::new();
+    let root = db
+        .state_store
+        .put_account_state_sets(
+            vec![account_state_set.into_iter().collect::<HashMap<_, _>>()],
+            version,
+            root_hash,
+            &mut batch,
+        )
+        .unwrap()[0];
+    db.commit(batch).unwrap();
+
+    root
+}
+
+fn is_readable(db: &LibraDB, address: AccountAddress, root: HashValue) -> bool {
+    db.state_store
+        .get_account_state_with_proof_by_state_root(address, root)
+        .is_ok()
+}
+
+/// Waits for the pruner worker to purge the state with `root`.
+fn wait_until_purged(db: &LibraDB, address: AccountAddress, root: HashValue) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while is_readable(db, address, root) {
+        assert!(Instant::now() < deadline, "Pruner didn't purge in time.");
+        sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_pruner() {
+    for_each_backend(test_pruner_impl);
+}
+
+fn test_pruner_impl(db: &LibraDB) {
+    let address = AccountAddress::new([1u8; ADDRESS_LENGTH]);
+    let value0 = AccountStateBlob::from(vec![0x01]);
+    let value1 = AccountStateBlob::from(vec![0x02]);
+    let value2 = AccountStateBlob::from(vec![0x03]);
+
+    let root0 = put_account_state_set(
+        db,
+        vec![(address, value0)],
+        0, /* version */
+        *SPARSE_MERKLE_PLACEHOLDER_HASH,
+    );
+    let root1 = put_account_state_set(db, vec![(address, value1)], 1 /* version */, root0);
+    let root2 = put_account_state_set(db, vec![(address, value2)], 2 /* version */, root1);
+
+    let pruner = Pruner::new(Arc::clone(&db.db), 1 /* prune_window */).unwrap();
+    // Nothing falls out of the window.
+    pruner.wake(1 /* latest_version */);
+    assert_eq!(pruner.least_readable_version(), 0);
+
+    pruner.wake(2 /* latest_version */);
+    assert_eq!(pruner.least_readable_version(), 1);
+    wait_until_purged(db, address, root0);
+    assert!(is_readable(db, address, root1));
+    assert!(is_readable(db, address, root2));
+
+    // The window never moves backward.
+    pruner.wake(1 /* latest_version */);
+    assert_eq!(pruner.least_readable_version(), 1);
+
+    pruner.wake(3 /* latest_version */);
+    wait_until_purged(db, address, root1);
+    assert!(is_readable(db, address, root2));
+}
+
+#[test]
+fn test_read_pruned_version() {
+    for_each_backend_with_prune_window(Some(1), test_read_pruned_version_impl);
+}
+
+fn test_read_pruned_version_impl(db: &LibraDB) {
+    let genesis_ledger_info_with_sigs = GENESIS_INFO.1.clone();
+    let genesis_txn = GENESIS_INFO.2.clone();
+    db.save_transactions(
+        &[genesis_txn.clone()],
+        0, /* first_version */
+        &Some(genesis_ledger_info_with_sigs),
+    )
+    .unwrap();
+    assert_eq!(db.least_readable_version(), 0);
+    db.save_transactions(
+        &[genesis_txn.clone(), genesis_txn],
+        1,     /* first_version */
+        &None, /* ledger_info_with_sigs */
+    )
+    .unwrap();
+    assert_eq!(db.least_readable_version(), 1);
+
+    let address = GENESIS_INFO.2.signed_txn().sender();
+    match db
+        .get_account_state_with_proof(address, 0 /* version */, 0 /* ledger_version */)
+        .unwrap_err()
+        .downcast::<LibraDbError>()
+        .unwrap()
+    {
+        LibraDbError::Pruned(version, least_readable_version) => {
+            assert_eq!(version, 0);
+            assert_eq!(least_readable_version, 1);
+        }
+        e => panic!("Unexpected error: {:?}", e),
+    }
+    assert!(db
+        .get_account_state_chunk_with_proof(0 /* version */, None, 1 /* limit */)
+        .is_err());
+}
+
+#[test]
+fn test_least_readable_version_survives_restart() {
+    let tmp_dir = tempfile::tempdir().unwrap();
+    let genesis_txn = GENESIS_INFO.2.clone();
+    {
+        let db = LibraDB::new_with_prune_window(&tmp_dir, Some(1));
+        db.save_transactions(
+            &[genesis_txn.clone()],
+            0, /* first_version */
+            &Some(GENESIS_INFO.1.clone()),
+        )
+        .unwrap();
+        db.save_transactions(
+            &[genesis_txn.clone(), genesis_txn.clone()],
+            1,     /* first_version */
+            &None, /* ledger_info_with_sigs */
+        )
+        .unwrap();
+        assert_eq!(db.least_readable_version(), 1);
+        // Dropping the db joins the pruner worker once it has handled the pending commands.
+    }
+
+    // A larger window does not make the versions purged before the restart readable again.
+    let db = LibraDB::new_with_prune_window(&tmp_dir, Some(10));
+    assert_eq!(db.least_readable_version(), 1);
+    let address = genesis_txn.signed_txn().sender();
+    match db
+        .get_account_state_with_proof(address, 0 /* version */, 2 /* ledger_version */)
+        .unwrap_err()
+        .downcast::<LibraDbError>()
+        .unwrap()
+    {
+        LibraDbError::Pruned(version, least_readable_version) => {
+            assert_eq!(version, 0);
+            assert_eq!(least_readable_version, 1);
+        }
+        e => panic!("Unexpected error: {:?}", e),
+    }
+}
@@ -14,7 +14,7 @@ use crypto::HashValue;
 use failure::prelude::*;
 use schemadb::{
     define_schema,
-    schema::{KeyCodec, ValueCodec},
+    schema::{ColumnFamilyConfig, KeyCodec, ValueCodec},
 };
 use types::account_state_blob::AccountStateBlob;
 
@@ -22,7 +22,11 @@ define_schema!(
     AccountStateSchema,
     HashValue,
     AccountStateBlob,
-    ACCOUNT_STATE_CF_NAME
+    ACCOUNT_STATE_CF_NAME,
+    ColumnFamilyConfig {
+        bloom_filter_bits_per_key: Some(10),
+        ..ColumnFamilyConfig::DEFAULT
+    }
 );
 
 impl KeyCodec<AccountStateSchema> for HashValue {
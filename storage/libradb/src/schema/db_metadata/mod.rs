@@ -0,0 +1,78 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for facts about the db itself, which are written
+//! by the operations changing the shape of the stored history, e.g. restoring a state snapshot or
+//! pruning, or when the db is opened for the first time since an index was introduced.
+//!
+//! ```text
+//! |<-----key---->|<--value-->|
+//! | metadata_key |  version  |
+//! ```
+//!
+//! `metadata_key` is a single byte and `Version` is serialized in big endian, the same as in other
+//! schemas.
+
+use crate::schema::{ensure_slice_len_eq, DB_METADATA_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt};
+use failure::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+use types::transaction::Version;
+
+define_schema!(
+    DbMetadataSchema,
+    DbMetadataKey,
+    Version,
+    DB_METADATA_CF_NAME
+);
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum DbMetadataKey {
+    /// The version of the state snapshot the db is restored from. The account states of earlier
+    /// versions were never written.
+    StateSnapshotVersion,
+    /// The first version whose events are indexed by type. The events of earlier versions were
+    /// committed before the index was introduced.
+    EventByTypeIndexStartVersion,
+    /// The least version whose account states are readable. The pruner may have purged the
+    /// account states of earlier versions.
+    LeastReadableStateVersion,
+}
+
+impl KeyCodec<DbMetadataSchema> for DbMetadataKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        Ok(vec![match self {
+            DbMetadataKey::StateSnapshotVersion => 0,
+            DbMetadataKey::EventByTypeIndexStartVersion => 1,
+            DbMetadataKey::LeastReadableStateVersion => 2,
+        }])
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 1)?;
+        match data[0] {
+            0 => Ok(DbMetadataKey::StateSnapshotVersion),
+            1 => Ok(DbMetadataKey::EventByTypeIndexStartVersion),
+            2 => Ok(DbMetadataKey::LeastReadableStateVersion),
+            other => bail!("Unknown db metadata key {}.", other),
+        }
+    }
+}
+
+impl ValueCodec<DbMetadataSchema> for Version {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, size_of::<Version>())?;
+        Ok((&data[..]).read_u64::<BigEndian>()?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,20 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::prelude::*;
+use schemadb::schema::assert_encode_decode;
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        key in prop_oneof![
+            Just(DbMetadataKey::StateSnapshotVersion),
+            Just(DbMetadataKey::EventByTypeIndexStartVersion),
+            Just(DbMetadataKey::LeastReadableStateVersion),
+        ],
+        version in any::<Version>(),
+    ) {
+        assert_encode_decode::<DbMetadataSchema>(&key, &version);
+    }
+}
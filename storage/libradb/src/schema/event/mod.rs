@@ -4,7 +4,8 @@
 //! This module defines physical storage schema for the contract events.
 //!
 //! An event is keyed by the version of the transaction it belongs to and the index of it among all
-//! events yielded by the same transaction.
+//! events yielded by the same transaction. The version is the fixed key prefix, so that the events
+//! of a transaction are read with a prefix iterator.
 //! ```text
 //! |<-------key----->|<---value--->|
 //! | version | index | event bytes |
@@ -16,12 +17,22 @@ use failure::prelude::*;
 use proto_conv::{FromProtoBytes, IntoProtoBytes};
 use schemadb::{
     define_schema,
-    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+    schema::{ColumnFamilyConfig, KeyCodec, SeekKeyCodec, ValueCodec},
 };
 use std::mem::size_of;
 use types::{contract_event::ContractEvent, transaction::Version};
 
-define_schema!(EventSchema, Key, ContractEvent, EVENT_CF_NAME);
+define_schema!(
+    EventSchema,
+    Key,
+    ContractEvent,
+    EVENT_CF_NAME,
+    ColumnFamilyConfig {
+        bloom_filter_bits_per_key: Some(10),
+        fixed_prefix_len: Some(size_of::<Version>()),
+        ..ColumnFamilyConfig::DEFAULT
+    }
+);
 
 type Index = u64;
 type Key = (Version, Index);
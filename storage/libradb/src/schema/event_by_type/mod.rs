@@ -0,0 +1,133 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which the events of a type
+//! can be found in the order of the transactions that emitted them, whichever accounts they are
+//! emitted under. The type of an event is the path of its access path, i.e. the access path
+//! without the account address, like that of all the received payment events. A ContractEvent is
+//! represented by a <txn_version, event_idx> tuple so that it can be fetched from `EventSchema`.
+//!
+//! ```text
+//! |<-------------------key------------------->|<-value->|
+//! | escaped path | terminator | txn_ver | idx  |  empty  |
+//! ```
+//!
+//! Each zero byte of the path is followed by `0xff` and the path ends with two zero bytes, so that
+//! the keys of a path sort before those of any longer path starting with it, and the escaped
+//! prefix of a path, e.g. the tag and hash of the resource holding the event handle, is a prefix
+//! of the keys of all the paths starting with it, see [`PathPrefix`]. Numbers are serialized in
+//! big endian, the same as in other schemas.
+
+use crate::schema::{ensure_slice_len_eq, EVENT_BY_TYPE_CF_NAME};
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use failure::prelude::*;
+use schemadb::{
+    define_schema,
+    schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+};
+use std::mem::size_of;
+use types::transaction::Version;
+
+define_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Path = Vec<u8>;
+type Index = u64;
+type Key = (Path, Version, Index);
+
+const ESCAPE: u8 = 0xff;
+const TERMINATOR: [u8; 2] = [0, 0];
+
+/// Seeks to the first key of the paths starting with the wrapped prefix.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PathPrefix(pub Path);
+
+/// Seeks past all the keys of the wrapped path, to the first key of the next path.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct PathEnd(pub Path);
+
+fn encode_path(path: &[u8], encoded: &mut Vec<u8>) {
+    for &byte in path {
+        encoded.push(byte);
+        if byte == 0 {
+            encoded.push(ESCAPE);
+        }
+    }
+}
+
+/// Returns the path at the start of `data` and the length of its encoding including the
+/// terminator.
+fn decode_path(data: &[u8]) -> Result<(Path, usize)> {
+    let mut path = Vec::new();
+    let mut bytes = data.iter().enumerate();
+    while let Some((_, &byte)) = bytes.next() {
+        if byte != 0 {
+            path.push(byte);
+            continue;
+        }
+        match bytes.next() {
+            Some((_, &ESCAPE)) => path.push(0),
+            Some((pos, &0)) => return Ok((path, pos + 1)),
+            Some((pos, other)) => bail!("Unexpected byte {} after zero at {}.", other, pos),
+            None => break,
+        }
+    }
+    bail!("Path is not terminated.")
+}
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref path, version, index) = *self;
+
+        let mut encoded = Vec::with_capacity(
+            path.len() + TERMINATOR.len() + size_of::<Version>() + size_of::<Index>(),
+        );
+        encode_path(path, &mut encoded);
+        encoded.extend_from_slice(&TERMINATOR);
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        let (path, path_len) = decode_path(data)?;
+        let mut reader = &data[path_len..];
+        ensure_slice_len_eq(reader, size_of::<Version>() + size_of::<Index>())?;
+        let version = reader.read_u64::<BigEndian>()?;
+        let index = reader.read_u64::<BigEndian>()?;
+
+        Ok((path, version, index))
+    }
+}
+
+impl SeekKeyCodec<EventByTypeSchema> for PathPrefix {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = Vec::with_capacity(self.0.len());
+        encode_path(&self.0, &mut encoded);
+        Ok(encoded)
+    }
+}
+
+impl SeekKeyCodec<EventByTypeSchema> for PathEnd {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        // Greater than the terminator, and less than the escaped or non-zero byte any longer path
+        // has in its place.
+        let mut encoded = Vec::with_capacity(self.0.len() + TERMINATOR.len());
+        encode_path(&self.0, &mut encoded);
+        encoded.extend_from_slice(&[0, 1]);
+        Ok(encoded)
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(vec![])
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,55 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use proptest::{collection::vec, prelude::*};
+use schemadb::schema::assert_encode_decode;
+
+fn encode(key: &Key) -> Vec<u8> {
+    <Key as KeyCodec<EventByTypeSchema>>::encode_key(key).unwrap()
+}
+
+/// Paths full of zero bytes, to exercise the escaping.
+fn arb_path() -> impl Strategy<Value = Path> {
+    vec(
+        prop_oneof![Just(0u8), Just(1u8), Just(ESCAPE), any::<u8>()],
+        0..10,
+    )
+}
+
+proptest! {
+    #[test]
+    fn test_encode_decode(
+        path in arb_path(),
+        version in any::<Version>(),
+        index in any::<u64>(),
+    ) {
+        assert_encode_decode::<EventByTypeSchema>(&(path, version, index), &());
+    }
+
+    #[test]
+    fn test_encoding_preserves_order(
+        key1 in (arb_path(), any::<Version>(), any::<u64>()),
+        key2 in (arb_path(), any::<Version>(), any::<u64>()),
+    ) {
+        prop_assert_eq!(encode(&key1).cmp(&encode(&key2)), key1.cmp(&key2));
+    }
+
+    #[test]
+    fn test_path_prefix(
+        prefix in arb_path(),
+        key in (arb_path(), any::<Version>(), any::<u64>()),
+    ) {
+        let seek_key = PathPrefix(prefix.clone()).encode_seek_key().unwrap();
+        prop_assert_eq!(encode(&key).starts_with(&seek_key), key.0.starts_with(&prefix));
+    }
+
+    #[test]
+    fn test_path_end(
+        path in arb_path(),
+        key in (arb_path(), any::<Version>(), any::<u64>()),
+    ) {
+        let seek_key = PathEnd(path.clone()).encode_seek_key().unwrap();
+        prop_assert_eq!(encode(&key) < seek_key, key.0 <= path);
+    }
+}
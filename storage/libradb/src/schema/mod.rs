@@ -7,14 +7,17 @@
 //! All schemas are `pub(crate)` so not shown in rustdoc, refer to the source code to see details.
 
 pub(crate) mod account_state;
+pub(crate) mod db_metadata;
 pub(crate) mod event;
 pub(crate) mod event_accumulator;
 pub(crate) mod event_by_access_path;
+pub(crate) mod event_by_type;
 pub(crate) mod ledger_info;
 pub(crate) mod retired_state_record;
 pub(crate) mod signed_transaction;
 pub(crate) mod state_merkle_node;
 pub(crate) mod transaction_accumulator;
+pub(crate) mod transaction_by_hash;
 pub(crate) mod transaction_info;
 pub(crate) mod validator;
 
@@ -22,13 +25,16 @@ use failure::prelude::*;
 use schemadb::ColumnFamilyName;
 
 pub(super) const ACCOUNT_STATE_CF_NAME: ColumnFamilyName = "account_state";
+pub(super) const DB_METADATA_CF_NAME: ColumnFamilyName = "db_metadata";
 pub(super) const EVENT_ACCUMULATOR_CF_NAME: ColumnFamilyName = "event_accumulator";
 pub(super) const EVENT_BY_ACCESS_PATH_CF_NAME: ColumnFamilyName = "event_by_access_path";
+pub(super) const EVENT_BY_TYPE_CF_NAME: ColumnFamilyName = "event_by_type";
 pub(super) const EVENT_CF_NAME: ColumnFamilyName = "event";
 pub(super) const RETIRED_STATE_RECORD_CF_NAME: ColumnFamilyName = "retired_state_record";
 pub(super) const SIGNED_TRANSACTION_CF_NAME: ColumnFamilyName = "signed_transaction";
 pub(super) const STATE_MERKLE_NODE_CF_NAME: ColumnFamilyName = "state_merkle_node";
 pub(super) const TRANSACTION_ACCUMULATOR_CF_NAME: ColumnFamilyName = "transaction_accumulator";
+pub(super) const TRANSACTION_BY_HASH_CF_NAME: ColumnFamilyName = "transaction_by_hash";
 pub(super) const TRANSACTION_INFO_CF_NAME: ColumnFamilyName = "transaction_info";
 pub(super) const VALIDATOR_CF_NAME: ColumnFamilyName = "validator";
 
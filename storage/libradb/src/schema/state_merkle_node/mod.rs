@@ -13,7 +13,7 @@ use crypto::HashValue;
 use failure::prelude::*;
 use schemadb::{
     define_schema,
-    schema::{KeyCodec, ValueCodec},
+    schema::{ColumnFamilyConfig, KeyCodec, ValueCodec},
 };
 use sparse_merkle::node_type::Node;
 
@@ -21,7 +21,11 @@ define_schema!(
     StateMerkleNodeSchema,
     HashValue,
     Node,
-    STATE_MERKLE_NODE_CF_NAME
+    STATE_MERKLE_NODE_CF_NAME,
+    ColumnFamilyConfig {
+        bloom_filter_bits_per_key: Some(10),
+        ..ColumnFamilyConfig::DEFAULT
+    }
 );
 
 impl KeyCodec<StateMerkleNodeSchema> for HashValue {
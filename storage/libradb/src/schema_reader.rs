@@ -0,0 +1,51 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines [`SchemaReader`], what the stores of a [`LibraDB`](crate::LibraDB) read
+//! from. The stores never write: the updates they prepare are put into a
+//! [`SchemaBatch`](schemadb::SchemaBatch) that the [`LibraDB`](crate::LibraDB) writes to its
+//! [`DB`].
+
+use failure::prelude::*;
+use schemadb::{
+    schema::{Schema, SeekKeyCodec},
+    ReadOptions, SchemaIterator, Snapshot, DB,
+};
+use std::sync::Arc;
+
+/// Either the db itself, reading the latest data, or a read-only snapshot of it.
+#[derive(Clone)]
+pub(crate) enum SchemaReader {
+    Db(Arc<DB>),
+    Snapshot(Arc<Snapshot>),
+}
+
+impl SchemaReader {
+    /// Reads single record by key.
+    pub fn get<S: Schema>(&self, schema_key: &S::Key) -> Result<Option<S::Value>> {
+        match self {
+            SchemaReader::Db(db) => db.get::<S>(schema_key),
+            SchemaReader::Snapshot(snapshot) => snapshot.get::<S>(schema_key),
+        }
+    }
+
+    /// Returns a [`SchemaIterator`] on a certain schema.
+    pub fn iter<S: Schema>(&self, opts: ReadOptions) -> Result<SchemaIterator<S>> {
+        match self {
+            SchemaReader::Db(db) => db.iter::<S>(opts),
+            SchemaReader::Snapshot(snapshot) => snapshot.iter::<S>(opts),
+        }
+    }
+
+    /// Returns a [`SchemaIterator`] on a certain schema positioned at `prefix`.
+    pub fn prefix_iter<S, SK>(&self, prefix: &SK) -> Result<SchemaIterator<S>>
+    where
+        S: Schema,
+        SK: SeekKeyCodec<S>,
+    {
+        match self {
+            SchemaReader::Db(db) => db.prefix_iter::<S, SK>(prefix),
+            SchemaReader::Snapshot(snapshot) => snapshot.prefix_iter::<S, SK>(prefix),
+        }
+    }
+}
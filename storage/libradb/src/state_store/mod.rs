@@ -6,15 +6,21 @@
 #[cfg(test)]
 mod state_store_test;
 
-use crate::schema::{
-    account_state::AccountStateSchema, retired_state_record::RetiredStateRecordSchema,
-    state_merkle_node::StateMerkleNodeSchema,
+use crate::{
+    schema::{
+        account_state::AccountStateSchema, retired_state_record::RetiredStateRecordSchema,
+        state_merkle_node::StateMerkleNodeSchema,
+    },
+    schema_reader::SchemaReader,
 };
 use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
-use schemadb::{ReadOptions, SchemaBatch, DB};
-use sparse_merkle::{node_type::Node, RetiredRecordType, SparseMerkleTree, TreeReader};
-use std::{collections::HashMap, sync::Arc};
+use schemadb::{ReadOptions, SchemaBatch};
+use sparse_merkle::{
+    node_type::Node, RetiredRecordType, RetiredStateRecord, SparseMerkleTree, TreeReader,
+    TreeUpdateBatch,
+};
+use std::collections::HashMap;
 use types::{
     account_address::AccountAddress,
     account_state_blob::AccountStateBlob,
@@ -23,11 +29,11 @@ use types::{
 };
 
 pub(crate) struct StateStore {
-    db: Arc<DB>,
+    db: SchemaReader,
 }
 
 impl StateStore {
-    pub fn new(db: Arc<DB>) -> Self {
+    pub fn new(db: SchemaReader) -> Self {
         Self { db }
     }
 
@@ -67,6 +73,58 @@ impl StateStore {
 
         let (new_root_hash_vec, tree_update_batch) =
             SparseMerkleTree::new(self).put_blob_sets(blob_sets, first_version, root_hash)?;
+        Self::put_tree_update_batch(tree_update_batch, batch)?;
+        Ok(new_root_hash_vec)
+    }
+
+    /// Get up to `limit` account states of the state Merkle tree with `root_hash` in ascending
+    /// order of the account address hashes, starting right after `start_key`, each with its proof.
+    pub fn get_account_state_chunk_with_proof(
+        &self,
+        root_hash: HashValue,
+        start_key: Option<HashValue>,
+        limit: usize,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, Vec<SparseMerkleProof>)> {
+        let tree = SparseMerkleTree::new(self);
+        let account_states = tree.get_account_states(root_hash, start_key, limit)?;
+        let proofs = account_states
+            .iter()
+            .map(|(key, _blob)| Ok(tree.get_with_proof(*key, root_hash)?.1))
+            .collect::<Result<Vec<_>>>()?;
+        Ok((account_states, proofs))
+    }
+
+    /// Get up to `limit` account states of the state Merkle tree with `root_hash` in ascending
+    /// order of the account address hashes, starting right after `start_key`.
+    pub fn get_account_states(
+        &self,
+        root_hash: HashValue,
+        start_key: Option<HashValue>,
+        limit: usize,
+    ) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+        SparseMerkleTree::new(self).get_account_states(root_hash, start_key, limit)
+    }
+
+    /// Put the results of inserting `account_states`, a chunk of a state snapshot being restored
+    /// at `version`, into the partially restored tree with `root_hash` to `batch` and return the
+    /// new root hash.
+    pub fn put_account_state_chunk(
+        &self,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        version: Version,
+        root_hash: HashValue,
+        batch: &mut SchemaBatch,
+    ) -> Result<HashValue> {
+        let (new_root_hash, tree_update_batch) =
+            SparseMerkleTree::new(self).put_blob_set(account_states, version, root_hash)?;
+        Self::put_tree_update_batch(tree_update_batch, batch)?;
+        Ok(new_root_hash)
+    }
+
+    fn put_tree_update_batch(
+        tree_update_batch: TreeUpdateBatch,
+        batch: &mut SchemaBatch,
+    ) -> Result<()> {
         let (node_batch, blob_batch, retired_record_batch) = tree_update_batch.into();
         node_batch
             .iter()
@@ -80,31 +138,32 @@ impl StateStore {
             .iter()
             .map(|row| batch.put::<RetiredStateRecordSchema>(row, &()))
             .collect::<Result<Vec<()>>>()?;
-        Ok(new_root_hash_vec)
+        Ok(())
     }
 
     /// Purges retired account state blobs and sparse Merkle tree nodes. Yields up to `limit`
     /// deletions to `batch` while keeps account states readable at `least readable version` and
     /// beyond.
-    #[allow(dead_code)] // TODO: remove
     pub fn purge_retired_records(
         &self,
         least_readable_version: Version,
         limit: usize,
         batch: &mut SchemaBatch,
     ) -> Result<usize> {
-        let mut num_purged = 0;
-
         let mut iter = self
             .db
             .iter::<RetiredStateRecordSchema>(ReadOptions::default())?;
         iter.seek_to_first();
-        let mut iter = iter.take(limit);
 
+        let mut num_purged = 0;
+        let mut first_purged = None;
+        // The first record kept, if any, which ends the range of purged records.
+        let mut first_kept = None;
         while let Some((record, _)) = iter.next().transpose()? {
             // Only records that have retired before or at version `least_readable_version` can be
             // pruned in order to keep that version still readable after pruning.
-            if record.version_retired > least_readable_version {
+            if num_purged == limit || record.version_retired > least_readable_version {
+                first_kept = Some(record);
                 break;
             }
             match record.record_type {
@@ -112,10 +171,22 @@ impl StateStore {
                     batch.delete::<StateMerkleNodeSchema>(&record.hash)?;
                 }
             }
-            batch.delete::<RetiredStateRecordSchema>(&record)?;
+            first_purged.get_or_insert(record);
             num_purged += 1;
         }
 
+        // The retired records themselves are contiguous, so they are deleted with a single range
+        // deletion instead of a tombstone each.
+        if let Some(first_purged) = first_purged {
+            let end = first_kept.unwrap_or(RetiredStateRecord {
+                version_retired: least_readable_version + 1,
+                record_type: RetiredRecordType::Node,
+                version_created: 0,
+                hash: HashValue::zero(),
+            });
+            batch.delete_range::<RetiredStateRecordSchema>(&first_purged, &end)?;
+        }
+
         Ok(num_purged)
     }
 }
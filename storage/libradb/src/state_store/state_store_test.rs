@@ -2,9 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::*;
-use crate::LibraDB;
+use crate::{test_helper::for_each_backend, LibraDB};
 use crypto::hash::{CryptoHash, SPARSE_MERKLE_PLACEHOLDER_HASH};
-use tempfile::tempdir;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_state_blob::AccountStateBlob,
@@ -62,8 +61,10 @@ fn verify_state_in_store(
 
 #[test]
 fn test_empty_store() {
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
+    for_each_backend(test_empty_store_impl);
+}
+
+fn test_empty_store_impl(db: &LibraDB) {
     let store = &db.state_store;
     let address = AccountAddress::new([1u8; ADDRESS_LENGTH]);
     let root = *SPARSE_MERKLE_PLACEHOLDER_HASH;
@@ -76,8 +77,10 @@ fn test_empty_store() {
 
 #[test]
 fn test_state_store_reader_writer() {
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
+    for_each_backend(test_state_store_reader_writer_impl);
+}
+
+fn test_state_store_reader_writer_impl(db: &LibraDB) {
     let store = &db.state_store;
     let address1 = AccountAddress::new([1u8; ADDRESS_LENGTH]);
     let address2 = AccountAddress::new([2u8; ADDRESS_LENGTH]);
@@ -95,7 +98,7 @@ fn test_state_store_reader_writer() {
 
     // Insert address1 with value 1 and verify new states.
     root = put_account_state_set(
-        &db,
+        db,
         vec![(address1, value1.clone())],
         0, /* version */
         root,
@@ -107,7 +110,7 @@ fn test_state_store_reader_writer() {
     // Insert address 1 with updated value1, address2 with value 2 and address3 with value3 and
     // verify new states.
     root = put_account_state_set(
-        &db,
+        db,
         vec![
             (address1, value1_update.clone()),
             (address2, value2.clone()),
@@ -123,6 +126,10 @@ fn test_state_store_reader_writer() {
 
 #[test]
 fn test_purge_retired_records() {
+    for_each_backend(test_purge_retired_records_impl);
+}
+
+fn test_purge_retired_records_impl(db: &LibraDB) {
     let address1 = AccountAddress::new([1u8; ADDRESS_LENGTH]);
     let address2 = AccountAddress::new([2u8; ADDRESS_LENGTH]);
     let address3 = AccountAddress::new([3u8; ADDRESS_LENGTH]);
@@ -133,8 +140,6 @@ fn test_purge_retired_records() {
     let value3_update = AccountStateBlob::from(vec![0x13]);
     let root_default = *SPARSE_MERKLE_PLACEHOLDER_HASH;
 
-    let tmp_dir = tempdir().unwrap();
-    let db = LibraDB::new(&tmp_dir);
     let store = &db.state_store;
 
     // Update.
@@ -145,13 +150,13 @@ fn test_purge_retired_records() {
     // | address3 |        | value3        | value3_update |
     // ```
     let root0 = put_account_state_set(
-        &db,
+        db,
         vec![(address1, value1.clone()), (address2, value2.clone())],
         0, /* version */
         root_default,
     );
     let root1 = put_account_state_set(
-        &db,
+        db,
         vec![
             (address2, value2_update.clone()),
             (address3, value3.clone()),
@@ -160,7 +165,7 @@ fn test_purge_retired_records() {
         root0,
     );
     let root2 = put_account_state_set(
-        &db,
+        db,
         vec![(address3, value3_update.clone())],
         2, /* version */
         root1,
@@ -170,7 +175,7 @@ fn test_purge_retired_records() {
     // Purge with limit=0, nothing is gone.
     {
         purge_retired_records(
-            &db, 1, /* least_readable_version */
+            db, 1, /* least_readable_version */
             0, /* limit */
             0, /* expected_num_purged */
         );
@@ -179,7 +184,7 @@ fn test_purge_retired_records() {
     // Purge till version=1.
     {
         purge_retired_records(
-            &db, 1,   /* least_readable_version */
+            db, 1,   /* least_readable_version */
             100, /* limit */
             2,   /* expected_num_purged */
         );
@@ -195,7 +200,7 @@ fn test_purge_retired_records() {
     // Purge till version=2.
     {
         purge_retired_records(
-            &db, 2,   /* least_readable_version */
+            db, 2,   /* least_readable_version */
             100, /* limit */
             2,   /* expected_num_purged */
         );
@@ -82,6 +82,29 @@ fn to_blocks_to_commit(
     Ok(blocks_to_commit)
 }
 
+/// Runs `test` against an empty LibraDB on RocksDB, and then against an empty one kept in memory,
+/// so that the stores are tested on both backends.
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn for_each_backend<F>(test: F)
+where
+    F: Fn(&LibraDB),
+{
+    for_each_backend_with_prune_window(None, test)
+}
+
+/// Same as [`for_each_backend`], but only the account states of the latest version and the
+/// `prune_window` versions before it stay readable, see
+/// [`LibraDB::new_with_prune_window`].
+#[cfg(any(test, feature = "fuzzing"))]
+pub fn for_each_backend_with_prune_window<F>(prune_window: Option<u64>, test: F)
+where
+    F: Fn(&LibraDB),
+{
+    let tmp_dir = tempfile::tempdir().unwrap();
+    test(&LibraDB::new_with_prune_window(&tmp_dir, prune_window));
+    test(&LibraDB::new_in_memory_with_prune_window(prune_window).unwrap());
+}
+
 /// This returns a [`proptest`](https://altsysrq.github.io/proptest-book/intro.html)
 /// [`Strategy`](https://docs.rs/proptest/0/proptest/strategy/trait.Strategy.html) that yields an
 /// arbitrary number of arbitrary batches of transactions to commit.
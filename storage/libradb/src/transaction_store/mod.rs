@@ -3,19 +3,19 @@
 
 //! This file defines transaction store APIs that are related to committed signed transactions.
 
-use super::schema::signed_transaction::*;
-use crate::errors::LibraDbError;
+use super::schema::{signed_transaction::*, transaction_by_hash::*};
+use crate::{errors::LibraDbError, schema_reader::SchemaReader};
+use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
-use schemadb::{SchemaBatch, DB};
-use std::sync::Arc;
+use schemadb::SchemaBatch;
 use types::transaction::{SignedTransaction, Version};
 
 pub(crate) struct TransactionStore {
-    db: Arc<DB>,
+    db: SchemaReader,
 }
 
 impl TransactionStore {
-    pub fn new(db: Arc<DB>) -> Self {
+    pub fn new(db: SchemaReader) -> Self {
         Self { db }
     }
 
@@ -26,13 +26,19 @@ impl TransactionStore {
             .ok_or_else(|| LibraDbError::NotFound(format!("Txn {}", version)).into())
     }
 
-    /// Save signed transaction at `version`
+    /// Get the version of the signed transaction with `hash` if it is committed
+    pub fn get_version_by_hash(&self, hash: &HashValue) -> Result<Option<Version>> {
+        self.db.get::<TransactionByHashSchema>(hash)
+    }
+
+    /// Save signed transaction at `version`, indexed by its hash
     pub fn put_transaction(
         &self,
         version: Version,
         signed_transaction: &SignedTransaction,
         batch: &mut SchemaBatch,
     ) -> Result<()> {
+        batch.put::<TransactionByHashSchema>(&signed_transaction.hash(), &version)?;
         batch.put::<SignedTransactionSchema>(&version, signed_transaction)
     }
 }
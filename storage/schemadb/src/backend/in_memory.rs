@@ -0,0 +1,267 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the backend that keeps everything in memory.
+
+use super::{Backend, BackendReader, RawIterator};
+use crate::{ColumnFamilyName, ReadOptions, SchemaBatch, WriteOp, DEFAULT_CF_NAME};
+use failure::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound,
+    sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+type ColumnFamily = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// The backend keeping each column family in a [`BTreeMap`]. Iterators and snapshots share the
+/// maps with the backend, which copies a map on write only while it's shared, so like those of
+/// RocksDB they see the data as of the time they are created.
+///
+/// Such a copy is of the whole column family, made by the first write to it while any iterator or
+/// snapshot is alive, so the backend is meant for tests and small data sets.
+#[derive(Debug)]
+pub(crate) struct InMemoryBackend {
+    cfs: RwLock<ColumnFamilies>,
+}
+
+impl InMemoryBackend {
+    /// Creates an empty backend with the `default` column family and those in `cf_names`.
+    pub fn new<I>(cf_names: I) -> Self
+    where
+        I: IntoIterator<Item = ColumnFamilyName>,
+    {
+        let cfs = std::iter::once(DEFAULT_CF_NAME)
+            .chain(cf_names)
+            .map(|cf_name| (cf_name, Arc::new(ColumnFamily::new())))
+            .collect();
+        Self {
+            cfs: RwLock::new(ColumnFamilies(cfs)),
+        }
+    }
+
+    fn read(&self) -> RwLockReadGuard<ColumnFamilies> {
+        self.cfs.read().expect("Failed to acquire read lock.")
+    }
+
+    fn write(&self) -> RwLockWriteGuard<ColumnFamilies> {
+        self.cfs.write().expect("Failed to acquire write lock.")
+    }
+}
+
+impl BackendReader for InMemoryBackend {
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        self.read().get(cf_name, key)
+    }
+
+    fn iter<'a>(&'a self, cf_name: &str, _opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(
+            self.read().new_iter(cf_name, None /* prefix_len */)?,
+        ))
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        cf_name: &str,
+        prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(self.read().new_iter(cf_name, Some(prefix_len))?))
+    }
+}
+
+impl Backend for InMemoryBackend {
+    fn put(&self, cf_name: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.write()
+            .get_cf_mut(cf_name)?
+            .insert(key.to_vec(), value.to_vec());
+        Ok(())
+    }
+
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<()> {
+        let mut cfs = self.write();
+        // Check all the column families before touching any, so that a batch is either applied as
+        // a whole or not at all.
+        for (cf_name, _, _) in batch.rows() {
+            cfs.get_cf(cf_name)?;
+        }
+
+        for (cf_name, key, write_op) in batch.rows() {
+            let cf = cfs.get_cf_mut(cf_name)?;
+            match write_op {
+                WriteOp::Value(value) => {
+                    cf.insert(key.to_vec(), value.clone());
+                }
+                WriteOp::Deletion => {
+                    cf.remove(key);
+                }
+                WriteOp::RangeDeletion { end_key } => {
+                    if key < end_key.as_slice() {
+                        let keys = cf
+                            .range::<[u8], _>((
+                                Bound::Included(key),
+                                Bound::Excluded(end_key.as_slice()),
+                            ))
+                            .map(|(key, _)| key.clone())
+                            .collect::<Vec<_>>();
+                        for key in keys {
+                            cf.remove(&key);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Box<dyn BackendReader + Send + Sync> {
+        Box::new(self.read().clone())
+    }
+
+    fn get_approximate_sizes_cf(&self) -> Result<BTreeMap<String, u64>> {
+        Ok(self.read().sizes())
+    }
+
+    fn flush_all(&self, _sync: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// All the column families of an [`InMemoryBackend`] at some point, which is also what its
+/// snapshots are.
+#[derive(Clone, Debug)]
+struct ColumnFamilies(HashMap<ColumnFamilyName, Arc<ColumnFamily>>);
+
+impl ColumnFamilies {
+    fn get_cf(&self, cf_name: &str) -> Result<&Arc<ColumnFamily>> {
+        self.0
+            .get(cf_name)
+            .ok_or_else(|| format_err!("Column family not found: {}", cf_name))
+    }
+
+    /// Returns the column family to update, copying it first if an iterator or a snapshot is
+    /// still reading it. [`Arc::make_mut`] clones the entire map in that case, which costs time
+    /// and memory linear in the size of the column family.
+    fn get_cf_mut(&mut self, cf_name: &str) -> Result<&mut ColumnFamily> {
+        self.0
+            .get_mut(cf_name)
+            .map(Arc::make_mut)
+            .ok_or_else(|| format_err!("Column family not found: {}", cf_name))
+    }
+
+    /// Returns the size of each column family in bytes.
+    fn sizes(&self) -> BTreeMap<String, u64> {
+        self.0
+            .iter()
+            .map(|(cf_name, cf)| {
+                let size = cf
+                    .iter()
+                    .map(|(key, value)| (key.len() + value.len()) as u64)
+                    .sum();
+                (cf_name.to_string(), size)
+            })
+            .collect()
+    }
+
+    /// Creates an iterator over column family `cf_name` that holds on to the data as of now.
+    fn new_iter(&self, cf_name: &str, prefix_len: Option<usize>) -> Result<InMemoryIterator> {
+        Ok(InMemoryIterator::new(
+            Arc::clone(self.get_cf(cf_name)?),
+            prefix_len,
+        ))
+    }
+}
+
+impl BackendReader for ColumnFamilies {
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self.get_cf(cf_name)?.get(key).cloned())
+    }
+
+    fn iter<'a>(&'a self, cf_name: &str, _opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(self.new_iter(cf_name, None /* prefix_len */)?))
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        cf_name: &str,
+        prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        Ok(Box::new(self.new_iter(cf_name, Some(prefix_len))?))
+    }
+}
+
+struct InMemoryIterator {
+    cf: Arc<ColumnFamily>,
+    /// The key the iterator is positioned at, if any.
+    current_key: Option<Vec<u8>>,
+    prefix_len: Option<usize>,
+    /// The prefix of the key last seeked to, which the iterator doesn't go beyond. Only set if
+    /// `prefix_len` is.
+    prefix: Option<Vec<u8>>,
+}
+
+impl InMemoryIterator {
+    fn new(cf: Arc<ColumnFamily>, prefix_len: Option<usize>) -> Self {
+        Self {
+            cf,
+            current_key: None,
+            prefix_len,
+            prefix: None,
+        }
+    }
+
+    /// Moves to the first key within `range`, or the last one if `rev` is true. The iterator ends
+    /// up unpositioned if there is no such key or it doesn't share the prefix.
+    fn position_at(&mut self, range: (Bound<&[u8]>, Bound<&[u8]>), rev: bool) -> bool {
+        let mut keys = self.cf.range::<[u8], _>(range).map(|(key, _)| key);
+        let key = if rev { keys.next_back() } else { keys.next() };
+        self.current_key = match &self.prefix {
+            Some(prefix) => key.filter(|key| key.starts_with(prefix)).cloned(),
+            None => key.cloned(),
+        };
+        self.current_key.is_some()
+    }
+
+    fn set_prefix(&mut self, key: &[u8]) {
+        if let Some(len) = self.prefix_len {
+            self.prefix = Some(key[..std::cmp::min(len, key.len())].to_vec());
+        }
+    }
+}
+
+impl RawIterator for InMemoryIterator {
+    fn seek_to_first(&mut self) -> bool {
+        self.prefix = None;
+        self.position_at((Bound::Unbounded, Bound::Unbounded), false /* rev */)
+    }
+
+    fn seek_to_last(&mut self) -> bool {
+        self.prefix = None;
+        self.position_at((Bound::Unbounded, Bound::Unbounded), true /* rev */)
+    }
+
+    fn seek(&mut self, key: &[u8]) -> bool {
+        self.set_prefix(key);
+        self.position_at(
+            (Bound::Included(key), Bound::Unbounded),
+            false, /* rev */
+        )
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) -> bool {
+        self.set_prefix(key);
+        self.position_at(
+            (Bound::Unbounded, Bound::Included(key)),
+            true, /* rev */
+        )
+    }
+
+    fn next_kv(&mut self) -> Option<(Vec<u8>, Vec<u8>)> {
+        let key = self.current_key.take()?;
+        let value = self.cf[&key].clone();
+        self.position_at(
+            (Bound::Excluded(key.as_slice()), Bound::Unbounded),
+            false, /* rev */
+        );
+        Some((key, value))
+    }
+}
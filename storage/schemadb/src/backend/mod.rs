@@ -0,0 +1,73 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines the key-value stores a [`DB`](crate::DB) can run on. A backend deals with
+//! raw bytes kept in named column families, each ordered by the binary representation of its
+//! keys, and knows nothing about schemas.
+//!
+//! [`RocksdbBackend`] persists the data on disk. [`InMemoryBackend`] keeps it in ordered maps,
+//! which is handy for tests that don't need the data to outlive the process. Other stores can be
+//! plugged in by implementing [`Backend`] and opening the DB with
+//! [`DB::open_with_backend`](crate::DB::open_with_backend).
+
+mod in_memory;
+mod rocks;
+
+pub(crate) use in_memory::InMemoryBackend;
+pub(crate) use rocks::RocksdbBackend;
+
+use crate::{ReadOptions, SchemaBatch};
+use failure::prelude::*;
+use std::{collections::BTreeMap, fmt::Debug};
+
+/// Read access to a backend.
+pub trait BackendReader {
+    /// Reads the value of `key` in column family `cf_name`.
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>>;
+
+    /// Returns an unpositioned iterator over all the keys of column family `cf_name`.
+    fn iter<'a>(&'a self, cf_name: &str, opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>>;
+
+    /// Returns an unpositioned iterator over column family `cf_name` that, once seeked to a key,
+    /// only yields the keys sharing its first `prefix_len` bytes.
+    fn prefix_iter<'a>(
+        &'a self,
+        cf_name: &str,
+        prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>>;
+}
+
+/// A key-value store with column families that a [`DB`](crate::DB) is backed by.
+pub trait Backend: BackendReader + Debug + Send + Sync {
+    /// Writes `value` to `key` in column family `cf_name`.
+    fn put(&self, cf_name: &str, key: &[u8], value: &[u8]) -> Result<()>;
+
+    /// Applies all the updates in `batch` atomically, in order.
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<()>;
+
+    /// Takes a consistent point-in-time view of all the column families, which can only be read.
+    fn snapshot(&self) -> Box<dyn BackendReader + Send + Sync>;
+
+    /// Returns the approximate size of each column family in bytes.
+    fn get_approximate_sizes_cf(&self) -> Result<BTreeMap<String, u64>>;
+
+    /// Flushes all the data buffered in memory, waiting until it's done if `sync` is true.
+    fn flush_all(&self, sync: bool) -> Result<()>;
+}
+
+/// An iterator over the raw key-value pairs of a column family in key order. It has to be
+/// positioned by one of the seek methods, which return whether it's positioned at a key.
+pub trait RawIterator {
+    fn seek_to_first(&mut self) -> bool;
+
+    fn seek_to_last(&mut self) -> bool;
+
+    /// Seeks to the first key equal to or greater than `key`.
+    fn seek(&mut self, key: &[u8]) -> bool;
+
+    /// Seeks to the last key less than or equal to `key`.
+    fn seek_for_prev(&mut self, key: &[u8]) -> bool;
+
+    /// Returns the key-value pair at the current position, if any, and moves to the next key.
+    fn next_kv(&mut self) -> Option<(Vec<u8>, Vec<u8>)>;
+}
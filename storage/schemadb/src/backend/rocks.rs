@@ -0,0 +1,300 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the backend on [RocksDB](https://rocksdb.org/).
+
+use super::{Backend, BackendReader, RawIterator};
+use crate::{
+    convert_rocksdb_err, ColumnFamilyOptionsMap, ReadOptions, SchemaBatch, WriteOp, DEFAULT_CF_NAME,
+};
+use failure::prelude::*;
+use rocksdb::{
+    rocksdb_options::ColumnFamilyDescriptor, CFHandle, DBOptions, SeekKey, Writable, WriteOptions,
+};
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+/// Checks underlying Rocksdb instance existence by checking `CURRENT` file existence, the same way
+/// Rocksdb adopts to detect db existence.
+fn db_exists(path: &Path) -> bool {
+    let rocksdb_current_file = path.join("CURRENT");
+    rocksdb_current_file.is_file()
+}
+
+/// For now we always use synchronous writes. This makes sure that once the operation returns
+/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
+/// selectively turning this off for some non-critical writes to improve performance.
+fn default_write_options() -> WriteOptions {
+    let mut opts = WriteOptions::new();
+    opts.set_sync(true);
+    opts
+}
+
+/// The backend keeping each column family in a RocksDB column family.
+#[derive(Debug)]
+pub(crate) struct RocksdbBackend {
+    /// Shared with the snapshots, which have to keep the db open.
+    db: Arc<rocksdb::DB>,
+}
+
+impl RocksdbBackend {
+    /// Create db with all the column families provided if it doesn't exist at `path`; Otherwise,
+    /// try to open it with all the column families.
+    pub fn open<P: AsRef<Path>>(path: P, mut cf_opts_map: ColumnFamilyOptionsMap) -> Result<Self> {
+        let mut db_opts = DBOptions::new();
+
+        // For now we set the max total WAL size to be 1G. This config can be useful when column
+        // families are updated at non-uniform frequencies.
+        db_opts.set_max_total_wal_size(1 << 30);
+
+        // If db exists, just open it with all cfs.
+        if db_exists(path.as_ref()) {
+            return Ok(Self::new(Self::open_cf(
+                db_opts,
+                &path,
+                cf_opts_map.into_iter().collect(),
+            )?));
+        }
+
+        // If db doesn't exist, create a db first with all column families.
+        db_opts.create_if_missing(true);
+
+        let mut db = Self::open_cf(
+            db_opts,
+            path,
+            vec![cf_opts_map
+                .remove_entry(&DEFAULT_CF_NAME)
+                .ok_or_else(|| format_err!("No \"default\" column family name found"))?],
+        )?;
+        cf_opts_map
+            .into_iter()
+            .map(|(cf_name, cf_opts)| Self::create_cf(&mut db, (cf_name, cf_opts)))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(db))
+    }
+
+    /// Open the existing db at `path` in read-only mode with all the column families provided.
+    pub fn open_readonly<P: AsRef<Path>>(
+        path: P,
+        cf_opts_map: ColumnFamilyOptionsMap,
+    ) -> Result<Self> {
+        ensure!(
+            db_exists(path.as_ref()),
+            "DB not found at {:?}.",
+            path.as_ref()
+        );
+        let db = rocksdb::DB::open_cf_for_read_only(
+            DBOptions::new(),
+            path.as_ref().to_str().ok_or_else(|| {
+                format_err!("Path {:?} can not be converted to string.", path.as_ref())
+            })?,
+            cf_opts_map.into_iter().collect(),
+            false, /* error_if_log_file_exist */
+        )
+        .map_err(convert_rocksdb_err)?;
+
+        Ok(Self::new(db))
+    }
+
+    fn new(db: rocksdb::DB) -> Self {
+        Self { db: Arc::new(db) }
+    }
+
+    fn open_cf<'a, P, T>(opts: DBOptions, path: P, cfds: Vec<T>) -> Result<rocksdb::DB>
+    where
+        P: AsRef<Path>,
+        T: Into<ColumnFamilyDescriptor<'a>>,
+    {
+        rocksdb::DB::open_cf(
+            opts,
+            path.as_ref().to_str().ok_or_else(|| {
+                format_err!("Path {:?} can not be converted to string.", path.as_ref())
+            })?,
+            cfds,
+        )
+        .map_err(convert_rocksdb_err)
+    }
+
+    fn create_cf<'a, T>(db: &mut rocksdb::DB, cfd: T) -> Result<()>
+    where
+        T: Into<ColumnFamilyDescriptor<'a>>,
+    {
+        let _cf_handle = db.create_cf(cfd).map_err(convert_rocksdb_err)?;
+        Ok(())
+    }
+
+    fn get_cf_handle(&self, cf_name: &str) -> Result<&CFHandle> {
+        get_cf_handle(&self.db, cf_name)
+    }
+}
+
+fn get_cf_handle<'a>(db: &'a rocksdb::DB, cf_name: &str) -> Result<&'a CFHandle> {
+    db.cf_handle(cf_name).ok_or_else(|| {
+        format_err!(
+            "DB::cf_handle not found for column family name: {}",
+            cf_name
+        )
+    })
+}
+
+/// Options of iterators that stop at the end of the prefix of the key they seek to. RocksDB takes
+/// the prefix from the extractor that [`column_family_options`](crate::column_family_options) sets
+/// up, which the `prefix_len` passed to `prefix_iter` agrees with.
+fn prefix_read_options() -> ReadOptions {
+    let mut opts = ReadOptions::default();
+    opts.set_prefix_same_as_start(true);
+    opts
+}
+
+impl BackendReader for RocksdbBackend {
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        Ok(self
+            .db
+            .get_cf(cf_handle, key)
+            .map_err(convert_rocksdb_err)?
+            .map(|value| value.to_vec()))
+    }
+
+    fn iter<'a>(&'a self, cf_name: &str, opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        Ok(Box::new(RocksdbIterator(
+            self.db.iter_cf_opt(cf_handle, opts),
+        )))
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        cf_name: &str,
+        _prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        self.iter(cf_name, prefix_read_options())
+    }
+}
+
+impl Backend for RocksdbBackend {
+    fn put(&self, cf_name: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        let cf_handle = self.get_cf_handle(cf_name)?;
+        self.db
+            .put_cf_opt(cf_handle, key, value, &default_write_options())
+            .map_err(convert_rocksdb_err)
+    }
+
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<()> {
+        let db_batch = rocksdb::WriteBatch::new();
+        for (cf_name, key, write_op) in batch.rows() {
+            let cf_handle = self.get_cf_handle(cf_name)?;
+            match write_op {
+                WriteOp::Value(value) => db_batch.put_cf(cf_handle, key, value),
+                WriteOp::Deletion => db_batch.delete_cf(cf_handle, key),
+                WriteOp::RangeDeletion { end_key } => {
+                    db_batch.delete_range_cf(cf_handle, key, end_key)
+                }
+            }
+            .map_err(convert_rocksdb_err)?;
+        }
+
+        self.db
+            .write_opt(&db_batch, &default_write_options())
+            .map_err(convert_rocksdb_err)
+    }
+
+    fn snapshot(&self) -> Box<dyn BackendReader + Send + Sync> {
+        Box::new(RocksdbSnapshot {
+            db: Arc::clone(&self.db),
+            inner: rocksdb::Snapshot::new(Arc::clone(&self.db)),
+        })
+    }
+
+    fn get_approximate_sizes_cf(&self) -> Result<BTreeMap<String, u64>> {
+        let mut cf_sizes = BTreeMap::new();
+
+        for cf_name in self.db.cf_names().into_iter().map(ToString::to_string) {
+            let cf_handle = self.get_cf_handle(&cf_name)?;
+            let size = self
+                .db
+                .get_property_int_cf(cf_handle, "rocksdb.estimate-live-data-size")
+                .ok_or_else(|| {
+                    format_err!(
+                        "Unable to get approximate size of {} column family.",
+                        cf_name,
+                    )
+                })?;
+            cf_sizes.insert(cf_name, size);
+        }
+
+        Ok(cf_sizes)
+    }
+
+    fn flush_all(&self, sync: bool) -> Result<()> {
+        for cf_name in self.db.cf_names() {
+            let cf_handle = self.get_cf_handle(cf_name)?;
+            self.db
+                .flush_cf(cf_handle, sync)
+                .map_err(convert_rocksdb_err)?;
+        }
+        Ok(())
+    }
+}
+
+/// A RocksDB snapshot.
+struct RocksdbSnapshot {
+    db: Arc<rocksdb::DB>,
+    inner: rocksdb::Snapshot<Arc<rocksdb::DB>>,
+}
+
+// A RocksDB snapshot is an immutable view of the db, which RocksDB allows to read from any thread.
+unsafe impl Send for RocksdbSnapshot {}
+unsafe impl Sync for RocksdbSnapshot {}
+
+impl BackendReader for RocksdbSnapshot {
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let cf_handle = get_cf_handle(&self.db, cf_name)?;
+        Ok(self
+            .inner
+            .get_cf(cf_handle, key)
+            .map_err(convert_rocksdb_err)?
+            .map(|value| value.to_vec()))
+    }
+
+    fn iter<'a>(&'a self, cf_name: &str, opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>> {
+        let cf_handle = get_cf_handle(&self.db, cf_name)?;
+        Ok(Box::new(RocksdbIterator(
+            self.inner.iter_cf(cf_handle, opts),
+        )))
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        cf_name: &str,
+        _prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        self.iter(cf_name, prefix_read_options())
+    }
+}
+
+struct RocksdbIterator<'a>(rocksdb::DBIterator<&'a rocksdb::DB>);
+
+impl<'a> RawIterator for RocksdbIterator<'a> {
+    fn seek_to_first(&mut self) -> bool {
+        self.0.seek(SeekKey::Start)
+    }
+
+    fn seek_to_last(&mut self) -> bool {
+        self.0.seek(SeekKey::End)
+    }
+
+    fn seek(&mut self, key: &[u8]) -> bool {
+        self.0.seek(SeekKey::Key(key))
+    }
+
+    fn seek_for_prev(&mut self, key: &[u8]) -> bool {
+        self.0.seek_for_prev(SeekKey::Key(key))
+    }
+
+    fn next_kv(&mut self) -> Option<(Vec<u8>, Vec<u8>)> {
+        self.0.kv().map(|kv| {
+            self.0.next();
+            kv
+        })
+    }
+}
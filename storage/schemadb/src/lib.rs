@@ -9,18 +9,30 @@
 //! It requires that different kinds of key-value pairs be stored in separate column
 //! families.  To use this library to store a kind of key-value pairs, the user needs to use the
 //! [`define_schema!`] macro to define the schema name, the types of key and value, and name of the
-//! column family.
+//! column family. A schema may also declare how its column family is tuned with a
+//! [`ColumnFamilyConfig`](schema::ColumnFamilyConfig), which [`column_family_options`] translates
+//! into RocksDB options.
+//!
+//! Reads that need to be consistent with each other across schemas should go through a read-only
+//! [`Snapshot`].
+//!
+//! Besides RocksDB, a DB can also be kept entirely in memory with [`DB::open_in_memory`], which
+//! behaves the same except that nothing is persisted.
 
 #[macro_use]
 pub mod schema;
+mod backend;
 
-use crate::schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec};
+pub use crate::backend::{Backend, BackendReader, RawIterator};
+
+use crate::{
+    backend::{InMemoryBackend, RocksdbBackend},
+    schema::{CompactionStyle, KeyCodec, Schema, SeekKeyCodec, ValueCodec},
+};
 use failure::prelude::*;
 use lazy_static::lazy_static;
 use metrics::OpMetrics;
-use rocksdb::{
-    rocksdb_options::ColumnFamilyDescriptor, CFHandle, DBOptions, Writable, WriteOptions,
-};
+use rocksdb::{BlockBasedOptions, DBCompactionStyle, SliceTransform};
 use std::{
     collections::{BTreeMap, HashMap},
     iter::Iterator,
@@ -46,10 +58,15 @@ pub type ColumnFamilyOptionsMap = HashMap<ColumnFamilyName, ColumnFamilyOptions>
 /// [`LedgerInfo`](../types/ledger_info/struct.LedgerInfo.html).
 pub const DEFAULT_CF_NAME: ColumnFamilyName = "default";
 
+/// An update of a key in a [`SchemaBatch`].
 #[derive(Debug)]
-enum WriteOp {
+pub enum WriteOp {
+    /// Sets the value of the key.
     Value(Vec<u8>),
+    /// Deletes the key.
     Deletion,
+    /// Deletes the keys from the key of the row (inclusive) to `end_key` (exclusive).
+    RangeDeletion { end_key: Vec<u8> },
 }
 
 /// `SchemaBatch` holds a collection of updates that can be applied to a DB atomically. The updates
@@ -81,12 +98,33 @@ impl SchemaBatch {
             .push((S::COLUMN_FAMILY_NAME, key, WriteOp::Deletion));
         Ok(())
     }
+
+    /// Adds an operation to the batch that deletes all the keys from `begin` (inclusive) to `end`
+    /// (exclusive), in the order of their binary representation.
+    pub fn delete_range<S: Schema>(&mut self, begin: &S::Key, end: &S::Key) -> Result<()> {
+        let begin_key = <S::Key as KeyCodec<S>>::encode_key(begin)?;
+        let end_key = <S::Key as KeyCodec<S>>::encode_key(end)?;
+        self.rows.push((
+            S::COLUMN_FAMILY_NAME,
+            begin_key,
+            WriteOp::RangeDeletion { end_key },
+        ));
+        Ok(())
+    }
+
+    /// Returns the updates in the order they were added, each as the column family, the raw key
+    /// and the operation on it, for a [`Backend`] to apply.
+    pub fn rows(&self) -> impl Iterator<Item = (ColumnFamilyName, &[u8], &WriteOp)> {
+        self.rows
+            .iter()
+            .map(|(cf_name, key, write_op)| (*cf_name, key.as_slice(), write_op))
+    }
 }
 
 /// DB Iterator parameterized on [`Schema`] that seeks with [`Schema::Key`] and yields
 /// [`Schema::Key`] and [`Schema::Value`]
 pub struct SchemaIterator<'a, S> {
-    db_iter: rocksdb::DBIterator<&'a rocksdb::DB>,
+    raw_iter: Box<dyn RawIterator + 'a>,
     phantom: PhantomData<S>,
 }
 
@@ -94,21 +132,21 @@ impl<'a, S> SchemaIterator<'a, S>
 where
     S: Schema,
 {
-    fn new(db_iter: rocksdb::DBIterator<&'a rocksdb::DB>) -> Self {
+    fn new(raw_iter: Box<dyn RawIterator + 'a>) -> Self {
         SchemaIterator {
-            db_iter,
+            raw_iter,
             phantom: PhantomData,
         }
     }
 
     /// Seeks to the first key.
     pub fn seek_to_first(&mut self) -> bool {
-        self.db_iter.seek(rocksdb::SeekKey::Start)
+        self.raw_iter.seek_to_first()
     }
 
     /// Seeks to the last key.
     pub fn seek_to_last(&mut self) -> bool {
-        self.db_iter.seek(rocksdb::SeekKey::End)
+        self.raw_iter.seek_to_last()
     }
 
     /// Seeks to the first key whose binary representation is equal to or greater than that of the
@@ -118,7 +156,7 @@ where
         SK: SeekKeyCodec<S>,
     {
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
-        Ok(self.db_iter.seek(rocksdb::SeekKey::Key(&key)))
+        Ok(self.raw_iter.seek(&key))
     }
 
     /// Seeks to the last key whose binary representation is less than or equal to that of the
@@ -130,7 +168,7 @@ where
         SK: SeekKeyCodec<S>,
     {
         let key = <SK as SeekKeyCodec<S>>::encode_seek_key(seek_key)?;
-        Ok(self.db_iter.seek_for_prev(rocksdb::SeekKey::Key(&key)))
+        Ok(self.raw_iter.seek_for_prev(&key))
     }
 }
 
@@ -141,8 +179,7 @@ where
     type Item = Result<(S::Key, S::Value)>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.db_iter.kv().map(|(raw_key, raw_value)| {
-            self.db_iter.next();
+        self.raw_iter.next_kv().map(|(raw_key, raw_value)| {
             Ok((
                 <S::Key as KeyCodec<S>>::decode_key(&raw_key)?,
                 <S::Value as ValueCodec<S>>::decode_value(&raw_value)?,
@@ -151,127 +188,107 @@ where
     }
 }
 
-/// Checks underlying Rocksdb instance existence by checking `CURRENT` file existence, the same way
-/// Rocksdb adopts to detect db existence.
-fn db_exists(path: &Path) -> bool {
-    let rocksdb_current_file = path.join("CURRENT");
-    rocksdb_current_file.is_file()
-}
-
 /// All the RocksDB methods return `std::result::Result<T, String>`. Since our methods return
 /// `failure::Result<T>`, manual conversion is needed.
 fn convert_rocksdb_err(msg: String) -> failure::Error {
     format_err!("RocksDB internal error: {}.", msg)
 }
 
-/// This DB is a schematized RocksDB wrapper where all data passed in and out are typed according to
-/// [`Schema`]s.
+/// This DB is a schematized key-value store wrapper where all data passed in and out are typed
+/// according to [`Schema`]s. It's backed by RocksDB unless opened with
+/// [`open_in_memory`](DB::open_in_memory) or on another [`Backend`] with
+/// [`open_with_backend`](DB::open_with_backend).
 #[derive(Debug)]
 pub struct DB {
-    inner: rocksdb::DB,
+    inner: Box<dyn Backend>,
 }
 
 impl DB {
     /// Create db with all the column families provided if it doesn't exist at `path`; Otherwise,
     /// try to open it with all the column families.
-    pub fn open<P: AsRef<Path>>(path: P, mut cf_opts_map: ColumnFamilyOptionsMap) -> Result<Self> {
-        let mut db_opts = DBOptions::new();
-
-        // For now we set the max total WAL size to be 1G. This config can be useful when column
-        // families are updated at non-uniform frequencies.
-        db_opts.set_max_total_wal_size(1 << 30);
-
-        // If db exists, just open it with all cfs.
-        if db_exists(path.as_ref()) {
-            return DB::open_cf(db_opts, &path, cf_opts_map.into_iter().collect());
-        }
-
-        // If db doesn't exist, create a db first with all column families.
-        db_opts.create_if_missing(true);
-
-        let mut db = DB::open_cf(
-            db_opts,
-            path,
-            vec![cf_opts_map
-                .remove_entry(&DEFAULT_CF_NAME)
-                .ok_or_else(|| format_err!("No \"default\" column family name found"))?],
-        )?;
-        cf_opts_map
-            .into_iter()
-            .map(|(cf_name, cf_opts)| db.create_cf((cf_name, cf_opts)))
-            .collect::<Result<Vec<_>>>()?;
-        Ok(db)
+    pub fn open<P: AsRef<Path>>(path: P, cf_opts_map: ColumnFamilyOptionsMap) -> Result<Self> {
+        Ok(DB {
+            inner: Box::new(RocksdbBackend::open(path, cf_opts_map)?),
+        })
     }
 
-    fn open_cf<'a, P, T>(opts: DBOptions, path: P, cfds: Vec<T>) -> Result<DB>
-    where
-        P: AsRef<Path>,
-        T: Into<ColumnFamilyDescriptor<'a>>,
-    {
-        let inner = rocksdb::DB::open_cf(
-            opts,
-            path.as_ref().to_str().ok_or_else(|| {
-                format_err!("Path {:?} can not be converted to string.", path.as_ref())
-            })?,
-            cfds,
-        )
-        .map_err(convert_rocksdb_err)?;
+    /// Open the existing db at `path` in read-only mode with all the column families provided.
+    /// Any write fails, and the db may be opened while another process is holding it for writes.
+    pub fn open_readonly<P: AsRef<Path>>(
+        path: P,
+        cf_opts_map: ColumnFamilyOptionsMap,
+    ) -> Result<Self> {
+        Ok(DB {
+            inner: Box::new(RocksdbBackend::open_readonly(path, cf_opts_map)?),
+        })
+    }
 
-        Ok(DB { inner })
+    /// Creates a db on `backend`, which has to hold the column families of all the schemas the db
+    /// is used with.
+    pub fn open_with_backend(backend: Box<dyn Backend>) -> Self {
+        DB { inner: backend }
     }
 
-    fn create_cf<'a, T>(&mut self, cfd: T) -> Result<()>
-    where
-        T: Into<ColumnFamilyDescriptor<'a>>,
-    {
-        let _cf_handle = self.inner.create_cf(cfd).map_err(convert_rocksdb_err)?;
-        Ok(())
+    /// Creates an empty db kept in memory with all the column families in `cf_opts_map`, whose
+    /// RocksDB options are ignored. Everything is gone once it's dropped, so this is mostly for
+    /// tests.
+    pub fn open_in_memory(cf_opts_map: ColumnFamilyOptionsMap) -> Self {
+        DB {
+            inner: Box::new(InMemoryBackend::new(
+                cf_opts_map
+                    .into_iter()
+                    .map(|(cf_name, _)| cf_name)
+                    .filter(|cf_name| *cf_name != DEFAULT_CF_NAME),
+            )),
+        }
     }
 
     /// Reads single record by key.
     pub fn get<S: Schema>(&self, schema_key: &S::Key) -> Result<Option<S::Value>> {
-        let k = <S::Key as KeyCodec<S>>::encode_key(&schema_key)?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
-
-        self.inner
-            .get_cf(cf_handle, &k)
-            .map_err(convert_rocksdb_err)?
-            .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
-            .transpose()
+        get::<S, _>(&*self.inner, schema_key)
     }
 
     /// Writes single record.
     pub fn put<S: Schema>(&self, key: &S::Key, value: &S::Value) -> Result<()> {
         let k = <S::Key as KeyCodec<S>>::encode_key(&key)?;
         let v = <S::Value as ValueCodec<S>>::encode_value(&value)?;
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
 
-        self.inner
-            .put_cf_opt(cf_handle, &k, &v, &default_write_options())
-            .map_err(convert_rocksdb_err)
+        self.inner.put(S::COLUMN_FAMILY_NAME, &k, &v)
     }
 
     /// Returns a [`SchemaIterator`] on a certain schema.
     pub fn iter<S: Schema>(&self, opts: ReadOptions) -> Result<SchemaIterator<S>> {
-        let cf_handle = self.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
-        Ok(SchemaIterator::new(self.inner.iter_cf_opt(cf_handle, opts)))
+        iter(&*self.inner, opts)
     }
 
-    /// Writes a group of records wrapped in a [`SchemaBatch`].
-    pub fn write_schemas(&self, batch: SchemaBatch) -> Result<()> {
-        let db_batch = rocksdb::WriteBatch::new();
-        for (cf_name, key, write_op) in &batch.rows {
-            let cf_handle = self.get_cf_handle(cf_name)?;
-            match write_op {
-                WriteOp::Value(value) => db_batch.put_cf(cf_handle, &key, &value),
-                WriteOp::Deletion => db_batch.delete_cf(cf_handle, &key),
-            }
-            .map_err(convert_rocksdb_err)?;
+    /// Returns a [`SchemaIterator`] on a certain schema positioned at `prefix`, which only yields
+    /// the keys sharing the prefix. The schema must declare a
+    /// [`fixed_prefix_len`](schema::ColumnFamilyConfig::fixed_prefix_len), and unlike
+    /// [`iter`](DB::iter), this makes use of the prefix bloom filters.
+    pub fn prefix_iter<S, SK>(&self, prefix: &SK) -> Result<SchemaIterator<S>>
+    where
+        S: Schema,
+        SK: SeekKeyCodec<S>,
+    {
+        prefix_iter(&*self.inner, prefix)
+    }
+
+    /// Takes a consistent point-in-time view of the DB across all the schemas. Reads through it
+    /// don't observe any write committed after it's taken, so a group of reads never sees some of
+    /// the updates in a [`SchemaBatch`] but not the others.
+    ///
+    /// A snapshot isn't free: RocksDB keeps the versions of the keys it sees until it's dropped,
+    /// and a DB [opened in memory](DB::open_in_memory) copies each column family written to while
+    /// a snapshot is alive, in full. Take one only for reads that need to agree with each other.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            inner: self.inner.snapshot(),
         }
+    }
 
-        self.inner
-            .write_opt(&db_batch, &default_write_options())
-            .map_err(convert_rocksdb_err)?;
+    /// Writes a group of records wrapped in a [`SchemaBatch`].
+    pub fn write_schemas(&self, batch: SchemaBatch) -> Result<()> {
+        self.inner.write_batch(&batch)?;
 
         for (cf_name, key, write_op) in &batch.rows {
             match write_op {
@@ -280,60 +297,163 @@ impl DB {
                     (key.len() + value.len()) as f64,
                 ),
                 WriteOp::Deletion => OP_COUNTER.inc(&format!("db_delete_{}", cf_name)),
+                WriteOp::RangeDeletion { .. } => {
+                    OP_COUNTER.inc(&format!("db_delete_range_{}", cf_name))
+                }
             };
         }
 
         Ok(())
     }
 
-    fn get_cf_handle(&self, cf_name: &str) -> Result<&CFHandle> {
-        self.inner.cf_handle(cf_name).ok_or_else(|| {
-            format_err!(
-                "DB::cf_handle not found for column family name: {}",
-                cf_name
-            )
-        })
-    }
-
     /// Returns the approximate size of each non-empty column family in bytes.
     pub fn get_approximate_sizes_cf(&self) -> Result<BTreeMap<String, u64>> {
-        let mut cf_sizes = BTreeMap::new();
-
-        for cf_name in self.inner.cf_names().into_iter().map(ToString::to_string) {
-            let cf_handle = self.get_cf_handle(&cf_name)?;
-            let size = self
-                .inner
-                .get_property_int_cf(cf_handle, "rocksdb.estimate-live-data-size")
-                .ok_or_else(|| {
-                    format_err!(
-                        "Unable to get approximate size of {} column family.",
-                        cf_name,
-                    )
-                })?;
-            cf_sizes.insert(cf_name, size);
-        }
-
-        Ok(cf_sizes)
+        self.inner.get_approximate_sizes_cf()
     }
 
     /// Flushes all memtable data. If `sync` is true, the flush will wait until it's done. This is
     /// only used for testing `get_approximate_sizes_cf` in unit tests.
     pub fn flush_all(&self, sync: bool) -> Result<()> {
-        for cf_name in self.inner.cf_names() {
-            let cf_handle = self.get_cf_handle(cf_name)?;
-            self.inner
-                .flush_cf(cf_handle, sync)
-                .map_err(convert_rocksdb_err)?;
-        }
-        Ok(())
+        self.inner.flush_all(sync)
+    }
+}
+
+/// A read-only point-in-time view of a [`DB`], taken by [`DB::snapshot`].
+pub struct Snapshot {
+    inner: Box<dyn BackendReader + Send + Sync>,
+}
+
+impl Snapshot {
+    /// Reads single record by key.
+    pub fn get<S: Schema>(&self, schema_key: &S::Key) -> Result<Option<S::Value>> {
+        get::<S, _>(&*self.inner, schema_key)
+    }
+
+    /// Returns a [`SchemaIterator`] on a certain schema. See [`DB::iter`].
+    pub fn iter<S: Schema>(&self, opts: ReadOptions) -> Result<SchemaIterator<S>> {
+        iter(&*self.inner, opts)
+    }
+
+    /// Returns a [`SchemaIterator`] on a certain schema positioned at `prefix`. See
+    /// [`DB::prefix_iter`].
+    pub fn prefix_iter<S, SK>(&self, prefix: &SK) -> Result<SchemaIterator<S>>
+    where
+        S: Schema,
+        SK: SeekKeyCodec<S>,
+    {
+        prefix_iter(&*self.inner, prefix)
     }
 }
 
-/// For now we always use synchronous writes. This makes sure that once the operation returns
-/// `Ok(())` the data is persisted even if the machine crashes. In the future we might consider
-/// selectively turning this off for some non-critical writes to improve performance.
-fn default_write_options() -> WriteOptions {
-    let mut opts = WriteOptions::new();
-    opts.set_sync(true);
+fn get<S, R>(reader: &R, schema_key: &S::Key) -> Result<Option<S::Value>>
+where
+    S: Schema,
+    R: BackendReader + ?Sized,
+{
+    let k = <S::Key as KeyCodec<S>>::encode_key(&schema_key)?;
+
+    reader
+        .get(S::COLUMN_FAMILY_NAME, &k)?
+        .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
+        .transpose()
+}
+
+fn iter<S, R>(reader: &R, opts: ReadOptions) -> Result<SchemaIterator<S>>
+where
+    S: Schema,
+    R: BackendReader + ?Sized,
+{
+    Ok(SchemaIterator::new(reader.iter(
+        S::COLUMN_FAMILY_NAME,
+        total_order_read_options::<S>(opts),
+    )?))
+}
+
+fn prefix_iter<'a, S, SK, R>(reader: &'a R, prefix: &SK) -> Result<SchemaIterator<'a, S>>
+where
+    S: Schema,
+    SK: SeekKeyCodec<S>,
+    R: BackendReader + ?Sized,
+{
+    let (prefix, prefix_len) = encode_prefix::<S, SK>(prefix)?;
+    let mut iter = SchemaIterator::new(reader.prefix_iter(S::COLUMN_FAMILY_NAME, prefix_len)?);
+    iter.raw_iter.seek(&prefix);
+    Ok(iter)
+}
+
+/// With a prefix extractor, RocksDB iterators may skip the keys not sharing the prefix of the key
+/// they seek to. Iterators returned by `iter` always see all the keys in order.
+fn total_order_read_options<S: Schema>(mut opts: ReadOptions) -> ReadOptions {
+    if S::COLUMN_FAMILY_CONFIG.fixed_prefix_len.is_some() {
+        opts.set_total_order_seek(true);
+    }
     opts
 }
+
+/// Encodes the `prefix` to seek to and returns it with the prefix length of the column family.
+fn encode_prefix<S, SK>(prefix: &SK) -> Result<(Vec<u8>, usize)>
+where
+    S: Schema,
+    SK: SeekKeyCodec<S>,
+{
+    let prefix_len = S::COLUMN_FAMILY_CONFIG.fixed_prefix_len.ok_or_else(|| {
+        format_err!(
+            "Column family {} has no fixed prefix length.",
+            S::COLUMN_FAMILY_NAME
+        )
+    })?;
+    let prefix = <SK as SeekKeyCodec<S>>::encode_seek_key(prefix)?;
+    ensure!(
+        prefix.len() >= prefix_len,
+        "Seek key of {} bytes is shorter than the prefix length {} of column family {}.",
+        prefix.len(),
+        prefix_len,
+        S::COLUMN_FAMILY_NAME,
+    );
+    Ok((prefix, prefix_len))
+}
+
+/// Takes the first `len` bytes of the keys as their prefixes.
+struct FixedPrefixSliceTransform {
+    len: usize,
+}
+
+impl SliceTransform for FixedPrefixSliceTransform {
+    fn transform<'a>(&mut self, key: &'a [u8]) -> &'a [u8] {
+        &key[..self.len]
+    }
+
+    fn in_domain(&mut self, key: &[u8]) -> bool {
+        key.len() >= self.len
+    }
+}
+
+/// Returns the column family name of schema `S` along with the RocksDB options translated from
+/// its [`ColumnFamilyConfig`](schema::ColumnFamilyConfig), to be put in the
+/// [`ColumnFamilyOptionsMap`] for [`DB::open`].
+pub fn column_family_options<S: Schema>() -> Result<(ColumnFamilyName, ColumnFamilyOptions)> {
+    let config = S::COLUMN_FAMILY_CONFIG;
+    let mut cf_opts = ColumnFamilyOptions::default();
+
+    cf_opts.compaction_style(match config.compaction_style {
+        CompactionStyle::Level => DBCompactionStyle::Level,
+        CompactionStyle::Universal => DBCompactionStyle::Universal,
+    });
+    if let Some(bits_per_key) = config.bloom_filter_bits_per_key {
+        let mut block_based_opts = BlockBasedOptions::new();
+        block_based_opts.set_bloom_filter(bits_per_key as i32, false /* block_based */);
+        cf_opts.set_block_based_table_factory(&block_based_opts);
+    }
+    if let Some(len) = config.fixed_prefix_len {
+        cf_opts
+            .set_prefix_extractor(
+                "FixedPrefixSliceTransform",
+                Box::new(FixedPrefixSliceTransform { len }),
+            )
+            .map_err(convert_rocksdb_err)?;
+        // Also filter memtable lookups by prefix.
+        cf_opts.set_memtable_prefix_bloom_size_ratio(0.1);
+    }
+
+    Ok((S::COLUMN_FAMILY_NAME, cf_opts))
+}
@@ -14,7 +14,7 @@ use std::fmt::Debug;
 /// use failure::Result;
 /// use schemadb::{
 ///     define_schema,
-///     schema::{KeyCodec, SeekKeyCodec, ValueCodec},
+///     schema::{ColumnFamilyConfig, KeyCodec, SeekKeyCodec, ValueCodec},
 /// };
 ///
 /// // Define key type and value type for a schema with derived traits (Clone, Debug, Eq, PartialEq)
@@ -48,6 +48,38 @@ use std::fmt::Debug;
 /// // column family name, by generating code that implements the `Schema` trait for the type.
 /// define_schema!(ExampleSchema, Key, Value, "exmaple_cf_name");
 ///
+/// // A schema can also declare how its column family is tuned, e.g. with bloom filters for point
+/// // lookups, instead of using the defaults.
+/// define_schema!(
+///     TunedSchema,
+///     Key,
+///     Value,
+///     "tuned_cf_name",
+///     ColumnFamilyConfig {
+///         bloom_filter_bits_per_key: Some(10),
+///         ..ColumnFamilyConfig::DEFAULT
+///     }
+/// );
+/// # impl KeyCodec<TunedSchema> for Key {
+/// #     fn encode_key(&self) -> Result<Vec<u8>> {
+/// #         Ok(vec![])
+/// #     }
+/// #
+/// #     fn decode_key(data: &[u8]) -> Result<Self> {
+/// #         Ok(Key)
+/// #     }
+/// # }
+/// #
+/// # impl ValueCodec<TunedSchema> for Value {
+/// #     fn encode_value(&self) -> Result<Vec<u8>> {
+/// #         Ok(vec![])
+/// #     }
+/// #
+/// #     fn decode_value(data: &[u8]) -> Result<Self> {
+/// #         Ok(Value)
+/// #     }
+/// # }
+///
 /// // SeekKeyCodec is automatically implemented for KeyCodec,
 /// // so you can seek an iterator with the Key type:
 /// // iter.seek(&Key);
@@ -67,10 +99,20 @@ use std::fmt::Debug;
 #[macro_export]
 macro_rules! define_schema {
     ($schema_type: ident, $key_type: ty, $value_type: ty, $cf_name: expr) => {
+        $crate::define_schema!(
+            $schema_type,
+            $key_type,
+            $value_type,
+            $cf_name,
+            $crate::schema::ColumnFamilyConfig::DEFAULT
+        );
+    };
+    ($schema_type: ident, $key_type: ty, $value_type: ty, $cf_name: expr, $cf_config: expr) => {
         pub(crate) struct $schema_type;
 
         impl $crate::schema::Schema for $schema_type {
             const COLUMN_FAMILY_NAME: $crate::ColumnFamilyName = $cf_name;
+            const COLUMN_FAMILY_CONFIG: $crate::schema::ColumnFamilyConfig = $cf_config;
             type Key = $key_type;
             type Value = $value_type;
         }
@@ -118,6 +160,8 @@ pub trait Schema {
     /// The column family name associated with this struct.
     /// Note: all schemas within the same SchemaDB must have distinct column family names.
     const COLUMN_FAMILY_NAME: ColumnFamilyName;
+    /// How the column family is tuned, see [`ColumnFamilyConfig`].
+    const COLUMN_FAMILY_CONFIG: ColumnFamilyConfig = ColumnFamilyConfig::DEFAULT;
 
     /// Type of the key.
     type Key: KeyCodec<Self>;
@@ -125,6 +169,47 @@ pub trait Schema {
     type Value: ValueCodec<Self>;
 }
 
+/// The options a [`Schema`] declares for its column family, which
+/// [`column_family_options`](crate::column_family_options) translates into RocksDB options. The
+/// defaults are those of RocksDB.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ColumnFamilyConfig {
+    /// How the column family is compacted.
+    pub compaction_style: CompactionStyle,
+    /// Bits per key of the bloom filters, which let point lookups of absent keys skip most of the
+    /// SST files. `None` means no bloom filter.
+    pub bloom_filter_bits_per_key: Option<u32>,
+    /// Length of the fixed key prefix that bloom filters are also built on, so that
+    /// [`DB::prefix_iter`](crate::DB::prefix_iter) skips the SST files without the prefix. Keys
+    /// shorter than this are not in any prefix bloom filter. `None` means no prefix extractor.
+    pub fixed_prefix_len: Option<usize>,
+}
+
+impl ColumnFamilyConfig {
+    /// The config of schemas that don't declare one.
+    pub const DEFAULT: Self = ColumnFamilyConfig {
+        compaction_style: CompactionStyle::Level,
+        bloom_filter_bits_per_key: None,
+        fixed_prefix_len: None,
+    };
+}
+
+impl Default for ColumnFamilyConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Compaction styles of a column family. See
+/// [`RocksDB doc`](https://github.com/facebook/rocksdb/wiki/Compaction).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CompactionStyle {
+    /// Leveled compaction, which keeps read and space amplification low.
+    Level,
+    /// Universal compaction, which keeps write amplification low for write-heavy data.
+    Universal,
+}
+
 /// Helper used in tests to assert a (key, value) pair for a certain [`Schema`] is able to convert
 /// to bytes and convert back.
 pub fn assert_encode_decode<S: Schema>(key: &S::Key, value: &S::Value) {
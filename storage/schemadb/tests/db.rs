@@ -1,12 +1,17 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use byteorder::{LittleEndian, ReadBytesExt};
-use failure::Result;
+use byteorder::{BigEndian, LittleEndian, ReadBytesExt, WriteBytesExt};
+use failure::{bail, Result};
 use schemadb::{
-    define_schema,
-    schema::{KeyCodec, Schema, ValueCodec},
-    ColumnFamilyOptions, ColumnFamilyOptionsMap, SchemaBatch, DB, DEFAULT_CF_NAME,
+    column_family_options, define_schema,
+    schema::{ColumnFamilyConfig, KeyCodec, Schema, SeekKeyCodec, ValueCodec},
+    Backend, BackendReader, ColumnFamilyOptions, ColumnFamilyOptionsMap, RawIterator, ReadOptions,
+    SchemaBatch, WriteOp, DB, DEFAULT_CF_NAME,
+};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::RwLock,
 };
 
 // Creating two schemas that share exactly the same structure but are stored in different column
@@ -70,8 +75,8 @@ impl ValueCodec<TestSchema2> for TestField {
     }
 }
 
-fn open_db(dir: &tempfile::TempDir) -> DB {
-    let cf_opts_map: ColumnFamilyOptionsMap = [
+fn test_cf_opts_map() -> ColumnFamilyOptionsMap {
+    [
         (DEFAULT_CF_NAME, ColumnFamilyOptions::default()),
         (
             TestSchema1::COLUMN_FAMILY_NAME,
@@ -84,12 +89,15 @@ fn open_db(dir: &tempfile::TempDir) -> DB {
     ]
     .iter()
     .cloned()
-    .collect();
-    DB::open(&dir, cf_opts_map).expect("Failed to open DB.")
+    .collect()
+}
+
+fn open_db(dir: &tempfile::TempDir) -> DB {
+    DB::open(&dir, test_cf_opts_map()).expect("Failed to open DB.")
 }
 
 struct TestDB {
-    _tmpdir: tempfile::TempDir,
+    _tmpdir: Option<tempfile::TempDir>,
     db: DB,
 }
 
@@ -99,10 +107,22 @@ impl TestDB {
         let db = open_db(&tmpdir);
 
         TestDB {
-            _tmpdir: tmpdir,
+            _tmpdir: Some(tmpdir),
             db,
         }
     }
+
+    fn new_in_memory() -> Self {
+        TestDB {
+            _tmpdir: None,
+            db: DB::open_in_memory(test_cf_opts_map()),
+        }
+    }
+
+    /// Returns an empty DB on RocksDB and one in memory, to run the same test against both.
+    fn all() -> Vec<Self> {
+        vec![Self::new(), Self::new_in_memory()]
+    }
 }
 
 impl std::ops::Deref for TestDB {
@@ -115,42 +135,42 @@ impl std::ops::Deref for TestDB {
 
 #[test]
 fn test_schema_put_get() {
-    let db = TestDB::new();
-
-    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
-    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
-    db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
-    db.put::<TestSchema2>(&TestField(2), &TestField(3)).unwrap();
-    db.put::<TestSchema2>(&TestField(3), &TestField(4)).unwrap();
-    db.put::<TestSchema2>(&TestField(4), &TestField(5)).unwrap();
+    for db in TestDB::all() {
+        db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+        db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+        db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
+        db.put::<TestSchema2>(&TestField(2), &TestField(3)).unwrap();
+        db.put::<TestSchema2>(&TestField(3), &TestField(4)).unwrap();
+        db.put::<TestSchema2>(&TestField(4), &TestField(5)).unwrap();
 
-    assert_eq!(
-        db.get::<TestSchema1>(&TestField(0)).unwrap(),
-        Some(TestField(0)),
-    );
-    assert_eq!(
-        db.get::<TestSchema1>(&TestField(1)).unwrap(),
-        Some(TestField(1)),
-    );
-    assert_eq!(
-        db.get::<TestSchema1>(&TestField(2)).unwrap(),
-        Some(TestField(2)),
-    );
-    assert_eq!(db.get::<TestSchema1>(&TestField(3)).unwrap(), None);
+        assert_eq!(
+            db.get::<TestSchema1>(&TestField(0)).unwrap(),
+            Some(TestField(0)),
+        );
+        assert_eq!(
+            db.get::<TestSchema1>(&TestField(1)).unwrap(),
+            Some(TestField(1)),
+        );
+        assert_eq!(
+            db.get::<TestSchema1>(&TestField(2)).unwrap(),
+            Some(TestField(2)),
+        );
+        assert_eq!(db.get::<TestSchema1>(&TestField(3)).unwrap(), None);
 
-    assert_eq!(db.get::<TestSchema2>(&TestField(1)).unwrap(), None);
-    assert_eq!(
-        db.get::<TestSchema2>(&TestField(2)).unwrap(),
-        Some(TestField(3)),
-    );
-    assert_eq!(
-        db.get::<TestSchema2>(&TestField(3)).unwrap(),
-        Some(TestField(4)),
-    );
-    assert_eq!(
-        db.get::<TestSchema2>(&TestField(4)).unwrap(),
-        Some(TestField(5)),
-    );
+        assert_eq!(db.get::<TestSchema2>(&TestField(1)).unwrap(), None);
+        assert_eq!(
+            db.get::<TestSchema2>(&TestField(2)).unwrap(),
+            Some(TestField(3)),
+        );
+        assert_eq!(
+            db.get::<TestSchema2>(&TestField(3)).unwrap(),
+            Some(TestField(4)),
+        );
+        assert_eq!(
+            db.get::<TestSchema2>(&TestField(4)).unwrap(),
+            Some(TestField(5)),
+        );
+    }
 }
 
 fn collect_values<S: Schema>(db: &TestDB) -> Vec<(S::Key, S::Value)> {
@@ -171,84 +191,84 @@ fn gen_expected_values(values: &[(u32, u32)]) -> Vec<(TestField, TestField)> {
 
 #[test]
 fn test_single_schema_batch() {
-    let db = TestDB::new();
-
-    let mut db_batch = SchemaBatch::new();
-    db_batch
-        .put::<TestSchema1>(&TestField(0), &TestField(0))
-        .unwrap();
-    db_batch
-        .put::<TestSchema1>(&TestField(1), &TestField(1))
-        .unwrap();
-    db_batch
-        .put::<TestSchema1>(&TestField(2), &TestField(2))
-        .unwrap();
-    db_batch
-        .put::<TestSchema2>(&TestField(3), &TestField(3))
-        .unwrap();
-    db_batch.delete::<TestSchema2>(&TestField(4)).unwrap();
-    db_batch.delete::<TestSchema2>(&TestField(3)).unwrap();
-    db_batch
-        .put::<TestSchema2>(&TestField(4), &TestField(4))
-        .unwrap();
-    db_batch
-        .put::<TestSchema2>(&TestField(5), &TestField(5))
-        .unwrap();
-    db.write_schemas(db_batch).unwrap();
+    for db in TestDB::all() {
+        let mut db_batch = SchemaBatch::new();
+        db_batch
+            .put::<TestSchema1>(&TestField(0), &TestField(0))
+            .unwrap();
+        db_batch
+            .put::<TestSchema1>(&TestField(1), &TestField(1))
+            .unwrap();
+        db_batch
+            .put::<TestSchema1>(&TestField(2), &TestField(2))
+            .unwrap();
+        db_batch
+            .put::<TestSchema2>(&TestField(3), &TestField(3))
+            .unwrap();
+        db_batch.delete::<TestSchema2>(&TestField(4)).unwrap();
+        db_batch.delete::<TestSchema2>(&TestField(3)).unwrap();
+        db_batch
+            .put::<TestSchema2>(&TestField(4), &TestField(4))
+            .unwrap();
+        db_batch
+            .put::<TestSchema2>(&TestField(5), &TestField(5))
+            .unwrap();
+        db.write_schemas(db_batch).unwrap();
 
-    assert_eq!(
-        collect_values::<TestSchema1>(&db),
-        gen_expected_values(&[(0, 0), (1, 1), (2, 2)]),
-    );
-    assert_eq!(
-        collect_values::<TestSchema2>(&db),
-        gen_expected_values(&[(4, 4), (5, 5)]),
-    );
+        assert_eq!(
+            collect_values::<TestSchema1>(&db),
+            gen_expected_values(&[(0, 0), (1, 1), (2, 2)]),
+        );
+        assert_eq!(
+            collect_values::<TestSchema2>(&db),
+            gen_expected_values(&[(4, 4), (5, 5)]),
+        );
+    }
 }
 
 #[test]
 fn test_two_schema_batches() {
-    let db = TestDB::new();
-
-    let mut db_batch1 = SchemaBatch::new();
-    db_batch1
-        .put::<TestSchema1>(&TestField(0), &TestField(0))
-        .unwrap();
-    db_batch1
-        .put::<TestSchema1>(&TestField(1), &TestField(1))
-        .unwrap();
-    db_batch1
-        .put::<TestSchema1>(&TestField(2), &TestField(2))
-        .unwrap();
-    db_batch1.delete::<TestSchema1>(&TestField(2)).unwrap();
-    db.write_schemas(db_batch1).unwrap();
+    for db in TestDB::all() {
+        let mut db_batch1 = SchemaBatch::new();
+        db_batch1
+            .put::<TestSchema1>(&TestField(0), &TestField(0))
+            .unwrap();
+        db_batch1
+            .put::<TestSchema1>(&TestField(1), &TestField(1))
+            .unwrap();
+        db_batch1
+            .put::<TestSchema1>(&TestField(2), &TestField(2))
+            .unwrap();
+        db_batch1.delete::<TestSchema1>(&TestField(2)).unwrap();
+        db.write_schemas(db_batch1).unwrap();
 
-    assert_eq!(
-        collect_values::<TestSchema1>(&db),
-        gen_expected_values(&[(0, 0), (1, 1)]),
-    );
+        assert_eq!(
+            collect_values::<TestSchema1>(&db),
+            gen_expected_values(&[(0, 0), (1, 1)]),
+        );
 
-    let mut db_batch2 = SchemaBatch::new();
-    db_batch2.delete::<TestSchema2>(&TestField(3)).unwrap();
-    db_batch2
-        .put::<TestSchema2>(&TestField(3), &TestField(3))
-        .unwrap();
-    db_batch2
-        .put::<TestSchema2>(&TestField(4), &TestField(4))
-        .unwrap();
-    db_batch2
-        .put::<TestSchema2>(&TestField(5), &TestField(5))
-        .unwrap();
-    db.write_schemas(db_batch2).unwrap();
+        let mut db_batch2 = SchemaBatch::new();
+        db_batch2.delete::<TestSchema2>(&TestField(3)).unwrap();
+        db_batch2
+            .put::<TestSchema2>(&TestField(3), &TestField(3))
+            .unwrap();
+        db_batch2
+            .put::<TestSchema2>(&TestField(4), &TestField(4))
+            .unwrap();
+        db_batch2
+            .put::<TestSchema2>(&TestField(5), &TestField(5))
+            .unwrap();
+        db.write_schemas(db_batch2).unwrap();
 
-    assert_eq!(
-        collect_values::<TestSchema1>(&db),
-        gen_expected_values(&[(0, 0), (1, 1)]),
-    );
-    assert_eq!(
-        collect_values::<TestSchema2>(&db),
-        gen_expected_values(&[(3, 3), (4, 4), (5, 5)]),
-    );
+        assert_eq!(
+            collect_values::<TestSchema1>(&db),
+            gen_expected_values(&[(0, 0), (1, 1)]),
+        );
+        assert_eq!(
+            collect_values::<TestSchema2>(&db),
+            gen_expected_values(&[(3, 3), (4, 4), (5, 5)]),
+        );
+    }
 }
 
 #[test]
@@ -271,25 +291,307 @@ fn test_reopen() {
     }
 }
 
+#[test]
+fn test_open_readonly() {
+    let tmpdir = tempfile::tempdir().expect("Failed to create temporary directory.");
+    let cf_opts_map = test_cf_opts_map();
+    assert!(DB::open_readonly(&tmpdir, cf_opts_map.clone()).is_err());
+
+    let db = open_db(&tmpdir);
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.flush_all(/* sync = */ true).unwrap();
+
+    let readonly_db = DB::open_readonly(&tmpdir, cf_opts_map).unwrap();
+    assert_eq!(
+        readonly_db.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(0)),
+    );
+    assert!(readonly_db
+        .put::<TestSchema1>(&TestField(1), &TestField(1))
+        .is_err());
+}
+
 #[test]
 fn test_report_size() {
-    let db = TestDB::new();
+    for db in TestDB::all() {
+        for i in 0..1000 {
+            let mut db_batch = SchemaBatch::new();
+            db_batch
+                .put::<TestSchema1>(&TestField(i), &TestField(i))
+                .unwrap();
+            db_batch
+                .put::<TestSchema2>(&TestField(i), &TestField(i))
+                .unwrap();
+            db.write_schemas(db_batch).unwrap();
+        }
+
+        db.flush_all(/* sync = */ true).unwrap();
+
+        let cf_sizes = db.get_approximate_sizes_cf().unwrap();
+        assert!(*cf_sizes.get("TestCF1").unwrap() > 0);
+        assert!(*cf_sizes.get("TestCF2").unwrap() > 0);
+        assert_eq!(*cf_sizes.get("default").unwrap(), 0);
+    }
+}
+
+#[test]
+fn test_delete_range() {
+    for db in TestDB::all() {
+        for i in 0..10 {
+            db.put::<TestSchema1>(&TestField(i), &TestField(i)).unwrap();
+            db.put::<TestSchema2>(&TestField(i), &TestField(i)).unwrap();
+        }
+
+        let mut db_batch = SchemaBatch::new();
+        db_batch
+            .delete_range::<TestSchema1>(&TestField(2), &TestField(8))
+            .unwrap();
+        db.write_schemas(db_batch).unwrap();
+
+        assert_eq!(
+            collect_values::<TestSchema1>(&db),
+            gen_expected_values(&[(0, 0), (1, 1), (8, 8), (9, 9)]),
+        );
+        assert_eq!(collect_values::<TestSchema2>(&db).len(), 10);
+    }
+}
+
+#[test]
+fn test_snapshot() {
+    for db in TestDB::all() {
+        db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+        db.put::<TestSchema2>(&TestField(0), &TestField(0)).unwrap();
+        let snapshot = db.snapshot();
 
-    for i in 0..1000 {
         let mut db_batch = SchemaBatch::new();
         db_batch
-            .put::<TestSchema1>(&TestField(i), &TestField(i))
+            .put::<TestSchema1>(&TestField(0), &TestField(1))
             .unwrap();
         db_batch
-            .put::<TestSchema2>(&TestField(i), &TestField(i))
+            .put::<TestSchema1>(&TestField(1), &TestField(1))
             .unwrap();
+        db_batch.delete::<TestSchema2>(&TestField(0)).unwrap();
         db.write_schemas(db_batch).unwrap();
+
+        // The snapshot sees none of the updates in the batch.
+        assert_eq!(
+            snapshot.get::<TestSchema1>(&TestField(0)).unwrap(),
+            Some(TestField(0)),
+        );
+        assert_eq!(snapshot.get::<TestSchema1>(&TestField(1)).unwrap(), None);
+        assert_eq!(
+            snapshot.get::<TestSchema2>(&TestField(0)).unwrap(),
+            Some(TestField(0)),
+        );
+        let mut iter = snapshot.iter::<TestSchema1>(Default::default()).unwrap();
+        iter.seek_to_first();
+        assert_eq!(
+            iter.collect::<Result<Vec<_>>>().unwrap(),
+            gen_expected_values(&[(0, 0)]),
+        );
+
+        // While the DB sees all of them.
+        assert_eq!(
+            collect_values::<TestSchema1>(&db),
+            gen_expected_values(&[(0, 1), (1, 1)]),
+        );
+        assert_eq!(db.get::<TestSchema2>(&TestField(0)).unwrap(), None);
     }
+}
 
-    db.flush_all(/* sync = */ true).unwrap();
+// A schema whose keys are prefixed by a group number, so that the keys of a group can be iterated
+// with a prefix iterator.
+define_schema!(
+    PrefixedSchema,
+    PrefixedKey,
+    TestField,
+    "PrefixedCF",
+    ColumnFamilyConfig {
+        bloom_filter_bits_per_key: Some(10),
+        fixed_prefix_len: Some(4),
+        ..ColumnFamilyConfig::DEFAULT
+    }
+);
+
+#[derive(Debug, Eq, PartialEq)]
+struct PrefixedKey {
+    group: u32,
+    index: u32,
+}
+
+impl KeyCodec<PrefixedSchema> for PrefixedKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let mut encoded = Vec::with_capacity(8);
+        encoded.write_u32::<BigEndian>(self.group)?;
+        encoded.write_u32::<BigEndian>(self.index)?;
+        Ok(encoded)
+    }
+
+    fn decode_key(data: &[u8]) -> Result<Self> {
+        let mut reader = std::io::Cursor::new(data);
+        Ok(PrefixedKey {
+            group: reader.read_u32::<BigEndian>()?,
+            index: reader.read_u32::<BigEndian>()?,
+        })
+    }
+}
+
+impl ValueCodec<PrefixedSchema> for TestField {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        self.to_bytes()
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Self::from_bytes(data)
+    }
+}
 
-    let cf_sizes = db.get_approximate_sizes_cf().unwrap();
-    assert!(*cf_sizes.get("TestCF1").unwrap() > 0);
-    assert!(*cf_sizes.get("TestCF2").unwrap() > 0);
-    assert_eq!(*cf_sizes.get("default").unwrap(), 0);
+impl SeekKeyCodec<PrefixedSchema> for u32 {
+    fn encode_seek_key(&self) -> Result<Vec<u8>> {
+        Ok(self.to_be_bytes().to_vec())
+    }
+}
+
+#[test]
+fn test_prefix_iter() {
+    let tmpdir = tempfile::tempdir().expect("Failed to create temporary directory.");
+    let cf_opts_map: ColumnFamilyOptionsMap = vec![
+        (DEFAULT_CF_NAME, ColumnFamilyOptions::default()),
+        column_family_options::<PrefixedSchema>().unwrap(),
+        column_family_options::<TestSchema1>().unwrap(),
+    ]
+    .into_iter()
+    .collect();
+    for db in vec![
+        DB::open(&tmpdir, cf_opts_map.clone()).unwrap(),
+        DB::open_in_memory(cf_opts_map),
+    ] {
+        let mut db_batch = SchemaBatch::new();
+        for group in 0..3 {
+            for index in 0..3 {
+                db_batch
+                    .put::<PrefixedSchema>(&PrefixedKey { group, index }, &TestField(group + index))
+                    .unwrap();
+            }
+        }
+        db.write_schemas(db_batch).unwrap();
+        db.flush_all(/* sync = */ true).unwrap();
+
+        let expected = (0..3)
+            .map(|index| (PrefixedKey { group: 1, index }, TestField(1 + index)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            db.prefix_iter::<PrefixedSchema, _>(&1u32)
+                .unwrap()
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+            expected,
+        );
+        assert!(db
+            .prefix_iter::<PrefixedSchema, _>(&3u32)
+            .unwrap()
+            .next()
+            .is_none());
+
+        // Regular iterators still see the keys of all the groups.
+        let mut iter = db.iter::<PrefixedSchema>(Default::default()).unwrap();
+        iter.seek(&1u32).unwrap();
+        assert_eq!(iter.count(), 6);
+
+        // Schemas without a fixed prefix length don't support prefix iterators.
+        assert!(db.prefix_iter::<TestSchema1, _>(&TestField(0)).is_err());
+    }
+}
+
+/// A backend keeping the latest value of each key in a `HashMap`, which can't be iterated.
+#[derive(Debug, Default)]
+struct HashMapBackend(RwLock<HashMap<(String, Vec<u8>), Vec<u8>>>);
+
+impl BackendReader for HashMapBackend {
+    fn get(&self, cf_name: &str, key: &[u8]) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .0
+            .read()
+            .unwrap()
+            .get(&(cf_name.to_string(), key.to_vec()))
+            .cloned())
+    }
+
+    fn iter<'a>(&'a self, _cf_name: &str, _opts: ReadOptions) -> Result<Box<dyn RawIterator + 'a>> {
+        bail!("Not supported.")
+    }
+
+    fn prefix_iter<'a>(
+        &'a self,
+        _cf_name: &str,
+        _prefix_len: usize,
+    ) -> Result<Box<dyn RawIterator + 'a>> {
+        bail!("Not supported.")
+    }
+}
+
+impl Backend for HashMapBackend {
+    fn put(&self, cf_name: &str, key: &[u8], value: &[u8]) -> Result<()> {
+        self.0
+            .write()
+            .unwrap()
+            .insert((cf_name.to_string(), key.to_vec()), value.to_vec());
+        Ok(())
+    }
+
+    fn write_batch(&self, batch: &SchemaBatch) -> Result<()> {
+        let mut map = self.0.write().unwrap();
+        for (cf_name, key, write_op) in batch.rows() {
+            let key = (cf_name.to_string(), key.to_vec());
+            match write_op {
+                WriteOp::Value(value) => {
+                    map.insert(key, value.clone());
+                }
+                WriteOp::Deletion => {
+                    map.remove(&key);
+                }
+                WriteOp::RangeDeletion { .. } => bail!("Not supported."),
+            }
+        }
+        Ok(())
+    }
+
+    fn snapshot(&self) -> Box<dyn BackendReader + Send + Sync> {
+        Box::new(HashMapBackend(RwLock::new(self.0.read().unwrap().clone())))
+    }
+
+    fn get_approximate_sizes_cf(&self) -> Result<BTreeMap<String, u64>> {
+        bail!("Not supported.")
+    }
+
+    fn flush_all(&self, _sync: bool) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_open_with_backend() {
+    let db = DB::open_with_backend(Box::new(HashMapBackend::default()));
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    let snapshot = db.snapshot();
+
+    let mut db_batch = SchemaBatch::new();
+    db_batch
+        .put::<TestSchema2>(&TestField(0), &TestField(1))
+        .unwrap();
+    db_batch.delete::<TestSchema1>(&TestField(0)).unwrap();
+    db.write_schemas(db_batch).unwrap();
+
+    assert_eq!(db.get::<TestSchema1>(&TestField(0)).unwrap(), None);
+    assert_eq!(
+        db.get::<TestSchema2>(&TestField(0)).unwrap(),
+        Some(TestField(1)),
+    );
+    assert_eq!(
+        snapshot.get::<TestSchema1>(&TestField(0)).unwrap(),
+        Some(TestField(0)),
+    );
+    assert_eq!(snapshot.get::<TestSchema2>(&TestField(0)).unwrap(), None);
+    // The errors of the backend are passed on.
+    assert!(db.iter::<TestSchema1>(Default::default()).is_err());
 }
@@ -0,0 +1,70 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::{mock_tree_store::MockTreeStore, SparseMerkleTree};
+use proptest::{collection::btree_map, prelude::*};
+
+fn iterate(
+    db: &MockTreeStore,
+    root_hash: HashValue,
+    starting_key: HashValue,
+) -> Vec<(HashValue, AccountStateBlob)> {
+    SparseMerkleIterator::new(db, root_hash, starting_key)
+        .collect::<Result<Vec<_>>>()
+        .unwrap()
+}
+
+#[test]
+fn test_iterate_empty_tree() {
+    let db = MockTreeStore::default();
+    assert!(iterate(&db, *SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue::zero()).is_empty());
+}
+
+#[test]
+fn test_iterate_missing_node() {
+    let db = MockTreeStore::default();
+    let mut iter = SparseMerkleIterator::new(&db, HashValue::random(), HashValue::zero());
+    assert!(iter.next().unwrap().is_err());
+    assert!(iter.next().is_none());
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(20))]
+
+    #[test]
+    fn test_iterate_in_order(
+        kvs in btree_map(any::<HashValue>(), any::<AccountStateBlob>(), 1..300),
+        starting_key in any::<HashValue>(),
+    ) {
+        let db = MockTreeStore::default();
+        let tree = SparseMerkleTree::new(&db);
+        let (root_hash, batch) = tree
+            .put_blob_set(
+                kvs.clone().into_iter().collect(),
+                0,                               /* version */
+                *SPARSE_MERKLE_PLACEHOLDER_HASH, /* root hash being based on */
+            )
+            .unwrap();
+        db.write_tree_update_batch(batch).unwrap();
+        let kvs = kvs.into_iter().collect::<Vec<_>>();
+
+        // From the very beginning.
+        prop_assert_eq!(&iterate(&db, root_hash, HashValue::zero()), &kvs);
+
+        // From a key that most likely doesn't exist.
+        let expected = kvs
+            .iter()
+            .filter(|(key, _)| *key >= starting_key)
+            .cloned()
+            .collect::<Vec<_>>();
+        prop_assert_eq!(iterate(&db, root_hash, starting_key), expected);
+
+        // From an existing key, which is included.
+        let existing_key = kvs[kvs.len() / 2].0;
+        prop_assert_eq!(
+            &iterate(&db, root_hash, existing_key)[..],
+            &kvs[kvs.len() / 2..]
+        );
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements [`SparseMerkleIterator`], which walks the leaves of a
+//! [`SparseMerkleTree`](crate::SparseMerkleTree) in ascending order of their keys, starting from
+//! a given key. Nodes are read lazily, so a caller can stop at any point without having loaded the
+//! rest of the tree.
+
+#[cfg(test)]
+mod iterator_test;
+
+use crate::{nibble_path::NibblePath, node_type::Node, TreeReader, ROOT_NIBBLE_HEIGHT};
+use crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
+use failure::prelude::*;
+use types::account_state_blob::AccountStateBlob;
+
+/// An iterator over the account states of the tree with a given root hash, in ascending order of
+/// their keys and starting from the smallest key greater than or equal to a given key.
+pub struct SparseMerkleIterator<'a, R: 'a + TreeReader> {
+    reader: &'a R,
+    /// The nibbles of the starting key. Subtrees whose keys are all smaller are skipped.
+    starting_nibbles: Vec<u8>,
+    starting_key: HashValue,
+    /// The nodes yet to be visited, each with its nibble path from the root. The top of the stack
+    /// is the leftmost one.
+    stack: Vec<(HashValue, Vec<u8>)>,
+}
+
+impl<'a, R> SparseMerkleIterator<'a, R>
+where
+    R: 'a + TreeReader,
+{
+    /// Creates an iterator over the tree with `root_hash`, starting from `starting_key`, which may
+    /// or may not exist in the tree.
+    pub fn new(reader: &'a R, root_hash: HashValue, starting_key: HashValue) -> Self {
+        let stack = if root_hash == *SPARSE_MERKLE_PLACEHOLDER_HASH {
+            vec![]
+        } else {
+            vec![(root_hash, vec![])]
+        };
+        Self {
+            reader,
+            starting_nibbles: NibblePath::new(starting_key.to_vec()).nibbles().collect(),
+            starting_key,
+            stack,
+        }
+    }
+
+    /// Visits the node with `node_hash` at `prefix`, returning its account state if it's a leaf in
+    /// range, otherwise pushing its children that may have leaves in range onto the stack.
+    fn visit(
+        &mut self,
+        node_hash: HashValue,
+        mut prefix: Vec<u8>,
+    ) -> Result<Option<(HashValue, AccountStateBlob)>> {
+        ensure!(
+            prefix.len() <= ROOT_NIBBLE_HEIGHT,
+            "Sparse Merkle tree has cyclic graph inside."
+        );
+        if prefix.as_slice() < &self.starting_nibbles[..prefix.len()] {
+            return Ok(None);
+        }
+
+        match self.reader.get_node(node_hash)? {
+            Node::Branch(branch_node) => {
+                // Push the children from right to left so that the leftmost one is visited first.
+                for nibble in (0..16).rev() {
+                    if let Some(child_hash) = branch_node.child(nibble) {
+                        let mut child_prefix = prefix.clone();
+                        child_prefix.push(nibble);
+                        self.stack.push((child_hash, child_prefix));
+                    }
+                }
+            }
+            Node::Extension(extension_node) => {
+                prefix.extend(extension_node.nibble_path().nibbles());
+                self.stack.push((extension_node.child(), prefix));
+            }
+            Node::Leaf(leaf_node) => {
+                if leaf_node.key() >= self.starting_key {
+                    return Ok(Some((
+                        leaf_node.key(),
+                        self.reader.get_blob(leaf_node.value_hash())?,
+                    )));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<'a, R> Iterator for SparseMerkleIterator<'a, R>
+where
+    R: 'a + TreeReader,
+{
+    type Item = Result<(HashValue, AccountStateBlob)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node_hash, prefix)) = self.stack.pop() {
+            match self.visit(node_hash, prefix) {
+                Ok(Some(account_state)) => return Some(Ok(account_state)),
+                Ok(None) => (),
+                Err(err) => {
+                    // Stop at the first error, the rest of the tree can't be trusted.
+                    self.stack.clear();
+                    return Some(Err(err));
+                }
+            }
+        }
+        None
+    }
+}
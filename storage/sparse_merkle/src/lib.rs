@@ -7,9 +7,12 @@
 //! results directly. The public APIs are only [`new`](SparseMerkleTree::new),
 //! [`put_blob_sets`](SparseMerkleTree::put_blob_sets),
 //! [`put_keyed_blob_set`](SparseMerkleTree::put_keyed_blob_set) and
-//! [`get_with_proof`](SparseMerkleTree::get_with_proof). After each put with a `keyed_blob_set`
-//! based on a known root, the tree will return a new root hash with a [`TreeUpdateBatch`]
-//! containing all newly generated tree nodes and blobs.
+//! [`get_with_proof`](SparseMerkleTree::get_with_proof),
+//! [`get_account_states`](SparseMerkleTree::get_account_states) and
+//! [`get_range_with_proof`](SparseMerkleTree::get_range_with_proof). Leaves can also be walked in
+//! order with a [`SparseMerkleIterator`](iterator::SparseMerkleIterator). After each put with a
+//! `keyed_blob_set` based on a known root, the tree will return a new root hash with a
+//! [`TreeUpdateBatch`] containing all newly generated tree nodes and blobs.
 //!
 //! The sparse Merkle tree itself logically is a 256-bit Merkle tree with an optimization
 //! that any subtree containing 0 or 1 leaf node will be replaced by that leaf node or a placeholder
@@ -19,6 +22,7 @@
 
 #![allow(clippy::unit_arg)]
 
+pub mod iterator;
 #[cfg(test)]
 mod mock_tree_store;
 mod nibble_path;
@@ -33,6 +37,7 @@ use crypto::{
     HashValue,
 };
 use failure::prelude::*;
+use iterator::SparseMerkleIterator;
 use nibble_path::{skip_common_prefix, NibbleIterator, NibblePath};
 use node_type::{BranchNode, ExtensionNode, LeafNode, Node};
 use num_derive::{FromPrimitive, ToPrimitive};
@@ -40,7 +45,8 @@ use proptest_derive::Arbitrary;
 use std::collections::{HashMap, HashSet};
 use tree_cache::TreeCache;
 use types::{
-    account_state_blob::AccountStateBlob, proof::definition::SparseMerkleProof,
+    account_state_blob::AccountStateBlob,
+    proof::definition::{SparseMerkleProof, SparseMerkleRangeProof},
     transaction::Version,
 };
 
@@ -575,6 +581,56 @@ where
         bail!("Sparse Merkle tree has cyclic graph inside.");
     }
 
+    /// Returns up to `limit` account states of the tree with `root_hash` in ascending order of
+    /// their keys, starting from the smallest key greater than `start_key`, or from the smallest
+    /// key in the tree if `start_key` is `None`.
+    pub fn get_account_states(
+        &self,
+        root_hash: HashValue,
+        start_key: Option<HashValue>,
+        limit: usize,
+    ) -> Result<Vec<(HashValue, AccountStateBlob)>> {
+        SparseMerkleIterator::new(
+            self.reader,
+            root_hash,
+            start_key.unwrap_or_else(HashValue::zero),
+        )
+        // The iterator starts from `start_key` itself if it exists.
+        .skip_while(|account_state| match (account_state, start_key) {
+            (Ok((key, _)), Some(start_key)) => *key == start_key,
+            _ => false,
+        })
+        .take(limit)
+        .collect()
+    }
+
+    /// Returns up to `limit` account states of the tree with `root_hash` in ascending order of
+    /// their keys, starting from the smallest key greater than or equal to `start_key`, and the
+    /// proof that no account state in between is left out. If fewer than `limit` are returned,
+    /// the proof also shows there is no more account state in the tree.
+    pub fn get_range_with_proof(
+        &self,
+        root_hash: HashValue,
+        start_key: HashValue,
+        limit: usize,
+    ) -> Result<(Vec<(HashValue, AccountStateBlob)>, SparseMerkleRangeProof)> {
+        let account_states = SparseMerkleIterator::new(self.reader, root_hash, start_key)
+            .take(limit)
+            .collect::<Result<Vec<_>>>()?;
+        let (_, first_key_proof) = self.get_with_proof(start_key, root_hash)?;
+        let last_key_proof = account_states
+            .last()
+            .map(|(last_key, _)| {
+                self.get_with_proof(*last_key, root_hash)
+                    .map(|(_, proof)| proof)
+            })
+            .transpose()?;
+        Ok((
+            account_states,
+            SparseMerkleRangeProof::new(first_key_proof, last_key_proof),
+        ))
+    }
+
     #[cfg(test)]
     pub fn get(&self, key: HashValue, root_hash: HashValue) -> Result<Option<AccountStateBlob>> {
         Ok(self.get_with_proof(key, root_hash)?.0)
@@ -5,7 +5,7 @@ use super::*;
 use crypto::{hash::SPARSE_MERKLE_PLACEHOLDER_HASH, HashValue};
 use mock_tree_store::MockTreeStore;
 use rand::{rngs::StdRng, Rng, SeedableRng};
-use types::proof::verify_sparse_merkle_element;
+use types::proof::{verify_sparse_merkle_element, verify_sparse_merkle_range};
 
 fn modify(original_key: &HashValue, n: usize, value: u8) -> HashValue {
     let mut key = original_key.to_vec();
@@ -648,6 +648,141 @@ fn many_keys_get_proof_and_verify_tree_root(seed: &[u8], num_keys: usize) {
     }
 }
 
+#[test]
+fn test_get_account_states_in_order() {
+    let seed: &[_] = &[5, 6, 7, 8];
+    let mut actual_seed = [0u8; 32];
+    actual_seed[..seed.len()].copy_from_slice(&seed);
+    let mut rng: StdRng = StdRng::from_seed(actual_seed);
+
+    let db = MockTreeStore::default();
+    let tree = SparseMerkleTree::new(&db);
+    assert!(tree
+        .get_account_states(*SPARSE_MERKLE_PLACEHOLDER_HASH, None, 10)
+        .unwrap()
+        .is_empty());
+
+    let mut kvs = vec![];
+    for _i in 0..300 {
+        let key = HashValue::random_with_rng(&mut rng);
+        let value = AccountStateBlob::from(HashValue::random_with_rng(&mut rng).to_vec());
+        kvs.push((key, value));
+    }
+    let (root, batch) = tree
+        .put_blob_set(
+            kvs.clone(),
+            0,                               /* version */
+            *SPARSE_MERKLE_PLACEHOLDER_HASH, /* root hash being based on */
+        )
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+    kvs.sort_by_key(|(key, _)| *key);
+
+    // Read all the account states back in chunks of various sizes.
+    for chunk_size in &[1, 7, 100, 300, 1000] {
+        let mut start_key = None;
+        let mut account_states = vec![];
+        loop {
+            let chunk = tree
+                .get_account_states(root, start_key, *chunk_size)
+                .unwrap();
+            assert!(chunk.len() <= *chunk_size);
+            start_key = chunk.last().map(|(key, _)| *key);
+            let is_last = chunk.len() < *chunk_size;
+            account_states.extend(chunk);
+            if is_last {
+                break;
+            }
+        }
+        assert_eq!(account_states, kvs);
+    }
+
+    // Start from a key that is not in the tree.
+    let start_key = modify(&kvs[100].0, HashValue::LENGTH - 1, 0xff);
+    let expected: Vec<_> = kvs
+        .iter()
+        .filter(|(key, _)| *key > start_key)
+        .take(10)
+        .cloned()
+        .collect();
+    assert_eq!(
+        tree.get_account_states(root, Some(start_key), 10).unwrap(),
+        expected
+    );
+}
+
+/// Returns the smallest key greater than `key`, which must not be the largest possible one.
+fn next_key(key: &HashValue) -> HashValue {
+    let mut bytes = key.to_vec();
+    let pos = bytes.iter().rposition(|byte| *byte != 0xff).unwrap();
+    bytes[pos] += 1;
+    bytes[pos + 1..].iter_mut().for_each(|byte| *byte = 0);
+    HashValue::from_slice(&bytes).unwrap()
+}
+
+#[test]
+fn test_get_range_with_proof() {
+    let seed: &[_] = &[9, 10, 11, 12];
+    let mut actual_seed = [0u8; 32];
+    actual_seed[..seed.len()].copy_from_slice(&seed);
+    let mut rng: StdRng = StdRng::from_seed(actual_seed);
+
+    let db = MockTreeStore::default();
+    let tree = SparseMerkleTree::new(&db);
+    let empty_root = *SPARSE_MERKLE_PLACEHOLDER_HASH;
+    let (account_states, proof) = tree
+        .get_range_with_proof(empty_root, HashValue::zero(), 10)
+        .unwrap();
+    assert!(account_states.is_empty());
+    assert!(
+        verify_sparse_merkle_range(empty_root, HashValue::zero(), &account_states, &proof).is_ok()
+    );
+
+    let mut kvs = vec![];
+    for _i in 0..300 {
+        let key = HashValue::random_with_rng(&mut rng);
+        let value = AccountStateBlob::from(HashValue::random_with_rng(&mut rng).to_vec());
+        kvs.push((key, value));
+    }
+    let (root, batch) = tree
+        .put_blob_set(
+            kvs.clone(),
+            0,                               /* version */
+            *SPARSE_MERKLE_PLACEHOLDER_HASH, /* root hash being based on */
+        )
+        .unwrap();
+    db.write_tree_update_batch(batch).unwrap();
+    kvs.sort_by_key(|(key, _)| *key);
+
+    // Walk the whole tree in chunks, each starting right after the previous one.
+    for chunk_size in &[1, 7, 100, 300, 1000] {
+        let mut start_key = HashValue::zero();
+        let mut account_states = vec![];
+        loop {
+            let (chunk, proof) = tree
+                .get_range_with_proof(root, start_key, *chunk_size)
+                .unwrap();
+            assert!(verify_sparse_merkle_range(root, start_key, &chunk, &proof).is_ok());
+            let is_last = chunk.len() < *chunk_size;
+            if let Some((last_key, _)) = chunk.last() {
+                start_key = next_key(last_key);
+            }
+            account_states.extend(chunk);
+            if is_last {
+                break;
+            }
+        }
+        assert_eq!(account_states, kvs);
+    }
+
+    // A range with an account state left out doesn't verify.
+    let start_key = kvs[100].0;
+    let (mut chunk, proof) = tree.get_range_with_proof(root, start_key, 10).unwrap();
+    assert_eq!(chunk, &kvs[100..110]);
+    chunk.remove(5);
+    assert!(verify_sparse_merkle_range(root, start_key, &chunk, &proof).is_err());
+}
+
 #[test]
 fn test_1000_keys() {
     let seed: &[_] = &[1, 2, 3, 4];
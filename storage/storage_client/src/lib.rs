@@ -11,26 +11,33 @@ mod state_view;
 
 use crypto::HashValue;
 use failure::prelude::*;
-use futures::{compat::Future01CompatExt, executor::block_on, prelude::*};
+use futures::{
+    compat::{Future01CompatExt, Stream01CompatExt},
+    executor::block_on,
+    prelude::*,
+};
 use futures_01::future::Future as Future01;
 use grpcio::{ChannelBuilder, Environment};
 use proto_conv::{FromProto, IntoProto};
 use std::{pin::Pin, sync::Arc};
 use storage_proto::{
     proto::{storage::GetExecutorStartupInfoRequest, storage_grpc},
-    ExecutorStartupInfo, GetAccountStateWithProofByStateRootRequest,
+    ExecutorStartupInfo, FinishStateSnapshotRequest, GetAccountStateChunkRequest,
+    GetAccountStateChunkResponse, GetAccountStateWithProofByStateRootRequest,
     GetAccountStateWithProofByStateRootResponse, GetExecutorStartupInfoResponse,
-    GetTransactionsRequest, GetTransactionsResponse, SaveTransactionsRequest,
+    GetTransactionsRequest, GetTransactionsResponse, RestoreAccountStateChunkRequest,
+    RestoreAccountStateChunkResponse, SaveTransactionsRequest, StreamAccountStatesRequest,
+    StreamAccountStatesResponse, StreamTransactionsRequest, StreamTransactionsResponse,
 };
 use types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     get_with_proof::{
         RequestItem, ResponseItem, UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse,
     },
     ledger_info::LedgerInfoWithSignatures,
-    proof::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::{AccumulatorProof, SparseMerkleProof},
+    transaction::{TransactionInfo, TransactionListWithProof, TransactionToCommit, Version},
     validator_change::ValidatorChangeEventWithProof,
 };
 
@@ -57,6 +64,40 @@ impl StorageReadServiceClient {
         let client = storage_grpc::StorageClient::new(channel);
         StorageReadServiceClient { client }
     }
+
+    /// Streams the committed transactions from `start_version` on, each with its info and events.
+    /// The stream keeps following new commits and never ends by itself.
+    pub fn stream_transactions(
+        &self,
+        start_version: Version,
+    ) -> Result<impl Stream<Item = Result<StreamTransactionsResponse>>> {
+        let req = StreamTransactionsRequest::new(start_version);
+        Ok(self
+            .client
+            .stream_transactions(&req.into_proto())
+            .map_err(convert_grpc_err)?
+            .compat()
+            .map(|resp| StreamTransactionsResponse::from_proto(resp.map_err(convert_grpc_err)?)))
+    }
+
+    /// Streams all the account states of the state snapshot at `version` in ascending order of the
+    /// account address hashes, in chunks.
+    pub fn stream_account_states(
+        &self,
+        version: Version,
+    ) -> Result<impl Stream<Item = Result<Vec<(HashValue, AccountStateBlob)>>>> {
+        let req = StreamAccountStatesRequest::new(version);
+        Ok(self
+            .client
+            .stream_account_states(&req.into_proto())
+            .map_err(convert_grpc_err)?
+            .compat()
+            .map(|resp| {
+                let resp =
+                    StreamAccountStatesResponse::from_proto(resp.map_err(convert_grpc_err)?)?;
+                Ok(resp.account_states)
+            }))
+    }
 }
 
 impl StorageRead for StorageReadServiceClient {
@@ -161,6 +202,30 @@ impl StorageRead for StorageReadServiceClient {
         .boxed()
     }
 
+    fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        block_on(self.get_account_state_chunk_with_proof_async(version, start_key, limit))
+    }
+
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>> {
+        let req = GetAccountStateChunkRequest::new(version, start_key, limit);
+        convert_grpc_response(self.client.get_account_state_chunk_async(&req.into_proto()))
+            .map(|resp| {
+                let resp = GetAccountStateChunkResponse::from_proto(resp?)?;
+                Ok(resp.account_state_chunk_with_proof)
+            })
+            .boxed()
+    }
+
     fn get_executor_startup_info(&self) -> Result<Option<ExecutorStartupInfo>> {
         block_on(self.get_executor_startup_info_async())
     }
@@ -215,6 +280,66 @@ impl StorageWrite for StorageWriteServiceClient {
             .map_ok(|_| ())
             .boxed()
     }
+
+    fn restore_account_state_chunk(
+        &self,
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Result<HashValue> {
+        block_on(self.restore_account_state_chunk_async(version, account_states, state_root_hash))
+    }
+
+    fn restore_account_state_chunk_async(
+        &self,
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Pin<Box<dyn Future<Output = Result<HashValue>> + Send>> {
+        let req = RestoreAccountStateChunkRequest::new(version, account_states, state_root_hash);
+        convert_grpc_response(
+            self.client
+                .restore_account_state_chunk_async(&req.into_proto()),
+        )
+        .map(|resp| {
+            let resp = RestoreAccountStateChunkResponse::from_proto(resp?)?;
+            Ok(resp.new_state_root_hash)
+        })
+        .boxed()
+    }
+
+    fn finish_state_snapshot(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()> {
+        block_on(self.finish_state_snapshot_async(
+            ledger_info_with_sigs,
+            transaction_info,
+            ledger_info_to_transaction_info_proof,
+            ledger_frozen_subtree_hashes,
+        ))
+    }
+
+    fn finish_state_snapshot_async(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>> {
+        let req = FinishStateSnapshotRequest::new(
+            ledger_info_with_sigs,
+            transaction_info,
+            ledger_info_to_transaction_info_proof,
+            ledger_frozen_subtree_hashes,
+        );
+        convert_grpc_response(self.client.finish_state_snapshot_async(&req.into_proto()))
+            .map_ok(|_| ())
+            .boxed()
+    }
 }
 
 /// This trait defines interfaces to be implemented by a storage read client.
@@ -299,6 +424,28 @@ pub trait StorageRead: Send + Sync {
         state_root_hash: HashValue,
     ) -> Pin<Box<dyn Future<Output = Result<(Option<AccountStateBlob>, SparseMerkleProof)>> + Send>>;
 
+    /// See [`LibraDB::get_account_state_chunk_with_proof`].
+    ///
+    /// [`LibraDB::get_account_state_chunk_with_proof`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk_with_proof
+    fn get_account_state_chunk_with_proof(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Result<AccountStateChunkWithProof>;
+
+    /// See [`LibraDB::get_account_state_chunk_with_proof`].
+    ///
+    /// [`LibraDB::get_account_state_chunk_with_proof`]:
+    /// ../libradb/struct.LibraDB.html#method.get_account_state_chunk_with_proof
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        version: Version,
+        start_key: Option<HashValue>,
+        limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>>;
+
     /// See [`LibraDB::get_executor_startup_info`].
     ///
     /// [`LibraDB::get_executor_startup_info`]:
@@ -339,6 +486,52 @@ pub trait StorageWrite: Send + Sync {
         first_version: Version,
         ledger_info_with_sigs: Option<LedgerInfoWithSignatures>,
     ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
+
+    /// See [`LibraDB::restore_account_state_chunk`].
+    ///
+    /// [`LibraDB::restore_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.restore_account_state_chunk
+    fn restore_account_state_chunk(
+        &self,
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Result<HashValue>;
+
+    /// See [`LibraDB::restore_account_state_chunk`].
+    ///
+    /// [`LibraDB::restore_account_state_chunk`]:
+    /// ../libradb/struct.LibraDB.html#method.restore_account_state_chunk
+    fn restore_account_state_chunk_async(
+        &self,
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Pin<Box<dyn Future<Output = Result<HashValue>> + Send>>;
+
+    /// See [`LibraDB::finish_state_snapshot`].
+    ///
+    /// [`LibraDB::finish_state_snapshot`]:
+    /// ../libradb/struct.LibraDB.html#method.finish_state_snapshot
+    fn finish_state_snapshot(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Result<()>;
+
+    /// See [`LibraDB::finish_state_snapshot`].
+    ///
+    /// [`LibraDB::finish_state_snapshot`]:
+    /// ../libradb/struct.LibraDB.html#method.finish_state_snapshot
+    fn finish_state_snapshot_async(
+        &self,
+        ledger_info_with_sigs: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Pin<Box<dyn Future<Output = Result<()>> + Send>>;
 }
 
 fn convert_grpc_err(e: grpcio::Error) -> Error {
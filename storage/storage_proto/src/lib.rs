@@ -33,10 +33,13 @@ use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
 use types::{
     account_address::AccountAddress,
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
+    contract_event::ContractEvent,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
-    proof::definition::SparseMerkleProof,
-    transaction::{TransactionListWithProof, TransactionToCommit, Version},
+    proof::definition::{AccumulatorProof, SparseMerkleProof},
+    transaction::{
+        SignedTransaction, TransactionInfo, TransactionListWithProof, TransactionToCommit, Version,
+    },
 };
 
 /// Helper to construct and parse [`proto::storage::GetAccountStateWithProofByStateRootRequest`]
@@ -273,6 +276,239 @@ impl GetTransactionsResponse {
     }
 }
 
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct GetAccountStateChunkRequest {
+    pub version: Version,
+    pub start_key: Option<HashValue>,
+    pub limit: u64,
+}
+
+impl GetAccountStateChunkRequest {
+    /// Constructor.
+    pub fn new(version: Version, start_key: Option<HashValue>, limit: u64) -> Self {
+        GetAccountStateChunkRequest {
+            version,
+            start_key,
+            limit,
+        }
+    }
+}
+
+impl FromProto for GetAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let start_key = object.take_start_key();
+        Ok(GetAccountStateChunkRequest {
+            version: object.get_version(),
+            start_key: if start_key.is_empty() {
+                None
+            } else {
+                Some(HashValue::from_proto(start_key)?)
+            },
+            limit: object.get_limit(),
+        })
+    }
+}
+
+impl IntoProto for GetAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::GetAccountStateChunkRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_version(self.version);
+        if let Some(start_key) = self.start_key {
+            out.set_start_key(start_key.into_proto());
+        }
+        out.set_limit(self.limit);
+        out
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::GetAccountStateChunkResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::storage::GetAccountStateChunkResponse)]
+pub struct GetAccountStateChunkResponse {
+    pub account_state_chunk_with_proof: AccountStateChunkWithProof,
+}
+
+impl GetAccountStateChunkResponse {
+    /// Constructor.
+    pub fn new(account_state_chunk_with_proof: AccountStateChunkWithProof) -> Self {
+        GetAccountStateChunkResponse {
+            account_state_chunk_with_proof,
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::RestoreAccountStateChunkRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct RestoreAccountStateChunkRequest {
+    pub version: Version,
+    pub account_states: Vec<(HashValue, AccountStateBlob)>,
+    pub state_root_hash: HashValue,
+}
+
+impl RestoreAccountStateChunkRequest {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        account_states: Vec<(HashValue, AccountStateBlob)>,
+        state_root_hash: HashValue,
+    ) -> Self {
+        RestoreAccountStateChunkRequest {
+            version,
+            account_states,
+            state_root_hash,
+        }
+    }
+}
+
+impl FromProto for RestoreAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::RestoreAccountStateChunkRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let account_states = object
+            .take_account_states()
+            .into_iter()
+            .map(|mut keyed_blob| {
+                Ok((
+                    HashValue::from_proto(keyed_blob.take_key())?,
+                    AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RestoreAccountStateChunkRequest {
+            version: object.get_version(),
+            account_states,
+            state_root_hash: HashValue::from_proto(object.take_state_root_hash())?,
+        })
+    }
+}
+
+impl IntoProto for RestoreAccountStateChunkRequest {
+    type ProtoType = crate::proto::storage::RestoreAccountStateChunkRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_version(self.version);
+        out.set_account_states(protobuf::RepeatedField::from_vec(
+            self.account_states
+                .into_iter()
+                .map(|(key, blob)| {
+                    let mut keyed_blob =
+                        types::proto::account_state_blob::KeyedAccountStateBlob::new();
+                    keyed_blob.set_key(key.into_proto());
+                    keyed_blob.set_blob(blob.into_proto());
+                    keyed_blob
+                })
+                .collect::<Vec<_>>(),
+        ));
+        out.set_state_root_hash(self.state_root_hash.into_proto());
+        out
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::RestoreAccountStateChunkResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::storage::RestoreAccountStateChunkResponse)]
+pub struct RestoreAccountStateChunkResponse {
+    pub new_state_root_hash: HashValue,
+}
+
+impl RestoreAccountStateChunkResponse {
+    /// Constructor.
+    pub fn new(new_state_root_hash: HashValue) -> Self {
+        RestoreAccountStateChunkResponse {
+            new_state_root_hash,
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::FinishStateSnapshotRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct FinishStateSnapshotRequest {
+    pub ledger_info_with_signatures: LedgerInfoWithSignatures,
+    pub transaction_info: TransactionInfo,
+    pub ledger_info_to_transaction_info_proof: AccumulatorProof,
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl FinishStateSnapshotRequest {
+    /// Constructor.
+    pub fn new(
+        ledger_info_with_signatures: LedgerInfoWithSignatures,
+        transaction_info: TransactionInfo,
+        ledger_info_to_transaction_info_proof: AccumulatorProof,
+        ledger_frozen_subtree_hashes: Vec<HashValue>,
+    ) -> Self {
+        FinishStateSnapshotRequest {
+            ledger_info_with_signatures,
+            transaction_info,
+            ledger_info_to_transaction_info_proof,
+            ledger_frozen_subtree_hashes,
+        }
+    }
+}
+
+impl FromProto for FinishStateSnapshotRequest {
+    type ProtoType = crate::proto::storage::FinishStateSnapshotRequest;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(FinishStateSnapshotRequest {
+            ledger_info_with_signatures: LedgerInfoWithSignatures::from_proto(
+                object.take_ledger_info_with_signatures(),
+            )?,
+            transaction_info: TransactionInfo::from_proto(object.take_transaction_info())?,
+            ledger_info_to_transaction_info_proof: AccumulatorProof::from_proto(
+                object.take_ledger_info_to_transaction_info_proof(),
+            )?,
+            ledger_frozen_subtree_hashes: object
+                .take_ledger_frozen_subtree_hashes()
+                .into_iter()
+                .map(HashValue::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+impl IntoProto for FinishStateSnapshotRequest {
+    type ProtoType = crate::proto::storage::FinishStateSnapshotRequest;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_ledger_info_with_signatures(self.ledger_info_with_signatures.into_proto());
+        out.set_transaction_info(self.transaction_info.into_proto());
+        out.set_ledger_info_to_transaction_info_proof(
+            self.ledger_info_to_transaction_info_proof.into_proto(),
+        );
+        out.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+            self.ledger_frozen_subtree_hashes
+                .into_iter()
+                .map(HashValue::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        out
+    }
+}
+
 /// Helper to construct and parse [`proto::storage::ExecutorStartupInfo`]
 ///
 /// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
@@ -360,6 +596,126 @@ impl IntoProto for GetExecutorStartupInfoResponse {
     }
 }
 
+/// Helper to construct and parse [`proto::storage::StreamTransactionsRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::storage::StreamTransactionsRequest)]
+pub struct StreamTransactionsRequest {
+    pub start_version: Version,
+}
+
+impl StreamTransactionsRequest {
+    /// Constructor.
+    pub fn new(start_version: Version) -> Self {
+        StreamTransactionsRequest { start_version }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::StreamTransactionsResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::storage::StreamTransactionsResponse)]
+pub struct StreamTransactionsResponse {
+    pub version: Version,
+    pub signed_transaction: SignedTransaction,
+    pub transaction_info: TransactionInfo,
+    pub events: Vec<ContractEvent>,
+}
+
+impl StreamTransactionsResponse {
+    /// Constructor.
+    pub fn new(
+        version: Version,
+        signed_transaction: SignedTransaction,
+        transaction_info: TransactionInfo,
+        events: Vec<ContractEvent>,
+    ) -> Self {
+        StreamTransactionsResponse {
+            version,
+            signed_transaction,
+            transaction_info,
+            events,
+        }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::StreamAccountStatesRequest`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq, FromProto, IntoProto)]
+#[ProtoType(crate::proto::storage::StreamAccountStatesRequest)]
+pub struct StreamAccountStatesRequest {
+    pub version: Version,
+}
+
+impl StreamAccountStatesRequest {
+    /// Constructor.
+    pub fn new(version: Version) -> Self {
+        StreamAccountStatesRequest { version }
+    }
+}
+
+/// Helper to construct and parse [`proto::storage::StreamAccountStatesResponse`]
+///
+/// It does so by implementing [`IntoProto`](#impl-IntoProto) and [`FromProto`](#impl-FromProto),
+/// providing [`into_proto`](IntoProto::into_proto) and [`from_proto`](FromProto::from_proto).
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct StreamAccountStatesResponse {
+    pub account_states: Vec<(HashValue, AccountStateBlob)>,
+}
+
+impl StreamAccountStatesResponse {
+    /// Constructor.
+    pub fn new(account_states: Vec<(HashValue, AccountStateBlob)>) -> Self {
+        StreamAccountStatesResponse { account_states }
+    }
+}
+
+impl FromProto for StreamAccountStatesResponse {
+    type ProtoType = crate::proto::storage::StreamAccountStatesResponse;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let account_states = object
+            .take_account_states()
+            .into_iter()
+            .map(|mut keyed_blob| {
+                Ok((
+                    HashValue::from_proto(keyed_blob.take_key())?,
+                    AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(StreamAccountStatesResponse { account_states })
+    }
+}
+
+impl IntoProto for StreamAccountStatesResponse {
+    type ProtoType = crate::proto::storage::StreamAccountStatesResponse;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_account_states(protobuf::RepeatedField::from_vec(
+            self.account_states
+                .into_iter()
+                .map(|(key, blob)| {
+                    let mut keyed_blob =
+                        types::proto::account_state_blob::KeyedAccountStateBlob::new();
+                    keyed_blob.set_key(key.into_proto());
+                    keyed_blob.set_blob(blob.into_proto());
+                    keyed_blob
+                })
+                .collect::<Vec<_>>(),
+        ));
+        out
+    }
+}
+
 pub mod prelude {
     pub use super::*;
 }
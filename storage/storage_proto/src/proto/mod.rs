@@ -3,7 +3,9 @@
 
 #![allow(bare_trait_objects)]
 
-use types::proto::{account_state_blob, get_with_proof, ledger_info, proof, transaction};
+use types::proto::{
+    account_state_blob, events, get_with_proof, ledger_info, proof, transaction, transaction_info,
+};
 
 pub mod storage;
 pub mod storage_grpc;
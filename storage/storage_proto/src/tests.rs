@@ -23,6 +23,35 @@ proptest! {
         assert_protobuf_encode_decode(&resp);
     }
 
+    #[test]
+    fn test_get_account_state_chunk_request(req in any::<GetAccountStateChunkRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_get_account_state_chunk_response(resp in any::<GetAccountStateChunkResponse>()) {
+        assert_protobuf_encode_decode(&resp);
+    }
+
+    #[test]
+    fn test_restore_account_state_chunk_request(
+        req in any::<RestoreAccountStateChunkRequest>(),
+    ) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_restore_account_state_chunk_response(
+        resp in any::<RestoreAccountStateChunkResponse>(),
+    ) {
+        assert_protobuf_encode_decode(&resp);
+    }
+
+    #[test]
+    fn test_finish_state_snapshot_request(req in any::<FinishStateSnapshotRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
     #[test]
     fn test_executor_startup_info(executor_startup_info in any::<ExecutorStartupInfo>()) {
         assert_protobuf_encode_decode(&executor_startup_info);
@@ -32,4 +61,24 @@ proptest! {
     fn test_get_executor_startup_info_response(res in any::<GetExecutorStartupInfoResponse>()) {
         assert_protobuf_encode_decode(&res);
     }
+
+    #[test]
+    fn test_stream_transactions_request(req in any::<StreamTransactionsRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_stream_transactions_response(resp in any::<StreamTransactionsResponse>()) {
+        assert_protobuf_encode_decode(&resp);
+    }
+
+    #[test]
+    fn test_stream_account_states_request(req in any::<StreamAccountStatesRequest>()) {
+        assert_protobuf_encode_decode(&req);
+    }
+
+    #[test]
+    fn test_stream_account_states_response(resp in any::<StreamAccountStatesResponse>()) {
+        assert_protobuf_encode_decode(&resp);
+    }
 }
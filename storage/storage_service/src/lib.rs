@@ -8,10 +8,14 @@
 //! [`StorageClient`](../storage_proto/proto/storage_grpc/struct.StorageClient.html) directly.
 
 pub mod mocks;
+mod streams;
 
 use config::config::NodeConfig;
 use failure::prelude::*;
-use grpc_helpers::{provide_grpc_response, spawn_service_thread_with_drop_closure, ServerHandle};
+use grpc_helpers::{
+    provide_grpc_response, provide_grpc_stream, spawn_service_thread_with_drop_closure,
+    ServerHandle,
+};
 use libradb::LibraDB;
 use logger::prelude::*;
 use metrics::counters::SVC_COUNTERS;
@@ -23,17 +27,25 @@ use std::{
 };
 use storage_proto::proto::{
     storage::{
-        GetAccountStateWithProofByStateRootRequest, GetAccountStateWithProofByStateRootResponse,
-        GetExecutorStartupInfoRequest, GetExecutorStartupInfoResponse, GetTransactionsRequest,
-        GetTransactionsResponse, SaveTransactionsRequest, SaveTransactionsResponse,
+        FinishStateSnapshotRequest, FinishStateSnapshotResponse, GetAccountStateChunkRequest,
+        GetAccountStateChunkResponse, GetAccountStateWithProofByStateRootRequest,
+        GetAccountStateWithProofByStateRootResponse, GetExecutorStartupInfoRequest,
+        GetExecutorStartupInfoResponse, GetTransactionsRequest, GetTransactionsResponse,
+        RestoreAccountStateChunkRequest, RestoreAccountStateChunkResponse, SaveTransactionsRequest,
+        SaveTransactionsResponse, StreamAccountStatesRequest, StreamAccountStatesResponse,
+        StreamTransactionsRequest, StreamTransactionsResponse,
     },
     storage_grpc::{create_storage, Storage},
 };
+use streams::{AccountStateStream, CommitNotifier, TransactionStream};
 use types::proto::get_with_proof::{UpdateToLatestLedgerRequest, UpdateToLatestLedgerResponse};
 
 /// Starts storage service according to config.
 pub fn start_storage_service(config: &NodeConfig) -> ServerHandle {
-    let (storage_service, shutdown_receiver) = StorageService::new(&config.storage.get_dir());
+    let (storage_service, shutdown_receiver) = StorageService::new_with_prune_window(
+        &config.storage.get_dir(),
+        config.storage.get_prune_window(),
+    );
     spawn_service_thread_with_drop_closure(
         create_storage(storage_service),
         config.storage.address.clone(),
@@ -54,6 +66,7 @@ pub fn start_storage_service(config: &NodeConfig) -> ServerHandle {
 #[derive(Clone)]
 pub struct StorageService {
     db: Arc<LibraDBWrapper>,
+    commit_notifier: Arc<CommitNotifier>,
 }
 
 /// When dropping GRPC server we want to wait until LibraDB is dropped first, so the RocksDB
@@ -72,8 +85,8 @@ struct LibraDBWrapper {
 }
 
 impl LibraDBWrapper {
-    pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let db = LibraDB::new(path);
+    pub fn new<P: AsRef<Path>>(path: &P, prune_window: Option<u64>) -> (Self, mpsc::Receiver<()>) {
+        let db = LibraDB::new_with_prune_window(path, prune_window);
         let (shutdown_sender, shutdown_receiver) = mpsc::channel();
         (
             Self {
@@ -125,10 +138,20 @@ impl StorageService {
     ///    // LibraDB instance is guaranteed to be properly dropped at this point.
     /// ```
     pub fn new<P: AsRef<Path>>(path: &P) -> (Self, mpsc::Receiver<()>) {
-        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path);
+        Self::new_with_prune_window(path, None)
+    }
+
+    /// Same as [`new`](StorageService::new), but the [`LibraDB`] only keeps the account states
+    /// of the latest `prune_window` versions when it is set.
+    pub fn new_with_prune_window<P: AsRef<Path>>(
+        path: &P,
+        prune_window: Option<u64>,
+    ) -> (Self, mpsc::Receiver<()>) {
+        let (db_wrapper, shutdown_receiver) = LibraDBWrapper::new(path, prune_window);
         (
             Self {
                 db: Arc::new(db_wrapper),
+                commit_notifier: Arc::new(CommitNotifier::default()),
             },
             shutdown_receiver,
         )
@@ -203,9 +226,56 @@ impl StorageService {
             rust_req.first_version,
             &rust_req.ledger_info_with_signatures,
         )?;
+        self.commit_notifier.notify();
         Ok(SaveTransactionsResponse::new())
     }
 
+    fn get_account_state_chunk_inner(
+        &self,
+        req: GetAccountStateChunkRequest,
+    ) -> Result<GetAccountStateChunkResponse> {
+        let rust_req = storage_proto::GetAccountStateChunkRequest::from_proto(req)?;
+
+        let account_state_chunk_with_proof = self.db.get_account_state_chunk_with_proof(
+            rust_req.version,
+            rust_req.start_key,
+            rust_req.limit,
+        )?;
+
+        let rust_resp =
+            storage_proto::GetAccountStateChunkResponse::new(account_state_chunk_with_proof);
+
+        Ok(rust_resp.into_proto())
+    }
+
+    fn restore_account_state_chunk_inner(
+        &self,
+        req: RestoreAccountStateChunkRequest,
+    ) -> Result<RestoreAccountStateChunkResponse> {
+        let rust_req = storage_proto::RestoreAccountStateChunkRequest::from_proto(req)?;
+        let new_state_root_hash = self.db.restore_account_state_chunk(
+            rust_req.version,
+            rust_req.account_states,
+            rust_req.state_root_hash,
+        )?;
+        let rust_resp = storage_proto::RestoreAccountStateChunkResponse::new(new_state_root_hash);
+        Ok(rust_resp.into_proto())
+    }
+
+    fn finish_state_snapshot_inner(
+        &self,
+        req: FinishStateSnapshotRequest,
+    ) -> Result<FinishStateSnapshotResponse> {
+        let rust_req = storage_proto::FinishStateSnapshotRequest::from_proto(req)?;
+        self.db.finish_state_snapshot(
+            &rust_req.ledger_info_with_signatures,
+            &rust_req.transaction_info,
+            &rust_req.ledger_info_to_transaction_info_proof,
+            &rust_req.ledger_frozen_subtree_hashes,
+        )?;
+        Ok(FinishStateSnapshotResponse::new())
+    }
+
     fn get_executor_startup_info_inner(&self) -> Result<GetExecutorStartupInfoResponse> {
         let info = self.db.get_executor_startup_info()?;
         let rust_resp = storage_proto::GetExecutorStartupInfoResponse { info };
@@ -262,6 +332,42 @@ impl Storage for StorageService {
         provide_grpc_response(resp, ctx, sink);
     }
 
+    fn get_account_state_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: GetAccountStateChunkRequest,
+        sink: grpcio::UnarySink<GetAccountStateChunkResponse>,
+    ) {
+        debug!("[GRPC] Storage::get_account_state_chunk");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.get_account_state_chunk_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn restore_account_state_chunk(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: RestoreAccountStateChunkRequest,
+        sink: grpcio::UnarySink<RestoreAccountStateChunkResponse>,
+    ) {
+        debug!("[GRPC] Storage::restore_account_state_chunk");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.restore_account_state_chunk_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
+    fn finish_state_snapshot(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: FinishStateSnapshotRequest,
+        sink: grpcio::UnarySink<FinishStateSnapshotResponse>,
+    ) {
+        debug!("[GRPC] Storage::finish_state_snapshot");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let resp = self.finish_state_snapshot_inner(req);
+        provide_grpc_response(resp, ctx, sink);
+    }
+
     fn get_executor_startup_info(
         &mut self,
         ctx: grpcio::RpcContext,
@@ -273,6 +379,34 @@ impl Storage for StorageService {
         let resp = self.get_executor_startup_info_inner();
         provide_grpc_response(resp, ctx, sink);
     }
+
+    fn stream_transactions(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: StreamTransactionsRequest,
+        sink: grpcio::ServerStreamingSink<StreamTransactionsResponse>,
+    ) {
+        debug!("[GRPC] Storage::stream_transactions");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let responses = TransactionStream::new(
+            Arc::clone(&self.db),
+            Arc::clone(&self.commit_notifier),
+            req.get_start_version(),
+        );
+        provide_grpc_stream(responses, ctx, sink);
+    }
+
+    fn stream_account_states(
+        &mut self,
+        ctx: grpcio::RpcContext,
+        req: StreamAccountStatesRequest,
+        sink: grpcio::ServerStreamingSink<StreamAccountStatesResponse>,
+    ) {
+        debug!("[GRPC] Storage::stream_account_states");
+        let _timer = SVC_COUNTERS.req(&ctx);
+        let responses = AccountStateStream::new(Arc::clone(&self.db), req.get_version());
+        provide_grpc_stream(responses, ctx, sink);
+    }
 }
 
 #[cfg(test)]
@@ -13,7 +13,7 @@ use storage_client::StorageRead;
 use storage_proto::ExecutorStartupInfo;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    account_state_blob::AccountStateBlob,
+    account_state_blob::{AccountStateBlob, AccountStateChunkWithProof},
     get_with_proof::{RequestItem, ResponseItem},
     ledger_info::LedgerInfoWithSignatures,
     proof::definition::SparseMerkleProof,
@@ -126,6 +126,24 @@ impl StorageRead for MockStorageReadClient {
         unimplemented!();
     }
 
+    fn get_account_state_chunk_with_proof(
+        &self,
+        _version: Version,
+        _start_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Result<AccountStateChunkWithProof> {
+        unimplemented!()
+    }
+
+    fn get_account_state_chunk_with_proof_async(
+        &self,
+        _version: Version,
+        _start_key: Option<HashValue>,
+        _limit: u64,
+    ) -> Pin<Box<dyn Future<Output = Result<AccountStateChunkWithProof>> + Send>> {
+        unimplemented!()
+    }
+
     fn get_executor_startup_info(&self) -> Result<Option<ExecutorStartupInfo>> {
         unimplemented!()
     }
@@ -225,6 +243,15 @@ fn get_mock_response_item(request_item: &ProtoRequestItem) -> Result<ProtoRespon
 
                 response_item.set_get_transactions_response(resp);
             }
+            RequestItem_oneof_requested_items::get_account_state_by_version_request(_request) => {
+                unimplemented!();
+            }
+            RequestItem_oneof_requested_items::get_transaction_by_hash_request(_request) => {
+                unimplemented!();
+            }
+            RequestItem_oneof_requested_items::get_events_by_event_type_request(_request) => {
+                unimplemented!();
+            }
         }
     }
     Ok(response_item)
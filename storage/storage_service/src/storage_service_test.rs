@@ -3,6 +3,7 @@
 
 use super::*;
 use config::config::NodeConfigHelpers;
+use futures::executor::block_on_stream;
 use grpcio::EnvBuilder;
 use itertools::zip_eq;
 use libradb::{mock_genesis::db_with_mock_genesis, test_helper::arb_blocks_to_commit};
@@ -103,4 +104,62 @@ proptest! {
             prop_assert_eq!(ledger_info_with_sigs, &response_ledger_info_with_sigs);
          }
     }
+
+    #[test]
+    fn test_storage_service_streams(blocks in arb_blocks_to_commit().no_shrink()) {
+        let (_tmp_dir, _server_handler, read_client, write_client) =
+            start_test_storage_with_read_write_client(/* need_to_use_genesis = */ true);
+
+        let (last_block, blocks) = blocks.split_last().unwrap();
+        let mut version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in blocks {
+            write_client
+                .save_transactions(
+                    txns_to_commit.clone(),
+                    version + 1, /* first_version */
+                    Some(ledger_info_with_sigs.clone()),
+                )
+                .unwrap();
+            version += txns_to_commit.len() as u64;
+        }
+
+        // The stream catches up with the committed transactions first.
+        let mut txn_stream = block_on_stream(read_client.stream_transactions(1).unwrap());
+        let mut expected_version = 1;
+        for txn_to_commit in blocks.iter().flat_map(|(txns_to_commit, _)| txns_to_commit) {
+            let resp = txn_stream.next().unwrap().unwrap();
+            prop_assert_eq!(resp.version, expected_version);
+            prop_assert_eq!(&resp.signed_transaction, txn_to_commit.signed_txn());
+            prop_assert_eq!(&resp.events[..], txn_to_commit.events());
+            expected_version += 1;
+        }
+
+        // Then follows the new commits.
+        let (txns_to_commit, ledger_info_with_sigs) = last_block;
+        write_client
+            .save_transactions(
+                txns_to_commit.clone(),
+                version + 1, /* first_version */
+                Some(ledger_info_with_sigs.clone()),
+            )
+            .unwrap();
+        version += txns_to_commit.len() as u64;
+        for txn_to_commit in txns_to_commit {
+            let resp = txn_stream.next().unwrap().unwrap();
+            prop_assert_eq!(resp.version, expected_version);
+            prop_assert_eq!(&resp.signed_transaction, txn_to_commit.signed_txn());
+            expected_version += 1;
+        }
+
+        // All the account states of the latest version.
+        let account_states = block_on_stream(read_client.stream_account_states(version).unwrap())
+            .collect::<Result<Vec<_>>>()
+            .unwrap()
+            .concat();
+        let expected = read_client
+            .get_account_state_chunk_with_proof(version, None /* start_key */, 1000 /* limit */)
+            .unwrap()
+            .account_states;
+        prop_assert_eq!(account_states, expected);
+    }
 }
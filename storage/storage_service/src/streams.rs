@@ -0,0 +1,169 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module implements the response streams of the server-streaming APIs. A stream reads a
+//! batch from the DB only when the call asks for more, so a client consuming slowly just makes its
+//! own stream read slowly.
+
+use crate::LibraDBWrapper;
+use crypto::HashValue;
+use failure::prelude::*;
+use futures_01::{
+    task::{self, Task},
+    Async, Poll, Stream,
+};
+use proto_conv::IntoProto;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+use storage_proto::proto::storage::{StreamAccountStatesResponse, StreamTransactionsResponse};
+use types::transaction::Version;
+
+/// The number of transactions or account states read from the DB at a time.
+const BATCH_SIZE: u64 = 100;
+
+/// Wakes up the transaction streams that have caught up with the ledger when new transactions are
+/// committed.
+#[derive(Default)]
+pub(crate) struct CommitNotifier {
+    inner: Mutex<CommitNotifierInner>,
+}
+
+#[derive(Default)]
+struct CommitNotifierInner {
+    /// Bumped on every commit.
+    num_commits: u64,
+    /// The tasks of the streams waiting for the next commit.
+    waiting: Vec<Task>,
+}
+
+impl CommitNotifier {
+    /// Wakes up the streams waiting for a commit, to be called after each commit.
+    pub fn notify(&self) {
+        let waiting = {
+            let mut inner = self.inner.lock().expect("Failed to lock mutex.");
+            inner.num_commits += 1;
+            std::mem::replace(&mut inner.waiting, vec![])
+        };
+        waiting.iter().for_each(Task::notify);
+    }
+
+    fn num_commits(&self) -> u64 {
+        self.inner
+            .lock()
+            .expect("Failed to lock mutex.")
+            .num_commits
+    }
+
+    /// Has the current task notified on the next commit. Returns `false` without doing so if there
+    /// has been a commit since `num_commits` was read, in which case the caller should read the DB
+    /// again.
+    fn wait_for_commit_after(&self, num_commits: u64) -> bool {
+        let mut inner = self.inner.lock().expect("Failed to lock mutex.");
+        if inner.num_commits != num_commits {
+            return false;
+        }
+        inner.waiting.push(task::current());
+        true
+    }
+}
+
+/// Streams the committed transactions from a start version on, and then the new ones as they are
+/// committed.
+pub(crate) struct TransactionStream {
+    db: Arc<LibraDBWrapper>,
+    commit_notifier: Arc<CommitNotifier>,
+    next_version: Version,
+    buffered: VecDeque<StreamTransactionsResponse>,
+}
+
+impl TransactionStream {
+    pub fn new(
+        db: Arc<LibraDBWrapper>,
+        commit_notifier: Arc<CommitNotifier>,
+        start_version: Version,
+    ) -> Self {
+        Self {
+            db,
+            commit_notifier,
+            next_version: start_version,
+            buffered: VecDeque::new(),
+        }
+    }
+}
+
+impl Stream for TransactionStream {
+    type Item = StreamTransactionsResponse;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        while self.buffered.is_empty() {
+            // Read the number of commits before the DB, so that a commit right after the read is
+            // not missed.
+            let num_commits = self.commit_notifier.num_commits();
+            let txns = self
+                .db
+                .get_transactions_with_events(self.next_version, BATCH_SIZE)?;
+            if txns.is_empty() && self.commit_notifier.wait_for_commit_after(num_commits) {
+                return Ok(Async::NotReady);
+            }
+            for (signed_transaction, transaction_info, events) in txns {
+                self.buffered.push_back(
+                    storage_proto::StreamTransactionsResponse::new(
+                        self.next_version,
+                        signed_transaction,
+                        transaction_info,
+                        events,
+                    )
+                    .into_proto(),
+                );
+                self.next_version += 1;
+            }
+        }
+        Ok(Async::Ready(self.buffered.pop_front()))
+    }
+}
+
+/// Streams all the account states of the state snapshot at a version in chunks, and ends after the
+/// last one.
+pub(crate) struct AccountStateStream {
+    db: Arc<LibraDBWrapper>,
+    version: Version,
+    /// The key of the last account state streamed, `None` before the first chunk.
+    last_key: Option<HashValue>,
+    exhausted: bool,
+}
+
+impl AccountStateStream {
+    pub fn new(db: Arc<LibraDBWrapper>, version: Version) -> Self {
+        Self {
+            db,
+            version,
+            last_key: None,
+            exhausted: false,
+        }
+    }
+}
+
+impl Stream for AccountStateStream {
+    type Item = StreamAccountStatesResponse;
+    type Error = Error;
+
+    fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
+        if self.exhausted {
+            return Ok(Async::Ready(None));
+        }
+        let account_states = self
+            .db
+            .get_account_states(self.version, self.last_key, BATCH_SIZE)?;
+        self.exhausted = (account_states.len() as u64) < BATCH_SIZE;
+        self.last_key = match account_states.last() {
+            Some((key, _)) => Some(*key),
+            None => return Ok(Async::Ready(None)),
+        };
+        Ok(Async::Ready(Some(
+            storage_proto::StreamAccountStatesResponse::new(account_states).into_proto(),
+        )))
+    }
+}
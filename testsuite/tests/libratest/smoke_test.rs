@@ -10,23 +10,36 @@ use std::str::FromStr;
 fn setup_swarm_and_client_proxy(
     num_nodes: usize,
     client_port_index: usize,
+) -> (LibraSwarm, ClientProxy) {
+    setup_swarm_with_full_nodes_and_client_proxy(num_nodes, 0, |swarm| {
+        *swarm
+            .get_validators_public_ports()
+            .get(client_port_index)
+            .unwrap()
+    })
+}
+
+/// Launches a swarm of `num_nodes` validators and `num_full_nodes` full nodes, and connects the
+/// client to the AC port picked by `client_port`.
+fn setup_swarm_with_full_nodes_and_client_proxy(
+    num_nodes: usize,
+    num_full_nodes: usize,
+    client_port: impl FnOnce(&LibraSwarm) -> u16,
 ) -> (LibraSwarm, ClientProxy) {
     ::logger::init_for_e2e_testing();
 
     let (faucet_account_keypair, faucet_key_file_path, _temp_dir) =
         generate_keypair::load_faucet_key_or_create_default(None);
 
-    let swarm = LibraSwarm::launch_swarm(
+    let swarm = LibraSwarm::launch_swarm_with_full_nodes(
         num_nodes,
+        num_full_nodes,
         false, /* disable_logging */
         faucet_account_keypair,
         true, /* tee_logs */
         None, /* config_dir */
     );
-    let port = *swarm
-        .get_validators_public_ports()
-        .get(client_port_index)
-        .unwrap();
+    let port = client_port(&swarm);
     let tmp_mnemonic_file = tempfile::NamedTempFile::new().unwrap();
     let client_proxy = ClientProxy::new(
         "localhost",
@@ -93,6 +106,16 @@ fn smoke_test_multi_node() {
     test_smoke_script(client_proxy);
 }
 
+#[test]
+fn smoke_test_full_node() {
+    // The transactions are submitted to the full node, which forwards them to a validator and
+    // serves the balances from the state synced from the validators.
+    let (_swarm, mut client_proxy) = setup_swarm_with_full_nodes_and_client_proxy(4, 1, |swarm| {
+        *swarm.get_full_nodes_public_ports().get(0).unwrap()
+    });
+    test_smoke_script(client_proxy);
+}
+
 #[test]
 fn test_concurrent_transfers_single_node() {
     let (_swarm, mut client_proxy) = setup_swarm_and_client_proxy(1, 0);
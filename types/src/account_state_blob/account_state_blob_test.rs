@@ -26,6 +26,11 @@ proptest! {
     fn account_state_with_proof(account_state_with_proof in any::<AccountStateWithProof>()) {
         assert_protobuf_encode_decode(&account_state_with_proof);
     }
+
+    #[test]
+    fn account_state_chunk_with_proof(chunk in any::<AccountStateChunkWithProof>()) {
+        assert_protobuf_encode_decode(&chunk);
+    }
 }
 
 #[test]
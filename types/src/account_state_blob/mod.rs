@@ -7,8 +7,11 @@ use crate::{
     account_address::AccountAddress,
     account_config::get_account_resource_or_default,
     ledger_info::LedgerInfo,
-    proof::{verify_account_state, AccountStateProof},
-    transaction::Version,
+    proof::{
+        verify_account_state, verify_account_state_chunk, verify_ledger_frozen_subtree_hashes,
+        AccountStateProof, AccumulatorProof, SparseMerkleProof,
+    },
+    transaction::{TransactionInfo, Version},
 };
 use canonical_serialization::{SimpleDeserializer, SimpleSerializer};
 use crypto::{
@@ -171,5 +174,121 @@ impl IntoProto for AccountStateWithProof {
     }
 }
 
+/// A chunk of consecutive account states of the state snapshot at `version`, in ascending order of
+/// the account address hashes, together with everything needed to authenticate it against a
+/// `LedgerInfo` and to bootstrap the transaction accumulator at `version`.
+#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+pub struct AccountStateChunkWithProof {
+    /// The version of the state snapshot.
+    pub version: Version,
+    /// The account states keyed by the hashes of the account addresses.
+    pub account_states: Vec<(HashValue, AccountStateBlob)>,
+    /// The proof of each account state towards the state root hash in `transaction_info`.
+    pub proofs: Vec<SparseMerkleProof>,
+    /// The proof of `transaction_info` towards the `LedgerInfo`.
+    pub ledger_info_to_transaction_info_proof: AccumulatorProof,
+    /// The info of the transaction at `version`.
+    pub transaction_info: TransactionInfo,
+    /// From left to right, root hashes of all frozen subtrees of the transaction accumulator at
+    /// `version`.
+    pub ledger_frozen_subtree_hashes: Vec<HashValue>,
+}
+
+impl AccountStateChunkWithProof {
+    /// Verifies the chunk against `ledger_info`, which must be at the same version as the snapshot.
+    /// `start_key` is the last key of the previous chunk, or `None` if this is the first chunk.
+    pub fn verify(&self, ledger_info: &LedgerInfo, start_key: Option<HashValue>) -> Result<()> {
+        ensure!(
+            self.version == ledger_info.version(),
+            "Snapshot version ({}) does not match the version of the ledger info ({}).",
+            self.version,
+            ledger_info.version(),
+        );
+
+        verify_account_state_chunk(
+            ledger_info,
+            self.version,
+            start_key,
+            &self.account_states,
+            &self.proofs,
+            &self.transaction_info,
+            &self.ledger_info_to_transaction_info_proof,
+        )?;
+        verify_ledger_frozen_subtree_hashes(ledger_info, &self.ledger_frozen_subtree_hashes)
+    }
+}
+
+impl FromProto for AccountStateChunkWithProof {
+    type ProtoType = crate::proto::account_state_blob::AccountStateChunkWithProof;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        Ok(AccountStateChunkWithProof {
+            version: object.get_version(),
+            account_states: object
+                .take_account_states()
+                .into_iter()
+                .map(|mut keyed_blob| {
+                    Ok((
+                        HashValue::from_slice(keyed_blob.get_key())?,
+                        AccountStateBlob::from_proto(keyed_blob.take_blob())?,
+                    ))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            proofs: object
+                .take_proofs()
+                .into_iter()
+                .map(SparseMerkleProof::from_proto)
+                .collect::<Result<Vec<_>>>()?,
+            ledger_info_to_transaction_info_proof: AccumulatorProof::from_proto(
+                object.take_ledger_info_to_transaction_info_proof(),
+            )?,
+            transaction_info: TransactionInfo::from_proto(object.take_transaction_info())?,
+            ledger_frozen_subtree_hashes: object
+                .get_ledger_frozen_subtree_hashes()
+                .iter()
+                .map(|hash| HashValue::from_slice(hash))
+                .collect::<Result<Vec<_>>>()?,
+        })
+    }
+}
+
+impl IntoProto for AccountStateChunkWithProof {
+    type ProtoType = crate::proto::account_state_blob::AccountStateChunkWithProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut out = Self::ProtoType::new();
+        out.set_version(self.version);
+        out.set_account_states(protobuf::RepeatedField::from_vec(
+            self.account_states
+                .into_iter()
+                .map(|(key, blob)| {
+                    let mut keyed_blob =
+                        crate::proto::account_state_blob::KeyedAccountStateBlob::new();
+                    keyed_blob.set_key(key.to_vec());
+                    keyed_blob.set_blob(blob.into_proto());
+                    keyed_blob
+                })
+                .collect::<Vec<_>>(),
+        ));
+        out.set_proofs(protobuf::RepeatedField::from_vec(
+            self.proofs
+                .into_iter()
+                .map(SparseMerkleProof::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        out.set_ledger_info_to_transaction_info_proof(
+            self.ledger_info_to_transaction_info_proof.into_proto(),
+        );
+        out.set_transaction_info(self.transaction_info.into_proto());
+        out.set_ledger_frozen_subtree_hashes(protobuf::RepeatedField::from_vec(
+            self.ledger_frozen_subtree_hashes
+                .iter()
+                .map(HashValue::to_vec)
+                .collect::<Vec<_>>(),
+        ));
+        out
+    }
+}
+
 #[cfg(test)]
 mod account_state_blob_test;
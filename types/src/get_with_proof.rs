@@ -13,16 +13,18 @@ use crate::{
     contract_event::EventWithProof,
     ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
     proto::get_with_proof::{
-        GetAccountStateRequest, GetAccountStateResponse,
-        GetAccountTransactionBySequenceNumberRequest,
+        GetAccountStateByVersionRequest, GetAccountStateByVersionResponse, GetAccountStateRequest,
+        GetAccountStateResponse, GetAccountTransactionBySequenceNumberRequest,
         GetAccountTransactionBySequenceNumberResponse, GetEventsByEventAccessPathRequest,
-        GetEventsByEventAccessPathResponse, GetTransactionsRequest, GetTransactionsResponse,
+        GetEventsByEventAccessPathResponse, GetEventsByEventTypeRequest,
+        GetEventsByEventTypeResponse, GetTransactionByHashRequest, GetTransactionByHashResponse,
+        GetTransactionsRequest, GetTransactionsResponse,
     },
     transaction::{SignedTransactionWithProof, TransactionListWithProof, Version},
     validator_change::ValidatorChangeEventWithProof,
     validator_verifier::ValidatorVerifier,
 };
-use crypto::hash::CryptoHash;
+use crypto::{hash::CryptoHash, HashValue};
 use failure::prelude::*;
 use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
@@ -197,6 +199,41 @@ fn verify_response_item(
             *fetch_events,
             txn_list_with_proof,
         ),
+        // GetAccountStateByVersion
+        (
+            RequestItem::GetAccountStateByVersion { address, version },
+            ResponseItem::GetAccountStateByVersion {
+                account_state_with_proof,
+            },
+        ) => account_state_with_proof.verify(ledger_info, *version, *address),
+        // GetTransactionByHash
+        (
+            RequestItem::GetTransactionByHash { hash },
+            ResponseItem::GetTransactionByHash {
+                signed_transaction_with_proof,
+            },
+        ) => {
+            verify_get_txn_by_hash_resp(ledger_info, *hash, signed_transaction_with_proof.as_ref())
+        }
+        // GetEventsByEventType
+        (
+            RequestItem::GetEventsByEventType {
+                event_path_prefix,
+                start_version,
+                start_event_index,
+                end_version,
+                limit,
+            },
+            ResponseItem::GetEventsByEventType { events_with_proof },
+        ) => verify_get_events_by_type_resp(
+            ledger_info,
+            event_path_prefix,
+            *start_version,
+            *start_event_index,
+            *end_version,
+            *limit,
+            events_with_proof,
+        ),
         // Request-response item types mismatch.
         _ => bail!(
             "RequestItem/ResponseItem types mismatch. request: {:?}, response: {:?}",
@@ -249,6 +286,34 @@ fn verify_get_txn_by_seq_num_resp(
     }
 }
 
+fn verify_get_txn_by_hash_resp(
+    ledger_info: &LedgerInfo,
+    req_hash: HashValue,
+    signed_transaction_with_proof: Option<&SignedTransactionWithProof>,
+) -> Result<()> {
+    // A transaction not committed comes with no proof.
+    if let Some(signed_transaction_with_proof) = signed_transaction_with_proof {
+        let signed_transaction = &signed_transaction_with_proof.signed_transaction;
+        ensure!(
+            signed_transaction.hash() == req_hash,
+            "Bad GetTransactionByHash response. Transaction hash ({}) not expected ({}).",
+            signed_transaction.hash(),
+            req_hash,
+        );
+        ensure!(
+            signed_transaction_with_proof.events.is_some(),
+            "Bad GetTransactionByHash response. Events not returned.",
+        );
+        signed_transaction_with_proof.verify(
+            ledger_info,
+            signed_transaction_with_proof.version,
+            signed_transaction.sender(),
+            signed_transaction.sequence_number(),
+        )?;
+    }
+    Ok(())
+}
+
 fn verify_get_events_by_access_path_resp(
     ledger_info: &LedgerInfo,
     req_access_path: &AccessPath,
@@ -307,6 +372,63 @@ fn verify_get_events_by_access_path_resp(
     Ok(())
 }
 
+fn verify_get_events_by_type_resp(
+    ledger_info: &LedgerInfo,
+    req_event_path_prefix: &[u8],
+    req_start_version: Version,
+    req_start_event_index: u64,
+    req_end_version: Version,
+    req_limit: u64,
+    events_with_proof: &[EventWithProof],
+) -> Result<()> {
+    ensure!(
+        events_with_proof.len() as u64 <= req_limit,
+        "Expecting at most {} events, got {}.",
+        req_limit,
+        events_with_proof.len(),
+    );
+
+    // The events are in the ledger, in order and within the requested range, which is all that can
+    // be verified since the index of events by type isn't authenticated.
+    let mut next_position = (req_start_version, req_start_event_index);
+    for e in events_with_proof {
+        ensure!(
+            e.event
+                .access_path()
+                .path
+                .starts_with(req_event_path_prefix),
+            "Event path ({}) doesn't start with the expected prefix ({}).",
+            hex::encode(&e.event.access_path().path),
+            hex::encode(req_event_path_prefix),
+        );
+        ensure!(
+            e.transaction_version <= req_end_version,
+            "Event at version {} is after the end version {}.",
+            e.transaction_version,
+            req_end_version,
+        );
+        ensure!(
+            (e.transaction_version, e.event_index) >= next_position,
+            "Event at version {} index {} is out of order, expecting no earlier than version {} \
+             index {}.",
+            e.transaction_version,
+            e.event_index,
+            next_position.0,
+            next_position.1,
+        );
+        e.verify(
+            ledger_info,
+            e.event.access_path(),
+            e.event.sequence_number(),
+            e.transaction_version,
+            e.event_index,
+        )?;
+        next_position = (e.transaction_version, e.event_index + 1);
+    }
+
+    Ok(())
+}
+
 fn get_next_event_seq_num(
     account_state_blob: &Option<AccountStateBlob>,
     access_path: &AccessPath,
@@ -373,6 +495,20 @@ pub enum RequestItem {
         limit: u64,
         fetch_events: bool,
     },
+    GetAccountStateByVersion {
+        address: AccountAddress,
+        version: Version,
+    },
+    GetTransactionByHash {
+        hash: HashValue,
+    },
+    GetEventsByEventType {
+        event_path_prefix: Vec<u8>,
+        start_version: Version,
+        start_event_index: u64,
+        end_version: Version,
+        limit: u64,
+    },
 }
 
 impl FromProto for RequestItem {
@@ -419,6 +555,32 @@ impl FromProto for RequestItem {
                 limit,
                 fetch_events,
             }
+        } else if object.has_get_account_state_by_version_request() {
+            let mut req = object.take_get_account_state_by_version_request();
+            let address = AccountAddress::from_proto(req.take_address())?;
+            let version = req.get_version();
+
+            RequestItem::GetAccountStateByVersion { address, version }
+        } else if object.has_get_transaction_by_hash_request() {
+            let hash =
+                HashValue::from_slice(object.get_get_transaction_by_hash_request().get_hash())?;
+
+            RequestItem::GetTransactionByHash { hash }
+        } else if object.has_get_events_by_event_type_request() {
+            let mut req = object.take_get_events_by_event_type_request();
+            let event_path_prefix = req.take_event_path_prefix();
+            let start_version = req.get_start_version();
+            let start_event_index = req.get_start_event_index();
+            let end_version = req.get_end_version();
+            let limit = req.get_limit();
+
+            RequestItem::GetEventsByEventType {
+                event_path_prefix,
+                start_version,
+                start_event_index,
+                end_version,
+                limit,
+            }
         } else {
             bail!("Unknown RequestItem type.")
         })
@@ -474,6 +636,35 @@ impl IntoProto for RequestItem {
 
                 out.set_get_transactions_request(req);
             }
+            RequestItem::GetAccountStateByVersion { address, version } => {
+                let mut req = GetAccountStateByVersionRequest::new();
+                req.set_address(address.into_proto());
+                req.set_version(version);
+
+                out.set_get_account_state_by_version_request(req);
+            }
+            RequestItem::GetTransactionByHash { hash } => {
+                let mut req = GetTransactionByHashRequest::new();
+                req.set_hash(hash.to_vec());
+
+                out.set_get_transaction_by_hash_request(req);
+            }
+            RequestItem::GetEventsByEventType {
+                event_path_prefix,
+                start_version,
+                start_event_index,
+                end_version,
+                limit,
+            } => {
+                let mut req = GetEventsByEventTypeRequest::new();
+                req.set_event_path_prefix(event_path_prefix);
+                req.set_start_version(start_version);
+                req.set_start_event_index(start_event_index);
+                req.set_end_version(end_version);
+                req.set_limit(limit);
+
+                out.set_get_events_by_event_type_request(req);
+            }
         }
         out
     }
@@ -497,6 +688,15 @@ pub enum ResponseItem {
     GetTransactions {
         txn_list_with_proof: TransactionListWithProof,
     },
+    GetAccountStateByVersion {
+        account_state_with_proof: AccountStateWithProof,
+    },
+    GetTransactionByHash {
+        signed_transaction_with_proof: Option<SignedTransactionWithProof>,
+    },
+    GetEventsByEventType {
+        events_with_proof: Vec<EventWithProof>,
+    },
 }
 
 impl ResponseItem {
@@ -547,6 +747,33 @@ impl ResponseItem {
             _ => bail!("Not ResponseItem::GetTransactions."),
         }
     }
+
+    pub fn into_get_account_state_by_version_response(self) -> Result<AccountStateWithProof> {
+        match self {
+            ResponseItem::GetAccountStateByVersion {
+                account_state_with_proof,
+            } => Ok(account_state_with_proof),
+            _ => bail!("Not ResponseItem::GetAccountStateByVersion."),
+        }
+    }
+
+    pub fn into_get_transaction_by_hash_response(
+        self,
+    ) -> Result<Option<SignedTransactionWithProof>> {
+        match self {
+            ResponseItem::GetTransactionByHash {
+                signed_transaction_with_proof,
+            } => Ok(signed_transaction_with_proof),
+            _ => bail!("Not ResponseItem::GetTransactionByHash."),
+        }
+    }
+
+    pub fn into_get_events_by_event_type_response(self) -> Result<Vec<EventWithProof>> {
+        match self {
+            ResponseItem::GetEventsByEventType { events_with_proof } => Ok(events_with_proof),
+            _ => bail!("Not ResponseItem::GetEventsByEventType."),
+        }
+    }
 }
 
 impl FromProto for ResponseItem {
@@ -607,6 +834,36 @@ impl FromProto for ResponseItem {
             ResponseItem::GetTransactions {
                 txn_list_with_proof,
             }
+        } else if object.has_get_account_state_by_version_response() {
+            let account_state_with_proof = AccountStateWithProof::from_proto(
+                object
+                    .take_get_account_state_by_version_response()
+                    .take_account_state_with_proof(),
+            )?;
+
+            ResponseItem::GetAccountStateByVersion {
+                account_state_with_proof,
+            }
+        } else if object.has_get_transaction_by_hash_response() {
+            let signed_transaction_with_proof = object
+                .take_get_transaction_by_hash_response()
+                .signed_transaction_with_proof
+                .take()
+                .map(SignedTransactionWithProof::from_proto)
+                .transpose()?;
+
+            ResponseItem::GetTransactionByHash {
+                signed_transaction_with_proof,
+            }
+        } else if object.has_get_events_by_event_type_response() {
+            let events_with_proof = object
+                .take_get_events_by_event_type_response()
+                .take_events_with_proof()
+                .into_iter()
+                .map(EventWithProof::from_proto)
+                .collect::<Result<Vec<_>>>()?;
+
+            ResponseItem::GetEventsByEventType { events_with_proof }
         } else {
             bail!("Unknown ResponseItem type.")
         })
@@ -667,6 +924,35 @@ impl IntoProto for ResponseItem {
 
                 out.set_get_transactions_response(res)
             }
+            ResponseItem::GetAccountStateByVersion {
+                account_state_with_proof,
+            } => {
+                let mut res = GetAccountStateByVersionResponse::new();
+                res.set_account_state_with_proof(account_state_with_proof.into_proto());
+
+                out.set_get_account_state_by_version_response(res);
+            }
+            ResponseItem::GetTransactionByHash {
+                signed_transaction_with_proof,
+            } => {
+                let mut res = GetTransactionByHashResponse::new();
+                if let Some(t) = signed_transaction_with_proof {
+                    res.set_signed_transaction_with_proof(t.into_proto())
+                }
+
+                out.set_get_transaction_by_hash_response(res);
+            }
+            ResponseItem::GetEventsByEventType { events_with_proof } => {
+                let mut res = GetEventsByEventTypeResponse::new();
+                res.set_events_with_proof(::protobuf::RepeatedField::from_vec(
+                    events_with_proof
+                        .into_iter()
+                        .map(EventWithProof::into_proto)
+                        .collect(),
+                ));
+
+                out.set_get_events_by_event_type_response(res);
+            }
         }
         out
     }
@@ -241,6 +241,81 @@ impl IntoProto for SparseMerkleProof {
     }
 }
 
+/// A proof that can be used to authenticate all the leaves of a Sparse Merkle Tree in a
+/// contiguous key range given trusted root hash, i.e. that none of them is left out. The range
+/// starts at a given key, which may or may not exist in the tree, and ends at the last key of the
+/// leaves being authenticated.
+///
+/// The siblings on the left of the path to the first key, and those on the right of the path to
+/// the last key, cover exactly the keys out of the range. Everything in between can be rebuilt
+/// from the leaves, so this structure consists of a `SparseMerkleProof` for each end of the range.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseMerkleRangeProof {
+    /// The proof of the start key of the range. It is an inclusion proof if the key exists in the
+    /// tree, otherwise a non-inclusion proof.
+    first_key_proof: SparseMerkleProof,
+
+    /// The inclusion proof of the last key of the range. It is `None` if the range is empty, in
+    /// which case `first_key_proof` alone shows that no key greater than the start key exists.
+    last_key_proof: Option<SparseMerkleProof>,
+}
+
+impl SparseMerkleRangeProof {
+    /// Constructs a new `SparseMerkleRangeProof` using the proofs of both ends of the range.
+    pub fn new(
+        first_key_proof: SparseMerkleProof,
+        last_key_proof: Option<SparseMerkleProof>,
+    ) -> Self {
+        SparseMerkleRangeProof {
+            first_key_proof,
+            last_key_proof,
+        }
+    }
+
+    /// Returns the proof of the start key of the range.
+    pub fn first_key_proof(&self) -> &SparseMerkleProof {
+        &self.first_key_proof
+    }
+
+    /// Returns the proof of the last key of the range, if any.
+    pub fn last_key_proof(&self) -> Option<&SparseMerkleProof> {
+        self.last_key_proof.as_ref()
+    }
+}
+
+impl FromProto for SparseMerkleRangeProof {
+    type ProtoType = crate::proto::proof::SparseMerkleRangeProof;
+
+    fn from_proto(mut proto_proof: Self::ProtoType) -> Result<Self> {
+        ensure!(
+            proto_proof.has_first_key_proof(),
+            "Malformed range proof. The proof of the first key is missing."
+        );
+        let first_key_proof = SparseMerkleProof::from_proto(proto_proof.take_first_key_proof())?;
+        let last_key_proof = if proto_proof.has_last_key_proof() {
+            Some(SparseMerkleProof::from_proto(
+                proto_proof.take_last_key_proof(),
+            )?)
+        } else {
+            None
+        };
+        Ok(SparseMerkleRangeProof::new(first_key_proof, last_key_proof))
+    }
+}
+
+impl IntoProto for SparseMerkleRangeProof {
+    type ProtoType = crate::proto::proof::SparseMerkleRangeProof;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto_proof = Self::ProtoType::new();
+        proto_proof.set_first_key_proof(self.first_key_proof.into_proto());
+        if let Some(last_key_proof) = self.last_key_proof {
+            proto_proof.set_last_key_proof(last_key_proof.into_proto());
+        }
+        proto_proof
+    }
+}
+
 /// The complete proof used to authenticate a `SignedTransaction` object.  This structure consists
 /// of an `AccumulatorProof` from `LedgerInfo` to `TransactionInfo` the verifier needs to verify
 /// the correctness of the `TransactionInfo` object, and the `TransactionInfo` object that is
@@ -31,6 +31,7 @@ use std::{collections::VecDeque, marker::PhantomData};
 
 pub use crate::proof::definition::{
     AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    SparseMerkleRangeProof,
 };
 
 /// Verifies that a `SignedTransaction` with hash value of `signed_transaction_hash`
@@ -99,6 +100,101 @@ pub fn verify_account_state(
     Ok(())
 }
 
+/// Verifies that `account_states` are the states of consecutive accounts at version
+/// `state_version`, in ascending order of the account address hashes, right after `start_key` (or
+/// from the smallest key if `start_key` is `None`). Each account state is verified with its own
+/// proof. Note that accounts at the end of the chunk can not be proven as consecutive before the
+/// whole state is restored and its root hash compared with `transaction_info`.
+pub fn verify_account_state_chunk(
+    ledger_info: &LedgerInfo,
+    state_version: Version,
+    start_key: Option<HashValue>,
+    account_states: &[(HashValue, AccountStateBlob)],
+    proofs: &[SparseMerkleProof],
+    transaction_info: &TransactionInfo,
+    ledger_info_to_transaction_info_proof: &AccumulatorProof,
+) -> Result<()> {
+    ensure!(
+        account_states.len() == proofs.len(),
+        "The number of account states ({}) does not match the number of proofs ({}).",
+        account_states.len(),
+        proofs.len(),
+    );
+
+    let mut prev_key = start_key;
+    for ((key, blob), proof) in account_states.iter().zip(proofs) {
+        if let Some(prev_key) = prev_key {
+            ensure!(
+                *key > prev_key,
+                "Account state keys are not in ascending order: {:x} after {:x}.",
+                key,
+                prev_key,
+            );
+        }
+        verify_sparse_merkle_element(
+            transaction_info.state_root_hash(),
+            *key,
+            &Some(blob.clone()),
+            proof,
+        )?;
+        prev_key = Some(*key);
+    }
+
+    verify_transaction_info(
+        ledger_info,
+        state_version,
+        transaction_info,
+        ledger_info_to_transaction_info_proof,
+    )?;
+    Ok(())
+}
+
+/// Verifies that `frozen_subtree_hashes` are, from left to right, the root hashes of the frozen
+/// subtrees of the transaction accumulator represented by `ledger_info`.
+pub fn verify_ledger_frozen_subtree_hashes(
+    ledger_info: &LedgerInfo,
+    frozen_subtree_hashes: &[HashValue],
+) -> Result<()> {
+    let num_leaves = ledger_info.version() + 1;
+    // There is a frozen subtree of 2^level leaves for each bit set in `num_leaves`, the largest
+    // one on the left.
+    let levels = (0..64)
+        .rev()
+        .filter(|level| num_leaves & (1u64 << *level) != 0)
+        .collect::<Vec<u64>>();
+    ensure!(
+        levels.len() == frozen_subtree_hashes.len(),
+        "Expected {} frozen subtrees for {} leaves, got {}.",
+        levels.len(),
+        num_leaves,
+        frozen_subtree_hashes.len(),
+    );
+
+    let mut subtrees = frozen_subtree_hashes.iter().zip(levels).rev();
+    let (mut hash, mut level) = match subtrees.next() {
+        Some((hash, level)) => (*hash, level),
+        None => bail!("No frozen subtree."),
+    };
+    for (left_hash, left_level) in subtrees {
+        // Pad the right side with placeholders up to the level of the subtree on the left.
+        while level < left_level {
+            hash =
+                TransactionAccumulatorInternalNode::new(hash, *ACCUMULATOR_PLACEHOLDER_HASH).hash();
+            level += 1;
+        }
+        hash = TransactionAccumulatorInternalNode::new(*left_hash, hash).hash();
+        level += 1;
+    }
+
+    ensure!(
+        hash == ledger_info.transaction_accumulator_hash(),
+        "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+        hash,
+        ledger_info.transaction_accumulator_hash(),
+    );
+    Ok(())
+}
+
 /// Verifies that a given event is correct using provided proof.
 pub(crate) fn verify_event(
     ledger_info: &LedgerInfo,
@@ -285,7 +381,7 @@ pub(crate) fn verify_transaction_list(
 }
 
 /// Verifies that a given `transaction_info` exists in the ledger using provided proof.
-fn verify_transaction_info(
+pub fn verify_transaction_info(
     ledger_info: &LedgerInfo,
     transaction_version: Version,
     transaction_info: &TransactionInfo,
@@ -485,6 +581,208 @@ pub fn verify_sparse_merkle_element(
     Ok(())
 }
 
+/// Verifies that `account_states`, in ascending order of their keys, are all the elements in the
+/// Sparse Merkle Tree with keys from `start_key` to the last key of `account_states`, using the
+/// provided proof. If `account_states` is empty, verifies that no element in the tree has a key
+/// greater than or equal to `start_key`.
+pub fn verify_sparse_merkle_range(
+    expected_root_hash: HashValue,
+    start_key: HashValue,
+    account_states: &[(HashValue, AccountStateBlob)],
+    range_proof: &SparseMerkleRangeProof,
+) -> Result<()> {
+    let mut prev_key = None;
+    for (key, _) in account_states {
+        ensure!(
+            prev_key.map_or(*key >= start_key, |prev_key| *key > prev_key),
+            "Keys are not in ascending order from the start key: {:x} after {:x}.",
+            key,
+            prev_key.unwrap_or(start_key),
+        );
+        prev_key = Some(*key);
+    }
+
+    let first_key_proof = range_proof.first_key_proof();
+    let start_key_blob = match account_states.first() {
+        Some((key, blob)) if *key == start_key => Some(blob.clone()),
+        _ => None,
+    };
+    verify_sparse_merkle_element(
+        expected_root_hash,
+        start_key,
+        &start_key_blob,
+        first_key_proof,
+    )?;
+
+    match (account_states.last(), range_proof.last_key_proof()) {
+        (Some((last_key, last_blob)), Some(last_key_proof)) => {
+            verify_sparse_merkle_element(
+                expected_root_hash,
+                *last_key,
+                &Some(last_blob.clone()),
+                last_key_proof,
+            )?;
+            // Both ends are authenticated, now make sure nothing in between is left out by
+            // rebuilding the root hash from the leaves.
+            let leaves = account_states
+                .iter()
+                .map(|(key, blob)| (*key, blob.hash()))
+                .collect::<Vec<_>>();
+            let walker = SparseMerkleRangeWalker {
+                start_key,
+                last_key: *last_key,
+                first_key_proof,
+                last_key_proof,
+            };
+            let actual_root_hash = walker.root_hash(&leaves)?;
+            ensure!(
+                actual_root_hash == expected_root_hash,
+                "Root hashes do not match. Actual root hash: {:x}. Expected root hash: {:x}.",
+                actual_root_hash,
+                expected_root_hash
+            );
+        }
+        (None, None) => {
+            // Any key greater than the start key would be either under a sibling on the right of
+            // the path to the start key, or the leaf at the end of the path.
+            ensure!(
+                first_key_proof
+                    .siblings()
+                    .iter()
+                    .zip(start_key.iter_bits())
+                    .all(|(sibling, bit)| bit || *sibling == *SPARSE_MERKLE_PLACEHOLDER_HASH),
+                "Expected no key after the start key, but a subtree on the right is not empty."
+            );
+            if let Some((key, _)) = first_key_proof.leaf() {
+                ensure!(
+                    key < start_key,
+                    "Expected no key after the start key. Found {:x}.",
+                    key
+                );
+            }
+        }
+        (Some(_), None) => bail!("Expected the proof of the last key. Found none."),
+        (None, Some(_)) => bail!("Expected no proof of the last key with no account state."),
+    }
+    Ok(())
+}
+
+/// Rebuilds the root hash of a Sparse Merkle Tree from the leaves in a key range and the proofs of
+/// both ends of the range, as part of `verify_sparse_merkle_range`.
+struct SparseMerkleRangeWalker<'a> {
+    start_key: HashValue,
+    last_key: HashValue,
+    first_key_proof: &'a SparseMerkleProof,
+    last_key_proof: &'a SparseMerkleProof,
+}
+
+impl<'a> SparseMerkleRangeWalker<'a> {
+    /// Returns the root hash of the tree given `leaves`, all the leaves in the range.
+    fn root_hash(&self, leaves: &[(HashValue, HashValue)]) -> Result<HashValue> {
+        // The root is on both paths.
+        let (on_first_path, on_last_path) = (true, true);
+        self.subtree_hash(0 /* depth */, leaves, on_first_path, on_last_path)
+    }
+
+    /// Returns the hash of the subtree at `depth` whose leaves in the range are `leaves`.
+    /// `on_first_path` and `on_last_path` tell whether the subtree is on the path from the root to
+    /// the start key and the last key respectively, along which the proofs provide the siblings.
+    fn subtree_hash(
+        &self,
+        depth: usize,
+        leaves: &[(HashValue, HashValue)],
+        on_first_path: bool,
+        on_last_path: bool,
+    ) -> Result<HashValue> {
+        let proof_ending_here = if on_first_path && depth == self.first_key_proof.siblings().len() {
+            Some(self.first_key_proof)
+        } else if on_last_path && depth == self.last_key_proof.siblings().len() {
+            Some(self.last_key_proof)
+        } else {
+            None
+        };
+        if let Some(proof) = proof_ending_here {
+            // The subtree has at most one leaf, which is given by the proof and is out of the
+            // range if it's smaller than the start key.
+            let leaf_in_range = proof.leaf().filter(|(key, _)| *key >= self.start_key);
+            ensure!(
+                leaves == leaf_in_range.as_ref().map_or(&[][..], std::slice::from_ref),
+                "Leaves at depth {} do not match the proof. Expected: {:?}. Found: {:?}.",
+                depth,
+                leaf_in_range,
+                leaves,
+            );
+            return Ok(match proof.leaf() {
+                Some((key, value_hash)) => SparseMerkleLeafNode::new(key, value_hash).hash(),
+                None => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+            });
+        }
+        if !on_first_path && !on_last_path {
+            // The subtree is entirely in the range, so all its leaves are known.
+            return Ok(sparse_merkle_subtree_hash(depth, leaves));
+        }
+
+        let (left_leaves, right_leaves) = split_sparse_merkle_leaves(depth, leaves);
+        let left_hash = if on_first_path && key_bit(self.start_key, depth) {
+            // All the keys on the left are smaller than the start key.
+            self.first_key_proof.siblings()[depth]
+        } else {
+            self.subtree_hash(
+                depth + 1,
+                left_leaves,
+                on_first_path,
+                on_last_path && !key_bit(self.last_key, depth),
+            )?
+        };
+        let right_hash = if on_last_path && !key_bit(self.last_key, depth) {
+            // All the keys on the right are greater than the last key.
+            self.last_key_proof.siblings()[depth]
+        } else {
+            self.subtree_hash(
+                depth + 1,
+                right_leaves,
+                on_first_path && key_bit(self.start_key, depth),
+                on_last_path,
+            )?
+        };
+        Ok(SparseMerkleInternalNode::new(left_hash, right_hash).hash())
+    }
+}
+
+/// Returns the hash of the subtree at `depth` of a Sparse Merkle Tree, given all its leaves in
+/// ascending order of their keys.
+fn sparse_merkle_subtree_hash(depth: usize, leaves: &[(HashValue, HashValue)]) -> HashValue {
+    match leaves {
+        [] => *SPARSE_MERKLE_PLACEHOLDER_HASH,
+        [(key, value_hash)] => SparseMerkleLeafNode::new(*key, *value_hash).hash(),
+        _ => {
+            let (left_leaves, right_leaves) = split_sparse_merkle_leaves(depth, leaves);
+            SparseMerkleInternalNode::new(
+                sparse_merkle_subtree_hash(depth + 1, left_leaves),
+                sparse_merkle_subtree_hash(depth + 1, right_leaves),
+            )
+            .hash()
+        }
+    }
+}
+
+/// Splits the sorted leaves of the subtree at `depth` into those of its left and right children.
+fn split_sparse_merkle_leaves(
+    depth: usize,
+    leaves: &[(HashValue, HashValue)],
+) -> (&[(HashValue, HashValue)], &[(HashValue, HashValue)]) {
+    let num_left_leaves = leaves
+        .iter()
+        .position(|(key, _)| key_bit(*key, depth))
+        .unwrap_or_else(|| leaves.len());
+    leaves.split_at(num_left_leaves)
+}
+
+/// Returns the bit of `key` at `depth`, where the most significant bit is at depth 0.
+fn key_bit(key: HashValue, depth: usize) -> bool {
+    key.as_ref()[depth / 8] & (1 << (7 - depth % 8)) != 0
+}
+
 pub struct MerkleTreeInternalNode<H> {
     left_child: HashValue,
     right_child: HashValue,
@@ -7,6 +7,7 @@
 use crate::{
     proof::{
         AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+        SparseMerkleRangeProof,
     },
     transaction::TransactionInfo,
 };
@@ -54,6 +55,15 @@ prop_compose! {
     }
 }
 
+prop_compose! {
+    fn arb_sparse_merkle_range_proof()(
+        first_key_proof in any::<SparseMerkleProof>(),
+        last_key_proof in any::<Option<SparseMerkleProof>>(),
+    ) -> SparseMerkleRangeProof {
+        SparseMerkleRangeProof::new(first_key_proof, last_key_proof)
+    }
+}
+
 prop_compose! {
     fn arb_signed_transaction_proof()(
         ledger_info_to_transaction_info_proof in any::<AccumulatorProof>(),
@@ -106,6 +116,7 @@ macro_rules! impl_arbitrary_for_proof {
 
 impl_arbitrary_for_proof!(AccumulatorProof, arb_accumulator_proof);
 impl_arbitrary_for_proof!(SparseMerkleProof, arb_sparse_merkle_proof);
+impl_arbitrary_for_proof!(SparseMerkleRangeProof, arb_sparse_merkle_range_proof);
 impl_arbitrary_for_proof!(SignedTransactionProof, arb_signed_transaction_proof);
 impl_arbitrary_for_proof!(AccountStateProof, arb_account_state_proof);
 impl_arbitrary_for_proof!(EventProof, arb_event_proof);
@@ -4,6 +4,7 @@
 use crate::proof::{
     definition::bitmap::{AccumulatorBitmap, SparseMerkleBitmap},
     AccountStateProof, AccumulatorProof, EventProof, SignedTransactionProof, SparseMerkleProof,
+    SparseMerkleRangeProof,
 };
 use crypto::{
     hash::{TestOnlyHash, ACCUMULATOR_PLACEHOLDER_HASH, SPARSE_MERKLE_PLACEHOLDER_HASH},
@@ -313,6 +314,13 @@ proptest! {
         assert_protobuf_encode_decode(&proof);
     }
 
+    #[test]
+    fn test_sparse_merkle_range_proof_protobuf_conversion_roundtrip(
+        proof in any::<SparseMerkleRangeProof>(),
+    ) {
+        assert_protobuf_encode_decode(&proof);
+    }
+
     #[test]
     fn test_signed_transaction_proof_protobuf_conversion_roundtrip(proof in any::<SignedTransactionProof>()) {
         assert_protobuf_encode_decode(&proof);
@@ -6,10 +6,12 @@ use crate::{
     account_state_blob::AccountStateBlob,
     ledger_info::LedgerInfo,
     proof::{
-        verify_account_state, verify_event, verify_signed_transaction,
-        verify_sparse_merkle_element, verify_test_accumulator_element, AccountStateProof,
-        AccumulatorProof, EventAccumulatorInternalNode, EventProof, MerkleTreeInternalNode,
-        SignedTransactionProof, SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof,
+        key_bit, sparse_merkle_subtree_hash, split_sparse_merkle_leaves, verify_account_state,
+        verify_account_state_chunk, verify_event, verify_ledger_frozen_subtree_hashes,
+        verify_signed_transaction, verify_sparse_merkle_element, verify_sparse_merkle_range,
+        verify_test_accumulator_element, AccountStateProof, AccumulatorProof,
+        EventAccumulatorInternalNode, EventProof, MerkleTreeInternalNode, SignedTransactionProof,
+        SparseMerkleInternalNode, SparseMerkleLeafNode, SparseMerkleProof, SparseMerkleRangeProof,
         TestAccumulatorInternalNode, TransactionAccumulatorInternalNode,
     },
     transaction::{
@@ -24,7 +26,11 @@ use crypto::{
     signing::generate_keypair,
     HashValue,
 };
-use proptest::{collection::vec, prelude::*};
+use proptest::{
+    collection::{btree_map, vec},
+    prelude::*,
+    sample::Index,
+};
 
 #[test]
 fn test_verify_empty_accumulator() {
@@ -468,6 +474,156 @@ fn test_verify_account_state_and_event() {
     .is_err());
 }
 
+#[test]
+fn test_verify_account_state_chunk_and_frozen_subtrees() {
+    // The same state tree as in `test_verify_account_state_and_event`:
+    //
+    //                 root
+    //                /    \
+    //               c      default
+    //              / \
+    //          key1   d
+    //                / \
+    //            key2   key3
+    let key1 = b"hello".test_only_hash();
+    let key2 = b"world".test_only_hash();
+    let key3 = b"!".test_only_hash();
+
+    let blob1 = AccountStateBlob::from(b"value1".to_vec());
+    let blob2 = AccountStateBlob::from(b"value2".to_vec());
+    let blob3 = AccountStateBlob::from(b"value3".to_vec());
+
+    let leaf1_hash = SparseMerkleLeafNode::new(key1, blob1.hash()).hash();
+    let leaf2_hash = SparseMerkleLeafNode::new(key2, blob2.hash()).hash();
+    let leaf3_hash = SparseMerkleLeafNode::new(key3, blob3.hash()).hash();
+    let internal_d_hash = SparseMerkleInternalNode::new(leaf2_hash, leaf3_hash).hash();
+    let internal_c_hash = SparseMerkleInternalNode::new(leaf1_hash, internal_d_hash).hash();
+    let state_root_hash =
+        SparseMerkleInternalNode::new(internal_c_hash, *SPARSE_MERKLE_PLACEHOLDER_HASH).hash();
+
+    let txn_info0_hash = b"hellohello".test_only_hash();
+    let txn_info1_hash = b"worldworld".test_only_hash();
+    let txn_info2 = TransactionInfo::new(
+        b"txn2".test_only_hash(),
+        state_root_hash,
+        b"event_root".test_only_hash(),
+        /* gas_used = */ 0,
+    );
+    let txn_info2_hash = txn_info2.hash();
+
+    let internal_a_hash =
+        TransactionAccumulatorInternalNode::new(txn_info0_hash, txn_info1_hash).hash();
+    let internal_b_hash =
+        TransactionAccumulatorInternalNode::new(txn_info2_hash, *ACCUMULATOR_PLACEHOLDER_HASH)
+            .hash();
+    let root_hash =
+        TransactionAccumulatorInternalNode::new(internal_a_hash, internal_b_hash).hash();
+    let ledger_info = LedgerInfo::new(
+        /* version = */ 2,
+        root_hash,
+        b"consensus_data".test_only_hash(),
+        *GENESIS_BLOCK_ID,
+        0,
+        /* timestamp = */ 10000,
+    );
+    let ledger_info_to_transaction_info_proof =
+        AccumulatorProof::new(vec![internal_a_hash, *ACCUMULATOR_PLACEHOLDER_HASH]);
+
+    let proof1 = SparseMerkleProof::new(
+        Some((key1, blob1.hash())),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, internal_d_hash],
+    );
+    let proof2 = SparseMerkleProof::new(
+        Some((key2, blob2.hash())),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, leaf1_hash, leaf3_hash],
+    );
+    let proof3 = SparseMerkleProof::new(
+        Some((key3, blob3.hash())),
+        vec![*SPARSE_MERKLE_PLACEHOLDER_HASH, leaf1_hash, leaf2_hash],
+    );
+
+    // The whole state in one chunk.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        /* start_key = */ None,
+        &[
+            (key1, blob1.clone()),
+            (key2, blob2.clone()),
+            (key3, blob3.clone())
+        ],
+        &[proof1.clone(), proof2.clone(), proof3.clone()],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_ok());
+    // The second chunk, right after `key1`.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        Some(key1),
+        &[(key2, blob2.clone()), (key3, blob3.clone())],
+        &[proof2.clone(), proof3.clone()],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_ok());
+    // Keys out of order.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        None,
+        &[(key3, blob3.clone()), (key2, blob2.clone())],
+        &[proof3.clone(), proof2.clone()],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_err());
+    // A chunk that doesn't start after `start_key`.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        Some(key2),
+        &[(key2, blob2.clone())],
+        &[proof2.clone()],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_err());
+    // A bad blob.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        None,
+        &[(key1, blob2.clone())],
+        &[proof1.clone()],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_err());
+    // Missing proofs.
+    assert!(verify_account_state_chunk(
+        &ledger_info,
+        /* state_version = */ 2,
+        None,
+        &[(key1, blob1.clone()), (key2, blob2.clone())],
+        &[proof1],
+        &txn_info2,
+        &ledger_info_to_transaction_info_proof,
+    )
+    .is_err());
+
+    assert!(
+        verify_ledger_frozen_subtree_hashes(&ledger_info, &[internal_a_hash, txn_info2_hash])
+            .is_ok()
+    );
+    assert!(
+        verify_ledger_frozen_subtree_hashes(&ledger_info, &[txn_info2_hash, internal_a_hash])
+            .is_err()
+    );
+    assert!(verify_ledger_frozen_subtree_hashes(&ledger_info, &[internal_a_hash]).is_err());
+}
+
 // Return a variable length of transaction_and_info list with a random range within [0,
 // list_length).
 fn arb_signed_txn_list_and_range(
@@ -596,3 +752,117 @@ proptest! {
         prop_assert!(txn_list_with_proof.verify(&ledger_info,first_version).is_ok());
     }
 }
+
+/// Returns the proof of `key` in the Sparse Merkle Tree made of `leaves`, which are sorted by key.
+fn sparse_merkle_proof(key: HashValue, leaves: &[(HashValue, HashValue)]) -> SparseMerkleProof {
+    let mut leaves = leaves;
+    let mut siblings = vec![];
+    while leaves.len() > 1 {
+        let depth = siblings.len();
+        let (left_leaves, right_leaves) = split_sparse_merkle_leaves(depth, leaves);
+        if key_bit(key, depth) {
+            siblings.push(sparse_merkle_subtree_hash(depth + 1, left_leaves));
+            leaves = right_leaves;
+        } else {
+            siblings.push(sparse_merkle_subtree_hash(depth + 1, right_leaves));
+            leaves = left_leaves;
+        }
+    }
+    SparseMerkleProof::new(leaves.first().cloned(), siblings)
+}
+
+/// Returns the proof of the range from `start_key` to the last key of `range`, in the Sparse
+/// Merkle Tree made of `leaves`.
+fn sparse_merkle_range_proof(
+    start_key: HashValue,
+    range: &[(HashValue, AccountStateBlob)],
+    leaves: &[(HashValue, HashValue)],
+) -> SparseMerkleRangeProof {
+    SparseMerkleRangeProof::new(
+        sparse_merkle_proof(start_key, leaves),
+        range
+            .last()
+            .map(|(last_key, _)| sparse_merkle_proof(*last_key, leaves)),
+    )
+}
+
+#[test]
+fn test_verify_sparse_merkle_range_empty_tree() {
+    let root_hash = *SPARSE_MERKLE_PLACEHOLDER_HASH;
+    let start_key = b"hello".test_only_hash();
+    let proof = SparseMerkleRangeProof::new(SparseMerkleProof::new(None, vec![]), None);
+    assert!(verify_sparse_merkle_range(root_hash, start_key, &[], &proof).is_ok());
+
+    let blob = AccountStateBlob::from(b"world".to_vec());
+    assert!(
+        verify_sparse_merkle_range(root_hash, start_key, &[(start_key, blob)], &proof).is_err()
+    );
+}
+
+// Returns the account states of a Sparse Merkle Tree sorted by key, a start key that is either an
+// existing key or a random one, and the maximum number of account states in the range.
+fn arb_account_states_and_range(
+) -> impl Strategy<Value = (Vec<(HashValue, AccountStateBlob)>, HashValue, usize)> {
+    (
+        btree_map(any::<HashValue>(), any::<AccountStateBlob>(), 0..50),
+        any::<Index>(),
+        any::<HashValue>(),
+        any::<bool>(),
+        1..60usize,
+    )
+        .prop_map(
+            |(account_states, index, random_key, use_existing_key, limit)| {
+                let account_states = account_states.into_iter().collect::<Vec<_>>();
+                let start_key = if use_existing_key && !account_states.is_empty() {
+                    account_states[index.index(account_states.len())].0
+                } else {
+                    random_key
+                };
+                (account_states, start_key, limit)
+            },
+        )
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(50))]
+
+    #[test]
+    fn test_sparse_merkle_range_proof(
+        (account_states, start_key, limit) in arb_account_states_and_range(),
+    ) {
+        let leaves = account_states
+            .iter()
+            .map(|(key, blob)| (*key, blob.hash()))
+            .collect::<Vec<_>>();
+        let root_hash = sparse_merkle_subtree_hash(0 /* depth */, &leaves);
+        let range = account_states
+            .iter()
+            .filter(|(key, _)| *key >= start_key)
+            .take(limit)
+            .cloned()
+            .collect::<Vec<_>>();
+        let proof = sparse_merkle_range_proof(start_key, &range, &leaves);
+        prop_assert!(verify_sparse_merkle_range(root_hash, start_key, &range, &proof).is_ok());
+
+        if !range.is_empty() {
+            // Claiming that the range is empty.
+            let empty_proof = sparse_merkle_range_proof(start_key, &[], &leaves);
+            prop_assert!(
+                verify_sparse_merkle_range(root_hash, start_key, &[], &empty_proof).is_err()
+            );
+            // Leaving out any account state but the last one.
+            for i in 0..range.len() - 1 {
+                let mut partial_range = range.clone();
+                partial_range.remove(i);
+                let partial_proof = sparse_merkle_range_proof(start_key, &partial_range, &leaves);
+                prop_assert!(verify_sparse_merkle_range(
+                    root_hash,
+                    start_key,
+                    &partial_range,
+                    &partial_proof,
+                )
+                .is_err());
+            }
+        }
+    }
+}
@@ -12,8 +12,8 @@ use crate::{
     proof::AccumulatorProof,
     transaction::{
         Program, RawTransaction, SignatureCheckedTransaction, SignedTransaction,
-        TransactionArgument, TransactionInfo, TransactionListWithProof, TransactionPayload,
-        TransactionStatus, TransactionToCommit, Version,
+        TransactionArgument, TransactionInfo, TransactionListWithProof, TransactionOutput,
+        TransactionPayload, TransactionStatus, TransactionToCommit, Version,
     },
     validator_change::ValidatorChangeEventWithProof,
     vm_error::VMStatus,
@@ -242,6 +242,26 @@ impl Arbitrary for TransactionStatus {
     }
 }
 
+prop_compose! {
+    fn arb_transaction_output()(
+        write_set in any::<WriteSet>(),
+        events in vec(any::<ContractEvent>(), 0..10),
+        gas_used in any::<u64>(),
+        status in any::<TransactionStatus>(),
+    ) -> TransactionOutput {
+        TransactionOutput::new(write_set, events, gas_used, status)
+    }
+}
+
+impl Arbitrary for TransactionOutput {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_args: Self::Parameters) -> Self::Strategy {
+        arb_transaction_output().boxed()
+    }
+}
+
 impl Arbitrary for TransactionPayload {
     type Parameters = ();
     type Strategy = BoxedStrategy<Self>;
@@ -278,6 +278,17 @@ impl SignatureCheckedTransaction {
     pub fn into_raw_transaction(self) -> RawTransaction {
         self.0.into_raw_transaction()
     }
+
+    /// Wraps a `SignedTransaction` without checking its signature.
+    ///
+    /// **IMPORTANT:** This must only be used for transactions whose output is never committed,
+    /// e.g. when admission control simulates a transaction on behalf of a client that has not
+    /// signed it yet. It's only compiled with the `simulation` feature, which the VM enables for its
+    /// simulation entry point.
+    #[cfg(feature = "simulation")]
+    pub fn new_unchecked_for_simulation(txn: SignedTransaction) -> Self {
+        SignatureCheckedTransaction(txn)
+    }
 }
 
 impl Deref for SignatureCheckedTransaction {
@@ -619,6 +630,37 @@ impl From<VMStatus> for TransactionStatus {
     }
 }
 
+impl FromProto for TransactionStatus {
+    type ProtoType = crate::proto::transaction::TransactionStatus;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        if object.has_keep() {
+            Ok(TransactionStatus::Keep(VMStatus::from_proto(
+                object.take_keep(),
+            )?))
+        } else if object.has_discard() {
+            Ok(TransactionStatus::Discard(VMStatus::from_proto(
+                object.take_discard(),
+            )?))
+        } else {
+            bail!("Missing status in TransactionStatus")
+        }
+    }
+}
+
+impl IntoProto for TransactionStatus {
+    type ProtoType = crate::proto::transaction::TransactionStatus;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        match self {
+            TransactionStatus::Keep(vm_status) => proto.set_keep(vm_status.into_proto()),
+            TransactionStatus::Discard(vm_status) => proto.set_discard(vm_status.into_proto()),
+        }
+        proto
+    }
+}
+
 /// The output of executing a transaction.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct TransactionOutput {
@@ -667,6 +709,46 @@ impl TransactionOutput {
     }
 }
 
+impl FromProto for TransactionOutput {
+    type ProtoType = crate::proto::transaction::TransactionOutput;
+
+    fn from_proto(mut object: Self::ProtoType) -> Result<Self> {
+        let write_set = WriteSet::from_proto(object.take_write_set())?;
+        let events = object
+            .take_events()
+            .into_iter()
+            .map(ContractEvent::from_proto)
+            .collect::<Result<Vec<_>>>()?;
+        let gas_used = object.get_gas_used();
+        let status = TransactionStatus::from_proto(object.take_status())?;
+
+        Ok(TransactionOutput {
+            write_set,
+            events,
+            gas_used,
+            status,
+        })
+    }
+}
+
+impl IntoProto for TransactionOutput {
+    type ProtoType = crate::proto::transaction::TransactionOutput;
+
+    fn into_proto(self) -> Self::ProtoType {
+        let mut proto = Self::ProtoType::new();
+        proto.set_write_set(self.write_set.into_proto());
+        proto.set_events(protobuf::RepeatedField::from_vec(
+            self.events
+                .into_iter()
+                .map(ContractEvent::into_proto)
+                .collect::<Vec<_>>(),
+        ));
+        proto.set_gas_used(self.gas_used);
+        proto.set_status(self.status.into_proto());
+        proto
+    }
+}
+
 /// `TransactionInfo` is the object we store in the transaction accumulator. It consists of the
 /// transaction as well as the execution result of this transaction.
 #[derive(Arbitrary, Clone, Debug, Eq, PartialEq, Serialize, Deserialize, FromProto, IntoProto)]
@@ -31,6 +31,16 @@ proptest! {
         assert_protobuf_encode_decode(&txn_info);
     }
 
+    #[test]
+    fn test_transaction_status(txn_status in any::<TransactionStatus>()) {
+        assert_protobuf_encode_decode(&txn_status);
+    }
+
+    #[test]
+    fn test_transaction_output(txn_output in any::<TransactionOutput>()) {
+        assert_protobuf_encode_decode(&txn_output);
+    }
+
     #[test]
     fn test_transaction_to_commit(txn_to_commit in any::<TransactionToCommit>()) {
         assert_protobuf_encode_decode(&txn_to_commit);
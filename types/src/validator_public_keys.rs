@@ -14,13 +14,14 @@ use crypto::{x25519::X25519PublicKey, PublicKey};
 use failure::Result;
 use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
+use serde::{Deserialize, Serialize};
 
 /// After executing a special transaction that sets the validators that should be used for the
 /// next epoch, consensus and networking get the new list of validators.  Consensus will have a
 /// public key to validate signed messages and networking will have a TBD public key for
 /// creating secure channels of communication between validators.  The validators and their
 /// public keys may or may not change between epochs.
-#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+#[derive(Arbitrary, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ValidatorPublicKeys {
     // Hash value of the current public key of the account address
     account_address: AccountAddress,
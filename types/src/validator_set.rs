@@ -14,6 +14,7 @@ use canonical_serialization::{
 use failure::prelude::*;
 use proptest_derive::Arbitrary;
 use proto_conv::{FromProto, IntoProto};
+use serde::{Deserialize, Serialize};
 use std::collections::btree_map::BTreeMap;
 
 pub const VALIDATOR_SET_MODULE_NAME: &str = "ValidatorSet";
@@ -32,7 +33,7 @@ pub(crate) fn validator_set_path() -> Vec<u8> {
     AccessPath::resource_access_vec(&validator_set_tag(), &Accesses::empty())
 }
 
-#[derive(Arbitrary, Clone, Debug, Eq, PartialEq)]
+#[derive(Arbitrary, Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct ValidatorSet(Vec<ValidatorPublicKeys>);
 
 impl ValidatorSet {
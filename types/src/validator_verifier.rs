@@ -1,7 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::account_address::AccountAddress;
+use crate::{account_address::AccountAddress, validator_set::ValidatorSet};
 use crypto::{signing, HashValue, PublicKey, Signature};
 use failure::prelude::*;
 use std::collections::HashMap;
@@ -169,6 +169,19 @@ impl ValidatorVerifier {
     }
 }
 
+impl From<&ValidatorSet> for ValidatorVerifier {
+    /// Verifies the consensus signatures of the given validators with the default quorum size.
+    fn from(validator_set: &ValidatorSet) -> Self {
+        ValidatorVerifier::new(
+            validator_set
+                .payload()
+                .iter()
+                .map(|keys| (*keys.account_address(), *keys.consensus_public_key()))
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
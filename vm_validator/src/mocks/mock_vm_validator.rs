@@ -7,8 +7,9 @@ use state_view::StateView;
 use std::convert::TryFrom;
 use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
-    transaction::SignedTransaction,
-    vm_error::{VMStatus, VMValidationStatus},
+    transaction::{SignedTransaction, TransactionOutput, TransactionStatus},
+    vm_error::{ExecutionStatus, VMStatus, VMValidationStatus},
+    write_set::WriteSet,
 };
 use vm_runtime::VMVerifier;
 
@@ -25,22 +26,9 @@ impl VMVerifier for MockVMValidator {
     }
 }
 
-impl TransactionValidation for MockVMValidator {
-    type ValidationInstance = MockVMValidator;
-    fn validate_transaction(
-        &self,
-        txn: SignedTransaction,
-    ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send> {
-        let txn = match txn.check_signature() {
-            Ok(txn) => txn,
-            Err(_) => {
-                return Box::new(ok(Some(VMStatus::Validation(
-                    VMValidationStatus::InvalidSignature,
-                ))))
-            }
-        };
-
-        let sender = txn.sender();
+impl MockVMValidator {
+    /// Returns the validation status assigned to `sender` by the test addresses below.
+    fn validate_sender(sender: AccountAddress) -> Option<VMStatus> {
         let account_dne_test_add = AccountAddress::try_from(&[0 as u8; ADDRESS_LENGTH]).unwrap();
         let invalid_sig_test_add = AccountAddress::try_from(&[1 as u8; ADDRESS_LENGTH]).unwrap();
         let insufficient_balance_test_add =
@@ -53,7 +41,7 @@ impl TransactionValidation for MockVMValidator {
             AccountAddress::try_from(&[5 as u8; ADDRESS_LENGTH]).unwrap();
         let invalid_auth_key_test_add =
             AccountAddress::try_from(&[6 as u8; ADDRESS_LENGTH]).unwrap();
-        let ret = if sender == account_dne_test_add {
+        if sender == account_dne_test_add {
             Some(VMStatus::Validation(
                 VMValidationStatus::SendingAccountDoesNotExist("TEST".to_string()),
             ))
@@ -77,7 +65,49 @@ impl TransactionValidation for MockVMValidator {
             Some(VMStatus::Validation(VMValidationStatus::InvalidAuthKey))
         } else {
             None
+        }
+    }
+}
+
+impl TransactionValidation for MockVMValidator {
+    type ValidationInstance = MockVMValidator;
+    fn validate_transaction(
+        &self,
+        txn: SignedTransaction,
+    ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send> {
+        let txn = match txn.check_signature() {
+            Ok(txn) => txn,
+            Err(_) => {
+                return Box::new(ok(Some(VMStatus::Validation(
+                    VMValidationStatus::InvalidSignature,
+                ))))
+            }
+        };
+        Box::new(ok(Self::validate_sender(txn.sender())))
+    }
+
+    fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send> {
+        let status = if !skip_signature_check && txn.clone().check_signature().is_err() {
+            Some(VMStatus::Validation(VMValidationStatus::InvalidSignature))
+        } else {
+            Self::validate_sender(txn.sender())
+        };
+        let output = match status {
+            Some(vm_status) => {
+                TransactionOutput::new(WriteSet::default(), vec![], 0, vm_status.into())
+            }
+            // Pretend that the transaction used all the gas it was allowed to use.
+            None => TransactionOutput::new(
+                WriteSet::default(),
+                vec![],
+                txn.max_gas_amount(),
+                TransactionStatus::Keep(VMStatus::Execution(ExecutionStatus::Executed)),
+            ),
         };
-        Box::new(ok(ret))
+        Box::new(ok(output))
     }
 }
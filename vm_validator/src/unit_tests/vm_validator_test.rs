@@ -18,8 +18,13 @@ use storage_service::start_storage_service;
 use types::{
     account_address, account_config,
     test_helpers::transaction_test_helpers,
-    transaction::{Program, SignedTransaction, TransactionArgument, MAX_TRANSACTION_SIZE_IN_BYTES},
-    vm_error::{VMStatus, VMValidationStatus, VMVerificationError, VMVerificationStatus},
+    transaction::{
+        Program, SignedTransaction, TransactionArgument, TransactionStatus,
+        MAX_TRANSACTION_SIZE_IN_BYTES,
+    },
+    vm_error::{
+        ExecutionStatus, VMStatus, VMValidationStatus, VMVerificationError, VMVerificationStatus,
+    },
 };
 use vm_genesis::encode_transfer_program;
 
@@ -488,3 +493,70 @@ fn test_validate_non_genesis_write_set() {
         Some(VMStatus::Validation(VMValidationStatus::RejectedWriteSet))
     );
 }
+
+#[test]
+fn test_simulate_transaction() {
+    let (config, keypair) = get_test_config();
+    let vm_validator = TestValidator::new(&config);
+
+    let address = account_config::association_address();
+    let program = encode_transfer_program(&address, 100);
+    let signed_txn = transaction_test_helpers::get_test_signed_txn(
+        address,
+        0,
+        keypair.private_key().clone(),
+        keypair.public_key(),
+        Some(program),
+    );
+    let output = vm_validator
+        .simulate_transaction(
+            SignedTransaction::from_proto(signed_txn).unwrap(),
+            false, /* skip_signature_check */
+        )
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Execution(ExecutionStatus::Executed))
+    );
+    assert!(output.gas_used() > 0);
+    assert!(!output.write_set().is_empty());
+}
+
+#[test]
+fn test_simulate_transaction_invalid_signature() {
+    let (config, keypair) = get_test_config();
+    let vm_validator = TestValidator::new(&config);
+
+    let (other_private_key, _) = ::crypto::signing::generate_keypair();
+    let other_keypair = KeyPair::new(other_private_key);
+
+    let address = account_config::association_address();
+    let program = encode_transfer_program(&address, 100);
+    let signed_txn = transaction_test_helpers::get_test_unchecked_txn(
+        address,
+        0,
+        other_keypair.private_key().clone(),
+        keypair.public_key(),
+        Some(program),
+    );
+
+    let output = vm_validator
+        .simulate_transaction(signed_txn.clone(), false /* skip_signature_check */)
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Discard(VMStatus::Validation(VMValidationStatus::InvalidSignature))
+    );
+
+    // The same transaction goes through if the client asks to skip the signature check.
+    let output = vm_validator
+        .simulate_transaction(signed_txn, true /* skip_signature_check */)
+        .wait()
+        .unwrap();
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(VMStatus::Execution(ExecutionStatus::Executed))
+    );
+}
@@ -1,7 +1,8 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use config::config::NodeConfig;
+use config::config::{NodeConfig, VMConfig};
+use crypto::HashValue;
 use failure::prelude::*;
 use futures::future::{err, ok, Future};
 use scratchpad::SparseMerkleTree;
@@ -11,10 +12,10 @@ use types::{
     account_address::{AccountAddress, ADDRESS_LENGTH},
     account_config::get_account_resource_or_default,
     get_with_proof::{RequestItem, ResponseItem},
-    transaction::SignedTransaction,
+    transaction::{SignedTransaction, TransactionOutput},
     vm_error::VMStatus,
 };
-use vm_runtime::{MoveVM, VMVerifier};
+use vm_runtime::{MoveVM, VMSimulator, VMVerifier};
 
 #[cfg(test)]
 #[path = "unit_tests/vm_validator_test.rs"]
@@ -27,12 +28,21 @@ pub trait TransactionValidation: Send + Sync {
         &self,
         _txn: SignedTransaction,
     ) -> Box<dyn Future<Item = Option<VMStatus>, Error = failure::Error> + Send>;
+
+    /// Execute a txn from client against the latest committed state without committing it. The
+    /// signature of the txn is not verified if `skip_signature_check` is set.
+    fn simulate_transaction(
+        &self,
+        _txn: SignedTransaction,
+        _skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send>;
 }
 
 #[derive(Clone)]
 pub struct VMValidator {
     storage_read_client: Arc<dyn StorageRead>,
     vm: MoveVM,
+    vm_config: VMConfig,
 }
 
 impl VMValidator {
@@ -40,6 +50,34 @@ impl VMValidator {
         VMValidator {
             storage_read_client,
             vm: MoveVM::new(&config.vm_config),
+            vm_config: config.vm_config.clone(),
+        }
+    }
+
+    /// Returns the state root of the latest committed state.
+    fn get_latest_state_root(&self) -> Result<HashValue> {
+        // Just ask something from storage. It doesn't matter what it is -- we just need the
+        // transaction info object in account state proof which contains the state root hash.
+        let address = AccountAddress::new([0xff; ADDRESS_LENGTH]);
+        let item = RequestItem::GetAccountState { address };
+
+        let (mut items, _, _) = self
+            .storage_read_client
+            .update_to_latest_ledger(/* client_known_version = */ 0, vec![item])?;
+        ensure!(
+            items.len() == 1,
+            "Unexpected number of items ({}).",
+            items.len()
+        );
+
+        match items.remove(0) {
+            ResponseItem::GetAccountState {
+                account_state_with_proof,
+            } => Ok(account_state_with_proof
+                .proof
+                .transaction_info()
+                .state_root_hash()),
+            _ => panic!("Unexpected item in response."),
         }
     }
 }
@@ -61,44 +99,35 @@ impl TransactionValidation for VMValidator {
         // validator set.
         // 3) Create VerifiedStateView with verified state
         // root.
+        let state_root = match self.get_latest_state_root() {
+            Ok(state_root) => state_root,
+            Err(e) => return Box::new(err(e)),
+        };
+        let smt = SparseMerkleTree::new(state_root);
+        let state_view =
+            VerifiedStateView::new(Arc::clone(&self.storage_read_client), state_root, &smt);
+        Box::new(ok(self.vm.validate_transaction(txn, &state_view)))
+    }
 
-        // Just ask something from storage. It doesn't matter what it is -- we just need the
-        // transaction info object in account state proof which contains the state root hash.
-        let address = AccountAddress::new([0xff; ADDRESS_LENGTH]);
-        let item = RequestItem::GetAccountState { address };
-
-        match self
-            .storage_read_client
-            .update_to_latest_ledger(/* client_known_version = */ 0, vec![item])
-        {
-            Ok((mut items, _, _)) => {
-                if items.len() != 1 {
-                    return Box::new(err(format_err!(
-                        "Unexpected number of items ({}).",
-                        items.len()
-                    )
-                    .into()));
-                }
-
-                match items.remove(0) {
-                    ResponseItem::GetAccountState {
-                        account_state_with_proof,
-                    } => {
-                        let transaction_info = account_state_with_proof.proof.transaction_info();
-                        let state_root = transaction_info.state_root_hash();
-                        let smt = SparseMerkleTree::new(state_root);
-                        let state_view = VerifiedStateView::new(
-                            Arc::clone(&self.storage_read_client),
-                            state_root,
-                            &smt,
-                        );
-                        Box::new(ok(self.vm.validate_transaction(txn, &state_view)))
-                    }
-                    _ => panic!("Unexpected item in response."),
-                }
-            }
-            Err(e) => Box::new(err(e.into())),
-        }
+    fn simulate_transaction(
+        &self,
+        txn: SignedTransaction,
+        skip_signature_check: bool,
+    ) -> Box<dyn Future<Item = TransactionOutput, Error = failure::Error> + Send> {
+        // Same trust assumptions as `validate_transaction`.
+        let state_root = match self.get_latest_state_root() {
+            Ok(state_root) => state_root,
+            Err(e) => return Box::new(err(e)),
+        };
+        let smt = SparseMerkleTree::new(state_root);
+        let state_view =
+            VerifiedStateView::new(Arc::clone(&self.storage_read_client), state_root, &smt);
+        Box::new(ok(MoveVM::simulate_transaction(
+            txn,
+            &self.vm_config,
+            &state_view,
+            skip_signature_check,
+        )))
     }
 }
 